@@ -0,0 +1,401 @@
+//! Screen-reader-friendly plain output mode (`mutagui --a11y`).
+//!
+//! Instead of drawing the full-screen interface, prints the specs as numbered
+//! lines of plain text and afterwards only announces what changed, one line
+//! per change. Commands are typed as words ("pause 2", "refresh") on standard
+//! input. Nothing uses box drawing, color, or status glyphs, and the cursor is
+//! never moved, so a terminal screen reader can follow along.
+
+use crate::app::{App, StatusMessage};
+use crate::project::{Project, SyncSpec, SyncSpecState};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::Duration;
+
+const HELP: &str = "Commands: list, refresh, start N, pause N, resume N, flush N, \
+terminate N, push N, help, quit. N is a spec number from the list or a spec name.";
+
+/// A spec identified by project file and spec name, which survive re-sorting.
+type SpecKey = (PathBuf, String);
+
+/// Plain description of a spec's state, e.g. "web: watching, 2 conflicts".
+pub fn describe_spec(spec: &SyncSpec) -> String {
+    let Some(session) = &spec.running_session else {
+        return format!("{}: not running", spec.name);
+    };
+
+    let mut parts = vec![if session.paused {
+        "paused".to_string()
+    } else {
+        session.status_text().to_lowercase()
+    }];
+    if spec.state == SyncSpecState::RunningPush {
+        parts.push("pushing one way".to_string());
+    }
+    if !session.alpha.connected {
+        parts.push("alpha disconnected".to_string());
+    }
+    if !session.beta.connected {
+        parts.push("beta disconnected".to_string());
+    }
+    match session.total_conflicts() {
+        0 => {}
+        1 => parts.push("1 conflict".to_string()),
+        n => parts.push(format!("{} conflicts", n)),
+    }
+    format!("{}: {}", spec.name, parts.join(", "))
+}
+
+/// Description of every spec, keyed by project file and spec name.
+fn snapshot(projects: &[Project]) -> HashMap<SpecKey, String> {
+    projects
+        .iter()
+        .flat_map(|project| {
+            project.specs.iter().map(|spec| {
+                (
+                    (project.file.path.clone(), spec.name.clone()),
+                    describe_spec(spec),
+                )
+            })
+        })
+        .collect()
+}
+
+/// One line per spec that was added, changed state, or went away since `previous`.
+pub fn announcements(previous: &HashMap<SpecKey, String>, projects: &[Project]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut seen = HashSet::new();
+
+    for project in projects {
+        for spec in &project.specs {
+            let key = (project.file.path.clone(), spec.name.clone());
+            let line = describe_spec(spec);
+            match previous.get(&key) {
+                None => lines.push(format!("New: {}", line)),
+                Some(old) if *old != line => lines.push(format!("Changed: {}", line)),
+                Some(_) => {}
+            }
+            seen.insert(key);
+        }
+    }
+
+    let mut removed: Vec<_> = previous
+        .keys()
+        .filter(|key| !seen.contains(*key))
+        .map(|(_, name)| format!("Removed: {}", name))
+        .collect();
+    removed.sort();
+    lines.extend(removed);
+    lines
+}
+
+/// Print every project and its specs, numbering the specs for commands.
+fn print_list(projects: &[Project]) -> Vec<SpecKey> {
+    let mut numbered = Vec::new();
+    if projects.is_empty() {
+        println!("No projects found.");
+    }
+    for project in projects {
+        let dir = project
+            .file
+            .path
+            .parent()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default();
+        println!(
+            "Project {} in {}, {} spec(s):",
+            project.file.display_name(),
+            dir,
+            project.specs.len()
+        );
+        for spec in &project.specs {
+            numbered.push((project.file.path.clone(), spec.name.clone()));
+            println!("{}. {}", numbered.len(), describe_spec(spec));
+        }
+    }
+    numbered
+}
+
+/// Find the spec a command refers to, by list number or by name.
+fn resolve_spec(
+    projects: &[Project],
+    numbered: &[SpecKey],
+    target: &str,
+) -> Result<(usize, usize), String> {
+    let key = match target.parse::<usize>() {
+        Ok(n) => numbered
+            .get(n.wrapping_sub(1))
+            .cloned()
+            .ok_or_else(|| format!("No spec number {}. Type list to see them.", n))?,
+        Err(_) => {
+            let mut matches = projects.iter().flat_map(|project| {
+                project
+                    .specs
+                    .iter()
+                    .filter(|spec| spec.name == target)
+                    .map(|spec| (project.file.path.clone(), spec.name.clone()))
+            });
+            let key = matches
+                .next()
+                .ok_or_else(|| format!("No spec named {}.", target))?;
+            if matches.next().is_some() {
+                return Err(format!(
+                    "Several projects have a spec named {}. Use its number.",
+                    target
+                ));
+            }
+            key
+        }
+    };
+
+    projects
+        .iter()
+        .enumerate()
+        .filter(|(_, project)| project.file.path == key.0)
+        .find_map(|(project_idx, project)| {
+            project
+                .specs
+                .iter()
+                .position(|spec| spec.name == key.1)
+                .map(|spec_idx| (project_idx, spec_idx))
+        })
+        .ok_or_else(|| format!("{} no longer exists. Type list to see the specs.", key.1))
+}
+
+/// What the loop should do after a command.
+enum Flow {
+    Continue,
+    List,
+    Refresh,
+    Quit,
+}
+
+async fn handle_command(app: &mut App, numbered: &[SpecKey], line: &str) -> Flow {
+    let mut words = line.split_whitespace();
+    let Some(command) = words.next() else {
+        return Flow::Continue;
+    };
+    let target = words.collect::<Vec<_>>().join(" ");
+
+    let action = command.to_lowercase();
+    match action.as_str() {
+        "quit" | "exit" | "q" => return Flow::Quit,
+        "help" | "h" | "?" => {
+            println!("{}", HELP);
+            return Flow::Continue;
+        }
+        "list" | "l" => return Flow::List,
+        "refresh" | "r" => return Flow::Refresh,
+        "start" | "pause" | "resume" | "flush" | "terminate" | "push" => {}
+        _ => {
+            println!("Unknown command {}. Type help for commands.", command);
+            return Flow::Continue;
+        }
+    }
+    if target.is_empty() {
+        println!("{} which spec? For example: {} 1", action, action);
+        return Flow::Continue;
+    }
+    let (project_idx, spec_idx) = match resolve_spec(&app.projects, numbered, &target) {
+        Ok(indices) => indices,
+        Err(message) => {
+            println!("{}", message);
+            return Flow::Continue;
+        }
+    };
+    app.select_spec(project_idx, spec_idx);
+
+    app.status_message = None;
+    match action.as_str() {
+        "start" => app.start_selected_spec().await,
+        "pause" => app.pause_selected().await,
+        "resume" => app.resume_selected().await,
+        "flush" => app.flush_selected().await,
+        "terminate" => app.terminate_selected().await,
+        "push" => app.push_selected_spec().await,
+        _ => unreachable!(),
+    }
+    match &app.status_message {
+        Some(message) => println!("{}", message.text()),
+        None => println!("Nothing to do: the spec is not running."),
+    }
+    Flow::Refresh
+}
+
+/// Refresh and announce what changed since `previous`.
+async fn refresh_and_announce(
+    app: &mut App,
+    previous: &mut HashMap<SpecKey, String>,
+    last_problem: &mut Option<String>,
+) -> Result<()> {
+    app.refresh_sessions().await?;
+    app.wait_for_discovery().await;
+    for line in announcements(previous, &app.projects) {
+        println!("{}", line);
+    }
+    *previous = snapshot(&app.projects);
+    report_problem(app, last_problem);
+    Ok(())
+}
+
+/// Print a refresh warning or error. These repeat until fixed, so each is said once.
+fn report_problem(app: &App, last_problem: &mut Option<String>) {
+    if let Some(StatusMessage::Warning(text) | StatusMessage::Error(text)) = &app.status_message {
+        if last_problem.as_ref() != Some(text) {
+            println!("{}", text);
+            *last_problem = Some(text.clone());
+        }
+    } else {
+        *last_problem = None;
+    }
+}
+
+pub async fn run(project_dir: Option<PathBuf>) -> Result<()> {
+    let mut app = App::plain(project_dir);
+
+    // Read commands on a plain thread; the loop below also has to refresh on a timer
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    println!("mutagui accessible mode. Type help for commands.");
+    let mut previous = HashMap::new();
+    let mut last_problem = None;
+    app.refresh_sessions().await?;
+    app.wait_for_discovery().await;
+    let mut numbered = print_list(&app.projects);
+    previous.extend(snapshot(&app.projects));
+    report_problem(&app, &mut last_problem);
+
+    loop {
+        match rx.try_recv() {
+            Ok(line) => match handle_command(&mut app, &numbered, line.trim()).await {
+                Flow::Continue => {}
+                Flow::List => {
+                    app.refresh_sessions().await?;
+                    app.wait_for_discovery().await;
+                    numbered = print_list(&app.projects);
+                    previous = snapshot(&app.projects);
+                }
+                Flow::Refresh => {
+                    refresh_and_announce(&mut app, &mut previous, &mut last_problem).await?;
+                }
+                Flow::Quit => break,
+            },
+            Err(TryRecvError::Empty) => {
+                if app.should_auto_refresh() {
+                    refresh_and_announce(&mut app, &mut previous, &mut last_problem).await?;
+                } else {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            }
+            // End of input
+            Err(TryRecvError::Disconnected) => break,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutagen::SyncSession;
+    use crate::project::{Hooks, ProjectFile};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    fn spec(name: &str, status: Option<&str>) -> SyncSpec {
+        SyncSpec {
+            name: name.to_string(),
+            state: match status {
+                Some(_) => SyncSpecState::RunningTwoWay,
+                None => SyncSpecState::NotRunning,
+            },
+            running_session: status.map(|status| {
+                let session = SyncSession::named(name)
+                    .with_status(status)
+                    .with_beta_connected(status != "connecting-beta");
+                Arc::new(session)
+            }),
+        }
+    }
+
+    fn project(specs: Vec<SyncSpec>) -> Project {
+        Project {
+            file: ProjectFile {
+                path: PathBuf::from("/code/app/mutagen.yml"),
+                target_name: None,
+                sessions: HashMap::new(),
+                defaults: None,
+                includes: Vec::new(),
+                hooks: Hooks::default(),
+                commands: BTreeMap::new(),
+            },
+            specs,
+            folded: false,
+            favorite: false,
+        }
+    }
+
+    #[test]
+    fn test_describe_spec_is_plain_text() {
+        assert_eq!(
+            describe_spec(&spec("web", Some("watching"))),
+            "web: watching"
+        );
+        assert_eq!(
+            describe_spec(&spec("api", Some("connecting-beta"))),
+            "api: connecting, beta disconnected"
+        );
+        assert_eq!(describe_spec(&spec("docs", None)), "docs: not running");
+    }
+
+    #[test]
+    fn test_announcements_only_report_changes() {
+        let before = vec![project(vec![
+            spec("web", Some("watching")),
+            spec("api", Some("watching")),
+            spec("old", None),
+        ])];
+        let after = vec![project(vec![
+            spec("web", Some("watching")),
+            spec("api", Some("staging-beta")),
+            spec("docs", None),
+        ])];
+
+        assert_eq!(
+            announcements(&snapshot(&before), &after),
+            vec![
+                "Changed: api: staging",
+                "New: docs: not running",
+                "Removed: old"
+            ]
+        );
+        assert!(announcements(&snapshot(&after), &after).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_spec_by_number_or_name() {
+        let projects = vec![project(vec![spec("web", None), spec("api", None)])];
+        let numbered = vec![
+            (projects[0].file.path.clone(), "web".to_string()),
+            (projects[0].file.path.clone(), "api".to_string()),
+        ];
+
+        assert_eq!(resolve_spec(&projects, &numbered, "2"), Ok((0, 1)));
+        assert_eq!(resolve_spec(&projects, &numbered, "web"), Ok((0, 0)));
+        assert!(resolve_spec(&projects, &numbered, "3").is_err());
+        assert!(resolve_spec(&projects, &numbered, "0").is_err());
+        assert!(resolve_spec(&projects, &numbered, "nope").is_err());
+    }
+}
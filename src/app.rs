@@ -1,11 +1,68 @@
-use crate::config::{Config, DisplayMode, ThemeMode};
-use crate::mutagen::MutagenClient;
-use crate::project::{correlate_projects_with_sessions, discover_project_files, Project};
-use crate::selection::SelectionManager;
-use crate::theme::{detect_theme, ColorScheme};
+use crate::audit::{AuditEntry, AuditLog, AUDIT_VIEW_LIMIT};
+use crate::bookmarks::Bookmarks;
+use crate::browse::{self, DirBrowser};
+use crate::clipboard;
+use crate::config::{
+    format_settings, ColorDepthMode, ColumnsConfig, Config, DisplayMode, LayoutMode, Setting,
+    SettingSource, SortMode, ThemeMode, WatchdogAction,
+};
+#[cfg(unix)]
+use crate::control::ControlServer;
+use crate::cycles::CycleTracker;
+use crate::disk::DiskSpace;
+use crate::endpoint::EndpointAddress;
+use crate::favorites::Favorites;
+use crate::health::{HealthEvent, HealthMonitor, WatchdogFlag};
+use crate::i18n::t;
+use crate::icons::Icons;
+use crate::ignores::IgnoreEditor;
+use crate::init::Setup;
+use crate::lint::{self, format_issues, ProjectIssue, Severity};
+use crate::metrics::{self, MetricsServer};
+use crate::mutagen::{
+    get_project_lock_path, Endpoint, ForwardSession, HostReachability, MutagenClient, SafetyHalt,
+    StaleLock, SyncSession, PROJECT_LABEL,
+};
+use crate::notify::{lifecycle_events, NotificationEvent, Notifier};
+use crate::operations::{detect_external_changes, ExternalChange, EXTERNAL_CHANGE_LOG_LIMIT};
+use crate::progress::{ProgressCounter, ProgressReporter, Redraw};
+use crate::project::{
+    find_selection, search_dirs, sort_projects, sort_specs, update_projects, DiscoveredProjects,
+    DiscoveryCache, Project, ProjectFile, ProjectFileError,
+};
+use crate::resolution::{
+    format_history, to_csv, ConflictTracker, ResolutionLog, Winner, RESOLUTION_VIEW_LIMIT,
+};
+use crate::schedule::{Activity, DataSource, Reading, RefreshScheduler};
+use crate::selection::{SelectableItem, SelectionManager};
+use crate::spec_edit::{self, SpecFields};
+use crate::sync_history::SyncHistory;
+use crate::tabs::Tab;
+use crate::task::{self, TaskRun};
+use crate::theme::{detect_theme, ColorDepth, ColorScheme};
+use crate::transfer::TransferTracker;
+use crate::verify::{Ignores, VerifyMode, VerifyReport};
+use crate::watch::ProjectWatcher;
+use crate::wizard::{SessionWizard, WizardTarget};
 use anyhow::Result;
 use chrono::{DateTime, Local};
-use std::path::PathBuf;
+use ratatui::style::Color;
+use ratatui::widgets::ListState;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The program that opens a directory in the system file manager.
+#[cfg(target_os = "macos")]
+const FILE_MANAGER: &str = "open";
+#[cfg(target_os = "windows")]
+const FILE_MANAGER: &str = "explorer";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const FILE_MANAGER: &str = "xdg-open";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SessionDisplayMode {
@@ -16,6 +73,8 @@ pub enum SessionDisplayMode {
 #[derive(Debug, Clone)]
 pub enum StatusMessage {
     Info(String),
+    /// Work that is still under way, which the next refresh reports over.
+    Progress(String),
     Warning(String),
     Error(String),
 }
@@ -25,6 +84,10 @@ impl StatusMessage {
         Self::Info(msg.into())
     }
 
+    pub fn progress(msg: impl Into<String>) -> Self {
+        Self::Progress(msg.into())
+    }
+
     pub fn warning(msg: impl Into<String>) -> Self {
         Self::Warning(msg.into())
     }
@@ -35,7 +98,146 @@ impl StatusMessage {
 
     pub fn text(&self) -> &str {
         match self {
-            Self::Info(s) | Self::Warning(s) | Self::Error(s) => s,
+            Self::Info(s) | Self::Progress(s) | Self::Warning(s) | Self::Error(s) => s,
+        }
+    }
+}
+
+/// Text too long for the main view (a full status message, raw session JSON),
+/// shown in a scrollable popup.
+#[derive(Debug, Clone)]
+pub struct TextPopup {
+    pub title: String,
+    pub text: String,
+    pub fg: Color,
+    /// First visible line.
+    pub scroll: u16,
+    /// Scroll offset that brings the last line into view, updated whenever the
+    /// popup is drawn since it depends on the terminal size.
+    pub max_scroll: Cell<u16>,
+    /// File name and contents written to the current directory by 'w'.
+    pub export: Option<(String, String)>,
+}
+
+impl TextPopup {
+    pub fn new(title: impl Into<String>, text: impl Into<String>, fg: Color) -> Self {
+        let text = text.into();
+        let max_scroll = text.lines().count().saturating_sub(1) as u16;
+        Self {
+            title: title.into(),
+            text,
+            fg,
+            scroll: 0,
+            max_scroll: Cell::new(max_scroll),
+            export: None,
+        }
+    }
+
+    /// Let 'w' save `contents` as `file_name`.
+    pub fn with_export(mut self, file_name: impl Into<String>, contents: String) -> Self {
+        self.export = Some((file_name.into(), contents));
+        self
+    }
+}
+
+/// The commands a project file defines, and the user's own filled in for
+/// the selected spec, offered for running.
+#[derive(Debug, Clone)]
+pub struct TaskMenu {
+    pub project_file: PathBuf,
+    pub project_name: String,
+    /// Command names, the shell commands they run, and where they're from.
+    pub commands: Vec<(String, String, TaskKind)>,
+    pub selected: usize,
+}
+
+/// Where a command in the menu comes from, which decides how it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskKind {
+    /// The project file's `commands`, run with `mutagen project run`.
+    Project,
+    /// `[commands]` in config.toml, run in a shell as filled in.
+    Custom,
+}
+
+/// Things about the selected spec that can be copied to the clipboard.
+pub struct YankMenu {
+    pub spec_name: String,
+    /// Labels, the keys that pick them, and the text each copies.
+    pub items: Vec<(String, char, String)>,
+    pub selected: usize,
+}
+
+/// Word typed to confirm resetting a safety-halted session.
+pub const RESET_CONFIRMATION: &str = "reset";
+
+/// How long after an action the list keeps refreshing at the busy rate, to
+/// show the sessions it changed settling down.
+const BUSY_AFTER_ACTION: Duration = Duration::from_secs(5);
+
+/// A session halted by a safety check, waiting for the user to confirm a
+/// reset by typing `RESET_CONFIRMATION`.
+#[derive(Debug, Clone)]
+pub struct PendingReset {
+    pub identifier: String,
+    pub spec_name: String,
+    pub halt: SafetyHalt,
+    /// One-way sessions reset to match alpha, deleting files on beta.
+    pub one_way: bool,
+    pub paused: bool,
+    pub typed: String,
+}
+
+/// A change of a running spec's name, waiting for `y` to save it and
+/// recreate the session under the new name.
+#[derive(Debug, Clone)]
+pub struct PendingRename {
+    pub path: PathBuf,
+    pub before: SpecFields,
+    pub after: SpecFields,
+    pub session: SyncSession,
+}
+
+/// A flush waiting for its session to complete a sync cycle.
+#[derive(Debug, Clone)]
+struct PendingFlush {
+    spec_name: String,
+    /// The session's successful cycle count when the flush was requested.
+    cycles: u64,
+    started: Instant,
+}
+
+impl PendingFlush {
+    fn new(spec_name: &str, session: &SyncSession) -> Self {
+        Self {
+            spec_name: spec_name.to_string(),
+            cycles: session.successful_cycles.unwrap_or(0),
+            started: Instant::now(),
+        }
+    }
+}
+
+/// Shared flag used to ask an in-flight operation to stop early.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Wait until the token is cancelled, for racing against a long-running command.
+    pub async fn cancelled(&self) {
+        while !self.is_cancelled() {
+            tokio::time::sleep(Duration::from_millis(50)).await;
         }
     }
 }
@@ -43,21 +245,184 @@ impl StatusMessage {
 #[derive(Debug, Clone)]
 pub struct BlockingOperation {
     pub message: String,
+    /// Set when the user presses Esc; checked between mutagen commands.
+    pub cancel: CancellationToken,
+    /// Steps completed so far, shown in the modal.
+    pub progress: ProgressCounter,
 }
 
+impl BlockingOperation {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            cancel: CancellationToken::new(),
+            progress: ProgressCounter::new(),
+        }
+    }
+}
+
+/// What a refresh lists and re-reads.
+#[derive(Debug)]
+enum RefreshScope {
+    /// Every session, with project files discovered afresh.
+    Everything,
+    /// Every session, keeping the project files already loaded.
+    Sessions,
+    /// The sessions of the project file at this path, which is re-read.
+    Project(PathBuf),
+}
+
+/// Discovery running on a blocking thread, which hands back the cache it
+/// used along with what it found.
+type Discovery = tokio::task::JoinHandle<(DiscoveryCache, Result<DiscoveredProjects>)>;
+
 pub struct App {
     pub projects: Vec<Project>,
+    /// Project files that were found but failed to parse on the last refresh.
+    pub project_errors: Vec<ProjectFileError>,
+    /// Selected row on the parse error screen.
+    pub selected_project_error: usize,
+    /// Problems found in project files on the last refresh, shown by `I`.
+    /// Includes `startup_issues`.
+    pub project_issues: Vec<ProjectIssue>,
+    /// Problems with the config file and its search paths, found at startup.
+    startup_issues: Vec<ProjectIssue>,
     pub selection: SelectionManager,
     pub should_quit: bool,
     pub status_message: Option<StatusMessage>,
     pub mutagen_client: MutagenClient,
     pub color_scheme: ColorScheme,
+    /// Unicode or ASCII glyphs for icons.
+    pub icons: Icons,
     pub last_refresh: Option<DateTime<Local>>,
     pub project_dir: Option<PathBuf>,
     pub session_display_mode: SessionDisplayMode,
+    /// View shown below the header.
+    pub tab: Tab,
+    /// Forwarding sessions, listed while the Forward tab is open.
+    pub forwards: Vec<ForwardSession>,
+    /// Version reported by the mutagen CLI, fetched when the Daemon tab opens.
+    pub mutagen_version: Option<String>,
+    /// Recent audit log entries for the Log tab.
+    pub log_entries: Vec<AuditEntry>,
+    /// Show details of the selected row in a pane beside the list.
+    pub split_layout: bool,
+    /// Order of specs within each project.
+    pub sort_mode: SortMode,
     pub viewing_conflicts: bool,
+    /// Selected conflict and scroll offset in the conflict overlay. Interior
+    /// mutability lets drawing keep the offset in step with the selection.
+    pub conflict_list: RefCell<ListState>,
+    /// Result of the last checksum verification, shown as an overlay until closed.
+    pub verify_report: Option<VerifyReport>,
+    /// Audit log entries shown in the viewer overlay, newest first.
+    pub audit_view: Option<Vec<AuditEntry>>,
+    /// Scrollable text popup; captures keys while open.
+    pub text_popup: Option<TextPopup>,
+    /// Reset of a safety-halted session awaiting confirmation.
+    pub pending_reset: Option<PendingReset>,
+    /// The selected project's commands, while the menu is open.
+    pub task_menu: Option<TaskMenu>,
+    pub yank_menu: Option<YankMenu>,
+    /// The mutagen commands the last action ran, shown in learning mode.
+    pub last_commands: RefCell<Vec<String>>,
+    /// Keys that change sessions or files are turned off, for a shared
+    /// dashboard.
+    pub read_only: bool,
+    /// Commands dry-run actions would have run, not yet shown.
+    dry_run_commands: RefCell<Vec<String>>,
+    /// Directory browser over the selected spec's endpoints.
+    pub browser: Option<DirBrowser>,
+    /// A project command that is still running, its output shown in the
+    /// text popup.
+    task: Option<TaskRun>,
+    /// The session form, while it is open.
+    pub wizard: Option<SessionWizard>,
+    /// The ignores overlay, while it is open.
+    pub ignore_editor: Option<IgnoreEditor>,
+    /// Project file and name of a spec waiting for `y` to delete it.
+    pub pending_delete: Option<(PathBuf, String)>,
+    /// Terminating sessions another machine or user created, waiting for `y`.
+    pub pending_terminate: bool,
+    /// A stale project lock waiting for `y` to remove it and start the
+    /// selected project again.
+    pub pending_lock_removal: Option<StaleLock>,
+    /// A running spec's new name, waiting for `y` to save it.
+    pub pending_rename: Option<PendingRename>,
+    /// First-run setup, offered while there's no config file.
+    pub setup: Option<Setup>,
     pub has_refresh_error: bool, // Track if last refresh failed to prevent error loops
     pub blocking_op: Option<BlockingOperation>,
+    /// Keys typed while a blocking operation ran, handled once it's done.
+    pub typeahead: Arc<Mutex<VecDeque<crossterm::event::KeyEvent>>>,
+    /// All sessions returned by the most recent successful refresh.
+    pub sessions: Vec<SyncSession>,
+    /// Latest external change per session name, shown as a row marker.
+    pub external_changes: HashMap<String, ExternalChange>,
+    /// Chronological log of external changes (bounded).
+    pub external_change_log: Vec<ExternalChange>,
+    /// Per-session state used to detect conflicts, disconnects, and stalls.
+    health: HealthMonitor,
+    /// Record of actions performed through the TUI.
+    pub audit_log: AuditLog,
+    /// History of resolved conflicts.
+    pub resolution_log: ResolutionLog,
+    /// Number keys bound to projects.
+    pub bookmarks: Bookmarks,
+    /// Projects that sort first, and that `A` can limit the list to.
+    pub favorites: Favorites,
+    /// When sessions last synced, kept across restarts.
+    sync_history: SyncHistory,
+    /// Whether `b` was pressed and the next digit binds a bookmark.
+    pub pending_bookmark: bool,
+    /// Key of the last action applied to a whole project, replayed by
+    /// Shift+digit.
+    pub last_project_action: Option<char>,
+    /// Count typed before a motion key, as in vim's `5j`.
+    pub pending_count: Option<usize>,
+    /// Rows of the project list that fit on screen, updated whenever it is
+    /// drawn, for half-page moves.
+    pub list_height: Cell<u16>,
+    /// When the last mutagen action ran, to refresh quickly for a while after
+    last_action: Cell<Option<Instant>>,
+    /// Conflicts seen on the last refresh, to notice ones that get resolved.
+    conflict_tracker: ConflictTracker,
+    /// Flushes waiting for their sync cycle, keyed by session identifier.
+    pending_flushes: HashMap<String, PendingFlush>,
+    /// Channels that health events are sent to.
+    notifier: Notifier,
+    /// Staging byte counts between refreshes, used for transfer rates.
+    pub transfers: TransferTracker,
+    /// Successful cycle counts between refreshes, used for cycle rates.
+    pub cycles: CycleTracker,
+    /// Where the session gauges are served, with `--metrics-addr`.
+    pub metrics: Option<MetricsServer>,
+    /// Requests from editor plugins and scripts, with `--control-socket`.
+    #[cfg(unix)]
+    pub control: Option<ControlServer>,
+    /// When the slower data sources (disk space) are next due.
+    pub scheduler: RefreshScheduler,
+    /// Latest free space per endpoint, keyed by `Endpoint::display_path`.
+    pub disk_space: HashMap<String, Reading<DiskSpace>>,
+    /// Latest reachability check per ssh host, keyed by the host as
+    /// endpoints name it.
+    pub host_checks: HashMap<String, Reading<HostReachability>>,
+    /// Reports edits to project files between refreshes; only the TUI has one.
+    project_watcher: Option<ProjectWatcher>,
+    /// Project discovery running in the background.
+    discovery: Option<Discovery>,
+    /// Project files loaded by earlier discoveries. Discovery takes it
+    /// while it runs.
+    discovery_cache: DiscoveryCache,
+    /// Something may have changed while discovery was running, so it should
+    /// run again.
+    rediscover: bool,
+    /// The project or spec to select once project files have been found,
+    /// and whether to open its conflicts.
+    startup_selection: Option<(String, bool)>,
+    /// Settings given as command-line flags, by dotted config key, with the
+    /// values they set in TOML syntax.
+    flag_settings: Vec<(String, String)>,
     config: Config,
 }
 
@@ -65,33 +430,311 @@ impl App {
     pub fn new(project_dir: Option<PathBuf>) -> Self {
         // Load config (use defaults if file doesn't exist or has errors)
         let config = Config::load().unwrap_or_default();
+        let depth = Self::color_depth_for(&config);
+        let (color_scheme, theme_error) = Self::color_scheme_for(&config, depth);
+
+        let notifier = Notifier::from_config(&config, false);
+        let watch_files = config.refresh.watch_files;
+        let mut app = Self::with_parts(project_dir, config, color_scheme, notifier);
+        if watch_files {
+            app.project_watcher = ProjectWatcher::new()
+                .inspect_err(|e| tracing::warn!(error = %e, "cannot watch project files"))
+                .ok();
+        }
+        let config_issues = Config::check();
+        let mut startup_issues = config_issues.clone();
+        startup_issues.extend(app.config.search_path_issues());
+        if !startup_issues.is_empty() {
+            app.startup_issues = startup_issues.clone();
+            app.set_project_issues(startup_issues);
+        }
+        if config_issues
+            .iter()
+            .any(|issue| issue.severity == Severity::Error)
+        {
+            app.show_config_errors(&config_issues, t!("status.config_defaults"));
+        }
+        app.setup = Setup::offer(app.project_dir.as_deref());
+        if let Some(e) = theme_error {
+            tracing::warn!(error = %e, "custom theme not applied");
+            app.status_message = Some(StatusMessage::warning(t!(
+                "status.theme_not_applied",
+                error = format!("{:#}", e)
+            )));
+        }
+        app
+    }
+
+    /// Color depth to draw with: probe the terminal unless forced in config.
+    fn color_depth_for(config: &Config) -> ColorDepth {
+        match config.ui.color_depth {
+            ColorDepthMode::Auto => ColorDepth::detect(),
+            ColorDepthMode::Basic => ColorDepth::Basic,
+            ColorDepthMode::Ansi256 => ColorDepth::Ansi256,
+            ColorDepthMode::TrueColor => ColorDepth::TrueColor,
+        }
+    }
 
-        // Determine color scheme based on config theme setting
+    /// Color scheme for the configured theme, and the reason the custom
+    /// colors weren't applied if they couldn't be. A bad custom theme
+    /// shouldn't keep the app from starting.
+    fn color_scheme_for(
+        config: &Config,
+        depth: ColorDepth,
+    ) -> (ColorScheme, Option<anyhow::Error>) {
         let color_scheme = match config.ui.theme {
-            ThemeMode::Auto => detect_theme(),
-            ThemeMode::Light => ColorScheme::light(),
-            ThemeMode::Dark => ColorScheme::dark(),
+            ThemeMode::Auto => detect_theme(depth),
+            ThemeMode::Light => ColorScheme::light_for(depth),
+            ThemeMode::Dark => ColorScheme::dark_for(depth),
+            ThemeMode::Custom => detect_theme(depth),
+        };
+        match Self::custom_theme(config, color_scheme.clone()) {
+            Ok(scheme) => (scheme, None),
+            Err(e) => (color_scheme, Some(e)),
+        }
+    }
+
+    /// Switch to the next of auto, light, and dark, and save the choice to
+    /// the config file.
+    pub fn cycle_theme(&mut self) {
+        self.config.ui.theme = self.config.ui.theme.next();
+        let (color_scheme, theme_error) =
+            Self::color_scheme_for(&self.config, self.color_scheme.depth);
+        self.color_scheme = color_scheme;
+
+        let theme = self.config.ui.theme;
+        self.status_message = Some(match (theme_error, Config::save_theme(theme)) {
+            (Some(e), _) => StatusMessage::warning(t!(
+                "status.theme_colors_not_applied",
+                theme = theme.label(),
+                error = format!("{:#}", e)
+            )),
+            (None, Ok(_)) => StatusMessage::info(t!("status.theme", theme = theme.label())),
+            (None, Err(e)) => {
+                tracing::warn!(error = %e, "failed to save theme");
+                StatusMessage::warning(t!(
+                    "status.theme_not_saved",
+                    theme = theme.label(),
+                    error = format!("{:#}", e)
+                ))
+            }
+        });
+    }
+
+    /// Re-read the config file and apply it. If it has errors, the current
+    /// settings stay and the errors are shown.
+    pub fn reload_config(&mut self) {
+        match Config::load() {
+            Ok(config) => {
+                self.status_message = Some(StatusMessage::info(t!("status.config_reloaded")));
+                self.apply_config(config);
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "config file not reloaded");
+                self.show_config_errors(&Config::check(), t!("status.config_kept"));
+                self.status_message = Some(StatusMessage::error(t!("status.config_not_reloaded")));
+            }
+        }
+    }
+
+    /// Switch to the settings in `config` without restarting. The theme,
+    /// icons, notifications, and watching take effect now; refresh intervals
+    /// and project discovery are read from the config as they're used. The
+    /// sort, layout, and display mode stay as they were chosen, and
+    /// `[mutagen]` settings apply from the next start.
+    pub fn apply_config(&mut self, config: Config) {
+        let depth = Self::color_depth_for(&config);
+        let (color_scheme, theme_error) = Self::color_scheme_for(&config, depth);
+        self.color_scheme = color_scheme;
+        if let Some(e) = theme_error {
+            self.status_message = Some(StatusMessage::warning(t!(
+                "status.theme_not_applied",
+                error = format!("{:#}", e)
+            )));
+        }
+        if !self.flag_settings.iter().any(|(key, _)| key == "ui.ascii") {
+            self.icons = Icons::new(config.ui.ascii);
+        }
+        self.notifier = Notifier::from_config(&config, false);
+        self.scheduler = RefreshScheduler::new()
+            .with_interval(DataSource::DiskSpace, config.refresh.disk_space_secs);
+        if !config.refresh.watch_files {
+            self.project_watcher = None;
+        } else if self.project_watcher.is_none() {
+            self.project_watcher = ProjectWatcher::new()
+                .inspect_err(|e| tracing::warn!(error = %e, "cannot watch project files"))
+                .ok();
+        }
+        self.config = config;
+
+        let found: Vec<ProjectIssue> = self
+            .project_issues
+            .iter()
+            .filter(|issue| !self.startup_issues.contains(issue))
+            .cloned()
+            .collect();
+        self.startup_issues = Config::check();
+        self.startup_issues.extend(self.config.search_path_issues());
+        self.set_project_issues(found);
+    }
+
+    /// Apply the palette file and `[ui.colors]` to `base` when the custom
+    /// theme or a palette is selected.
+    fn custom_theme(config: &Config, base: ColorScheme) -> Result<ColorScheme> {
+        if config.ui.theme != ThemeMode::Custom && config.ui.palette.is_none() {
+            return Ok(base);
+        }
+        let mut colors = match &config.ui.palette {
+            Some(name) => Config::load_palette(name)?,
+            None => Default::default(),
         };
+        colors.extend(config.ui.colors.clone());
+        base.with_colors(&colors)
+    }
+
+    /// Create an app for daemon mode: no terminal probing, desktop notifications on.
+    pub fn headless(project_dir: Option<PathBuf>) -> Self {
+        let config = Config::load().unwrap_or_default();
+        let notifier = Notifier::from_config(&config, true);
+        Self::with_parts(project_dir, config, ColorScheme::dark(), notifier)
+    }
+
+    /// Create an app for the plain-text accessible mode: no terminal probing,
+    /// notifications as configured.
+    pub fn plain(project_dir: Option<PathBuf>) -> Self {
+        let config = Config::load().unwrap_or_default();
+        let notifier = Notifier::from_config(&config, false);
+        Self::with_parts(project_dir, config, ColorScheme::dark(), notifier)
+    }
+
+    /// Create an app that runs mutagen commands through `runner` and keeps
+    /// its state files in `state_dir`, with default config and no
+    /// notifications.
+    #[cfg(test)]
+    pub fn for_test(
+        project_dir: PathBuf,
+        runner: crate::mutagen::SharedRunner,
+        state_dir: &std::path::Path,
+    ) -> Self {
+        let mut app = Self::with_parts(
+            Some(project_dir),
+            Config::default(),
+            ColorScheme::dark(),
+            Notifier::new(),
+        );
+        app.mutagen_client = MutagenClient::with_runner(runner);
+        app.audit_log = AuditLog::at(&state_dir.join("audit.log"));
+        app.resolution_log = ResolutionLog::at(&state_dir.join("resolutions.log"));
+        app.bookmarks = Bookmarks::at(&state_dir.join("bookmarks.json"));
+        app.favorites = Favorites::at(&state_dir.join("favorites.json"));
+        app.sync_history = SyncHistory::at(&state_dir.join("sync_history.json"));
+        app
+    }
+
+    /// The config, for tests to change settings after loading.
+    #[cfg(test)]
+    pub fn config_mut(&mut self) -> &mut Config {
+        &mut self.config
+    }
 
+    fn with_parts(
+        project_dir: Option<PathBuf>,
+        config: Config,
+        color_scheme: ColorScheme,
+        notifier: Notifier,
+    ) -> Self {
+        let mutagen_client = MutagenClient::with_config(&config.mutagen);
+        if config.ui.learning {
+            mutagen_client.record_commands();
+        }
         // Map config display mode to session display mode
         let session_display_mode = match config.ui.default_display_mode {
             DisplayMode::Paths => SessionDisplayMode::ShowPaths,
             DisplayMode::LastRefresh => SessionDisplayMode::ShowLastRefresh,
         };
+        let mut health = HealthMonitor::new(Duration::from_secs(config.notifications.stall_secs));
+        if config.watchdog.disconnect_secs > 0 {
+            health = health.offline_after(Duration::from_secs(config.watchdog.disconnect_secs));
+        }
 
         Self {
             projects: Vec::new(),
-            selection: SelectionManager::new(),
+            project_errors: Vec::new(),
+            project_issues: Vec::new(),
+            startup_issues: Vec::new(),
+            selected_project_error: 0,
+            selection: SelectionManager::new()
+                .sorted_by(config.ui.default_sort)
+                .with_ssh_config(mutagen_client.ssh_config().clone()),
             should_quit: false,
             status_message: None,
-            mutagen_client: MutagenClient::new(),
+            mutagen_client,
             color_scheme,
+            icons: Icons::new(config.ui.ascii),
             last_refresh: None,
             project_dir,
             session_display_mode,
+            tab: Tab::Sync,
+            forwards: Vec::new(),
+            mutagen_version: None,
+            log_entries: Vec::new(),
+            split_layout: config.ui.layout == LayoutMode::Split,
+            sort_mode: config.ui.default_sort,
             viewing_conflicts: false,
+            conflict_list: RefCell::new(ListState::default()),
+            verify_report: None,
+            audit_view: None,
+            text_popup: None,
+            pending_reset: None,
+            task_menu: None,
+            yank_menu: None,
+            last_commands: RefCell::new(Vec::new()),
+            read_only: false,
+            dry_run_commands: RefCell::new(Vec::new()),
+            browser: None,
+            task: None,
+            wizard: None,
+            ignore_editor: None,
+            pending_delete: None,
+            pending_terminate: false,
+            pending_lock_removal: None,
+            pending_rename: None,
+            setup: None,
             has_refresh_error: false,
             blocking_op: None,
+            typeahead: Arc::default(),
+            sessions: Vec::new(),
+            external_changes: HashMap::new(),
+            external_change_log: Vec::new(),
+            audit_log: AuditLog::new(),
+            resolution_log: ResolutionLog::new(),
+            bookmarks: Bookmarks::load(),
+            favorites: Favorites::load(),
+            sync_history: SyncHistory::load(),
+            pending_bookmark: false,
+            last_project_action: None,
+            pending_count: None,
+            list_height: Cell::new(20),
+            last_action: Cell::new(None),
+            conflict_tracker: ConflictTracker::new(),
+            pending_flushes: HashMap::new(),
+            health,
+            notifier,
+            transfers: TransferTracker::new(),
+            cycles: CycleTracker::new(),
+            metrics: None,
+            #[cfg(unix)]
+            control: None,
+            scheduler: RefreshScheduler::new()
+                .with_interval(DataSource::DiskSpace, config.refresh.disk_space_secs),
+            disk_space: HashMap::new(),
+            host_checks: HashMap::new(),
+            project_watcher: None,
+            discovery: None,
+            discovery_cache: DiscoveryCache::default(),
+            rediscover: false,
+            startup_selection: None,
+            flag_settings: Vec::new(),
             config,
         }
     }
@@ -99,118 +742,357 @@ impl App {
     // ============ Selection accessors (delegate to SelectionManager) ============
 
 
+    /// Refresh every session and start rediscovering project files. What
+    /// discovery finds shows up once it finishes.
     pub async fn refresh_sessions(&mut self) -> Result<()> {
-        match self.mutagen_client.list_sessions().await {
-            Ok(sessions) => {
-                // Track when successfulCycles changes to detect actual sync activity
-                // We need to preserve sync_time from previous refresh
-                let is_first_refresh = self.projects.is_empty();
+        self.refresh(RefreshScope::Everything).await?;
+        self.refresh_tab().await;
+        Ok(())
+    }
 
-                // Build map of old sessions by identifier for sync_time tracking
-                let mut old_sessions_by_id = std::collections::HashMap::new();
-                for project in &self.projects {
-                    for spec in &project.specs {
-                        if let Some(session) = &spec.running_session {
-                            old_sessions_by_id.insert(session.identifier.clone(), session.clone());
-                        }
+    /// The timed refresh. Project files are only rediscovered when there's
+    /// no watcher to report new ones.
+    pub async fn auto_refresh(&mut self) -> Result<()> {
+        let scope = if self.project_watcher.is_some() {
+            RefreshScope::Sessions
+        } else {
+            RefreshScope::Everything
+        };
+        self.refresh(scope).await?;
+        self.refresh_tab().await;
+        Ok(())
+    }
+
+    /// Switch to `tab` and load what it shows.
+    pub async fn select_tab(&mut self, tab: Tab) {
+        self.tab = tab;
+        self.refresh_tab().await;
+    }
+
+    /// Reload the data shown by the open tab. The sessions in the sync and
+    /// hosts tabs come from the regular refresh.
+    async fn refresh_tab(&mut self) {
+        match self.tab {
+            Tab::Sync | Tab::Hosts => {}
+            Tab::Forward => match self.mutagen_client.list_forwards().await {
+                Ok(forwards) => self.forwards = forwards,
+                Err(e) => {
+                    self.status_message = Some(StatusMessage::error(t!(
+                        "status.forwards_failed",
+                        error = e
+                    )));
+                }
+            },
+            Tab::Daemon => {
+                if self.mutagen_version.is_none() {
+                    match self.mutagen_client.version().await {
+                        Ok(version) => self.mutagen_version = Some(version),
+                        Err(e) => tracing::debug!(error = %e, "mutagen version failed"),
                     }
                 }
+            }
+            Tab::Log => match self.audit_log.recent(AUDIT_VIEW_LIMIT) {
+                Ok(entries) => self.log_entries = entries,
+                Err(e) => {
+                    self.status_message = Some(StatusMessage::error(t!(
+                        "status.audit_log_failed",
+                        error = e
+                    )));
+                }
+            },
+        }
+    }
 
-                let new_sessions: Vec<_> = sessions
-                    .into_iter()
-                    .map(|mut new_session| {
-                        // Find the previous version of this session
-                        if let Some(old_session) = old_sessions_by_id.get(&new_session.identifier) {
-                            // If successfulCycles increased, we observed a sync
-                            let new_cycles = new_session.successful_cycles.unwrap_or(0);
-                            let old_cycles = old_session.successful_cycles.unwrap_or(0);
-                            if new_cycles > old_cycles {
-                                new_session.sync_time = crate::mutagen::SyncTime::At;
-                            } else {
-                                // Keep the previous sync_time
-                                new_session.sync_time = old_session.sync_time.clone();
-                            }
-                        } else {
-                            // Newly discovered session
-                            if is_first_refresh {
-                                // First refresh: all sessions pre-existed, sync history unknown
-                                new_session.sync_time = crate::mutagen::SyncTime::Unknown;
-                            } else {
-                                // Session discovered after first refresh
-                                let cycles = new_session.successful_cycles.unwrap_or(0);
-                                new_session.sync_time = if cycles > 0 {
-                                    crate::mutagen::SyncTime::At
-                                } else {
-                                    crate::mutagen::SyncTime::Never
-                                };
-                            }
-                        }
-                        new_session
-                    })
-                    .collect();
+    /// Refresh only the selected project's sessions and re-read its project file.
+    ///
+    /// Falls back to a full refresh when no project is selected or a session
+    /// has gone away. Sessions created elsewhere for the project only show up
+    /// after a full refresh, unless `mutagen project start` labeled them.
+    pub async fn refresh_selected_project(&mut self) -> Result<()> {
+        let scope = self
+            .get_selected_project_index()
+            .and_then(|idx| self.projects.get(idx))
+            .map(|project| project.file.path.clone());
+        self.refresh(scope.map_or(RefreshScope::Everything, RefreshScope::Project))
+            .await
+    }
 
-                // Save current fold state before rebuilding projects
-                let fold_state: std::collections::HashMap<_, _> = self
-                    .projects
-                    .iter()
-                    .map(|p| (p.file.path.clone(), p.folded))
-                    .collect();
+    /// Sessions from the last refresh with those of the project at `path`
+    /// listed afresh. Also returns the identifiers that were listed.
+    ///
+    /// A project `mutagen project start` is running is listed by its label,
+    /// which also finds sessions it gained or lost since the last refresh;
+    /// sessions without the label are listed by identifier.
+    async fn list_project_sessions(
+        &self,
+        path: &Path,
+    ) -> Result<(Vec<SyncSession>, Vec<String>)> {
+        let project = self.projects.iter().find(|p| p.file.path == path);
+        let label = project.and_then(|p| p.project_identifier());
+        let labeled = |session: &SyncSession| {
+            label.is_some() && session.labels.get(PROJECT_LABEL).map(String::as_str) == label
+        };
+        let identifiers: Vec<String> = project
+            .into_iter()
+            .flat_map(|p| &p.specs)
+            .filter_map(|spec| spec.running_session.as_ref())
+            .filter(|session| !labeled(session))
+            .map(|session| session.identifier.clone())
+            .collect();
+        if label.is_none() && identifiers.is_empty() {
+            return Ok((self.sessions.clone(), identifiers));
+        }
 
-                match discover_project_files(
-                    self.project_dir.as_deref(),
-                    Some(&self.config.projects),
-                ) {
-                    Ok(project_files) => {
-                        self.projects =
-                            correlate_projects_with_sessions(project_files, &new_sessions);
-
-                        // Restore fold state for existing projects, use auto-unfold for new ones
-                        for project in &mut self.projects {
-                            if let Some(&saved_folded) = fold_state.get(&project.file.path) {
-                                project.folded = saved_folded;
-                            }
-                            // Otherwise keep the auto-unfold value from correlate_projects_with_sessions
-                        }
+        let mut fresh = Vec::new();
+        if let Some(label) = label {
+            let selector = format!("{}={}", PROJECT_LABEL, label);
+            fresh = self
+                .mutagen_client
+                .list_sessions_by_label(&selector)
+                .await?;
+        }
+        if !identifiers.is_empty() {
+            fresh.extend(
+                self.mutagen_client
+                    .list_sessions_by_id(&identifiers)
+                    .await?,
+            );
+        }
+
+        let listed: Vec<String> = fresh.iter().map(|s| s.identifier.clone()).collect();
+        let mut fresh: HashMap<String, SyncSession> = fresh
+            .into_iter()
+            .map(|session| (session.identifier.clone(), session))
+            .collect();
+        let mut sessions: Vec<SyncSession> = self
+            .sessions
+            .iter()
+            .filter_map(|session| {
+                if labeled(session) || identifiers.contains(&session.identifier) {
+                    fresh.remove(&session.identifier)
+                } else {
+                    Some(session.clone())
+                }
+            })
+            .collect();
+        // Sessions the label found that weren't listed before, in listing order
+        sessions.extend(
+            listed
+                .iter()
+                .filter_map(|identifier| fresh.remove(identifier)),
+        );
+        Ok((sessions, listed))
+    }
+
+    /// Project files from the last refresh, with the one at `path` re-read
+    /// from disk, and the issues found in them.
+    fn reread_project_file(&self, path: &Path) -> (Vec<ProjectFile>, Vec<ProjectIssue>) {
+        let (loaded, found) = lint::load(path);
+        // Discovery skips unreadable files, so a deleted or broken file drops out here too
+        let mut loaded = loaded
+            .inspect_err(|e| tracing::warn!(error = %e, "failed to re-read project file"))
+            .ok();
+        let files = self
+            .projects
+            .iter()
+            .filter_map(|project| {
+                if project.file.path != path {
+                    return Some(project.file.clone());
+                }
+                loaded.take()
+            })
+            .collect();
+        let mut issues: Vec<_> = self
+            .project_issues
+            .iter()
+            .filter(|issue| issue.path != path)
+            .cloned()
+            .collect();
+        issues.extend(found);
+        (files, issues)
+    }
+
+    /// Have the watcher report changes to the files behind the current
+    /// projects, including the ones that failed to load and the files they
+    /// include, to the workspace and config files, and to the directories
+    /// discovery searches.
+    fn watch_project_files(&mut self) {
+        if let Some(watcher) = &mut self.project_watcher {
+            let loaded = self.projects.iter().map(|p| p.file.path.as_path());
+            let failed = self.project_errors.iter().map(|e| e.path.as_path());
+            let included = self
+                .projects
+                .iter()
+                .flat_map(|p| p.file.includes.iter().map(PathBuf::as_path));
+            let workspace = self.config.projects.workspace.clone();
+            let config = Config::config_path();
+            let search_dirs = search_dirs(self.project_dir.as_deref(), Some(&self.config.projects));
+            let files = loaded
+                .chain(failed)
+                .chain(included)
+                .chain(workspace.as_deref())
+                .chain(config.as_deref());
+            watcher.watch(files, &search_dirs);
+        }
+    }
+
+    /// Re-read project files that changed on disk since the last check.
+    pub async fn reload_changed_project_files(&mut self) {
+        let Some(watcher) = &self.project_watcher else {
+            return;
+        };
+        let mut changes = watcher.changes();
+        if changes.is_empty() {
+            return;
+        }
+        tracing::debug!(?changes, "project files changed");
+        let config_path = Config::config_path();
+        if let Some(index) = changes
+            .files
+            .iter()
+            .position(|path| Some(path) == config_path.as_ref())
+        {
+            changes.files.remove(index);
+            // Saving the theme writes the file too; only report real changes
+            let current = toml::Value::try_from(&self.config).ok();
+            match Config::load() {
+                Ok(config) if toml::Value::try_from(&config).ok() == current => {}
+                _ => self.reload_config(),
+            }
+            // The search paths may have changed
+            changes.discover = true;
+        }
+        let all_loaded = changes
+            .files
+            .iter()
+            .all(|path| self.projects.iter().any(|p| &p.file.path == path));
+        if all_loaded && !changes.discover {
+            if self.is_discovering() {
+                // Discovery may have read the file before this change
+                self.rediscover = true;
+            }
+            for path in changes.files {
+                let _ = self.refresh(RefreshScope::Project(path)).await;
+            }
+        } else {
+            // A file that failed to parse may load now; only discovery picks it up
+            let _ = self.refresh(RefreshScope::Everything).await;
+        }
+    }
 
-                        // Sort projects alphabetically by display name
-                        self.projects
-                            .sort_by(|a, b| a.file.display_name().cmp(&b.file.display_name()));
+    /// Refresh sessions and projects as far as `scope` says.
+    #[tracing::instrument(skip(self))]
+    async fn refresh(&mut self, scope: RefreshScope) -> Result<()> {
+        let started = std::time::Instant::now();
+        let mut discover = matches!(scope, RefreshScope::Everything);
+        let (listed, scope) = match scope {
+            RefreshScope::Project(path) => match self.list_project_sessions(&path).await {
+                Ok((sessions, identifiers)) => (Ok(sessions), Some((path, identifiers))),
+                Err(e) => {
+                    // Most likely a session was terminated elsewhere; list everything instead
+                    tracing::debug!(error = %e, "project refresh failed, refreshing all");
+                    discover = true;
+                    (self.mutagen_client.list_sessions().await, None)
+                }
+            },
+            RefreshScope::Everything | RefreshScope::Sessions => {
+                (self.mutagen_client.list_sessions().await, None)
+            }
+        };
+        match listed {
+            Ok(sessions) => {
+                tracing::debug!(
+                    sessions = sessions.len(),
+                    elapsed_ms = started.elapsed().as_millis() as u64,
+                    "listed sessions"
+                );
+                // Track when successfulCycles changes to detect actual sync activity
+                let mut new_sessions = sessions;
+                let previous = self
+                    .last_refresh
+                    .is_some()
+                    .then_some(self.sessions.as_slice());
+                self.sync_history.observe(previous, &mut new_sessions);
+                let lifecycle = previous
+                    .map(|previous| lifecycle_events(previous, &new_sessions))
+                    .unwrap_or_default();
+
+                let external = if self.last_refresh.is_some() {
+                    let mut journal = self.mutagen_client.journal();
+                    let changes = detect_external_changes(&self.sessions, &new_sessions, &journal);
+                    journal.prune();
+                    changes
+                } else {
+                    Vec::new()
+                };
+                let health_events = self.health.check(&new_sessions);
+                for (spec, roots) in self.conflict_tracker.observe(&new_sessions) {
+                    self.record_resolutions(&spec, &roots, Winner::Unknown, "external");
+                }
+                match &scope {
+                    Some((_, identifiers)) => {
+                        let listed: Vec<_> = new_sessions
+                            .iter()
+                            .filter(|s| identifiers.contains(&s.identifier))
+                            .cloned()
+                            .collect();
+                        self.transfers.observe_subset(&listed);
+                        self.cycles.observe_subset(&listed);
                     }
-                    Err(e) => {
-                        // Note: Error is silently ignored here as project discovery is optional
-                        // The app continues to work without project correlation
-                        let _ = e; // Explicit acknowledgment of ignored error
+                    None => {
+                        self.transfers.observe(&new_sessions);
+                        self.cycles.observe(&new_sessions);
                     }
                 }
+                self.sessions = new_sessions;
 
-                // Rebuild selection manager from projects
-                self.selection.rebuild_from_projects(&self.projects);
+                // New project files show up when discovery finishes; until then
+                // the ones already loaded are matched with the new sessions
+                let discovered = match &scope {
+                    Some((path, _)) => {
+                        let (files, issues) = self.reread_project_file(path);
+                        DiscoveredProjects {
+                            files,
+                            errors: self.project_errors.clone(),
+                            issues,
+                        }
+                    }
+                    None => DiscoveredProjects {
+                        files: self.projects.iter().map(|p| p.file.clone()).collect(),
+                        errors: self.project_errors.clone(),
+                        issues: self.project_issues.clone(),
+                    },
+                };
+                self.set_projects(discovered);
+                self.update_metrics();
+                if discover {
+                    self.start_discovery();
+                }
 
                 self.last_refresh = Some(Local::now());
-                // Only show "Sessions refreshed" if there's no status message, or if showing temporary messages
-                let should_show_refreshed = self.status_message.is_none()
-                    || self
-                        .status_message
-                        .as_ref()
-                        .map(|msg| {
-                            msg.text() == "Creating push session..."
-                                || msg.text() == "Starting sync spec..."
-                        })
-                        .unwrap_or(false);
-
-                if should_show_refreshed {
-                    self.status_message = Some(StatusMessage::info("Sessions refreshed"));
+                // Only show "Sessions refreshed" over nothing or work in progress
+                if matches!(self.status_message, None | Some(StatusMessage::Progress(_))) {
+                    self.status_message = Some(StatusMessage::info(t!("status.refreshed")));
+                }
+                self.record_external_changes(external);
+                self.report_health_events(health_events).await;
+                for event in lifecycle {
+                    self.notify_event(event).await;
                 }
+                self.report_finished_flushes().await;
                 self.has_refresh_error = false; // Clear error flag on success
+                tracing::debug!(
+                    elapsed_ms = started.elapsed().as_millis() as u64,
+                    "refresh finished"
+                );
                 Ok(())
             }
             Err(e) => {
+                tracing::warn!(error = %e, "refresh failed");
                 // Display error to user but don't crash the UI
                 // Transient CLI failures (missing binary, timeouts) should not tear down the terminal
-                self.status_message = Some(StatusMessage::error(format!(
-                    "Error: {} (press 'r' to retry)",
-                    e
-                )));
+                self.status_message =
+                    Some(StatusMessage::error(t!("status.refresh_failed", error = e)));
                 self.has_refresh_error = true; // Set error flag to prevent auto-refresh loop
 
                 // Error is displayed in the UI status bar, no need for stderr output
@@ -219,14 +1101,291 @@ impl App {
         }
     }
 
+    /// Mark rows affected by external changes and report them in the status bar.
+    fn record_external_changes(&mut self, changes: Vec<ExternalChange>) {
+        // A local operation on a session supersedes any earlier external marker
+        {
+            let journal = self.mutagen_client.journal();
+            let sessions = &self.sessions;
+            self.external_changes.retain(|name, _| {
+                !sessions
+                    .iter()
+                    .any(|s| &s.name == name && journal.covers(s))
+            });
+        }
+
+        if changes.is_empty() {
+            return;
+        }
+
+        let message = if changes.len() == 1 {
+            t!("status.external_change", change = changes[0].describe())
+        } else {
+            t!(
+                "status.external_changes",
+                count = changes.len(),
+                change = changes[0].describe()
+            )
+        };
+        self.status_message = Some(StatusMessage::warning(message));
+
+        for change in changes {
+            self.external_changes
+                .insert(change.session_name.clone(), change.clone());
+            self.external_change_log.push(change);
+        }
+        let overflow = self
+            .external_change_log
+            .len()
+            .saturating_sub(EXTERNAL_CHANGE_LOG_LIMIT);
+        self.external_change_log.drain(..overflow);
+    }
+
+    /// Get the external change marker for a spec, if any.
+    ///
+    /// Looks up both the two-way and push session names since a terminated
+    /// session leaves the spec without a running session to match on.
+    pub fn external_change_for(&self, spec: &crate::project::SyncSpec) -> Option<&ExternalChange> {
+        if let Some(session) = &spec.running_session {
+            return self.external_changes.get(&session.name);
+        }
+        self.external_changes
+            .get(&spec.name)
+            .or_else(|| self.external_changes.get(&format!("{}-push", spec.name)))
+    }
+
+    /// How long a session has been in its current status (since first observed).
+    pub fn status_elapsed(&self, session: &SyncSession) -> Option<Duration> {
+        self.health.status_elapsed(&session.identifier)
+    }
+
+    /// How long a flush of a session has been waiting for its sync cycle.
+    pub fn flush_elapsed(&self, session: &SyncSession) -> Option<Duration> {
+        self.pending_flushes
+            .get(&session.identifier)
+            .map(|flush| flush.started.elapsed())
+    }
+
+    /// Whether the watchdog has flagged a session as stalled or offline.
+    pub fn watchdog_flag(&self, session: &SyncSession) -> Option<WatchdogFlag> {
+        self.health.flag(&session.identifier)
+    }
+
+    /// Resume or reset the sessions that `events` newly flag, as
+    /// `[watchdog] action` says. Returns a note for the status bar if
+    /// anything was done.
+    async fn run_watchdog(&mut self, events: &[HealthEvent]) -> Option<String> {
+        let flagged: Vec<_> = events
+            .iter()
+            .filter(|event| event.kind.is_watchdog())
+            .filter_map(|event| self.sessions.iter().find(|s| s.name == event.session_name))
+            .map(|session| (session.name.clone(), session.identifier.clone()))
+            .collect();
+        let action = self.config.watchdog.action;
+        if flagged.is_empty() || action == WatchdogAction::None {
+            return None;
+        }
+
+        let mut done = 0;
+        let mut failure = None;
+        for (name, identifier) in flagged {
+            let (verb, result) = match action {
+                WatchdogAction::Reset => (
+                    "auto-reset",
+                    self.mutagen_client.reset_session(&identifier).await,
+                ),
+                _ => (
+                    "auto-resume",
+                    self.mutagen_client.resume_session(&identifier).await,
+                ),
+            };
+            self.audit(verb, &name, &result);
+            match result {
+                Ok(()) => done += 1,
+                Err(e) => {
+                    failure.get_or_insert_with(|| {
+                        t!("status.watchdog_failed", name = name, error = e)
+                    });
+                }
+            }
+        }
+        failure.or_else(|| {
+            Some(match action {
+                WatchdogAction::Reset => t!("status.watchdog_reset", count = done),
+                _ => t!("status.watchdog_resumed", count = done),
+            })
+        })
+    }
+
+    /// Send health events to the notifier and summarize them in the status bar.
+    async fn report_health_events(&mut self, events: Vec<HealthEvent>) {
+        if events.is_empty() {
+            return;
+        }
+
+        let notifications: Vec<_> = events
+            .iter()
+            .cloned()
+            .map(NotificationEvent::Health)
+            .collect();
+        let failures = self.notifier.notify_all(&notifications).await;
+        let watchdog = self.run_watchdog(&events).await;
+
+        let mut message = events
+            .iter()
+            .map(HealthEvent::describe)
+            .collect::<Vec<_>>()
+            .join("; ");
+        if let Some(note) = watchdog {
+            message.push_str(&format!("; {}", note));
+        }
+        if let Some(failure) = failures.first() {
+            message.push_str(&format!(" ({})", failure));
+        }
+        self.status_message = Some(StatusMessage::warning(message));
+    }
+
+    /// Announce flushes whose session has completed a sync cycle since the
+    /// flush was requested, and give up on ones that have waited longer than
+    /// `flush_timeout_secs`. Ones whose session went away are forgotten.
+    async fn report_finished_flushes(&mut self) {
+        if self.pending_flushes.is_empty() {
+            return;
+        }
+        let sessions = &self.sessions;
+        let timeout_secs = self.config.mutagen.flush_timeout_secs;
+        let mut finished = Vec::new();
+        let mut unfinished = Vec::new();
+        self.pending_flushes.retain(|identifier, flush| {
+            let Some(session) = sessions.iter().find(|s| &s.identifier == identifier) else {
+                return false;
+            };
+            if session.successful_cycles.unwrap_or(0) > flush.cycles {
+                finished.push(flush.spec_name.clone());
+                return false;
+            }
+            if flush.started.elapsed() >= Duration::from_secs(timeout_secs) {
+                unfinished.push(flush.spec_name.clone());
+                return false;
+            }
+            true
+        });
+        if finished.is_empty() {
+            if !unfinished.is_empty() {
+                self.status_message = Some(StatusMessage::warning(t!(
+                    "status.flush_unfinished",
+                    secs = timeout_secs,
+                    names = unfinished.join(", ")
+                )));
+            }
+            return;
+        }
+
+        let events: Vec<_> = finished
+            .iter()
+            .cloned()
+            .map(NotificationEvent::FlushCompleted)
+            .collect();
+        let failures = self.notifier.notify_all(&events).await;
+        let mut message = t!("status.flush_finished", names = finished.join(", "));
+        if let Some(failure) = failures.first() {
+            message.push_str(&format!(" ({})", failure));
+        }
+        self.status_message = Some(StatusMessage::info(message));
+    }
+
+    /// Note failures background notifications reported in the status bar.
+    pub fn poll_notifications(&mut self) {
+        let failures = self.notifier.take_failures();
+        if let Some(failure) = failures.first() {
+            self.status_message = Some(StatusMessage::warning(failure.clone()));
+        }
+    }
+
+    /// Send a lifecycle event, noting any delivery failure in the status bar.
+    async fn notify_event(&mut self, event: NotificationEvent) {
+        let failures = self.notifier.notify_all(&[event]).await;
+        if let Some(failure) = failures.first() {
+            let text = self
+                .status_message
+                .as_ref()
+                .map(|status| format!("{} ({})", status.text(), failure))
+                .unwrap_or_else(|| failure.clone());
+            self.status_message = Some(StatusMessage::warning(text));
+        }
+    }
+
     pub fn select_next(&mut self) {
+        if self.all_projects_invalid() {
+            if self.selected_project_error + 1 < self.project_errors.len() {
+                self.selected_project_error += 1;
+            }
+            return;
+        }
         self.selection.select_next();
     }
 
     pub fn select_previous(&mut self) {
+        if self.all_projects_invalid() {
+            self.selected_project_error = self.selected_project_error.saturating_sub(1);
+            return;
+        }
         self.selection.select_previous();
     }
 
+    /// Move the selection `delta` rows, stopping at the first or last row.
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.all_projects_invalid() {
+            let last = self.project_errors.len().saturating_sub(1);
+            self.selected_project_error = self
+                .selected_project_error
+                .saturating_add_signed(delta)
+                .min(last);
+            return;
+        }
+        self.selection.move_by(delta);
+    }
+
+    /// Move the selection half the height of the project list, down if
+    /// `down` and otherwise up.
+    pub fn move_half_page(&mut self, down: bool) {
+        let rows = (self.list_height.get() / 2).max(1) as isize;
+        self.move_selection(if down { rows } else { -rows });
+    }
+
+    /// Select the row `count`, counting from 1, or the last row.
+    pub fn select_row(&mut self, count: Option<usize>) {
+        self.move_selection(isize::MIN);
+        match count {
+            Some(count) => self.move_selection(count.saturating_sub(1) as isize),
+            None => self.move_selection(isize::MAX),
+        }
+    }
+
+    /// Add `digit` to the count for the next motion key.
+    pub fn push_count_digit(&mut self, count: Option<usize>, digit: u8) {
+        let count = count
+            .unwrap_or(0)
+            .saturating_mul(10)
+            .saturating_add(digit as usize);
+        self.pending_count = Some(count);
+        self.status_message = Some(StatusMessage::info(t!("status.count", count = count)));
+    }
+
+    /// Whether project files were found but none of them could be parsed,
+    /// as opposed to there being no project files at all.
+    pub fn all_projects_invalid(&self) -> bool {
+        self.projects.is_empty() && !self.project_errors.is_empty()
+    }
+
+    /// The failed project file selected on the parse error screen.
+    pub fn selected_project_error(&self) -> Option<&ProjectFileError> {
+        if !self.all_projects_invalid() {
+            return None;
+        }
+        self.project_errors.get(self.selected_project_error)
+    }
+
     /// Toggle fold state for a project
     pub fn toggle_project_fold(&mut self, project_idx: usize) {
         if let Some(project) = self.projects.get_mut(project_idx) {
@@ -236,40 +1395,267 @@ impl App {
         }
     }
 
-    /// Get the selected project index (either directly or parent of selected spec)
-    pub fn get_selected_project_index(&self) -> Option<usize> {
-        self.selection.selected_project_index()
+    /// Fold or unfold every project. A selected spec that gets folded away
+    /// leaves its project selected.
+    pub fn set_all_folded(&mut self, folded: bool) {
+        for project in &mut self.projects {
+            project.folded = folded;
+        }
+        self.selection.rebuild_from_projects(&self.projects);
     }
 
-    /// Get the selected spec (returns (project_index, spec_index) if a spec is selected)
-    pub fn get_selected_spec(&self) -> Option<(usize, usize)> {
-        self.selection.selected_spec()
+    /// Get the selected project index (either directly or parent of selected spec)
+    /// Once project files have been found, select the project or spec named
+    /// on the command line or in `startup.select`, and open its conflicts if
+    /// asked to and there are any.
+    pub fn select_on_startup(&mut self, select: Option<String>, open_conflicts: bool) {
+        let Some(target) = select.or_else(|| self.config.startup.select.clone()) else {
+            return;
+        };
+        let open_conflicts = open_conflicts || self.config.startup.open_conflicts;
+        self.startup_selection = Some((target, open_conflicts));
     }
 
-    pub fn selected_project_has_running_specs(&self) -> bool {
-        if let Some(project_idx) = self.get_selected_project_index() {
-            if let Some(project) = self.projects.get(project_idx) {
-                return project.specs.iter().any(|s| s.is_running());
+    fn select_target(&mut self, target: &str, open_conflicts: bool) {
+        match find_selection(&self.projects, target) {
+            Some((project_idx, Some(spec_idx))) => {
+                self.select_spec(project_idx, spec_idx);
+                if open_conflicts && self.projects[project_idx].specs[spec_idx].has_conflicts() {
+                    self.toggle_conflict_view();
+                }
+            }
+            Some((project_idx, None)) => {
+                self.projects[project_idx].folded = false;
+                self.selection.rebuild_from_projects(&self.projects);
+                self.selection
+                    .select_item(&SelectableItem::Project { index: project_idx });
+            }
+            None => {
+                self.status_message = Some(StatusMessage::warning(t!(
+                    "status.no_match",
+                    target = target
+                )));
             }
         }
-        false
     }
 
-    pub async fn pause_selected(&mut self) {
-        if let Some((proj_idx, spec_idx)) = self.get_selected_spec() {
+    /// Wait for a digit to bind the selected project to.
+    pub fn start_bookmark(&mut self) {
+        let Some(project) = self
+            .get_selected_project_index()
+            .and_then(|idx| self.projects.get(idx))
+        else {
+            self.status_message =
+                Some(StatusMessage::info(t!("status.select_project_to_bookmark")));
+            return;
+        };
+        self.pending_bookmark = true;
+        self.status_message = Some(StatusMessage::info(t!(
+            "status.bookmark_prompt",
+            project = project.file.display_name()
+        )));
+    }
+
+    /// Bind `digit` to the selected project, or clear its binding for 0.
+    pub fn assign_bookmark(&mut self, digit: u8) {
+        self.pending_bookmark = false;
+        let Some(project) = self
+            .get_selected_project_index()
+            .and_then(|idx| self.projects.get(idx))
+        else {
+            return;
+        };
+        let name = project.file.display_name();
+        let result = if digit == 0 {
+            self.bookmarks
+                .clear(&project.file.path)
+                .map(|cleared| match cleared {
+                    Some(old) => t!("status.bookmark_removed", digit = old, project = name),
+                    None => t!("status.no_bookmark_to_remove", project = name),
+                })
+        } else {
+            self.bookmarks
+                .assign(digit, &project.file.path)
+                .map(|()| t!("status.bookmarked", project = name, digit = digit))
+        };
+        self.status_message = Some(match result {
+            Ok(message) => StatusMessage::info(message),
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to save bookmarks");
+                StatusMessage::warning(t!("status.bookmark_not_saved", error = format!("{:#}", e)))
+            }
+        });
+    }
+
+    /// Mark the selected project as a favorite, or unmark it, moving it to
+    /// its place in the list.
+    pub fn toggle_favorite(&mut self) {
+        let Some(project) = self
+            .get_selected_project_index()
+            .and_then(|idx| self.projects.get_mut(idx))
+        else {
+            self.status_message =
+                Some(StatusMessage::info(t!("status.select_project_to_favorite")));
+            return;
+        };
+        let name = project.file.display_name();
+        let result = self.favorites.toggle(&project.file.path);
+        project.favorite = self.favorites.contains(&project.file.path);
+        self.status_message = Some(match result {
+            Ok(true) => StatusMessage::info(t!("status.favorite_added", project = name)),
+            Ok(false) => StatusMessage::info(t!("status.favorite_removed", project = name)),
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to save favorites");
+                StatusMessage::warning(t!("status.favorite_not_saved", error = format!("{:#}", e)))
+            }
+        });
+        sort_projects(&mut self.projects);
+        self.selection.rebuild_from_projects(&self.projects);
+    }
+
+    /// Switch between listing all projects and only the favorites.
+    pub fn toggle_favorites_only(&mut self) {
+        let favorites_only = !self.selection.favorites_only;
+        if favorites_only && !self.projects.iter().any(|p| p.favorite) {
+            self.status_message = Some(StatusMessage::info(t!("status.no_favorites")));
+            return;
+        }
+        self.selection.favorites_only = favorites_only;
+        self.selection.rebuild_from_projects(&self.projects);
+        self.status_message = Some(StatusMessage::info(if favorites_only {
+            t!("status.favorites_only")
+        } else {
+            t!("status.all_projects")
+        }));
+    }
+
+    /// Select and unfold the project bound to `digit`. Returns false if the
+    /// digit isn't bound or its project is no longer listed.
+    pub fn jump_to_bookmark(&mut self, digit: u8) -> bool {
+        let Some(path) = self.bookmarks.get(digit) else {
+            self.status_message =
+                Some(StatusMessage::info(t!("status.no_bookmark", digit = digit)));
+            return false;
+        };
+        let Some(project_idx) = self.projects.iter().position(|p| p.file.path == path) else {
+            self.status_message = Some(StatusMessage::warning(t!(
+                "status.bookmark_missing",
+                digit = digit,
+                path = path.display()
+            )));
+            return false;
+        };
+        self.projects[project_idx].folded = false;
+        self.selection.rebuild_from_projects(&self.projects);
+        self.selection
+            .select_item(&SelectableItem::Project { index: project_idx });
+        true
+    }
+
+    /// Select the next spec with conflicts, an error, or a safety halt after
+    /// the selected row, or the previous one before it if not `forward`,
+    /// wrapping around. Specs in folded projects count too.
+    pub fn select_adjacent_problem(&mut self, forward: bool) {
+        // Indices sort in display order, since projects and specs are sorted
+        let problems: Vec<(usize, usize)> = self
+            .projects
+            .iter()
+            .enumerate()
+            .flat_map(|(p, project)| {
+                project
+                    .specs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, spec)| spec.has_problem())
+                    .map(move |(s, _)| (p, s))
+            })
+            .collect();
+        let spec = self.get_selected_spec();
+        let project = self.get_selected_project_index();
+        let next = if forward {
+            problems
+                .iter()
+                .position(|&row| match (spec, project) {
+                    (Some(spec), _) => row > spec,
+                    (None, Some(project)) => row.0 >= project,
+                    (None, None) => true,
+                })
+                .or((!problems.is_empty()).then_some(0))
+        } else {
+            problems
+                .iter()
+                .rposition(|&row| match (spec, project) {
+                    (Some(spec), _) => row < spec,
+                    (None, Some(project)) => row.0 < project,
+                    (None, None) => true,
+                })
+                .or(problems.len().checked_sub(1))
+        };
+        let Some(index) = next else {
+            self.status_message = Some(StatusMessage::info(t!("status.no_problems")));
+            return;
+        };
+        let (project_idx, spec_idx) = problems[index];
+        self.select_spec(project_idx, spec_idx);
+        self.status_message = Some(StatusMessage::info(t!(
+            "status.problem",
+            index = index + 1,
+            count = problems.len()
+        )));
+    }
+
+    /// Select a spec directly, unfolding its project so the row exists.
+    pub fn select_spec(&mut self, project_idx: usize, spec_idx: usize) -> bool {
+        let Some(project) = self.projects.get_mut(project_idx) else {
+            return false;
+        };
+        if project.folded {
+            project.folded = false;
+            self.selection.rebuild_from_projects(&self.projects);
+        }
+        self.selection.select_item(&SelectableItem::Spec {
+            project_index: project_idx,
+            spec_index: spec_idx,
+        })
+    }
+
+    pub fn get_selected_project_index(&self) -> Option<usize> {
+        self.selection.selected_project_index()
+    }
+
+    /// Get the selected spec (returns (project_index, spec_index) if a spec is selected)
+    pub fn get_selected_spec(&self) -> Option<(usize, usize)> {
+        self.selection.selected_spec()
+    }
+
+    pub fn selected_project_has_running_specs(&self) -> bool {
+        if let Some(project_idx) = self.get_selected_project_index() {
+            if let Some(project) = self.projects.get(project_idx) {
+                return project.specs.iter().any(|s| s.is_running());
+            }
+        }
+        false
+    }
+
+    pub async fn pause_selected(&mut self) {
+        if let Some((proj_idx, spec_idx)) = self.get_selected_spec() {
             if let Some(project) = self.projects.get(proj_idx) {
                 if let Some(spec) = project.specs.get(spec_idx) {
                     if let Some(session) = &spec.running_session {
-                        match self.mutagen_client.pause_session(&session.identifier).await {
+                        let result = self.mutagen_client.pause_session(&session.identifier).await;
+                        self.audit("pause", &spec.name, &result);
+                        match result {
                             Ok(_) => {
-                                self.status_message = Some(StatusMessage::info(format!(
-                                    "Paused spec: {}",
-                                    spec.name
+                                self.status_message = Some(StatusMessage::info(t!(
+                                    "status.paused_spec",
+                                    name = spec.name
                                 )));
                             }
                             Err(e) => {
-                                self.status_message =
-                                    Some(StatusMessage::error(format!("Failed to pause: {}", e)));
+                                self.status_message = Some(StatusMessage::error(t!(
+                                    "status.pause_failed",
+                                    error = e
+                                )));
                             }
                         }
                     }
@@ -283,16 +1669,20 @@ impl App {
             if let Some(project) = self.projects.get(proj_idx) {
                 if let Some(spec) = project.specs.get(spec_idx) {
                     if let Some(session) = &spec.running_session {
-                        match self.mutagen_client.resume_session(&session.identifier).await {
+                        let result = self.mutagen_client.resume_session(&session.identifier).await;
+                        self.audit("resume", &spec.name, &result);
+                        match result {
                             Ok(_) => {
-                                self.status_message = Some(StatusMessage::info(format!(
-                                    "Resumed spec: {}",
-                                    spec.name
+                                self.status_message = Some(StatusMessage::info(t!(
+                                    "status.resumed_spec",
+                                    name = spec.name
                                 )));
                             }
                             Err(e) => {
-                                self.status_message =
-                                    Some(StatusMessage::error(format!("Failed to resume: {}", e)));
+                                self.status_message = Some(StatusMessage::error(t!(
+                                    "status.resume_failed",
+                                    error = e
+                                )));
                             }
                         }
                     }
@@ -301,21 +1691,518 @@ impl App {
         }
     }
 
+    /// If the selected spec is halted by a safety check, ask to reset it
+    /// instead of resuming, which wouldn't get it syncing again. Returns
+    /// whether the confirmation was opened.
+    pub fn offer_safety_reset(&mut self) -> bool {
+        let Some((proj_idx, spec_idx)) = self.get_selected_spec() else {
+            return false;
+        };
+        let spec = &self.projects[proj_idx].specs[spec_idx];
+        let Some(session) = &spec.running_session else {
+            return false;
+        };
+        let Some(halt) = session.safety_halt() else {
+            return false;
+        };
+        self.pending_reset = Some(PendingReset {
+            identifier: session.identifier.clone(),
+            spec_name: spec.name.clone(),
+            halt,
+            one_way: session.is_one_way(),
+            paused: session.paused,
+            typed: String::new(),
+        });
+        true
+    }
+
+    pub fn cancel_reset(&mut self) {
+        self.pending_reset = None;
+        self.status_message = Some(StatusMessage::info(t!("status.reset_cancelled")));
+    }
+
+    /// Reset the pending session if the confirmation word was typed, and
+    /// resume it if it was also paused.
+    pub async fn confirm_reset(&mut self) {
+        let Some(pending) = self.pending_reset.take() else {
+            return;
+        };
+        if pending.typed != RESET_CONFIRMATION {
+            self.status_message = Some(StatusMessage::info(t!(
+                "status.reset_unconfirmed",
+                word = RESET_CONFIRMATION
+            )));
+            return;
+        }
+        let mut result = self.mutagen_client.reset_session(&pending.identifier).await;
+        if result.is_ok() && pending.paused {
+            result = self
+                .mutagen_client
+                .resume_session(&pending.identifier)
+                .await;
+        }
+        self.audit("reset", &pending.spec_name, &result);
+        self.status_message = Some(match result {
+            Ok(_) => StatusMessage::info(t!("status.reset_spec", name = pending.spec_name)),
+            Err(e) => StatusMessage::error(t!("status.reset_failed", error = e)),
+        });
+    }
+
+    /// Open the new session form, saving to the selected project by default.
+    pub fn open_wizard(&mut self) {
+        let files = self.projects.iter().map(|p| p.file.path.clone()).collect();
+        let selected = self
+            .get_selected_project_index()
+            .and_then(|idx| self.projects.get(idx))
+            .map(|project| project.file.path.as_path());
+        let templates = self.config.templates.clone().into_iter().collect();
+        self.wizard = Some(SessionWizard::new(files, selected).with_templates(templates));
+    }
+
+    /// Open the session form on the selected spec, to change it in its
+    /// project file.
+    pub fn open_spec_editor(&mut self) {
+        let Some((proj_idx, spec_idx)) = self.get_selected_spec() else {
+            self.status_message = Some(StatusMessage::info(t!("status.select_spec_to_edit")));
+            return;
+        };
+        let project = &self.projects[proj_idx];
+        let name = &project.specs[spec_idx].name;
+        if let Some(definition) = project.file.sessions.get(name) {
+            self.wizard = Some(SessionWizard::edit(
+                project.file.path.clone(),
+                name,
+                definition,
+                project.file.defaults.as_ref(),
+            ));
+        }
+    }
+
+    /// Open the session form on a copy of the selected spec, to add to its
+    /// project file under a new name.
+    pub fn open_spec_duplicate(&mut self) {
+        let Some((proj_idx, spec_idx)) = self.get_selected_spec() else {
+            self.status_message = Some(StatusMessage::info(t!("status.select_spec_to_duplicate")));
+            return;
+        };
+        let project = &self.projects[proj_idx];
+        let name = &project.specs[spec_idx].name;
+        if let Some(definition) = project.file.sessions.get(name) {
+            let taken: Vec<&str> = project.file.sessions.keys().map(String::as_str).collect();
+            self.wizard = Some(SessionWizard::duplicate(
+                project.file.path.clone(),
+                name,
+                definition,
+                project.file.defaults.as_ref(),
+                &taken,
+            ));
+        }
+    }
+
+    pub fn close_wizard(&mut self) {
+        let wizard = self.wizard.take();
+        let editing = wizard.as_ref().is_some_and(|w| w.editing.is_some());
+        let copying = wizard.is_some_and(|w| w.copying.is_some());
+        self.status_message = Some(StatusMessage::info(if editing {
+            t!("status.edit_cancelled")
+        } else if copying {
+            t!("status.duplicate_cancelled")
+        } else {
+            t!("status.new_session_cancelled")
+        }));
+    }
+
+    /// Open the ignores overlay on the selected spec.
+    pub fn open_ignore_editor(&mut self) {
+        let Some((proj_idx, spec_idx)) = self.get_selected_spec() else {
+            self.status_message = Some(StatusMessage::info(t!("status.select_spec_for_ignores")));
+            return;
+        };
+        let project = &self.projects[proj_idx];
+        let name = &project.specs[spec_idx].name;
+        match project.file.sessions.get(name) {
+            Some(definition) => {
+                self.ignore_editor = Some(IgnoreEditor::new(
+                    project.file.path.clone(),
+                    name,
+                    definition,
+                    project.file.defaults.as_ref(),
+                ));
+            }
+            None => {
+                self.status_message = Some(StatusMessage::error(t!(
+                    "status.definition_not_found",
+                    name = name
+                )));
+            }
+        }
+    }
+
+    pub fn close_ignore_editor(&mut self) {
+        if let Some(editor) = self.ignore_editor.take().filter(IgnoreEditor::is_changed) {
+            self.status_message = Some(StatusMessage::info(t!(
+                "status.ignores_discarded",
+                name = editor.spec_name
+            )));
+        }
+    }
+
+    /// Apply the ignores overlay's changes: save them to the project file if
+    /// asked to, then recreate the spec's session, if it is running, with
+    /// the new patterns and its mode. The overlay stays open with the error
+    /// if saving fails. Returns whether the changes were applied.
+    pub async fn apply_ignore_editor(&mut self) -> bool {
+        let Some(mut editor) = self.ignore_editor.take() else {
+            return false;
+        };
+        if !editor.is_changed() {
+            self.status_message = Some(StatusMessage::info(t!(
+                "status.ignores_unchanged",
+                name = editor.spec_name
+            )));
+            return false;
+        }
+        let session = self
+            .projects
+            .iter()
+            .filter(|p| p.file.path == editor.path)
+            .flat_map(|p| &p.specs)
+            .find(|spec| spec.name == editor.spec_name)
+            .and_then(|spec| spec.running_session.clone());
+        if session.is_none() && !editor.save_to_file {
+            editor.error = Some(t!("status.ignores_not_running", name = editor.spec_name));
+            self.ignore_editor = Some(editor);
+            return false;
+        }
+
+        if editor.save_to_file {
+            let result = spec_edit::update_ignores(
+                &editor.path,
+                &editor.spec_name,
+                editor.vcs,
+                &editor.paths,
+            );
+            self.audit("edit-ignores", &editor.spec_name, &result);
+            if let Err(e) = result {
+                editor.error = Some(format!("{:#}", e));
+                self.ignore_editor = Some(editor);
+                return false;
+            }
+            self.status_message = Some(StatusMessage::info(t!(
+                "status.ignores_saved",
+                name = editor.spec_name,
+                path = editor.path.display()
+            )));
+        }
+        let Some(session) = session else {
+            return true;
+        };
+
+        let terminated = self
+            .mutagen_client
+            .terminate_session(&session.identifier)
+            .await;
+        self.audit("terminate", &session.name, &terminated);
+        if let Err(e) = terminated {
+            self.status_message = Some(StatusMessage::error(t!(
+                "status.terminate_failed",
+                error = e
+            )));
+            return false;
+        }
+        let ignore = editor.effective_patterns();
+        let created = self
+            .mutagen_client
+            .create_session(
+                &session.name,
+                &editor.definition.alpha,
+                &editor.definition.beta,
+                session.mode.as_deref().unwrap_or("two-way-safe"),
+                &ignore,
+            )
+            .await;
+        self.audit("recreate", &session.name, &created);
+        let applied = created.is_ok();
+        self.status_message = Some(match created {
+            Ok(()) => StatusMessage::info(t!(
+                "status.ignores_recreated",
+                name = session.name,
+                count = ignore.len()
+            )),
+            Err(e) => {
+                StatusMessage::error(t!("status.recreate_failed", name = session.name, error = e))
+            }
+        });
+        applied
+    }
+
+    /// Ask to delete the selected spec from its project file; the next key
+    /// confirms with `y` or cancels. Running specs are refused, since their
+    /// session would be left without a spec.
+    pub fn start_delete_spec(&mut self) {
+        let Some((proj_idx, spec_idx)) = self.get_selected_spec() else {
+            self.status_message = Some(StatusMessage::info(t!("status.select_spec_to_delete")));
+            return;
+        };
+        let project = &self.projects[proj_idx];
+        let spec = &project.specs[spec_idx];
+        if spec.is_running() {
+            self.status_message = Some(StatusMessage::warning(t!(
+                "status.delete_running",
+                name = spec.name
+            )));
+            return;
+        }
+        self.status_message = Some(StatusMessage::warning(t!(
+            "status.delete_prompt",
+            name = spec.name,
+            path = project.file.path.display()
+        )));
+        self.pending_delete = Some((project.file.path.clone(), spec.name.clone()));
+    }
+
+    /// Ask for confirmation if the selected spec, or any running spec of the
+    /// selected project, belongs to a session another machine or user
+    /// created. Returns whether it asked.
+    pub fn confirm_foreign_terminate(&mut self) -> bool {
+        let Some(ours) = self.mutagen_client.origin() else {
+            return false;
+        };
+        let specs: Vec<_> = match self.get_selected_spec() {
+            Some((proj_idx, spec_idx)) => vec![&self.projects[proj_idx].specs[spec_idx]],
+            None => self
+                .get_selected_project_index()
+                .and_then(|idx| self.projects.get(idx))
+                .map(|project| project.specs.iter().collect())
+                .unwrap_or_default(),
+        };
+        let foreign: Vec<_> = specs
+            .iter()
+            .filter_map(|spec| {
+                let origin = spec.running_session.as_ref()?.origin()?;
+                (origin != *ours).then(|| (spec.name.clone(), origin))
+            })
+            .collect();
+        let Some((name, origin)) = foreign.first() else {
+            return false;
+        };
+        let message = if foreign.len() == 1 {
+            t!("status.foreign_terminate", name = name, origin = origin)
+        } else {
+            t!(
+                "status.foreign_terminate_many",
+                count = foreign.len(),
+                name = name,
+                origin = origin
+            )
+        };
+        self.status_message = Some(StatusMessage::warning(message));
+        self.pending_terminate = true;
+        true
+    }
+
+    /// Delete the spec waiting for confirmation.
+    pub fn confirm_delete_spec(&mut self) {
+        let Some((path, name)) = self.pending_delete.take() else {
+            return;
+        };
+        let result = spec_edit::remove_spec(&path, &name);
+        self.audit("delete-spec", &name, &result);
+        self.status_message = Some(match result {
+            Ok(()) => StatusMessage::info(t!(
+                "status.deleted_spec",
+                name = name,
+                path = path.display()
+            )),
+            Err(e) => StatusMessage::error(t!("status.delete_failed", error = format!("{:#}", e))),
+        });
+    }
+
+    /// Save the rename waiting for confirmation, then terminate the spec's
+    /// session and create it again under the new name, so it still goes
+    /// with its spec.
+    pub async fn confirm_rename_spec(&mut self) {
+        let Some(rename) = self.pending_rename.take() else {
+            return;
+        };
+        let (old, new) = (&rename.before.name, &rename.after.name);
+        let saved = spec_edit::update_spec(&rename.path, &rename.before, &rename.after);
+        self.audit("rename-spec", new, &saved);
+        if let Err(e) = saved {
+            self.status_message = Some(StatusMessage::error(t!(
+                "status.rename_failed",
+                name = old,
+                error = format!("{:#}", e)
+            )));
+            return;
+        }
+        let terminated = self
+            .mutagen_client
+            .terminate_session(&rename.session.identifier)
+            .await;
+        self.audit("terminate", old, &terminated);
+        if let Err(e) = terminated {
+            self.status_message = Some(StatusMessage::error(t!(
+                "status.renamed_not_recreated",
+                name = new,
+                error = e
+            )));
+            return;
+        }
+        let started = match ProjectFile::from_path(rename.path.clone()) {
+            Ok(file) => {
+                let identifier = rename.session.labels.get(PROJECT_LABEL);
+                self.start_spec_from(&file, new, identifier.map(String::as_str))
+                    .await
+            }
+            Err(e) => Err(format!("{:#}", e)),
+        };
+        self.status_message = Some(match started {
+            Ok(()) => StatusMessage::info(t!("status.renamed_spec", name = old, new_name = new)),
+            Err(e) => {
+                StatusMessage::error(t!("status.renamed_not_recreated", name = new, error = e))
+            }
+        });
+    }
+
+    /// Create the session described by the form, add it to the chosen
+    /// project file, or save the changes to the spec being edited. The form
+    /// stays open with the error if that fails.
+    pub async fn submit_wizard(&mut self) {
+        let Some(wizard) = &mut self.wizard else {
+            return;
+        };
+        let session = match wizard.validate() {
+            Ok(session) => session,
+            Err(e) => {
+                wizard.error = Some(e);
+                return;
+            }
+        };
+        let target = wizard.selected_target().clone();
+        let editing = wizard.editing.clone();
+        if let (WizardTarget::Project(path), Some(before)) = (&target, &editing) {
+            let running = self
+                .projects
+                .iter()
+                .filter(|p| &p.file.path == path)
+                .flat_map(|p| &p.specs)
+                .find(|spec| spec.name == before.name)
+                .and_then(|spec| spec.running_session.as_deref().cloned());
+            // Renaming a running spec recreates its session, so it's asked first
+            if let Some(running) = running.filter(|_| session.name != before.name) {
+                self.wizard = None;
+                self.status_message = Some(StatusMessage::warning(t!(
+                    "status.rename_prompt",
+                    name = before.name,
+                    new_name = session.name
+                )));
+                self.pending_rename = Some(PendingRename {
+                    path: path.clone(),
+                    before: before.clone(),
+                    after: session,
+                    session: running,
+                });
+                return;
+            }
+        }
+        if let (WizardTarget::Project(path), Some(source)) = (&target, &wizard.copying) {
+            let path = path.clone();
+            let source = source.clone();
+            let result = spec_edit::duplicate_spec(&path, &source, &session);
+            self.audit("duplicate-spec", &session.name, &result);
+            match result {
+                Ok(()) => {
+                    self.wizard = None;
+                    self.status_message = Some(StatusMessage::info(t!(
+                        "status.duplicated_spec",
+                        source = source.name,
+                        name = session.name,
+                        path = path.display()
+                    )));
+                }
+                Err(e) => {
+                    if let Some(wizard) = &mut self.wizard {
+                        wizard.error = Some(format!("{:#}", e));
+                    }
+                }
+            }
+            return;
+        }
+        let (action, result) = match (&target, &editing) {
+            (WizardTarget::Create, _) => (
+                "create",
+                self.mutagen_client
+                    .create_session(
+                        &session.name,
+                        &session.alpha,
+                        &session.beta,
+                        session.mode,
+                        &session.ignore,
+                    )
+                    .await,
+            ),
+            (WizardTarget::Project(path), None) => {
+                ("add-spec", spec_edit::add_spec(path, &session))
+            }
+            (WizardTarget::Project(path), Some(before)) => {
+                ("edit-spec", spec_edit::update_spec(path, before, &session))
+            }
+        };
+        self.audit(action, &session.name, &result);
+        match result {
+            Ok(()) => {
+                self.wizard = None;
+                let message = match (target, editing) {
+                    (WizardTarget::Create, _) => t!("status.created_session", name = session.name),
+                    (WizardTarget::Project(path), None) => t!(
+                        "status.added_spec",
+                        name = session.name,
+                        path = path.display()
+                    ),
+                    (WizardTarget::Project(path), Some(before)) => {
+                        let running = self
+                            .projects
+                            .iter()
+                            .filter(|p| p.file.path == path)
+                            .flat_map(|p| &p.specs)
+                            .any(|spec| spec.name == before.name && spec.is_running());
+                        if running {
+                            t!("status.saved_running_spec", name = session.name)
+                        } else {
+                            t!("status.saved_spec", name = session.name)
+                        }
+                    }
+                };
+                self.status_message = Some(StatusMessage::info(message));
+            }
+            Err(e) => {
+                if let Some(wizard) = &mut self.wizard {
+                    wizard.error = Some(format!("{:#}", e));
+                }
+            }
+        }
+    }
+
     pub async fn terminate_selected(&mut self) {
         if let Some((proj_idx, spec_idx)) = self.get_selected_spec() {
             if let Some(project) = self.projects.get(proj_idx) {
                 if let Some(spec) = project.specs.get(spec_idx) {
                     if let Some(session) = &spec.running_session {
-                        match self.mutagen_client.terminate_session(&session.identifier).await {
+                        let result = self.mutagen_client.terminate_session(&session.identifier).await;
+                        self.audit("terminate", &spec.name, &result);
+                        match result {
                             Ok(_) => {
-                                self.status_message = Some(StatusMessage::info(format!(
-                                    "Terminated spec: {}",
-                                    spec.name
+                                self.status_message = Some(StatusMessage::info(t!(
+                                    "status.terminated_spec",
+                                    name = spec.name
                                 )));
                             }
                             Err(e) => {
-                                self.status_message =
-                                    Some(StatusMessage::error(format!("Failed to terminate: {}", e)));
+                                self.status_message = Some(StatusMessage::error(t!(
+                                    "status.terminate_failed",
+                                    error = e
+                                )));
                             }
                         }
                     }
@@ -329,16 +2216,24 @@ impl App {
             if let Some(project) = self.projects.get(proj_idx) {
                 if let Some(spec) = project.specs.get(spec_idx) {
                     if let Some(session) = &spec.running_session {
-                        match self.mutagen_client.flush_session(&session.identifier).await {
+                        let result = self.mutagen_client.flush_session(&session.identifier).await;
+                        self.audit("flush", &spec.name, &result);
+                        match result {
                             Ok(_) => {
-                                self.status_message = Some(StatusMessage::info(format!(
-                                    "Flushed spec: {}",
-                                    spec.name
+                                self.pending_flushes.insert(
+                                    session.identifier.clone(),
+                                    PendingFlush::new(&spec.name, session),
+                                );
+                                self.status_message = Some(StatusMessage::progress(t!(
+                                    "status.flushing_spec",
+                                    name = spec.name
                                 )));
                             }
                             Err(e) => {
-                                self.status_message =
-                                    Some(StatusMessage::error(format!("Failed to flush: {}", e)));
+                                self.status_message = Some(StatusMessage::error(t!(
+                                    "status.flush_failed",
+                                    error = e
+                                )));
                             }
                         }
                     }
@@ -347,112 +2242,1144 @@ impl App {
         }
     }
 
-    pub async fn start_selected_spec(&mut self) {
-        if let Some((project_idx, spec_idx)) = self.selection.selected_spec() {
-            if let Some(project) = self.projects.get(project_idx) {
-                if let Some(spec) = project.specs.get(spec_idx) {
-                    // Don't start if already running
-                    if spec.is_running() {
-                        self.status_message = Some(StatusMessage::warning(format!(
-                            "Spec already running: {}",
-                            spec.name
-                        )));
-                        return;
-                    }
+    /// Compare file checksums across the selected spec's endpoints and show the result.
+    pub async fn verify_selected(&mut self, mode: VerifyMode) {
+        let Some((proj_idx, spec_idx)) = self.get_selected_spec() else {
+            self.status_message = Some(StatusMessage::error(t!("status.select_spec_to_verify")));
+            return;
+        };
+        let Some(spec) = self
+            .projects
+            .get(proj_idx)
+            .and_then(|project| project.specs.get(spec_idx))
+        else {
+            return;
+        };
+        let Some(session) = &spec.running_session else {
+            self.status_message = Some(StatusMessage::error(t!(
+                "status.spec_not_running",
+                name = spec.name
+            )));
+            return;
+        };
+        let file = &self.projects[proj_idx].file;
+        let defaults = file
+            .defaults
+            .as_ref()
+            .and_then(|defaults| serde_yaml::to_value(defaults).ok());
+        let patterns = file
+            .sessions
+            .get(&spec.name)
+            .map(|definition| definition.get_ignore_patterns(defaults.as_ref()))
+            .unwrap_or_default();
+        let ignores = Ignores::new(&patterns);
 
-                    // Get session definition from project file
-                    if let Some(session_def) = project.file.sessions.get(&spec.name) {
-                        // Get defaults for ignore patterns
-                        let defaults_value = project
-                            .file
-                            .defaults
-                            .as_ref()
-                            .and_then(|defaults| serde_yaml::to_value(defaults).ok());
+        // Dropping the verification on Esc kills the hashing commands
+        let cancel = self.blocking_op.as_ref().map(|op| op.cancel.clone());
+        let result = tokio::select! {
+            result = self.mutagen_client.verify_session(session, mode, &ignores) => result,
+            _ = async {
+                match &cancel {
+                    Some(token) => token.cancelled().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                self.status_message = Some(StatusMessage::warning(t!("status.verify_cancelled")));
+                return;
+            }
+        };
 
-                        // Extract ignore patterns (same as push_selected_spec)
-                        let ignore_patterns = session_def.get_ignore_patterns(defaults_value.as_ref());
-                        let ignore = if ignore_patterns.is_empty() {
-                            None
-                        } else {
-                            Some(ignore_patterns)
-                        };
+        match result {
+            Ok(report) => {
+                self.status_message = Some(if report.mismatches.is_empty() {
+                    StatusMessage::info(report.summary())
+                } else {
+                    StatusMessage::warning(report.summary())
+                });
+                self.verify_report = Some(report);
+            }
+            Err(e) => {
+                self.status_message = Some(StatusMessage::error(t!(
+                    "status.verify_failed",
+                    name = spec.name,
+                    error = e
+                )));
+            }
+        }
+    }
 
-                        // Ensure directories exist (same pattern as push_selected_spec)
-                        if let Err(e) = self
-                            .mutagen_client
-                            .ensure_endpoint_directory_exists(&session_def.alpha)
-                            .await
-                        {
-                            self.status_message = Some(StatusMessage::error(format!(
-                                "Failed to create alpha directory: {}",
-                                e
-                            )));
-                            return;
-                        }
-                        if let Err(e) = self
-                            .mutagen_client
-                            .ensure_endpoint_directory_exists(&session_def.beta)
-                            .await
-                        {
-                            self.status_message = Some(StatusMessage::error(format!(
-                                "Failed to create beta directory: {}",
-                                e
-                            )));
-                            return;
-                        }
+    pub fn close_verify_report(&mut self) {
+        self.verify_report = None;
+    }
 
-                        // Create two-way session
-                        match self
-                            .mutagen_client
-                            .create_two_way_session(
-                                &spec.name,
-                                &session_def.alpha,
-                                &session_def.beta,
-                                ignore.as_deref(),
-                            )
-                            .await
-                        {
-                            Ok(_) => {
-                                self.status_message = Some(StatusMessage::info(format!(
-                                    "Started spec: {}",
-                                    spec.name
-                                )));
-                            }
-                            Err(e) => {
-                                self.status_message = Some(StatusMessage::error(format!(
-                                    "Failed to start spec: {}",
-                                    e
-                                )));
-                            }
+    /// Append an action to the audit log.
+    ///
+    /// Logging must never get in the way of the action itself, so write
+    /// failures are ignored.
+    fn audit<T>(&self, action: &str, target: &str, result: &Result<T>) {
+        match result {
+            Ok(_) => tracing::info!(action, target, "action succeeded"),
+            Err(e) => tracing::warn!(action, target, error = %e, "action failed"),
+        }
+        self.last_action.set(Some(Instant::now()));
+        let commands = self.mutagen_client.take_commands();
+        // Nothing happened in a dry run, so there's nothing to audit
+        if self.mutagen_client.is_dry_run() {
+            self.dry_run_commands
+                .borrow_mut()
+                .extend(commands.iter().cloned());
+        } else {
+            let _ = self.audit_log.record(action, target, &commands, result);
+        }
+        if !commands.is_empty() {
+            *self.last_commands.borrow_mut() = commands;
+        }
+    }
+
+    /// Say that `key` does nothing because mutagui is read-only.
+    /// Whether the empty screen offers first-run setup: there's no config
+    /// file and no project files were found.
+    pub fn offers_setup(&self) -> bool {
+        self.setup.is_some() && self.projects.is_empty() && !self.is_discovering()
+    }
+
+    /// Write the starter config file and example project file.
+    pub fn run_setup(&mut self) {
+        let Some(setup) = &self.setup else {
+            return;
+        };
+        let result = setup.write();
+        self.audit("init", &setup.project.display().to_string(), &result);
+        self.status_message = Some(match result {
+            Ok(written) => {
+                self.setup = None;
+                let files: Vec<String> = written.iter().map(|p| p.display().to_string()).collect();
+                StatusMessage::info(t!("status.setup_written", files = files.join(", ")))
+            }
+            Err(e) => StatusMessage::error(t!("status.setup_failed", error = format!("{:#}", e))),
+        });
+    }
+
+    pub fn refuse_read_only(&mut self, key: &str) {
+        self.status_message = Some(StatusMessage::warning(t!("status.read_only", key = key)));
+    }
+
+    /// Turn dry run on or off. In a dry run, actions show the commands they
+    /// would run instead of running them.
+    pub fn toggle_dry_run(&mut self) {
+        let on = !self.mutagen_client.is_dry_run();
+        self.mutagen_client.set_dry_run(on);
+        self.status_message = Some(StatusMessage::info(if on {
+            t!("status.dry_run_on")
+        } else {
+            t!("status.dry_run_off")
+        }));
+    }
+
+    /// Show the commands the dry-run actions since the last call would have
+    /// run, if there were any.
+    pub fn show_dry_run_commands(&mut self) {
+        let commands = std::mem::take(&mut *self.dry_run_commands.borrow_mut());
+        if commands.is_empty() {
+            return;
+        }
+        self.text_popup = Some(TextPopup::new(
+            t!("title.dry_run"),
+            commands.join("\n"),
+            self.color_scheme.status_message_fg,
+        ));
+    }
+
+    /// Copy the mutagen commands the last action ran, shown in learning
+    /// mode, to the clipboard.
+    pub fn copy_last_commands(&mut self) {
+        if !self.learning() {
+            self.status_message = Some(StatusMessage::info(t!("status.learning_off")));
+            return;
+        }
+        let commands = self.last_commands.borrow().clone();
+        if commands.is_empty() {
+            self.status_message = Some(StatusMessage::info(t!("status.no_commands_run")));
+            return;
+        }
+        self.status_message = Some(match clipboard::copy(&commands.join("\n")) {
+            Ok(()) => StatusMessage::info(t!("status.copied_commands", count = commands.len())),
+            Err(e) => StatusMessage::error(t!("status.copy_failed", error = e)),
+        });
+    }
+
+    /// Append resolved conflicts to the resolution log. As with the audit
+    /// log, write failures are ignored.
+    fn record_resolutions(&self, spec: &str, roots: &[String], winner: Winner, via: &str) {
+        if roots.is_empty() {
+            return;
+        }
+        tracing::info!(
+            spec,
+            count = roots.len(),
+            winner = winner.label(),
+            via,
+            "conflicts resolved"
+        );
+        let _ = self.resolution_log.record(spec, roots, winner, via);
+    }
+
+    /// Show the selected spec's resolved conflicts, newest first.
+    pub fn open_resolution_history(&mut self) {
+        let Some((proj_idx, spec_idx)) = self.get_selected_spec() else {
+            self.status_message = Some(StatusMessage::warning(t!("status.select_spec")));
+            return;
+        };
+        let name = self.projects[proj_idx].specs[spec_idx].name.clone();
+        match self.resolution_log.for_spec(&name, RESOLUTION_VIEW_LIMIT) {
+            Ok(entries) => {
+                let popup = TextPopup::new(
+                    t!("title.resolution_history", name = name),
+                    format_history(&entries),
+                    self.color_scheme.status_message_fg,
+                );
+                self.text_popup = Some(if entries.is_empty() {
+                    popup
+                } else {
+                    popup.with_export(format!("{}-resolutions.csv", name), to_csv(&entries))
+                });
+            }
+            Err(e) => {
+                self.status_message =
+                    Some(StatusMessage::error(t!("status.history_failed", error = e)));
+            }
+        }
+    }
+
+    pub fn toggle_audit_view(&mut self) {
+        if self.audit_view.take().is_some() {
+            return;
+        }
+        match self.audit_log.recent(AUDIT_VIEW_LIMIT) {
+            Ok(entries) => self.audit_view = Some(entries),
+            Err(e) => {
+                self.status_message = Some(StatusMessage::error(t!(
+                    "status.audit_log_failed",
+                    error = e
+                )));
+            }
+        }
+    }
+
+    /// Rebuild the project list from `discovered` and the sessions of the
+    /// last refresh, keeping folds and the selected row.
+    fn set_projects(&mut self, discovered: DiscoveredProjects) {
+        let DiscoveredProjects {
+            files,
+            errors,
+            issues,
+        } = discovered;
+
+        // Only projects whose file or sessions changed are rebuilt
+        let changed = update_projects(&mut self.projects, files, &self.sessions);
+        self.selected_project_error = self
+            .selected_project_error
+            .min(errors.len().saturating_sub(1));
+        self.project_errors = errors;
+        self.set_project_issues(issues);
+        for project in &mut self.projects {
+            project.favorite = self.favorites.contains(&project.file.path);
+        }
+
+        // Discovery lists files in its own order, so sort even when nothing
+        // changed; specs that were kept are already in order
+        let moved = sort_projects(&mut self.projects);
+        if changed {
+            for project in &mut self.projects {
+                sort_specs(&mut project.specs, self.sort_mode);
+            }
+        }
+
+        // Rebuild selection manager from projects, following the selected row
+        if changed || moved {
+            self.selection.rebuild_from_projects(&self.projects);
+        }
+    }
+
+    /// Look for project files on a blocking thread, so a slow search (a
+    /// large home directory, a network mount) doesn't hold up the UI. If
+    /// discovery is already running it runs again once it finishes.
+    fn start_discovery(&mut self) {
+        if self.discovery.is_some() {
+            self.rediscover = true;
+            return;
+        }
+        let mut cache = std::mem::take(&mut self.discovery_cache);
+        let project_dir = self.project_dir.clone();
+        let config = self.config.projects.clone();
+        self.discovery = Some(tokio::task::spawn_blocking(move || {
+            let discovered = cache.discover(project_dir.as_deref(), Some(&config));
+            (cache, discovered)
+        }));
+    }
+
+    /// Whether project files are still being looked for.
+    pub fn is_discovering(&self) -> bool {
+        self.discovery.is_some()
+    }
+
+    /// Show the project files found by discovery, if it has finished.
+    pub async fn finish_discovery(&mut self) {
+        if let Some(discovery) = self.discovery.take_if(|discovery| discovery.is_finished()) {
+            self.apply_discovery(discovery).await;
+        }
+    }
+
+    /// Wait for discovery to finish, including any rerun it owes, and show
+    /// the project files it found.
+    pub async fn wait_for_discovery(&mut self) {
+        while let Some(discovery) = self.discovery.take() {
+            self.apply_discovery(discovery).await;
+        }
+    }
+
+    async fn apply_discovery(&mut self, discovery: Discovery) {
+        match discovery.await {
+            Ok((cache, discovered)) => {
+                self.discovery_cache = cache;
+                match discovered {
+                    Ok(discovered) => {
+                        self.set_projects(discovered);
+                        self.watch_project_files();
+                        if let Some((target, open_conflicts)) = self.startup_selection.take() {
+                            self.select_target(&target, open_conflicts);
                         }
-                    } else {
-                        self.status_message = Some(StatusMessage::error(format!(
-                            "Session definition not found: {}",
-                            spec.name
+                    }
+                    Err(e) => {
+                        // Project discovery is optional: the app continues to work
+                        // without project correlation, so the error only goes to the log
+                        tracing::warn!(error = %e, "project discovery failed");
+                    }
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "project discovery panicked"),
+        }
+        if std::mem::take(&mut self.rediscover) {
+            self.start_discovery();
+        }
+    }
+
+    /// Keep the issues found in project files, along with those in the
+    /// search paths, pointing out any new ones.
+    fn set_project_issues(&mut self, found: Vec<ProjectIssue>) {
+        let mut issues = self.startup_issues.clone();
+        for issue in found {
+            if !issues.contains(&issue) {
+                issues.push(issue);
+            }
+        }
+        if issues
+            .iter()
+            .any(|issue| !self.project_issues.contains(issue))
+        {
+            self.status_message = Some(StatusMessage::warning(t!(
+                "status.project_issues",
+                count = issues.len()
+            )));
+        }
+        self.project_issues = issues;
+    }
+
+    /// Show why the config file couldn't be read in a popup, with the line
+    /// each problem is on.
+    fn show_config_errors(&mut self, issues: &[ProjectIssue], note: &str) {
+        let mut text = String::new();
+        if let Some(path) = Config::config_path() {
+            text.push_str(&format!("{}\n\n", path.display()));
+        }
+        text.push_str(&lint::format_config_issues(
+            issues,
+            &Config::file_contents(),
+        ));
+        text.push_str(&format!("\n{}\n", note));
+        self.text_popup = Some(TextPopup::new(
+            t!("title.config_errors"),
+            text,
+            self.color_scheme.status_error_fg,
+        ));
+    }
+
+    /// List the problems found in project files in a popup.
+    pub fn open_project_issues(&mut self) {
+        if self.project_issues.is_empty() {
+            self.status_message = Some(StatusMessage::info(t!("status.no_project_issues")));
+            return;
+        }
+        let fg = if self
+            .project_issues
+            .iter()
+            .any(|issue| issue.severity == Severity::Error)
+        {
+            self.color_scheme.status_error_fg
+        } else {
+            self.color_scheme.status_paused_fg
+        };
+        self.text_popup = Some(TextPopup::new(
+            t!("title.project_issues"),
+            format_issues(&self.project_issues),
+            fg,
+        ));
+    }
+
+    /// Note that a command-line flag set the config setting `key` to `value`,
+    /// for the settings popup.
+    /// Update the gauges served for `--metrics-addr`, if it was given.
+    fn update_metrics(&self) {
+        let Some(metrics) = &self.metrics else {
+            return;
+        };
+        let projects = self
+            .projects
+            .iter()
+            .flat_map(|project| {
+                let name = project.file.display_name();
+                project.specs.iter().filter_map(move |spec| {
+                    let session = spec.running_session.as_ref()?;
+                    Some((session.identifier.clone(), name.clone()))
+                })
+            })
+            .collect();
+        metrics.update(metrics::render(&self.sessions, &projects));
+    }
+
+    pub fn record_flag(&mut self, key: &str, value: impl Into<toml::Value>) {
+        self.flag_settings
+            .push((key.to_string(), value.into().to_string()));
+    }
+
+    /// List every setting in effect and where it came from in a popup.
+    pub fn open_settings(&mut self) {
+        let mut config = self.config.clone();
+        config.ui.default_sort = self.sort_mode;
+        config.ui.default_display_mode = match self.session_display_mode {
+            SessionDisplayMode::ShowPaths => DisplayMode::Paths,
+            SessionDisplayMode::ShowLastRefresh => DisplayMode::LastRefresh,
+        };
+        config.ui.layout = if self.split_layout {
+            LayoutMode::Split
+        } else {
+            LayoutMode::Stacked
+        };
+        let mut settings = config.settings(&Config::file_contents());
+        for (key, value) in &self.flag_settings {
+            let flag = Setting {
+                key: key.clone(),
+                value: value.clone(),
+                source: SettingSource::Flag,
+            };
+            match settings.iter_mut().find(|setting| &setting.key == key) {
+                Some(setting) => *setting = flag,
+                None => settings.push(flag),
+            }
+        }
+        settings.sort_by(|a, b| a.key.cmp(&b.key));
+        self.text_popup = Some(TextPopup::new(
+            t!("title.settings"),
+            format_settings(&settings),
+            self.color_scheme.status_message_fg,
+        ));
+    }
+
+    /// Open the current status message in a popup, untruncated.
+    pub fn open_status_detail(&mut self) {
+        let Some(msg) = &self.status_message else {
+            self.status_message = Some(StatusMessage::info(t!("status.no_status_message")));
+            return;
+        };
+        let fg = match msg {
+            StatusMessage::Error(_) => self.color_scheme.status_error_fg,
+            StatusMessage::Warning(_) => self.color_scheme.status_paused_fg,
+            StatusMessage::Info(_) | StatusMessage::Progress(_) => {
+                self.color_scheme.status_message_fg
+            }
+        };
+        self.text_popup = Some(TextPopup::new(t!("title.status_message"), msg.text(), fg));
+    }
+
+    /// Show the JSON mutagen returned for the selected session, pretty-printed.
+    pub fn open_session_json(&mut self) {
+        let Some(session) = self.get_selected_spec().and_then(|(proj_idx, spec_idx)| {
+            self.projects[proj_idx].specs[spec_idx].running_session.as_ref()
+        }) else {
+            self.status_message = Some(StatusMessage::warning(t!("status.select_running_spec")));
+            return;
+        };
+        let Some(raw) = self.mutagen_client.raw_session(&session.identifier) else {
+            self.status_message = Some(StatusMessage::warning(t!(
+                "status.no_json",
+                name = session.name
+            )));
+            return;
+        };
+        let text = serde_json::to_string_pretty(&raw).unwrap_or_else(|_| raw.to_string());
+        self.text_popup = Some(TextPopup::new(
+            t!("title.session_json", name = session.name),
+            text,
+            self.color_scheme.status_message_fg,
+        ));
+    }
+
+    /// Show the key reference.
+    pub fn open_help(&mut self, text: String) {
+        self.text_popup = Some(TextPopup::new(
+            t!("title.keys"),
+            text,
+            self.color_scheme.status_message_fg,
+        ));
+    }
+
+    pub fn close_text_popup(&mut self) {
+        self.text_popup = None;
+        if let Some(mut task) = self.task.take() {
+            task.stop();
+            self.status_message = Some(StatusMessage::info(t!(
+                "status.command_stopped",
+                name = task.name
+            )));
+        }
+    }
+
+    /// Offer the selected project's commands for running.
+    /// Browse the selected spec's endpoints, starting with beta.
+    pub async fn open_browser(&mut self) {
+        let Some((name, alpha, beta)) =
+            self.selected_spec_endpoints(t!("status.select_spec_to_browse"))
+        else {
+            return;
+        };
+        self.browser = Some(DirBrowser::new(&name, alpha, beta));
+        self.list_browser_dir(None).await;
+    }
+
+    /// The selected spec's name and its alpha and beta endpoints, with
+    /// relative local paths resolved against the project file, as mutagen
+    /// does. Shows `unselected` if no spec is selected.
+    fn selected_spec_endpoints(
+        &mut self,
+        unselected: impl Into<String>,
+    ) -> Option<(String, EndpointAddress, EndpointAddress)> {
+        let Some((project_idx, spec_idx)) = self.selection.selected_spec() else {
+            self.status_message = Some(StatusMessage::error(unselected));
+            return None;
+        };
+        let project = self.projects.get(project_idx)?;
+        let spec = project.specs.get(spec_idx)?;
+        let Some(definition) = project.file.sessions.get(&spec.name) else {
+            self.status_message = Some(StatusMessage::error(t!(
+                "status.definition_not_found",
+                name = spec.name
+            )));
+            return None;
+        };
+        let project_dir = project.file.path.parent().unwrap_or(Path::new("."));
+        // Collecting the components drops the `.` in `./api`
+        let address = |endpoint: &str| match EndpointAddress::parse(endpoint).expand_tilde() {
+            EndpointAddress::Local(path) => {
+                EndpointAddress::Local(project_dir.join(path).components().collect())
+            }
+            remote => remote,
+        };
+        Some((
+            spec.name.clone(),
+            address(&definition.alpha),
+            address(&definition.beta),
+        ))
+    }
+
+    /// Open the selected spec's alpha directory in the system file manager.
+    pub fn open_alpha_in_file_manager(&mut self) {
+        let Some((_, alpha, _)) =
+            self.selected_spec_endpoints(t!("status.select_spec_for_endpoint"))
+        else {
+            return;
+        };
+        let EndpointAddress::Local(path) = &alpha else {
+            self.status_message = Some(StatusMessage::error(t!(
+                "status.alpha_not_local",
+                endpoint = alpha
+            )));
+            return;
+        };
+        self.status_message = Some(match Command::new(FILE_MANAGER).arg(path).spawn() {
+            Ok(_) => StatusMessage::info(t!("status.opened_endpoint", endpoint = alpha)),
+            Err(e) => StatusMessage::error(t!(
+                "status.open_endpoint_failed",
+                program = FILE_MANAGER,
+                error = e
+            )),
+        });
+    }
+
+    /// Copy the selected spec's alpha endpoint to the clipboard.
+    pub fn copy_alpha_path(&mut self) {
+        let Some((_, alpha, _)) =
+            self.selected_spec_endpoints(t!("status.select_spec_for_endpoint"))
+        else {
+            return;
+        };
+        self.copy_to_clipboard(&alpha.to_string());
+    }
+
+    fn copy_to_clipboard(&mut self, text: &str) {
+        self.status_message = Some(match clipboard::copy(text) {
+            Ok(()) => StatusMessage::info(t!("status.copied", title = text)),
+            Err(e) => StatusMessage::error(t!("status.copy_failed", error = e)),
+        });
+    }
+
+    /// Offer the selected spec's endpoints, session identifier, and last
+    /// error for copying. The error is the session's, or else the one in
+    /// the status bar.
+    pub fn open_yank_menu(&mut self) {
+        let Some((name, alpha, beta)) =
+            self.selected_spec_endpoints(t!("status.select_spec_to_copy"))
+        else {
+            return;
+        };
+        let session = self
+            .get_selected_spec()
+            .and_then(|(p, s)| self.projects.get(p)?.specs.get(s)?.running_session.as_ref());
+        let mut items = vec![
+            (t!("yank.alpha").to_string(), 'a', alpha.to_string()),
+            (t!("yank.beta").to_string(), 'b', beta.to_string()),
+        ];
+        if let Some(session) = session {
+            items.push((
+                t!("yank.identifier").to_string(),
+                'i',
+                session.identifier.clone(),
+            ));
+        }
+        let status_error = match &self.status_message {
+            Some(StatusMessage::Error(message)) => Some(message.clone()),
+            _ => None,
+        };
+        if let Some(error) = session.and_then(|s| s.last_error.clone()).or(status_error) {
+            items.push((t!("yank.error").to_string(), 'e', error));
+        }
+        self.yank_menu = Some(YankMenu {
+            spec_name: name,
+            items,
+            selected: 0,
+        });
+    }
+
+    pub fn close_yank_menu(&mut self) {
+        self.yank_menu = None;
+    }
+
+    /// Move the copy menu's selection by `delta`, stopping at either end.
+    pub fn move_yank_selection(&mut self, delta: i32) {
+        if let Some(menu) = &mut self.yank_menu {
+            let last = menu.items.len().saturating_sub(1) as i32;
+            menu.selected = (menu.selected as i32 + delta).clamp(0, last) as usize;
+        }
+    }
+
+    /// Copy the menu item picked by `key`, or the selected one if `key` is
+    /// `None`, and close the menu.
+    pub fn copy_yank_item(&mut self, key: Option<char>) {
+        let Some(menu) = &self.yank_menu else {
+            return;
+        };
+        let item = match key {
+            Some(key) => menu.items.iter().find(|(_, k, _)| *k == key),
+            None => menu.items.get(menu.selected),
+        };
+        let Some((_, _, text)) = item.cloned() else {
+            return;
+        };
+        self.yank_menu = None;
+        self.copy_to_clipboard(&text);
+    }
+
+    /// The command that opens an interactive shell in the selected spec's
+    /// beta directory, and the endpoint it opens into.
+    pub fn beta_shell_command(&mut self) -> Option<(Command, EndpointAddress)> {
+        let (_, _, beta) = self.selected_spec_endpoints(t!("status.select_spec_for_endpoint"))?;
+        Some((self.mutagen_client.shell_command(&beta), beta))
+    }
+
+    pub fn close_browser(&mut self) {
+        self.browser = None;
+    }
+
+    /// List the browser's current directory, selecting `name` in it.
+    async fn list_browser_dir(&mut self, name: Option<&str>) {
+        let Some(browser) = &self.browser else {
+            return;
+        };
+        let listing = self
+            .mutagen_client
+            .list_directory(browser.current())
+            .await
+            .map_err(|e| e.to_string());
+        if let Some(browser) = &mut self.browser {
+            browser.show(listing, name);
+        }
+    }
+
+    /// Open the directory selected in the browser.
+    pub async fn browser_enter(&mut self) {
+        let Some(browser) = &mut self.browser else {
+            return;
+        };
+        let Some(entry) = browser.selected_dir() else {
+            return;
+        };
+        let dir = browser.current().path().join(&entry.name);
+        browser.set_dir(dir);
+        self.list_browser_dir(None).await;
+    }
+
+    /// Go up to the parent of the browser's directory.
+    pub async fn browser_up(&mut self) {
+        let Some(browser) = &mut self.browser else {
+            return;
+        };
+        let dir = browser.current().path().to_path_buf();
+        let Some(parent) = browse::parent_dir(&dir) else {
+            return;
+        };
+        browser.set_dir(parent);
+        let name = dir.file_name().map(|n| n.to_string_lossy().into_owned());
+        self.list_browser_dir(name.as_deref()).await;
+    }
+
+    /// Switch the browser to the spec's other endpoint.
+    pub async fn browser_switch_side(&mut self) {
+        if let Some(browser) = &mut self.browser {
+            browser.side = 1 - browser.side;
+            self.list_browser_dir(None).await;
+        }
+    }
+
+    pub fn move_browser_selection(&mut self, delta: i32) {
+        if let Some(browser) = &mut self.browser {
+            browser.move_selection(delta);
+        }
+    }
+
+    pub fn open_task_menu(&mut self) {
+        let Some(project) = self
+            .get_selected_project_index()
+            .and_then(|idx| self.projects.get(idx))
+        else {
+            self.status_message = Some(StatusMessage::error(t!("status.no_project_selected")));
+            return;
+        };
+        let name = project.file.display_name();
+        let mut commands: Vec<_> = project
+            .file
+            .commands
+            .iter()
+            .map(|(name, command)| (name.clone(), command.clone(), TaskKind::Project))
+            .collect();
+        let fields = self.task_fields();
+        commands.extend(self.config.commands.iter().filter_map(|(name, template)| {
+            let command = task::fill_in(template, &fields)?;
+            Some((name.clone(), command, TaskKind::Custom))
+        }));
+        if commands.is_empty() {
+            self.status_message = Some(StatusMessage::info(t!("status.no_commands", name = name)));
+            return;
+        }
+        self.task_menu = Some(TaskMenu {
+            project_file: project.file.path.clone(),
+            project_name: name,
+            commands,
+            selected: 0,
+        });
+    }
+
+    /// Fields that `[commands]` can use: the selected project, and the
+    /// selected spec's name and endpoints if a spec is selected.
+    fn task_fields(&self) -> Vec<(&'static str, Option<String>)> {
+        let project = self
+            .get_selected_project_index()
+            .and_then(|idx| self.projects.get(idx));
+        let definition = self
+            .get_selected_spec()
+            .and_then(|(project_idx, spec_idx)| {
+                let project = self.projects.get(project_idx)?;
+                let spec = project.specs.get(spec_idx)?;
+                Some((spec.name.clone(), project.file.sessions.get(&spec.name)?))
+            });
+        let alpha = definition
+            .as_ref()
+            .map(|(_, def)| EndpointAddress::parse(&def.alpha));
+        let beta = definition
+            .as_ref()
+            .map(|(_, def)| EndpointAddress::parse(&def.beta));
+        let beta_host = match &beta {
+            Some(EndpointAddress::Ssh {
+                user: Some(user),
+                host,
+                ..
+            }) => Some(format!("{}@{}", user, host)),
+            Some(EndpointAddress::Ssh { host, .. }) => Some(host.clone()),
+            Some(EndpointAddress::Docker { container, .. }) => Some(container.clone()),
+            _ => None,
+        };
+        let path = |address: &Option<EndpointAddress>| {
+            address
+                .as_ref()
+                .map(|address| address.path().display().to_string())
+        };
+        vec![
+            ("project", project.map(|p| p.file.display_name())),
+            ("spec", definition.as_ref().map(|(name, _)| name.clone())),
+            (
+                "alpha",
+                definition.as_ref().map(|(_, def)| def.alpha.clone()),
+            ),
+            ("beta", definition.as_ref().map(|(_, def)| def.beta.clone())),
+            ("alpha_path", path(&alpha)),
+            ("beta_host", beta_host),
+            ("beta_path", path(&beta)),
+        ]
+    }
+
+    pub fn close_task_menu(&mut self) {
+        self.task_menu = None;
+    }
+
+    /// Move the command menu's selection by `delta`, stopping at either end.
+    pub fn move_task_selection(&mut self, delta: i32) {
+        if let Some(menu) = &mut self.task_menu {
+            let last = menu.commands.len().saturating_sub(1) as i32;
+            menu.selected = (menu.selected as i32 + delta).clamp(0, last) as usize;
+        }
+    }
+
+    /// Run the command in the menu whose name is the single character
+    /// `key`, if there is one.
+    pub fn run_task_by_key(&mut self, key: char) {
+        let found = self.task_menu.as_ref().and_then(|menu| {
+            menu.commands
+                .iter()
+                .position(|(name, _, _)| name.chars().eq([key]))
+        });
+        if let (Some(menu), Some(index)) = (&mut self.task_menu, found) {
+            menu.selected = index;
+            self.run_selected_task();
+        }
+    }
+
+    /// Run the command selected in the menu, showing its output as it
+    /// arrives. Closing the output stops the command.
+    pub fn run_selected_task(&mut self) {
+        let Some(menu) = self.task_menu.take() else {
+            return;
+        };
+        let Some((name, command, kind)) = menu.commands.get(menu.selected) else {
+            return;
+        };
+        let result = match kind {
+            TaskKind::Project => TaskRun::start(&self.config.mutagen, &menu.project_file, name),
+            TaskKind::Custom => TaskRun::shell(name, command),
+        }
+        .map_err(anyhow::Error::from);
+        self.audit("run", &format!("{} {}", menu.project_name, name), &result);
+        match result {
+            Ok(task) => {
+                self.task = Some(task);
+                self.text_popup = Some(TextPopup::new(
+                    t!("title.command", name = name),
+                    String::new(),
+                    self.color_scheme.status_message_fg,
+                ));
+            }
+            Err(e) => {
+                self.status_message = Some(StatusMessage::error(t!(
+                    "status.command_failed",
+                    name = name,
+                    error = e
+                )));
+            }
+        }
+    }
+
+    /// Show what the running command printed since the last call, following
+    /// the end of the output unless the popup was scrolled up, and report
+    /// when it finishes.
+    pub fn poll_task(&mut self) {
+        let Some(task) = &mut self.task else {
+            return;
+        };
+        let status = task.poll();
+        if let Some(popup) = &mut self.text_popup {
+            let following = popup.scroll >= popup.max_scroll.get();
+            popup.text = task.output();
+            if following {
+                popup.scroll = u16::MAX;
+            }
+        }
+        let Some(status) = status else {
+            return;
+        };
+        self.status_message = Some(match status.code() {
+            Some(0) => StatusMessage::info(t!("status.command_finished", name = task.name)),
+            Some(code) => {
+                StatusMessage::error(t!("status.command_exited", name = task.name, code = code))
+            }
+            None => StatusMessage::error(t!("status.command_stopped", name = task.name)),
+        });
+        self.task = None;
+    }
+
+    /// Scroll the text popup by `delta` lines, stopping when the first or last line is in view.
+    pub fn scroll_text_popup(&mut self, delta: i32) {
+        if let Some(popup) = &mut self.text_popup {
+            let max_scroll = popup.max_scroll.get();
+            // A popup following its output is scrolled past the end
+            let scroll = popup.scroll.min(max_scroll) as i32;
+            popup.scroll = (scroll + delta).clamp(0, max_scroll as i32) as u16;
+        }
+    }
+
+    /// Copy the text popup's contents to the clipboard.
+    pub fn copy_text_popup(&mut self) {
+        let Some(popup) = &self.text_popup else {
+            return;
+        };
+        self.status_message = Some(match clipboard::copy(&popup.text) {
+            Ok(()) => StatusMessage::info(t!("status.copied", title = popup.title)),
+            Err(e) => StatusMessage::error(t!("status.copy_failed", error = e)),
+        });
+    }
+
+    /// Write the text popup's export, if it has one, to the current directory.
+    pub fn export_text_popup(&mut self) {
+        let Some((file_name, contents)) = self.text_popup.as_ref().and_then(|p| p.export.as_ref())
+        else {
+            self.status_message = Some(StatusMessage::info(t!("status.nothing_to_export")));
+            return;
+        };
+        self.status_message = Some(match std::fs::write(file_name, contents) {
+            Ok(()) => StatusMessage::info(t!("status.exported", file = file_name)),
+            Err(e) => StatusMessage::error(t!("status.write_failed", file = file_name, error = e)),
+        });
+    }
+
+    pub async fn start_selected_spec(&mut self) {
+        if !self.precheck_selected_hosts().await {
+            return;
+        }
+        if let Some((project_idx, spec_idx)) = self.selection.selected_spec() {
+            if let Some(project) = self.projects.get(project_idx) {
+                if project.file.is_compose() {
+                    self.status_message = Some(StatusMessage::warning(t!(
+                        "status.compose_project",
+                        name = project.file.display_name()
+                    )));
+                    return;
+                }
+                if let Some(spec) = project.specs.get(spec_idx) {
+                    // Don't start if already running
+                    if spec.is_running() {
+                        self.status_message = Some(StatusMessage::warning(t!(
+                            "status.spec_already_running",
+                            name = spec.name
                         )));
+                        return;
                     }
+                    let name = spec.name.clone();
+                    self.status_message =
+                        Some(match self.start_spec(project_idx, spec_idx).await {
+                            Ok(()) => StatusMessage::info(t!("status.started_spec", name = name)),
+                            Err(message) => StatusMessage::error(message),
+                        });
                 }
             }
         }
     }
 
+    /// Create the session of a spec that isn't running, with the mode and
+    /// ignores its project file gives it, making its endpoint directories
+    /// first. A failure is returned as the status message to show.
+    async fn start_spec(&mut self, project_idx: usize, spec_idx: usize) -> Result<(), String> {
+        let project = &self.projects[project_idx];
+        let (file, name) = (project.file.clone(), project.specs[spec_idx].name.clone());
+        let identifier = project.project_identifier().map(str::to_string);
+        self.start_spec_from(&file, &name, identifier.as_deref())
+            .await
+    }
+
+    /// Create the session of the spec `name` as defined in `file`, which may
+    /// have changed since the project was loaded, with all the settings
+    /// `mutagen project start` would give it. A session of a project that
+    /// `mutagen project start` is running carries its `identifier`, so it's
+    /// terminated with the rest.
+    async fn start_spec_from(
+        &mut self,
+        file: &ProjectFile,
+        name: &str,
+        identifier: Option<&str>,
+    ) -> Result<(), String> {
+        let Some(session_def) = file.sessions.get(name) else {
+            return Err(t!("status.definition_not_found", name = name));
+        };
+        let defaults_value = file
+            .defaults
+            .as_ref()
+            .and_then(|defaults| serde_yaml::to_value(defaults).ok());
+        let mut args = session_def.create_args(defaults_value.as_ref());
+        if let Some(identifier) = identifier {
+            args.extend([
+                "--label".to_string(),
+                format!("{}={}", PROJECT_LABEL, identifier),
+            ]);
+        }
+
+        self.mutagen_client
+            .ensure_endpoint_directory_exists(&session_def.alpha)
+            .await
+            .map_err(|e| t!("status.alpha_dir_failed", error = e))?;
+        self.mutagen_client
+            .ensure_endpoint_directory_exists(&session_def.beta)
+            .await
+            .map_err(|e| t!("status.beta_dir_failed", error = e))?;
+
+        let result = self
+            .mutagen_client
+            .create_spec_session(name, &session_def.alpha, &session_def.beta, &args)
+            .await;
+        self.audit("start", name, &result);
+        result.map_err(|e| t!("status.start_spec_failed", error = e))
+    }
+
+    /// Start the selected project. If some of its specs are already
+    /// running, which `mutagen project start` refuses, only the others are
+    /// started, one by one, so the project can be brought up to its file.
+    /// So are the specs of a project that includes shared defaults, which
+    /// Mutagen wouldn't read.
     pub async fn start_selected_project(&mut self) {
-        if let Some(project_idx) = self.get_selected_project_index() {
-            if let Some(project) = self.projects.get(project_idx) {
-                match self.mutagen_client.start_project(&project.file.path).await {
-                    Ok(_) => {
-                        self.status_message = Some(StatusMessage::info(format!(
-                            "Started project: {}",
-                            project.file.display_name()
-                        )));
-                    }
-                    Err(e) => {
-                        self.status_message = Some(StatusMessage::error(format!(
-                            "Failed to start project: {}",
-                            e
-                        )));
-                    }
+        if !self.precheck_selected_hosts().await {
+            return;
+        }
+        let Some(project_idx) = self.get_selected_project_index() else {
+            return;
+        };
+        let Some(project) = self.projects.get(project_idx) else {
+            return;
+        };
+        let name = project.file.display_name();
+        let one_by_one = project.is_active() || !project.file.includes.is_empty();
+        if one_by_one && !project.file.is_compose() {
+            let missing: Vec<usize> = (0..project.specs.len())
+                .filter(|&idx| !project.specs[idx].is_running())
+                .collect();
+            self.start_missing_specs(project_idx, &missing, name).await;
+            return;
+        }
+
+        let result = self.mutagen_client.start_project(&project.file.path).await;
+        self.audit("start", &name, &result);
+        match result {
+            Ok(_) => {
+                self.status_message = Some(StatusMessage::info(t!(
+                    "status.started_project",
+                    name = name
+                )));
+                self.notify_event(NotificationEvent::ProjectStarted(name))
+                    .await;
+            }
+            Err(e) => match e.downcast_ref::<StaleLock>() {
+                Some(lock) => {
+                    let minutes = lock.age.map_or(0, |age| age.as_secs() / 60);
+                    self.status_message = Some(StatusMessage::warning(t!(
+                        "status.stale_lock",
+                        path = lock.path.display(),
+                        identifier = lock.identifier,
+                        minutes = minutes
+                    )));
+                    self.pending_lock_removal = Some(lock.clone());
+                }
+                None => {
+                    self.status_message = Some(StatusMessage::error(t!(
+                        "status.start_project_failed",
+                        error = e
+                    )));
                 }
+            },
+        }
+    }
+
+    /// Remove the stale lock waiting for confirmation, noting it in the
+    /// audit log, and start the selected project again.
+    pub async fn confirm_remove_stale_lock(&mut self) {
+        let Some(lock) = self.pending_lock_removal.take() else {
+            return;
+        };
+        let result = self.mutagen_client.remove_stale_lock(&lock);
+        self.audit("remove-lock", &lock.path.display().to_string(), &result);
+        match result {
+            Ok(()) => self.start_selected_project().await,
+            Err(e) => {
+                self.status_message = Some(StatusMessage::error(t!(
+                    "status.remove_lock_failed",
+                    error = format!("{:#}", e)
+                )));
+            }
+        }
+    }
+
+    /// Start the specs of a partly running project that aren't running.
+    async fn start_missing_specs(&mut self, project_idx: usize, missing: &[usize], name: String) {
+        if missing.is_empty() {
+            self.status_message = Some(StatusMessage::info(t!(
+                "status.project_already_running",
+                name = name
+            )));
+            return;
+        }
+        let mut failures = Vec::new();
+        for &spec_idx in missing {
+            if let Err(message) = self.start_spec(project_idx, spec_idx).await {
+                failures.push(message);
+            }
+        }
+        let started = missing.len() - failures.len();
+        self.status_message = Some(match failures.first() {
+            None => StatusMessage::info(t!(
+                "status.started_missing_specs",
+                count = started,
+                name = name
+            )),
+            Some(failure) => StatusMessage::error(t!(
+                "status.start_missing_failed",
+                failed = failures.len(),
+                count = missing.len(),
+                error = failure
+            )),
+        });
+    }
+
+    /// Terminate the project in the file at `path` the way its tool does:
+    /// `mutagen project terminate`, or `mutagen-compose down`.
+    async fn terminate_project_file(&mut self, path: &Path, name: String) {
+        let result = self.mutagen_client.terminate_project(path).await;
+        self.audit("terminate", &name, &result);
+        match result {
+            Ok(_) => {
+                self.status_message = Some(StatusMessage::info(t!(
+                    "status.terminated_project",
+                    name = name
+                )));
+                self.notify_event(NotificationEvent::ProjectTerminated(name))
+                    .await;
+            }
+            Err(e) => {
+                self.status_message = Some(StatusMessage::error(t!(
+                    "status.terminate_project_failed",
+                    error = e
+                )));
             }
         }
     }
@@ -464,29 +3391,19 @@ impl App {
 
                 if is_running {
                     // Project is running → terminate it
-                    match self.mutagen_client.terminate_project(&project.file.path).await {
-                        Ok(_) => {
-                            self.status_message = Some(StatusMessage::info(format!(
-                                "Terminated project: {}",
-                                project.file.display_name()
-                            )));
-                        }
-                        Err(e) => {
-                            self.status_message = Some(StatusMessage::error(format!(
-                                "Failed to terminate project: {}",
-                                e
-                            )));
-                        }
-                    }
+                    let path = project.file.path.clone();
+                    self.terminate_project_file(&path, project.file.display_name())
+                        .await;
                 } else {
                     // Project not running → start it
                     // First terminate any lingering sessions that might interfere
                     for spec in &project.specs {
                         if let Some(session) = &spec.running_session {
-                            let _ = self
+                            let result = self
                                 .mutagen_client
                                 .terminate_session(&session.identifier)
                                 .await;
+                            self.audit("terminate", &spec.name, &result);
                         }
                     }
                     self.start_selected_project().await;
@@ -495,101 +3412,133 @@ impl App {
         }
     }
 
-    pub async fn terminate_selected_project(&mut self) {
+    pub async fn terminate_selected_project(&mut self, redraw: Redraw<'_>) {
         if let Some(project_idx) = self.get_selected_project_index() {
             if let Some(project) = self.projects.get(project_idx) {
                 let running_specs: Vec<_> = project.specs.iter().filter(|s| s.is_running()).collect();
 
                 if running_specs.is_empty() {
-                    self.status_message = Some(StatusMessage::info("No running specs to terminate"));
+                    self.status_message = Some(StatusMessage::info(t!("status.none_to_terminate")));
+                    return;
+                }
+
+                if project.file.is_compose() {
+                    // Brings the containers down along with the sessions
+                    let path = project.file.path.clone();
+                    self.terminate_project_file(&path, project.file.display_name())
+                        .await;
                     return;
                 }
 
-                let mut terminated_count = 0;
-                let mut errors: Vec<String> = Vec::new();
+                // Sessions `mutagen project start` created are terminated
+                // through it too, which runs the file's hooks and removes
+                // its lock file, so it can start the project again
+                if project.project_identifier().is_some()
+                    && get_project_lock_path(&project.file.path).exists()
+                {
+                    let path = project.file.path.clone();
+                    self.terminate_project_file(&path, project.file.display_name())
+                        .await;
+                    return;
+                }
+
+                let hooks = &project.file.hooks;
+                if let Err(e) = self
+                    .mutagen_client
+                    .run_hooks(
+                        &project.file.path,
+                        "beforeTerminate",
+                        &hooks.before_terminate,
+                    )
+                    .await
+                {
+                    self.status_message =
+                        Some(StatusMessage::error(t!("status.hook_failed", error = e)));
+                    return;
+                }
+
+                let mut progress = ProgressReporter::new(
+                    "progress.terminated",
+                    "progress.terminate",
+                    running_specs.len(),
+                )
+                    .attach(self.blocking_op.as_ref())
+                    .on_redraw(redraw);
 
                 for spec in running_specs {
+                    if progress.is_cancelled() {
+                        self.status_message = Some(progress.cancelled());
+                        return;
+                    }
                     if let Some(session) = &spec.running_session {
-                        match self
-                            .mutagen_client
-                            .terminate_session(&session.identifier)
-                            .await
-                        {
-                            Ok(_) => terminated_count += 1,
-                            Err(e) => errors.push(format!("{}: {}", spec.name, e)),
-                        }
+                        let result = self.mutagen_client.terminate_session(&session.identifier).await;
+                        self.audit("terminate", &spec.name, &result);
+                        progress.record(&spec.name, result);
+                        progress.tick(self);
                     }
                 }
 
-                // Status message (follows pattern from push_selected_project)
-                if terminated_count > 0 && errors.is_empty() {
-                    self.status_message = Some(StatusMessage::info(format!(
-                        "Terminated {} session(s)",
-                        terminated_count
-                    )));
-                } else if terminated_count > 0 && !errors.is_empty() {
-                    self.status_message = Some(StatusMessage::warning(format!(
-                        "Terminated {} session(s), {} failed. First error: {}",
-                        terminated_count,
-                        errors.len(),
-                        errors[0]
-                    )));
-                } else {
-                    self.status_message = Some(StatusMessage::error(format!(
-                        "Failed to terminate {} session(s). First error: {}",
-                        errors.len(),
-                        errors[0]
-                    )));
+                self.status_message = Some(progress.finish());
+                if let Err(e) = self
+                    .mutagen_client
+                    .run_hooks(&project.file.path, "afterTerminate", &hooks.after_terminate)
+                    .await
+                {
+                    self.status_message =
+                        Some(StatusMessage::error(t!("status.hook_failed", error = e)));
+                }
+                if progress.completed() > 0 {
+                    let name = project.file.display_name();
+                    self.notify_event(NotificationEvent::ProjectTerminated(name))
+                        .await;
                 }
             }
         }
     }
 
-    pub async fn flush_selected_project(&mut self) {
+    pub async fn flush_selected_project(&mut self, redraw: Redraw<'_>) {
         if let Some(project_idx) = self.get_selected_project_index() {
             if let Some(project) = self.projects.get(project_idx) {
                 let running_specs: Vec<_> = project.specs.iter().filter(|s| s.is_running()).collect();
 
                 if running_specs.is_empty() {
-                    self.status_message = Some(StatusMessage::info("No running specs to flush"));
+                    self.status_message = Some(StatusMessage::info(t!("status.none_to_flush")));
                     return;
                 }
 
-                let mut flushed_count = 0;
-                let mut errors: Vec<String> = Vec::new();
+                let mut progress = ProgressReporter::new(
+                    "progress.started_flushing",
+                    "progress.flush",
+                    running_specs.len(),
+                )
+                    .attach(self.blocking_op.as_ref())
+                    .on_redraw(redraw);
 
                 for spec in running_specs {
+                    if progress.is_cancelled() {
+                        self.status_message = Some(progress.cancelled());
+                        return;
+                    }
                     if let Some(session) = &spec.running_session {
-                        match self.mutagen_client.flush_session(&session.identifier).await {
-                            Ok(_) => flushed_count += 1,
-                            Err(e) => errors.push(format!("{}: {}", spec.name, e)),
+                        let result = self.mutagen_client.flush_session(&session.identifier).await;
+                        self.audit("flush", &spec.name, &result);
+                        if result.is_ok() {
+                            self.pending_flushes.insert(
+                                session.identifier.clone(),
+                                PendingFlush::new(&spec.name, session),
+                            );
                         }
+                        progress.record(&spec.name, result);
+                        progress.tick(self);
                     }
                 }
 
-                // Status message (same pattern as terminate)
-                if flushed_count > 0 && errors.is_empty() {
-                    self.status_message =
-                        Some(StatusMessage::info(format!("Flushed {} session(s)", flushed_count)));
-                } else if flushed_count > 0 && !errors.is_empty() {
-                    self.status_message = Some(StatusMessage::warning(format!(
-                        "Flushed {} session(s), {} failed. First error: {}",
-                        flushed_count,
-                        errors.len(),
-                        errors[0]
-                    )));
-                } else {
-                    self.status_message = Some(StatusMessage::error(format!(
-                        "Failed to flush {} session(s). First error: {}",
-                        errors.len(),
-                        errors[0]
-                    )));
-                }
+                self.status_message = Some(progress.finish());
             }
         }
     }
 
-    pub async fn resume_selected_project(&mut self) {
+    pub async fn resume_selected_project(&mut self, redraw: Redraw<'_>) {
         if let Some(project_idx) = self.get_selected_project_index() {
             if let Some(project) = self.projects.get(project_idx) {
                 let paused_specs: Vec<_> = project
@@ -599,67 +3548,99 @@ impl App {
                     .collect();
 
                 if paused_specs.is_empty() {
-                    self.status_message = Some(StatusMessage::info("No paused specs to resume"));
+                    self.status_message = Some(StatusMessage::info(t!("status.none_to_resume")));
                     return;
                 }
 
-                let mut resumed_count = 0;
-                let mut errors: Vec<String> = Vec::new();
+                let mut progress = ProgressReporter::new(
+                    "progress.resumed",
+                    "progress.resume",
+                    paused_specs.len(),
+                )
+                    .attach(self.blocking_op.as_ref())
+                    .on_redraw(redraw);
 
                 for spec in paused_specs {
+                    if progress.is_cancelled() {
+                        self.status_message = Some(progress.cancelled());
+                        return;
+                    }
                     if let Some(session) = &spec.running_session {
-                        match self.mutagen_client.resume_session(&session.identifier).await {
-                            Ok(_) => resumed_count += 1,
-                            Err(e) => errors.push(format!("{}: {}", spec.name, e)),
-                        }
+                        let result = self.mutagen_client.resume_session(&session.identifier).await;
+                        self.audit("resume", &spec.name, &result);
+                        progress.record(&spec.name, result);
+                        progress.tick(self);
                     }
                 }
 
-                // Status message (same pattern)
-                if resumed_count > 0 && errors.is_empty() {
-                    self.status_message =
-                        Some(StatusMessage::info(format!("Resumed {} session(s)", resumed_count)));
-                } else if resumed_count > 0 && !errors.is_empty() {
-                    self.status_message = Some(StatusMessage::warning(format!(
-                        "Resumed {} session(s), {} failed. First error: {}",
-                        resumed_count,
-                        errors.len(),
-                        errors[0]
-                    )));
-                } else {
-                    self.status_message = Some(StatusMessage::error(format!(
-                        "Failed to resume {} session(s). First error: {}",
-                        errors.len(),
-                        errors[0]
-                    )));
-                }
+                self.status_message = Some(progress.finish());
             }
         }
     }
 
-    pub async fn push_selected_project(&mut self) {
+    pub async fn push_selected_project(&mut self, redraw: Redraw<'_>) {
+        if !self.precheck_selected_hosts().await {
+            return;
+        }
         if let Some(project_idx) = self.get_selected_project_index() {
             if let Some(project) = self.projects.get(project_idx) {
+                if project.file.is_compose() {
+                    self.status_message = Some(StatusMessage::warning(t!(
+                        "status.compose_project",
+                        name = project.file.display_name()
+                    )));
+                    return;
+                }
                 // Terminate all running sessions for this project before creating push sessions
+                let cancel = self.blocking_op.as_ref().map(|op| op.cancel.clone());
+                let mut conflicts: HashMap<String, Vec<String>> = HashMap::new();
+                let mut terminated = 0;
                 for spec in &project.specs {
+                    if cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+                        self.status_message = Some(StatusMessage::warning(match terminated {
+                            0 => t!("status.push_cancelled").to_string(),
+                            _ => t!("status.push_cancelled_terminated", count = terminated),
+                        }));
+                        return;
+                    }
                     if let Some(session) = &spec.running_session {
-                        let _ = self
+                        let result = self
                             .mutagen_client
                             .terminate_session(&session.identifier)
                             .await;
+                        self.audit("terminate", &spec.name, &result);
+                        terminated += usize::from(result.is_ok());
+                        conflicts.insert(spec.name.clone(), session.conflict_roots());
                     }
                 }
 
                 if project.file.sessions.is_empty() {
                     self.status_message =
-                        Some(StatusMessage::error("No sessions defined in project file"));
+                        Some(StatusMessage::error(t!("status.no_sessions_defined")));
+                    return;
+                }
+
+                // Run the hooks mutagen runs around creating a project's sessions
+                let hooks = &project.file.hooks;
+                if let Err(e) = self
+                    .mutagen_client
+                    .run_hooks(&project.file.path, "beforeCreate", &hooks.before_create)
+                    .await
+                {
+                    self.status_message =
+                        Some(StatusMessage::error(t!("status.hook_failed", error = e)));
                     return;
                 }
 
                 // Create push sessions for ALL sessions in the project
-                let mut created_count = 0;
-                let mut errors: Vec<(String, String)> = Vec::new();
-                let total_sessions = project.file.sessions.len();
+                let mut progress = ProgressReporter::new(
+                    "progress.created",
+                    "progress.create",
+                    project.file.sessions.len(),
+                )
+                .noun("progress.push_sessions")
+                .attach(self.blocking_op.as_ref())
+                .on_redraw(redraw);
 
                 // Get defaults for ignore patterns
                 let defaults_value = project
@@ -669,6 +3650,10 @@ impl App {
                     .and_then(|defaults| serde_yaml::to_value(defaults).ok());
 
                 for (session_name, session_def) in &project.file.sessions {
+                    if progress.is_cancelled() {
+                        self.status_message = Some(progress.cancelled());
+                        return;
+                    }
                     let push_name = format!("{}-push", session_name);
 
                     // Extract ignore patterns, merging with defaults
@@ -685,10 +3670,8 @@ impl App {
                         .ensure_endpoint_directory_exists(&session_def.alpha)
                         .await
                     {
-                        errors.push((
-                            session_name.clone(),
-                            format!("Failed to create alpha directory: {}", e),
-                        ));
+                        progress.fail(session_name.as_str(), format!("Failed to create alpha directory: {}", e));
+                        progress.tick(self);
                         continue;
                     }
                     if let Err(e) = self
@@ -696,14 +3679,12 @@ impl App {
                         .ensure_endpoint_directory_exists(&session_def.beta)
                         .await
                     {
-                        errors.push((
-                            session_name.clone(),
-                            format!("Failed to create beta directory: {}", e),
-                        ));
+                        progress.fail(session_name.as_str(), format!("Failed to create beta directory: {}", e));
+                        progress.tick(self);
                         continue;
                     }
 
-                    match self
+                    let result = self
                         .mutagen_client
                         .create_push_session(
                             &push_name,
@@ -711,65 +3692,59 @@ impl App {
                             &session_def.beta,
                             ignore.as_deref(),
                         )
-                        .await
-                    {
-                        Ok(_) => {
-                            created_count += 1;
-                        }
-                        Err(e) => {
-                            errors.push((session_name.clone(), e.to_string()));
+                        .await;
+                    self.audit("push", &push_name, &result);
+                    if result.is_ok() {
+                        if let Some(roots) = conflicts.get(session_name) {
+                            self.record_resolutions(session_name, roots, Winner::Alpha, "push");
                         }
                     }
+                    progress.record(session_name, result);
+                    progress.tick(self);
                 }
 
-                // Set status message based on results
-                if created_count > 0 && errors.is_empty() {
-                    let msg = if created_count == total_sessions {
-                        format!("Created {} push session(s)", created_count)
-                    } else {
-                        format!("Created {} of {} push session(s)", created_count, total_sessions)
-                    };
-                    self.status_message = Some(StatusMessage::info(msg));
-                } else if created_count > 0 && !errors.is_empty() {
-                    // Show first error for context
-                    let first_error = &errors[0];
-                    self.status_message = Some(StatusMessage::warning(format!(
-                        "Created {} push session(s), {} failed. First error: {}: {}",
-                        created_count,
-                        errors.len(),
-                        first_error.0,
-                        first_error.1
-                    )));
-                } else {
-                    // All failed
-                    let error_msg = if errors.len() == 1 {
-                        format!("Failed to create push session {}: {}", errors[0].0, errors[0].1)
-                    } else {
-                        let error_details: Vec<String> = errors.iter().map(|(name, err)| format!("{}: {}", name, err)).collect();
-                        format!("Failed to create {} push sessions: {}", errors.len(), error_details.join("; "))
-                    };
-                    self.status_message = Some(StatusMessage::error(error_msg));
+                self.status_message = Some(progress.finish());
+                if let Err(e) = self
+                    .mutagen_client
+                    .run_hooks(&project.file.path, "afterCreate", &hooks.after_create)
+                    .await
+                {
+                    self.status_message =
+                        Some(StatusMessage::error(t!("status.hook_failed", error = e)));
                 }
             } else {
-                self.status_message = Some(StatusMessage::error("Failed to get selected project"));
+                self.status_message = Some(StatusMessage::error(t!("status.no_selected_project")));
             }
         } else {
-            self.status_message = Some(StatusMessage::error("No project selected"));
+            self.status_message = Some(StatusMessage::error(t!("status.no_project_selected")));
         }
     }
 
     /// Create a push session for the selected spec, replacing any existing two-way session.
     pub async fn push_selected_spec(&mut self) {
+        if !self.precheck_selected_hosts().await {
+            return;
+        }
         if let Some((project_idx, spec_idx)) = self.selection.selected_spec() {
             if let Some(project) = self.projects.get(project_idx) {
+                if project.file.is_compose() {
+                    self.status_message = Some(StatusMessage::warning(t!(
+                        "status.compose_project",
+                        name = project.file.display_name()
+                    )));
+                    return;
+                }
                 if let Some(spec) = project.specs.get(spec_idx) {
                     // Terminate any running two-way session for this spec
+                    let mut conflicts = Vec::new();
                     if let Some(session) = &spec.running_session {
                         if spec.state == crate::project::SyncSpecState::RunningTwoWay {
-                            let _ = self
+                            let result = self
                                 .mutagen_client
                                 .terminate_session(&session.identifier)
                                 .await;
+                            self.audit("terminate", &spec.name, &result);
+                            conflicts = session.conflict_roots();
                         }
                     }
 
@@ -798,9 +3773,9 @@ impl App {
                             .ensure_endpoint_directory_exists(&session_def.alpha)
                             .await
                         {
-                            self.status_message = Some(StatusMessage::error(format!(
-                                "Failed to create alpha directory: {}",
-                                e
+                            self.status_message = Some(StatusMessage::error(t!(
+                                "status.alpha_dir_failed",
+                                error = e
                             )));
                             return;
                         }
@@ -809,15 +3784,15 @@ impl App {
                             .ensure_endpoint_directory_exists(&session_def.beta)
                             .await
                         {
-                            self.status_message = Some(StatusMessage::error(format!(
-                                "Failed to create beta directory: {}",
-                                e
+                            self.status_message = Some(StatusMessage::error(t!(
+                                "status.beta_dir_failed",
+                                error = e
                             )));
                             return;
                         }
 
                         // Create the push session
-                        match self
+                        let result = self
                             .mutagen_client
                             .create_push_session(
                                 &push_name,
@@ -825,137 +3800,79 @@ impl App {
                                 &session_def.beta,
                                 ignore.as_deref(),
                             )
-                            .await
-                        {
+                            .await;
+                        self.audit("push", &push_name, &result);
+                        match result {
                             Ok(_) => {
-                                self.status_message = Some(StatusMessage::info(format!(
-                                    "Created push session: {}",
-                                    push_name
+                                self.record_resolutions(
+                                    &spec.name,
+                                    &conflicts,
+                                    Winner::Alpha,
+                                    "push",
+                                );
+                                self.status_message = Some(StatusMessage::info(t!(
+                                    "status.created_push",
+                                    name = push_name
                                 )));
                             }
                             Err(e) => {
-                                self.status_message = Some(StatusMessage::error(format!(
-                                    "Failed to create push session: {}",
-                                    e
+                                self.status_message = Some(StatusMessage::error(t!(
+                                    "status.create_push_failed",
+                                    error = e
                                 )));
                             }
                         }
                     } else {
-                        self.status_message = Some(StatusMessage::error(format!(
-                            "Session definition not found: {}",
-                            spec.name
+                        self.status_message = Some(StatusMessage::error(t!(
+                            "status.definition_not_found",
+                            name = spec.name
                         )));
                     }
                 } else {
-                    self.status_message = Some(StatusMessage::error("Failed to get selected spec"));
+                    self.status_message = Some(StatusMessage::error(t!("status.no_selected_spec")));
                 }
             } else {
-                self.status_message = Some(StatusMessage::error("Failed to get selected project"));
+                self.status_message = Some(StatusMessage::error(t!("status.no_selected_project")));
             }
         } else {
-            self.status_message = Some(StatusMessage::error("No spec selected"));
+            self.status_message = Some(StatusMessage::error(t!("status.no_spec_selected")));
         }
     }
 
-    pub async fn pause_selected_project(&mut self) {
+    pub async fn pause_selected_project(&mut self, redraw: Redraw<'_>) {
         if let Some(project_idx) = self.get_selected_project_index() {
             if let Some(project) = self.projects.get(project_idx) {
-                let running_specs: Vec<_> = project
-                    .specs
-                    .iter()
-                    .filter(|s| s.is_running())
-                    .collect();
+                let running_specs: Vec<_> = project.specs.iter().filter(|s| s.is_running()).collect();
 
                 if running_specs.is_empty() {
-                    self.status_message = Some(StatusMessage::info("No running specs to pause"));
+                    self.status_message = Some(StatusMessage::info(t!("status.none_to_pause")));
                     return;
                 }
 
-                // Pause ALL running sessions individually
-                let mut paused_count = 0;
-                let mut errors: Vec<String> = Vec::new();
+                let mut progress =
+                    ProgressReporter::new("progress.paused", "progress.pause", running_specs.len())
+                        .attach(self.blocking_op.as_ref())
+                        .on_redraw(redraw);
 
                 for spec in running_specs {
-                    if let Some(session) = &spec.running_session {
-                        match self.mutagen_client.pause_session(&session.identifier).await {
-                            Ok(_) => paused_count += 1,
-                            Err(e) => errors.push(format!("{}: {}", spec.name, e)),
-                        }
+                    if progress.is_cancelled() {
+                        self.status_message = Some(progress.cancelled());
+                        return;
                     }
-                }
-
-                // Set status message based on results
-                if paused_count > 0 && errors.is_empty() {
-                    self.status_message = Some(StatusMessage::info(format!(
-                        "Paused {} session(s)",
-                        paused_count
-                    )));
-                } else if paused_count > 0 && !errors.is_empty() {
-                    self.status_message = Some(StatusMessage::warning(format!(
-                        "Paused {} session(s), {} failed",
-                        paused_count,
-                        errors.len()
-                    )));
-                } else {
-                    self.status_message = Some(StatusMessage::error(format!(
-                        "Failed to pause {} session(s)",
-                        errors.len()
-                    )));
-                }
-            }
-        }
-    }
-
-    pub async fn resume_selected_project(&mut self) {
-        if let Some(project_idx) = self.get_selected_project_index() {
-            if let Some(project) = self.projects.get(project_idx) {
-                let running_specs: Vec<_> = project
-                    .specs
-                    .iter()
-                    .filter(|s| s.is_running())
-                    .collect();
-
-                if running_specs.is_empty() {
-                    self.status_message = Some(StatusMessage::info("No running specs to resume"));
-                    return;
-                }
-
-                // Resume ALL running sessions individually
-                let mut resumed_count = 0;
-                let mut errors: Vec<String> = Vec::new();
-
-                for spec in running_specs {
                     if let Some(session) = &spec.running_session {
-                        match self.mutagen_client.resume_session(&session.identifier).await {
-                            Ok(_) => resumed_count += 1,
-                            Err(e) => errors.push(format!("{}: {}", spec.name, e)),
-                        }
+                        let result = self.mutagen_client.pause_session(&session.identifier).await;
+                        self.audit("pause", &spec.name, &result);
+                        progress.record(&spec.name, result);
+                        progress.tick(self);
                     }
                 }
 
-                // Set status message based on results
-                if resumed_count > 0 && errors.is_empty() {
-                    self.status_message = Some(StatusMessage::info(format!(
-                        "Resumed {} session(s)",
-                        resumed_count
-                    )));
-                } else if resumed_count > 0 && !errors.is_empty() {
-                    self.status_message = Some(StatusMessage::warning(format!(
-                        "Resumed {} session(s), {} failed",
-                        resumed_count,
-                        errors.len()
-                    )));
-                } else {
-                    self.status_message = Some(StatusMessage::error(format!(
-                        "Failed to resume {} session(s)",
-                        errors.len()
-                    )));
-                }
+                self.status_message = Some(progress.finish());
             }
         }
     }
 
-    pub async fn toggle_pause_selected(&mut self) {
+    pub async fn toggle_pause_selected(&mut self, redraw: Redraw<'_>) {
         if let Some((proj_idx, spec_idx)) = self.get_selected_spec() {
             // Individual spec selected - toggle its pause state
             if let Some(project) = self.projects.get(proj_idx) {
@@ -973,21 +3890,76 @@ impl App {
                 let running_specs: Vec<_> = project.specs.iter().filter(|s| s.is_running()).collect();
 
                 if running_specs.is_empty() {
-                    self.status_message = Some(StatusMessage::info(
-                        "Project has no running specs. Use 's' to start.",
-                    ));
+                    self.status_message =
+                        Some(StatusMessage::info(t!("status.project_not_running")));
                     return;
                 }
 
                 // Check if any spec is running (not paused)
                 let has_running = running_specs.iter().any(|s| !s.is_paused());
                 if has_running {
-                    self.pause_selected_project().await;
+                    self.pause_selected_project(redraw).await;
                 } else {
-                    self.resume_selected_project().await;
+                    self.resume_selected_project(redraw).await;
                 }
             }
+        } else if self.selection.selected_host().is_some() {
+            self.toggle_pause_selected_host(redraw).await;
+        }
+    }
+
+    /// The sessions of the running specs under the selected host header,
+    /// with their spec names.
+    pub fn selected_host_sessions(&self) -> Vec<(String, SyncSession)> {
+        self.selection
+            .selected_host_specs()
+            .into_iter()
+            .filter_map(|(proj_idx, spec_idx)| self.projects.get(proj_idx)?.specs.get(spec_idx))
+            .filter_map(|spec| Some((spec.name.clone(), spec.running_session.as_deref()?.clone())))
+            .collect()
+    }
+
+    /// Pause every running spec under the selected host header, or resume
+    /// them all if they're all paused.
+    async fn toggle_pause_selected_host(&mut self, redraw: Redraw<'_>) {
+        let sessions = self.selected_host_sessions();
+        if sessions.is_empty() {
+            self.status_message = Some(StatusMessage::info(t!("status.host_not_running")));
+            return;
+        }
+        let pause = sessions.iter().any(|(_, session)| !session.paused);
+        let sessions: Vec<_> = sessions
+            .into_iter()
+            .filter(|(_, session)| session.paused != pause)
+            .collect();
+
+        let (past, action) = if pause {
+            ("progress.paused", "progress.pause")
+        } else {
+            ("progress.resumed", "progress.resume")
+        };
+        let mut progress = ProgressReporter::new(past, action, sessions.len())
+            .attach(self.blocking_op.as_ref())
+            .on_redraw(redraw);
+
+        for (name, session) in sessions {
+            if progress.is_cancelled() {
+                self.status_message = Some(progress.cancelled());
+                return;
+            }
+            let result = if pause {
+                self.mutagen_client.pause_session(&session.identifier).await
+            } else {
+                self.mutagen_client
+                    .resume_session(&session.identifier)
+                    .await
+            };
+            self.audit(action, &name, &result);
+            progress.record(&name, result);
+            progress.tick(self);
         }
+
+        self.status_message = Some(progress.finish());
     }
 
     pub fn quit(&mut self) {
@@ -999,12 +3971,44 @@ impl App {
             SessionDisplayMode::ShowPaths => SessionDisplayMode::ShowLastRefresh,
             SessionDisplayMode::ShowLastRefresh => SessionDisplayMode::ShowPaths,
         };
-        self.status_message = Some(StatusMessage::info(format!(
-            "Display mode: {}",
-            match self.session_display_mode {
-                SessionDisplayMode::ShowPaths => "Paths",
-                SessionDisplayMode::ShowLastRefresh => "Last Sync Time",
-            }
+        self.status_message = Some(StatusMessage::info(match self.session_display_mode {
+            SessionDisplayMode::ShowPaths => t!("status.display_paths"),
+            SessionDisplayMode::ShowLastRefresh => t!("status.display_last_sync"),
+        }));
+    }
+
+    /// Switch between listing specs under their projects, in one list, and
+    /// under their beta hosts, keeping the cursor on the same spec.
+    pub fn cycle_grouping(&mut self) {
+        self.selection.grouping = self.selection.grouping.next();
+        self.selection.rebuild_from_projects(&self.projects);
+        self.status_message = Some(StatusMessage::info(t!(
+            "status.grouping",
+            grouping = self.selection.grouping.label()
+        )));
+    }
+
+    /// Switch between the full-width list and the list with a details pane.
+    pub fn toggle_split_layout(&mut self) {
+        self.split_layout = !self.split_layout;
+        self.status_message = Some(StatusMessage::info(if self.split_layout {
+            t!("status.layout_split")
+        } else {
+            t!("status.layout_list")
+        }));
+    }
+
+    /// Switch to the next sort order, keeping the cursor on the same row.
+    pub fn cycle_sort_mode(&mut self) {
+        self.sort_mode = self.sort_mode.next();
+        for project in &mut self.projects {
+            sort_specs(&mut project.specs, self.sort_mode);
+        }
+        self.selection.sort_mode = self.sort_mode;
+        self.selection.rebuild_from_projects(&self.projects);
+        self.status_message = Some(StatusMessage::info(t!(
+            "status.sort",
+            sort = self.sort_mode.label()
         )));
     }
 
@@ -1015,21 +4019,92 @@ impl App {
                     if spec.has_conflicts() {
                         self.viewing_conflicts = !self.viewing_conflicts;
                         if self.viewing_conflicts {
-                            self.status_message = Some(StatusMessage::info(format!(
-                                "Viewing conflicts for: {}",
-                                spec.name
+                            *self.conflict_list.borrow_mut() =
+                                ListState::default().with_selected(Some(0));
+                            self.status_message = Some(StatusMessage::info(t!(
+                                "status.viewing_conflicts",
+                                name = spec.name
                             )));
                         } else {
-                            self.status_message = Some(StatusMessage::info("Closed conflict view"));
+                            self.status_message =
+                                Some(StatusMessage::info(t!("status.conflicts_closed")));
                         }
                     } else {
-                        self.status_message =
-                            Some(StatusMessage::error("No conflicts in selected spec"));
+                        self.status_message = Some(StatusMessage::error(t!("status.no_conflicts")));
                     }
                 }
             }
         } else {
-            self.status_message = Some(StatusMessage::error("Select a spec to view conflicts"));
+            self.status_message =
+                Some(StatusMessage::error(t!("status.select_spec_for_conflicts")));
+        }
+    }
+
+    pub fn close_conflict_view(&mut self) {
+        self.viewing_conflicts = false;
+        self.status_message = Some(StatusMessage::info(t!("status.conflicts_closed")));
+    }
+
+    /// Move the conflict selection by `delta` entries. Drawing clamps it to the list.
+    pub fn move_conflict_selection(&mut self, delta: i32) {
+        let mut state = self.conflict_list.borrow_mut();
+        if delta < 0 {
+            state.scroll_up_by(delta.unsigned_abs().min(u16::MAX as u32) as u16);
+        } else {
+            state.scroll_down_by(delta.min(u16::MAX as i32) as u16);
+        }
+    }
+
+    pub fn select_first_conflict(&mut self) {
+        self.conflict_list.borrow_mut().select_first();
+    }
+
+    pub fn select_last_conflict(&mut self) {
+        self.conflict_list.borrow_mut().select_last();
+    }
+
+    /// Add the highlighted conflict's path, anchored to the sync root, to the
+    /// spec's ignores in its project file, and recreate the session without
+    /// it. For build artifacts that should never have been synced.
+    pub async fn ignore_selected_conflict(&mut self) {
+        let Some(root) = self
+            .get_selected_spec_conflicts()
+            .filter(|conflicts| !conflicts.is_empty())
+            .map(|conflicts| {
+                let selected = self.conflict_list.borrow().selected().unwrap_or(0);
+                conflicts[selected.min(conflicts.len() - 1)].root.clone()
+            })
+        else {
+            return;
+        };
+        if root.is_empty() {
+            self.status_message = Some(StatusMessage::warning(t!("status.cannot_ignore_root")));
+            return;
+        }
+        self.open_ignore_editor();
+        let Some(editor) = &mut self.ignore_editor else {
+            return;
+        };
+        let pattern = format!("/{}", root);
+        let name = editor.spec_name.clone();
+        if editor.paths.contains(&pattern) {
+            self.ignore_editor = None;
+            self.status_message = Some(StatusMessage::warning(t!(
+                "status.already_ignored",
+                pattern = pattern,
+                name = name
+            )));
+            return;
+        }
+        editor.paths.push(pattern.clone());
+        editor.save_to_file = true;
+        if self.apply_ignore_editor().await {
+            self.viewing_conflicts = false;
+            self.status_message = Some(StatusMessage::info(t!(
+                "status.conflict_ignored",
+                pattern = pattern,
+                name = name
+            )));
         }
     }
 
@@ -1044,6 +4119,187 @@ impl App {
         None
     }
 
+    /// Whether actions show the mutagen commands they run.
+    pub fn learning(&self) -> bool {
+        self.config.ui.learning
+    }
+
+    /// Configured time between automatic refreshes.
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.config.refresh.interval_secs)
+    }
+
+    /// Time until the next automatic refresh: shorter while sessions are
+    /// busy or an action just ran, longer while they're all idle.
+    pub fn next_refresh_interval(&self) -> Duration {
+        let acted = self
+            .last_action
+            .get()
+            .is_some_and(|at| at.elapsed() < BUSY_AFTER_ACTION);
+        let activity = if acted {
+            Activity::Busy
+        } else {
+            Activity::of(&self.sessions)
+        };
+        self.config.refresh.interval(activity)
+    }
+
+    /// Columns to show in spec rows.
+    pub fn columns(&self) -> ColumnsConfig {
+        self.config.ui.columns
+    }
+
+    /// Whether a slower data source is due. Waits for the session list, and
+    /// pauses along with auto-refresh after a failed refresh.
+    pub fn should_refresh_slow_sources(&self) -> bool {
+        self.last_refresh.is_some() && !self.has_refresh_error && !self.scheduler.due().is_empty()
+    }
+
+    /// Refresh each data source whose interval has elapsed.
+    pub async fn refresh_slow_sources(&mut self) {
+        for source in self.scheduler.due() {
+            match source {
+                DataSource::DiskSpace => self.refresh_disk_space().await,
+            }
+            self.scheduler.mark_run(source);
+        }
+    }
+
+    /// Check free space on every connected endpoint. An endpoint that fails
+    /// keeps its previous reading, which is then shown as stale.
+    #[tracing::instrument(skip(self))]
+    async fn refresh_disk_space(&mut self) {
+        let mut endpoints: Vec<Endpoint> = Vec::new();
+        for session in &self.sessions {
+            for endpoint in [&session.alpha, &session.beta] {
+                let key = endpoint.display_path();
+                if endpoint.connected && !endpoints.iter().any(|e| e.display_path() == key) {
+                    endpoints.push(endpoint.clone());
+                }
+            }
+        }
+
+        for endpoint in endpoints {
+            match self.mutagen_client.disk_space(&endpoint).await {
+                Ok(space) => {
+                    self.disk_space
+                        .insert(endpoint.display_path(), Reading::new(space));
+                }
+                Err(e) => tracing::warn!(error = %e, "disk space check failed"),
+            }
+        }
+
+        // Forget endpoints that no longer belong to any session
+        let sessions = &self.sessions;
+        self.disk_space.retain(|key, _| {
+            sessions.iter().any(|s| {
+                s.alpha.display_path() == *key || s.beta.display_path() == *key
+            })
+        });
+    }
+
+    /// The ssh hosts the selected spec's endpoints are on, or those of every
+    /// spec in the selected project, as (user, host, port).
+    fn selected_ssh_hosts(&self) -> Vec<(Option<String>, String, Option<u16>)> {
+        let Some(project) = self
+            .get_selected_project_index()
+            .and_then(|idx| self.projects.get(idx))
+        else {
+            return Vec::new();
+        };
+        let spec_name = self
+            .selection
+            .selected_spec()
+            .and_then(|(_, spec_idx)| project.specs.get(spec_idx))
+            .map(|spec| spec.name.as_str());
+        let mut hosts: Vec<(Option<String>, String, Option<u16>)> = Vec::new();
+        for (name, definition) in &project.file.sessions {
+            if spec_name.is_some_and(|spec| spec != name) {
+                continue;
+            }
+            for endpoint in [&definition.alpha, &definition.beta] {
+                if let EndpointAddress::Ssh {
+                    user, host, port, ..
+                } = EndpointAddress::parse(endpoint)
+                {
+                    if !hosts.iter().any(|(_, h, _)| *h == host) {
+                        hosts.push((user, host, port));
+                    }
+                }
+            }
+        }
+        hosts
+    }
+
+    /// Check that each of `hosts` answers over ssh and remember the results.
+    /// Hosts that couldn't be checked at all (ssh not installed, say) are
+    /// left out.
+    async fn check_hosts(
+        &mut self,
+        hosts: Vec<(Option<String>, String, Option<u16>)>,
+    ) -> Vec<(String, HostReachability)> {
+        let mut results = Vec::new();
+        for (user, host, port) in hosts {
+            match self.mutagen_client.check_ssh_host(user, &host, port).await {
+                Ok(reachability) => {
+                    self.host_checks
+                        .insert(host.clone(), Reading::new(reachability.clone()));
+                    results.push((host, reachability));
+                }
+                Err(e) => tracing::warn!(host, error = %e, "ssh check failed"),
+            }
+        }
+        results
+    }
+
+    /// Check the ssh hosts of the selected project or spec and report
+    /// whether they answered.
+    pub async fn check_selected_hosts(&mut self) {
+        let hosts = self.selected_ssh_hosts();
+        if hosts.is_empty() {
+            self.status_message = Some(StatusMessage::info(t!("status.no_ssh_hosts")));
+            return;
+        }
+        let results = self.check_hosts(hosts).await;
+        let problem = results
+            .iter()
+            .find(|(_, r)| r.is_unreachable())
+            .or_else(|| {
+                results
+                    .iter()
+                    .find(|(_, r)| *r != HostReachability::Reachable)
+            });
+        self.status_message = Some(match problem {
+            Some((host, HostReachability::Unreachable(error))) => {
+                StatusMessage::error(t!("status.host_unreachable", host = host, error = error))
+            }
+            Some((host, HostReachability::NeedsAuthentication(error))) => {
+                StatusMessage::warning(t!("status.host_needs_auth", host = host, error = error))
+            }
+            _ => StatusMessage::info(t!("status.hosts_reachable", count = results.len())),
+        });
+    }
+
+    /// Before creating sessions, check that the ssh hosts they need answer,
+    /// so an unreachable host is reported as such rather than as whatever
+    /// mutagen makes of it. Returns false, with the status set, if one
+    /// doesn't.
+    async fn precheck_selected_hosts(&mut self) -> bool {
+        let hosts = self.selected_ssh_hosts();
+        let results = self.check_hosts(hosts).await;
+        match results.iter().find(|(_, r)| r.is_unreachable()) {
+            Some((host, HostReachability::Unreachable(error))) => {
+                self.status_message = Some(StatusMessage::error(t!(
+                    "status.host_unreachable",
+                    host = host,
+                    error = error
+                )));
+                false
+            }
+            _ => true,
+        }
+    }
+
     pub fn should_auto_refresh(&self) -> bool {
         // Check if auto-refresh is enabled in config
         if !self.config.refresh.enabled {
@@ -1056,12 +4312,10 @@ impl App {
             return false;
         }
 
-        let interval_secs = self.config.refresh.interval_secs as i64;
-
         match self.last_refresh {
             Some(last) => {
                 let elapsed = Local::now().signed_duration_since(last);
-                elapsed.num_seconds() >= interval_secs
+                elapsed.to_std().unwrap_or_default() >= self.next_refresh_interval()
             }
             None => true,
         }
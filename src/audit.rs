@@ -0,0 +1,188 @@
+//! Audit log of actions performed through mutagui.
+//!
+//! Every start, pause, resume, terminate, flush, and push is appended to
+//! `audit.log` in the state directory as one JSON object per line, so the
+//! history survives restarts and can be inspected with ordinary tools. On a
+//! shared machine this answers "who terminated my session?".
+
+use anyhow::{Context, Result};
+use chrono::{Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Maximum number of entries shown in the viewer.
+pub const AUDIT_VIEW_LIMIT: usize = 200;
+
+/// One recorded action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// RFC 3339 local time.
+    pub timestamp: String,
+    pub user: String,
+    /// Verb such as "terminate" or "push".
+    pub action: String,
+    /// Spec, session, or project the action was applied to.
+    pub target: String,
+    /// Error message if the action failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The mutagen command lines the action ran, when they're recorded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<String>,
+}
+
+/// Append-only log file of actions.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: Option<PathBuf>,
+}
+
+impl AuditLog {
+    /// Log to `audit.log` under the platform state directory
+    /// (`~/.local/state/mutagui` on Linux, Application Support on macOS).
+    pub fn new() -> Self {
+        let path = dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("mutagui").join("audit.log"));
+        Self { path }
+    }
+
+    /// Log to a specific file.
+    #[cfg(test)]
+    pub fn at(path: &Path) -> Self {
+        Self {
+            path: Some(path.to_path_buf()),
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Append an entry for an action, the commands it ran, and its result.
+    pub fn record<T, E: std::fmt::Display>(
+        &self,
+        action: &str,
+        target: &str,
+        commands: &[String],
+        result: &std::result::Result<T, E>,
+    ) -> Result<()> {
+        let entry = AuditEntry {
+            timestamp: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+            user: current_user(),
+            action: action.to_string(),
+            target: target.to_string(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+            commands: commands.to_vec(),
+        };
+        self.append(&entry)
+    }
+
+    fn append(&self, entry: &AuditEntry) -> Result<()> {
+        let path = self
+            .path
+            .as_ref()
+            .context("No state directory for the audit log")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    /// The most recent `limit` entries, newest first. Unreadable lines are skipped.
+    pub fn recent(&self, limit: usize) -> Result<Vec<AuditEntry>> {
+        let Some(path) = &self.path else {
+            return Ok(Vec::new());
+        };
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        Ok(contents
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .take(limit)
+            .collect())
+    }
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_read_back_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::at(&dir.path().join("state").join("audit.log"));
+
+        let commands = ["mutagen sync pause sync_web".to_string()];
+        log.record::<(), String>("pause", "web", &commands, &Ok(()))
+            .unwrap();
+        log.record::<(), _>("terminate", "api", &[], &Err("session not found"))
+            .unwrap();
+
+        let entries = log.recent(10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, "terminate");
+        assert_eq!(entries[0].target, "api");
+        assert_eq!(entries[0].error.as_deref(), Some("session not found"));
+        assert!(entries[1].error.is_none());
+        assert!(entries[0].commands.is_empty());
+        assert_eq!(entries[1].commands, commands);
+    }
+
+    #[test]
+    fn test_recent_skips_bad_lines_and_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let log = AuditLog::at(&path);
+        for target in ["a", "b", "c"] {
+            log.record::<(), String>("flush", target, &[], &Ok(()))
+                .unwrap();
+        }
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        let targets: Vec<_> = log
+            .recent(2)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.target)
+            .collect();
+        assert_eq!(targets, vec!["c", "b"]);
+    }
+
+    #[test]
+    fn test_missing_log_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::at(&dir.path().join("audit.log"));
+        assert!(log.recent(10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+//! Number keys bound to projects for quick switching.
+//!
+//! `b` followed by a digit binds the selected project to that digit, and
+//! the digit alone then jumps to the project from anywhere in the list.
+//! Bindings are saved to `bookmarks.json` in the state directory, keyed by
+//! project file path, so they survive restarts.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Digit-to-project bindings and the file they are saved in.
+#[derive(Debug, Clone, Default)]
+pub struct Bookmarks {
+    path: Option<PathBuf>,
+    slots: BTreeMap<u8, PathBuf>,
+}
+
+impl Bookmarks {
+    /// Load bookmarks from the state directory. A missing or unreadable
+    /// file gives no bookmarks.
+    pub fn load() -> Self {
+        let path = dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("mutagui").join("bookmarks.json"));
+        Self::load_path(path)
+    }
+
+    /// Load bookmarks from a specific file.
+    #[cfg(test)]
+    pub fn at(path: &Path) -> Self {
+        Self::load_path(Some(path.to_path_buf()))
+    }
+
+    fn load_path(path: Option<PathBuf>) -> Self {
+        let slots = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| {
+                serde_json::from_str(&contents)
+                    .inspect_err(|e| tracing::warn!(error = %e, "ignoring unreadable bookmarks"))
+                    .ok()
+            })
+            .unwrap_or_default();
+        Self { path, slots }
+    }
+
+    /// The project file bound to `digit`.
+    pub fn get(&self, digit: u8) -> Option<&Path> {
+        self.slots.get(&digit).map(PathBuf::as_path)
+    }
+
+    /// The digit bound to the project file at `project`, if any.
+    pub fn digit_for(&self, project: &Path) -> Option<u8> {
+        self.slots
+            .iter()
+            .find(|(_, path)| path.as_path() == project)
+            .map(|(digit, _)| *digit)
+    }
+
+    /// Bind `digit` to `project`, replacing whatever it was bound to and any
+    /// other digit the project had, and save.
+    pub fn assign(&mut self, digit: u8, project: &Path) -> Result<()> {
+        self.slots.retain(|_, path| path.as_path() != project);
+        self.slots.insert(digit, project.to_path_buf());
+        self.save()
+    }
+
+    /// Remove the project's binding, if it has one, and save. Returns the
+    /// digit it was bound to.
+    pub fn clear(&mut self, project: &Path) -> Result<Option<u8>> {
+        let digit = self.digit_for(project);
+        if let Some(digit) = digit {
+            self.slots.remove(&digit);
+            self.save()?;
+        }
+        Ok(digit)
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = self
+            .path
+            .as_ref()
+            .context("No state directory for bookmarks")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&self.slots)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assign_persists_and_moves_binding() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("state").join("bookmarks.json");
+        let web = Path::new("/code/web/mutagen.yml");
+        let api = Path::new("/code/api/mutagen.yml");
+
+        let mut bookmarks = Bookmarks::at(&file);
+        bookmarks.assign(1, web).unwrap();
+        bookmarks.assign(2, api).unwrap();
+        // A project has one digit; rebinding it frees the old one
+        bookmarks.assign(3, web).unwrap();
+
+        let reloaded = Bookmarks::at(&file);
+        assert_eq!(reloaded.get(1), None);
+        assert_eq!(reloaded.get(2), Some(api));
+        assert_eq!(reloaded.digit_for(web), Some(3));
+    }
+
+    #[test]
+    fn test_clear_and_unreadable_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("bookmarks.json");
+        let web = Path::new("/code/web/mutagen.yml");
+
+        let mut bookmarks = Bookmarks::at(&file);
+        bookmarks.assign(4, web).unwrap();
+        assert_eq!(bookmarks.clear(web).unwrap(), Some(4));
+        assert_eq!(bookmarks.clear(web).unwrap(), None);
+        assert_eq!(Bookmarks::at(&file).digit_for(web), None);
+
+        std::fs::write(&file, "not json").unwrap();
+        assert_eq!(Bookmarks::at(&file).get(4), None);
+    }
+}
@@ -0,0 +1,167 @@
+//! Browsing the directories of a spec's endpoints.
+//!
+//! Before starting a new spec, or after a push, it helps to see what is
+//! actually in an endpoint's directory. Local directories are read
+//! directly; remote ones are listed with `ls` over ssh or in the container,
+//! the way mutagui runs its other commands on endpoints.
+
+use crate::endpoint::EndpointAddress;
+use crate::verify::shell_dir;
+use std::path::{Path, PathBuf};
+
+/// One name in a directory listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirEntry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// Directories first, then files, each by name.
+pub fn sort_entries(entries: &mut [DirEntry]) {
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+}
+
+/// Script that lists `dir`, marking directories with a trailing `/`.
+/// `-A` and `-p` are POSIX, so this works with BusyBox too.
+pub fn ls_script(dir: &str) -> String {
+    format!("cd {} || exit 1; ls -1Ap", shell_dir(dir))
+}
+
+/// Parse the output of `ls_script`.
+pub fn parse_ls(output: &str) -> Vec<DirEntry> {
+    let mut entries: Vec<DirEntry> = output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.strip_suffix('/') {
+            Some(name) => DirEntry {
+                name: name.to_string(),
+                is_dir: true,
+            },
+            None => DirEntry {
+                name: line.to_string(),
+                is_dir: false,
+            },
+        })
+        .collect();
+    sort_entries(&mut entries);
+    entries
+}
+
+/// The directory above `dir`. A relative remote path is relative to the
+/// directory ssh or docker starts in, so its last parent is `.`.
+pub fn parent_dir(dir: &Path) -> Option<PathBuf> {
+    match dir.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => Some(parent.to_path_buf()),
+        Some(_) if dir != Path::new(".") => Some(PathBuf::from(".")),
+        _ => None,
+    }
+}
+
+/// The endpoints of one spec being browsed, each at the directory last
+/// shown for it.
+#[derive(Debug, Clone)]
+pub struct DirBrowser {
+    pub spec_name: String,
+    /// Alpha and beta, with their paths moved to the directory being shown.
+    pub endpoints: [EndpointAddress; 2],
+    /// Index into `endpoints` of the one shown.
+    pub side: usize,
+    pub entries: Vec<DirEntry>,
+    pub selected: usize,
+    /// Why the directory couldn't be listed.
+    pub error: Option<String>,
+}
+
+impl DirBrowser {
+    pub fn new(spec_name: &str, alpha: EndpointAddress, beta: EndpointAddress) -> Self {
+        Self {
+            spec_name: spec_name.to_string(),
+            endpoints: [alpha, beta],
+            side: 1,
+            entries: Vec::new(),
+            selected: 0,
+            error: None,
+        }
+    }
+
+    pub fn side_name(&self) -> &'static str {
+        if self.side == 0 {
+            "alpha"
+        } else {
+            "beta"
+        }
+    }
+
+    /// The endpoint shown, at its current directory.
+    pub fn current(&self) -> &EndpointAddress {
+        &self.endpoints[self.side]
+    }
+
+    /// Show the directory `dir` of the current endpoint.
+    pub fn set_dir(&mut self, dir: PathBuf) {
+        let endpoint = &mut self.endpoints[self.side];
+        *endpoint = endpoint.clone().with_path(dir);
+    }
+
+    /// The selected entry, if it's a directory.
+    pub fn selected_dir(&self) -> Option<&DirEntry> {
+        self.entries.get(self.selected).filter(|entry| entry.is_dir)
+    }
+
+    /// Move the selection by `delta`, stopping at either end.
+    pub fn move_selection(&mut self, delta: i32) {
+        let last = self.entries.len().saturating_sub(1) as i32;
+        self.selected = (self.selected as i32 + delta).clamp(0, last) as usize;
+    }
+
+    /// Replace the listing, selecting `name` if it's there.
+    pub fn show(&mut self, listing: Result<Vec<DirEntry>, String>, name: Option<&str>) {
+        match listing {
+            Ok(entries) => {
+                self.selected = name
+                    .and_then(|name| entries.iter().position(|e| e.name == name))
+                    .unwrap_or(0);
+                self.entries = entries;
+                self.error = None;
+            }
+            Err(error) => {
+                self.entries.clear();
+                self.selected = 0;
+                self.error = Some(error);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ls() {
+        let entries = parse_ls("notes.txt\nsrc/\n.git/\n.env\n\n");
+        let names: Vec<_> = entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.is_dir))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                (".git", true),
+                ("src", true),
+                (".env", false),
+                ("notes.txt", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parent_dir() {
+        let parent = |dir: &str| parent_dir(Path::new(dir));
+        assert_eq!(parent("/srv/api"), Some(PathBuf::from("/srv")));
+        assert_eq!(parent("/"), None);
+        assert_eq!(parent("~/code"), Some(PathBuf::from("~")));
+        assert_eq!(parent("api"), Some(PathBuf::from(".")));
+        assert_eq!(parent("."), None);
+    }
+}
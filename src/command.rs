@@ -4,10 +4,13 @@
 //! allowing the `MutagenClient` to be tested without requiring the actual
 //! `mutagen` binary.
 
+use crate::config::MutagenConfig;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use std::borrow::Cow;
 use std::process::Output;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::process::Command as TokioCommand;
 use tokio::time::timeout;
 
@@ -29,34 +32,83 @@ pub trait CommandRunner: Send + Sync {
     async fn run(&self, program: &str, args: &[&str], timeout_secs: u64) -> Result<Output>;
 }
 
+/// A shared runner, so a test can keep a handle on a mock runner after
+/// giving it to a client.
+#[async_trait]
+impl<T: CommandRunner + ?Sized> CommandRunner for Arc<T> {
+    async fn run(&self, program: &str, args: &[&str], timeout_secs: u64) -> Result<Output> {
+        (**self).run(program, args, timeout_secs).await
+    }
+}
+
 /// Production implementation that executes real system commands.
 #[derive(Debug, Clone, Default)]
-pub struct SystemCommandRunner;
+pub struct SystemCommandRunner {
+    /// How to run mutagen and mutagen-compose.
+    mutagen: MutagenConfig,
+}
 
 impl SystemCommandRunner {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// A runner that runs mutagen as `mutagen` configures.
+    pub fn with_mutagen(mutagen: MutagenConfig) -> Self {
+        Self { mutagen }
+    }
+
+    fn command(&self, program: &str) -> TokioCommand {
+        if program.starts_with("mutagen") {
+            TokioCommand::from(self.mutagen.command(program))
+        } else {
+            TokioCommand::new(program)
+        }
     }
 }
 
 #[async_trait]
 impl CommandRunner for SystemCommandRunner {
     async fn run(&self, program: &str, args: &[&str], timeout_secs: u64) -> Result<Output> {
-        let child = TokioCommand::new(program)
+        let started = Instant::now();
+        tracing::debug!(program, args = %args.join(" "), "running command");
+        let child = self
+            .command(program)
             .args(args)
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .kill_on_drop(true) // Ensure process is killed if dropped on timeout
             .spawn()
-            .map_err(|e| anyhow!("Failed to spawn command '{}': {}", program, e))?;
+            .map_err(|e| {
+                tracing::warn!(program, error = %e, "failed to spawn command");
+                anyhow!("Failed to spawn command '{}': {}", program, e)
+            })?;
 
         let timeout_duration = Duration::from_secs(timeout_secs);
 
         match timeout(timeout_duration, child.wait_with_output()).await {
-            Ok(Ok(output)) => Ok(output),
-            Ok(Err(e)) => Err(anyhow!("Command '{}' failed: {}", program, e)),
+            Ok(Ok(output)) => {
+                tracing::info!(
+                    program,
+                    args = %args.join(" "),
+                    elapsed_ms = started.elapsed().as_millis() as u64,
+                    status = %output.status,
+                    "command finished"
+                );
+                Ok(output)
+            }
+            Ok(Err(e)) => {
+                tracing::warn!(program, error = %e, "command failed");
+                Err(anyhow!("Command '{}' failed: {}", program, e))
+            }
             Err(_) => {
                 // Timeout occurred - child process will be killed automatically via kill_on_drop
+                tracing::warn!(
+                    program,
+                    args = %args.join(" "),
+                    timeout_secs,
+                    "command timed out"
+                );
                 anyhow::bail!(
                     "Command '{}' timed out after {} seconds",
                     program,
@@ -67,6 +119,44 @@ impl CommandRunner for SystemCommandRunner {
     }
 }
 
+/// Decode command output, replacing invalid UTF-8 with U+FFFD.
+///
+/// File names needn't be UTF-8, and ssh relays remote messages in whatever
+/// encoding the remote locale uses. The replacement character keeps JSON
+/// parseable; the raw bytes go to the debug log so the original is still
+/// recoverable.
+pub fn decode_output(bytes: &[u8]) -> Cow<'_, str> {
+    let text = String::from_utf8_lossy(bytes);
+    if let Cow::Owned(_) = text {
+        tracing::debug!(raw = %bytes.escape_ascii(), "output is not valid UTF-8");
+    }
+    text
+}
+
+/// Decode output for showing in the UI, such as an error message from stderr.
+pub fn decode_message(bytes: &[u8]) -> String {
+    printable(&decode_output(bytes)).into_owned()
+}
+
+/// Make text safe to draw in the terminal: carriage returns are dropped and
+/// other control characters except newline and tab are shown escaped, so an
+/// escape sequence in a file name or message can't corrupt the screen.
+pub fn printable(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(|c| c.is_control() && c != '\n' && c != '\t') {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\r' => {}
+            '\n' | '\t' => out.push(c),
+            c if c.is_control() => out.extend(c.escape_default()),
+            c => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
 /// Mock implementation for testing that returns pre-configured responses.
 /// Supports sequential responses: if the same command is expected multiple times,
 /// each call will return the next response in the sequence.
@@ -153,14 +243,25 @@ impl CommandRunner for MockCommandRunner {
     }
 }
 
+/// An exit status with exit code `code`, for mock output.
+#[cfg(all(test, unix))]
+fn exit_status(code: i32) -> std::process::ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    std::process::ExitStatus::from_raw(code << 8)
+}
+
+/// An exit status with exit code `code`, for mock output.
+#[cfg(all(test, windows))]
+fn exit_status(code: i32) -> std::process::ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    std::process::ExitStatus::from_raw(code as u32)
+}
+
 /// Helper to create a successful Output with given stdout.
 #[cfg(test)]
 pub fn success_output(stdout: &str) -> Output {
-    use std::os::unix::process::ExitStatusExt;
-    use std::process::ExitStatus;
-
     Output {
-        status: ExitStatus::from_raw(0),
+        status: exit_status(0),
         stdout: stdout.as_bytes().to_vec(),
         stderr: Vec::new(),
     }
@@ -169,11 +270,8 @@ pub fn success_output(stdout: &str) -> Output {
 /// Helper to create a failed Output with given stderr.
 #[cfg(test)]
 pub fn failure_output(stderr: &str) -> Output {
-    use std::os::unix::process::ExitStatusExt;
-    use std::process::ExitStatus;
-
     Output {
-        status: ExitStatus::from_raw(1 << 8), // Exit code 1
+        status: exit_status(1),
         stdout: Vec::new(),
         stderr: stderr.as_bytes().to_vec(),
     }
@@ -183,6 +281,25 @@ pub fn failure_output(stderr: &str) -> Output {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_decode_output_replaces_invalid_utf8() {
+        assert_eq!(decode_output(b"caf\xc3\xa9"), "café");
+        assert_eq!(decode_output(b"caf\xe9 \xff"), "caf\u{fffd} \u{fffd}");
+        assert!(matches!(decode_output(b"plain"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_decode_message_escapes_control_characters() {
+        assert_eq!(
+            decode_message(b"\x1b[31mPermission denied\x1b[0m\r\n"),
+            "\\u{1b}[31mPermission denied\\u{1b}[0m\n"
+        );
+        assert_eq!(
+            decode_message(b"Zugriff verweigert: \xe4\tx\n"),
+            "Zugriff verweigert: \u{fffd}\tx\n"
+        );
+    }
+
     #[tokio::test]
     async fn test_mock_runner_returns_configured_output() {
         let mock = MockCommandRunner::new();
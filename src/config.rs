@@ -3,9 +3,14 @@
 //! This module provides user configuration management, supporting TOML config files
 //! in standard locations (XDG on Linux, ~/Library on macOS, etc.)
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::lint::{self, ProjectIssue};
+use crate::schedule::Activity;
 
 /// Application configuration.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -17,6 +22,23 @@ pub struct Config {
     pub refresh: RefreshConfig,
     /// Project discovery settings.
     pub projects: ProjectConfig,
+    /// Health notification settings.
+    pub notifications: NotificationConfig,
+    /// What to do about sessions that stall or stay disconnected.
+    pub watchdog: WatchdogConfig,
+    /// Diagnostic logging settings.
+    pub log: LogConfig,
+    /// What to show on launch.
+    pub startup: StartupConfig,
+    /// How to run mutagen.
+    pub mutagen: MutagenConfig,
+    /// Shell commands to run on events, by event name.
+    pub hooks: BTreeMap<String, String>,
+    /// Shell commands offered by `X` for any spec, by name, with fields of
+    /// the spec like `{beta_host}` filled in.
+    pub commands: BTreeMap<String, String>,
+    /// Skeletons for new specs, by name, offered by the new session form.
+    pub templates: BTreeMap<String, SpecTemplate>,
 }
 
 /// UI configuration options.
@@ -27,6 +49,30 @@ pub struct UiConfig {
     pub theme: ThemeMode,
     /// Show session paths or last refresh time by default.
     pub default_display_mode: DisplayMode,
+    /// Details of the selected row in a pane beside the list, or only in the
+    /// status bar.
+    pub layout: LayoutMode,
+    /// Initial order of specs within each project.
+    pub default_sort: SortMode,
+    /// Color depth to render with (auto-detected by default).
+    pub color_depth: ColorDepthMode,
+    /// Draw icons as plain ASCII. Also set by `--ascii`.
+    pub ascii: bool,
+    /// Which columns spec rows show (`[ui.columns]`).
+    pub columns: ColumnsConfig,
+    /// Palette file to color with, on top of `theme`: a name looked up in the
+    /// `themes` directory next to this file, or a path to a `.toml` file.
+    pub palette: Option<String>,
+    /// Colors for the custom theme (`[ui.colors]`), keyed by `ColorScheme`
+    /// field name, as `#rrggbb`, a color name, or a 256-color index. These
+    /// override the palette file.
+    pub colors: BTreeMap<String, String>,
+    /// Language of the interface text, e.g. `de`. Defaults to the one in
+    /// `LANG`; catalogs other than the built-in ones are read from the
+    /// `locales` directory next to this file.
+    pub locale: Option<String>,
+    /// Show the mutagen command line behind each action, to copy with `L`.
+    pub learning: bool,
 }
 
 /// Theme mode selection.
@@ -40,6 +86,46 @@ pub enum ThemeMode {
     Light,
     /// Force dark theme.
     Dark,
+    /// Colors from `palette` and `[ui.colors]`, on top of the detected theme.
+    Custom,
+}
+
+impl ThemeMode {
+    /// The mode after this one, for cycling with a key. The custom theme
+    /// isn't in the cycle since it needs colors from the config file.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Auto => Self::Light,
+            Self::Light => Self::Dark,
+            Self::Dark | Self::Custom => Self::Auto,
+        }
+    }
+
+    /// Name as written in the config file.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Light => "light",
+            Self::Dark => "dark",
+            Self::Custom => "custom",
+        }
+    }
+}
+
+/// Color depth selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorDepthMode {
+    /// Detect from `COLORTERM` and `TERM`.
+    #[default]
+    Auto,
+    /// The 16 standard ANSI colors.
+    Basic,
+    /// The 256-color palette.
+    #[serde(rename = "256")]
+    Ansi256,
+    /// 24-bit RGB colors.
+    TrueColor,
 }
 
 /// Default display mode for sessions.
@@ -53,6 +139,82 @@ pub enum DisplayMode {
     LastRefresh,
 }
 
+/// Arrangement of the main panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LayoutMode {
+    /// Project list across the full width.
+    #[default]
+    Stacked,
+    /// Project list on the left, details of the selected row on the right.
+    Split,
+}
+
+/// Columns shown in each spec row. Hidden columns free up width for the
+/// endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnsConfig {
+    /// Spec name.
+    pub name: bool,
+    /// Alpha and beta endpoints, truncated to fit the terminal.
+    pub endpoints: bool,
+    /// Session status, staging progress, and conflict count.
+    pub stats: bool,
+    /// When the session last synced, remembered across restarts.
+    #[serde(alias = "last-sync")]
+    pub last_sync: bool,
+    /// How long ago the session was created.
+    pub age: bool,
+    /// A "(push)" tag on push sessions.
+    pub mode: bool,
+}
+
+impl Default for ColumnsConfig {
+    fn default() -> Self {
+        Self {
+            name: true,
+            endpoints: true,
+            stats: true,
+            last_sync: false,
+            age: false,
+            mode: true,
+        }
+    }
+}
+
+/// Order of specs within a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortMode {
+    /// Alphabetical by spec name.
+    #[default]
+    Name,
+    /// Specs needing attention (conflicts, errors, disconnects) first.
+    Status,
+    /// Most recently synced specs first.
+    LastSync,
+}
+
+impl SortMode {
+    /// The mode after this one, for cycling with a key.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Status,
+            Self::Status => Self::LastSync,
+            Self::LastSync => Self::Name,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Status => "status",
+            Self::LastSync => "last sync",
+        }
+    }
+}
+
 /// Auto-refresh configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -61,6 +223,18 @@ pub struct RefreshConfig {
     pub enabled: bool,
     /// Refresh interval in seconds.
     pub interval_secs: u64,
+    /// Refresh faster while sessions are busy and slower while they're idle,
+    /// instead of always every `interval_secs`.
+    pub adaptive: bool,
+    /// Milliseconds between refreshes while a session is scanning, staging,
+    /// or otherwise busy, and just after an action.
+    pub busy_interval_ms: u64,
+    /// Seconds between refreshes while every session is watching or paused.
+    pub idle_interval_secs: u64,
+    /// Seconds between checks of free disk space on each endpoint (0 to disable).
+    pub disk_space_secs: u64,
+    /// Reload project files as soon as they change on disk.
+    pub watch_files: bool,
 }
 
 /// Project discovery configuration.
@@ -71,6 +245,189 @@ pub struct ProjectConfig {
     pub search_paths: Vec<PathBuf>,
     /// Directories to exclude from project discovery.
     pub exclude_patterns: Vec<String>,
+    /// How many levels of subdirectories below the base directory are
+    /// searched for project files.
+    pub max_depth: usize,
+    /// `projects.toml` next to the config file, if there is one. The project
+    /// files it lists are loaded instead of searching for them.
+    #[serde(skip)]
+    pub workspace: Option<PathBuf>,
+}
+
+/// Health notification configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Send desktop notifications from the TUI (always on in daemon mode).
+    pub desktop: bool,
+    /// Seconds a session may stay in one transitional state before it's reported as stalled.
+    pub stall_secs: u64,
+    /// POST events to a webhook (Slack incoming webhooks and the like).
+    pub webhook: Option<WebhookConfig>,
+    /// Ring the terminal bell when a flush started from mutagui finishes.
+    pub flush_bell: bool,
+    /// Shell command to run when a flush finishes, with the spec name as `$1`.
+    /// On Windows it runs with `cmd /C`, and the spec name follows it.
+    pub flush_command: Option<String>,
+    /// Seconds a `[hooks]` command may run before it is stopped.
+    pub hook_timeout_secs: u64,
+}
+
+/// Startup configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StartupConfig {
+    /// Project or spec to select after the first refresh, as `project`,
+    /// `project:spec`, or `:spec`. Overridden by `--select`.
+    pub select: Option<String>,
+    /// Also open the selected spec's conflict view if it has conflicts.
+    pub open_conflicts: bool,
+}
+
+/// A skeleton for new specs (`[templates.NAME]`). `{name}` in its
+/// endpoints stands for the new spec's name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpecTemplate {
+    pub alpha: Option<String>,
+    pub beta: String,
+    pub mode: Option<String>,
+    pub ignore: Vec<String>,
+}
+
+/// How to run mutagen (`[mutagen]`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MutagenConfig {
+    /// The mutagen executable, if it isn't `mutagen` on `PATH`. A leading
+    /// `~` is expanded.
+    pub binary_path: Option<PathBuf>,
+    /// Arguments put before those of every mutagen command.
+    pub extra_args: Vec<String>,
+    /// Environment variables to run mutagen and mutagen-compose with, such
+    /// as `MUTAGEN_DATA_DIRECTORY`.
+    pub environment: BTreeMap<String, String>,
+    /// Seconds to wait for listing sessions, and for pausing, resuming,
+    /// flushing, or terminating one.
+    pub command_timeout_secs: u64,
+    /// Seconds to wait for resetting a session, starting or terminating a
+    /// project, and making an endpoint's directory.
+    pub project_timeout_secs: u64,
+    /// Seconds to wait for creating a session, and for browsing an endpoint
+    /// or checking its free space.
+    pub create_timeout_secs: u64,
+    /// Seconds to watch for the sync cycle of a flush started from mutagui,
+    /// which returns before the cycle runs, before giving up on it.
+    pub flush_timeout_secs: u64,
+    /// How many more times to try a command that failed for a reason that
+    /// may pass, such as a timeout or the daemon still starting.
+    pub retries: u32,
+    /// Milliseconds to wait before the first retry. Each later wait doubles.
+    pub retry_delay_ms: u64,
+    /// Put before the name of each session mutagui creates, such as
+    /// `mutagui-`, and taken off again when it is listed. Like the start of
+    /// a name, a letter, then letters, digits, and dashes.
+    #[serde(deserialize_with = "session_prefix")]
+    pub session_prefix: String,
+}
+
+/// Read a session prefix, which must start a valid session name.
+fn session_prefix<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let prefix = String::deserialize(deserializer)?;
+    if prefix.is_empty() || crate::wizard::is_valid_name(&prefix) {
+        Ok(prefix)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "session prefix {:?} must be a letter, then letters, digits, and dashes",
+            prefix
+        )))
+    }
+}
+
+impl Default for MutagenConfig {
+    fn default() -> Self {
+        Self {
+            binary_path: None,
+            extra_args: Vec::new(),
+            environment: BTreeMap::new(),
+            command_timeout_secs: 5,
+            project_timeout_secs: 10,
+            create_timeout_secs: 15,
+            flush_timeout_secs: 600,
+            retries: 2,
+            retry_delay_ms: 500,
+            session_prefix: String::new(),
+        }
+    }
+}
+
+impl MutagenConfig {
+    /// A command that runs `program`, which is `mutagen` or another program
+    /// that talks to its daemon such as `mutagen-compose`, as configured.
+    pub fn command(&self, program: &str) -> std::process::Command {
+        let mut command = match &self.binary_path {
+            Some(path) if program == "mutagen" => {
+                std::process::Command::new(crate::project::expand_tilde_in_path(path))
+            }
+            _ => std::process::Command::new(program),
+        };
+        if program == "mutagen" {
+            command.args(&self.extra_args);
+        }
+        command.envs(&self.environment);
+        command
+    }
+}
+
+/// Watchdog configuration. A session is stalled when it has been busy
+/// without completing a sync cycle for `notifications.stall_secs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    /// Seconds an endpoint may stay disconnected before the session is
+    /// flagged as offline. 0 never flags it.
+    pub disconnect_secs: u64,
+    /// What to do about a stalled or offline session besides flagging it.
+    pub action: WatchdogAction,
+}
+
+/// What the watchdog does about a stalled or offline session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchdogAction {
+    /// Only flag it and report it.
+    #[default]
+    None,
+    /// Run `mutagen sync resume`, which reconnects its endpoints.
+    Resume,
+    /// Run `mutagen sync reset`, which also discards its sync history.
+    Reset,
+}
+
+/// Diagnostic logging configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// File to append a log of mutagen, ssh, and other command invocations to.
+    /// Overridden by `--log-file`.
+    pub path: Option<PathBuf>,
+}
+
+/// Webhook notification configuration (`[notifications.webhook]`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    /// URL to POST to.
+    pub url: String,
+    /// Events to send: conflict, error, disconnect, stall, project_started, project_terminated.
+    pub events: Vec<String>,
+    /// JSON body with `{event}`, `{subject}`, `{title}`, `{message}`, and `{timestamp}`
+    /// placeholders. A generic JSON object is sent if unset.
+    pub template: Option<String>,
+    /// Extra attempts after a failed POST.
+    pub retries: u32,
+    /// Maximum POSTs per minute; events beyond this are dropped.
+    pub max_per_minute: u32,
 }
 
 impl Default for UiConfig {
@@ -78,6 +435,27 @@ impl Default for UiConfig {
         Self {
             theme: ThemeMode::Auto,
             default_display_mode: DisplayMode::Paths,
+            layout: LayoutMode::Stacked,
+            default_sort: SortMode::Name,
+            color_depth: ColorDepthMode::Auto,
+            ascii: false,
+            columns: ColumnsConfig::default(),
+            palette: None,
+            colors: BTreeMap::new(),
+            locale: None,
+            learning: false,
+        }
+    }
+}
+
+impl RefreshConfig {
+    /// Time to wait before the next refresh, given what the sessions are doing.
+    pub fn interval(&self, activity: Activity) -> Duration {
+        match activity {
+            _ if !self.adaptive => Duration::from_secs(self.interval_secs),
+            Activity::Busy => Duration::from_millis(self.busy_interval_ms),
+            Activity::Waiting => Duration::from_secs(self.interval_secs),
+            Activity::Idle => Duration::from_secs(self.idle_interval_secs),
         }
     }
 }
@@ -87,6 +465,56 @@ impl Default for RefreshConfig {
         Self {
             enabled: true,
             interval_secs: 3,
+            adaptive: true,
+            busy_interval_ms: 500,
+            idle_interval_secs: 10,
+            disk_space_secs: 300,
+            watch_files: true,
+        }
+    }
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            desktop: false,
+            stall_secs: crate::health::DEFAULT_STALL_SECS,
+            webhook: None,
+            flush_bell: false,
+            flush_command: None,
+            hook_timeout_secs: 600,
+        }
+    }
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            disconnect_secs: 120,
+            action: WatchdogAction::None,
+        }
+    }
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            events: [
+                "conflict",
+                "error",
+                "disconnect",
+                "stall",
+                "offline",
+                "project_started",
+                "project_terminated",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            template: None,
+            retries: 2,
+            max_per_minute: 10,
         }
     }
 }
@@ -100,6 +528,8 @@ impl Default for ProjectConfig {
                 ".git".to_string(),
                 "target".to_string(),
             ],
+            max_depth: 1,
+            workspace: None,
         }
     }
 }
@@ -109,18 +539,117 @@ impl Config {
     ///
     /// Returns the default config if no config file exists.
     pub fn load() -> Result<Self> {
+        let mut config = Self::default();
         if let Some(path) = Self::config_path() {
             if path.exists() {
                 let contents = std::fs::read_to_string(&path)?;
-                let config: Config = toml::from_str(&contents)?;
-                return Ok(config);
+                config = toml::from_str(&contents)?;
             }
+            let workspace = path.with_file_name("projects.toml");
+            config.projects.workspace = workspace.exists().then_some(workspace);
+        }
+        Ok(config)
+    }
+
+    /// Load the colors of a named palette (`themes/<name>.toml` in the
+    /// config directory) or of a palette file given by path.
+    pub fn load_palette(name: &str) -> Result<BTreeMap<String, String>> {
+        let path = if name.ends_with(".toml") || name.contains(std::path::MAIN_SEPARATOR) {
+            crate::project::expand_tilde_in_path(std::path::Path::new(name))
+        } else {
+            let dir = Self::config_path()
+                .and_then(|path| path.parent().map(|dir| dir.join("themes")))
+                .with_context(|| format!("No config directory for palette {}", name))?;
+            dir.join(format!("{}.toml", name))
+        };
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read palette {}", path.display()))?;
+        toml::from_str(&contents).with_context(|| format!("Invalid palette {}", path.display()))
+    }
+
+    /// Write `theme` to the config file, keeping the rest of the file,
+    /// comments included, as it is. Returns the file's path.
+    pub fn save_theme(theme: ThemeMode) -> Result<PathBuf> {
+        let path = Self::config_path().context("No config directory")?;
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let value = format!("\"{}\"", theme.label());
+        let updated = set_toml_value(&contents, "ui", "theme", &value);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(&path, updated)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Directory of message catalogs for locales that aren't built in.
+    pub fn locales_dir() -> Option<PathBuf> {
+        Self::config_path().and_then(|path| path.parent().map(|dir| dir.join("locales")))
+    }
+
+    /// Problems with where project files are looked for: configured search
+    /// paths that don't exist and the like, pointing at their lines in the
+    /// config file, and a workspace file that can't be read.
+    pub fn search_path_issues(&self) -> Vec<ProjectIssue> {
+        let mut issues = Vec::new();
+        if let Some(path) = Self::config_path() {
+            let contents = std::fs::read_to_string(&path).unwrap_or_default();
+            issues = lint::check_search_paths(&path, &contents, &self.projects.search_paths);
+        }
+        if let Some(workspace) = &self.projects.workspace {
+            issues.extend(lint::check_workspace(workspace));
+        }
+        issues
+    }
+
+    /// Every setting in this config and where it came from, given the text
+    /// of the config file: the file if it sets the same value, the defaults
+    /// if it doesn't set one, and otherwise a change made while running.
+    pub fn settings(&self, file: &str) -> Vec<Setting> {
+        let file = file.parse::<toml::Table>().unwrap_or_default();
+        let mut in_file = BTreeMap::new();
+        flatten("", &toml::Value::Table(file), &mut in_file);
+        let defaults = flat_settings(&Config::default());
+        flat_settings(self)
+            .into_iter()
+            .map(|(key, value)| {
+                let source = match in_file.get(&key) {
+                    Some(set) if *set == value => SettingSource::ConfigFile,
+                    None if defaults.get(&key) == Some(&value) => SettingSource::Default,
+                    _ => SettingSource::Runtime,
+                };
+                Setting {
+                    key,
+                    value: value.to_string(),
+                    source,
+                }
+            })
+            .collect()
+    }
+
+    /// Problems with the config file: one that doesn't parse, which
+    /// [`Config::load`] refuses, or settings that aren't known.
+    pub fn check() -> Vec<ProjectIssue> {
+        match Self::config_path() {
+            Some(path) if path.exists() => lint::check_config(&path, &Self::file_contents()),
+            _ => Vec::new(),
         }
-        Ok(Self::default())
+    }
+
+    /// Text of the config file, empty if there isn't one.
+    pub fn file_contents() -> String {
+        Self::config_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .unwrap_or_default()
     }
 
     /// Get the standard config file path for the current platform.
-    fn config_path() -> Option<PathBuf> {
+    pub fn config_path() -> Option<PathBuf> {
         dirs::config_dir().map(|mut path| {
             path.push("mutagui");
             path.push("config.toml");
@@ -129,10 +658,165 @@ impl Config {
     }
 }
 
+/// Set `key` in `[section]` of a TOML document to `value` (already in TOML
+/// syntax), editing only that line. The key is added to the section, and
+/// the section to the end of the file, if missing.
+fn set_toml_value(contents: &str, section: &str, key: &str, value: &str) -> String {
+    let header = format!("[{}]", section);
+    let setting = format!("{} = {}", key, value);
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+
+    let is_header = |line: &str| line.trim_start().starts_with('[');
+    let Some(start) = lines.iter().position(|line| line.trim() == header) else {
+        if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(header);
+        lines.push(setting);
+        return lines.join("\n") + "\n";
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| is_header(line))
+        .map_or(lines.len(), |offset| start + 1 + offset);
+
+    let existing = (start + 1..end).find(|&i| {
+        lines[i]
+            .split_once('=')
+            .is_some_and(|(name, _)| name.trim() == key)
+    });
+    match existing {
+        Some(i) => lines[i] = setting,
+        None => lines.insert(start + 1, setting),
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Where the value of a setting in effect came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingSource {
+    Default,
+    ConfigFile,
+    /// A command-line flag.
+    Flag,
+    /// Changed with a key while running, like the sort order.
+    Runtime,
+}
+
+impl SettingSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::ConfigFile => "config file",
+            Self::Flag => "command line",
+            Self::Runtime => "changed while running",
+        }
+    }
+}
+
+/// A setting in effect, by its dotted key in the config file, with its
+/// value in TOML syntax.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setting {
+    pub key: String,
+    pub value: String,
+    pub source: SettingSource,
+}
+
+/// `settings` one per line as `key = value`, with where each came from in
+/// a comment, lined up.
+pub fn format_settings(settings: &[Setting]) -> String {
+    let lines: Vec<String> = settings
+        .iter()
+        .map(|setting| format!("{} = {}", setting.key, setting.value))
+        .collect();
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .zip(settings)
+        .map(|(line, setting)| format!("{:width$}  # {}\n", line, setting.source.label()))
+        .collect()
+}
+
+/// The settings of `config` by dotted key. Unset options are left out.
+fn flat_settings(config: &Config) -> BTreeMap<String, toml::Value> {
+    let mut settings = BTreeMap::new();
+    match toml::Value::try_from(config) {
+        Ok(value) => flatten("", &value, &mut settings),
+        Err(e) => tracing::warn!(error = %e, "cannot list settings"),
+    }
+    settings
+}
+
+/// Collect the values in `value` into `settings` by dotted key, starting
+/// with `prefix`. Empty tables, like `ui.colors` by default, are left out.
+fn flatten(prefix: &str, value: &toml::Value, settings: &mut BTreeMap<String, toml::Value>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&key, value, settings);
+            }
+        }
+        _ => {
+            settings.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_setting_sources() {
+        let file = "[ui]\ntheme = \"dark\"\nlayout = \"stacked\"\n";
+        let mut config: Config = toml::from_str(file).unwrap();
+        config.ui.default_sort = SortMode::Status;
+
+        let settings = config.settings(file);
+        let source = |key: &str| {
+            let setting = settings.iter().find(|s| s.key == key).unwrap();
+            (setting.value.as_str(), setting.source)
+        };
+        assert_eq!(source("ui.theme"), ("\"dark\"", SettingSource::ConfigFile));
+        assert_eq!(
+            source("ui.layout"),
+            ("\"stacked\"", SettingSource::ConfigFile)
+        );
+        assert_eq!(
+            source("ui.default_sort"),
+            ("\"status\"", SettingSource::Runtime)
+        );
+        assert_eq!(
+            source("refresh.interval_secs"),
+            ("3", SettingSource::Default)
+        );
+        assert!(!settings.iter().any(|s| s.key == "ui.palette"));
+        assert!(!settings.iter().any(|s| s.key.starts_with("ui.colors")));
+
+        let notifications: Vec<Setting> = settings
+            .iter()
+            .filter(|s| s.key.starts_with("notifications."))
+            .take(2)
+            .cloned()
+            .collect();
+        let text = format_settings(&notifications);
+        assert_eq!(
+            text,
+            "notifications.desktop = false     # default\n\
+             notifications.flush_bell = false  # default\n"
+        );
+    }
+
     #[test]
     fn test_default_config() {
         let config = Config::default();
@@ -165,6 +849,7 @@ mod tests {
         assert_eq!(config.refresh.interval_secs, 5);
         // Default values for unspecified fields
         assert!(config.refresh.enabled);
+        assert_eq!(config.refresh.disk_space_secs, 300);
         assert_eq!(config.ui.theme, ThemeMode::Auto);
     }
 
@@ -179,6 +864,119 @@ mod tests {
         assert_eq!(config.ui.theme, ThemeMode::Dark);
     }
 
+    #[test]
+    fn test_custom_colors_parsing() {
+        let toml_str = r##"
+            [ui]
+            theme = "custom"
+
+            [ui.colors]
+            header_fg = "#268bd2"
+            selection_bg = "black"
+        "##;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.ui.theme, ThemeMode::Custom);
+        assert_eq!(config.ui.colors["header_fg"], "#268bd2");
+        assert_eq!(config.ui.colors.len(), 2);
+        assert!(config.ui.palette.is_none());
+    }
+
+    #[test]
+    fn test_load_palette_from_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("solarized.toml");
+        std::fs::write(&path, "status_error_fg = \"#dc322f\"\n").unwrap();
+
+        let colors = Config::load_palette(path.to_str().unwrap()).unwrap();
+        assert_eq!(colors["status_error_fg"], "#dc322f");
+        assert!(Config::load_palette(dir.path().join("missing.toml").to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_set_toml_value() {
+        let contents = "# My settings\n[ui]\n# light clashes with Solarized\ntheme = \"light\"\n\n[refresh]\ninterval_secs = 5\n";
+        assert_eq!(
+            set_toml_value(contents, "ui", "theme", "\"dark\""),
+            "# My settings\n[ui]\n# light clashes with Solarized\ntheme = \"dark\"\n\n[refresh]\ninterval_secs = 5\n"
+        );
+
+        // Key added to an existing section
+        assert_eq!(
+            set_toml_value("[ui]\ncolor_depth = \"256\"\n", "ui", "theme", "\"dark\""),
+            "[ui]\ntheme = \"dark\"\ncolor_depth = \"256\"\n"
+        );
+
+        // Section added
+        assert_eq!(
+            set_toml_value("[refresh]\nenabled = false\n", "ui", "theme", "\"auto\""),
+            "[refresh]\nenabled = false\n\n[ui]\ntheme = \"auto\"\n"
+        );
+        assert_eq!(
+            set_toml_value("", "ui", "theme", "\"auto\""),
+            "[ui]\ntheme = \"auto\"\n"
+        );
+
+        // Keys with the same name in other sections are left alone
+        let contents = "[ui.colors]\ntheme = \"x\"\n";
+        let updated = set_toml_value(contents, "ui", "theme", "\"dark\"");
+        let config: Config = toml::from_str(&updated).unwrap();
+        assert_eq!(config.ui.theme, ThemeMode::Dark);
+        assert_eq!(config.ui.colors["theme"], "x");
+    }
+
+    #[test]
+    fn test_theme_mode_cycle() {
+        assert_eq!(ThemeMode::Auto.next(), ThemeMode::Light);
+        assert_eq!(ThemeMode::Light.next(), ThemeMode::Dark);
+        assert_eq!(ThemeMode::Dark.next(), ThemeMode::Auto);
+        assert_eq!(ThemeMode::Custom.next(), ThemeMode::Auto);
+    }
+
+    #[test]
+    fn test_color_depth_parsing() {
+        let toml_str = r#"
+            [ui]
+            color_depth = "256"
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.ui.color_depth, ColorDepthMode::Ansi256);
+        assert_eq!(Config::default().ui.color_depth, ColorDepthMode::Auto);
+    }
+
+    #[test]
+    fn test_layout_parsing() {
+        let config: Config = toml::from_str("[ui]\nlayout = \"split\"\n").unwrap();
+        assert_eq!(config.ui.layout, LayoutMode::Split);
+        assert_eq!(Config::default().ui.layout, LayoutMode::Stacked);
+    }
+
+    #[test]
+    fn test_columns_parsing() {
+        let toml_str = r#"
+            [ui.columns]
+            endpoints = false
+            last-sync = true
+            age = true
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let columns = config.ui.columns;
+        assert!(columns.name && columns.stats && columns.mode);
+        assert!(!columns.endpoints);
+        assert!(columns.last_sync && columns.age);
+        assert!(!Config::default().ui.columns.last_sync);
+        assert!(!Config::default().ui.columns.age);
+    }
+
+    #[test]
+    fn test_ascii_parsing() {
+        let config: Config = toml::from_str("[ui]\nascii = true\n").unwrap();
+        assert!(config.ui.ascii);
+        assert!(!Config::default().ui.ascii);
+    }
+
     #[test]
     fn test_display_mode_parsing() {
         let toml_str = r#"
@@ -190,6 +988,154 @@ mod tests {
         assert_eq!(config.ui.default_display_mode, DisplayMode::LastRefresh);
     }
 
+    #[test]
+    fn test_sort_mode_parsing() {
+        let toml_str = r#"
+            [ui]
+            default_sort = "lastsync"
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.ui.default_sort, SortMode::LastSync);
+        assert_eq!(Config::default().ui.default_sort, SortMode::Name);
+    }
+
+    #[test]
+    fn test_notification_config_parsing() {
+        let toml_str = r#"
+            [notifications]
+            desktop = true
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(config.notifications.desktop);
+        assert_eq!(config.notifications.stall_secs, 300);
+        assert!(!Config::default().notifications.desktop);
+    }
+
+    #[test]
+    fn test_adaptive_refresh_interval() {
+        let mut refresh = RefreshConfig::default();
+        assert_eq!(refresh.interval(Activity::Busy), Duration::from_millis(500));
+        assert_eq!(refresh.interval(Activity::Waiting), Duration::from_secs(3));
+        assert_eq!(refresh.interval(Activity::Idle), Duration::from_secs(10));
+
+        refresh.adaptive = false;
+        assert_eq!(refresh.interval(Activity::Busy), Duration::from_secs(3));
+        assert_eq!(refresh.interval(Activity::Idle), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_watchdog_config_parsing() {
+        let toml_str = r#"
+            [watchdog]
+            action = "resume"
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.watchdog.action, WatchdogAction::Resume);
+        assert_eq!(config.watchdog.disconnect_secs, 120);
+        assert_eq!(Config::default().watchdog.action, WatchdogAction::None);
+        assert!(toml::from_str::<Config>("[watchdog]\naction = \"restart\"").is_err());
+    }
+
+    #[test]
+    fn test_webhook_config_parsing() {
+        let toml_str = r#"
+            [notifications.webhook]
+            url = "https://hooks.slack.com/services/T000/B000/XXX"
+            events = ["conflict", "error"]
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let webhook = config.notifications.webhook.unwrap();
+        assert_eq!(webhook.events, vec!["conflict", "error"]);
+        assert_eq!(webhook.retries, 2);
+        assert_eq!(webhook.max_per_minute, 10);
+        assert!(webhook.template.is_none());
+        assert!(Config::default().notifications.webhook.is_none());
+    }
+
+    #[test]
+    fn test_log_config_parsing() {
+        let toml_str = r#"
+            [log]
+            path = "/tmp/mutagui.log"
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.log.path, Some(PathBuf::from("/tmp/mutagui.log")));
+        assert!(Config::default().log.path.is_none());
+    }
+
+    #[test]
+    fn test_startup_config_parsing() {
+        let toml_str = r#"
+            [startup]
+            select = "web:api"
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.startup.select.as_deref(), Some("web:api"));
+        assert!(!config.startup.open_conflicts);
+    }
+
+    #[test]
+    fn test_templates_parsing() {
+        let toml_str = r#"
+            [templates.service]
+            beta = "devbox:/srv/{name}"
+            mode = "two-way-resolved"
+            ignore = ["node_modules", "target"]
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let template = &config.templates["service"];
+        assert_eq!(template.beta, "devbox:/srv/{name}");
+        assert_eq!(template.alpha, None);
+        assert_eq!(template.mode.as_deref(), Some("two-way-resolved"));
+        assert_eq!(template.ignore, vec!["node_modules", "target"]);
+    }
+
+    #[test]
+    fn test_mutagen_config_parsing() {
+        let toml_str = r#"
+            [mutagen]
+            binary_path = "/opt/mutagen/bin/mutagen"
+            extra_args = ["--verbose"]
+            environment = { MUTAGEN_DATA_DIRECTORY = "/tmp/mutagen" }
+            command_timeout_secs = 8
+            flush_timeout_secs = 1800
+            retries = 0
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.mutagen.command_timeout_secs, 8);
+        assert_eq!(config.mutagen.create_timeout_secs, 15);
+        assert_eq!(config.mutagen.flush_timeout_secs, 1800);
+        assert_eq!(config.mutagen.retries, 0);
+        let command = config.mutagen.command("mutagen");
+        assert_eq!(command.get_program(), "/opt/mutagen/bin/mutagen");
+        assert_eq!(command.get_args().collect::<Vec<_>>(), ["--verbose"]);
+        assert_eq!(
+            command.get_envs().collect::<Vec<_>>(),
+            [(
+                "MUTAGEN_DATA_DIRECTORY".as_ref(),
+                Some("/tmp/mutagen".as_ref())
+            )]
+        );
+
+        let compose = config.mutagen.command("mutagen-compose");
+        assert_eq!(compose.get_program(), "mutagen-compose");
+        assert_eq!(compose.get_args().count(), 0);
+        assert_eq!(compose.get_envs().count(), 1);
+
+        assert_eq!(
+            Config::default().mutagen.command("mutagen").get_program(),
+            "mutagen"
+        );
+    }
+
     #[test]
     fn test_project_config_defaults() {
         let config = ProjectConfig::default();
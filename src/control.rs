@@ -0,0 +1,363 @@
+//! Control socket (`--control-socket`).
+//!
+//! Editor plugins and scripts can ask a running mutagui for its projects,
+//! with each spec matched to its session, and act on a project or spec
+//! through it instead of racing it with raw mutagen commands. Each line
+//! written to the Unix socket is a JSON-RPC 2.0 request, answered with one
+//! line:
+//!
+//! ```text
+//! {"jsonrpc": "2.0", "id": 1, "method": "projects"}
+//! {"jsonrpc": "2.0", "id": 2, "method": "pause", "params": {"target": "web:assets"}}
+//! ```
+//!
+//! Requests are read on background threads and handled by the main loop
+//! between refreshes, like keys.
+
+use crate::a11y::describe_spec;
+use crate::app::App;
+use crate::project::{find_selection, SyncSpecState};
+use crate::selection::SelectableItem;
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Actions that take a `target` of `PROJECT`, `PROJECT:SPEC`, or `:SPEC`.
+const ACTIONS: [&str; 5] = ["start", "pause", "resume", "flush", "terminate"];
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// The action was refused, as with `--read-only`.
+const REFUSED: i64 = -32000;
+
+/// A request from a client, with where to send the answer.
+struct Request {
+    id: Value,
+    method: String,
+    params: Value,
+    reply: Sender<Value>,
+}
+
+/// Accepts connections on a Unix socket, and removes it when dropped.
+pub struct ControlServer {
+    path: PathBuf,
+    requests: Receiver<Request>,
+}
+
+impl ControlServer {
+    /// Listen on a Unix socket at `path`, replacing a stale one left there.
+    /// Anything at `path` that isn't a socket is left alone.
+    pub fn start(path: &Path) -> Result<Self> {
+        if let Ok(metadata) = std::fs::symlink_metadata(path) {
+            if !metadata.file_type().is_socket() {
+                anyhow::bail!("{} exists and is not a socket", path.display());
+            }
+            if UnixStream::connect(path).is_err() {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to listen on {}", path.display()))?;
+        let (tx, requests) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let tx = tx.clone();
+                std::thread::spawn(move || serve(stream, tx));
+            }
+        });
+        Ok(Self {
+            path: path.to_path_buf(),
+            requests,
+        })
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Read requests from one client until it disconnects, answering each in turn.
+fn serve(stream: UnixStream, requests: Sender<Request>) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(request) => {
+                let (reply, answer) = mpsc::channel();
+                let request = Request {
+                    id: request["id"].clone(),
+                    method: request["method"].as_str().unwrap_or_default().to_string(),
+                    params: request["params"].clone(),
+                    reply,
+                };
+                // The main loop has quit if either end is gone
+                if requests.send(request).is_err() {
+                    break;
+                }
+                match answer.recv() {
+                    Ok(response) => response,
+                    Err(_) => break,
+                }
+            }
+            Err(e) => error(Value::Null, PARSE_ERROR, &e.to_string()),
+        };
+        if writeln!(writer, "{}", response).is_err() {
+            break;
+        }
+    }
+}
+
+/// Handle the requests that have come in since the last call.
+pub async fn poll(app: &mut App) {
+    let requests: Vec<Request> = match &app.control {
+        Some(server) => server.requests.try_iter().collect(),
+        None => return,
+    };
+    for request in requests {
+        let response = match handle(app, &request.method, &request.params).await {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": request.id, "result": result}),
+            Err((code, message)) => error(request.id.clone(), code, &message),
+        };
+        let _ = request.reply.send(response);
+    }
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+}
+
+async fn handle(app: &mut App, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+    match method {
+        "projects" => Ok(projects_json(app)),
+        "refresh" => {
+            let _ = app.refresh_sessions().await;
+            Ok(projects_json(app))
+        }
+        _ if ACTIONS.contains(&method) => {
+            if app.read_only {
+                return Err((REFUSED, "mutagui is read-only".to_string()));
+            }
+            let target = params["target"]
+                .as_str()
+                .ok_or((INVALID_PARAMS, "missing target".to_string()))?;
+            let (project_idx, spec_idx) = find_selection(&app.projects, target)
+                .ok_or_else(|| (INVALID_PARAMS, format!("no project or spec {}", target)))?;
+            let message = run_action(app, method, project_idx, spec_idx).await;
+            let _ = app.refresh_sessions().await;
+            Ok(json!({ "message": message }))
+        }
+        _ => Err((METHOD_NOT_FOUND, format!("unknown method {}", method))),
+    }
+}
+
+/// Run `action` on a project or one of its specs through the same methods
+/// as the keys, then put the selection back where the user left it.
+async fn run_action(
+    app: &mut App,
+    action: &str,
+    project_idx: usize,
+    spec_idx: Option<usize>,
+) -> Option<String> {
+    let selected = app.selection.selected_item().cloned();
+    let previous_message = app.status_message.take();
+    let mut redraw = |_: &App| {};
+    match spec_idx {
+        Some(spec_idx) => {
+            app.select_spec(project_idx, spec_idx);
+            match action {
+                "start" => app.start_selected_spec().await,
+                "pause" => app.pause_selected().await,
+                "resume" => app.resume_selected().await,
+                "flush" => app.flush_selected().await,
+                _ => app.terminate_selected().await,
+            }
+        }
+        None => {
+            app.selection
+                .select_item(&SelectableItem::Project { index: project_idx });
+            match action {
+                "start" => app.start_selected_project().await,
+                "pause" => app.pause_selected_project(&mut redraw).await,
+                "resume" => app.resume_selected_project(&mut redraw).await,
+                "flush" => app.flush_selected_project(&mut redraw).await,
+                _ => app.terminate_selected_project(&mut redraw).await,
+            }
+        }
+    }
+    if let Some(item) = selected {
+        app.selection.select_item(&item);
+    }
+    let message = app.status_message.as_ref().map(|m| m.text().to_string());
+    if message.is_none() {
+        app.status_message = previous_message;
+    }
+    message
+}
+
+/// Projects with each spec's state and, if it is running, its session.
+fn projects_json(app: &App) -> Value {
+    let ssh_config = app.mutagen_client.ssh_config();
+    let projects: Vec<Value> = app
+        .projects
+        .iter()
+        .map(|project| {
+            let specs: Vec<Value> = project
+                .specs
+                .iter()
+                .map(|spec| {
+                    let state = match spec.state {
+                        SyncSpecState::NotRunning => "not_running",
+                        SyncSpecState::RunningTwoWay => "running",
+                        SyncSpecState::RunningPush => "running_push",
+                    };
+                    let session = spec.running_session.as_ref().map(|session| {
+                        json!({
+                            "identifier": session.identifier,
+                            "status": session.status,
+                            "paused": session.paused,
+                            "conflicts": session.conflict_count(),
+                            "alpha": session.alpha_display(),
+                            "beta": session.beta_display(ssh_config),
+                            "successful_cycles": session.successful_cycles,
+                        })
+                    });
+                    json!({
+                        "name": spec.name,
+                        "state": state,
+                        "description": describe_spec(spec),
+                        "session": session,
+                    })
+                })
+                .collect();
+            json!({
+                "name": project.file.display_name(),
+                "file": project.file.path,
+                "specs": specs,
+            })
+        })
+        .collect();
+    Value::Array(projects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{success_output, MockCommandRunner};
+    use std::sync::Arc;
+
+    const LIST: &str = "mutagen sync list --template {{json .}}";
+
+    /// Send `requests` from another thread while the main loop polls, and
+    /// return the responses.
+    async fn exchange(app: &mut App, path: &Path, requests: &[Value]) -> Vec<Value> {
+        let stream = UnixStream::connect(path).unwrap();
+        let lines: Vec<String> = requests.iter().map(Value::to_string).collect();
+        let client = std::thread::spawn(move || {
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            lines
+                .iter()
+                .map(|line| {
+                    writeln!(writer, "{}", line).unwrap();
+                    let mut response = String::new();
+                    reader.read_line(&mut response).unwrap();
+                    serde_json::from_str(&response).unwrap()
+                })
+                .collect()
+        });
+        while !client.is_finished() {
+            poll(app).await;
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        client.join().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_control_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_dir = dir.path().join("app");
+        std::fs::create_dir(&project_dir).unwrap();
+        std::fs::write(
+            project_dir.join("mutagen.yml"),
+            "sync:\n  api:\n    alpha: ./api\n    beta: server:/srv/api\n",
+        )
+        .unwrap();
+        let session = json!({
+            "name": "api",
+            "identifier": "sync_api",
+            "alpha": {"protocol": "local", "path": "/code/app/api", "connected": true},
+            "beta": {"protocol": "ssh", "path": "/srv/api", "host": "server", "connected": true},
+            "status": "Watching for changes",
+            "paused": false,
+        });
+        let runner = Arc::new(MockCommandRunner::new());
+        runner.expect(LIST, success_output(&json!([session]).to_string()));
+        runner.expect("mutagen sync pause sync_api", success_output(""));
+        let mut app = App::for_test(dir.path().to_path_buf(), runner, &dir.path().join("state"));
+        app.refresh_sessions().await.unwrap();
+        app.wait_for_discovery().await;
+
+        let path = dir.path().join("control.sock");
+        app.control = Some(ControlServer::start(&path).unwrap());
+        let responses = exchange(
+            &mut app,
+            &path,
+            &[
+                json!({"jsonrpc": "2.0", "id": 1, "method": "projects"}),
+                json!({"jsonrpc": "2.0", "id": 2, "method": "pause", "params": {"target": "app"}}),
+                json!({"jsonrpc": "2.0", "id": 3, "method": "pause", "params": {"target": "nope"}}),
+                json!({"jsonrpc": "2.0", "id": 4, "method": "explode"}),
+            ],
+        )
+        .await;
+
+        let spec = &responses[0]["result"][0]["specs"][0];
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(spec["name"], "api");
+        assert_eq!(spec["state"], "running");
+        assert_eq!(spec["session"]["identifier"], "sync_api");
+        assert_eq!(responses[1]["result"]["message"], "Paused 1 session(s)");
+        assert_eq!(responses[2]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(responses[3]["error"]["code"], METHOD_NOT_FOUND);
+
+        app.read_only = true;
+        let responses = exchange(
+            &mut app,
+            &path,
+            &[json!({"jsonrpc": "2.0", "id": 5, "method": "flush", "params": {"target": "app:api"}})],
+        )
+        .await;
+        assert_eq!(responses[0]["error"]["code"], REFUSED);
+
+        drop(app);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_only_replaces_sockets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("control.sock");
+        drop(UnixListener::bind(&path).unwrap());
+        let server = ControlServer::start(&path).unwrap();
+        drop(server);
+
+        let notes = dir.path().join("notes.txt");
+        std::fs::write(&notes, "keep me").unwrap();
+        let error = ControlServer::start(&notes).err().unwrap();
+        assert!(error.to_string().contains("is not a socket"));
+        assert_eq!(std::fs::read_to_string(&notes).unwrap(), "keep me");
+    }
+}
@@ -0,0 +1,143 @@
+//! Sync-cycle rates, to tell a busy session from a wedged one.
+//!
+//! Mutagen counts the cycles a session has completed. The count is noted
+//! each time it changes, and the rate over the last few minutes is derived
+//! from the change across that window.
+
+use crate::mutagen::SyncSession;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// How far back the cycle rate looks.
+pub const CYCLE_RATE_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// How long a session must be watched before its rate means anything.
+const MIN_OBSERVED: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    cycles: u64,
+    at: Instant,
+}
+
+/// Cycle counts per session identifier, with when each was first seen.
+#[derive(Debug, Default)]
+pub struct CycleTracker {
+    history: HashMap<String, VecDeque<Sample>>,
+}
+
+impl CycleTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the cycle count of every session from a refresh.
+    pub fn observe(&mut self, sessions: &[SyncSession]) {
+        self.observe_at(sessions, Instant::now());
+    }
+
+    /// Record the cycle count of some sessions, as listed by a refresh
+    /// scoped to one project, keeping the history of all others.
+    pub fn observe_subset(&mut self, sessions: &[SyncSession]) {
+        let listed: HashSet<&str> = sessions.iter().map(|s| s.identifier.as_str()).collect();
+        let (mine, others): (HashMap<_, _>, HashMap<_, _>) = std::mem::take(&mut self.history)
+            .into_iter()
+            .partition(|(id, _)| listed.contains(id.as_str()));
+        self.history = mine;
+        self.observe_at(sessions, Instant::now());
+        self.history.extend(others);
+    }
+
+    fn observe_at(&mut self, sessions: &[SyncSession], now: Instant) {
+        let mut history = HashMap::new();
+
+        for session in sessions {
+            let cycles = session.successful_cycles.unwrap_or(0);
+            let mut samples = self.history.remove(&session.identifier).unwrap_or_default();
+            match samples.back() {
+                // A lower count means the session was reset; old samples no longer apply
+                Some(last) if cycles < last.cycles => {
+                    samples.clear();
+                    samples.push_back(Sample { cycles, at: now });
+                }
+                Some(last) if cycles == last.cycles => {}
+                _ => samples.push_back(Sample { cycles, at: now }),
+            }
+            // The count at the start of the window is the last one seen before it
+            while samples
+                .get(1)
+                .is_some_and(|next| now.duration_since(next.at) >= CYCLE_RATE_WINDOW)
+            {
+                samples.pop_front();
+            }
+            history.insert(session.identifier.clone(), samples);
+        }
+
+        // Terminated sessions are dropped
+        self.history = history;
+    }
+
+    /// Cycles per minute over the last [`CYCLE_RATE_WINDOW`], once the
+    /// session has been watched for long enough.
+    pub fn rate(&self, identifier: &str) -> Option<f64> {
+        self.rate_at(identifier, Instant::now())
+    }
+
+    fn rate_at(&self, identifier: &str, now: Instant) -> Option<f64> {
+        let samples = self.history.get(identifier)?;
+        let (first, last) = (samples.front()?, samples.back()?);
+        let observed = now.duration_since(first.at).min(CYCLE_RATE_WINDOW);
+        if observed < MIN_OBSERVED {
+            return None;
+        }
+        Some((last.cycles - first.cycles) as f64 * 60.0 / observed.as_secs_f64())
+    }
+}
+
+/// Format a cycle rate: "2.5/min", or "0/min" for an idle session.
+pub fn format_cycle_rate(per_minute: f64) -> String {
+    if per_minute == 0.0 {
+        "0/min".to_string()
+    } else if per_minute < 0.1 {
+        "<0.1/min".to_string()
+    } else {
+        format!("{:.1}/min", per_minute)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(cycles: u64) -> SyncSession {
+        SyncSession::named("web").with_cycles(cycles)
+    }
+
+    #[test]
+    fn test_cycle_rate() {
+        let mut tracker = CycleTracker::new();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        tracker.observe_at(&[session(10)], start);
+        tracker.observe_at(&[session(12)], at(20));
+        assert_eq!(tracker.rate_at("sync_web", at(20)), None);
+
+        // Four cycles in two minutes
+        tracker.observe_at(&[session(14)], at(120));
+        assert_eq!(tracker.rate_at("sync_web", at(120)), Some(2.0));
+
+        // Twenty idle minutes later, the window holds no cycles
+        tracker.observe_at(&[session(14)], at(1320));
+        assert_eq!(tracker.rate_at("sync_web", at(1320)), Some(0.0));
+
+        // A reset count starts over
+        tracker.observe_at(&[session(1)], at(1330));
+        assert_eq!(tracker.rate_at("sync_web", at(1330)), None);
+
+        tracker.observe_at(&[], at(1340));
+        assert_eq!(tracker.rate_at("sync_web", at(1340)), None);
+        assert_eq!(format_cycle_rate(2.0), "2.0/min");
+        assert_eq!(format_cycle_rate(0.05), "<0.1/min");
+    }
+}
@@ -0,0 +1,50 @@
+//! Headless monitor mode (`mutagui daemon`).
+//!
+//! Runs the same refresh loop as the TUI without drawing anything. Health
+//! events go out through the notifier; warnings and errors are also written
+//! to stderr so the daemon can be followed from a service log.
+
+use crate::app::{App, StatusMessage};
+use crate::metrics::MetricsServer;
+use anyhow::Result;
+use chrono::Local;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub async fn run(
+    project_dir: Option<PathBuf>,
+    interval: Option<Duration>,
+    metrics: Option<MetricsServer>,
+) -> Result<()> {
+    let mut app = App::headless(project_dir);
+    if let Some(metrics) = metrics {
+        eprintln!(
+            "mutagui daemon: serving metrics at http://{}/metrics",
+            metrics.addr()
+        );
+        app.metrics = Some(metrics);
+    }
+    let interval = interval
+        .unwrap_or_else(|| app.refresh_interval())
+        .max(Duration::from_secs(1));
+    let mut last_reported: Option<String> = None;
+
+    eprintln!("mutagui daemon: refreshing every {}s", interval.as_secs());
+
+    loop {
+        app.refresh_sessions().await?;
+        app.wait_for_discovery().await;
+        app.poll_notifications();
+
+        // Only log problems, and only when the message changes
+        if let Some(StatusMessage::Warning(text) | StatusMessage::Error(text)) = &app.status_message
+        {
+            if last_reported.as_ref() != Some(text) {
+                eprintln!("[{}] {}", Local::now().format("%Y-%m-%d %H:%M:%S"), text);
+                last_reported = Some(text.clone());
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
@@ -0,0 +1,87 @@
+//! Free disk space on sync endpoints.
+//!
+//! A sync that fills the receiving disk halts with an error, so the space
+//! left on each endpoint is worth keeping an eye on. It is read with POSIX
+//! `df -Pk`, which behaves the same on Linux, macOS, and BusyBox containers.
+
+use crate::verify::shell_dir;
+
+/// Size and free space of the filesystem an endpoint lives on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskSpace {
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+/// Script that prints `df` output for the filesystem holding `dir`, or its
+/// parent if `dir` doesn't exist yet.
+pub fn df_script(dir: &str) -> String {
+    let dir = shell_dir(dir);
+    format!(
+        "cd {} 2>/dev/null || cd \"$(dirname {})\" || exit 1; df -Pk .",
+        dir, dir
+    )
+}
+
+/// Parse `df -Pk` output.
+///
+/// Filesystem names and mount points may contain spaces, so the numbers are
+/// found relative to the capacity column (the one ending in `%`).
+pub fn parse_df(output: &str) -> Option<DiskSpace> {
+    let line = output.lines().rev().find(|line| !line.trim().is_empty())?;
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let capacity = fields.iter().rposition(|field| field.ends_with('%'))?;
+    if capacity < 3 {
+        return None;
+    }
+    let kb = |field: &str| field.parse::<u64>().ok().map(|kb| kb * 1024);
+    Some(DiskSpace {
+        total_bytes: kb(fields[capacity - 3])?,
+        available_bytes: kb(fields[capacity - 1])?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df() {
+        let output = "\
+Filesystem     1024-blocks      Used Available Capacity Mounted on
+/dev/disk3s1s1   239362496  10234088 121049416       8% /
+";
+        assert_eq!(
+            parse_df(output),
+            Some(DiskSpace {
+                total_bytes: 239362496 * 1024,
+                available_bytes: 121049416 * 1024,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_df_with_spaces_in_names() {
+        let output = "\
+Filesystem 1024-blocks Used Available Capacity Mounted on
+//server/My Share 1000 400 600 40% /Volumes/My Share
+";
+        let space = parse_df(output).unwrap();
+        assert_eq!(space.total_bytes, 1000 * 1024);
+        assert_eq!(space.available_bytes, 600 * 1024);
+    }
+
+    #[test]
+    fn test_parse_df_rejects_garbage() {
+        assert_eq!(parse_df(""), None);
+        assert_eq!(parse_df("df: .: No such file or directory\n"), None);
+    }
+
+    #[test]
+    fn test_df_script_quotes_home() {
+        assert_eq!(
+            df_script("~/my project"),
+            "cd \"$HOME\"/'my project' 2>/dev/null || cd \"$(dirname \"$HOME\"/'my project')\" || exit 1; df -Pk ."
+        );
+    }
+}
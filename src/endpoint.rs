@@ -3,6 +3,7 @@
 //! This module provides a type-safe representation of Mutagen endpoint addresses,
 //! supporting local paths, SSH remotes, and Docker containers.
 
+use std::fmt;
 use std::path::{Path, PathBuf};
 
 /// Represents a parsed endpoint address.
@@ -11,7 +12,7 @@ use std::path::{Path, PathBuf};
 /// - Local paths: `/path/to/dir`, `./relative`, `~/home`
 /// - SSH shorthand: `host:/path`, `user@host:/path`
 /// - SSH URL: `ssh://[user@]host[:port]/path`
-/// - Docker: `docker://container/path`
+/// - Docker: `docker://[user@]container/path`
 /// - IPv6: `[::1]:/path`, `[2001:db8::1]:/path`
 #[derive(Debug, Clone, PartialEq)]
 pub enum EndpointAddress {
@@ -25,7 +26,21 @@ pub enum EndpointAddress {
         path: PathBuf,
     },
     /// A Docker container endpoint
-    Docker { container: String, path: PathBuf },
+    Docker {
+        user: Option<String>,
+        container: String,
+        path: PathBuf,
+    },
+}
+
+/// Whether `s` starts with a Windows drive: a single letter followed by `:`
+/// and then `\` or `/`.
+pub fn is_drive_path(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(
+        (chars.next(), chars.next(), chars.next()),
+        (Some(drive), Some(':'), Some('\\' | '/')) if drive.is_ascii_alphabetic()
+    )
 }
 
 impl EndpointAddress {
@@ -35,7 +50,7 @@ impl EndpointAddress {
     /// - Local: `/path`, `./relative`, `~/home`, `C:\windows` (Windows)
     /// - SSH shorthand: `host:/path`, `user@host:/path`
     /// - SSH URL: `ssh://[user@]host[:port]/path`
-    /// - Docker: `docker://container/path`
+    /// - Docker: `docker://[user@]container/path`
     /// - IPv6: `[::1]:/path`, `[2001:db8::1]:/path`
     pub fn parse(s: &str) -> Self {
         // 1. Check for URL schemes
@@ -63,13 +78,7 @@ impl EndpointAddress {
         }
 
         // 3. Check for Windows drive letters: C:\path or C:/path
-        // A single letter followed by : and then \ or / is a Windows path
-        let chars: Vec<char> = s.chars().collect();
-        if chars.len() >= 3
-            && chars[0].is_ascii_alphabetic()
-            && chars[1] == ':'
-            && (chars[2] == '\\' || chars[2] == '/')
-        {
+        if is_drive_path(s) {
             return EndpointAddress::Local(PathBuf::from(s));
         }
 
@@ -77,8 +86,12 @@ impl EndpointAddress {
         // Look for ':' that's followed by '/' (to distinguish from Windows paths)
         if let Some(colon_pos) = s.find(':') {
             let after_colon = &s[colon_pos + 1..];
-            // SSH paths typically start with / or ~ after the colon
-            if after_colon.starts_with('/') || after_colon.starts_with('~') {
+            // SSH paths typically start with / or ~ after the colon, or are
+            // Windows paths on a Windows host: host:C:\path
+            if after_colon.starts_with('/')
+                || after_colon.starts_with('~')
+                || is_drive_path(after_colon)
+            {
                 let host_part = &s[..colon_pos];
                 let path = &s[colon_pos + 1..];
 
@@ -143,17 +156,19 @@ impl EndpointAddress {
 
     /// Parse a Docker URL (without the docker:// prefix)
     fn parse_docker_url(s: &str) -> Self {
-        // Format: container/path
-        if let Some(slash_pos) = s.find('/') {
-            EndpointAddress::Docker {
-                container: s[..slash_pos].to_string(),
-                path: PathBuf::from(&s[slash_pos..]),
-            }
-        } else {
-            EndpointAddress::Docker {
-                container: s.to_string(),
-                path: PathBuf::from("/"),
-            }
+        // Format: [user@]container/path
+        let (user_container, path) = match s.find('/') {
+            Some(slash_pos) => (&s[..slash_pos], &s[slash_pos..]),
+            None => (s, "/"),
+        };
+        let (user, container) = match user_container.split_once('@') {
+            Some((user, container)) => (Some(user.to_string()), container),
+            None => (None, user_container),
+        };
+        EndpointAddress::Docker {
+            user,
+            container: container.to_string(),
+            path: PathBuf::from(path),
         }
     }
 
@@ -193,6 +208,176 @@ impl EndpointAddress {
             EndpointAddress::Docker { path, .. } => path,
         }
     }
+
+    /// The same endpoint at another path.
+    pub fn with_path(self, path: PathBuf) -> Self {
+        match self {
+            EndpointAddress::Local(_) => EndpointAddress::Local(path),
+            EndpointAddress::Ssh {
+                user, host, port, ..
+            } => EndpointAddress::Ssh {
+                user,
+                host,
+                port,
+                path,
+            },
+            EndpointAddress::Docker {
+                user, container, ..
+            } => EndpointAddress::Docker {
+                user,
+                container,
+                path,
+            },
+        }
+    }
+}
+
+/// Formats the endpoint the way `parse` reads it back.
+impl fmt::Display for EndpointAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let user = |user: &Option<String>| match user {
+            Some(user) => format!("{}@", user),
+            None => String::new(),
+        };
+        match self {
+            EndpointAddress::Local(path) => write!(f, "{}", path.display()),
+            EndpointAddress::Ssh {
+                user: u,
+                host,
+                port: Some(port),
+                path,
+            } => {
+                let slash = if path.starts_with("/") { "" } else { "/" };
+                write!(
+                    f,
+                    "ssh://{}{}:{}{}{}",
+                    user(u),
+                    host,
+                    port,
+                    slash,
+                    path.display()
+                )
+            }
+            EndpointAddress::Ssh {
+                user: u,
+                host,
+                path,
+                ..
+            } if host.contains(':') => write!(f, "{}[{}]:{}", user(u), host, path.display()),
+            EndpointAddress::Ssh {
+                user: u,
+                host,
+                path,
+                ..
+            } => write!(f, "{}{}:{}", user(u), host, path.display()),
+            EndpointAddress::Docker {
+                user: u,
+                container,
+                path,
+            } => {
+                let slash = if path.starts_with("/") { "" } else { "/" };
+                write!(
+                    f,
+                    "docker://{}{}{}{}",
+                    user(u),
+                    container,
+                    slash,
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+/// Which Docker daemon a container endpoint is reached through.
+///
+/// Mutagen records `DOCKER_HOST`, `DOCKER_CONTEXT`, and the TLS variables when
+/// it creates a session with a Docker endpoint, and keeps using that daemon
+/// afterwards. Commands run in the container have to go to the same one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DockerConnection {
+    pub host: Option<String>,
+    pub context: Option<String>,
+    pub tls_verify: bool,
+    pub cert_path: Option<PathBuf>,
+}
+
+impl DockerConnection {
+    /// The connection described by the `DOCKER_*` variables `lookup` returns.
+    pub fn from_environment(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let value = |name: &str| lookup(name).filter(|v| !v.is_empty());
+        DockerConnection {
+            host: value("DOCKER_HOST"),
+            context: value("DOCKER_CONTEXT"),
+            // docker treats any non-empty value as set, even "0"
+            tls_verify: value("DOCKER_TLS_VERIFY").is_some(),
+            cert_path: value("DOCKER_CERT_PATH").map(PathBuf::from),
+        }
+    }
+
+    /// Global `docker` options that select this connection. A host wins over
+    /// a context, as it does for docker itself.
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(host) = &self.host {
+            args.extend(["--host".to_string(), host.clone()]);
+        } else if let Some(context) = &self.context {
+            args.extend(["--context".to_string(), context.clone()]);
+        }
+        if self.tls_verify {
+            args.push("--tlsverify".to_string());
+        }
+        if let Some(cert_path) = &self.cert_path {
+            for (option, file) in [
+                ("--tlscacert", "ca.pem"),
+                ("--tlscert", "cert.pem"),
+                ("--tlskey", "key.pem"),
+            ] {
+                args.push(option.to_string());
+                args.push(cert_path.join(file).to_string_lossy().into_owned());
+            }
+        }
+        args
+    }
+
+    /// Arguments to `docker` that run `script` in `container`, as `user` if
+    /// one is given.
+    pub fn exec_args(&self, user: Option<&str>, container: &str, script: &str) -> Vec<String> {
+        self.exec(&[], user, container, script)
+    }
+
+    /// Like `exec_args`, but attached to the terminal, for an interactive
+    /// shell.
+    pub fn interactive_exec_args(
+        &self,
+        user: Option<&str>,
+        container: &str,
+        script: &str,
+    ) -> Vec<String> {
+        self.exec(&["-it"], user, container, script)
+    }
+
+    fn exec(
+        &self,
+        flags: &[&str],
+        user: Option<&str>,
+        container: &str,
+        script: &str,
+    ) -> Vec<String> {
+        let mut args = self.args();
+        args.push("exec".to_string());
+        args.extend(flags.iter().map(|flag| flag.to_string()));
+        if let Some(user) = user {
+            args.extend(["--user".to_string(), user.to_string()]);
+        }
+        args.extend([
+            container.to_string(),
+            "sh".to_string(),
+            "-c".to_string(),
+            script.to_string(),
+        ]);
+        args
+    }
 }
 
 #[cfg(test)]
@@ -239,6 +424,20 @@ mod tests {
         assert_eq!(ep, EndpointAddress::Local(PathBuf::from("D:/Projects/app")));
     }
 
+    #[test]
+    fn test_parse_ssh_shorthand_windows_host() {
+        let ep = EndpointAddress::parse("me@winbox:C:\\Users\\me\\app");
+        assert_eq!(
+            ep,
+            EndpointAddress::Ssh {
+                user: Some("me".to_string()),
+                host: "winbox".to_string(),
+                port: None,
+                path: PathBuf::from("C:\\Users\\me\\app"),
+            }
+        );
+    }
+
     // SSH shorthand tests
     #[test]
     fn test_parse_ssh_shorthand_simple() {
@@ -375,18 +574,107 @@ mod tests {
         assert_eq!(
             ep,
             EndpointAddress::Docker {
+                user: None,
                 container: "mycontainer".to_string(),
                 path: PathBuf::from("/app/data"),
             }
         );
     }
 
+    #[test]
+    fn test_display_round_trips() {
+        for endpoint in [
+            "/home/user/project",
+            "~/code",
+            "server:/srv/api",
+            "deploy@server:~/api",
+            "ssh://deploy@server:2222/srv/api",
+            "[::1]:/srv/api",
+            "docker://www-data@web/var/www",
+        ] {
+            let parsed = EndpointAddress::parse(endpoint);
+            assert_eq!(parsed.to_string(), endpoint);
+            assert_eq!(EndpointAddress::parse(&parsed.to_string()), parsed);
+        }
+        let moved = EndpointAddress::parse("server:/srv/api").with_path(PathBuf::from("/srv"));
+        assert_eq!(moved.to_string(), "server:/srv");
+    }
+
+    #[test]
+    fn test_docker_connection_args() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            DockerConnection::from_environment(move |name| {
+                vars.iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, v)| v.to_string())
+            })
+        };
+
+        let local = env(&[]);
+        assert_eq!(local, DockerConnection::default());
+        assert_eq!(
+            local.exec_args(None, "web", "ls"),
+            ["exec", "web", "sh", "-c", "ls"]
+        );
+
+        let context = env(&[("DOCKER_CONTEXT", "staging"), ("DOCKER_HOST", "")]);
+        assert_eq!(
+            context.exec_args(Some("www-data"), "web", "ls"),
+            [
+                "--context",
+                "staging",
+                "exec",
+                "--user",
+                "www-data",
+                "web",
+                "sh",
+                "-c",
+                "ls"
+            ]
+        );
+
+        let remote = env(&[
+            ("DOCKER_HOST", "tcp://build:2376"),
+            ("DOCKER_CONTEXT", "staging"),
+            ("DOCKER_TLS_VERIFY", "1"),
+            ("DOCKER_CERT_PATH", "/certs"),
+        ]);
+        assert_eq!(
+            remote.args(),
+            [
+                "--host",
+                "tcp://build:2376",
+                "--tlsverify",
+                "--tlscacert",
+                "/certs/ca.pem",
+                "--tlscert",
+                "/certs/cert.pem",
+                "--tlskey",
+                "/certs/key.pem",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_docker_url_with_user() {
+        let ep = EndpointAddress::parse("docker://www-data@web/var/www");
+        assert_eq!(
+            ep,
+            EndpointAddress::Docker {
+                user: Some("www-data".to_string()),
+                container: "web".to_string(),
+                path: PathBuf::from("/var/www"),
+            }
+        );
+    }
+
     #[test]
     fn test_parse_docker_url_no_path() {
         let ep = EndpointAddress::parse("docker://mycontainer");
         assert_eq!(
             ep,
             EndpointAddress::Docker {
+                user: None,
                 container: "mycontainer".to_string(),
                 path: PathBuf::from("/"),
             }
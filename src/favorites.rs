@@ -0,0 +1,100 @@
+//! Projects marked as favorites, which sort to the top of the list.
+//!
+//! `F` marks or unmarks the selected project, and `A` switches between all
+//! projects and favorites only. Favorites are saved to `favorites.json` in
+//! the state directory, by project file path, so they survive restarts.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Favorite project files and the file they are saved in.
+#[derive(Debug, Clone, Default)]
+pub struct Favorites {
+    path: Option<PathBuf>,
+    projects: BTreeSet<PathBuf>,
+}
+
+impl Favorites {
+    /// Load favorites from the state directory. A missing or unreadable
+    /// file gives no favorites.
+    pub fn load() -> Self {
+        let path = dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("mutagui").join("favorites.json"));
+        Self::load_path(path)
+    }
+
+    /// Load favorites from a specific file.
+    #[cfg(test)]
+    pub fn at(path: &Path) -> Self {
+        Self::load_path(Some(path.to_path_buf()))
+    }
+
+    fn load_path(path: Option<PathBuf>) -> Self {
+        let projects = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| {
+                serde_json::from_str(&contents)
+                    .inspect_err(|e| tracing::warn!(error = %e, "ignoring unreadable favorites"))
+                    .ok()
+            })
+            .unwrap_or_default();
+        Self { path, projects }
+    }
+
+    /// Whether the project file at `project` is a favorite.
+    pub fn contains(&self, project: &Path) -> bool {
+        self.projects.contains(project)
+    }
+
+    /// Mark `project` as a favorite, or unmark it if it is one, and save.
+    /// Returns whether it is a favorite now.
+    pub fn toggle(&mut self, project: &Path) -> Result<bool> {
+        let favorite = !self.projects.remove(project);
+        if favorite {
+            self.projects.insert(project.to_path_buf());
+        }
+        self.save()?;
+        Ok(favorite)
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = self
+            .path
+            .as_ref()
+            .context("No state directory for favorites")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&self.projects)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("state").join("favorites.json");
+        let web = Path::new("/code/web/mutagen.yml");
+        let api = Path::new("/code/api/mutagen.yml");
+
+        let mut favorites = Favorites::at(&file);
+        assert!(favorites.toggle(web).unwrap());
+        assert!(favorites.toggle(api).unwrap());
+        assert!(!favorites.toggle(web).unwrap());
+
+        let reloaded = Favorites::at(&file);
+        assert!(!reloaded.contains(web));
+        assert!(reloaded.contains(api));
+
+        std::fs::write(&file, "not json").unwrap();
+        assert!(!Favorites::at(&file).contains(api));
+    }
+}
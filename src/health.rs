@@ -0,0 +1,433 @@
+//! Session health monitoring.
+//!
+//! `HealthMonitor` compares each refresh with the previous one and reports
+//! the transitions worth interrupting someone for: new conflicts, endpoints
+//! losing their connection, and sessions stuck in a transitional state.
+//! Conditions already present when a session is first seen are taken as the
+//! baseline and not reported.
+//!
+//! It also keeps a watchdog's eye on each session: one that is busy without
+//! completing a sync cycle for the stall threshold is stalled, and one with
+//! an endpoint disconnected for the offline threshold is offline. Both are
+//! reported once and stay flagged until the session recovers.
+
+use crate::i18n::t;
+use crate::mutagen::SyncSession;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Default time a session may stay in one transitional state before it is stalled.
+pub const DEFAULT_STALL_SECS: u64 = 300;
+
+/// Kind of health problem detected on a session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthEventKind {
+    /// The number of conflicts went up to this count.
+    Conflicts(usize),
+    /// An endpoint ("alpha" or "beta") lost its connection.
+    Disconnected(&'static str),
+    /// The session has been busy without completing a sync cycle for longer
+    /// than the stall threshold, and now shows this status.
+    Stalled(String),
+    /// An endpoint ("alpha" or "beta") has been disconnected for longer than
+    /// the offline threshold.
+    Offline(&'static str),
+    /// Mutagen halted the session because of an error.
+    Halted,
+}
+
+impl HealthEventKind {
+    /// Stable name used to select events in config.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Conflicts(_) => "conflict",
+            Self::Disconnected(_) => "disconnect",
+            Self::Stalled(_) => "stall",
+            Self::Offline(_) => "offline",
+            Self::Halted => "error",
+        }
+    }
+
+    /// Whether the watchdog flags the session for this, and may resume or
+    /// reset it.
+    pub fn is_watchdog(&self) -> bool {
+        matches!(self, Self::Stalled(_) | Self::Offline(_))
+    }
+}
+
+/// A health problem on one session.
+#[derive(Debug, Clone)]
+pub struct HealthEvent {
+    pub session_name: String,
+    /// The session's endpoints, for hooks.
+    pub alpha: String,
+    pub beta: String,
+    pub kind: HealthEventKind,
+}
+
+impl HealthEvent {
+    /// Short title for notifications.
+    pub fn title(&self) -> String {
+        let what = match &self.kind {
+            HealthEventKind::Conflicts(_) => "conflict",
+            HealthEventKind::Disconnected(_) => "disconnected",
+            HealthEventKind::Stalled(_) => "stalled",
+            HealthEventKind::Offline(_) => "offline",
+            HealthEventKind::Halted => "halted",
+        };
+        format!("mutagui: {} {}", self.session_name, what)
+    }
+
+    /// One-line description for notifications and status messages.
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            HealthEventKind::Conflicts(count) => format!(
+                "{} has {} conflict{}",
+                self.session_name,
+                count,
+                if *count == 1 { "" } else { "s" }
+            ),
+            HealthEventKind::Disconnected(side) => {
+                format!("{}: {} endpoint disconnected", self.session_name, side)
+            }
+            HealthEventKind::Stalled(status) => {
+                format!("{} stuck in \"{}\"", self.session_name, status)
+            }
+            HealthEventKind::Offline(side) => {
+                format!(
+                    "{}: {} endpoint still disconnected",
+                    self.session_name, side
+                )
+            }
+            HealthEventKind::Halted => format!("{} halted with an error", self.session_name),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SessionHealth {
+    conflicts: usize,
+    alpha_connected: bool,
+    beta_connected: bool,
+    status: String,
+    since: Instant,
+    cycles: Option<u64>,
+    /// When the session last completed a cycle or was idle
+    progress_since: Instant,
+    stall_reported: bool,
+    /// When an endpoint was first seen disconnected, while one still is
+    disconnected_since: Option<Instant>,
+    offline_reported: bool,
+}
+
+/// Why the watchdog has flagged a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogFlag {
+    Stalled,
+    Offline,
+}
+
+impl WatchdogFlag {
+    /// Short label for the session's row.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Stalled => t!("view.stalled"),
+            Self::Offline => t!("view.offline"),
+        }
+    }
+}
+
+/// Tracks per-session state across refreshes to detect health transitions.
+#[derive(Debug)]
+pub struct HealthMonitor {
+    stall_after: Duration,
+    offline_after: Option<Duration>,
+    sessions: HashMap<String, SessionHealth>,
+}
+
+impl HealthMonitor {
+    pub fn new(stall_after: Duration) -> Self {
+        Self {
+            stall_after,
+            offline_after: None,
+            sessions: HashMap::new(),
+        }
+    }
+
+    /// Report sessions with an endpoint disconnected for `after` as offline.
+    pub fn offline_after(mut self, after: Duration) -> Self {
+        self.offline_after = Some(after);
+        self
+    }
+
+    /// Record a refresh and return newly detected problems.
+    pub fn check(&mut self, sessions: &[SyncSession]) -> Vec<HealthEvent> {
+        self.check_at(sessions, Instant::now())
+    }
+
+    fn check_at(&mut self, sessions: &[SyncSession], now: Instant) -> Vec<HealthEvent> {
+        let mut events = Vec::new();
+        let mut next = HashMap::new();
+
+        for session in sessions {
+            let event = |kind| HealthEvent {
+                session_name: session.name.clone(),
+                alpha: session.alpha.display_path(),
+                beta: session.beta.display_path(),
+                kind,
+            };
+            let conflicts = session.conflict_count();
+
+            let mut health = match self.sessions.remove(&session.identifier) {
+                None => SessionHealth {
+                    conflicts,
+                    alpha_connected: session.alpha.connected,
+                    beta_connected: session.beta.connected,
+                    status: session.status.clone(),
+                    since: now,
+                    cycles: session.successful_cycles,
+                    progress_since: now,
+                    stall_reported: false,
+                    disconnected_since: None,
+                    offline_reported: false,
+                },
+                Some(mut previous) => {
+                    if conflicts > previous.conflicts {
+                        events.push(event(HealthEventKind::Conflicts(conflicts)));
+                    }
+                    // Paused sessions disconnect on purpose
+                    if !session.paused {
+                        if previous.alpha_connected && !session.alpha.connected {
+                            events.push(event(HealthEventKind::Disconnected("alpha")));
+                        }
+                        if previous.beta_connected && !session.beta.connected {
+                            events.push(event(HealthEventKind::Disconnected("beta")));
+                        }
+                    }
+                    let halted = |status: &str| status.to_lowercase().contains("halt");
+                    if halted(&session.status) && !halted(&previous.status) {
+                        events.push(event(HealthEventKind::Halted));
+                    }
+                    if previous.status != session.status {
+                        previous.status = session.status.clone();
+                        previous.since = now;
+                    }
+                    previous.conflicts = conflicts;
+                    previous.alpha_connected = session.alpha.connected;
+                    previous.beta_connected = session.beta.connected;
+                    previous
+                }
+            };
+
+            // A busy session makes progress by completing cycles, even if
+            // its status flips between scanning and staging on the way
+            let transitional =
+                !session.paused && !matches!(session.status_text(), "Watching" | "Halted");
+            if !transitional || session.successful_cycles > health.cycles {
+                health.progress_since = now;
+                health.stall_reported = false;
+            }
+            health.cycles = session.successful_cycles;
+            if transitional
+                && !health.stall_reported
+                && now.duration_since(health.progress_since) >= self.stall_after
+            {
+                events.push(event(HealthEventKind::Stalled(
+                    session.status_text().to_string(),
+                )));
+                health.stall_reported = true;
+            }
+
+            let disconnected = match (session.alpha.connected, session.beta.connected) {
+                _ if session.paused => None,
+                (false, _) => Some("alpha"),
+                (_, false) => Some("beta"),
+                _ => None,
+            };
+            match disconnected {
+                None => {
+                    health.disconnected_since = None;
+                    health.offline_reported = false;
+                }
+                Some(side) => {
+                    let since = *health.disconnected_since.get_or_insert(now);
+                    if let Some(after) = self.offline_after {
+                        if !health.offline_reported && now.duration_since(since) >= after {
+                            events.push(event(HealthEventKind::Offline(side)));
+                            health.offline_reported = true;
+                        }
+                    }
+                }
+            }
+
+            next.insert(session.identifier.clone(), health);
+        }
+
+        self.sessions = next;
+        events
+    }
+
+    /// How long a session has shown its current status (since first observed).
+    pub fn status_elapsed(&self, identifier: &str) -> Option<Duration> {
+        self.sessions
+            .get(identifier)
+            .map(|health| health.since.elapsed())
+    }
+
+    /// Whether the watchdog has flagged a session, and why.
+    pub fn flag(&self, identifier: &str) -> Option<WatchdogFlag> {
+        let health = self.sessions.get(identifier)?;
+        if health.offline_reported {
+            Some(WatchdogFlag::Offline)
+        } else if health.stall_reported {
+            Some(WatchdogFlag::Stalled)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(DEFAULT_STALL_SECS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(status: &str, beta_connected: bool, conflicts: usize) -> SyncSession {
+        SyncSession::named("web")
+            .with_status(status)
+            .with_beta_connected(beta_connected)
+            .with_conflicts(&["file0", "file1", "file2"][..conflicts])
+    }
+
+    #[test]
+    fn test_first_observation_is_baseline() {
+        let mut monitor = HealthMonitor::default();
+        let events = monitor.check(&[session("Connecting to beta", false, 3)]);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_new_conflicts_and_disconnect() {
+        let mut monitor = HealthMonitor::default();
+        monitor.check(&[session("Watching for changes", true, 0)]);
+
+        let events = monitor.check(&[session("Watching for changes", false, 2)]);
+        let kinds: Vec<_> = events.iter().map(|e| e.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                HealthEventKind::Conflicts(2),
+                HealthEventKind::Disconnected("beta")
+            ]
+        );
+
+        // Unchanged state does not repeat the events
+        assert!(monitor
+            .check(&[session("Watching for changes", false, 2)])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_stall_reported_once_per_state() {
+        let mut monitor = HealthMonitor::new(Duration::from_secs(60));
+        let start = Instant::now();
+
+        monitor.check_at(&[session("Staging files on beta", true, 0)], start);
+        let later = start + Duration::from_secs(61);
+        let events = monitor.check_at(&[session("Staging files on beta", true, 0)], later);
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].kind,
+            HealthEventKind::Stalled("Staging".to_string())
+        );
+        assert_eq!(events[0].describe(), "web stuck in \"Staging\"");
+
+        let even_later = later + Duration::from_secs(120);
+        assert!(monitor
+            .check_at(&[session("Staging files on beta", true, 0)], even_later)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_stall_needs_cycles_to_clear() {
+        let mut monitor = HealthMonitor::new(Duration::from_secs(60));
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let busy = |status: &str, cycles| session(status, true, 0).with_cycles(cycles);
+
+        monitor.check_at(&[busy("Scanning files", 4)], start);
+        // Flipping between states without finishing a cycle isn't progress
+        assert!(monitor
+            .check_at(&[busy("Staging files on beta", 4)], at(40))
+            .is_empty());
+        let events = monitor.check_at(&[busy("Scanning files", 4)], at(70));
+        assert_eq!(
+            events[0].kind,
+            HealthEventKind::Stalled("Scanning".to_string())
+        );
+        assert_eq!(monitor.flag("sync_web"), Some(WatchdogFlag::Stalled));
+
+        // A finished cycle clears the flag and restarts the clock
+        assert!(monitor
+            .check_at(&[busy("Scanning files", 5)], at(80))
+            .is_empty());
+        assert_eq!(monitor.flag("sync_web"), None);
+        assert!(monitor
+            .check_at(&[busy("Scanning files", 5)], at(130))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_offline_after_threshold() {
+        let mut monitor = HealthMonitor::default().offline_after(Duration::from_secs(120));
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        monitor.check_at(&[session("Watching for changes", true, 0)], start);
+        monitor.check_at(&[session("Connecting to beta", false, 0)], at(10));
+        assert!(monitor
+            .check_at(&[session("Connecting to beta", false, 0)], at(100))
+            .is_empty());
+        let events = monitor.check_at(&[session("Connecting to beta", false, 0)], at(130));
+        assert_eq!(events[0].kind, HealthEventKind::Offline("beta"));
+        assert_eq!(
+            events[0].describe(),
+            "web: beta endpoint still disconnected"
+        );
+        assert_eq!(monitor.flag("sync_web"), Some(WatchdogFlag::Offline));
+        assert!(monitor
+            .check_at(&[session("Connecting to beta", false, 0)], at(250))
+            .is_empty());
+
+        monitor.check_at(&[session("Watching for changes", true, 0)], at(310));
+        assert_eq!(monitor.flag("sync_web"), None);
+    }
+
+    #[test]
+    fn test_halted_reported_on_transition() {
+        let mut monitor = HealthMonitor::default();
+        monitor.check(&[session("Watching for changes", true, 0)]);
+
+        let events = monitor.check(&[session("Halted on root emptied", true, 0)]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, HealthEventKind::Halted);
+        assert_eq!(events[0].kind.key(), "error");
+    }
+
+    #[test]
+    fn test_watching_never_stalls() {
+        let mut monitor = HealthMonitor::new(Duration::from_secs(1));
+        let start = Instant::now();
+        monitor.check_at(&[session("Watching for changes", true, 0)], start);
+        let events = monitor.check_at(
+            &[session("Watching for changes", true, 0)],
+            start + Duration::from_secs(10),
+        );
+        assert!(events.is_empty());
+    }
+}
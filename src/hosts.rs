@@ -0,0 +1,146 @@
+//! Specs grouped by the machine their remote endpoints are on.
+//!
+//! Endpoints usually name hosts by their `~/.ssh/config` alias, and two
+//! aliases can lead to the same machine. The hosts view resolves each alias
+//! to the hostname and port ssh actually connects to, so everything syncing
+//! with one machine is listed together, whichever project it's in.
+
+use crate::endpoint::EndpointAddress;
+use crate::project::Project;
+use crate::ssh_config::SshConfig;
+
+/// The specs whose endpoints are on one host.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostGroup {
+    /// Where ssh connects, from `SshConfig::target`, or None for specs with
+    /// no ssh endpoint.
+    pub target: Option<String>,
+    /// Names the endpoints use for the host where they differ from the
+    /// target, such as ssh config aliases.
+    pub aliases: Vec<String>,
+    /// Specs as (project index, spec index), in project order.
+    pub specs: Vec<(usize, usize)>,
+}
+
+/// The host an endpoint is on, as the target ssh connects to (see
+/// `SshConfig::target`), or None for an endpoint that isn't reached over ssh.
+pub fn host_key(endpoint: &EndpointAddress, ssh_config: &SshConfig) -> Option<String> {
+    match endpoint {
+        EndpointAddress::Ssh { host, port, .. } => Some(ssh_config.target(host, *port)),
+        _ => None,
+    }
+}
+
+/// Group the specs of `projects` by the host of their ssh endpoints. A spec
+/// with endpoints on two hosts is in both groups. Groups are sorted by
+/// target, with the specs that have no ssh endpoint last.
+pub fn group_by_host(projects: &[Project], ssh_config: &SshConfig) -> Vec<HostGroup> {
+    let mut groups: Vec<HostGroup> = Vec::new();
+    let mut local = Vec::new();
+    for (project_idx, project) in projects.iter().enumerate() {
+        for (spec_idx, spec) in project.specs.iter().enumerate() {
+            let Some(definition) = project.file.sessions.get(&spec.name) else {
+                continue;
+            };
+            let mut remote = false;
+            for endpoint in [&definition.alpha, &definition.beta] {
+                let address = EndpointAddress::parse(endpoint);
+                let Some(target) = host_key(&address, ssh_config) else {
+                    continue;
+                };
+                remote = true;
+                let index = match groups
+                    .iter()
+                    .position(|g| g.target.as_deref() == Some(target.as_str()))
+                {
+                    Some(index) => index,
+                    None => {
+                        groups.push(HostGroup {
+                            target: Some(target.clone()),
+                            aliases: Vec::new(),
+                            specs: Vec::new(),
+                        });
+                        groups.len() - 1
+                    }
+                };
+                let group = &mut groups[index];
+                if let EndpointAddress::Ssh { host, .. } = address {
+                    if host != target && !group.aliases.contains(&host) {
+                        group.aliases.push(host);
+                    }
+                }
+                if !group.specs.contains(&(project_idx, spec_idx)) {
+                    group.specs.push((project_idx, spec_idx));
+                }
+            }
+            if !remote {
+                local.push((project_idx, spec_idx));
+            }
+        }
+    }
+    groups.sort_by(|a, b| a.target.cmp(&b.target));
+    if !local.is_empty() {
+        groups.push(HostGroup {
+            target: None,
+            aliases: Vec::new(),
+            specs: local,
+        });
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::{correlate_projects_with_sessions, ProjectFile};
+
+    fn project(name: &str, specs: &[(&str, &str)]) -> ProjectFile {
+        let mut yaml = String::from("sync:\n");
+        for (spec, beta) in specs {
+            yaml.push_str(&format!(
+                "  {}:\n    alpha: ./{}\n    beta: {}\n",
+                spec, spec, beta
+            ));
+        }
+        ProjectFile::from_contents(format!("/code/{}/mutagen.yml", name).into(), &yaml).unwrap()
+    }
+
+    #[test]
+    fn test_group_by_host() {
+        let ssh_config = SshConfig::parse(
+            "Host dev devbox\n    HostName devbox.example.com\n\nHost build\n    Port 2222\n",
+        );
+        let projects = correlate_projects_with_sessions(
+            vec![
+                project("api", &[("code", "dev:/srv/api"), ("cache", "/tmp/cache")]),
+                project(
+                    "web",
+                    &[
+                        ("assets", "devbox.example.com:/srv/web"),
+                        ("site", "ssh://ci@build/srv/web"),
+                        ("logs", "devbox:/var/log"),
+                    ],
+                ),
+            ],
+            &[],
+        );
+        let names = |group: &HostGroup| -> Vec<String> {
+            group
+                .specs
+                .iter()
+                .map(|&(p, s)| projects[p].specs[s].name.clone())
+                .collect()
+        };
+
+        let groups = group_by_host(&projects, &ssh_config);
+        let targets: Vec<_> = groups.iter().map(|g| g.target.as_deref()).collect();
+        assert_eq!(
+            targets,
+            [Some("build:2222"), Some("devbox.example.com"), None]
+        );
+        assert_eq!(groups[0].aliases, ["build"]);
+        assert_eq!(groups[1].aliases, ["dev", "devbox"]);
+        assert_eq!(names(&groups[1]), ["code", "assets", "logs"]);
+        assert_eq!(names(&groups[2]), ["cache"]);
+    }
+}
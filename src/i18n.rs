@@ -0,0 +1,283 @@
+//! Translated UI text.
+//!
+//! Status messages, overlay titles, and the key help are looked up by key
+//! in a message catalog, so translating the interface means writing a TOML
+//! file rather than touching the code that draws it. Catalogs live in
+//! `locales/`; English is built in and fills in any key a translation
+//! lacks. A catalog for a locale that isn't built in is read from
+//! `locales/<locale>.toml` in the config directory.
+//!
+//! Messages name their arguments in braces, e.g. `Paused spec: {name}`.
+//! A message that counts something has a form for one, `conflicts_one`, and
+//! for any other count, `conflicts_other`; `tn!` picks between them.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use crate::config::Config;
+
+/// Catalogs compiled into the binary.
+const BUILT_IN: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.toml")),
+    ("de", include_str!("../locales/de.toml")),
+];
+
+static CATALOG: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Look up the message `key`, with `name = value` arguments filled in.
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::text($key)
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::format(
+            $key,
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+        )
+    };
+}
+pub(crate) use t;
+
+/// Look up the form of the message `key` for `count` things, with
+/// `{count}` and any `name = value` arguments filled in.
+macro_rules! tn {
+    ($key:expr, $count:expr $(, $name:ident = $value:expr)* $(,)?) => {{
+        let count = $count;
+        $crate::i18n::plural(
+            $key,
+            count as u64,
+            &[
+                ("count", &count as &dyn std::fmt::Display),
+                $((stringify!($name), &$value as &dyn std::fmt::Display)),*
+            ],
+        )
+    }};
+}
+pub(crate) use tn;
+
+/// Use the catalog for `locale`, or for the locale in the environment if
+/// none is configured. Falls back to English, logging why, if there's no
+/// catalog for it. Only the first call has an effect.
+pub fn init(locale: Option<&str>) {
+    let locale = locale
+        .map(str::to_string)
+        .or_else(environment_locale)
+        .unwrap_or_else(|| "en".to_string());
+    let catalog = load(&locale).unwrap_or_else(|e| {
+        tracing::warn!(locale, error = %e, "using English messages");
+        english()
+    });
+    let _ = CATALOG.set(catalog);
+}
+
+/// The language of `LC_ALL`, `LC_MESSAGES`, or `LANG`, e.g. `de` for
+/// `de_DE.UTF-8`.
+fn environment_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map(|value| {
+            value
+                .split(['_', '.', '@'])
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .filter(|language| !language.is_empty() && language != "C" && language != "POSIX")
+}
+
+/// English, overlaid with the catalog for `locale`.
+fn load(locale: &str) -> Result<HashMap<String, String>> {
+    let mut catalog = english();
+    if locale == "en" {
+        return Ok(catalog);
+    }
+    let translation = match BUILT_IN.iter().find(|(name, _)| *name == locale) {
+        Some((_, contents)) => parse(contents)?,
+        None => {
+            let path = Config::locales_dir()
+                .map(|dir| dir.join(format!("{}.toml", locale)))
+                .with_context(|| format!("No config directory for locale {}", locale))?;
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("No catalog for locale {}", locale))?;
+            parse(&contents).with_context(|| format!("Invalid catalog {}", path.display()))?
+        }
+    };
+    catalog.extend(translation);
+    Ok(catalog)
+}
+
+fn english() -> HashMap<String, String> {
+    parse(BUILT_IN[0].1).expect("the English catalog parses")
+}
+
+/// Flatten a catalog's tables into dotted keys: `title` under `[status]`
+/// becomes `status.title`.
+fn parse(contents: &str) -> Result<HashMap<String, String>> {
+    fn flatten(prefix: &str, table: toml::Table, messages: &mut HashMap<String, String>) {
+        for (key, value) in table {
+            let key = if prefix.is_empty() {
+                key
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                toml::Value::Table(table) => flatten(&key, table, messages),
+                toml::Value::String(text) => {
+                    messages.insert(key, text);
+                }
+                other => tracing::warn!(key, value = %other, "ignoring non-text message"),
+            }
+        }
+    }
+    let mut messages = HashMap::new();
+    flatten("", toml::from_str(contents)?, &mut messages);
+    Ok(messages)
+}
+
+fn lookup(key: &str) -> Option<&'static str> {
+    let catalog = CATALOG.get_or_init(english);
+    catalog.get(key).map(String::as_str)
+}
+
+/// The message `key`. An unknown key is shown as itself, so a mistake is
+/// visible rather than blank.
+pub fn text(key: &'static str) -> &'static str {
+    lookup(key).unwrap_or(key)
+}
+
+/// The message `key` with each `{name}` replaced by its value.
+pub fn format(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    fill(text(key), args)
+}
+
+/// The `_one` or `_other` form of the message `key` for `count`, filled in
+/// like `format`. English and German both use the singular for exactly one.
+pub fn plural(key: &'static str, count: u64, args: &[(&str, &dyn Display)]) -> String {
+    let form = format!("{}_{}", key, if count == 1 { "one" } else { "other" });
+    match lookup(&form) {
+        Some(message) => fill(message, args),
+        None => form,
+    }
+}
+
+fn fill(message: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut message = message.to_string();
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// The `{name}` arguments a message takes.
+    fn arguments(message: &str) -> BTreeSet<&str> {
+        message
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    /// Every Rust source file but this one, with its contents.
+    fn sources() -> Vec<(String, String)> {
+        fn walk(dir: &std::path::Path, files: &mut Vec<(String, String)>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    walk(&path, files);
+                } else if path.extension().is_some_and(|ext| ext == "rs")
+                    && !path.ends_with("i18n.rs")
+                {
+                    let contents = std::fs::read_to_string(&path).unwrap();
+                    files.push((path.display().to_string(), contents));
+                }
+            }
+        }
+        let mut files = Vec::new();
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        walk(&src, &mut files);
+        files
+    }
+
+    #[test]
+    fn test_every_key_used_resolves() {
+        let english = english();
+        for (file, contents) in sources() {
+            for (macro_call, forms) in [("t!(\"", &[""][..]), ("tn!(\"", &["_one", "_other"])] {
+                for (start, call) in contents.match_indices(macro_call) {
+                    // Not the end of another macro's name, like `format!`
+                    if contents[..start].ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+                        continue;
+                    }
+                    let rest = &contents[start + call.len()..];
+                    let key = rest.split('"').next().unwrap();
+                    for form in forms {
+                        let key = format!("{}{}", key, form);
+                        assert!(
+                            english.contains_key(&key),
+                            "{} uses unknown key {}",
+                            file,
+                            key
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_key_is_used() {
+        let sources: String = sources().into_iter().map(|(_, contents)| contents).collect();
+        for key in english().keys() {
+            let key = key
+                .strip_suffix("_one")
+                .or_else(|| key.strip_suffix("_other"))
+                .unwrap_or(key);
+            assert!(sources.contains(&format!("\"{}\"", key)), "{} is unused", key);
+        }
+    }
+
+    #[test]
+    fn test_translations_are_complete() {
+        let english = english();
+        for (locale, contents) in &BUILT_IN[1..] {
+            let translation = parse(contents).unwrap();
+            for (key, message) in &english {
+                let translated = translation
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{} is missing {}", locale, key));
+                assert_eq!(
+                    arguments(translated),
+                    arguments(message),
+                    "{} has different arguments for {}",
+                    locale,
+                    key
+                );
+            }
+            let extra: Vec<_> = translation.keys().filter(|k| !english.contains_key(*k)).collect();
+            assert!(extra.is_empty(), "{} has unknown keys {:?}", locale, extra);
+        }
+    }
+
+    #[test]
+    fn test_format_and_fallback() {
+        let catalog = load("de").unwrap();
+        assert_eq!(catalog["tab.sync"], "Sync");
+        assert_eq!(catalog["hint.quit"], "Beenden");
+        assert!(load("xx-unknown").is_err());
+
+        assert_eq!(t!("status.paused_spec", name = "api"), "Paused spec: api");
+        assert_eq!(t!("no.such.key"), "no.such.key");
+        assert_eq!(tn!("view.conflicts", 1, more = ""), "1 conflict");
+        assert_eq!(tn!("view.conflicts", 3, more = ""), "3 conflicts");
+        assert_eq!(tn!("no.such.key", 2), "no.such.key_other");
+    }
+}
@@ -0,0 +1,111 @@
+//! Glyphs used in the interface, and plain ASCII stand-ins for them.
+//!
+//! Some terminals (certain ssh + screen setups, fonts without the symbols)
+//! draw the Unicode icons as garbage or at the wrong width, which shifts
+//! every column after them. With `ui.ascii` or `--ascii` each glyph is
+//! replaced by ASCII text.
+
+use std::borrow::Cow;
+
+/// Each glyph the UI draws, with its ASCII replacement.
+const ASCII: &[(&str, &str)] = &[
+    // Folding, selection, and run state
+    ("▶", ">"),
+    ("▼", "v"),
+    ("⏸", "="),
+    ("○", "o"),
+    ("✓", "+"),
+    ("✗", "x"),
+    ("⚠", "!"),
+    ("⚡", "*"),
+    ("★", "*"),
+    // Sync direction
+    ("⇄", "<>"),
+    ("⬆", "^"),
+    ("→", "->"),
+    // Endpoint state
+    ("⊗", "X"),
+    ("⟳", "~"),
+    // Session status
+    ("👁", "W"),
+    ("🔍", "S"),
+    ("📦", "T"),
+    ("⚖", "R"),
+    ("💾", "V"),
+    ("🔌", "C"),
+    ("⏳", "~"),
+    ("⛔", "E"),
+    ("🛑", "H"),
+    ("•", "*"),
+    // Verification
+    ("≠", "!="),
+    ("−", "-"),
+    // Progress bars
+    ("█", "#"),
+    ("░", "."),
+    // Punctuation and keys
+    ("·", "-"),
+    ("│", "|"),
+    ("…", "..."),
+    ("↑", "Up"),
+    ("↓", "Dn"),
+    ("↵", "Enter"),
+];
+
+/// Chooses between the Unicode glyphs and their ASCII stand-ins.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Icons {
+    ascii: bool,
+}
+
+impl Icons {
+    pub fn new(ascii: bool) -> Self {
+        Self { ascii }
+    }
+
+    /// The glyph to draw for `glyph`.
+    pub fn get(self, glyph: &'static str) -> &'static str {
+        if !self.ascii {
+            return glyph;
+        }
+        ASCII
+            .iter()
+            .find(|(unicode, _)| *unicode == glyph)
+            .map_or(glyph, |(_, ascii)| ascii)
+    }
+
+    /// `text` with every glyph replaced, for labels that embed them.
+    pub fn text(self, text: &str) -> Cow<'_, str> {
+        if !self.ascii || text.is_ascii() {
+            return Cow::Borrowed(text);
+        }
+        let mut out = text.to_string();
+        for (unicode, ascii) in ASCII {
+            if out.contains(unicode) {
+                out = out.replace(unicode, ascii);
+            }
+        }
+        Cow::Owned(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode_passes_through() {
+        let icons = Icons::new(false);
+        assert_eq!(icons.get("⚠"), "⚠");
+        assert_eq!(icons.text("↑/↓ Nav"), "↑/↓ Nav");
+    }
+
+    #[test]
+    fn test_ascii_replacements() {
+        let icons = Icons::new(true);
+        assert_eq!(icons.get("⏸"), "=");
+        assert_eq!(icons.get("👁"), "W");
+        assert_eq!(icons.text("⚠ 2 conflicts · ↑/↓"), "! 2 conflicts - Up/Dn");
+        assert!(ASCII.iter().all(|(_, ascii)| ascii.is_ascii()));
+    }
+}
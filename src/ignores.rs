@@ -0,0 +1,181 @@
+//! Editing a spec's ignores without leaving mutagui.
+//!
+//! `i` lists the patterns the selected spec's session ignores: those from the
+//! project file's defaults, which are shown but changed only in the file,
+//! then the spec's own. Patterns can be added and removed and VCS
+//! directories turned on or off. Mutagen can't change the ignores of a
+//! running session, so applying the changes recreates it, and saves them to
+//! the project file if asked to.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::input::TextInput;
+use crate::project::SessionDefinition;
+use crate::spec_edit::{ignore_paths, ignores_vcs};
+
+/// State of the ignores overlay.
+#[derive(Debug, Clone)]
+pub struct IgnoreEditor {
+    pub path: PathBuf,
+    pub spec_name: String,
+    /// The spec's definition as it was when the overlay opened.
+    pub definition: SessionDefinition,
+    defaults: Option<serde_yaml::Value>,
+    pub vcs: bool,
+    pub paths: Vec<String>,
+    /// Index of the highlighted pattern in `paths`.
+    pub selected: usize,
+    /// A pattern being typed, until Enter adds it.
+    pub adding: Option<TextInput>,
+    /// Whether applying also writes the changes to the project file.
+    pub save_to_file: bool,
+    /// Why the last attempt to apply failed.
+    pub error: Option<String>,
+}
+
+impl IgnoreEditor {
+    /// The overlay for the spec `name` from the project file at `path`.
+    pub fn new(
+        path: PathBuf,
+        name: &str,
+        definition: &SessionDefinition,
+        defaults: Option<&HashMap<String, serde_yaml::Value>>,
+    ) -> Self {
+        Self {
+            path,
+            spec_name: name.to_string(),
+            definition: definition.clone(),
+            defaults: defaults.and_then(|defaults| serde_yaml::to_value(defaults).ok()),
+            vcs: ignores_vcs(definition),
+            paths: ignore_paths(definition),
+            selected: 0,
+            adding: None,
+            save_to_file: false,
+            error: None,
+        }
+    }
+
+    /// The patterns that come from the project file's defaults.
+    pub fn default_patterns(&self) -> Vec<String> {
+        let definition = SessionDefinition {
+            ignore: None,
+            ..self.definition.clone()
+        };
+        definition.get_ignore_patterns(self.defaults.as_ref())
+    }
+
+    /// Every pattern the session is given, defaults first, as when the spec
+    /// is started.
+    pub fn effective_patterns(&self) -> Vec<String> {
+        let mut ignore = serde_yaml::Mapping::new();
+        if self.vcs {
+            ignore.insert("vcs".into(), true.into());
+        }
+        let paths = self.paths.iter().map(|p| p.as_str().into()).collect();
+        ignore.insert("paths".into(), serde_yaml::Value::Sequence(paths));
+        let definition = SessionDefinition {
+            ignore: Some(serde_yaml::Value::Mapping(ignore)),
+            ..self.definition.clone()
+        };
+        definition.get_ignore_patterns(self.defaults.as_ref())
+    }
+
+    /// Whether anything differs from the spec's definition.
+    pub fn is_changed(&self) -> bool {
+        self.vcs != ignores_vcs(&self.definition) || self.paths != ignore_paths(&self.definition)
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.paths.is_empty() {
+            return;
+        }
+        let len = self.paths.len() as isize;
+        self.selected = (self.selected as isize + delta).rem_euclid(len) as usize;
+    }
+
+    pub fn start_adding(&mut self) {
+        self.adding = Some(TextInput::default());
+    }
+
+    /// Add the pattern being typed, unless it is blank or already there.
+    pub fn finish_adding(&mut self) {
+        let Some(input) = self.adding.take() else {
+            return;
+        };
+        let pattern = input.value().trim();
+        if pattern.is_empty() {
+            return;
+        }
+        match self.paths.iter().position(|p| p == pattern) {
+            Some(index) => self.selected = index,
+            None => {
+                self.paths.push(pattern.to_string());
+                self.selected = self.paths.len() - 1;
+            }
+        }
+    }
+
+    pub fn remove_selected(&mut self) {
+        if self.selected < self.paths.len() {
+            self.paths.remove(self.selected);
+            self.selected = self.selected.min(self.paths.len().saturating_sub(1));
+        }
+    }
+
+    pub fn toggle_vcs(&mut self) {
+        self.vcs = !self.vcs;
+    }
+
+    pub fn toggle_save_to_file(&mut self) {
+        self.save_to_file = !self.save_to_file;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::ProjectFile;
+
+    #[test]
+    fn test_edit_ignores() {
+        let project = ProjectFile::from_contents(
+            PathBuf::from("mutagen.yml"),
+            "sync:\n  defaults:\n    ignore:\n      paths: [.cache]\n  \
+             api:\n    alpha: ./api\n    beta: server:/srv/api\n    \
+             ignore:\n      paths: [node_modules]\n",
+        )
+        .unwrap();
+        let mut editor = IgnoreEditor::new(
+            project.path.clone(),
+            "api",
+            &project.sessions["api"],
+            project.defaults.as_ref(),
+        );
+        assert_eq!(editor.default_patterns(), vec![".cache"]);
+        assert_eq!(editor.effective_patterns(), vec![".cache", "node_modules"]);
+        assert!(!editor.is_changed());
+
+        editor.start_adding();
+        editor.adding.as_mut().unwrap().set(" *.log ");
+        editor.finish_adding();
+        editor.toggle_vcs();
+        assert_eq!(editor.paths, vec!["node_modules", "*.log"]);
+        assert_eq!(editor.selected, 1);
+        assert_eq!(editor.effective_patterns()[1], ".git");
+        assert!(editor
+            .effective_patterns()
+            .ends_with(&["node_modules".into(), "*.log".into()]));
+
+        // Adding one that's there just selects it
+        editor.start_adding();
+        editor.adding.as_mut().unwrap().set("node_modules");
+        editor.finish_adding();
+        assert_eq!(editor.selected, 0);
+
+        editor.remove_selected();
+        editor.toggle_vcs();
+        assert_eq!(editor.paths, vec!["*.log"]);
+        assert!(editor.is_changed());
+    }
+}
@@ -0,0 +1,149 @@
+//! First-run setup (`mutagui init`).
+//!
+//! With no config file and no project files there is nothing to show, so
+//! mutagui offers to write a starter `config.toml`, its settings at their
+//! defaults with the common ones explained, and an example `mutagen.yml`
+//! whose endpoints only need to be filled in. Files that already exist are
+//! left as they are.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+/// The starter config file: the settings most people change first, at their
+/// defaults, with the rest left to the README.
+pub const STARTER_CONFIG: &str = r#"# mutagui settings. Every setting is optional; these are the defaults.
+# See https://github.com/osteele/mutagui#configuration-files for the rest.
+
+[ui]
+theme = "auto"            # or "light", "dark"
+
+[refresh]
+interval_secs = 3         # how often the session list is refreshed
+
+[projects]
+# Directories searched for mutagen.yml files, besides the current one
+search_paths = []
+max_depth = 1             # how deep below each directory to look
+
+[mutagen]
+command_timeout_secs = 5
+"#;
+
+/// The example project file.
+pub const EXAMPLE_PROJECT: &str = r#"# An example Mutagen project, written by mutagui init.
+# Point beta at a host you can ssh to, then select the spec in mutagui and
+# press s to start it. https://mutagen.io/documentation/orchestration/projects
+sync:
+  defaults:
+    mode: two-way-safe
+    ignore:
+      vcs: true
+  example:
+    alpha: .
+    beta: user@example.com:~/example
+    ignore:
+      paths:
+        - node_modules
+        - target
+"#;
+
+/// The files setup writes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setup {
+    pub config: PathBuf,
+    pub project: PathBuf,
+}
+
+impl Setup {
+    /// Setup writing the config file to its usual place and the project file
+    /// to `dir`, or the current directory.
+    pub fn new(dir: Option<&Path>) -> Option<Self> {
+        let dir = match dir {
+            Some(dir) => dir.to_path_buf(),
+            None => std::env::current_dir().ok()?,
+        };
+        Some(Self {
+            config: Config::config_path()?,
+            project: dir.join("mutagen.yml"),
+        })
+    }
+
+    /// Setup to offer when starting: only if there's no config file yet.
+    pub fn offer(dir: Option<&Path>) -> Option<Self> {
+        Self::new(dir).filter(|setup| !setup.config.exists())
+    }
+
+    /// Write the files that don't exist yet. Returns those written.
+    pub fn write(&self) -> Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+        for (path, contents) in [
+            (&self.config, STARTER_CONFIG),
+            (&self.project, EXAMPLE_PROJECT),
+        ] {
+            if path.exists() {
+                continue;
+            }
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)
+                    .with_context(|| format!("Failed to create {}", dir.display()))?;
+            }
+            std::fs::write(path, contents)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            written.push(path.clone());
+        }
+        Ok(written)
+    }
+}
+
+/// `mutagui init`: write the starter files and say what was done.
+pub fn run(project_dir: Option<PathBuf>) -> Result<()> {
+    let setup = Setup::new(project_dir.as_deref()).context("No config directory")?;
+    let written = setup.write()?;
+    for path in [&setup.config, &setup.project] {
+        if written.contains(path) {
+            println!("Wrote {}", path.display());
+        } else {
+            println!("{} already exists, left as it is", path.display());
+        }
+    }
+    if written.contains(&setup.project) {
+        println!(
+            "Edit the endpoints in {}, then run mutagui",
+            setup.project.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::ProjectFile;
+
+    #[test]
+    fn test_starter_files() {
+        let config: Config = toml::from_str(STARTER_CONFIG).unwrap();
+        let defaults = Config::default();
+        assert_eq!(config.refresh.interval_secs, defaults.refresh.interval_secs);
+        assert_eq!(config.projects.max_depth, defaults.projects.max_depth);
+
+        let dir = tempfile::tempdir().unwrap();
+        let setup = Setup {
+            config: dir.path().join("config/mutagui/config.toml"),
+            project: dir.path().join("mutagen.yml"),
+        };
+        std::fs::write(&setup.project, "sync: {}\n").unwrap();
+        assert_eq!(setup.write().unwrap(), vec![setup.config.clone()]);
+        assert_eq!(
+            std::fs::read_to_string(&setup.project).unwrap(),
+            "sync: {}\n"
+        );
+
+        std::fs::remove_file(&setup.project).unwrap();
+        assert_eq!(setup.write().unwrap(), vec![setup.project.clone()]);
+        let project = ProjectFile::from_path(setup.project.clone()).unwrap();
+        assert_eq!(project.sessions["example"].alpha, ".");
+    }
+}
@@ -0,0 +1,212 @@
+//! Single-line text input for forms.
+//!
+//! Holds the text and a cursor and applies the usual editing keys. Drawing
+//! is left to the form that owns the input, since each lays out its fields
+//! differently.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::Path;
+
+use crate::endpoint::EndpointAddress;
+use crate::project::expand_tilde_in_path;
+
+/// Editable text with a cursor, kept on a character boundary.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInput {
+    value: String,
+    /// Byte offset of the cursor in `value`.
+    cursor: usize,
+}
+
+impl TextInput {
+    /// An input holding `value`, with the cursor at the end.
+    pub fn new(value: impl Into<String>) -> Self {
+        let value = value.into();
+        let cursor = value.len();
+        Self { value, cursor }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The text before and after the cursor.
+    pub fn split(&self) -> (&str, &str) {
+        self.value.split_at(self.cursor)
+    }
+
+    /// Replace the text, moving the cursor to the end.
+    pub fn set(&mut self, value: impl Into<String>) {
+        *self = Self::new(value);
+    }
+
+    /// Apply an editing key. Returns false for keys that don't edit text,
+    /// which the form handles itself.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('u') if ctrl => {
+                self.value.replace_range(..self.cursor, "");
+                self.cursor = 0;
+            }
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.value.len(),
+            KeyCode::Char(_) if ctrl => return false,
+            KeyCode::Char(c) => {
+                self.value.insert(self.cursor, c);
+                self.cursor += c.len_utf8();
+            }
+            KeyCode::Backspace => {
+                if let Some(c) = self.value[..self.cursor].chars().next_back() {
+                    self.cursor -= c.len_utf8();
+                    self.value.remove(self.cursor);
+                }
+            }
+            KeyCode::Delete => {
+                if self.cursor < self.value.len() {
+                    self.value.remove(self.cursor);
+                }
+            }
+            KeyCode::Left => {
+                if let Some(c) = self.value[..self.cursor].chars().next_back() {
+                    self.cursor -= c.len_utf8();
+                }
+            }
+            KeyCode::Right => {
+                if let Some(c) = self.value[self.cursor..].chars().next() {
+                    self.cursor += c.len_utf8();
+                }
+            }
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.value.len(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Complete the text as a local path, as a shell does on Tab. Returns
+    /// false if it isn't a local path or nothing new matched.
+    pub fn complete_path(&mut self) -> bool {
+        if !matches!(
+            EndpointAddress::parse(&self.value),
+            EndpointAddress::Local(_)
+        ) {
+            return false;
+        }
+        match complete_path(&self.value) {
+            Some(completed) if completed != self.value => {
+                self.set(completed);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Extend `text` to the longest prefix shared by every directory entry that
+/// starts with it, adding a `/` after a lone directory. `~` is expanded for
+/// the lookup but kept in the result. Hidden entries are only offered once
+/// the name being completed starts with a dot.
+pub fn complete_path(text: &str) -> Option<String> {
+    let (dir, prefix) = match text.rfind('/') {
+        Some(slash) => text.split_at(slash + 1),
+        None => ("", text),
+    };
+    let lookup = if dir.is_empty() {
+        Path::new(".").to_path_buf()
+    } else {
+        expand_tilde_in_path(Path::new(dir))
+    };
+
+    let mut matches: Vec<(String, bool)> = std::fs::read_dir(lookup)
+        .ok()?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name().into_string().ok()?;
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            // Follows symlinks, so a link to a directory completes like one
+            let is_dir = entry.path().is_dir();
+            Some((name, is_dir))
+        })
+        .collect();
+    matches.sort();
+
+    let (first, first_is_dir) = matches.first()?;
+    let common = matches[1..]
+        .iter()
+        .fold(first.as_str(), |common, (name, _)| {
+            let shared = common
+                .char_indices()
+                .zip(name.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(common.len().min(name.len()), |((i, _), _)| i);
+            &common[..shared]
+        });
+
+    let mut completed = format!("{}{}", dir, common);
+    if matches.len() == 1 && *first_is_dir {
+        completed.push('/');
+    }
+    Some(completed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_editing_keys() {
+        let mut input = TextInput::new("héllo");
+        input.handle_key(&key(KeyCode::Left));
+        input.handle_key(&key(KeyCode::Left));
+        input.handle_key(&key(KeyCode::Left));
+        input.handle_key(&key(KeyCode::Left));
+        input.handle_key(&key(KeyCode::Backspace));
+        assert_eq!(input.value(), "éllo");
+        input.handle_key(&key(KeyCode::Char('y')));
+        input.handle_key(&key(KeyCode::Delete));
+        assert_eq!(input.split(), ("y", "llo"));
+        input.handle_key(&KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        assert_eq!(input.split(), ("", "llo"));
+        assert!(!input.handle_key(&key(KeyCode::Enter)));
+    }
+
+    #[test]
+    fn test_complete_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("photos")).unwrap();
+        std::fs::create_dir(dir.path().join("projects")).unwrap();
+        std::fs::write(dir.path().join("projection.txt"), "").unwrap();
+        std::fs::write(dir.path().join(".private"), "").unwrap();
+        let base = format!("{}/", dir.path().display());
+
+        // Shared prefix of several matches
+        assert_eq!(
+            complete_path(&format!("{}pr", base)),
+            Some(format!("{}project", base))
+        );
+        // A lone directory gets a trailing slash
+        assert_eq!(
+            complete_path(&format!("{}ph", base)),
+            Some(format!("{}photos/", base))
+        );
+        assert_eq!(complete_path(&format!("{}x", base)), None);
+        assert_eq!(
+            complete_path(&format!("{}.p", base)),
+            Some(format!("{}.private", base))
+        );
+    }
+
+    #[test]
+    fn test_remote_endpoints_are_not_completed() {
+        let mut input = TextInput::new("server:/srv/da");
+        assert!(!input.complete_path());
+        assert_eq!(input.value(), "server:/srv/da");
+    }
+}
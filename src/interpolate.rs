@@ -0,0 +1,104 @@
+//! Environment variables in project files and search paths.
+//!
+//! An endpoint like `${HOME}/code/api` or `deploy@${DEV_HOST}:/srv/api` lets
+//! one `mutagen.yml` be shared between machines. `$VAR` and `${VAR}` are
+//! replaced when the file is loaded, and `$$` stands for a literal `$`. A
+//! variable that isn't set is left as written, so the problem shows up in the
+//! path rather than as an empty string, and the project checks point it out.
+
+/// `text` with the environment variables in it replaced by their values.
+pub fn expand(text: &str) -> String {
+    expand_with(text, |name| std::env::var(name).ok()).0
+}
+
+/// The variables `text` refers to that aren't set, in order.
+pub fn unset_variables(text: &str) -> Vec<String> {
+    expand_with(text, |name| std::env::var(name).ok()).1
+}
+
+/// `text` with variables replaced by what `lookup` returns for them, and the
+/// names `lookup` had nothing for.
+fn expand_with(text: &str, lookup: impl Fn(&str) -> Option<String>) -> (String, Vec<String>) {
+    let mut expanded = String::with_capacity(text.len());
+    let mut unset = Vec::new();
+    let mut rest = text;
+    while let Some(dollar) = rest.find('$') {
+        expanded.push_str(&rest[..dollar]);
+        let variable = &rest[dollar..];
+        let after = &variable[1..];
+        let (name, written, remaining) = if let Some(escaped) = after.strip_prefix('$') {
+            expanded.push('$');
+            rest = escaped;
+            continue;
+        } else if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) if is_name(&braced[..end]) => {
+                    (&braced[..end], &variable[..end + 3], &braced[end + 1..])
+                }
+                _ => ("", "$", after),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            if is_name(&after[..end]) {
+                (&after[..end], &variable[..end + 1], &after[end..])
+            } else {
+                ("", "$", after)
+            }
+        };
+        if name.is_empty() {
+            expanded.push('$');
+        } else {
+            match lookup(name) {
+                Some(value) => expanded.push_str(&value),
+                None => {
+                    expanded.push_str(written);
+                    if !unset.iter().any(|n| n == name) {
+                        unset.push(name.to_string());
+                    }
+                }
+            }
+        }
+        rest = remaining;
+    }
+    expanded.push_str(rest);
+    (expanded, unset)
+}
+
+/// Whether `name` can be a variable name: a letter or `_`, then letters,
+/// digits, and `_`.
+fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/jane".to_string()),
+            "DEV_HOST" => Some("box7".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expand() {
+        let expand = |text: &str| expand_with(text, lookup);
+        assert_eq!(expand("${HOME}/code").0, "/home/jane/code");
+        assert_eq!(expand("deploy@$DEV_HOST:/srv").0, "deploy@box7:/srv");
+        assert_eq!(expand("$HOME$DEV_HOST").0, "/home/janebox7");
+        assert_eq!(expand("cost$$5 $ ${} ${1X} $9").0, "cost$5 $ ${} ${1X} $9");
+        assert_eq!(expand("plain").0, "plain");
+
+        let (text, unset) = expand("${NOPE}/$NOPE/$ALSO_NOPE");
+        assert_eq!(text, "${NOPE}/$NOPE/$ALSO_NOPE");
+        assert_eq!(unset, ["NOPE", "ALSO_NOPE"]);
+    }
+}
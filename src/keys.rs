@@ -5,16 +5,25 @@
 
 use anyhow::Result;
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent, KeyModifiers},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::Backend, Terminal};
+use std::collections::VecDeque;
 use std::io;
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
 
-use crate::app::{App, BlockingOperation, StatusMessage};
+use crate::app::{App, BlockingOperation, CancellationToken, StatusMessage, RESET_CONFIRMATION};
+use crate::i18n::t;
+use crate::tabs::Tab;
 use crate::ui;
+use crate::verify::{VerifyMode, DEFAULT_SAMPLE_SIZE};
+use crate::wizard::WizardField;
 
 /// Result of handling a key event.
 pub enum KeyAction {
@@ -24,6 +33,211 @@ pub enum KeyAction {
     Quit,
     /// Refresh sessions after the action
     Refresh,
+    /// Refresh only the selected project
+    RefreshSelected,
+}
+
+/// Key reference shown by `?`, by section, as catalog keys for the section
+/// titles and descriptions.
+const KEY_HELP: &[(&str, &[(&str, &str)])] = &[
+    (
+        "help.section.navigation",
+        &[
+            ("↑ / k", "help.move_up"),
+            ("↓ / j", "help.move_down"),
+            ("g / Home", "help.first_row"),
+            ("G / End", "help.last_row"),
+            ("Ctrl+d / Ctrl+u", "help.half_page"),
+            ("5j, 5k, 5G", "help.count"),
+            ("] / [", "help.next_problem"),
+            ("h / ←", "help.fold"),
+            ("l / → / Enter", "help.fold"),
+            ("- / +", "help.fold_all"),
+            ("1-9", "help.jump"),
+            ("Shift+1-9", "help.jump_repeat"),
+            ("Tab / Shift+Tab", "help.switch_view"),
+        ],
+    ),
+    (
+        "help.section.project",
+        &[
+            ("e", "help.edit_file"),
+            ("s", "help.start_all"),
+            ("t", "help.terminate_all"),
+            ("f", "help.flush_all"),
+            ("p", "help.push_all"),
+            ("Space", "help.pause_all"),
+            ("u", "help.resume_all"),
+            ("X", "help.run_command"),
+            ("S", "help.check_hosts"),
+            ("b then 1-9", "help.bookmark"),
+            ("F", "help.favorite"),
+            ("A", "help.favorites_only"),
+        ],
+    ),
+    (
+        "help.section.spec",
+        &[
+            ("s", "help.start"),
+            ("t", "help.terminate"),
+            ("f", "help.flush"),
+            ("p", "help.push"),
+            ("Space", "help.pause"),
+            ("u", "help.resume"),
+            ("E", "help.edit_spec"),
+            ("N", "help.duplicate_spec"),
+            ("i", "help.ignores"),
+            ("D then y", "help.delete_spec"),
+            ("c", "help.conflicts"),
+            ("H", "help.history"),
+            ("v", "help.verify_sample"),
+            ("V", "help.verify_full"),
+            ("S", "help.check_hosts"),
+            ("B", "help.browse"),
+            ("O", "help.open_alpha"),
+            ("Y", "help.copy_alpha"),
+            ("y", "help.copy_menu"),
+            ("W", "help.beta_shell"),
+            ("F12", "help.json"),
+        ],
+    ),
+    (
+        "help.section.general",
+        &[
+            ("r", "help.refresh"),
+            ("R", "help.refresh_all"),
+            ("m", "help.display_mode"),
+            ("o", "help.sort"),
+            ("M", "help.grouping"),
+            ("d", "help.layout"),
+            ("T", "help.theme"),
+            ("x", "help.status_detail"),
+            ("I", "help.project_issues"),
+            ("C", "help.settings"),
+            ("Ctrl-R", "help.reload_config"),
+            ("a", "help.audit_log"),
+            ("L", "help.copy_commands"),
+            ("Z", "help.dry_run"),
+            ("Esc", "help.cancel"),
+            ("n", "help.new_session"),
+            ("?", "help.help"),
+            ("q / Ctrl-C", "help.quit"),
+        ],
+    ),
+    (
+        "help.section.form",
+        &[
+            ("↑ / ↓", "help.form_fields"),
+            ("Tab", "help.form_complete"),
+            ("← / →", "help.form_choose"),
+            ("Enter", "help.form_save"),
+            ("Esc", "help.form_cancel"),
+        ],
+    ),
+    (
+        "help.section.popups",
+        &[
+            ("j / k, ↑ / ↓", "help.scroll_line"),
+            ("PgUp / PgDn", "help.scroll_page"),
+            ("g / G", "help.scroll_ends"),
+            ("y", "help.copy"),
+            ("w", "help.export"),
+            ("Esc", "help.close"),
+        ],
+    ),
+];
+
+/// The key reference as plain text with aligned columns.
+pub fn help_text() -> String {
+    let key_width = KEY_HELP
+        .iter()
+        .flat_map(|(_, keys)| keys.iter())
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0);
+    let sections: Vec<String> = KEY_HELP
+        .iter()
+        .map(|(section, keys)| {
+            let mut lines = vec![t!(section).to_string()];
+            lines.extend(keys.iter().map(|(key, action)| {
+                let pad = key_width - key.chars().count();
+                format!("  {}{}  {}", key, " ".repeat(pad), t!(action))
+            }));
+            lines.join("\n")
+        })
+        .collect();
+    sections.join("\n\n")
+}
+
+/// Background reader that cancels the current blocking operation on Esc.
+///
+/// The main loop isn't polling input while an operation is awaited, so this
+/// thread owns the terminal input until it is dropped. Other keys go to
+/// `typeahead`, for the main loop to handle afterwards.
+struct EscapeWatcher {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl EscapeWatcher {
+    fn spawn(token: CancellationToken, typeahead: Arc<Mutex<VecDeque<KeyEvent>>>) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+        let handle = std::thread::spawn(move || {
+            while !stop_flag.load(Ordering::SeqCst) {
+                match event::poll(Duration::from_millis(50)) {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    // The input is gone, so there's no Esc left to wait for
+                    Err(_) => break,
+                }
+                match event::read() {
+                    Ok(Event::Key(key)) if key.code == KeyCode::Esc => token.cancel(),
+                    Ok(Event::Key(key)) => typeahead
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .push_back(key),
+                    Ok(_) => {}
+                    Err(_) => break,
+                }
+            }
+        });
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for EscapeWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Show the blocking modal and start watching for Esc.
+///
+/// Cancellation stays armed until the returned watcher is dropped.
+fn begin_blocking_op<B: Backend>(
+    app: &mut App,
+    terminal: &mut Terminal<B>,
+    message: impl Into<String>,
+) -> Result<EscapeWatcher> {
+    let op = BlockingOperation::new(message);
+    let watcher = EscapeWatcher::spawn(op.cancel.clone(), app.typeahead.clone());
+    app.blocking_op = Some(op);
+    terminal.draw(|f| ui::draw(f, app))?;
+    Ok(watcher)
+}
+
+/// Redraw callback used to show progress while a blocking operation runs.
+fn redraw<B: Backend>(terminal: &mut Terminal<B>) -> impl FnMut(&App) + '_ {
+    move |app| {
+        let _ = terminal.draw(|f| ui::draw(f, app));
+    }
 }
 
 /// Get the configured editor from environment variables.
@@ -131,12 +345,174 @@ pub async fn handle_key_event<B: Backend>(
         return Ok(KeyAction::Quit);
     }
 
+    if app.text_popup.is_some() {
+        handle_text_popup_key(key, app);
+        return Ok(KeyAction::Continue);
+    }
+
+    if app.task_menu.is_some() {
+        handle_task_menu_key(key, app);
+        return Ok(KeyAction::Continue);
+    }
+
+    if app.yank_menu.is_some() {
+        handle_yank_menu_key(key, app);
+        return Ok(KeyAction::Continue);
+    }
+
+    if app.browser.is_some() {
+        handle_browser_key(key, app).await;
+        return Ok(KeyAction::Continue);
+    }
+
+    if app.pending_reset.is_some() {
+        return Ok(handle_reset_key(key, app).await);
+    }
+
+    if app.wizard.is_some() {
+        return Ok(handle_wizard_key(key, app).await);
+    }
+
+    if app.ignore_editor.is_some() {
+        return Ok(handle_ignore_editor_key(key, app).await);
+    }
+
+    if app.viewing_conflicts && handle_conflict_view_key(key, app).await {
+        return Ok(KeyAction::Continue);
+    }
+
     match key.code {
+        KeyCode::Tab => {
+            app.select_tab(app.tab.next()).await;
+            return Ok(KeyAction::Continue);
+        }
+        KeyCode::BackTab => {
+            app.select_tab(app.tab.previous()).await;
+            return Ok(KeyAction::Continue);
+        }
+        _ => {}
+    }
+
+    // Outside the sync view only the keys that don't act on a selection apply
+    if app.tab != Tab::Sync
+        && !matches!(
+            key.code,
+            KeyCode::Char('q' | 'r' | 'R' | 'T' | 'x' | 'a' | '?')
+        )
+    {
+        return Ok(KeyAction::Continue);
+    }
+
+    if app.pending_delete.is_some() {
+        if key.code == KeyCode::Char('y') {
+            app.confirm_delete_spec();
+            return Ok(KeyAction::Refresh);
+        }
+        app.pending_delete = None;
+        app.status_message = Some(StatusMessage::info(t!("status.delete_cancelled")));
+        return Ok(KeyAction::Continue);
+    }
+
+    if app.pending_rename.is_some() {
+        if key.code == KeyCode::Char('y') {
+            app.confirm_rename_spec().await;
+            return Ok(KeyAction::Refresh);
+        }
+        app.pending_rename = None;
+        app.status_message = Some(StatusMessage::info(t!("status.rename_cancelled")));
+        return Ok(KeyAction::Continue);
+    }
+
+    if app.pending_terminate {
+        app.pending_terminate = false;
+        if key.code == KeyCode::Char('y') {
+            terminate(app, terminal).await?;
+            return Ok(KeyAction::Refresh);
+        }
+        app.status_message = Some(StatusMessage::info(t!("status.terminate_cancelled")));
+        return Ok(KeyAction::Continue);
+    }
+
+    if app.pending_lock_removal.is_some() {
+        if key.code == KeyCode::Char('y') {
+            app.confirm_remove_stale_lock().await;
+            return Ok(KeyAction::Refresh);
+        }
+        app.pending_lock_removal = None;
+        app.status_message = Some(StatusMessage::info(t!("status.lock_kept")));
+        return Ok(KeyAction::Continue);
+    }
+
+    if app.pending_bookmark {
+        match key.code {
+            KeyCode::Char(c @ '0'..='9') => app.assign_bookmark(c as u8 - b'0'),
+            _ => {
+                app.pending_bookmark = false;
+                app.status_message = Some(StatusMessage::info(t!("status.bookmark_cancelled")));
+            }
+        }
+        return Ok(KeyAction::Continue);
+    }
+
+    // Shift+digit: jump to a bookmarked project and repeat the last project action
+    if let Some(digit) = shifted_digit(&key) {
+        if !app.jump_to_bookmark(digit) {
+            return Ok(KeyAction::Continue);
+        }
+        return match app.last_project_action {
+            Some(action) if app.read_only => {
+                app.refuse_read_only(&key_name(action));
+                Ok(KeyAction::Continue)
+            }
+            Some(action) => run_action(action, app, terminal).await,
+            None => {
+                app.status_message = Some(StatusMessage::info(t!("status.no_action_to_repeat")));
+                Ok(KeyAction::Continue)
+            }
+        };
+    }
+
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    if let KeyCode::Char(c) = key.code {
+        if app.read_only && !ctrl && MUTATING_KEYS.contains(&c) {
+            app.refuse_read_only(&key_name(c));
+            return Ok(KeyAction::Continue);
+        }
+    }
+
+    // A count applies to the next key only
+    let count = app.pending_count.take();
+
+    match key.code {
+        KeyCode::Char('d') if ctrl => {
+            app.move_half_page(true);
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::PageDown => {
+            app.move_half_page(true);
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::PageUp => {
+            app.move_half_page(false);
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('u') if ctrl => {
+            app.move_half_page(false);
+            Ok(KeyAction::Continue)
+        }
         KeyCode::Char('q') => {
             app.quit();
             Ok(KeyAction::Quit)
         }
+        // Project discovery may be set up differently now
+        KeyCode::Char('r') if ctrl => {
+            app.reload_config();
+            Ok(KeyAction::Refresh)
+        }
+        // Other tabs have no selected project to scope the refresh to
+        KeyCode::Char('r') if app.tab == Tab::Sync => Ok(KeyAction::RefreshSelected),
         KeyCode::Char('r') => Ok(KeyAction::Refresh),
+        KeyCode::Char('R') => Ok(KeyAction::Refresh),
         KeyCode::Char('m') => {
             app.toggle_session_display();
             Ok(KeyAction::Continue)
@@ -148,6 +524,14 @@ pub async fn handle_key_event<B: Backend>(
             }
             Ok(KeyAction::Continue)
         }
+        KeyCode::Enter if app.offers_setup() && app.read_only => {
+            app.refuse_read_only("Enter");
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Enter if app.offers_setup() => {
+            app.run_setup();
+            Ok(KeyAction::Refresh)
+        }
         KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
             // Toggle fold for selected project
             if let Some(proj_idx) = app.get_selected_project_index() {
@@ -155,119 +539,504 @@ pub async fn handle_key_event<B: Backend>(
             }
             Ok(KeyAction::Continue)
         }
+        KeyCode::Char(c @ ('-' | '+' | '=')) => {
+            app.set_all_folded(c == '-');
+            Ok(KeyAction::Continue)
+        }
         KeyCode::Char('e') => {
             handle_enter_key(app, terminal)?;
             Ok(KeyAction::Refresh)
         }
-        KeyCode::Char('s') => {
-            handle_start(app, terminal).await?;
-            Ok(KeyAction::Refresh)
+        KeyCode::Char(action @ ('s' | 't' | 'f' | 'u' | 'p' | ' ')) => {
+            if app.selection.is_project_selected() {
+                app.last_project_action = Some(action);
+            }
+            run_action(action, app, terminal).await
         }
-        KeyCode::Char('t') => {
-            handle_terminate(app, terminal).await?;
-            Ok(KeyAction::Refresh)
+        // Digits with no bookmark start a count, which later digits extend
+        KeyCode::Char(c @ '0'..='9')
+            if count.is_some() || (c != '0' && app.bookmarks.get(c as u8 - b'0').is_none()) =>
+        {
+            app.push_count_digit(count, c as u8 - b'0');
+            Ok(KeyAction::Continue)
         }
-        KeyCode::Char('f') => {
-            handle_flush(app, terminal).await?;
-            Ok(KeyAction::Refresh)
+        KeyCode::Char(c @ '1'..='9') => {
+            app.jump_to_bookmark(c as u8 - b'0');
+            Ok(KeyAction::Continue)
         }
-        KeyCode::Char('u') => {
-            handle_resume(app, terminal).await?;
-            Ok(KeyAction::Refresh)
+        KeyCode::Char('b') => {
+            app.start_bookmark();
+            Ok(KeyAction::Continue)
         }
-        KeyCode::Char('p') => {
-            handle_pause_or_push(app, terminal).await?;
-            Ok(KeyAction::Refresh)
+        KeyCode::Char('F') => {
+            app.toggle_favorite();
+            Ok(KeyAction::Continue)
         }
-        KeyCode::Char(' ') => {
-            handle_toggle_pause(app, terminal).await?;
-            Ok(KeyAction::Refresh)
+        KeyCode::Char('A') => {
+            app.toggle_favorites_only();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('n') => {
+            app.open_wizard();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('E') => {
+            app.open_spec_editor();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('N') => {
+            app.open_spec_duplicate();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('i') => {
+            app.open_ignore_editor();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('D') => {
+            app.start_delete_spec();
+            Ok(KeyAction::Continue)
         }
         KeyCode::Up | KeyCode::Char('k') => {
-            app.select_previous();
+            match count {
+                Some(count) => app.move_selection(-(count as isize)),
+                None => app.select_previous(),
+            }
             Ok(KeyAction::Continue)
         }
         KeyCode::Down | KeyCode::Char('j') => {
-            app.select_next();
+            match count {
+                Some(count) => app.move_selection(count as isize),
+                None => app.select_next(),
+            }
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char(c @ (']' | '[')) => {
+            app.select_adjacent_problem(c == ']');
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Home | KeyCode::Char('g') => {
+            app.select_row(Some(count.unwrap_or(1)));
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::End | KeyCode::Char('G') => {
+            app.select_row(count);
             Ok(KeyAction::Continue)
         }
         KeyCode::Char('c') => {
             app.toggle_conflict_view();
             Ok(KeyAction::Continue)
         }
+        KeyCode::Char('o') => {
+            app.cycle_sort_mode();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('M') => {
+            app.cycle_grouping();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('d') => {
+            app.toggle_split_layout();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('T') => {
+            app.cycle_theme();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('?') => {
+            app.open_help(help_text());
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('x') => {
+            app.open_status_detail();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('I') => {
+            app.open_project_issues();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('C') => {
+            app.open_settings();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('X') => {
+            app.open_task_menu();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('B') => {
+            app.open_browser().await;
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('O') => {
+            app.open_alpha_in_file_manager();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('Y') => {
+            app.copy_alpha_path();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('y') => {
+            app.open_yank_menu();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('L') => {
+            app.copy_last_commands();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('Z') => {
+            app.toggle_dry_run();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('W') => {
+            handle_shell_key(app, terminal)?;
+            Ok(KeyAction::Refresh)
+        }
+        KeyCode::F(12) => {
+            app.open_session_json();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('a') => {
+            app.toggle_audit_view();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('H') => {
+            app.open_resolution_history();
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('v') => {
+            handle_verify(app, terminal, VerifyMode::Sample(DEFAULT_SAMPLE_SIZE)).await?;
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('V') => {
+            handle_verify(app, terminal, VerifyMode::Full).await?;
+            Ok(KeyAction::Continue)
+        }
+        KeyCode::Char('S') => {
+            let _esc = begin_blocking_op(app, terminal, t!("progress.checking_hosts"))?;
+            app.check_selected_hosts().await;
+            app.blocking_op = None;
+            Ok(KeyAction::Continue)
+        }
         _ => Ok(KeyAction::Continue),
     }
 }
 
+/// Keys that change sessions, project files, or endpoints, which read-only
+/// mode turns off.
+const MUTATING_KEYS: &[char] = &[
+    's', 't', 'f', 'u', 'p', ' ', 'e', 'n', 'E', 'N', 'i', 'D', 'X', 'W',
+];
+
+/// How a key is written in messages.
+fn key_name(c: char) -> String {
+    match c {
+        ' ' => "Space".to_string(),
+        c => c.to_string(),
+    }
+}
+
+/// Run the start, terminate, flush, resume, push, or pause action bound to
+/// `action` on the selected project or spec.
+async fn run_action<B: Backend>(
+    action: char,
+    app: &mut App,
+    terminal: &mut Terminal<B>,
+) -> Result<KeyAction> {
+    match action {
+        's' => handle_start(app, terminal).await?,
+        't' => handle_terminate(app, terminal).await?,
+        'f' => handle_flush(app, terminal).await?,
+        'u' => handle_resume(app, terminal).await?,
+        'p' => handle_pause_or_push(app, terminal).await?,
+        ' ' => handle_toggle_pause(app, terminal).await?,
+        _ => return Ok(KeyAction::Continue),
+    }
+    Ok(KeyAction::Refresh)
+}
+
+/// The digit of a Shift+digit key. Most terminals send the shifted symbol
+/// rather than the digit with a modifier, so this assumes a US layout.
+fn shifted_digit(key: &KeyEvent) -> Option<u8> {
+    match key.code {
+        KeyCode::Char(c @ '1'..='9') if key.modifiers.contains(KeyModifiers::SHIFT) => {
+            Some(c as u8 - b'0')
+        }
+        KeyCode::Char(c) => "!@#$%^&*(".find(c).map(|i| i as u8 + 1),
+        _ => None,
+    }
+}
+
+/// Keys while a text popup is open: scroll, copy, or close.
+fn handle_text_popup_key(key: KeyEvent, app: &mut App) {
+    match key.code {
+        KeyCode::Esc
+        | KeyCode::Enter
+        | KeyCode::F(12)
+        | KeyCode::Char('?')
+        | KeyCode::Char('x')
+        | KeyCode::Char('q') => app.close_text_popup(),
+        KeyCode::Down | KeyCode::Char('j') => app.scroll_text_popup(1),
+        KeyCode::Up | KeyCode::Char('k') => app.scroll_text_popup(-1),
+        KeyCode::PageDown => app.scroll_text_popup(10),
+        KeyCode::PageUp => app.scroll_text_popup(-10),
+        KeyCode::Home | KeyCode::Char('g') => app.scroll_text_popup(i32::MIN / 2),
+        KeyCode::End | KeyCode::Char('G') => app.scroll_text_popup(i32::MAX / 2),
+        KeyCode::Char('y') => app.copy_text_popup(),
+        KeyCode::Char('w') => app.export_text_popup(),
+        _ => {}
+    }
+}
+
+/// Keys while the project's commands are offered: choose one and run it.
+fn handle_task_menu_key(key: KeyEvent, app: &mut App) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('X') => app.close_task_menu(),
+        KeyCode::Down | KeyCode::Char('j') => app.move_task_selection(1),
+        KeyCode::Up | KeyCode::Char('k') => app.move_task_selection(-1),
+        KeyCode::Enter => app.run_selected_task(),
+        KeyCode::Char(c) => app.run_task_by_key(c),
+        _ => {}
+    }
+}
+
+/// Keys while the copy menu is open: pick an item by its letter, or move
+/// to it and press Enter.
+fn handle_yank_menu_key(key: KeyEvent, app: &mut App) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('y') => app.close_yank_menu(),
+        KeyCode::Down | KeyCode::Char('j') => app.move_yank_selection(1),
+        KeyCode::Up | KeyCode::Char('k') => app.move_yank_selection(-1),
+        KeyCode::Enter => app.copy_yank_item(None),
+        KeyCode::Char(c) => app.copy_yank_item(Some(c)),
+        _ => {}
+    }
+}
+
+/// Keys in the directory browser: move, open a directory or go up, and
+/// switch between the spec's endpoints.
+async fn handle_browser_key(key: KeyEvent, app: &mut App) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('B') => app.close_browser(),
+        KeyCode::Down | KeyCode::Char('j') => app.move_browser_selection(1),
+        KeyCode::Up | KeyCode::Char('k') => app.move_browser_selection(-1),
+        KeyCode::PageDown => app.move_browser_selection(10),
+        KeyCode::PageUp => app.move_browser_selection(-10),
+        KeyCode::Home | KeyCode::Char('g') => app.move_browser_selection(i32::MIN / 2),
+        KeyCode::End | KeyCode::Char('G') => app.move_browser_selection(i32::MAX / 2),
+        KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => app.browser_enter().await,
+        KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => app.browser_up().await,
+        KeyCode::Tab => app.browser_switch_side().await,
+        _ => {}
+    }
+}
+
+/// Keys while confirming a reset: type the confirmation word, then Enter.
+async fn handle_reset_key(key: KeyEvent, app: &mut App) -> KeyAction {
+    let Some(pending) = &mut app.pending_reset else {
+        return KeyAction::Continue;
+    };
+    match key.code {
+        KeyCode::Esc => app.cancel_reset(),
+        KeyCode::Enter => {
+            app.confirm_reset().await;
+            return KeyAction::Refresh;
+        }
+        KeyCode::Backspace => {
+            pending.typed.pop();
+        }
+        KeyCode::Char(c) if pending.typed.len() < RESET_CONFIRMATION.len() * 2 => {
+            pending.typed.push(c);
+        }
+        _ => {}
+    }
+    KeyAction::Continue
+}
+
+/// Keys in the new session form: edit the focused field, move between
+/// fields, complete paths with Tab, and save with Enter.
+async fn handle_wizard_key(key: KeyEvent, app: &mut App) -> KeyAction {
+    let Some(wizard) = &mut app.wizard else {
+        return KeyAction::Continue;
+    };
+    match key.code {
+        KeyCode::Esc => app.close_wizard(),
+        KeyCode::Enter => {
+            app.submit_wizard().await;
+            if app.wizard.is_none() {
+                return KeyAction::Refresh;
+            }
+        }
+        KeyCode::Down => wizard.move_focus(1),
+        KeyCode::Up | KeyCode::BackTab => wizard.move_focus(-1),
+        KeyCode::Tab => match wizard.focus {
+            WizardField::Alpha => {
+                wizard.alpha.complete_path();
+            }
+            WizardField::Beta => {
+                wizard.beta.complete_path();
+            }
+            _ => wizard.move_focus(1),
+        },
+        KeyCode::Left if wizard.focus.is_choice() => wizard.cycle_choice(-1),
+        KeyCode::Right | KeyCode::Char(' ') if wizard.focus.is_choice() => wizard.cycle_choice(1),
+        _ => {
+            if let Some(input) = wizard.focused_input() {
+                input.handle_key(&key);
+            }
+        }
+    }
+    KeyAction::Continue
+}
+
+/// Keys in the ignores overlay: move between the spec's patterns, add or
+/// remove one, turn VCS directories or saving to the file on or off, and
+/// apply with Enter.
+async fn handle_ignore_editor_key(key: KeyEvent, app: &mut App) -> KeyAction {
+    let Some(editor) = &mut app.ignore_editor else {
+        return KeyAction::Continue;
+    };
+    if let Some(input) = &mut editor.adding {
+        match key.code {
+            KeyCode::Esc => editor.adding = None,
+            KeyCode::Enter => editor.finish_adding(),
+            _ => {
+                input.handle_key(&key);
+            }
+        }
+        return KeyAction::Continue;
+    }
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('i') => app.close_ignore_editor(),
+        KeyCode::Down | KeyCode::Char('j') => editor.move_selection(1),
+        KeyCode::Up | KeyCode::Char('k') => editor.move_selection(-1),
+        KeyCode::Char('a') => editor.start_adding(),
+        KeyCode::Char('d') | KeyCode::Delete => editor.remove_selected(),
+        KeyCode::Char('v') => editor.toggle_vcs(),
+        KeyCode::Char('w') => editor.toggle_save_to_file(),
+        KeyCode::Enter => {
+            app.apply_ignore_editor().await;
+            if app.ignore_editor.is_none() {
+                return KeyAction::Refresh;
+            }
+        }
+        _ => {}
+    }
+    KeyAction::Continue
+}
+
+/// Navigation keys in the conflict overlay, and `i` to ignore the
+/// highlighted path. Returns false for keys it doesn't handle, which then
+/// work as usual.
+async fn handle_conflict_view_key(key: KeyEvent, app: &mut App) -> bool {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('c') => app.close_conflict_view(),
+        KeyCode::Char('i') if app.read_only => app.refuse_read_only("i"),
+        KeyCode::Char('i') => app.ignore_selected_conflict().await,
+        KeyCode::Down | KeyCode::Char('j') => app.move_conflict_selection(1),
+        KeyCode::Up | KeyCode::Char('k') => app.move_conflict_selection(-1),
+        KeyCode::PageDown => app.move_conflict_selection(10),
+        KeyCode::PageUp => app.move_conflict_selection(-10),
+        KeyCode::Home | KeyCode::Char('g') => app.select_first_conflict(),
+        KeyCode::End | KeyCode::Char('G') => app.select_last_conflict(),
+        _ => return false,
+    }
+    true
+}
+
 /// Handle Enter key - edit selected project file.
 fn handle_enter_key<B: Backend>(app: &mut App, terminal: &mut Terminal<B>) -> Result<()> {
-    if let Some(project_idx) = app.get_selected_project_index() {
-        if let Some(project) = app.projects.get(project_idx) {
-            let editor = get_editor();
-            let file_path = &project.file.path;
-            let is_gui = is_gui_editor(&editor);
-
-            if is_gui {
-                // GUI editor - spawn detached, don't wait
-                match Command::new(&editor).arg(file_path).spawn() {
-                    Ok(_) => {
-                        app.status_message = Some(StatusMessage::info(format!(
-                            "Opened in {}: {}",
-                            editor,
-                            project.file.display_name()
-                        )));
-                    }
-                    Err(e) => {
-                        app.status_message = Some(StatusMessage::error(format!(
-                            "Failed to launch editor: {}",
-                            e
-                        )));
-                    }
-                }
-            } else {
-                // Terminal editor - suspend TUI and wait for editor to exit
-                disable_raw_mode()?;
-                execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
-                terminal.show_cursor()?;
-
-                let status = Command::new(&editor).arg(file_path).status();
-
-                // Restore TUI
-                enable_raw_mode()?;
-                execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
-                terminal.hide_cursor()?;
-
-                // Handle editor result
-                match status {
-                    Ok(exit_status) if exit_status.success() => {
-                        app.status_message = Some(StatusMessage::info(format!(
-                            "Edited: {}",
-                            project.file.display_name()
-                        )));
-                    }
-                    Ok(exit_status) => {
-                        app.status_message = Some(StatusMessage::warning(format!(
-                            "Editor exited with code: {}",
-                            exit_status.code().unwrap_or(-1)
-                        )));
-                    }
-                    Err(e) => {
-                        app.status_message = Some(StatusMessage::error(format!(
-                            "Failed to launch editor: {}",
-                            e
-                        )));
-                    }
-                }
+    let selected_project = app
+        .get_selected_project_index()
+        .and_then(|idx| app.projects.get(idx));
+    let target = match selected_project {
+        Some(project) => Some((project.file.path.clone(), project.file.display_name())),
+        // On the parse error screen, edit the selected broken file
+        None => app
+            .selected_project_error()
+            .map(|error| (error.path.clone(), error.path.display().to_string())),
+    };
+    let Some((file_path, display_name)) = target else {
+        app.status_message = Some(StatusMessage::info(t!("status.select_project_to_edit")));
+        return Ok(());
+    };
+
+    let editor = get_editor();
+    let is_gui = is_gui_editor(&editor);
+
+    if is_gui {
+        // GUI editor - spawn detached, don't wait
+        match Command::new(&editor).arg(&file_path).spawn() {
+            Ok(_) => {
+                app.status_message = Some(StatusMessage::info(t!(
+                    "status.opened_in_editor",
+                    editor = editor,
+                    file = display_name
+                )));
+            }
+            Err(e) => {
+                app.status_message =
+                    Some(StatusMessage::error(t!("status.editor_failed", error = e)));
             }
         }
     } else {
-        app.status_message = Some(StatusMessage::info(
-            "Select a project to edit its configuration file",
-        ));
+        // Terminal editor - suspend TUI and wait for editor to exit
+        disable_raw_mode()?;
+        execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+        terminal.show_cursor()?;
+
+        let status = Command::new(&editor).arg(&file_path).status();
+
+        // Restore TUI
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        terminal.hide_cursor()?;
+
+        // Handle editor result
+        match status {
+            Ok(exit_status) if exit_status.success() => {
+                app.status_message = Some(StatusMessage::info(t!(
+                    "status.edited",
+                    file = display_name
+                )));
+            }
+            Ok(exit_status) => {
+                app.status_message = Some(StatusMessage::warning(t!(
+                    "status.editor_exit_code",
+                    code = exit_status.code().unwrap_or(-1)
+                )));
+            }
+            Err(e) => {
+                app.status_message =
+                    Some(StatusMessage::error(t!("status.editor_failed", error = e)));
+            }
+        }
     }
     Ok(())
 }
 
+/// Handle 'W' key - open a shell in the selected spec's beta directory,
+/// suspending the TUI until it exits.
+fn handle_shell_key<B: Backend>(app: &mut App, terminal: &mut Terminal<B>) -> Result<()> {
+    let Some((mut command, endpoint)) = app.beta_shell_command() else {
+        return Ok(());
+    };
+
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    terminal.show_cursor()?;
+
+    let status = command.status();
+
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.hide_cursor()?;
+
+    app.status_message = Some(match status {
+        Ok(_) => StatusMessage::info(t!("status.shell_exited", endpoint = endpoint)),
+        Err(e) => StatusMessage::error(t!("status.shell_failed", error = e)),
+    });
+    Ok(())
+}
+
 /// Handle 's' key - start project or spec.
 async fn handle_start<B: Backend>(
     app: &mut App,
@@ -278,10 +1047,7 @@ async fn handle_start<B: Backend>(
         app.start_selected_spec().await;
     } else {
         // Project selected: start all specs (show blocking modal)
-        app.blocking_op = Some(BlockingOperation {
-            message: "Starting project...".to_string(),
-        });
-        terminal.draw(|f| ui::draw(f, app))?;
+        let _esc = begin_blocking_op(app, terminal, t!("progress.starting_project"))?;
 
         app.start_selected_project().await;
         app.blocking_op = None;
@@ -289,22 +1055,27 @@ async fn handle_start<B: Backend>(
     Ok(())
 }
 
-/// Handle 't' key - terminate project or spec.
+/// Handle 't' key - terminate project or spec, asking first if another
+/// machine or user created any of its sessions.
 async fn handle_terminate<B: Backend>(
     app: &mut App,
     terminal: &mut Terminal<B>,
 ) -> Result<()> {
+    if app.confirm_foreign_terminate() {
+        return Ok(());
+    }
+    terminate(app, terminal).await
+}
+
+async fn terminate<B: Backend>(app: &mut App, terminal: &mut Terminal<B>) -> Result<()> {
     if app.selection.is_spec_selected() {
         // Spec selected: terminate just this spec (no modal - quick operation)
         app.terminate_selected().await;
     } else {
         // Project selected: terminate all specs (show blocking modal)
-        app.blocking_op = Some(BlockingOperation {
-            message: "Terminating project...".to_string(),
-        });
-        terminal.draw(|f| ui::draw(f, app))?;
+        let _esc = begin_blocking_op(app, terminal, t!("progress.terminating_project"))?;
 
-        app.terminate_selected_project().await;
+        app.terminate_selected_project(&mut redraw(terminal)).await;
         app.blocking_op = None;
     }
     Ok(())
@@ -320,12 +1091,9 @@ async fn handle_flush<B: Backend>(
         app.flush_selected().await;
     } else {
         // Project selected: flush all specs (show blocking modal)
-        app.blocking_op = Some(BlockingOperation {
-            message: "Flushing project...".to_string(),
-        });
-        terminal.draw(|f| ui::draw(f, app))?;
+        let _esc = begin_blocking_op(app, terminal, t!("progress.flushing_project"))?;
 
-        app.flush_selected_project().await;
+        app.flush_selected_project(&mut redraw(terminal)).await;
         app.blocking_op = None;
     }
     Ok(())
@@ -337,29 +1105,52 @@ async fn handle_resume<B: Backend>(
     terminal: &mut Terminal<B>,
 ) -> Result<()> {
     if app.selection.is_spec_selected() {
-        // Spec selected: resume just this spec (no modal)
-        app.resume_selected().await;
+        // Spec selected: resume just this spec (no modal), or offer a reset
+        // if a safety check halted it
+        if !app.offer_safety_reset() {
+            app.resume_selected().await;
+        }
     } else {
         // Project selected: resume all specs (show blocking modal)
-        app.blocking_op = Some(BlockingOperation {
-            message: "Resuming project...".to_string(),
-        });
-        terminal.draw(|f| ui::draw(f, app))?;
+        let _esc = begin_blocking_op(app, terminal, t!("progress.resuming_project"))?;
 
-        app.resume_selected_project().await;
+        app.resume_selected_project(&mut redraw(terminal)).await;
         app.blocking_op = None;
     }
     Ok(())
 }
 
+/// Handle 'v'/'V' keys - verify checksums of the selected spec, or close the results.
+async fn handle_verify<B: Backend>(
+    app: &mut App,
+    terminal: &mut Terminal<B>,
+    mode: VerifyMode,
+) -> Result<()> {
+    if app.verify_report.is_some() {
+        app.close_verify_report();
+        return Ok(());
+    }
+    if !app.selection.is_spec_selected() {
+        app.status_message = Some(StatusMessage::error(t!("status.select_spec_to_verify")));
+        return Ok(());
+    }
+
+    let message = match mode {
+        VerifyMode::Sample(_) => t!("progress.verifying_sample"),
+        VerifyMode::Full => t!("progress.verifying_full"),
+    };
+    let _esc = begin_blocking_op(app, terminal, message)?;
+
+    app.verify_selected(mode).await;
+    app.blocking_op = None;
+    Ok(())
+}
+
 /// Handle 'p' key - create push session.
 async fn handle_pause_or_push<B: Backend>(app: &mut App, terminal: &mut Terminal<B>) -> Result<()> {
     if app.selection.is_spec_selected() {
         // Individual spec selected: create push session (replaces two-way if running)
-        app.blocking_op = Some(BlockingOperation {
-            message: "Creating push session...".to_string(),
-        });
-        terminal.draw(|f| ui::draw(f, app))?;
+        let _esc = begin_blocking_op(app, terminal, t!("progress.creating_push_session"))?;
 
         app.push_selected_spec().await;
         app.blocking_op = None;
@@ -375,16 +1166,15 @@ async fn handle_pause_or_push<B: Backend>(app: &mut App, terminal: &mut Terminal
             0
         };
         let message = if session_count == 1 {
-            "Creating push session...".to_string()
+            t!("progress.creating_push_session").to_string()
         } else {
-            format!("Creating {} push sessions...", session_count)
+            t!("progress.creating_push_sessions", count = session_count)
         };
 
         // Show blocking modal before operation
-        app.blocking_op = Some(BlockingOperation { message });
-        terminal.draw(|f| ui::draw(f, app))?;
+        let _esc = begin_blocking_op(app, terminal, message)?;
 
-        app.push_selected_project().await;
+        app.push_selected_project(&mut redraw(terminal)).await;
         app.blocking_op = None;
     }
     Ok(())
@@ -408,21 +1198,1047 @@ async fn handle_toggle_pause<B: Backend>(app: &mut App, terminal: &mut Terminal<
         };
 
         let operation_name = if has_running {
-            "Pausing all specs..."
+            t!("progress.pausing_all")
         } else {
-            "Resuming all specs..."
+            t!("progress.resuming_all")
         };
 
-        app.blocking_op = Some(BlockingOperation {
-            message: operation_name.to_string(),
-        });
-        terminal.draw(|f| ui::draw(f, app))?;
+        let _esc = begin_blocking_op(app, terminal, operation_name)?;
+
+        app.toggle_pause_selected(&mut redraw(terminal)).await;
+        app.blocking_op = None;
+    } else if app.selection.selected_host().is_some() {
+        // Host selected: pause or resume all of its specs, like a project
+        let has_running = app
+            .selected_host_sessions()
+            .iter()
+            .any(|(_, session)| !session.paused);
+        let operation_name = if has_running {
+            t!("progress.pausing_all")
+        } else {
+            t!("progress.resuming_all")
+        };
+
+        let _esc = begin_blocking_op(app, terminal, operation_name)?;
 
-        app.toggle_pause_selected().await;
+        app.toggle_pause_selected(&mut redraw(terminal)).await;
         app.blocking_op = None;
     } else {
         // Single spec: no modal needed (quick operation)
-        app.toggle_pause_selected().await;
+        app.toggle_pause_selected(&mut |_| {}).await;
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::TaskKind;
+    use crate::command::{failure_output, success_output, MockCommandRunner};
+    use crate::config::Config;
+    use crate::icons::Icons;
+    use crate::lint;
+    use crate::mutagen::{MutagenClient, SharedRunner};
+    use crate::origin::Origin;
+    use crate::project::ProjectFile;
+    use crate::selection::SelectableItem;
+    use ratatui::backend::TestBackend;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    const LIST: &str = "mutagen sync list --template {{json .}}";
+
+    /// An app over two projects, `app` with specs `api` and `web` and `docs`
+    /// with spec `site`, whose mutagen commands go to a mock runner.
+    struct Harness {
+        app: App,
+        terminal: Terminal<TestBackend>,
+        runner: Arc<MockCommandRunner>,
+        _dir: TempDir,
+    }
+
+    fn session(name: &str, status: &str, paused: bool) -> serde_json::Value {
+        json!({
+            "name": name,
+            "identifier": format!("sync_{}", name),
+            "alpha": {"protocol": "local", "path": "/code/app", "connected": true},
+            "beta": {"protocol": "ssh", "path": "/srv/app", "host": "server", "connected": true},
+            "mode": "two-way-safe",
+            "status": status,
+            "paused": paused,
+        })
+    }
+
+    impl Harness {
+        /// Load the projects with `sessions` running.
+        async fn new(sessions: &[serde_json::Value]) -> Self {
+            let dir = tempfile::tempdir().unwrap();
+            for (project, specs) in [("app", &["api", "web"][..]), ("docs", &["site"][..])] {
+                let project_dir = dir.path().join(project);
+                std::fs::create_dir(&project_dir).unwrap();
+                let mut yaml = String::from("sync:\n");
+                for spec in specs {
+                    yaml.push_str(&format!(
+                        "  {}:\n    alpha: ./{}\n    beta: server:/srv/{}\n",
+                        spec, spec, spec
+                    ));
+                }
+                std::fs::write(project_dir.join("mutagen.yml"), yaml).unwrap();
+            }
+
+            let runner = Arc::new(MockCommandRunner::new());
+            runner.expect(LIST, success_output(&json!(sessions).to_string()));
+            let mut app = App::for_test(
+                dir.path().to_path_buf(),
+                runner.clone(),
+                &dir.path().join("state"),
+            );
+            app.refresh_sessions().await.unwrap();
+            app.wait_for_discovery().await;
+
+            Self {
+                app,
+                terminal: Terminal::new(TestBackend::new(100, 30)).unwrap(),
+                runner,
+                _dir: dir,
+            }
+        }
+
+        async fn press(&mut self, code: KeyCode) -> KeyAction {
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            handle_key_event(key, &mut self.app, &mut self.terminal)
+                .await
+                .unwrap()
+        }
+
+        async fn type_keys(&mut self, keys: &str) {
+            for c in keys.chars() {
+                self.press(KeyCode::Char(c)).await;
+            }
+        }
+
+        /// The selected row as a project name, or `project/spec`.
+        fn selected(&self) -> String {
+            let project_name = |index: usize| {
+                let path = &self.app.projects[index].file.path;
+                path.parent()
+                    .unwrap()
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            };
+            match self.app.selection.selected_item() {
+                Some(SelectableItem::Project { index }) => project_name(*index),
+                Some(SelectableItem::Host { index }) => {
+                    format!("@{}", self.app.selection.hosts()[*index])
+                }
+                Some(SelectableItem::Spec {
+                    project_index,
+                    spec_index,
+                }) => format!(
+                    "{}/{}",
+                    project_name(*project_index),
+                    self.app.projects[*project_index].specs[*spec_index].name
+                ),
+                None => String::new(),
+            }
+        }
+
+        fn message(&self) -> &str {
+            self.app.status_message.as_ref().map_or("", |m| m.text())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_navigation_and_folding() {
+        // `app` is unfolded because only some of its specs are running
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        assert_eq!(h.selected(), "app");
+
+        h.press(KeyCode::Char('j')).await;
+        h.press(KeyCode::Down).await;
+        assert_eq!(h.selected(), "app/web");
+        h.press(KeyCode::Char('j')).await;
+        assert_eq!(h.selected(), "docs");
+
+        // Unfolding `docs` shows its spec
+        h.press(KeyCode::Char('l')).await;
+        h.press(KeyCode::Char('j')).await;
+        assert_eq!(h.selected(), "docs/site");
+
+        // Folding `app` skips over its specs
+        h.type_keys("kkkk").await;
+        assert_eq!(h.selected(), "app");
+        h.press(KeyCode::Char('h')).await;
+        h.press(KeyCode::Char('j')).await;
+        assert_eq!(h.selected(), "docs");
+        assert!(h.runner.executed_commands() == [LIST]);
+    }
+
+    #[tokio::test]
+    async fn test_spec_actions_run_mutagen() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.press(KeyCode::Char('j')).await;
+
+        h.runner
+            .expect("mutagen sync pause sync_api", success_output(""));
+        let action = h.press(KeyCode::Char(' ')).await;
+        assert!(matches!(action, KeyAction::Refresh));
+        assert_eq!(h.message(), "Paused spec: api");
+
+        h.runner
+            .expect("mutagen sync terminate sync_api", success_output(""));
+        h.press(KeyCode::Char('t')).await;
+        assert_eq!(h.message(), "Terminated spec: api");
+        assert!(h.runner.was_executed("mutagen sync terminate sync_api"));
+        // A spec action isn't remembered for repeating on projects
+        assert_eq!(h.app.last_project_action, None);
+    }
+
+    #[tokio::test]
+    async fn test_actions_record_their_commands() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.app.mutagen_client.record_commands();
+        h.press(KeyCode::Char('j')).await;
+        h.runner
+            .expect("mutagen sync pause sync_api", success_output(""));
+        h.press(KeyCode::Char(' ')).await;
+        assert_eq!(
+            *h.app.last_commands.borrow(),
+            ["mutagen sync pause sync_api"]
+        );
+
+        // Learning mode is off, so there's nothing to copy
+        h.press(KeyCode::Char('L')).await;
+        assert!(h.message().starts_with("Set learning = true"));
+    }
+
+    #[tokio::test]
+    async fn test_read_only_turns_off_mutating_keys() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.app.read_only = true;
+        h.app.last_project_action = Some('t');
+        h.app.assign_bookmark(1);
+        h.press(KeyCode::Char('!')).await;
+        assert_eq!(h.message(), "Read-only: t is turned off");
+
+        h.press(KeyCode::Char('j')).await;
+        h.press(KeyCode::Char(' ')).await;
+        assert_eq!(h.message(), "Read-only: Space is turned off");
+        h.press(KeyCode::Char('D')).await;
+        assert!(h.app.pending_delete.is_none());
+        h.press(KeyCode::Char('n')).await;
+        assert!(h.app.wizard.is_none());
+        assert_eq!(h.runner.executed_commands(), [LIST]);
+
+        // Looking around still works
+        h.press(KeyCode::Char('a')).await;
+        assert!(h.app.audit_view.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_shows_commands_instead_of_running_them() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.press(KeyCode::Char('Z')).await;
+        assert!(h.app.mutagen_client.is_dry_run());
+        h.press(KeyCode::Char('j')).await;
+        h.press(KeyCode::Char(' ')).await;
+        assert!(!h.runner.was_executed("mutagen sync pause sync_api"));
+
+        h.app.show_dry_run_commands();
+        let popup = h.app.text_popup.take().unwrap();
+        assert_eq!(popup.title, "Dry run: would run");
+        assert_eq!(popup.text, "mutagen sync pause sync_api");
+        assert!(h.app.audit_log.recent(10).unwrap().is_empty());
+
+        h.press(KeyCode::Char('Z')).await;
+        assert!(!h.app.mutagen_client.is_dry_run());
+        h.app.show_dry_run_commands();
+        assert!(h.app.text_popup.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_start_partly_running_project() {
+        let mut api = session("api", "Watching for changes", false);
+        api["labels"] = json!({"io.mutagen.project": "project_app"});
+        let mut h = Harness::new(&[api]).await;
+        h.press(KeyCode::Char('Z')).await;
+        assert_eq!(h.selected(), "app");
+
+        // Only the spec that isn't running is created, as part of the
+        // project mutagen started
+        h.press(KeyCode::Char('s')).await;
+        assert_eq!(
+            h.message(),
+            "Started 1 spec(s) of mutagen that weren't running"
+        );
+        h.app.show_dry_run_commands();
+        let popup = h.app.text_popup.take().unwrap();
+        assert!(popup
+            .text
+            .contains("mutagen sync create ./web 'server:/srv/web' -n web"));
+        assert!(popup
+            .text
+            .contains("--label io.mutagen.project=project_app"));
+        assert!(!popup.text.contains("mutagen project start"));
+        assert!(!popup.text.contains("-n api"));
+    }
+
+    #[tokio::test]
+    async fn test_start_project_with_includes() {
+        let mut h = Harness::new(&[]).await;
+        h.press(KeyCode::Char('Z')).await;
+        let shared = h._dir.path().join("common.yml");
+        let docs = &mut h.app.projects[1].file;
+        docs.includes = vec![shared];
+        docs.defaults = Some(HashMap::from([(
+            "mode".to_string(),
+            serde_yaml::Value::from("one-way-replica"),
+        )]));
+        h.app
+            .selection
+            .select_item(&SelectableItem::Project { index: 1 });
+        assert_eq!(h.selected(), "docs");
+
+        // Mutagen wouldn't see the shared defaults, so the specs are
+        // created one by one with them merged in
+        h.press(KeyCode::Char('s')).await;
+        assert_eq!(
+            h.message(),
+            "Started 1 spec(s) of mutagen that weren't running"
+        );
+        h.app.show_dry_run_commands();
+        let popup = h.app.text_popup.take().unwrap();
+        assert!(popup.text.contains("-n site"));
+        assert!(popup.text.contains("one-way-replica"));
+        assert!(!popup.text.contains("mutagen project start"));
+    }
+
+    #[tokio::test]
+    async fn test_remove_stale_lock() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        let project = h._dir.path().join("docs").join("mutagen.yml");
+        let lock = h._dir.path().join("docs").join("mutagen.yml.lock");
+        std::fs::write(&lock, "project_docs").unwrap();
+        let start = format!("mutagen project start -f {}", project.display());
+        let running = json!([session("api", "Watching for changes", false)]).to_string();
+        h.app
+            .selection
+            .select_item(&SelectableItem::Project { index: 1 });
+        assert_eq!(h.selected(), "docs");
+
+        // Any key but y keeps the lock
+        h.runner
+            .expect(&start, failure_output("Error: project already running"));
+        h.runner.expect(LIST, success_output(&running));
+        h.press(KeyCode::Char('s')).await;
+        assert!(h
+            .message()
+            .contains("(project project_docs, written 0 min ago)"));
+        h.press(KeyCode::Char('n')).await;
+        assert_eq!(h.message(), "Lock file kept");
+        assert!(lock.exists());
+
+        h.runner
+            .expect(&start, failure_output("Error: project already running"));
+        h.runner.expect(LIST, success_output(&running));
+        h.press(KeyCode::Char('s')).await;
+        h.runner.expect(&start, success_output(""));
+        h.press(KeyCode::Char('y')).await;
+        assert!(!lock.exists());
+        assert_eq!(h.message(), "Started project: mutagen");
+        let entries = h.app.audit_log.recent(10).unwrap();
+        assert!(entries.iter().any(|entry| entry.action == "remove-lock"));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_project_started_by_mutagen() {
+        let label = json!({"io.mutagen.project": "project_app"});
+        let mut api = session("api", "Watching for changes", false);
+        api["labels"] = label.clone();
+        let mut h =
+            Harness::new(&[api.clone(), session("site", "Watching for changes", false)]).await;
+        assert_eq!(h.selected(), "app");
+
+        // Listed by its label, which finds a session created elsewhere
+        let mut web = session("web", "Scanning files", false);
+        web["labels"] = label;
+        let selector = format!("{} --label-selector io.mutagen.project=project_app", LIST);
+        h.runner
+            .expect(&selector, success_output(&json!([api, web]).to_string()));
+        assert!(matches!(
+            h.press(KeyCode::Char('r')).await,
+            KeyAction::RefreshSelected
+        ));
+        h.app.refresh_selected_project().await.unwrap();
+        assert!(h.runner.was_executed(&selector));
+        let running = |h: &Harness, project: usize| {
+            h.app.projects[project]
+                .specs
+                .iter()
+                .filter(|spec| spec.is_running())
+                .count()
+        };
+        assert_eq!(running(&h, 0), 2);
+        assert_eq!(running(&h, 1), 1);
+        assert_eq!(h.app.sessions.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_terminate_project_started_by_mutagen() {
+        let mut api = session("api", "Watching for changes", false);
+        api["labels"] = json!({"io.mutagen.project": "project_app"});
+        let mut h = Harness::new(&[api]).await;
+        let project = h._dir.path().join("app").join("mutagen.yml");
+        assert_eq!(h.app.projects[0].project_identifier(), Some("project_app"));
+
+        // Through mutagen project terminate, while its lock file is there
+        std::fs::write(project.with_extension("yml.lock"), "project_app").unwrap();
+        let terminate = format!("mutagen project terminate -f {}", project.display());
+        h.runner.expect(&terminate, success_output(""));
+        h.press(KeyCode::Char('t')).await;
+        assert!(h.runner.was_executed(&terminate));
+        assert!(!h.runner.was_executed("mutagen sync terminate sync_api"));
+        assert_eq!(h.message(), "Terminated project: mutagen");
+    }
+
+    #[tokio::test]
+    async fn test_help_popup_swallows_keys() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.press(KeyCode::Char('?')).await;
+        assert!(h.app.text_popup.is_some());
+
+        // Keys scroll the popup rather than acting on the list
+        h.type_keys("jt").await;
+        assert_eq!(h.selected(), "app");
+        assert_eq!(h.runner.executed_commands(), [LIST]);
+
+        h.press(KeyCode::Esc).await;
+        assert!(h.app.text_popup.is_none());
+        h.press(KeyCode::Char('j')).await;
+        assert_eq!(h.selected(), "app/api");
+    }
+
+    #[tokio::test]
+    async fn test_other_tabs_ignore_selection_keys() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.press(KeyCode::Char('j')).await;
+
+        h.runner.expect(
+            "mutagen forward list --template {{json .}}",
+            success_output("[]"),
+        );
+        h.press(KeyCode::Tab).await;
+        assert_eq!(h.app.tab, Tab::Hosts);
+        h.press(KeyCode::Tab).await;
+        assert_eq!(h.app.tab, Tab::Forward);
+
+        h.type_keys("tj").await;
+        assert_eq!(h.selected(), "app/api");
+        assert!(!h.runner.was_executed("mutagen sync terminate sync_api"));
+
+        h.press(KeyCode::BackTab).await;
+        h.press(KeyCode::BackTab).await;
+        assert_eq!(h.app.tab, Tab::Sync);
+    }
+
+    #[tokio::test]
+    async fn test_bookmark_and_jump() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.type_keys("jjj").await;
+        assert_eq!(h.selected(), "docs");
+
+        h.type_keys("b1").await;
+        assert_eq!(h.message(), "Bookmarked mutagen as 1");
+        assert!(!h.app.pending_bookmark);
+
+        h.type_keys("kk").await;
+        h.press(KeyCode::Char('1')).await;
+        assert_eq!(h.selected(), "docs");
+
+        // Any other key cancels a pending bookmark
+        h.type_keys("bx").await;
+        assert_eq!(h.message(), "Bookmark cancelled");
+    }
+
+    #[tokio::test]
+    async fn test_delete_spec_needs_confirmation() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        let file = h.app.projects[0].file.path.clone();
+
+        // A running spec can't be deleted
+        h.type_keys("jD").await;
+        assert_eq!(h.message(), "Terminate api before deleting its spec");
+        assert!(h.app.pending_delete.is_none());
+
+        h.type_keys("jDn").await;
+        assert_eq!(h.message(), "Delete cancelled");
+        h.press(KeyCode::Char('D')).await;
+        let action = h.press(KeyCode::Char('y')).await;
+        assert!(matches!(action, KeyAction::Refresh));
+        let contents = std::fs::read_to_string(&file).unwrap();
+        assert!(contents.contains("api:"));
+        assert!(!contents.contains("web"));
+    }
+
+    #[tokio::test]
+    async fn test_rename_running_spec() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        let file = h.app.projects[0].file.path.clone();
+        std::fs::create_dir(file.parent().unwrap().join("api")).unwrap();
+        h.type_keys("ZjE").await;
+        h.app.wizard.as_mut().unwrap().name.set("backend");
+        h.press(KeyCode::Enter).await;
+        assert!(h.message().starts_with("Rename api to backend?"));
+        h.press(KeyCode::Char('x')).await;
+        assert_eq!(h.message(), "Rename cancelled");
+        assert!(h.app.wizard.is_none());
+        assert!(!std::fs::read_to_string(&file).unwrap().contains("backend"));
+
+        h.press(KeyCode::Char('E')).await;
+        h.app.wizard.as_mut().unwrap().name.set("backend");
+        h.press(KeyCode::Enter).await;
+        let action = h.press(KeyCode::Char('y')).await;
+        assert!(matches!(action, KeyAction::Refresh));
+        assert_eq!(
+            h.message(),
+            "Renamed api to backend and recreated its session"
+        );
+        assert!(std::fs::read_to_string(&file)
+            .unwrap()
+            .contains("  backend:\n"));
+        h.app.show_dry_run_commands();
+        let popup = h.app.text_popup.take().unwrap();
+        assert!(popup.text.contains("mutagen sync terminate sync_api"));
+        assert!(popup
+            .text
+            .contains("mutagen sync create ./api 'server:/srv/api' -n backend"));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_spec() {
+        let mut h = Harness::new(&[]).await;
+        let file = h.app.projects[0].file.path.clone();
+        std::fs::create_dir(file.parent().unwrap().join("api")).unwrap();
+
+        h.app.select_spec(0, 0);
+        h.press(KeyCode::Char('N')).await;
+        let wizard = h.app.wizard.as_mut().unwrap();
+        assert_eq!(wizard.name.value(), "api-copy");
+        h.press(KeyCode::Esc).await;
+        assert_eq!(h.message(), "Duplicate cancelled");
+
+        h.press(KeyCode::Char('N')).await;
+        let wizard = h.app.wizard.as_mut().unwrap();
+        wizard.name.set("api-next");
+        wizard.beta.set("server:/srv/api-next");
+        let action = h.press(KeyCode::Enter).await;
+        assert!(matches!(action, KeyAction::Refresh));
+        assert!(h.message().starts_with("Copied api to api-next in "));
+        let project = ProjectFile::from_path(file).unwrap();
+        assert_eq!(project.sessions["api-next"].alpha, "./api");
+        assert_eq!(project.sessions["api-next"].beta, "server:/srv/api-next");
+    }
+
+    #[tokio::test]
+    async fn test_terminating_another_users_session_asks_first() {
+        let mut api = session("api", "Watching for changes", false);
+        api["labels"] = json!({"mutagui.host": "laptop", "mutagui.user": "sam"});
+        let mut h = Harness::new(&[api]).await;
+        h.app.mutagen_client = MutagenClient::with_runner(h.runner.clone() as SharedRunner)
+            .with_origin(Origin {
+                host: "desk".to_string(),
+                user: "jane".to_string(),
+            });
+
+        h.type_keys("jt").await;
+        assert_eq!(
+            h.message(),
+            "api was created by sam@laptop. Press y to terminate it anyway"
+        );
+        h.press(KeyCode::Char('n')).await;
+        assert_eq!(h.message(), "Terminate cancelled");
+        assert_eq!(h.runner.executed_commands(), [LIST]);
+
+        h.runner
+            .expect("mutagen sync terminate sync_api", success_output(""));
+        h.type_keys("ty").await;
+        assert_eq!(h.message(), "Terminated spec: api");
+    }
+
+    #[tokio::test]
+    async fn test_project_issues_popup() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.press(KeyCode::Char('I')).await;
+        assert_eq!(h.message(), "No problems found in project files");
+        assert!(h.app.text_popup.is_none());
+
+        h.app.project_issues = lint::lint(std::path::Path::new("mutagen.yml"), "afterStart: []\n");
+        h.press(KeyCode::Char('I')).await;
+        let popup = h.app.text_popup.as_ref().unwrap();
+        assert_eq!(
+            popup.text,
+            "mutagen.yml\n  warning (line 1): unknown key afterStart\n"
+        );
+        h.press(KeyCode::Esc).await;
+        assert!(h.app.text_popup.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_apply_config() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.app.project_issues = lint::lint(std::path::Path::new("mutagen.yml"), "afterStart: []\n");
+        let mut config = Config::default();
+        config.ui.ascii = true;
+        config.refresh.interval_secs = 30;
+        h.app.apply_config(config);
+        assert_eq!(h.app.icons, Icons::new(true));
+        assert_eq!(h.app.refresh_interval(), Duration::from_secs(30));
+        // Issues found in project files outlast the reload
+        assert!(h.app.project_issues[0].message.contains("afterStart"));
+        assert_eq!(h.selected(), "app");
+
+        // --ascii wins over the file
+        h.app.record_flag("ui.ascii", true);
+        h.app.apply_config(Config::default());
+        assert_eq!(h.app.icons, Icons::new(true));
+    }
+
+    #[tokio::test]
+    async fn test_task_menu() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.press(KeyCode::Char('X')).await;
+        assert_eq!(h.message(), "No commands for mutagen or the selected spec");
+        assert!(h.app.task_menu.is_none());
+
+        let commands = &mut h.app.projects[0].file.commands;
+        commands.insert("build".to_string(), "make".to_string());
+        commands.insert("deploy".to_string(), "./deploy.sh".to_string());
+        h.press(KeyCode::Char('X')).await;
+        h.type_keys("jj").await;
+        let menu = h.app.task_menu.as_ref().unwrap();
+        assert_eq!(menu.selected, 1);
+        assert_eq!(menu.commands[menu.selected].0, "deploy");
+        h.press(KeyCode::Esc).await;
+        assert!(h.app.task_menu.is_none());
+
+        // Commands from config.toml are filled in for the selected spec
+        let commands = &mut h.app.config_mut().commands;
+        commands.insert("r".to_string(), "echo {spec} on {beta_host}".to_string());
+        commands.insert("tail".to_string(), "tail {alpha_path}".to_string());
+        h.press(KeyCode::Char('j')).await;
+        assert_eq!(h.selected(), "app/api");
+        h.press(KeyCode::Char('X')).await;
+        let menu = h.app.task_menu.as_ref().unwrap();
+        assert_eq!(
+            menu.commands[2],
+            (
+                "r".to_string(),
+                "echo api on server".to_string(),
+                TaskKind::Custom
+            )
+        );
+        assert_eq!(menu.commands.len(), 4);
+        h.press(KeyCode::Char('r')).await;
+        assert!(h.app.task_menu.is_none());
+        assert_eq!(h.app.text_popup.as_ref().unwrap().title, "Command: r");
+
+        // With the project selected there's no spec to fill in
+        h.press(KeyCode::Esc).await;
+        h.press(KeyCode::Char('g')).await;
+        h.press(KeyCode::Char('X')).await;
+        assert_eq!(h.app.task_menu.as_ref().unwrap().commands.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_ignore_editor() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.press(KeyCode::Char('i')).await;
+        assert_eq!(h.message(), "Select a spec to edit its ignores");
+
+        h.type_keys("ji").await;
+        h.type_keys("a*.log").await;
+        h.press(KeyCode::Enter).await;
+        h.type_keys("v").await;
+        let editor = h.app.ignore_editor.as_ref().unwrap();
+        assert_eq!(editor.paths, vec!["*.log"]);
+        assert!(editor.vcs);
+
+        // The running session is recreated with the new patterns and its mode
+        let create = "mutagen sync create ./api server:/srv/api -m two-way-safe -n api \
+                      --ignore .git --ignore .svn --ignore .hg --ignore .bzr --ignore _darcs \
+                      --ignore .fossil-settings --ignore *.log";
+        h.runner
+            .expect("mutagen sync terminate sync_api", success_output(""));
+        h.runner.expect(create, success_output(""));
+        assert!(matches!(h.press(KeyCode::Enter).await, KeyAction::Refresh));
+        assert!(h.app.ignore_editor.is_none());
+        assert_eq!(h.message(), "Recreated api with 7 ignore pattern(s)");
+        assert!(h.runner.was_executed(create));
+
+        // A spec that isn't running can only be saved to the file
+        h.type_keys("jia").await;
+        h.type_keys("dist").await;
+        h.press(KeyCode::Enter).await;
+        h.press(KeyCode::Enter).await;
+        let editor = h.app.ignore_editor.as_ref().unwrap();
+        assert_eq!(
+            editor.error.as_deref(),
+            Some("web isn't running; press w to save the changes to the project file")
+        );
+        h.type_keys("w").await;
+        h.press(KeyCode::Enter).await;
+        assert!(h.app.ignore_editor.is_none());
+        let contents = std::fs::read_to_string(&h.app.projects[0].file.path).unwrap();
+        assert!(contents.ends_with(
+            "    beta: server:/srv/web\n    ignore:\n      paths:\n        - \"dist\"\n"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_flush_in_progress() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.press(KeyCode::Char('j')).await;
+        h.runner.expect(
+            "mutagen sync flush --skip-wait sync_api",
+            success_output(""),
+        );
+        h.press(KeyCode::Char('f')).await;
+        assert_eq!(h.message(), "Flushing spec: api");
+        let flushing = |h: &Harness| {
+            h.app
+                .sessions
+                .iter()
+                .any(|s| h.app.flush_elapsed(s).is_some())
+        };
+        assert!(flushing(&h));
+
+        // A refresh before it finishes reports over the progress message
+        let unsynced = session("api", "Watching for changes", false);
+        h.runner
+            .expect(LIST, success_output(&json!([unsynced]).to_string()));
+        h.app.refresh_sessions().await.unwrap();
+        assert_eq!(h.message(), "Sessions refreshed");
+        assert!(flushing(&h));
+
+        let mut synced = session("api", "Watching for changes", false);
+        synced["successfulCycles"] = json!(1);
+        h.runner
+            .expect(LIST, success_output(&json!([synced]).to_string()));
+        h.app.refresh_sessions().await.unwrap();
+        assert_eq!(h.message(), "Flush finished: api");
+        assert!(!flushing(&h));
+
+        // One that doesn't finish in time is given up on
+        h.runner.expect(
+            "mutagen sync flush --skip-wait sync_api",
+            success_output(""),
+        );
+        h.press(KeyCode::Char('f')).await;
+        h.app.config_mut().mutagen.flush_timeout_secs = 0;
+        h.runner
+            .expect(LIST, success_output(&json!([synced]).to_string()));
+        h.app.refresh_sessions().await.unwrap();
+        assert_eq!(
+            h.message(),
+            "Flush not finished after 0s, no longer watching: api"
+        );
+        assert!(!flushing(&h));
+    }
+
+    #[tokio::test]
+    async fn test_ignore_conflict_path() {
+        let mut api = session("api", "Watching for changes", false);
+        api["conflicts"] = json!([{"root": "build/app.o"}, {"root": "dist/bundle.js"}]);
+        let mut h = Harness::new(&[api]).await;
+        h.type_keys("jcj").await;
+        assert!(h.app.viewing_conflicts);
+
+        let create = "mutagen sync create ./api server:/srv/api -m two-way-safe -n api \
+                      --ignore /dist/bundle.js";
+        h.runner
+            .expect("mutagen sync terminate sync_api", success_output(""));
+        h.runner.expect(create, success_output(""));
+        h.press(KeyCode::Char('i')).await;
+        assert_eq!(
+            h.message(),
+            "api now ignores /dist/bundle.js, saved to its project file"
+        );
+        assert!(!h.app.viewing_conflicts);
+        assert!(h.runner.was_executed(create));
+        let contents = std::fs::read_to_string(&h.app.projects[0].file.path).unwrap();
+        assert!(contents.contains("    ignore:\n      paths:\n        - \"/dist/bundle.js\"\n"));
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_host_stops_start() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.type_keys("jj").await;
+        assert_eq!(h.selected(), "app/web");
+
+        let check = "ssh -o BatchMode=yes -o ConnectTimeout=5 server true";
+        let refused = "ssh: connect to host server port 22: Connection refused";
+        h.runner.expect(check, failure_output(refused));
+        h.press(KeyCode::Char('S')).await;
+        assert_eq!(h.message(), format!("server is unreachable: {}", refused));
+        assert!(h.app.host_checks["server"].value.is_unreachable());
+
+        h.runner.expect(check, failure_output(refused));
+        h.press(KeyCode::Char('s')).await;
+        assert_eq!(h.message(), format!("server is unreachable: {}", refused));
+        assert!(!h
+            .runner
+            .executed_commands()
+            .iter()
+            .any(|c| c.starts_with("mutagen sync create")));
+
+        h.runner.expect(check, success_output(""));
+        h.press(KeyCode::Char('S')).await;
+        assert_eq!(h.message(), "SSH hosts reachable: 1");
+    }
+
+    #[tokio::test]
+    async fn test_vim_navigation() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.press(KeyCode::Char('G')).await;
+        assert_eq!(h.selected(), "docs");
+        h.press(KeyCode::Char('g')).await;
+        assert_eq!(h.selected(), "app");
+
+        // Counts move without wrapping around, and pick a row for g and G
+        h.type_keys("3j").await;
+        assert_eq!(h.selected(), "docs");
+        h.type_keys("9j").await;
+        assert_eq!(h.selected(), "docs");
+        h.type_keys("2G").await;
+        assert_eq!(h.selected(), "app/api");
+        h.type_keys("2k").await;
+        assert_eq!(h.selected(), "app");
+
+        // A bookmarked digit jumps instead, unless it extends a count
+        h.type_keys("4Gb2g2").await;
+        assert_eq!(h.selected(), "docs");
+        h.type_keys("12k").await;
+        assert_eq!(h.selected(), "app");
+
+        h.app.list_height.set(4);
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        handle_key_event(ctrl('d'), &mut h.app, &mut h.terminal)
+            .await
+            .unwrap();
+        assert_eq!(h.selected(), "app/web");
+        handle_key_event(ctrl('u'), &mut h.app, &mut h.terminal)
+            .await
+            .unwrap();
+        assert_eq!(h.selected(), "app");
+    }
+
+    #[tokio::test]
+    async fn test_favorites() {
+        let mut h = Harness::new(&[]).await;
+        h.press(KeyCode::Char('A')).await;
+        assert_eq!(h.message(), "No favorite projects yet (F marks one)");
+
+        h.type_keys("GF").await;
+        assert_eq!(h.message(), "Marked mutagen as a favorite");
+        assert_eq!(h.selected(), "docs");
+        assert!(h.app.projects[0].favorite);
+
+        h.press(KeyCode::Char('A')).await;
+        assert_eq!(h.app.selection.total_items(), 1);
+        h.press(KeyCode::Char('A')).await;
+        assert_eq!(h.app.selection.total_items(), 2);
+        assert!(h.app.favorites.contains(&h.app.projects[0].file.path));
+    }
+
+    #[tokio::test]
+    async fn test_pause_host() {
+        let mut h = Harness::new(&[
+            session("api", "Watching for changes", false),
+            session("site", "Watching for changes", true),
+        ])
+        .await;
+        h.type_keys("MM").await;
+        assert_eq!(h.message(), "List: by host");
+        assert_eq!(h.selected(), "app/api");
+        h.press(KeyCode::Char('g')).await;
+        assert_eq!(h.selected(), "@server");
+        assert_eq!(h.app.selection.selected_host_specs().len(), 3);
+
+        // Only the spec that isn't paused yet gets paused
+        h.runner
+            .expect("mutagen sync pause sync_api", success_output(""));
+        h.press(KeyCode::Char(' ')).await;
+        assert_eq!(h.message(), "Paused 1 session(s)");
+        assert!(!h.runner.was_executed("mutagen sync pause sync_site"));
+    }
+
+    #[tokio::test]
+    async fn test_fold_all() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.press(KeyCode::Char('+')).await;
+        assert!(h.app.projects.iter().all(|p| !p.folded));
+        h.type_keys("G").await;
+        assert_eq!(h.selected(), "docs/site");
+
+        h.press(KeyCode::Char('-')).await;
+        assert!(h.app.projects.iter().all(|p| p.folded));
+        assert_eq!(h.selected(), "docs");
+        assert_eq!(h.app.selection.total_items(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_jump_to_problems() {
+        let mut h = Harness::new(&[session("web", "Watching for changes", false)]).await;
+        h.press(KeyCode::Char(']')).await;
+        assert_eq!(h.message(), "No specs with conflicts or errors");
+
+        let mut web = session("web", "Watching for changes", false);
+        web["lastError"] = json!("permission denied");
+        let site = session("site", "Halted due to root emptying", false);
+        let sessions = [session("api", "Watching for changes", false), web, site];
+        let mut h = Harness::new(&sessions).await;
+        h.press(KeyCode::Char(']')).await;
+        assert_eq!(h.selected(), "app/web");
+        h.press(KeyCode::Char(']')).await;
+        assert_eq!(h.selected(), "docs/site");
+        h.press(KeyCode::Char(']')).await;
+        assert_eq!(h.selected(), "app/web");
+        h.press(KeyCode::Char('[')).await;
+        assert_eq!(h.selected(), "docs/site");
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_keys_need_a_spec() {
+        let mut h = Harness::new(&[]).await;
+        for key in ['O', 'Y', 'W'] {
+            h.app.status_message = None;
+            h.press(KeyCode::Char(key)).await;
+            assert_eq!(h.message(), "Select a spec to open its endpoints");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_yank_menu() {
+        let mut api = session("api", "Watching for changes", false);
+        api["lastError"] = json!("scan failed: permission denied");
+        let mut h = Harness::new(&[api]).await;
+        h.press(KeyCode::Char('y')).await;
+        assert_eq!(h.message(), "Select a spec to copy its paths");
+
+        h.press(KeyCode::Char('j')).await;
+        h.press(KeyCode::Char('y')).await;
+        let menu = h.app.yank_menu.as_ref().unwrap();
+        let items: Vec<_> = menu
+            .items
+            .iter()
+            .map(|(_, key, text)| (*key, text))
+            .collect();
+        assert!(items[0].1.ends_with("/app/api"));
+        assert_eq!(
+            items[1..],
+            [
+                ('b', &"server:/srv/api".to_string()),
+                ('i', &"sync_api".to_string()),
+                ('e', &"scan failed: permission denied".to_string()),
+            ]
+        );
+
+        h.press(KeyCode::Char('G')).await;
+        h.press(KeyCode::Char('j')).await;
+        assert_eq!(h.app.yank_menu.as_ref().unwrap().selected, 1);
+        h.press(KeyCode::Esc).await;
+        assert!(h.app.yank_menu.is_none());
+
+        // A spec without a session has only its endpoints to copy, unless
+        // the status bar shows an error
+        h.press(KeyCode::Char('j')).await;
+        h.app.status_message = Some(StatusMessage::error("Failed to start"));
+        h.press(KeyCode::Char('y')).await;
+        let menu = h.app.yank_menu.as_ref().unwrap();
+        assert_eq!(menu.items[2].2, "Failed to start");
+        h.press(KeyCode::Esc).await;
+        h.app.status_message = None;
+        h.press(KeyCode::Char('y')).await;
+        assert_eq!(h.app.yank_menu.as_ref().unwrap().items.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_browse_endpoints() {
+        let mut h = Harness::new(&[session("api", "Watching for changes", false)]).await;
+        h.press(KeyCode::Char('B')).await;
+        assert_eq!(h.message(), "Select a spec to browse its endpoints");
+
+        h.press(KeyCode::Char('j')).await;
+        let ls = |dir: &str| format!("ssh server cd {} || exit 1; ls -1Ap", dir);
+        h.runner
+            .expect(&ls("/srv/api"), success_output("README.md\nsrc/\n"));
+        h.press(KeyCode::Char('B')).await;
+        let browser = h.app.browser.as_ref().unwrap();
+        assert_eq!(browser.current().to_string(), "server:/srv/api");
+        assert_eq!(browser.entries[0].name, "src");
+        assert!(browser.entries[0].is_dir);
+
+        h.runner
+            .expect(&ls("/srv/api/src"), success_output("main.rs\n"));
+        h.press(KeyCode::Enter).await;
+        let browser = h.app.browser.as_ref().unwrap();
+        assert_eq!(browser.current().to_string(), "server:/srv/api/src");
+        assert_eq!(browser.entries.len(), 1);
+
+        // Going up selects the directory we came from
+        h.runner
+            .expect(&ls("/srv/api"), success_output("README.md\nsrc/\nzz/\n"));
+        h.press(KeyCode::Char('j')).await;
+        h.press(KeyCode::Backspace).await;
+        let browser = h.app.browser.as_ref().unwrap();
+        assert_eq!(browser.entries[browser.selected].name, "src");
+
+        // Alpha is local, relative to the project file, and doesn't exist
+        h.press(KeyCode::Tab).await;
+        let browser = h.app.browser.as_ref().unwrap();
+        assert_eq!(browser.side_name(), "alpha");
+        assert!(browser.error.is_some());
+
+        h.press(KeyCode::Esc).await;
+        assert!(h.app.browser.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_safety_halt_reset_flow() {
+        let mut h = Harness::new(&[session("api", "halted-on-root-emptied", true)]).await;
+        h.press(KeyCode::Char('j')).await;
+
+        // Resuming a halted session asks for confirmation instead
+        h.press(KeyCode::Char('u')).await;
+        assert!(h.app.pending_reset.is_some());
+        assert!(!h.runner.was_executed("mutagen sync resume sync_api"));
+
+        // Enter without the confirmation typed cancels
+        h.type_keys("yes").await;
+        h.press(KeyCode::Enter).await;
+        assert!(h.app.pending_reset.is_none());
+        assert_eq!(h.message(), "Reset cancelled (type 'reset' to confirm)");
+        assert_eq!(h.runner.executed_commands(), [LIST]);
+
+        h.runner
+            .expect("mutagen sync reset sync_api", success_output(""));
+        h.runner
+            .expect("mutagen sync resume sync_api", success_output(""));
+        h.press(KeyCode::Char('u')).await;
+        h.type_keys(RESET_CONFIRMATION).await;
+        let action = h.press(KeyCode::Enter).await;
+        assert!(matches!(action, KeyAction::Refresh));
+        assert!(h.app.pending_reset.is_none());
+        assert_eq!(h.message(), "Reset spec: api");
+        assert_eq!(
+            h.runner.executed_commands()[1..],
+            [
+                "mutagen sync reset sync_api",
+                "mutagen sync resume sync_api"
+            ]
+        );
+    }
+}
@@ -0,0 +1,871 @@
+//! Checks on project files beyond whether they parse.
+//!
+//! Mutagen reports a bad project file only when the project is started, and
+//! a file that doesn't parse used to just drop out of the list. Discovery
+//! runs these checks on every file it finds, and the problems are listed in
+//! the project issues popup (`I`): unknown keys, which mutagen rejects or
+//! silently ignores depending on where they are, specs without both
+//! endpoints, modes mutagen doesn't have, and malformed endpoint addresses.
+//!
+//! The search paths in `config.toml` and the workspace file are checked once
+//! at startup and listed there too, since a mistyped one otherwise just finds
+//! nothing. So is `config.toml` itself: a file that doesn't parse leaves
+//! mutagui on its default settings, and a misspelled setting is ignored.
+
+use serde_yaml::Value;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::endpoint::EndpointAddress;
+use crate::interpolate;
+use crate::project::{expand_path, is_compose_file, read_workspace, ProjectFile};
+use crate::wizard::{is_valid_name, MODES};
+
+/// Top-level keys of a project file.
+const PROJECT_KEYS: &[&str] = &[
+    "include",
+    "sync",
+    "commands",
+    "forward",
+    "beforeCreate",
+    "afterCreate",
+    "beforeTerminate",
+    "afterTerminate",
+];
+
+/// Settings a sync session (or `sync.defaults`) can have, besides its
+/// endpoints.
+const SESSION_KEYS: &[&str] = &[
+    "mode",
+    "hash",
+    "maxEntryCount",
+    "maxStagingFileSize",
+    "probeMode",
+    "scanMode",
+    "stageMode",
+    "symlink",
+    "watch",
+    "ignore",
+    "permissions",
+    "compression",
+    "configurationAlpha",
+    "configurationBeta",
+];
+
+/// Keys of an `ignore` mapping.
+const IGNORE_KEYS: &[&str] = &["paths", "vcs", "syntax"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Mutagen will refuse the file, or mutagui can't load it.
+    Error,
+    /// Probably a mistake, but the file still works.
+    Warning,
+}
+
+/// A problem found in a project file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectIssue {
+    pub path: PathBuf,
+    /// 1-based line the problem is on, when it can be pinned down.
+    pub line: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for ProjectIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match self.line {
+            Some(line) => write!(f, "{} (line {}): {}", severity, line, self.message),
+            None => write!(f, "{}: {}", severity, self.message),
+        }
+    }
+}
+
+/// Load the project file at `path`, with the problems found in it. A file
+/// that fails to load always has at least one error.
+pub fn load(path: &Path) -> (anyhow::Result<ProjectFile>, Vec<ProjectIssue>) {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            let issue = issue(path, None, Severity::Error, e.to_string());
+            return (Err(e.into()), vec![issue]);
+        }
+    };
+    let loaded = ProjectFile::from_contents(path.to_path_buf(), &contents);
+    let mut issues = lint(path, &contents);
+    if let Err(e) = &loaded {
+        // The checks usually say the same thing more precisely
+        if !issues.iter().any(|i| i.severity == Severity::Error) {
+            issues.insert(0, issue(path, None, Severity::Error, e.root_cause().to_string()));
+        }
+    }
+    (loaded, issues)
+}
+
+/// Check the project file at `path` with `contents`. YAML syntax errors
+/// aren't reported here; loading the file reports those.
+pub fn lint(path: &Path, contents: &str) -> Vec<ProjectIssue> {
+    let Ok(mut document) = serde_yaml::from_str::<Value>(contents) else {
+        return Vec::new();
+    };
+    let compose = is_compose_file(path);
+    if compose {
+        document = document.get("x-mutagen").cloned().unwrap_or_default();
+    }
+    let mut checker = Checker {
+        path,
+        lines: contents.lines().collect(),
+        root: if compose { &["x-mutagen"] } else { &[] },
+        issues: Vec::new(),
+    };
+    checker.project(&document);
+    // In file order, with issues that have no line first
+    checker.issues.sort_by_key(|issue| issue.line.unwrap_or(0));
+    checker.issues
+}
+
+/// The issues as text for the issues popup, grouped by file.
+pub fn format_issues(issues: &[ProjectIssue]) -> String {
+    let mut text = String::new();
+    let mut current: Option<&Path> = None;
+    for issue in issues {
+        if current != Some(issue.path.as_path()) {
+            if current.is_some() {
+                text.push('\n');
+            }
+            text.push_str(&format!("{}\n", issue.path.display()));
+            current = Some(&issue.path);
+        }
+        text.push_str(&format!("  {}\n", issue));
+    }
+    text
+}
+
+/// Problems with the `projects.search_paths` of the config file at
+/// `config_path` with `contents`: paths that don't exist, with the sibling
+/// directory they were probably meant to be, aren't directories, can't be
+/// read, use shell syntax that isn't expanded, or refer to environment
+/// variables that aren't set.
+pub fn check_search_paths(
+    config_path: &Path,
+    contents: &str,
+    search_paths: &[PathBuf],
+) -> Vec<ProjectIssue> {
+    let mut issues = Vec::new();
+    for path in search_paths {
+        let raw = path.to_string_lossy();
+        let line = contents
+            .lines()
+            .position(|l| l.contains(&format!("\"{}\"", raw)) || l.contains(&format!("'{}'", raw)))
+            .map(|index| index + 1);
+        let mut report = |message: String| {
+            issues.push(issue(config_path, line, Severity::Warning, message));
+        };
+        if raw.starts_with('~') && raw != "~" && !raw.starts_with("~/") {
+            report(format!("search path {}: only ~ and ~/ are expanded", raw));
+            continue;
+        }
+        let unset = interpolate::unset_variables(&raw);
+        if !unset.is_empty() {
+            report(format!("search path {}: {}", raw, describe_unset(&unset)));
+            continue;
+        }
+        let expanded = expand_path(path);
+        match fs::metadata(&expanded) {
+            Ok(metadata) if !metadata.is_dir() => {
+                report(format!("search path {} is not a directory", raw));
+            }
+            Ok(_) => {
+                if let Err(e) = fs::read_dir(&expanded) {
+                    report(format!("cannot read search path {}: {}", raw, e));
+                }
+            }
+            Err(_) => {
+                let mut message = format!("search path {} does not exist", raw);
+                if let Some(sibling) = similar_sibling(&expanded) {
+                    let raw_parent = Path::new(raw.as_ref()).parent().unwrap_or(Path::new(""));
+                    message.push_str(&format!(
+                        " (did you mean {}?)",
+                        raw_parent.join(sibling).display()
+                    ));
+                }
+                report(message);
+            }
+        }
+    }
+    issues
+}
+
+/// A workspace file at `path` that can't be read or parsed, in which case
+/// discovery searches for project files as if it weren't there.
+pub fn check_workspace(path: &Path) -> Vec<ProjectIssue> {
+    match read_workspace(path) {
+        Ok(_) => Vec::new(),
+        Err(e) => vec![issue(
+            path,
+            None,
+            Severity::Error,
+            format!("{} (searching for project files instead)", e.root_cause()),
+        )],
+    }
+}
+
+/// Problems with the config file at `config_path` with `contents`: a syntax
+/// error or a value of the wrong type, which leave mutagui on the default
+/// settings, or settings it doesn't know, which are ignored.
+pub fn check_config(config_path: &Path, contents: &str) -> Vec<ProjectIssue> {
+    let config = match toml::from_str::<Config>(contents) {
+        Ok(config) => config,
+        Err(e) => {
+            let line = e.span().map(|span| line_at(contents, span.start));
+            let message = e.message().trim().to_string();
+            return vec![issue(config_path, line, Severity::Error, message)];
+        }
+    };
+    // A setting that was read comes back when the config is written out
+    let (Ok(file), Ok(toml::Value::Table(read))) = (
+        contents.parse::<toml::Table>(),
+        toml::Value::try_from(&config),
+    ) else {
+        return Vec::new();
+    };
+    let mut issues = Vec::new();
+    unknown_settings(config_path, contents, "", &file, &read, &mut issues);
+    issues.sort_by_key(|issue| issue.line.unwrap_or(0));
+    issues
+}
+
+/// Report the settings in `file`, the table `prefix` of the config file,
+/// that aren't in `read`, the same table as the config read it.
+fn unknown_settings(
+    config_path: &Path,
+    contents: &str,
+    prefix: &str,
+    file: &toml::Table,
+    read: &toml::Table,
+    issues: &mut Vec<ProjectIssue>,
+) {
+    for (key, value) in file {
+        let dotted = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        // Spelled with a hyphen, some settings are read under an alias
+        match read.get(key).or_else(|| read.get(&key.replace('-', "_"))) {
+            Some(toml::Value::Table(read)) => {
+                if let toml::Value::Table(file) = value {
+                    unknown_settings(config_path, contents, &dotted, file, read, issues);
+                }
+            }
+            Some(_) => {}
+            None => {
+                let known: Vec<&str> = read.keys().map(String::as_str).collect();
+                let mut message = format!("unknown setting {}, which is ignored", dotted);
+                if let Some(name) = closest(key, &known) {
+                    message.push_str(&format!(" (did you mean {}?)", name));
+                }
+                let line = setting_line(contents, prefix, key);
+                issues.push(issue(config_path, line, Severity::Warning, message));
+            }
+        }
+    }
+}
+
+/// The 1-based line of the byte at `offset` in `contents`.
+fn line_at(contents: &str, offset: usize) -> usize {
+    contents[..offset.min(contents.len())].matches('\n').count() + 1
+}
+
+/// The 1-based line that sets `key` in the `[prefix]` table of a TOML
+/// document, or that starts `key`'s own table.
+fn setting_line(contents: &str, prefix: &str, key: &str) -> Option<usize> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let table = |name: &str| {
+        lines
+            .iter()
+            .position(|line| line.trim() == format!("[{}]", name))
+    };
+    let dotted = if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    };
+    if let Some(index) = table(&dotted) {
+        return Some(index + 1);
+    }
+    let start = if prefix.is_empty() {
+        0
+    } else {
+        table(prefix)? + 1
+    };
+    lines[start..]
+        .iter()
+        .take_while(|line| !line.trim_start().starts_with('['))
+        .position(|line| {
+            line.split_once('=')
+                .is_some_and(|(name, _)| name.trim().trim_matches('"') == key)
+        })
+        .map(|index| start + index + 1)
+}
+
+/// The problems found in the config file with `contents`, each followed by
+/// the line it is on.
+pub fn format_config_issues(issues: &[ProjectIssue], contents: &str) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut text = String::new();
+    for issue in issues {
+        text.push_str(&format!(
+            "{}
+",
+            issue
+        ));
+        if let Some(line) = issue.line.and_then(|line| lines.get(line - 1)) {
+            text.push_str(&format!(
+                "    {}
+",
+                line.trim()
+            ));
+        }
+    }
+    text
+}
+
+/// A directory beside the missing `path` whose name is close to its own.
+fn similar_sibling(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let siblings: Vec<String> = fs::read_dir(path.parent()?)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    let siblings: Vec<&str> = siblings.iter().map(String::as_str).collect();
+    closest(name, &siblings).map(str::to_string)
+}
+
+fn issue(path: &Path, line: Option<usize>, severity: Severity, message: String) -> ProjectIssue {
+    ProjectIssue {
+        path: path.to_path_buf(),
+        line,
+        severity,
+        message,
+    }
+}
+
+struct Checker<'a> {
+    path: &'a Path,
+    lines: Vec<&'a str>,
+    /// Keys of the section being checked: `x-mutagen` in a compose file.
+    root: &'static [&'static str],
+    issues: Vec<ProjectIssue>,
+}
+
+impl Checker<'_> {
+    fn report(&mut self, keys: &[&str], severity: Severity, message: String) {
+        let line = line_of(&self.lines, &[self.root, keys].concat());
+        self.issues.push(issue(self.path, line, severity, message));
+    }
+
+    fn project(&mut self, document: &Value) {
+        let Some(map) = document.as_mapping() else {
+            if !document.is_null() {
+                self.report(&[], Severity::Error, "expected a mapping of settings".into());
+            }
+            return;
+        };
+        self.unknown_keys(&[], map, PROJECT_KEYS);
+        self.include(map.get("include"));
+        match map.get("sync") {
+            None | Some(Value::Null) => {}
+            Some(Value::Mapping(sessions)) => {
+                for (name, session) in sessions {
+                    let Some(name) = name.as_str() else { continue };
+                    if name == "defaults" {
+                        self.settings(&["sync", name], session, false);
+                    } else {
+                        self.session(name, session);
+                    }
+                }
+            }
+            Some(_) => self.report(
+                &["sync"],
+                Severity::Error,
+                "sync: expected a mapping of session names to sessions".into(),
+            ),
+        }
+    }
+
+    fn include(&mut self, include: Option<&Value>) {
+        let paths = match include {
+            None | Some(Value::Null) => return,
+            Some(Value::String(path)) => vec![path.as_str()],
+            Some(Value::Sequence(paths)) if paths.iter().all(Value::is_string) => {
+                paths.iter().filter_map(Value::as_str).collect()
+            }
+            Some(_) => {
+                let message = "include: expected a file path or a list of them".into();
+                self.report(&["include"], Severity::Error, message);
+                return;
+            }
+        };
+        for path in paths {
+            let unset = interpolate::unset_variables(path);
+            if !unset.is_empty() {
+                let message = format!("include: {}", describe_unset(&unset));
+                self.report(&["include"], Severity::Warning, message);
+            }
+        }
+    }
+
+    fn session(&mut self, name: &str, session: &Value) {
+        let keys = ["sync", name];
+        if !is_valid_name(name) {
+            self.report(
+                &keys,
+                Severity::Error,
+                format!(
+                    "{}: session names must start with a letter and contain only \
+                     letters, digits, and hyphens",
+                    name
+                ),
+            );
+        }
+        if !session.is_mapping() {
+            self.report(&keys, Severity::Error, format!("{}: expected a mapping", name));
+            return;
+        }
+        for endpoint in ["alpha", "beta"] {
+            match session.get(endpoint) {
+                None => self.report(
+                    &keys,
+                    Severity::Error,
+                    format!("{}: missing {}", name, endpoint),
+                ),
+                Some(Value::String(address)) => {
+                    self.unset_variables(&["sync", name, endpoint], address);
+                    if let Some(problem) = endpoint_problem(&interpolate::expand(address)) {
+                        self.report(
+                            &["sync", name, endpoint],
+                            Severity::Error,
+                            format!("{}.{}: {}", name, endpoint, problem),
+                        );
+                    }
+                }
+                Some(_) => self.report(
+                    &["sync", name, endpoint],
+                    Severity::Error,
+                    format!("{}.{}: expected an endpoint address", name, endpoint),
+                ),
+            }
+        }
+        self.settings(&keys, session, true);
+    }
+
+    /// Check a session's settings, or the defaults' if not `with_endpoints`.
+    fn settings(&mut self, keys: &[&str], settings: &Value, with_endpoints: bool) {
+        let name = keys[keys.len() - 1];
+        let Some(map) = settings.as_mapping() else {
+            if !settings.is_null() {
+                self.report(keys, Severity::Error, format!("{}: expected a mapping", name));
+            }
+            return;
+        };
+        let mut known = SESSION_KEYS.to_vec();
+        if with_endpoints {
+            known.extend(["alpha", "beta"]);
+        }
+        self.unknown_keys(keys, map, &known);
+
+        if let Some(mode) = map.get("mode") {
+            if !mode.as_str().is_some_and(|mode| MODES.contains(&mode)) {
+                self.report(
+                    &[keys, &["mode"]].concat(),
+                    Severity::Error,
+                    format!(
+                        "{}.mode: {} is not a sync mode (expected one of {})",
+                        name,
+                        describe(mode),
+                        MODES.join(", ")
+                    ),
+                );
+            }
+        }
+        match map.get("ignore") {
+            None | Some(Value::Null) => {}
+            Some(Value::Sequence(paths)) => self.unset_in_paths(keys, paths),
+            Some(Value::Mapping(ignore)) => {
+                self.unknown_keys(&[keys, &["ignore"]].concat(), ignore, IGNORE_KEYS);
+                if let Some(Value::Sequence(paths)) = ignore.get("paths") {
+                    self.unset_in_paths(keys, paths);
+                }
+            }
+            Some(_) => self.report(
+                &[keys, &["ignore"]].concat(),
+                Severity::Error,
+                format!("{}.ignore: expected a list of paths or a mapping", name),
+            ),
+        }
+    }
+
+    /// Warn about variables in `text`, at `keys`, that aren't set. They're
+    /// left as written, which is seldom what was meant.
+    fn unset_variables(&mut self, keys: &[&str], text: &str) {
+        let unset = interpolate::unset_variables(text);
+        if !unset.is_empty() {
+            let setting = keys[1..].join(".");
+            let message = format!("{}: {}", setting, describe_unset(&unset));
+            self.report(keys, Severity::Warning, message);
+        }
+    }
+
+    fn unset_in_paths(&mut self, keys: &[&str], paths: &[Value]) {
+        for path in paths.iter().filter_map(Value::as_str) {
+            self.unset_variables(&[keys, &["ignore"]].concat(), path);
+        }
+    }
+
+    fn unknown_keys(&mut self, keys: &[&str], map: &serde_yaml::Mapping, known: &[&str]) {
+        for key in map.keys().filter_map(Value::as_str) {
+            if known.contains(&key) {
+                continue;
+            }
+            let prefix = keys[keys.len().min(1)..].join(".");
+            let qualified = if prefix.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            let hint = match closest(key, known) {
+                Some(suggestion) => format!(" (did you mean {}?)", suggestion),
+                None => String::new(),
+            };
+            self.report(
+                &[keys, &[key]].concat(),
+                Severity::Warning,
+                format!("unknown key {}{}", qualified, hint),
+            );
+        }
+    }
+}
+
+/// `names` of unset variables as a sentence fragment.
+fn describe_unset(names: &[String]) -> String {
+    let names: Vec<String> = names.iter().map(|name| format!("${{{}}}", name)).collect();
+    if names.len() == 1 {
+        format!("{} is not set", names[0])
+    } else {
+        format!("{} are not set", names.join(", "))
+    }
+}
+
+/// What's wrong with an endpoint address, if anything.
+fn endpoint_problem(address: &str) -> Option<String> {
+    if address.trim().is_empty() {
+        return Some("empty endpoint".into());
+    }
+    match EndpointAddress::parse(address) {
+        EndpointAddress::Ssh { user, host, .. } => {
+            if host.is_empty() {
+                Some(format!("no host in {}", address))
+            } else if user.as_deref() == Some("") {
+                Some(format!("empty user name in {}", address))
+            } else if address.starts_with("ssh://")
+                && address[6..]
+                    .split('/')
+                    .next()
+                    .filter(|authority| !authority.ends_with(']'))
+                    .and_then(|authority| authority.rsplit_once(':'))
+                    .is_some_and(|(_, port)| port.parse::<u16>().is_err())
+            {
+                Some(format!("invalid port in {}", address))
+            } else {
+                None
+            }
+        }
+        EndpointAddress::Docker { container, .. } if container.is_empty() => {
+            Some(format!("no container in {}", address))
+        }
+        EndpointAddress::Docker { .. } => None,
+        EndpointAddress::Local(_) => None,
+    }
+}
+
+/// A YAML value as it would be written, for messages.
+fn describe(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("\"{}\"", s),
+        other => serde_yaml::to_string(other)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// The known key a misspelled `key` most likely meant.
+fn closest<'a>(key: &str, known: &[&'a str]) -> Option<&'a str> {
+    known
+        .iter()
+        .map(|candidate| (edit_distance(&key.to_lowercase(), &candidate.to_lowercase()), *candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The 1-based line of the entry at `keys` (e.g. `sync`, `api`, `mode`),
+/// found by indentation: each key is looked for among the lines nested
+/// under the previous one.
+fn line_of(lines: &[&str], keys: &[&str]) -> Option<usize> {
+    let mut start = 0;
+    let mut end = lines.len();
+    let mut parent_indent: Option<usize> = None;
+    let mut found = None;
+    for key in keys {
+        let mut child_indent = None;
+        let index = (start..end).find(|&i| {
+            let line = lines[i];
+            let content = line.trim_start();
+            if content.is_empty() || content.starts_with('#') {
+                return false;
+            }
+            let indent = line.len() - content.len();
+            if parent_indent.is_some_and(|parent| indent <= parent) {
+                return false;
+            }
+            // Only direct children: the first entry sets their indentation
+            if *child_indent.get_or_insert(indent) != indent {
+                return false;
+            }
+            let name = content.split(':').next().unwrap_or_default();
+            name.trim().trim_matches(|c| c == '"' || c == '\'') == *key
+        })?;
+        let indent = lines[index].len() - lines[index].trim_start().len();
+        start = index + 1;
+        end = (start..lines.len())
+            .find(|&i| {
+                let content = lines[i].trim_start();
+                !content.is_empty()
+                    && !content.starts_with('#')
+                    && lines[i].len() - content.len() <= indent
+            })
+            .unwrap_or(lines.len());
+        parent_indent = Some(indent);
+        found = Some(index + 1);
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(contents: &str) -> Vec<String> {
+        lint(Path::new("mutagen.yml"), contents)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_file_has_no_issues() {
+        let contents = "\
+sync:
+  defaults:
+    mode: one-way-safe
+    ignore:
+      vcs: true
+  api:
+    alpha: ./api
+    beta: user@server:/srv/api
+    ignore: [target]
+forward:
+  web:
+    source: tcp:localhost:8080
+    destination: server:tcp::80
+";
+        assert_eq!(messages(contents), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_problems_are_reported_with_lines() {
+        let contents = "\
+sync:
+  defaults:
+    mdoe: two-way-safe
+  api:
+    alpha: ./api
+    mode: two-way
+  web:
+    alpha: ./web
+    beta: docker:///srv
+    ignore:
+      path: [node_modules]
+afterStart: [echo]
+";
+        assert_eq!(
+            messages(contents),
+            [
+                "warning (line 3): unknown key defaults.mdoe (did you mean mode?)",
+                "error (line 4): api: missing beta",
+                "error (line 6): api.mode: \"two-way\" is not a sync mode (expected one of \
+                 two-way-safe, two-way-resolved, one-way-safe, one-way-replica)",
+                "error (line 9): web.beta: no container in docker:///srv",
+                "warning (line 11): unknown key web.ignore.path (did you mean paths?)",
+                "warning (line 12): unknown key afterStart",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_path_problems() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        std::fs::create_dir(base.join("projects")).unwrap();
+        std::fs::write(base.join("notes.txt"), "").unwrap();
+        let paths = [
+            base.join("projects"),
+            base.join("projcts"),
+            base.join("notes.txt"),
+            PathBuf::from("~bob/code"),
+            PathBuf::from("$MUTAGUI_UNSET_VAR/code"),
+        ];
+        let contents: String = paths
+            .iter()
+            .map(|path| format!("  \"{}\",\n", path.display()))
+            .collect();
+        let contents = format!("[projects]\nsearch_paths = [\n{}]\n", contents);
+
+        let messages: Vec<String> = check_search_paths(Path::new("config.toml"), &contents, &paths)
+            .iter()
+            .map(|issue| {
+                issue
+                    .to_string()
+                    .replace(&base.display().to_string(), "<dir>")
+            })
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "warning (line 4): search path <dir>/projcts does not exist \
+                 (did you mean <dir>/projects?)",
+                "warning (line 5): search path <dir>/notes.txt is not a directory",
+                "warning (line 6): search path ~bob/code: only ~ and ~/ are expanded",
+                "warning (line 7): search path $MUTAGUI_UNSET_VAR/code: \
+                 ${MUTAGUI_UNSET_VAR} is not set",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_config_problems() {
+        let path = Path::new("config.toml");
+        let contents = "[refresh]\ninterval_secs = \"5\"\n";
+        let issues = check_config(path, contents);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].line, Some(2));
+        assert_eq!(
+            format_config_issues(&issues, contents),
+            "error (line 2): invalid type: string \"5\", expected u64\n    interval_secs = \"5\"\n"
+        );
+
+        let contents = "theme = \"dark\"\n[ui]\nthem = \"dark\"\n[ui.columns]\nlast-sync = true\n\
+                        [ui.colors]\nheader_fg = \"#268bd2\"\n[refesh]\ninterval_secs = 5\n";
+        let messages: Vec<String> = check_config(path, contents)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "warning (line 1): unknown setting theme, which is ignored",
+                "warning (line 3): unknown setting ui.them, which is ignored (did you mean theme?)",
+                "warning (line 8): unknown setting refesh, which is ignored (did you mean refresh?)",
+            ]
+        );
+        assert!(check_config(path, "").is_empty());
+
+        let contents = "[mutagen]\nsession_prefix = \"mutagui/\"\n";
+        let issues = check_config(path, contents);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, Some(2));
+        assert!(issues[0].message.contains("\"mutagui/\" must be a letter"));
+        assert!(check_config(path, "[mutagen]\nsession_prefix = \"mutagui-\"\n").is_empty());
+    }
+
+    #[test]
+    fn test_unset_variables() {
+        let contents = "include: [shared.yml, $MUTAGUI_UNSET_DIR/base.yml]\n\
+                        sync:\n  \
+                        api:\n    \
+                        alpha: ${HOME}/api\n    \
+                        beta: $MUTAGUI_UNSET_HOST:/srv/api\n    \
+                        ignore:\n      \
+                        paths: [\"${MUTAGUI_UNSET_DIR}\"]\n";
+        assert_eq!(
+            messages(contents),
+            [
+                "warning (line 1): include: ${MUTAGUI_UNSET_DIR} is not set",
+                "warning (line 5): api.beta: ${MUTAGUI_UNSET_HOST} is not set",
+                "warning (line 6): api.ignore: ${MUTAGUI_UNSET_DIR} is not set",
+            ]
+        );
+        assert_eq!(
+            messages("include: {file: shared.yml}\n"),
+            ["error (line 1): include: expected a file path or a list of them"]
+        );
+    }
+
+    #[test]
+    fn test_endpoint_problems() {
+        assert_eq!(endpoint_problem("server:/srv"), None);
+        assert_eq!(endpoint_problem("ssh://server:2222/srv"), None);
+        assert_eq!(endpoint_problem(":/srv"), Some("no host in :/srv".to_string()));
+        assert_eq!(
+            endpoint_problem("ssh://server:http/srv"),
+            Some("invalid port in ssh://server:http/srv".to_string())
+        );
+        assert_eq!(endpoint_problem(" "), Some("empty endpoint".to_string()));
+    }
+
+    #[test]
+    fn test_load_reports_why_a_file_failed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mutagen.yml");
+
+        // The missing endpoint explains the parse failure
+        fs::write(&path, "sync:\n  api:\n    alpha: ./api\n").unwrap();
+        let (loaded, issues) = load(&path);
+        assert!(loaded.is_err());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].message, "api: missing beta");
+
+        // A syntax error is reported as is
+        fs::write(&path, "sync:\n  api:\n    alpha: [unclosed\n").unwrap();
+        let (loaded, issues) = load(&path);
+        assert!(loaded.is_err());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+    }
+}
@@ -0,0 +1,33 @@
+//! Diagnostic log file.
+//!
+//! When `--log-file` or `log.path` is set, every mutagen, ssh, and docker
+//! invocation is logged with its arguments, duration, and exit status,
+//! nested under the operation (refresh, pause, verify, ...) that ran it.
+//! Without a log file, tracing events are discarded.
+
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::level_filters::LevelFilter;
+
+/// Send tracing output to `path`, appending to it if it exists.
+pub fn init(path: &Path) -> Result<()> {
+    let path = crate::project::expand_tilde_in_path(path);
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open log file {}", path.display()))?;
+
+    tracing_subscriber::fmt()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_max_level(LevelFilter::DEBUG)
+        .try_init()
+        .map_err(|e| anyhow::anyhow!("Failed to initialize logging: {}", e))
+}
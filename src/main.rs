@@ -1,19 +1,58 @@
+mod a11y;
 mod app;
+mod audit;
+mod bookmarks;
+mod browse;
+mod clipboard;
 mod command;
 mod config;
+#[cfg(unix)]
+mod control;
+mod cycles;
+mod daemon;
+mod disk;
 mod endpoint;
+mod favorites;
+mod health;
+mod hosts;
+mod i18n;
+mod icons;
+mod ignores;
+mod init;
+mod input;
+mod interpolate;
 mod keys;
+mod lint;
+mod logging;
+mod metrics;
 mod mutagen;
+mod notify;
+mod operations;
+mod origin;
+mod progress;
 mod project;
+mod resolution;
+mod schedule;
 mod selection;
+mod spec_edit;
+mod ssh_config;
+mod sync_history;
+mod tabs;
+mod task;
 mod theme;
+mod transfer;
 mod ui;
+mod verify;
+mod watch;
 mod widgets;
+mod wizard;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use app::App;
-use clap::Parser;
+use icons::Icons;
+use clap::{Parser, Subcommand};
 use crossterm::{
+    cursor,
     event::{self, DisableMouseCapture, EnableMouseCapture, Event},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -21,6 +60,7 @@ use crossterm::{
 use keys::KeyAction;
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -31,30 +71,149 @@ struct Cli {
     /// Directory to search for mutagen project files (default: current directory)
     #[arg(short = 'd', long, value_name = "DIR")]
     project_dir: Option<PathBuf>,
+
+    /// Append a log of mutagen and ssh invocations with their durations to FILE
+    #[arg(long, value_name = "FILE", global = true)]
+    log_file: Option<PathBuf>,
+
+    /// Select a project or spec on launch: PROJECT, PROJECT:SPEC, or :SPEC
+    #[arg(long, value_name = "PROJECT[:SPEC]")]
+    select: Option<String>,
+
+    /// With --select, also open the spec's conflict view if it has conflicts
+    #[arg(long)]
+    open_conflicts: bool,
+
+    /// Plain line-oriented interface for screen readers: no box drawing or
+    /// glyphs, state changes announced as lines of text
+    #[arg(long)]
+    a11y: bool,
+
+    /// Draw icons as plain ASCII, for terminals that garble the Unicode symbols
+    #[arg(long)]
+    ascii: bool,
+
+    /// Show the mutagen commands actions would run instead of running them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Turn off the keys that start, stop, or change sessions, for a shared dashboard
+    #[arg(long)]
+    read_only: bool,
+
+    /// Serve Prometheus gauges for each session at http://ADDR/metrics
+    #[arg(long, value_name = "ADDR", global = true)]
+    metrics_addr: Option<SocketAddr>,
+
+    /// Take JSON-RPC requests to list or act on projects on a Unix socket at PATH
+    #[arg(long, value_name = "PATH")]
+    control_socket: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Monitor sessions without a TUI, sending notifications on conflicts, disconnects, and stalls
+    Daemon {
+        /// Seconds between refreshes (default: refresh.interval_secs from the config file)
+        #[arg(long, value_name = "SECS")]
+        interval: Option<u64>,
+    },
+    /// Write a starter config.toml and an example mutagen.yml in the project directory
+    Init,
+    /// Work with the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Report syntax errors and unknown settings in config.toml, with their lines
+    Check,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    let config = config::Config::load().ok();
+    let log_file = cli
+        .log_file
+        .clone()
+        .or_else(|| config.as_ref().and_then(|c| c.log.path.clone()));
+    if let Some(path) = log_file {
+        logging::init(&path)?;
+    }
+    i18n::init(config.and_then(|c| c.ui.locale).as_deref());
+
+    let metrics = cli
+        .metrics_addr
+        .map(metrics::MetricsServer::start)
+        .transpose()?;
+
+    match cli.command {
+        Some(Commands::Daemon { interval }) => {
+            return daemon::run(cli.project_dir, interval.map(Duration::from_secs), metrics).await;
+        }
+        Some(Commands::Init) => return init::run(cli.project_dir),
+        Some(Commands::Config {
+            command: ConfigCommand::Check,
+        }) => return check_config(),
+        None => {}
+    }
+
+    if cli.a11y {
+        return a11y::run(cli.project_dir).await;
+    }
+
+    install_panic_hook();
+    let guard = TerminalGuard::enter()?;
 
-    let backend = CrosstermBackend::new(stdout);
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
     let mut app = App::new(cli.project_dir);
+    if cli.ascii {
+        app.icons = Icons::new(true);
+        app.record_flag("ui.ascii", true);
+    }
+    if let Some(path) = &cli.log_file {
+        app.record_flag("log.path", path.display().to_string());
+    }
+    if let Some(select) = &cli.select {
+        app.record_flag("startup.select", select.as_str());
+    }
+    if cli.open_conflicts {
+        app.record_flag("startup.open_conflicts", true);
+    }
+    if let Some(metrics) = metrics {
+        app.record_flag("metrics.addr", metrics.addr().to_string());
+        app.metrics = Some(metrics);
+    }
+    if let Some(path) = &cli.control_socket {
+        #[cfg(not(unix))]
+        anyhow::bail!(
+            "Cannot listen on {}: --control-socket needs Unix domain sockets",
+            path.display()
+        );
+        #[cfg(unix)]
+        {
+            app.control = Some(control::ControlServer::start(path)?);
+            app.record_flag("control.socket", path.display().to_string());
+        }
+    }
+    if cli.dry_run {
+        app.mutagen_client.set_dry_run(true);
+    }
+    app.read_only = cli.read_only;
 
-    let res = run_app(&mut terminal, &mut app).await;
+    let res = run_app(&mut terminal, &mut app, cli.select, cli.open_conflicts).await;
 
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    drop(guard);
 
     if let Err(err) = res {
         eprintln!("Error: {:?}", err);
@@ -63,22 +222,99 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// `mutagui config check`: print the problems with the config file, and
+/// exit with status 1 if there are any.
+fn check_config() -> Result<()> {
+    let path = config::Config::config_path().context("No config directory")?;
+    if !path.exists() {
+        println!("{} doesn't exist; the defaults are used", path.display());
+        return Ok(());
+    }
+    let issues = config::Config::check();
+    if issues.is_empty() {
+        println!("{}: no problems found", path.display());
+        return Ok(());
+    }
+    println!("{}\n", path.display());
+    print!("{}", lint::format_config_issues(&issues, &config::Config::file_contents()));
+    std::process::exit(1);
+}
+
+/// Puts the terminal in raw mode on the alternate screen with mouse capture,
+/// and restores it when dropped, including on early returns and panics.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        enable_raw_mode()?;
+        // Constructed first so a failure below still leaves raw mode
+        let guard = Self;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+/// Leave raw mode, the alternate screen, and mouse capture, and show the cursor.
+/// Safe to call more than once.
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        cursor::Show
+    );
+}
+
+/// Restore the terminal before the panic message is printed, so it lands on
+/// the normal screen instead of vanishing with the alternate one.
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
+}
+
 async fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut App,
+    select: Option<String>,
+    open_conflicts: bool,
 ) -> Result<()> {
+    app.select_on_startup(select, open_conflicts);
     app.refresh_sessions().await?;
 
     loop {
         terminal.draw(|f| ui::draw(f, app))?;
 
-        if event::poll(Duration::from_millis(100))? {
-            match event::read()? {
+        // Keys typed during a blocking operation come first
+        let typeahead = app
+            .typeahead
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front();
+        let event = match typeahead {
+            Some(key) => Some(Event::Key(key)),
+            None if event::poll(Duration::from_millis(100))? => Some(event::read()?),
+            None => None,
+        };
+        if let Some(event) = event {
+            match event {
                 Event::Key(key) => match keys::handle_key_event(key, app, terminal).await? {
                     KeyAction::Quit => break,
                     KeyAction::Refresh => {
                         app.refresh_sessions().await?;
                     }
+                    KeyAction::RefreshSelected => {
+                        app.refresh_selected_project().await?;
+                    }
                     KeyAction::Continue => {}
                 },
                 Event::Resize(_, _) => {
@@ -89,8 +325,17 @@ async fn run_app<B: ratatui::backend::Backend>(
                 }
             }
         } else if app.should_auto_refresh() {
-            let _ = app.refresh_sessions().await;
+            let _ = app.auto_refresh().await;
+        } else if app.should_refresh_slow_sources() {
+            app.refresh_slow_sources().await;
         }
+        app.show_dry_run_commands();
+        app.reload_changed_project_files().await;
+        app.finish_discovery().await;
+        app.poll_task();
+        app.poll_notifications();
+        #[cfg(unix)]
+        control::poll(app).await;
 
         if app.should_quit {
             break;
@@ -0,0 +1,185 @@
+//! Prometheus metrics (`--metrics-addr`).
+//!
+//! After each refresh the session gauges are rendered in the Prometheus text
+//! format, and a small HTTP server on a background thread serves the latest
+//! rendering at `/metrics`, so sync health can be alerted on from an existing
+//! monitoring stack.
+
+use crate::mutagen::{StagingProgress, SyncSession};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Gauges, with their help text and the value of each session.
+type Gauge = (&'static str, &'static str, fn(&SyncSession) -> u64);
+
+const GAUGES: [Gauge; 5] = [
+    ("mutagui_session_conflicts", "Number of conflicts.", |s| {
+        s.total_conflicts()
+    }),
+    (
+        "mutagui_session_paused",
+        "1 if the session is paused.",
+        |s| s.paused as u64,
+    ),
+    (
+        "mutagui_session_connected",
+        "1 if both endpoints are connected.",
+        |s| (s.alpha.connected && s.beta.connected) as u64,
+    ),
+    (
+        "mutagui_session_staged_bytes",
+        "Bytes received by the staging in progress.",
+        |s| {
+            s.staging_progress()
+                .and_then(StagingProgress::bytes_received)
+                .unwrap_or(0)
+        },
+    ),
+    (
+        "mutagui_session_successful_cycles",
+        "Synchronization cycles completed.",
+        |s| s.successful_cycles.unwrap_or(0),
+    ),
+];
+
+/// Render `sessions` in the Prometheus text format, labeled with their name,
+/// identifier, and the project they belong to from `projects`, by identifier.
+pub fn render(sessions: &[SyncSession], projects: &HashMap<String, String>) -> String {
+    let mut text = String::new();
+    for (name, help, value) in GAUGES {
+        let _ = writeln!(text, "# HELP {} {}", name, help);
+        let _ = writeln!(text, "# TYPE {} gauge", name);
+        for session in sessions {
+            let project = projects.get(&session.identifier).map_or("", String::as_str);
+            let _ = writeln!(
+                text,
+                "{}{{session=\"{}\",identifier=\"{}\",project=\"{}\"}} {}",
+                name,
+                escape_label(&session.name),
+                escape_label(&session.identifier),
+                escape_label(project),
+                value(session)
+            );
+        }
+    }
+    text
+}
+
+/// Escape a label value: backslashes, quotes, and newlines.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serves the latest rendering of the metrics until the program exits.
+#[derive(Debug)]
+pub struct MetricsServer {
+    addr: SocketAddr,
+    body: Arc<Mutex<String>>,
+}
+
+impl MetricsServer {
+    /// Listen on `addr` and serve `/metrics` from a background thread.
+    pub fn start(addr: SocketAddr) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .with_context(|| format!("Failed to listen for metrics on {}", addr))?;
+        let addr = listener.local_addr()?;
+        let body = Arc::new(Mutex::new(String::new()));
+        let served = Arc::clone(&body);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Err(e) = respond(stream, &served) {
+                    tracing::debug!(error = %e, "metrics request failed");
+                }
+            }
+        });
+        Ok(Self { addr, body })
+    }
+
+    /// The address being listened on, with the port chosen if it was 0.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Replace what `/metrics` serves.
+    pub fn update(&self, text: String) {
+        if let Ok(mut body) = self.body.lock() {
+            *body = text;
+        }
+    }
+}
+
+/// Answer one request: the metrics for `GET /metrics`, 404 for anything else.
+fn respond(mut stream: TcpStream, body: &Mutex<String>) -> Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => {
+            ("200 OK", body.lock().map(|b| b.clone()).unwrap_or_default())
+        }
+        _ => ("404 Not Found", "Not found\n".to_string()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn session(name: &str, paused: bool) -> SyncSession {
+        SyncSession::named(name)
+            .with_paused(paused)
+            .with_beta_connected(!paused)
+            .with_cycles(7)
+            .with_conflicts(&["a.txt"])
+    }
+
+    #[test]
+    fn test_render_and_serve() {
+        let sessions = [session("api", false), session("web\"", true)];
+        let projects = HashMap::from([("sync_api".to_string(), "app".to_string())]);
+        let text = render(&sessions, &projects);
+        assert!(text.contains("# TYPE mutagui_session_paused gauge\n"));
+        assert!(text.contains(
+            "mutagui_session_connected{session=\"api\",identifier=\"sync_api\",project=\"app\"} 1\n"
+        ));
+        assert!(text.contains(
+            "mutagui_session_paused{session=\"web\\\"\",identifier=\"sync_web\\\"\",project=\"\"} 1\n"
+        ));
+        assert!(text.contains("mutagui_session_successful_cycles{session=\"api\","));
+
+        let server = MetricsServer::start("127.0.0.1:0".parse().unwrap()).unwrap();
+        server.update(text.clone());
+        let addr = server.addr();
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = get("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(&text));
+        assert!(get("/").starts_with("HTTP/1.1 404"));
+    }
+}
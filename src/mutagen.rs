@@ -1,27 +1,145 @@
-use crate::command::{CommandRunner, SystemCommandRunner};
-use crate::project::ProjectFile;
+use crate::browse::{self, DirEntry};
+use crate::command::{decode_message, decode_output, CommandRunner, SystemCommandRunner};
+use crate::config::MutagenConfig;
+use crate::disk::{self, DiskSpace};
+use crate::endpoint::{is_drive_path, DockerConnection, EndpointAddress};
+use crate::i18n::t;
+use crate::operations::OperationJournal;
+use crate::origin::Origin;
+use crate::project::{is_compose_file, ProjectFile};
+use crate::ssh_config::SshConfig;
+use crate::verify::{self, Ignores, VerifyMode, VerifyReport};
 use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use shell_escape::escape;
 use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// How long a project file's hook command may run, such as a build before
+/// the sessions are created.
+const HOOK_TIMEOUT_SECS: u64 = 300;
+
+/// How long `mutagen-compose up` or `down` may take, which includes
+/// pulling images and starting containers.
+const COMPOSE_TIMEOUT_SECS: u64 = 300;
+
+/// How long a reachability check waits for an ssh host to answer.
+const SSH_CHECK_TIMEOUT_SECS: u64 = 5;
 
 /// Get the lock file path for a Mutagen project file.
 /// Mutagen creates a `.lock` file with the same name as the project file
 /// (e.g., `project.yml.lock` for `project.yml`).
-fn get_project_lock_path(project_file: &Path) -> PathBuf {
+pub fn get_project_lock_path(project_file: &Path) -> PathBuf {
     let mut lock_path = project_file.as_os_str().to_owned();
     lock_path.push(".lock");
     PathBuf::from(lock_path)
 }
 
+/// The label `mutagen project start` gives the sessions it creates, with
+/// the project's identifier as its value.
+pub const PROJECT_LABEL: &str = "io.mutagen.project";
+
+/// A project lock file left behind with none of the project's sessions
+/// running, which makes `mutagen project start` say the project is already
+/// running. Mutagen writes the project's identifier into the lock, not the
+/// pid of the command holding it, so it can't be told apart from a start
+/// still in progress without asking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleLock {
+    pub path: PathBuf,
+    /// The project identifier written in the lock.
+    pub identifier: String,
+    /// How long ago the lock was last written.
+    pub age: Option<Duration>,
+}
+
+impl StaleLock {
+    fn read(path: PathBuf) -> Option<Self> {
+        let identifier = std::fs::read_to_string(&path).ok()?.trim().to_string();
+        let age = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok());
+        Some(Self {
+            path,
+            identifier,
+            age,
+        })
+    }
+}
+
+impl std::fmt::Display for StaleLock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "project already running, but none of its sessions are: {} may be stale",
+            self.path.display()
+        )
+    }
+}
+
+impl std::error::Error for StaleLock {}
+
+/// `program` and `args` as a command line that can be pasted into a shell.
+fn command_line(program: &str, args: &[&str]) -> String {
+    std::iter::once(program)
+        .chain(args.iter().copied())
+        .map(|arg| escape(Cow::Borrowed(arg)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The command that makes the directory `path` on an ssh host, and its
+/// parents. A Windows path (`C:\work`, or `/C:/work` from an ssh URL) gets
+/// PowerShell, which also runs from cmd, the default shell of Windows'
+/// OpenSSH server. Other paths get `mkdir -p`.
+fn remote_mkdir_command(path: &str) -> String {
+    let windows_path = path.strip_prefix('/').filter(|p| is_drive_path(p));
+    match windows_path.or(Some(path).filter(|p| is_drive_path(p))) {
+        Some(path) => {
+            let script = format!(
+                "New-Item -ItemType Directory -Force -Path '{}' | Out-Null",
+                path.replace('\'', "''")
+            );
+            format!("powershell -NoProfile -Command \"{}\"", script)
+        }
+        None => format!("mkdir -p {}", escape(Cow::Borrowed(path))),
+    }
+}
+
+/// Failures that may pass if the command is tried again: mutagen couldn't
+/// reach a daemon that is still starting, or the command took too long.
+const TRANSIENT_FAILURES: &[&str] = &["unable to connect to daemon", "timed out"];
+
+/// Whether a failure with `message` may pass on its own.
+fn is_transient(message: &str) -> bool {
+    TRANSIENT_FAILURES
+        .iter()
+        .any(|failure| message.contains(failure))
+}
+
 /// Returns true if any running Mutagen sessions belong to the specified project file.
-/// Matches sessions by name (including "-push" variants) to avoid deleting lock files
-/// for unrelated projects.
-fn project_has_running_sessions(project_file: &Path, sessions: &[SyncSession]) -> bool {
+/// Matches sessions by the project identifier they're labeled with, or by name
+/// (including "-push" variants), to avoid deleting lock files for unrelated projects.
+fn project_has_running_sessions(
+    project_file: &Path,
+    identifier: &str,
+    sessions: &[SyncSession],
+) -> bool {
     if sessions.is_empty() {
         return false;
     }
+    if sessions
+        .iter()
+        .any(|session| session.labels.get(PROJECT_LABEL).map(String::as_str) == Some(identifier))
+    {
+        return true;
+    }
 
     match ProjectFile::from_path(project_file.to_path_buf()) {
         Ok(project_file) => sessions.iter().any(|session| {
@@ -37,29 +155,29 @@ fn project_has_running_sessions(project_file: &Path, sessions: &[SyncSession]) -
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub enum SyncTime {
-    Never,   // Brand new session, no syncs yet
+    Never, // Brand new session, no syncs yet
     #[default]
     Unknown, // Pre-existing session, sync history unknown
-    At,      // Observed sync (timestamp not tracked)
+    At(DateTime<Local>), // When a completed sync was last seen
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FileState {
     pub kind: String,
     #[serde(default)]
     pub digest: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Change {
     pub path: String,
     pub old: Option<FileState>,
     pub new: Option<FileState>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Conflict {
     pub root: String,
     #[serde(rename = "alphaChanges", default)]
@@ -68,7 +186,14 @@ pub struct Conflict {
     pub beta_changes: Vec<Change>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A path an endpoint couldn't scan or change, and why.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Problem {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StagingProgress {
     #[serde(default)]
     pub path: Option<String>,
@@ -84,13 +209,24 @@ pub struct StagingProgress {
     pub total_received_size: Option<u64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl StagingProgress {
+    /// Bytes received so far, preferring the running total across files.
+    pub fn bytes_received(&self) -> Option<u64> {
+        self.total_received_size.or(self.received_size)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Endpoint {
     pub protocol: String,
     pub path: String,
     #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
     pub host: Option<String>,
     #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
     pub connected: bool,
     #[serde(default)]
     pub scanned: bool,
@@ -104,6 +240,20 @@ pub struct Endpoint {
     pub total_file_size: Option<u64>,
     #[serde(rename = "stagingProgress", default)]
     pub staging_progress: Option<StagingProgress>,
+    #[serde(rename = "scanProblems", default)]
+    pub scan_problems: Vec<Problem>,
+    /// Scan problems left out of `scan_problems` because there were too many.
+    #[serde(rename = "excludedScanProblems", default)]
+    pub excluded_scan_problems: u64,
+    #[serde(rename = "transitionProblems", default)]
+    pub transition_problems: Vec<Problem>,
+    /// Transition problems left out of `transition_problems`.
+    #[serde(rename = "excludedTransitionProblems", default)]
+    pub excluded_transition_problems: u64,
+    /// Environment mutagen recorded when the session was created, such as
+    /// the `DOCKER_HOST` or `DOCKER_CONTEXT` a Docker endpoint was reached with.
+    #[serde(default)]
+    pub environment: BTreeMap<String, String>,
 }
 
 impl Endpoint {
@@ -116,6 +266,15 @@ impl Endpoint {
         }
     }
 
+    /// Like `display_path`, but shows the ssh config alias for the host if one exists.
+    pub fn display_path_aliased(&self, ssh_config: &SshConfig) -> String {
+        let path = self.path_with_tilde();
+        match &self.host {
+            Some(host) => format!("{}:{}", ssh_config.display_host(host), path),
+            None => path,
+        }
+    }
+
     /// Replace home directory prefix with ~ for display
     fn path_with_tilde(&self) -> String {
         if let Ok(home) = std::env::var("HOME") {
@@ -126,7 +285,7 @@ impl Endpoint {
         self.path.clone()
     }
 
-    pub fn status_icon(&self) -> &str {
+    pub fn status_icon(&self) -> &'static str {
         if !self.connected {
             "⊗"
         } else if !self.scanned {
@@ -137,7 +296,90 @@ impl Endpoint {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Whether an ssh host answered a connection check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostReachability {
+    Reachable,
+    /// The host answered but wants a password, key, or host key confirmation
+    /// that a batch connection can't give. Mutagen may still connect, since
+    /// it can prompt for these.
+    NeedsAuthentication(String),
+    /// The host couldn't be reached at all, with ssh's explanation.
+    Unreachable(String),
+}
+
+impl HostReachability {
+    /// Classify the message a failed `ssh -o BatchMode=yes` printed.
+    fn from_ssh_error(stderr: &str) -> Self {
+        // The last line is ssh's own; earlier ones may be warnings or banners
+        let message = stderr.lines().last().unwrap_or_default().to_string();
+        let answered = [
+            "Permission denied",
+            "Host key verification failed",
+            "passphrase",
+        ]
+        .iter()
+        .any(|marker| stderr.contains(marker));
+        if answered {
+            Self::NeedsAuthentication(message)
+        } else if message.is_empty() {
+            Self::Unreachable("ssh failed".to_string())
+        } else {
+            Self::Unreachable(message)
+        }
+    }
+
+    pub fn is_unreachable(&self) -> bool {
+        matches!(self, Self::Unreachable(_))
+    }
+}
+
+/// A safety check on the sync root that made mutagen halt a session rather
+/// than propagate a change that would wipe out the other endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafetyHalt {
+    Emptied,
+    Deleted,
+    TypeChanged,
+}
+
+impl SafetyHalt {
+    /// Parse a session status, either the JSON code ("halted-on-root-deletion")
+    /// or its description ("Halted due to root deletion").
+    pub fn from_status(status: &str) -> Option<Self> {
+        let status = status.to_lowercase();
+        if !status.contains("halted") {
+            None
+        } else if status.contains("empt") {
+            Some(Self::Emptied)
+        } else if status.contains("deletion") {
+            Some(Self::Deleted)
+        } else if status.contains("type") {
+            Some(Self::TypeChanged)
+        } else {
+            None
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Emptied => t!("view.halt_emptied"),
+            Self::Deleted => t!("view.halt_deleted"),
+            Self::TypeChanged => t!("view.halt_type_changed"),
+        }
+    }
+
+    /// What happened, for the status bar and the reset confirmation.
+    pub fn explanation(self) -> &'static str {
+        match self {
+            Self::Emptied => t!("view.halt_emptied_explanation"),
+            Self::Deleted => t!("view.halt_deleted_explanation"),
+            Self::TypeChanged => t!("view.halt_type_changed_explanation"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SyncSession {
     pub name: String,
     pub identifier: String,
@@ -153,6 +395,14 @@ pub struct SyncSession {
     pub successful_cycles: Option<u64>,
     #[serde(default)]
     pub conflicts: Vec<Conflict>,
+    /// Conflicts left out of `conflicts` because there were too many to list.
+    #[serde(rename = "excludedConflicts", default)]
+    pub excluded_conflicts: u64,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// The most recent error mutagen reported for the session, if any.
+    #[serde(rename = "lastError", default)]
+    pub last_error: Option<String>,
     #[serde(skip, default)]
     pub sync_time: SyncTime,
 }
@@ -166,18 +416,80 @@ impl SyncSession {
         self.conflicts.len()
     }
 
+    /// Conflicts, including those mutagen left out of the list.
+    pub fn total_conflicts(&self) -> u64 {
+        self.conflicts.len() as u64 + self.excluded_conflicts
+    }
+
+    /// Root paths of the current conflicts.
+    pub fn conflict_roots(&self) -> Vec<String> {
+        self.conflicts.iter().map(|c| c.root.clone()).collect()
+    }
+
+    /// Scan and transition problems on both endpoints.
+    pub fn problems(&self) -> impl Iterator<Item = &Problem> {
+        [&self.alpha, &self.beta].into_iter().flat_map(|endpoint| {
+            endpoint
+                .scan_problems
+                .iter()
+                .chain(&endpoint.transition_problems)
+        })
+    }
+
+    /// Problems mutagen left out of the endpoints' lists.
+    pub fn excluded_problems(&self) -> u64 {
+        [&self.alpha, &self.beta]
+            .iter()
+            .map(|endpoint| endpoint.excluded_scan_problems + endpoint.excluded_transition_problems)
+            .sum()
+    }
+
     pub fn alpha_display(&self) -> String {
         self.alpha.display_path()
     }
 
-    pub fn beta_display(&self) -> String {
-        self.beta.display_path()
+    /// Beta endpoint for display, using the ssh config alias for its host.
+    pub fn beta_display(&self, ssh_config: &SshConfig) -> String {
+        self.beta.display_path_aliased(ssh_config)
+    }
+
+    /// When the session was created, from Mutagen's RFC 3339 `creationTime`.
+    pub fn created_at(&self) -> Option<DateTime<Local>> {
+        let created = self.creation_time.as_deref()?;
+        DateTime::parse_from_rfc3339(created)
+            .ok()
+            .map(|at| at.with_timezone(&Local))
+    }
+
+    /// The machine and user that created the session, if mutagui did.
+    pub fn origin(&self) -> Option<Origin> {
+        Origin::from_labels(&self.labels)
+    }
+
+    /// Whether the session needs attention: it has conflicts or an error, or
+    /// a safety check halted it.
+    pub fn has_problem(&self) -> bool {
+        self.has_conflicts() || self.last_error.is_some() || self.safety_halt().is_some()
+    }
+
+    /// The safety check the session is halted on, if any.
+    pub fn safety_halt(&self) -> Option<SafetyHalt> {
+        SafetyHalt::from_status(&self.status)
+    }
+
+    /// Whether the session runs one way, so a reset makes beta match alpha.
+    pub fn is_one_way(&self) -> bool {
+        self.mode
+            .as_deref()
+            .is_some_and(|mode| mode.starts_with("one-way"))
     }
 
     /// Map session status to a compact icon for display
     pub fn status_icon(&self) -> &'static str {
         let status_lower = self.status.to_lowercase();
-        if status_lower.contains("watching") {
+        if self.safety_halt().is_some() {
+            "🛑" // Halted by a safety check
+        } else if status_lower.contains("watching") {
             "👁" // Watching for changes
         } else if status_lower.contains("scanning") {
             "🔍" // Scanning files
@@ -225,6 +537,14 @@ impl SyncSession {
         }
     }
 
+    /// Staging progress of whichever endpoint is receiving files (beta first).
+    pub fn staging_progress(&self) -> Option<&StagingProgress> {
+        self.beta
+            .staging_progress
+            .as_ref()
+            .or(self.alpha.staging_progress.as_ref())
+    }
+
     /// Get progress percentage from staging progress if available
     pub fn progress_percentage(&self) -> Option<u8> {
         // Check beta endpoint for staging progress (more common for push operations)
@@ -248,24 +568,217 @@ impl SyncSession {
 
 }
 
+/// Sessions for tests, built up from a plain running one.
+#[cfg(test)]
+impl SyncSession {
+    /// A watching, unpaused session named `name`, with the identifier
+    /// `sync_<name>`, between connected local endpoints.
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            identifier: format!("sync_{}", name),
+            alpha: Endpoint::local(&format!("/code/{}", name)),
+            beta: Endpoint::local(&format!("/srv/{}", name)),
+            status: "Watching for changes".to_string(),
+            paused: false,
+            mode: None,
+            creation_time: None,
+            successful_cycles: None,
+            conflicts: vec![],
+            excluded_conflicts: 0,
+            labels: BTreeMap::new(),
+            last_error: None,
+            sync_time: SyncTime::Unknown,
+        }
+    }
+
+    pub fn with_status(mut self, status: &str) -> Self {
+        self.status = status.to_string();
+        self
+    }
+
+    pub fn with_paused(mut self, paused: bool) -> Self {
+        self.paused = paused;
+        self
+    }
+
+    pub fn with_cycles(mut self, cycles: u64) -> Self {
+        self.successful_cycles = Some(cycles);
+        self
+    }
+
+    /// Conflicts at each of `roots`, with no changes listed.
+    pub fn with_conflicts(mut self, roots: &[&str]) -> Self {
+        self.conflicts = roots
+            .iter()
+            .map(|root| Conflict {
+                root: root.to_string(),
+                alpha_changes: vec![],
+                beta_changes: vec![],
+            })
+            .collect();
+        self
+    }
+
+    /// Whether the beta endpoint is connected; false as when its host is
+    /// unreachable.
+    pub fn with_beta_connected(mut self, connected: bool) -> Self {
+        self.beta.connected = connected;
+        self
+    }
+}
+
+#[cfg(test)]
+impl Endpoint {
+    /// A connected, scanned local endpoint at `path`.
+    pub fn local(path: &str) -> Self {
+        Self {
+            protocol: "local".to_string(),
+            path: path.to_string(),
+            user: None,
+            host: None,
+            port: None,
+            connected: true,
+            scanned: true,
+            directories: None,
+            files: None,
+            symbolic_links: None,
+            total_file_size: None,
+            staging_progress: None,
+            scan_problems: vec![],
+            excluded_scan_problems: 0,
+            transition_problems: vec![],
+            excluded_transition_problems: 0,
+            environment: BTreeMap::new(),
+        }
+    }
+}
+
+/// One end of a forwarding session, such as `tcp:localhost:8080` on a host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForwardEndpoint {
+    pub endpoint: String,
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub connected: bool,
+}
+
+impl ForwardEndpoint {
+    pub fn display(&self) -> String {
+        match &self.host {
+            Some(host) => format!("{}:{}", host, self.endpoint),
+            None => self.endpoint.clone(),
+        }
+    }
+}
+
+/// A network forwarding session from `mutagen forward list`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForwardSession {
+    #[serde(default)]
+    pub name: String,
+    pub identifier: String,
+    pub source: ForwardEndpoint,
+    pub destination: ForwardEndpoint,
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub paused: bool,
+    #[serde(rename = "openConnections", default)]
+    pub open_connections: u64,
+}
+
+/// Aggregate counts across a set of sessions, for the summary bar.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSummary {
+    pub running: usize,
+    pub paused: usize,
+    pub conflicts: usize,
+    /// Bytes received so far by sessions that are currently staging.
+    pub bytes_staged: u64,
+}
+
+impl SessionSummary {
+    pub fn from_sessions(sessions: &[SyncSession]) -> Self {
+        sessions.iter().fold(Self::default(), |mut summary, session| {
+            if session.paused {
+                summary.paused += 1;
+            } else {
+                summary.running += 1;
+            }
+            summary.conflicts += session.conflict_count();
+            summary.bytes_staged += session
+                .staging_progress()
+                .and_then(StagingProgress::bytes_received)
+                .unwrap_or(0);
+            summary
+        })
+    }
+}
+
 /// Client for interacting with the Mutagen CLI.
 ///
 /// Generic over `CommandRunner` to allow dependency injection of mock
 /// implementations for testing.
-pub struct MutagenClient<R: CommandRunner = SystemCommandRunner> {
+pub struct MutagenClient<R: CommandRunner = SharedRunner> {
     runner: R,
+    /// Sessions targeted by mutating calls, used to tell local changes from external ones.
+    journal: Mutex<OperationJournal>,
+    /// User's ssh config, consulted for user and port when running ssh directly.
+    ssh_config: SshConfig,
+    /// JSON for each session from the last listing, keyed by identifier, before
+    /// deserialization drops the fields mutagui doesn't model.
+    raw_sessions: Mutex<HashMap<String, serde_json::Value>>,
+    /// Labels sessions this client creates with, if any.
+    origin: Option<Origin>,
+    /// Command lines of the actions run since they were last taken.
+    commands: Mutex<Vec<String>>,
+    /// Whether to keep actions' command lines for `take_commands`.
+    recording: AtomicBool,
+    /// Whether to note actions without running them.
+    dry_run: AtomicBool,
+    /// Timeouts and retries from the `[mutagen]` settings.
+    settings: MutagenConfig,
 }
 
-impl MutagenClient<SystemCommandRunner> {
+/// The runner the app's client uses: the system runner, or a mock in tests
+/// that drive the whole app.
+pub type SharedRunner = Arc<dyn CommandRunner>;
+
+impl MutagenClient<SharedRunner> {
     /// Create a new MutagenClient with the default system command runner.
     pub fn new() -> Self {
+        Self::with_shared_runner(Arc::new(SystemCommandRunner::new()))
+    }
+
+    /// Create a client that runs mutagen as the `[mutagen]` settings say.
+    pub fn with_config(mutagen: &MutagenConfig) -> Self {
+        let runner = SystemCommandRunner::with_mutagen(mutagen.clone());
+        Self {
+            settings: mutagen.clone(),
+            ..Self::with_shared_runner(Arc::new(runner))
+        }
+    }
+
+    /// Create a client that reads the user's ssh config and runs commands
+    /// through `runner`.
+    pub fn with_shared_runner(runner: SharedRunner) -> Self {
         Self {
-            runner: SystemCommandRunner::new(),
+            runner,
+            journal: Mutex::new(OperationJournal::new()),
+            ssh_config: SshConfig::load(),
+            raw_sessions: Mutex::new(HashMap::new()),
+            origin: Some(Origin::current()),
+            commands: Mutex::new(Vec::new()),
+            recording: AtomicBool::new(false),
+            dry_run: AtomicBool::new(false),
+            settings: MutagenConfig::default(),
         }
     }
 }
 
-impl Default for MutagenClient<SystemCommandRunner> {
+impl Default for MutagenClient<SharedRunner> {
     fn default() -> Self {
         Self::new()
     }
@@ -276,126 +789,400 @@ impl<R: CommandRunner> MutagenClient<R> {
     /// Primarily used for testing with mock runners.
     #[cfg(test)]
     pub fn with_runner(runner: R) -> Self {
-        Self { runner }
+        Self {
+            runner,
+            journal: Mutex::new(OperationJournal::new()),
+            ssh_config: SshConfig::default(),
+            raw_sessions: Mutex::new(HashMap::new()),
+            origin: None,
+            commands: Mutex::new(Vec::new()),
+            recording: AtomicBool::new(false),
+            dry_run: AtomicBool::new(false),
+            settings: MutagenConfig::default(),
+        }
+    }
+
+    /// Replace the ssh config used for direct ssh commands.
+    #[cfg(test)]
+    pub fn with_ssh_config(mut self, ssh_config: SshConfig) -> Self {
+        self.ssh_config = ssh_config;
+        self
+    }
+
+    /// Use `settings` in place of the default `[mutagen]` settings.
+    #[cfg(test)]
+    pub fn with_settings(mut self, settings: MutagenConfig) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Label created sessions as made by `origin`.
+    #[cfg(test)]
+    pub fn with_origin(mut self, origin: Origin) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Keep the command line of each action this client runs, for
+    /// `take_commands`.
+    pub fn record_commands(&self) {
+        self.recording.store(true, Ordering::Relaxed);
+    }
+
+    /// Note actions' command lines for `take_commands` instead of running
+    /// them, while `on`.
+    pub fn set_dry_run(&self, on: bool) {
+        self.dry_run.store(on, Ordering::Relaxed);
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.load(Ordering::Relaxed)
+    }
+
+    /// The command lines of the actions run since the last call, oldest
+    /// first. Empty unless commands are being recorded or this is a dry run.
+    pub fn take_commands(&self) -> Vec<String> {
+        std::mem::take(&mut *self.commands.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Note the command line of an action if commands are being recorded.
+    /// Returns whether to skip running it for a dry run.
+    fn note_action(&self, program: &str, args: &[&str]) -> bool {
+        let dry_run = self.is_dry_run();
+        if dry_run || self.recording.load(Ordering::Relaxed) {
+            let mut commands = self.commands.lock().unwrap_or_else(|e| e.into_inner());
+            commands.push(command_line(program, args));
+        }
+        dry_run
+    }
+
+    /// Run a command that changes sessions or endpoints, noting its command
+    /// line. A dry run only notes it, and reports success.
+    async fn run_action(
+        &self,
+        program: &str,
+        args: &[&str],
+        timeout_secs: u64,
+    ) -> Result<std::process::Output> {
+        if self.note_action(program, args) {
+            return Ok(std::process::Output {
+                status: std::process::ExitStatus::default(),
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+            });
+        }
+        self.run_retrying(program, args, timeout_secs, false).await
+    }
+
+    /// Run a command, trying again after a transient failure with a delay
+    /// that doubles each time. A command that timed out is only tried again
+    /// if `retry_timeouts`, since an action may have happened anyway.
+    async fn run_retrying(
+        &self,
+        program: &str,
+        args: &[&str],
+        timeout_secs: u64,
+        retry_timeouts: bool,
+    ) -> Result<std::process::Output> {
+        let mut delay = Duration::from_millis(self.settings.retry_delay_ms);
+        let mut attempt = 0;
+        loop {
+            let result = self.runner.run(program, args, timeout_secs).await;
+            let transient = match &result {
+                Ok(output) => {
+                    !output.status.success() && is_transient(&decode_message(&output.stderr))
+                }
+                Err(e) => retry_timeouts && is_transient(&e.to_string()),
+            };
+            if !transient || attempt >= self.settings.retries {
+                return result;
+            }
+            attempt += 1;
+            tracing::info!(program, args = %args.join(" "), attempt, "retrying command");
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+
+    /// The user's ssh config, for displaying host aliases.
+    pub fn ssh_config(&self) -> &SshConfig {
+        &self.ssh_config
+    }
+
+    /// The host and user sessions created by this client are labeled with.
+    pub fn origin(&self) -> Option<&Origin> {
+        self.origin.as_ref()
+    }
+
+    /// The name to create a session `name` under, with the configured
+    /// prefix.
+    fn prefixed(&self, name: &str) -> String {
+        format!("{}{}", self.settings.session_prefix, name)
+    }
+
+    /// A listed session's name without the configured prefix, if it has it
+    /// and mutagui created the session.
+    fn unprefixed<'a>(&self, session: &'a SyncSession) -> Option<&'a str> {
+        let prefix = &self.settings.session_prefix;
+        session.origin()?;
+        session
+            .name
+            .strip_prefix(prefix.as_str())
+            .filter(|name| !prefix.is_empty() && !name.is_empty())
+    }
+
+    /// `--label` arguments marking a created session with our origin.
+    fn label_args(&self) -> Vec<String> {
+        self.origin
+            .as_ref()
+            .map(Origin::label_args)
+            .unwrap_or_default()
     }
 
+    /// Access the journal of recently issued local operations.
+    pub fn journal(&self) -> MutexGuard<'_, OperationJournal> {
+        self.journal.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The JSON mutagen returned for a session in the last listing.
+    pub fn raw_session(&self, identifier: &str) -> Option<serde_json::Value> {
+        let raw = self.raw_sessions.lock().unwrap_or_else(|e| e.into_inner());
+        raw.get(identifier).cloned()
+    }
+
+    /// Record every session defined by a project file (including push variants).
+    fn record_project_sessions(&self, project_file: &Path) {
+        if let Ok(file) = ProjectFile::from_path(project_file.to_path_buf()) {
+            let mut journal = self.journal();
+            for name in file.sessions.keys() {
+                journal.record(name.clone());
+                journal.record(format!("{}-push", name));
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
     pub async fn list_sessions(&self) -> Result<Vec<SyncSession>> {
-        let output = self
-            .runner
-            .run("mutagen", &["sync", "list", "--template", "{{json .}}"], 5)
-            .await?;
+        self.list(&[]).await
+    }
+
+    /// List only the given sessions. Fails if any of them no longer exists.
+    #[tracing::instrument(skip(self))]
+    pub async fn list_sessions_by_id(&self, identifiers: &[String]) -> Result<Vec<SyncSession>> {
+        self.list(identifiers).await
+    }
+
+    /// List only the sessions whose labels match `selector`, such as
+    /// `io.mutagen.project=project_abc`.
+    #[tracing::instrument(skip(self))]
+    pub async fn list_sessions_by_label(&self, selector: &str) -> Result<Vec<SyncSession>> {
+        self.list(&["--label-selector".to_string(), selector.to_string()])
+            .await
+    }
+
+    /// List the sessions `filter` picks, identifiers or a label selector, or
+    /// all sessions if it is empty.
+    async fn list(&self, filter: &[String]) -> Result<Vec<SyncSession>> {
+        let mut args = vec!["sync", "list", "--template", "{{json .}}"];
+        args.extend(filter.iter().map(String::as_str));
+        let timeout = self.settings.command_timeout_secs;
+        let output = self.run_retrying("mutagen", &args, timeout, true).await?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = decode_message(&output.stderr);
             anyhow::bail!("mutagen sync list failed: {}", stderr);
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = decode_output(&output.stdout);
 
         // Parse the JSON output
         // Note: The mutagen template '{{json .}}' outputs a JSON array: [{session1}, {session2}, ...]
         // This is NOT JSONL format (one object per line). The entire output is a single JSON array.
         // See: https://mutagen.io/documentation/introduction/templates
-        let sessions: Vec<SyncSession> = if stdout.trim().is_empty() {
+        let values: Vec<serde_json::Value> = if stdout.trim().is_empty() {
             Vec::new()
         } else {
             serde_json::from_str(&stdout).context("Failed to parse mutagen output")?
         };
 
+        let mut sessions = Vec::with_capacity(values.len());
+        let mut raw = HashMap::with_capacity(values.len());
+        for value in values {
+            let mut session: SyncSession =
+                serde_json::from_value(value.clone()).context("Failed to parse mutagen output")?;
+            if let Some(name) = self.unprefixed(&session) {
+                session.name = name.to_string();
+            }
+            raw.insert(session.identifier.clone(), value);
+            sessions.push(session);
+        }
+        let mut raw_sessions = self.raw_sessions.lock().unwrap_or_else(|e| e.into_inner());
+        if filter.is_empty() {
+            *raw_sessions = raw;
+        } else {
+            raw_sessions.extend(raw);
+        }
+
         Ok(sessions)
     }
 
+    /// List forwarding sessions.
+    pub async fn list_forwards(&self) -> Result<Vec<ForwardSession>> {
+        let args = ["forward", "list", "--template", "{{json .}}"];
+        let timeout = self.settings.command_timeout_secs;
+        let output = self.run_retrying("mutagen", &args, timeout, true).await?;
+
+        if !output.status.success() {
+            let stderr = decode_message(&output.stderr);
+            anyhow::bail!("mutagen forward list failed: {}", stderr);
+        }
+
+        let stdout = decode_output(&output.stdout);
+        if stdout.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        serde_json::from_str(&stdout).context("Failed to parse mutagen output")
+    }
+
+    /// The version of the mutagen CLI, e.g. "0.18.1".
+    pub async fn version(&self) -> Result<String> {
+        let timeout = self.settings.command_timeout_secs;
+        let output = self
+            .run_retrying("mutagen", &["version"], timeout, true)
+            .await?;
+
+        if !output.status.success() {
+            let stderr = decode_message(&output.stderr);
+            anyhow::bail!("mutagen version failed: {}", stderr);
+        }
+
+        Ok(decode_output(&output.stdout).trim().to_string())
+    }
+
+    #[tracing::instrument(skip(self))]
     pub async fn pause_session(&self, identifier: &str) -> Result<()> {
+        self.journal().record(identifier);
         let output = self
-            .runner
-            .run("mutagen", &["sync", "pause", identifier], 5)
+            .run_action(
+                "mutagen",
+                &["sync", "pause", identifier],
+                self.settings.command_timeout_secs,
+            )
             .await?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = decode_message(&output.stderr);
             anyhow::bail!("mutagen sync pause failed: {}", stderr);
         }
 
         Ok(())
     }
 
+    #[tracing::instrument(skip(self))]
     pub async fn resume_session(&self, identifier: &str) -> Result<()> {
+        self.journal().record(identifier);
         let output = self
-            .runner
-            .run("mutagen", &["sync", "resume", identifier], 5)
+            .run_action(
+                "mutagen",
+                &["sync", "resume", identifier],
+                self.settings.command_timeout_secs,
+            )
             .await?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = decode_message(&output.stderr);
             anyhow::bail!("mutagen sync resume failed: {}", stderr);
         }
 
         Ok(())
     }
 
+    #[tracing::instrument(skip(self))]
     pub async fn terminate_session(&self, identifier: &str) -> Result<()> {
+        self.journal().record(identifier);
         let output = self
-            .runner
-            .run("mutagen", &["sync", "terminate", identifier], 5)
+            .run_action(
+                "mutagen",
+                &["sync", "terminate", identifier],
+                self.settings.command_timeout_secs,
+            )
             .await?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = decode_message(&output.stderr);
             anyhow::bail!("mutagen sync terminate failed: {}", stderr);
         }
 
         Ok(())
     }
 
+    /// Clear the session's sync history, which is how mutagen is told to
+    /// carry on after a safety halt.
+    #[tracing::instrument(skip(self))]
+    pub async fn reset_session(&self, identifier: &str) -> Result<()> {
+        self.journal().record(identifier);
+        let output = self
+            .run_action(
+                "mutagen",
+                &["sync", "reset", identifier],
+                self.settings.project_timeout_secs,
+            )
+            .await?;
+
+        if !output.status.success() {
+            let stderr = decode_message(&output.stderr);
+            anyhow::bail!("mutagen sync reset failed: {}", stderr);
+        }
+
+        Ok(())
+    }
+
+    /// Request a sync cycle without waiting for it, since a large tree can
+    /// take far longer than the command timeout. The caller watches
+    /// `successful_cycles` to see it finish.
+    #[tracing::instrument(skip(self))]
     pub async fn flush_session(&self, identifier: &str) -> Result<()> {
         let output = self
-            .runner
-            .run("mutagen", &["sync", "flush", identifier], 5)
+            .run_action(
+                "mutagen",
+                &["sync", "flush", "--skip-wait", identifier],
+                self.settings.command_timeout_secs,
+            )
             .await?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = decode_message(&output.stderr);
             anyhow::bail!("mutagen sync flush failed: {}", stderr);
         }
 
         Ok(())
     }
 
+    #[tracing::instrument(skip(self))]
     pub async fn start_project(&self, project_file: &Path) -> Result<()> {
+        self.record_project_sessions(project_file);
+        if is_compose_file(project_file) {
+            return self.compose(project_file, &["up", "--detach"]).await;
+        }
         let path_str = project_file.to_string_lossy();
         let output = self
-            .runner
-            .run("mutagen", &["project", "start", "-f", &path_str], 10)
+            .run_action(
+                "mutagen",
+                &["project", "start", "-f", &path_str],
+                self.settings.project_timeout_secs,
+            )
             .await?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = decode_message(&output.stderr);
 
-            // Check if failure is due to "project already running"
+            // A lock left behind by a start that didn't finish makes mutagen
+            // refuse; it's removed only once the user confirms
             if stderr.contains("project already running") {
-                // Check if there are actually any running sessions for this project
-                let sessions = self.list_sessions().await.unwrap_or_default();
-                if !project_has_running_sessions(project_file, &sessions) {
-                    // No sessions from this project are running - stale lock file
-                    // Remove it and retry
-                    let lock_file = get_project_lock_path(project_file);
-                    if lock_file.exists() {
-                        std::fs::remove_file(&lock_file).with_context(|| {
-                            format!("Failed to remove stale lock file: {}", lock_file.display())
-                        })?;
-
-                        // Retry the start
-                        let retry_output = self
-                            .runner
-                            .run("mutagen", &["project", "start", "-f", &path_str], 10)
-                            .await?;
-
-                        if !retry_output.status.success() {
-                            let retry_stderr = String::from_utf8_lossy(&retry_output.stderr);
-                            anyhow::bail!("mutagen project start failed: {}", retry_stderr);
-                        }
-
-                        return Ok(());
+                if let Some(lock) = StaleLock::read(get_project_lock_path(project_file)) {
+                    let sessions = self.list_sessions().await.unwrap_or_default();
+                    if !project_has_running_sessions(project_file, &lock.identifier, &sessions) {
+                        return Err(lock.into());
                     }
                 }
             }
@@ -406,27 +1193,115 @@ impl<R: CommandRunner> MutagenClient<R> {
         Ok(())
     }
 
+    /// Remove a stale project lock the user has confirmed, unless it has
+    /// been rewritten since it was read, as by another start of the project.
+    pub fn remove_stale_lock(&self, lock: &StaleLock) -> Result<()> {
+        let current = StaleLock::read(lock.path.clone());
+        if current.as_ref().map(|current| &current.identifier) != Some(&lock.identifier) {
+            anyhow::bail!("{} changed since it was checked", lock.path.display());
+        }
+        if self.note_action("rm", &[&lock.path.to_string_lossy()]) {
+            return Ok(());
+        }
+        std::fs::remove_file(&lock.path).with_context(|| {
+            format!("Failed to remove stale lock file: {}", lock.path.display())
+        })?;
+        tracing::warn!(
+            path = %lock.path.display(),
+            identifier = %lock.identifier,
+            "removed stale project lock"
+        );
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
     pub async fn terminate_project(&self, project_file: &Path) -> Result<()> {
+        self.record_project_sessions(project_file);
+        if is_compose_file(project_file) {
+            return self.compose(project_file, &["down"]).await;
+        }
         let path_str = project_file.to_string_lossy();
         let output = self
-            .runner
-            .run("mutagen", &["project", "terminate", "-f", &path_str], 10)
+            .run_action(
+                "mutagen",
+                &["project", "terminate", "-f", &path_str],
+                self.settings.project_timeout_secs,
+            )
             .await?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = decode_message(&output.stderr);
             anyhow::bail!("mutagen project terminate failed: {}", stderr);
         }
 
         Ok(())
     }
 
-    /// Ensures a directory exists on an endpoint (local or remote).
-    /// For remote endpoints (SSH, Docker), uses SSH to create the directory.
-    /// For local paths, uses std::fs::create_dir_all with tilde expansion.
-    pub async fn ensure_endpoint_directory_exists(&self, endpoint: &str) -> Result<()> {
-        use crate::endpoint::EndpointAddress;
+    /// Run `mutagen-compose` on the compose file at `compose_file`, which
+    /// starts or stops the containers and the sessions in its `x-mutagen`
+    /// section together.
+    async fn compose(&self, compose_file: &Path, args: &[&str]) -> Result<()> {
+        let path_str = compose_file.to_string_lossy();
+        let mut compose_args = vec!["-f", &*path_str];
+        compose_args.extend(args);
+        let output = self
+            .run_action("mutagen-compose", &compose_args, COMPOSE_TIMEOUT_SECS)
+            .await?;
+        if !output.status.success() {
+            let stderr = decode_message(&output.stderr);
+            anyhow::bail!("mutagen-compose {} failed: {}", args.join(" "), stderr);
+        }
+        Ok(())
+    }
 
+    /// Run a project file's `hook` commands (e.g. `beforeCreate`) in order,
+    /// in the file's directory, the way `mutagen project start` and
+    /// `terminate` do. Stops at the first command that fails.
+    #[tracing::instrument(skip(self, commands))]
+    pub async fn run_hooks(
+        &self,
+        project_file: &Path,
+        hook: &str,
+        commands: &[String],
+    ) -> Result<()> {
+        let dir = project_file.parent().unwrap_or(Path::new("."));
+        for command in commands {
+            let output = self.run_hook(dir, command).await?;
+            let stdout = decode_output(&output.stdout);
+            if !output.status.success() {
+                let stderr = decode_message(&output.stderr);
+                let message = if stderr.is_empty() {
+                    stdout.trim()
+                } else {
+                    stderr.as_str()
+                };
+                anyhow::bail!("{} command `{}` failed: {}", hook, command, message);
+            }
+            tracing::info!(hook, command, output = %stdout.trim(), "hook ran");
+        }
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    async fn run_hook(&self, dir: &Path, command: &str) -> Result<std::process::Output> {
+        let dir = dir.to_string_lossy();
+        let script = format!("cd {} && {}", escape(Cow::Borrowed(&*dir)), command);
+        self.run_action("sh", &["-c", &script], HOOK_TIMEOUT_SECS)
+            .await
+    }
+
+    #[cfg(not(unix))]
+    async fn run_hook(&self, dir: &Path, command: &str) -> Result<std::process::Output> {
+        let script = format!("cd /d \"{}\" && {}", dir.display(), command);
+        self.run_action("cmd", &["/C", &script], HOOK_TIMEOUT_SECS)
+            .await
+    }
+
+    /// Ensures a directory exists on an endpoint (local or remote).
+    /// For remote endpoints (SSH, Docker), uses SSH to create the directory.
+    /// For local paths, uses std::fs::create_dir_all with tilde expansion.
+    #[tracing::instrument(skip(self))]
+    pub async fn ensure_endpoint_directory_exists(&self, endpoint: &str) -> Result<()> {
         let parsed = EndpointAddress::parse(endpoint);
 
         match parsed {
@@ -434,6 +1309,9 @@ impl<R: CommandRunner> MutagenClient<R> {
                 // Expand tilde for local paths
                 let expanded = EndpointAddress::Local(path).expand_tilde();
                 let final_path = expanded.path();
+                if self.note_action("mkdir", &["-p", &final_path.to_string_lossy()]) {
+                    return Ok(());
+                }
 
                 std::fs::create_dir_all(final_path)
                     .with_context(|| format!("Failed to create local directory {:?}", final_path))
@@ -441,47 +1319,43 @@ impl<R: CommandRunner> MutagenClient<R> {
             EndpointAddress::Ssh {
                 user, host, port, path
             } => {
-                // Build the SSH host string (user@host or just host)
-                let ssh_host = match user {
-                    Some(u) => format!("{}@{}", u, host),
-                    None => host,
-                };
-
                 // Remote tilde is handled by the remote shell, don't expand it
-                let path_str = path.to_string_lossy();
-                let escaped_path = escape(Cow::Borrowed(&*path_str));
-                let mkdir_cmd = format!("mkdir -p {}", escaped_path);
+                let mkdir_cmd = remote_mkdir_command(&path.to_string_lossy());
 
-                // Build SSH args with optional port
-                let mut ssh_args = Vec::new();
-                if let Some(p) = port {
-                    ssh_args.push("-p".to_string());
-                    ssh_args.push(p.to_string());
-                }
-                ssh_args.push(ssh_host);
+                let mut ssh_args = self.ssh_destination(user, &host, port);
                 ssh_args.push(mkdir_cmd);
 
                 let ssh_args_refs: Vec<&str> = ssh_args.iter().map(|s| s.as_str()).collect();
-                let output = self.runner.run("ssh", &ssh_args_refs, 10).await?;
+                let output = self
+                    .run_action("ssh", &ssh_args_refs, self.settings.project_timeout_secs)
+                    .await?;
 
                 if !output.status.success() {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    let stderr = decode_message(&output.stderr);
                     anyhow::bail!("Failed to create remote directory {}: {}", endpoint, stderr);
                 }
                 Ok(())
             }
-            EndpointAddress::Docker { container, path } => {
-                // Use docker exec to create directory in container
+            EndpointAddress::Docker {
+                user,
+                container,
+                path,
+            } => {
+                // Use docker exec to create directory in container. docker
+                // picks up DOCKER_HOST and DOCKER_CONTEXT from our environment,
+                // just as mutagen will when it creates the session.
                 let path_str = path.to_string_lossy();
                 let escaped_path = escape(Cow::Borrowed(&*path_str));
                 let mkdir_cmd = format!("mkdir -p {}", escaped_path);
+                let args =
+                    DockerConnection::default().exec_args(user.as_deref(), &container, &mkdir_cmd);
+                let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
                 let output = self
-                    .runner
-                    .run("docker", &["exec", &container, "sh", "-c", &mkdir_cmd], 10)
+                    .run_action("docker", &arg_refs, self.settings.project_timeout_secs)
                     .await?;
 
                 if !output.status.success() {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    let stderr = decode_message(&output.stderr);
                     anyhow::bail!(
                         "Failed to create directory in container {}: {}",
                         container,
@@ -493,6 +1367,290 @@ impl<R: CommandRunner> MutagenClient<R> {
         }
     }
 
+    /// Arguments to `ssh` that connect to `host`: `-p port` if there is one,
+    /// and `user@host` or just `host`. The user and port fall back to
+    /// ~/.ssh/config when the endpoint doesn't give them.
+    fn ssh_destination(&self, user: Option<String>, host: &str, port: Option<u16>) -> Vec<String> {
+        let host_config = self.ssh_config.resolve(host);
+        let mut args = Vec::new();
+        if let Some(p) = port.or(host_config.port) {
+            args.push("-p".to_string());
+            args.push(p.to_string());
+        }
+        args.push(match user.or(host_config.user) {
+            Some(u) => format!("{}@{}", u, host),
+            None => host.to_string(),
+        });
+        args
+    }
+
+    /// Check whether `host` accepts an ssh connection, without prompting
+    /// for a password or host key. An error means ssh itself couldn't run.
+    #[tracing::instrument(skip(self))]
+    pub async fn check_ssh_host(
+        &self,
+        user: Option<String>,
+        host: &str,
+        port: Option<u16>,
+    ) -> Result<HostReachability> {
+        let mut args = vec![
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            format!("ConnectTimeout={}", SSH_CHECK_TIMEOUT_SECS),
+        ];
+        args.extend(self.ssh_destination(user, host, port));
+        args.push("true".to_string());
+
+        let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let output = self
+            .runner
+            .run("ssh", &arg_refs, SSH_CHECK_TIMEOUT_SECS + 5)
+            .await?;
+        Ok(if output.status.success() {
+            HostReachability::Reachable
+        } else {
+            HostReachability::from_ssh_error(decode_message(&output.stderr).trim())
+        })
+    }
+
+    /// List the directory an endpoint address points at: read locally, or
+    /// with `ls` over ssh or in the container.
+    #[tracing::instrument(skip(self), fields(endpoint = %address))]
+    pub async fn list_directory(&self, address: &EndpointAddress) -> Result<Vec<DirEntry>> {
+        let (program, args) = match address {
+            EndpointAddress::Local(path) => {
+                let mut entries = Vec::new();
+                let dir = std::fs::read_dir(path)
+                    .with_context(|| format!("Cannot read {}", path.display()))?;
+                for entry in dir {
+                    let entry = entry?;
+                    entries.push(DirEntry {
+                        name: entry.file_name().to_string_lossy().into_owned(),
+                        // Follows symlinks, as `ls -p` does
+                        is_dir: entry.path().is_dir(),
+                    });
+                }
+                browse::sort_entries(&mut entries);
+                return Ok(entries);
+            }
+            EndpointAddress::Ssh {
+                user,
+                host,
+                port,
+                path,
+            } => {
+                let mut args = self.ssh_destination(user.clone(), host, *port);
+                args.push(browse::ls_script(&path.to_string_lossy()));
+                ("ssh", args)
+            }
+            EndpointAddress::Docker {
+                user,
+                container,
+                path,
+            } => (
+                "docker",
+                DockerConnection::default().exec_args(
+                    user.as_deref(),
+                    container,
+                    &browse::ls_script(&path.to_string_lossy()),
+                ),
+            ),
+        };
+
+        let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let timeout = self.settings.create_timeout_secs;
+        let output = self.runner.run(program, &arg_refs, timeout).await?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Cannot list {}: {}",
+                address,
+                decode_message(&output.stderr).trim()
+            );
+        }
+        Ok(browse::parse_ls(&decode_output(&output.stdout)))
+    }
+
+    /// A command that opens an interactive shell in `address`'s directory:
+    /// the user's shell locally, or a login shell over `ssh -t` or
+    /// `docker exec -it`. It takes over the terminal, so it's run directly
+    /// rather than through the command runner.
+    pub fn shell_command(&self, address: &EndpointAddress) -> std::process::Command {
+        let script = |path: &Path| {
+            format!(
+                "cd {} || exit 1; exec \"${{SHELL:-sh}}\" -l",
+                verify::shell_dir(&path.to_string_lossy())
+            )
+        };
+        let (program, args) = match address {
+            EndpointAddress::Local(path) => {
+                let shell = std::env::var("SHELL")
+                    .or_else(|_| std::env::var("COMSPEC"))
+                    .unwrap_or_else(|_| "sh".to_string());
+                let mut command = std::process::Command::new(shell);
+                command.current_dir(path);
+                return command;
+            }
+            EndpointAddress::Ssh {
+                user,
+                host,
+                port,
+                path,
+            } => {
+                let mut args = vec!["-t".to_string()];
+                args.extend(self.ssh_destination(user.clone(), host, *port));
+                args.push(script(path));
+                ("ssh", args)
+            }
+            EndpointAddress::Docker {
+                user,
+                container,
+                path,
+            } => (
+                "docker",
+                DockerConnection::default().interactive_exec_args(
+                    user.as_deref(),
+                    container,
+                    &script(path),
+                ),
+            ),
+        };
+        let mut command = std::process::Command::new(program);
+        command.args(args);
+        command
+    }
+
+    /// Run a shell script where a session endpoint lives: locally, over ssh, or in its container.
+    /// Returns the script's stdout.
+    async fn run_on_endpoint(
+        &self,
+        endpoint: &Endpoint,
+        script: &str,
+        timeout_secs: u64,
+    ) -> Result<String> {
+        let (program, args) = match (endpoint.protocol.as_str(), &endpoint.host) {
+            ("local", _) => ("sh", vec!["-c".to_string(), script.to_string()]),
+            ("ssh", Some(host)) => {
+                let mut args = self.ssh_destination(endpoint.user.clone(), host, endpoint.port);
+                args.push(script.to_string());
+                ("ssh", args)
+            }
+            ("docker", Some(container)) => {
+                // Reach the daemon the session was created against, which may
+                // not be the one our own environment points at
+                let connection = DockerConnection::from_environment(|name| {
+                    endpoint.environment.get(name).cloned()
+                });
+                (
+                    "docker",
+                    connection.exec_args(endpoint.user.as_deref(), container, script),
+                )
+            }
+            (protocol, _) => anyhow::bail!(
+                "Cannot run commands on {} endpoint {}",
+                protocol,
+                endpoint.display_path()
+            ),
+        };
+
+        let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let output = self.runner.run(program, &arg_refs, timeout_secs).await?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} failed on {}: {}",
+                program,
+                endpoint.display_path(),
+                decode_message(&output.stderr).trim()
+            );
+        }
+        Ok(decode_output(&output.stdout).into_owned())
+    }
+
+    /// Size and free space of the filesystem an endpoint's directory is on.
+    #[tracing::instrument(skip(self, endpoint), fields(endpoint = %endpoint.display_path()))]
+    pub async fn disk_space(&self, endpoint: &Endpoint) -> Result<DiskSpace> {
+        let output = self
+            .run_on_endpoint(
+                endpoint,
+                &disk::df_script(&endpoint.path),
+                self.settings.create_timeout_secs,
+            )
+            .await?;
+        disk::parse_df(&output).with_context(|| {
+            format!(
+                "Unexpected df output from {}: {}",
+                endpoint.display_path(),
+                output.trim()
+            )
+        })
+    }
+
+    /// Hash files on both endpoints of a session and report where they
+    /// differ, leaving out the files it `ignores`.
+    #[tracing::instrument(skip(self, session, ignores), fields(session = %session.name))]
+    pub async fn verify_session(
+        &self,
+        session: &SyncSession,
+        mode: VerifyMode,
+        ignores: &Ignores,
+    ) -> Result<VerifyReport> {
+        let (alpha, beta) = (&session.alpha, &session.beta);
+
+        let (paths, alpha_sums, beta_sums) = match mode {
+            VerifyMode::Sample(count) => {
+                let listing = self
+                    .run_on_endpoint(alpha, &verify::list_script(&alpha.path), 60)
+                    .await?;
+                let mut files = verify::parse_file_list(&listing);
+                files.retain(|path| !ignores.is_ignored(path));
+                let sample = verify::sample_files(&files, count);
+                if sample.is_empty() {
+                    return Ok(VerifyReport::compare(
+                        &session.name,
+                        mode,
+                        &[],
+                        &HashMap::new(),
+                        &HashMap::new(),
+                    ));
+                }
+
+                let alpha_script = verify::hash_script(&alpha.path, Some(&sample));
+                let beta_script = verify::hash_script(&beta.path, Some(&sample));
+                let (alpha_out, beta_out) = tokio::try_join!(
+                    self.run_on_endpoint(alpha, &alpha_script, 120),
+                    self.run_on_endpoint(beta, &beta_script, 120),
+                )?;
+                (
+                    sample,
+                    verify::parse_checksums(&alpha_out),
+                    verify::parse_checksums(&beta_out),
+                )
+            }
+            VerifyMode::Full => {
+                let alpha_script = verify::hash_script(&alpha.path, None);
+                let beta_script = verify::hash_script(&beta.path, None);
+                let (alpha_out, beta_out) = tokio::try_join!(
+                    self.run_on_endpoint(alpha, &alpha_script, 1800),
+                    self.run_on_endpoint(beta, &beta_script, 1800),
+                )?;
+                let mut alpha_sums = verify::parse_checksums(&alpha_out);
+                let mut beta_sums = verify::parse_checksums(&beta_out);
+                alpha_sums.retain(|path, _| !ignores.is_ignored(path));
+                beta_sums.retain(|path, _| !ignores.is_ignored(path));
+                (verify::all_paths(&alpha_sums, &beta_sums), alpha_sums, beta_sums)
+            }
+        };
+
+        Ok(VerifyReport::compare(
+            &session.name,
+            mode,
+            &paths,
+            &alpha_sums,
+            &beta_sums,
+        ))
+    }
+
+    #[tracing::instrument(skip(self))]
     pub async fn create_push_session(
         &self,
         name: &str,
@@ -500,6 +1658,8 @@ impl<R: CommandRunner> MutagenClient<R> {
         beta: &str,
         ignore: Option<&[String]>,
     ) -> Result<()> {
+        self.journal().record(name);
+        let name = self.prefixed(name);
         let mut args = vec![
             "sync",
             "create",
@@ -508,7 +1668,7 @@ impl<R: CommandRunner> MutagenClient<R> {
             "-m",
             "one-way-replica",
             "-n",
-            name,
+            &name,
         ];
 
         // Collect ignore patterns as owned strings to extend lifetime
@@ -521,48 +1681,75 @@ impl<R: CommandRunner> MutagenClient<R> {
         // Convert to &str slice for the runner
         let ignore_refs: Vec<&str> = ignore_args.iter().map(|s| s.as_str()).collect();
         args.extend(ignore_refs);
+        let labels = self.label_args();
+        args.extend(labels.iter().map(String::as_str));
 
-        let output = self.runner.run("mutagen", &args, 15).await?;
+        let output = self
+            .run_action("mutagen", &args, self.settings.create_timeout_secs)
+            .await?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = decode_message(&output.stderr);
             anyhow::bail!("mutagen sync create failed: {}", stderr);
         }
 
         Ok(())
     }
 
-    pub async fn create_two_way_session(
+    /// Create a session with an explicit sync mode, for the new session form.
+    #[tracing::instrument(skip(self))]
+    pub async fn create_session(
         &self,
         name: &str,
         alpha: &str,
         beta: &str,
-        ignore: Option<&[String]>,
+        mode: &str,
+        ignore: &[String],
     ) -> Result<()> {
-        let mut args = vec![
-            "sync",
-            "create",
-            alpha,
-            beta,
-            "-n",
-            name,
-        ];
+        self.journal().record(name);
+        let name = self.prefixed(name);
+        let mut args = vec!["sync", "create", alpha, beta, "-m", mode, "-n", &name];
+        for pattern in ignore {
+            args.extend(["--ignore", pattern.as_str()]);
+        }
+        let labels = self.label_args();
+        args.extend(labels.iter().map(String::as_str));
 
-        // Collect ignore patterns as owned strings to extend lifetime
-        let ignore_args: Vec<String> = ignore
-            .unwrap_or(&[])
-            .iter()
-            .flat_map(|pattern| vec!["--ignore".to_string(), pattern.clone()])
-            .collect();
+        let output = self
+            .run_action("mutagen", &args, self.settings.create_timeout_secs)
+            .await?;
 
-        // Convert to &str slice for the runner
-        let ignore_refs: Vec<&str> = ignore_args.iter().map(|s| s.as_str()).collect();
-        args.extend(ignore_refs);
+        if !output.status.success() {
+            let stderr = decode_message(&output.stderr);
+            anyhow::bail!("mutagen sync create failed: {}", stderr);
+        }
+
+        Ok(())
+    }
+
+    /// Create the session of a project file's spec, with the arguments
+    /// `SessionDefinition::create_args` made from its definition.
+    #[tracing::instrument(skip(self))]
+    pub async fn create_spec_session(
+        &self,
+        name: &str,
+        alpha: &str,
+        beta: &str,
+        create_args: &[String],
+    ) -> Result<()> {
+        self.journal().record(name);
+        let name = self.prefixed(name);
+        let mut args = vec!["sync", "create", alpha, beta, "-n", &name];
+        args.extend(create_args.iter().map(String::as_str));
+        let labels = self.label_args();
+        args.extend(labels.iter().map(String::as_str));
 
-        let output = self.runner.run("mutagen", &args, 15).await?;
+        let output = self
+            .run_action("mutagen", &args, self.settings.create_timeout_secs)
+            .await?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = decode_message(&output.stderr);
             anyhow::bail!("mutagen sync create failed: {}", stderr);
         }
 
@@ -575,6 +1762,88 @@ mod tests {
     use super::*;
     use crate::command::{failure_output, success_output, MockCommandRunner};
 
+    // ============ SessionSummary tests ============
+
+    #[test]
+    fn test_session_summary() {
+        let json = r#"[
+            {
+                "name": "a", "identifier": "a", "status": "Watching for changes", "paused": false,
+                "alpha": { "protocol": "local", "path": "/a" },
+                "beta": { "protocol": "local", "path": "/b" },
+                "conflicts": [{ "root": "x" }, { "root": "y" }]
+            },
+            {
+                "name": "b", "identifier": "b", "status": "Staging files on beta", "paused": false,
+                "alpha": { "protocol": "local", "path": "/a" },
+                "beta": { "protocol": "local", "path": "/b", "stagingProgress": { "totalReceivedSize": 2048 } }
+            },
+            {
+                "name": "c", "identifier": "c", "status": "Paused", "paused": true,
+                "alpha": { "protocol": "local", "path": "/a" },
+                "beta": { "protocol": "local", "path": "/b" }
+            }
+        ]"#;
+        let sessions: Vec<SyncSession> = serde_json::from_str(json).unwrap();
+
+        let summary = SessionSummary::from_sessions(&sessions);
+        assert_eq!(
+            summary,
+            SessionSummary {
+                running: 2,
+                paused: 1,
+                conflicts: 2,
+                bytes_staged: 2048,
+            }
+        );
+    }
+
+    #[test]
+    fn test_created_at() {
+        let json = r#"{
+            "name": "a", "identifier": "a", "status": "Watching for changes", "paused": false,
+            "alpha": { "protocol": "local", "path": "/a" },
+            "beta": { "protocol": "local", "path": "/b" },
+            "creationTime": "2026-10-14T08:30:00.123456789Z"
+        }"#;
+        let mut session: SyncSession = serde_json::from_str(json).unwrap();
+        let created = session.created_at().unwrap();
+        assert_eq!(
+            created.to_utc().to_rfc3339(),
+            "2026-10-14T08:30:00.123456789+00:00"
+        );
+
+        session.creation_time = Some("yesterday".to_string());
+        assert_eq!(session.created_at(), None);
+        session.creation_time = None;
+        assert_eq!(session.created_at(), None);
+    }
+
+    #[test]
+    fn test_excluded_conflicts_and_problems() {
+        let json = r#"{
+            "name": "a", "identifier": "a", "status": "Watching for changes", "paused": false,
+            "alpha": {
+                "protocol": "local", "path": "/a",
+                "scanProblems": [{"path": "secret", "error": "permission denied"}],
+                "excludedScanProblems": 4
+            },
+            "beta": {
+                "protocol": "local", "path": "/b",
+                "transitionProblems": [{"path": "big.iso", "error": "no space left on device"}],
+                "excludedTransitionProblems": 1
+            },
+            "conflicts": [{"root": "a.txt"}],
+            "excludedConflicts": 12
+        }"#;
+        let session: SyncSession = serde_json::from_str(json).unwrap();
+        assert_eq!(session.conflict_count(), 1);
+        assert_eq!(session.total_conflicts(), 13);
+        let paths: Vec<_> = session.problems().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, vec!["secret", "big.iso"]);
+        assert_eq!(session.excluded_problems(), 5);
+    }
+
     // ============ list_sessions tests ============
 
     #[tokio::test]
@@ -648,6 +1917,111 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_list_sessions_keeps_raw_json() {
+        let runner = MockCommandRunner::new();
+        let json = r#"[{
+            "name": "web",
+            "identifier": "sync_abc",
+            "alpha": {"protocol": "local", "path": "/a", "connected": true},
+            "beta": {"protocol": "local", "path": "/b", "connected": true},
+            "status": "watching",
+            "paused": false,
+            "mode": "two-way-resolved",
+            "labels": {"team": "infra"}
+        }]"#;
+        runner.expect(
+            "mutagen sync list --template {{json .}}",
+            success_output(json),
+        );
+
+        let client = MutagenClient::with_runner(runner);
+        client.list_sessions().await.unwrap();
+
+        // Fields SyncSession doesn't model are still available
+        let raw = client.raw_session("sync_abc").unwrap();
+        assert_eq!(raw["mode"], "two-way-resolved");
+        assert_eq!(raw["labels"]["team"], "infra");
+        assert!(client.raw_session("sync_other").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_sessions_by_id_passes_identifiers() {
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            "mutagen sync list --template {{json .}} sync_a sync_b",
+            success_output("[]"),
+        );
+
+        let client = MutagenClient::with_runner(runner);
+        let ids = vec!["sync_a".to_string(), "sync_b".to_string()];
+        assert!(client.list_sessions_by_id(&ids).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_sessions_by_label_passes_selector() {
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            "mutagen sync list --template {{json .}} --label-selector io.mutagen.project=project_a",
+            success_output("[]"),
+        );
+
+        let client = MutagenClient::with_runner(runner);
+        let selector = format!("{}=project_a", PROJECT_LABEL);
+        assert!(client
+            .list_sessions_by_label(&selector)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_session_prefix() {
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            "mutagen sync create /a server:/b -m two-way-safe -n mg-web",
+            success_output(""),
+        );
+        let session = |name: &str, labels: serde_json::Value| {
+            serde_json::json!({
+                "name": name,
+                "identifier": format!("sync_{}", name),
+                "alpha": {"protocol": "local", "path": "/a"},
+                "beta": {"protocol": "ssh", "path": "/b", "host": "server"},
+                "status": "Watching for changes",
+                "paused": false,
+                "labels": labels,
+            })
+        };
+        let origin = serde_json::json!({"mutagui.host": "laptop", "mutagui.user": "ada"});
+        let listed = serde_json::json!([
+            session("mg-web", origin.clone()),
+            session("api", serde_json::json!({})),
+            session("mg-", origin),
+            session("mg-docs", serde_json::json!({})),
+        ]);
+        runner.expect(
+            "mutagen sync list --template {{json .}}",
+            success_output(&listed.to_string()),
+        );
+
+        let client = MutagenClient::with_runner(runner).with_settings(MutagenConfig {
+            session_prefix: "mg-".to_string(),
+            ..MutagenConfig::default()
+        });
+        client
+            .create_session("web", "/a", "server:/b", "two-way-safe", &[])
+            .await
+            .unwrap();
+
+        // Shown without the prefix; sessions made by hand are left as they
+        // are, even when their names start the same way
+        let sessions = client.list_sessions().await.unwrap();
+        let names: Vec<_> = sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["web", "api", "mg-", "mg-docs"]);
+        assert_eq!(client.raw_session("sync_mg-web").unwrap()["name"], "mg-web");
+    }
+
     #[tokio::test]
     async fn test_list_sessions_command_fails() {
         let runner = MockCommandRunner::new();
@@ -657,58 +2031,302 @@ mod tests {
         );
 
         let client = MutagenClient::with_runner(runner);
-        let result = client.list_sessions().await;
-
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("daemon not running"));
+        let result = client.list_sessions().await;
+
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("daemon not running"));
+    }
+
+    #[tokio::test]
+    async fn test_list_sessions_invalid_json() {
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            "mutagen sync list --template {{json .}}",
+            success_output("not valid json"),
+        );
+
+        let client = MutagenClient::with_runner(runner);
+        let result = client.list_sessions().await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_sessions_with_invalid_utf8_path() {
+        // Latin-1 file names, as left behind by an old archive
+        let json = [
+            br#"[{"name": "photos", "identifier": "sync_photos", "status": "Watching for changes","#
+                .as_slice(),
+            br#""paused": false, "alpha": { "protocol": "local", "path": "/data/caf"#,
+            b"\xe9",
+            br#"" }, "beta": { "protocol": "local", "path": "/backup" },"#,
+            br#""conflicts": [{ "root": "r"#,
+            b"\xe9sum\xe9",
+            br#".txt" }]}]"#,
+        ]
+        .concat();
+        let mut output = success_output("");
+        output.stdout = json;
+
+        let runner = MockCommandRunner::new();
+        runner.expect("mutagen sync list --template {{json .}}", output);
+
+        let client = MutagenClient::with_runner(runner);
+        let sessions = client.list_sessions().await.unwrap();
+        assert_eq!(sessions[0].alpha.path, "/data/caf\u{fffd}");
+        assert_eq!(sessions[0].conflicts[0].root, "r\u{fffd}sum\u{fffd}.txt");
+    }
+
+    #[tokio::test]
+    async fn test_failure_message_with_invalid_utf8() {
+        let runner = MockCommandRunner::new();
+        let mut output = failure_output("");
+        output.stderr = b"ssh: Verbindung abgelehnt \xfc\x1b[0m\r\n".to_vec();
+        runner.expect("mutagen sync pause sync_1", output);
+
+        let client = MutagenClient::with_runner(runner);
+        let error = client.pause_session("sync_1").await.unwrap_err().to_string();
+        assert!(error.contains("Verbindung abgelehnt \u{fffd}\\u{1b}[0m"));
+        assert!(!error.contains('\r'));
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures() {
+        let runner = Arc::new(MockCommandRunner::new());
+        let list = "mutagen sync list --template {{json .}}";
+        let daemon_down = "Error: unable to connect to daemon: connection refused";
+        runner.expect_error(list, "Command 'mutagen' timed out after 5 seconds");
+        runner.expect(list, failure_output(daemon_down));
+        runner.expect(list, success_output("[]"));
+        runner.expect_error("mutagen sync pause sync_1", "Command 'mutagen' timed out");
+        runner.expect("mutagen sync resume sync_1", failure_output(daemon_down));
+        runner.expect("mutagen sync resume sync_1", failure_output(daemon_down));
+        runner.expect("mutagen sync resume sync_1", failure_output(daemon_down));
+
+        let mut client = MutagenClient::with_runner(runner.clone());
+        client.settings.retry_delay_ms = 0;
+        assert!(client.list_sessions().await.unwrap().is_empty());
+        assert_eq!(runner.executed_commands().len(), 3);
+
+        // An action that timed out may have happened, so it isn't repeated.
+        assert!(client.pause_session("sync_1").await.is_err());
+        assert_eq!(runner.executed_commands().len(), 4);
+
+        let error = client.resume_session("sync_1").await.unwrap_err();
+        assert!(error.to_string().contains("unable to connect to daemon"));
+        assert_eq!(runner.executed_commands().len(), 7);
+    }
+
+    // ============ pause_session tests ============
+
+    #[tokio::test]
+    async fn test_pause_session_success() {
+        let runner = MockCommandRunner::new();
+        runner.expect("mutagen sync pause session-123", success_output(""));
+
+        let client = MutagenClient::with_runner(runner);
+        let result = client.pause_session("session-123").await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_pause_session_failure() {
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            "mutagen sync pause session-123",
+            failure_output("session not found"),
+        );
+
+        let client = MutagenClient::with_runner(runner);
+        let result = client.pause_session("session-123").await;
+
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("session not found"));
+    }
+
+    // ============ list_forwards tests ============
+
+    #[tokio::test]
+    async fn test_list_forwards() {
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            "mutagen forward list --template {{json .}}",
+            success_output(
+                r#"[{"identifier": "fwrd_1", "name": "web",
+                    "source": {"protocol": "local", "endpoint": "tcp:localhost:8080",
+                               "connected": true},
+                    "destination": {"protocol": "ssh", "host": "server",
+                                    "endpoint": "tcp::80", "connected": false},
+                    "paused": false, "status": "connecting-destination",
+                    "openConnections": 2}]"#,
+            ),
+        );
+
+        let client = MutagenClient::with_runner(runner);
+        let forwards = client.list_forwards().await.unwrap();
+
+        assert_eq!(forwards.len(), 1);
+        assert_eq!(forwards[0].name, "web");
+        assert_eq!(forwards[0].source.display(), "tcp:localhost:8080");
+        assert_eq!(forwards[0].destination.display(), "server:tcp::80");
+        assert!(!forwards[0].destination.connected);
+        assert_eq!(forwards[0].open_connections, 2);
+    }
+
+    // ============ create_session tests ============
+
+    #[tokio::test]
+    async fn test_create_session_with_mode_and_ignores() {
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            "mutagen sync create /data nas:/data -m one-way-safe -n data --ignore *.tmp --ignore .cache",
+            success_output(""),
+        );
+
+        let client = MutagenClient::with_runner(runner);
+        let ignore = vec!["*.tmp".to_string(), ".cache".to_string()];
+        client
+            .create_session("data", "/data", "nas:/data", "one-way-safe", &ignore)
+            .await
+            .unwrap();
     }
 
     #[tokio::test]
-    async fn test_list_sessions_invalid_json() {
+    async fn test_records_action_commands() {
         let runner = MockCommandRunner::new();
         runner.expect(
             "mutagen sync list --template {{json .}}",
-            success_output("not valid json"),
+            success_output("[]"),
+        );
+        runner.expect("mutagen sync pause sync_api", success_output(""));
+        runner.expect(
+            "mutagen project start -f /code/my app/mutagen.yml",
+            success_output(""),
         );
-
         let client = MutagenClient::with_runner(runner);
-        let result = client.list_sessions().await;
+        client.pause_session("sync_api").await.unwrap();
+        assert!(client.take_commands().is_empty());
 
-        assert!(result.is_err());
+        client
+            .runner
+            .expect("mutagen sync pause sync_api", success_output(""));
+        client.record_commands();
+        client.list_sessions().await.unwrap();
+        client.pause_session("sync_api").await.unwrap();
+        client
+            .start_project(Path::new("/code/my app/mutagen.yml"))
+            .await
+            .unwrap();
+        assert_eq!(
+            client.take_commands(),
+            [
+                "mutagen sync pause sync_api",
+                "mutagen project start -f '/code/my app/mutagen.yml'",
+            ]
+        );
+        assert!(client.take_commands().is_empty());
     }
 
-    // ============ pause_session tests ============
+    #[tokio::test]
+    async fn test_dry_run_skips_actions() {
+        let dir = tempfile::tempdir().unwrap();
+        let new_dir = dir.path().join("new");
+        let client = MutagenClient::with_runner(MockCommandRunner::new());
+        client.set_dry_run(true);
+        client.terminate_session("sync_api").await.unwrap();
+        client
+            .ensure_endpoint_directory_exists(&new_dir.to_string_lossy())
+            .await
+            .unwrap();
+        client
+            .create_session("api", "/code/api", "server:/srv/api", "two-way-safe", &[])
+            .await
+            .unwrap();
+
+        assert!(client.runner.executed_commands().is_empty());
+        assert!(!new_dir.exists());
+        assert_eq!(
+            client.take_commands(),
+            [
+                "mutagen sync terminate sync_api".to_string(),
+                format!("mkdir -p {}", new_dir.display()),
+                "mutagen sync create /code/api 'server:/srv/api' -m two-way-safe -n api"
+                    .to_string(),
+            ]
+        );
+    }
 
     #[tokio::test]
-    async fn test_pause_session_success() {
+    async fn test_created_sessions_are_labeled_with_origin() {
         let runner = MockCommandRunner::new();
-        runner.expect("mutagen sync pause session-123", success_output(""));
-
-        let client = MutagenClient::with_runner(runner);
-        let result = client.pause_session("session-123").await;
+        runner.expect(
+            "mutagen sync create /data nas:/data -n data --label mutagui.host=box --label mutagui.user=jane",
+            success_output(""),
+        );
+        runner.expect(
+            "mutagen sync list --template {{json .}}",
+            success_output(
+                r#"[{"name": "data", "identifier": "sync_data",
+                    "alpha": {"protocol": "local", "path": "/data", "connected": true},
+                    "beta": {"protocol": "ssh", "host": "nas", "path": "/data", "connected": true},
+                    "status": "watching", "paused": false,
+                    "labels": {"mutagui.host": "box", "mutagui.user": "jane"}}]"#,
+            ),
+        );
 
-        assert!(result.is_ok());
+        let origin = Origin {
+            host: "box".to_string(),
+            user: "jane".to_string(),
+        };
+        let client = MutagenClient::with_runner(runner).with_origin(origin.clone());
+        client
+            .create_spec_session("data", "/data", "nas:/data", &[])
+            .await
+            .unwrap();
+        let sessions = client.list_sessions().await.unwrap();
+        assert_eq!(sessions[0].origin(), Some(origin));
     }
 
+    // ============ reset_session tests ============
+
     #[tokio::test]
-    async fn test_pause_session_failure() {
+    async fn test_reset_session() {
         let runner = MockCommandRunner::new();
+        runner.expect("mutagen sync reset session-123", success_output(""));
         runner.expect(
-            "mutagen sync pause session-123",
-            failure_output("session not found"),
+            "mutagen sync reset session-404",
+            failure_output("unable to locate requested sessions"),
         );
 
         let client = MutagenClient::with_runner(runner);
-        let result = client.pause_session("session-123").await;
+        assert!(client.reset_session("session-123").await.is_ok());
+        let err = client.reset_session("session-404").await.unwrap_err();
+        assert!(err.to_string().contains("unable to locate"));
+    }
 
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("session not found"));
+    #[test]
+    fn test_safety_halt_from_status() {
+        assert_eq!(
+            SafetyHalt::from_status("halted-on-root-deletion"),
+            Some(SafetyHalt::Deleted)
+        );
+        assert_eq!(
+            SafetyHalt::from_status("Halted due to root emptying"),
+            Some(SafetyHalt::Emptied)
+        );
+        assert_eq!(
+            SafetyHalt::from_status("halted-on-root-type-change"),
+            Some(SafetyHalt::TypeChanged)
+        );
+        assert_eq!(SafetyHalt::from_status("Watching for changes"), None);
+        assert_eq!(SafetyHalt::from_status("disconnected"), None);
     }
 
     // ============ resume_session tests ============
@@ -742,7 +2360,10 @@ mod tests {
     #[tokio::test]
     async fn test_flush_session_success() {
         let runner = MockCommandRunner::new();
-        runner.expect("mutagen sync flush session-123", success_output(""));
+        runner.expect(
+            "mutagen sync flush --skip-wait session-123",
+            success_output(""),
+        );
 
         let client = MutagenClient::with_runner(runner);
         let result = client.flush_session("session-123").await;
@@ -878,6 +2499,45 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_ensure_endpoint_directory_exists_uses_ssh_config() {
+        let runner = MockCommandRunner::new();
+        runner.expect("ssh -p 2222 deploy@server mkdir -p /remote/path", success_output(""));
+        runner.expect("ssh -p 2200 root@server mkdir -p /remote/path", success_output(""));
+
+        let ssh_config = SshConfig::parse("Host server\n  User deploy\n  Port 2222\n");
+        let client = MutagenClient::with_runner(runner).with_ssh_config(ssh_config);
+
+        // Values from ~/.ssh/config fill in what the endpoint leaves out
+        let result = client
+            .ensure_endpoint_directory_exists("server:/remote/path")
+            .await;
+        assert!(result.is_ok());
+
+        // Values in the endpoint take precedence
+        let result = client
+            .ensure_endpoint_directory_exists("ssh://root@server:2200/remote/path")
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_ensure_endpoint_directory_exists_on_windows_host() {
+        let runner = MockCommandRunner::new();
+        let script = "New-Item -ItemType Directory -Force -Path 'C:\\Users\\me''s app' | Out-Null";
+        runner.expect(
+            &format!("ssh winbox powershell -NoProfile -Command \"{}\"", script),
+            success_output(""),
+        );
+
+        let client = MutagenClient::with_runner(runner);
+        let result = client
+            .ensure_endpoint_directory_exists("winbox:C:\\Users\\me's app")
+            .await;
+        assert!(result.is_ok(), "{:?}", result);
+        assert!(remote_mkdir_command("/D:/work").contains(" -Path 'D:/work' "));
+    }
+
     #[tokio::test]
     async fn test_ensure_endpoint_directory_exists_windows_drive_letter() {
         // Windows paths like C:\path should be treated as local, not remote
@@ -933,6 +2593,297 @@ mod tests {
             .contains("Permission denied"));
     }
 
+    #[tokio::test]
+    async fn test_ensure_endpoint_directory_exists_docker_user() {
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            "docker exec --user www-data web sh -c mkdir -p /var/www",
+            success_output(""),
+        );
+
+        let client = MutagenClient::with_runner(runner);
+        let result = client
+            .ensure_endpoint_directory_exists("docker://www-data@web/var/www")
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_compose_project_uses_mutagen_compose() {
+        let runner = Arc::new(MockCommandRunner::new());
+        runner.expect(
+            "mutagen-compose -f /app/compose.yaml up --detach",
+            success_output(""),
+        );
+        runner.expect(
+            "mutagen-compose -f /app/compose.yaml down",
+            failure_output("no such service"),
+        );
+
+        let client = MutagenClient::with_runner(runner.clone());
+        let compose_file = Path::new("/app/compose.yaml");
+        client.start_project(compose_file).await.unwrap();
+        let error = client.terminate_project(compose_file).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "mutagen-compose down failed: no such service"
+        );
+        assert!(!runner
+            .executed_commands()
+            .iter()
+            .any(|c| c.starts_with("mutagen project")));
+    }
+
+    // ============ run_hooks tests ============
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_hooks_stops_at_failure() {
+        let runner = Arc::new(MockCommandRunner::new());
+        runner.expect(
+            "sh -c cd '/my projects' && make assets",
+            success_output("built\n"),
+        );
+        runner.expect(
+            "sh -c cd '/my projects' && ./notify",
+            failure_output("no network"),
+        );
+
+        let client = MutagenClient::with_runner(runner.clone());
+        let commands = ["make assets", "./notify", "echo done"].map(String::from);
+        let error = client
+            .run_hooks(
+                Path::new("/my projects/mutagen.yml"),
+                "beforeCreate",
+                &commands,
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "beforeCreate command `./notify` failed: no network"
+        );
+        assert_eq!(runner.executed_commands().len(), 2);
+    }
+
+    // ============ verify_session tests ============
+
+    #[tokio::test]
+    async fn test_verify_session_sample_over_ssh() {
+        let json = r#"{
+            "name": "web", "identifier": "sync_web", "status": "Watching for changes", "paused": false,
+            "alpha": { "protocol": "local", "path": "/src/web" },
+            "beta": { "protocol": "ssh", "user": "deploy", "host": "server", "path": "~/web" }
+        }"#;
+        let session: SyncSession = serde_json::from_str(json).unwrap();
+        let files = vec!["a.txt".to_string(), "b.txt".to_string()];
+
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            &format!("sh -c {}", verify::list_script("/src/web")),
+            success_output("./b.txt\n./a.txt\n"),
+        );
+        runner.expect(
+            &format!("sh -c {}", verify::hash_script("/src/web", Some(&files))),
+            success_output("1111  a.txt\n2222  b.txt\n"),
+        );
+        runner.expect(
+            &format!("ssh -p 2222 deploy@server {}", verify::hash_script("~/web", Some(&files))),
+            success_output("1111  a.txt\n"),
+        );
+
+        let ssh_config = SshConfig::parse("Host server\n  Port 2222\n");
+        let client = MutagenClient::with_runner(runner).with_ssh_config(ssh_config);
+        let report = client
+            .verify_session(&session, VerifyMode::Sample(10), &Ignores::new(&[]))
+            .await
+            .unwrap();
+
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.mismatches.len(), 1);
+        assert_eq!(report.mismatches[0].path, "b.txt");
+        assert_eq!(report.mismatches[0].kind, verify::MismatchKind::MissingOnBeta);
+    }
+
+    #[tokio::test]
+    async fn test_verify_session_leaves_out_ignored_files() {
+        let json = r#"{
+            "name": "web", "identifier": "sync_web", "status": "Watching for changes", "paused": false,
+            "alpha": { "protocol": "local", "path": "/src/web" },
+            "beta": { "protocol": "local", "path": "/srv/web" }
+        }"#;
+        let session: SyncSession = serde_json::from_str(json).unwrap();
+        let ignores = Ignores::new(&["node_modules".to_string(), ".git".to_string()]);
+
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            &format!("sh -c {}", verify::hash_script("/src/web", None)),
+            success_output("1111  ./a.txt\n2222  ./node_modules/x.js\n3333  ./.git/HEAD\n"),
+        );
+        runner.expect(
+            &format!("sh -c {}", verify::hash_script("/srv/web", None)),
+            success_output("1111  ./a.txt\n"),
+        );
+        let client = MutagenClient::with_runner(runner);
+        let report = client
+            .verify_session(&session, VerifyMode::Full, &ignores)
+            .await
+            .unwrap();
+        assert_eq!(report.checked, 1);
+        assert!(report.mismatches.is_empty());
+
+        // Sampling doesn't pick ignored files either
+        let files = vec!["a.txt".to_string()];
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            &format!("sh -c {}", verify::list_script("/src/web")),
+            success_output("./node_modules/x.js\n./a.txt\n"),
+        );
+        runner.expect(
+            &format!("sh -c {}", verify::hash_script("/src/web", Some(&files))),
+            success_output("1111  a.txt\n"),
+        );
+        runner.expect(
+            &format!("sh -c {}", verify::hash_script("/srv/web", Some(&files))),
+            success_output("1111  a.txt\n"),
+        );
+        let client = MutagenClient::with_runner(runner);
+        let report = client
+            .verify_session(&session, VerifyMode::Sample(10), &ignores)
+            .await
+            .unwrap();
+        assert_eq!(report.checked, 1);
+        assert!(report.mismatches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_disk_space_over_ssh() {
+        let endpoint: Endpoint = serde_json::from_str(
+            r#"{ "protocol": "ssh", "host": "server", "path": "/srv/web" }"#,
+        )
+        .unwrap();
+
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            &format!("ssh server {}", disk::df_script("/srv/web")),
+            success_output(
+                "Filesystem 1024-blocks Used Available Capacity Mounted on\n\
+                 /dev/sda1 1000 250 750 25% /\n",
+            ),
+        );
+
+        let client = MutagenClient::with_runner(runner);
+        let space = client.disk_space(&endpoint).await.unwrap();
+        assert_eq!(space.available_bytes, 750 * 1024);
+        assert_eq!(space.total_bytes, 1000 * 1024);
+    }
+
+    #[tokio::test]
+    async fn test_disk_space_in_container_uses_session_daemon() {
+        let endpoint: Endpoint = serde_json::from_str(
+            r#"{
+                "protocol": "docker",
+                "user": "app",
+                "host": "web",
+                "path": "/srv/web",
+                "environment": { "DOCKER_CONTEXT": "staging" }
+            }"#,
+        )
+        .unwrap();
+
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            &format!(
+                "docker --context staging exec --user app web sh -c {}",
+                disk::df_script("/srv/web")
+            ),
+            success_output(
+                "Filesystem 1024-blocks Used Available Capacity Mounted on\n\
+                 overlay 1000 250 750 25% /\n",
+            ),
+        );
+
+        let client = MutagenClient::with_runner(runner);
+        let space = client.disk_space(&endpoint).await.unwrap();
+        assert_eq!(space.available_bytes, 750 * 1024);
+    }
+
+    #[tokio::test]
+    async fn test_check_ssh_host() {
+        let check = "ssh -o BatchMode=yes -o ConnectTimeout=5";
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            &format!("{check} -p 2222 deploy@up true"),
+            success_output(""),
+        );
+        runner.expect(
+            &format!("{check} locked true"),
+            failure_output("deploy@locked: Permission denied (publickey,password)."),
+        );
+        runner.expect(
+            &format!("{check} down true"),
+            failure_output(
+                "Warning: reverse mapping failed\n\
+                 ssh: connect to host down port 22: Connection timed out\n",
+            ),
+        );
+
+        let client = MutagenClient::with_runner(runner);
+        let up = client.check_ssh_host(Some("deploy".to_string()), "up", Some(2222));
+        assert_eq!(up.await.unwrap(), HostReachability::Reachable);
+        assert!(matches!(
+            client.check_ssh_host(None, "locked", None).await.unwrap(),
+            HostReachability::NeedsAuthentication(_)
+        ));
+        assert_eq!(
+            client.check_ssh_host(None, "down", None).await.unwrap(),
+            HostReachability::Unreachable(
+                "ssh: connect to host down port 22: Connection timed out".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_shell_command() {
+        let client = MutagenClient::with_runner(MockCommandRunner::new());
+        let args = |endpoint: &str| {
+            let command = client.shell_command(&EndpointAddress::parse(endpoint));
+            std::iter::once(command.get_program())
+                .chain(command.get_args())
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+        let shell = "exec \"${SHELL:-sh}\" -l";
+        assert_eq!(
+            args("ssh://deploy@server:2222/srv/my app"),
+            [
+                "ssh",
+                "-t",
+                "-p",
+                "2222",
+                "deploy@server",
+                &format!("cd '/srv/my app' || exit 1; {shell}"),
+            ]
+        );
+        assert_eq!(
+            args("docker://web/app"),
+            [
+                "docker",
+                "exec",
+                "-it",
+                "web",
+                "sh",
+                "-c",
+                &format!("cd /app || exit 1; {shell}"),
+            ]
+        );
+
+        let local = client.shell_command(&EndpointAddress::parse("/tmp"));
+        assert_eq!(local.get_current_dir(), Some(Path::new("/tmp")));
+    }
+
     // ============ get_project_lock_path tests ============
 
     #[test]
@@ -1031,23 +2982,28 @@ mod tests {
             success_output("[]"),
         );
 
-        // Second start attempt succeeds (after lock removal)
-        runner.expect(
-            &format!(
-                "mutagen project start -f {}",
-                project_path.to_string_lossy()
-            ),
-            success_output(""),
-        );
-
         let client = MutagenClient::with_runner(runner);
-        let result = client.start_project(&project_path).await;
+        let error = client.start_project(&project_path).await.unwrap_err();
+
+        // The lock is only reported until the user confirms removing it
+        let lock = error.downcast_ref::<StaleLock>().unwrap().clone();
+        assert_eq!(lock.path, lock_path);
+        assert_eq!(lock.identifier, "proj_stale_identifier");
+        assert!(
+            lock_path.exists(),
+            "Lock file should be kept until confirmed"
+        );
 
-        assert!(result.is_ok(), "start_project should succeed");
+        client.remove_stale_lock(&lock).unwrap();
         assert!(
             !lock_path.exists(),
             "Lock file should have been removed after stale lock cleanup"
         );
+
+        // A lock written again since it was checked is left alone
+        std::fs::write(&lock_path, "proj_new_identifier").unwrap();
+        assert!(client.remove_stale_lock(&lock).is_err());
+        assert!(lock_path.exists());
     }
 
     #[tokio::test]
@@ -1097,22 +3053,48 @@ mod tests {
             success_output(unrelated_sessions),
         );
 
+        let client = MutagenClient::with_runner(runner);
+        let error = client.start_project(&project_path).await.unwrap_err();
+        let lock = error.downcast_ref::<StaleLock>().unwrap();
+        assert_eq!(lock.identifier, "another_stale_session");
+    }
+
+    #[tokio::test]
+    async fn test_start_project_lock_held_by_labeled_sessions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project_path = temp_dir.path().join("mutagen.yml");
+        std::fs::write(
+            &project_path,
+            "sync:\n  cool:\n    alpha: /cool\n    beta: server:/cool\n",
+        )
+        .unwrap();
+        std::fs::write(temp_dir.path().join("mutagen.yml.lock"), "project_abc").unwrap();
+
+        // A session renamed since the project started still carries its label
+        let runner = MockCommandRunner::new();
+        let start = format!(
+            "mutagen project start -f {}",
+            project_path.to_string_lossy()
+        );
+        runner.expect(&start, failure_output("Error: project already running"));
+        let sessions = serde_json::json!([{
+            "name": "renamed",
+            "identifier": "session-1",
+            "alpha": {"protocol": "local", "path": "/cool"},
+            "beta": {"protocol": "ssh", "path": "/cool", "host": "server"},
+            "status": "Watching for changes",
+            "paused": false,
+            "labels": {PROJECT_LABEL: "project_abc"},
+        }]);
         runner.expect(
-            &format!(
-                "mutagen project start -f {}",
-                project_path.to_string_lossy()
-            ),
-            success_output(""),
+            "mutagen sync list --template {{json .}}",
+            success_output(&sessions.to_string()),
         );
 
         let client = MutagenClient::with_runner(runner);
-        let result = client.start_project(&project_path).await;
-
-        assert!(result.is_ok(), "start_project should succeed");
-        assert!(
-            !lock_path.exists(),
-            "Lock file should have been removed after cleaning up unrelated sessions"
-        );
+        let error = client.start_project(&project_path).await.unwrap_err();
+        assert!(error.downcast_ref::<StaleLock>().is_none());
+        assert!(error.to_string().contains("project already running"));
     }
 
     #[tokio::test]
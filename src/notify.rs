@@ -0,0 +1,965 @@
+//! Notification delivery for sync events.
+//!
+//! A `Notifier` fans each event out to its configured channels. Channels
+//! shell out through `CommandRunner` like the rest of mutagui (`notify-send`,
+//! `osascript`, `curl`), so they can be exercised with the mock runner in
+//! tests. Channels that may take a while, such as user hooks, are sent to
+//! from a background task, so refreshes and key handling don't wait on them.
+
+use crate::command::{CommandRunner, SystemCommandRunner};
+use crate::config::{Config, WebhookConfig};
+use crate::health::HealthEvent;
+use crate::mutagen::SyncSession;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Local;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Events a background channel can have waiting before more are dropped.
+const QUEUE_LEN: usize = 32;
+
+/// Something worth telling the user about.
+#[derive(Debug, Clone)]
+pub enum NotificationEvent {
+    /// A problem detected by the health monitor.
+    Health(HealthEvent),
+    /// A project was started from mutagui.
+    ProjectStarted(String),
+    /// A project's sessions were terminated from mutagui.
+    ProjectTerminated(String),
+    /// A flush started from mutagui finished its sync cycle.
+    FlushCompleted(String),
+    /// A session appeared, started from mutagui or elsewhere.
+    SessionStarted(SessionRef),
+    /// A push session finished its first sync cycle.
+    PushCompleted(SessionRef),
+}
+
+/// The session a lifecycle event is about.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionRef {
+    pub name: String,
+    pub alpha: String,
+    pub beta: String,
+}
+
+impl SessionRef {
+    fn of(session: &SyncSession) -> Self {
+        Self {
+            name: session.name.clone(),
+            alpha: session.alpha.display_path(),
+            beta: session.beta.display_path(),
+        }
+    }
+}
+
+/// Sessions that started or finished a push between two refreshes.
+pub fn lifecycle_events(
+    previous: &[SyncSession],
+    sessions: &[SyncSession],
+) -> Vec<NotificationEvent> {
+    let previous: HashMap<_, _> = previous
+        .iter()
+        .map(|session| (session.identifier.as_str(), session))
+        .collect();
+    let mut events = Vec::new();
+    for session in sessions {
+        let old = previous.get(session.identifier.as_str());
+        if old.is_none() {
+            events.push(NotificationEvent::SessionStarted(SessionRef::of(session)));
+        }
+        // Each push creates a new session, so its first cycle is the push
+        let cycles = |s: &SyncSession| s.successful_cycles.unwrap_or(0);
+        if session.is_one_way() && cycles(session) > 0 && old.is_none_or(|old| cycles(old) == 0) {
+            events.push(NotificationEvent::PushCompleted(SessionRef::of(session)));
+        }
+    }
+    events
+}
+
+impl NotificationEvent {
+    /// Stable name used to select events in config.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Health(event) => event.kind.key(),
+            Self::ProjectStarted(_) => "project_started",
+            Self::ProjectTerminated(_) => "project_terminated",
+            Self::FlushCompleted(_) => "flush_completed",
+            Self::SessionStarted(_) => "session_started",
+            Self::PushCompleted(_) => "push_completed",
+        }
+    }
+
+    /// Session or project the event is about.
+    pub fn subject(&self) -> &str {
+        match self {
+            Self::Health(event) => &event.session_name,
+            Self::ProjectStarted(name)
+            | Self::ProjectTerminated(name)
+            | Self::FlushCompleted(name) => name,
+            Self::SessionStarted(session) | Self::PushCompleted(session) => &session.name,
+        }
+    }
+
+    /// Alpha and beta endpoints of the session the event is about.
+    pub fn endpoints(&self) -> Option<(&str, &str)> {
+        match self {
+            Self::Health(event) => Some((&event.alpha, &event.beta)),
+            Self::SessionStarted(session) | Self::PushCompleted(session) => {
+                Some((&session.alpha, &session.beta))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            Self::Health(event) => event.title(),
+            Self::ProjectStarted(name) => format!("mutagui: {} started", name),
+            Self::ProjectTerminated(name) => format!("mutagui: {} terminated", name),
+            Self::FlushCompleted(name) => format!("mutagui: {} flushed", name),
+            Self::SessionStarted(session) => format!("mutagui: {} started", session.name),
+            Self::PushCompleted(session) => format!("mutagui: {} pushed", session.name),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Health(event) => event.describe(),
+            Self::ProjectStarted(name) => format!("Project {} started", name),
+            Self::ProjectTerminated(name) => format!("Project {} terminated", name),
+            Self::FlushCompleted(name) => format!("Flush of {} finished", name),
+            Self::SessionStarted(session) => format!("Session {} started", session.name),
+            Self::PushCompleted(session) => format!("Push of {} finished", session.name),
+        }
+    }
+}
+
+/// A destination for notifications.
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    /// Name used in error messages.
+    fn name(&self) -> &'static str;
+
+    /// Whether this channel wants the event at all.
+    fn accepts(&self, event: &NotificationEvent) -> bool;
+
+    /// Whether to send from a background task instead of while the caller
+    /// waits.
+    fn background(&self) -> bool {
+        false
+    }
+
+    /// Check an event as it's handed over, before it waits for a background
+    /// task, so a limit applies when events happen rather than when they're
+    /// sent.
+    fn admit(&self, _event: &NotificationEvent) -> Result<()> {
+        Ok(())
+    }
+
+    async fn send(&self, event: &NotificationEvent) -> Result<()>;
+}
+
+/// Desktop notifications via `osascript` on macOS and `notify-send` elsewhere.
+///
+/// Only health problems are shown; the user already knows about actions
+/// they took themselves.
+pub struct DesktopNotifier<R: CommandRunner = SystemCommandRunner> {
+    runner: R,
+}
+
+impl DesktopNotifier<SystemCommandRunner> {
+    pub fn new() -> Self {
+        Self {
+            runner: SystemCommandRunner::new(),
+        }
+    }
+}
+
+impl<R: CommandRunner> DesktopNotifier<R> {
+    #[cfg(test)]
+    pub fn with_runner(runner: R) -> Self {
+        Self { runner }
+    }
+
+    /// Program and arguments that display a notification on this platform.
+    fn command(title: &str, body: &str) -> (&'static str, Vec<String>) {
+        if cfg!(target_os = "macos") {
+            let quote = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+            let script = format!(
+                "display notification \"{}\" with title \"{}\"",
+                quote(body),
+                quote(title)
+            );
+            ("osascript", vec!["-e".to_string(), script])
+        } else {
+            ("notify-send", vec![title.to_string(), body.to_string()])
+        }
+    }
+}
+
+#[async_trait]
+impl<R: CommandRunner> NotificationChannel for DesktopNotifier<R> {
+    fn name(&self) -> &'static str {
+        "desktop"
+    }
+
+    fn accepts(&self, event: &NotificationEvent) -> bool {
+        matches!(event, NotificationEvent::Health(_))
+    }
+
+    async fn send(&self, event: &NotificationEvent) -> Result<()> {
+        let (program, args) = Self::command(&event.title(), &event.describe());
+        let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let output = self.runner.run(program, &args, 5).await?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} failed: {}",
+                program,
+                crate::command::decode_message(&output.stderr)
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Alert for finished flushes: a terminal bell, a shell command, or both,
+/// so a long flush can be left running and noticed when it's done.
+///
+/// The command runs with `sh -c`, with the spec name as `$1`.
+pub struct FlushAlert<R: CommandRunner = SystemCommandRunner> {
+    runner: R,
+    bell: bool,
+    command: Option<String>,
+}
+
+impl FlushAlert<SystemCommandRunner> {
+    pub fn new(bell: bool, command: Option<String>) -> Self {
+        Self::with_runner(SystemCommandRunner::new(), bell, command)
+    }
+}
+
+impl<R: CommandRunner> FlushAlert<R> {
+    pub fn with_runner(runner: R, bell: bool, command: Option<String>) -> Self {
+        Self {
+            runner,
+            bell,
+            command,
+        }
+    }
+}
+
+#[async_trait]
+impl<R: CommandRunner> NotificationChannel for FlushAlert<R> {
+    fn name(&self) -> &'static str {
+        "flush alert"
+    }
+
+    fn accepts(&self, event: &NotificationEvent) -> bool {
+        matches!(event, NotificationEvent::FlushCompleted(_))
+    }
+
+    async fn send(&self, event: &NotificationEvent) -> Result<()> {
+        if self.bell {
+            use std::io::Write;
+            let mut stdout = std::io::stdout();
+            stdout.write_all(b"\x07")?;
+            stdout.flush()?;
+        }
+        if let Some(command) = &self.command {
+            #[cfg(unix)]
+            let (program, args) = ("sh", ["-c", command.as_str(), "mutagui", event.subject()]);
+            // cmd has no positional parameters, so the spec name follows the command
+            #[cfg(not(unix))]
+            let (program, args) = ("cmd", ["/C", command.as_str(), event.subject()]);
+            let output = self.runner.run(program, &args, 30).await?;
+            if !output.status.success() {
+                anyhow::bail!(
+                    "command exited with {}: {}",
+                    output.status,
+                    crate::command::decode_message(&output.stderr).trim()
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// User commands from the `[hooks]` config section, by event name.
+///
+/// Each runs with `sh -c`, with the event in `MUTAGUI_EVENT`, the session or
+/// project in `MUTAGUI_SESSION`, and, for session events, the endpoints in
+/// `MUTAGUI_ALPHA` and `MUTAGUI_BETA`.
+pub struct HookRunner<R: CommandRunner = SystemCommandRunner> {
+    runner: R,
+    hooks: BTreeMap<String, String>,
+    timeout_secs: u64,
+}
+
+impl HookRunner<SystemCommandRunner> {
+    pub fn new(hooks: BTreeMap<String, String>, timeout_secs: u64) -> Self {
+        Self::with_runner(SystemCommandRunner::new(), hooks, timeout_secs)
+    }
+}
+
+impl<R: CommandRunner> HookRunner<R> {
+    pub fn with_runner(runner: R, hooks: BTreeMap<String, String>, timeout_secs: u64) -> Self {
+        Self {
+            runner,
+            hooks,
+            timeout_secs,
+        }
+    }
+}
+
+#[async_trait]
+impl<R: CommandRunner> NotificationChannel for HookRunner<R> {
+    fn name(&self) -> &'static str {
+        "hook"
+    }
+
+    fn accepts(&self, event: &NotificationEvent) -> bool {
+        self.hooks.contains_key(event.key())
+    }
+
+    fn background(&self) -> bool {
+        true
+    }
+
+    async fn send(&self, event: &NotificationEvent) -> Result<()> {
+        let Some(command) = self.hooks.get(event.key()) else {
+            return Ok(());
+        };
+        let mut env = vec![
+            ("MUTAGUI_EVENT", event.key()),
+            ("MUTAGUI_SESSION", event.subject()),
+        ];
+        if let Some((alpha, beta)) = event.endpoints() {
+            env.extend([("MUTAGUI_ALPHA", alpha), ("MUTAGUI_BETA", beta)]);
+        }
+
+        #[cfg(unix)]
+        let (program, args) = {
+            let mut args: Vec<String> = env
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+            args.extend(["sh".to_string(), "-c".to_string(), command.clone()]);
+            ("env", args)
+        };
+        #[cfg(not(unix))]
+        let (program, args) = {
+            let mut line: Vec<String> = env
+                .iter()
+                .map(|(key, value)| format!("set \"{}={}\"", key, value))
+                .collect();
+            line.push(command.clone());
+            ("cmd", vec!["/C".to_string(), line.join(" && ")])
+        };
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = self.runner.run(program, &args, self.timeout_secs).await?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} exited with {}: {}",
+                event.key(),
+                output.status,
+                crate::command::decode_message(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Webhook notifications: POSTs JSON with `curl`, with retries and a rate
+/// limit. They're sent in the background, so an endpoint that doesn't answer
+/// holds up only the webhooks queued behind it.
+pub struct WebhookNotifier<R: CommandRunner = SystemCommandRunner> {
+    runner: R,
+    config: WebhookConfig,
+    retry_delay: Duration,
+    /// Send times within the last minute, for rate limiting.
+    recent: Mutex<VecDeque<Instant>>,
+}
+
+impl WebhookNotifier<SystemCommandRunner> {
+    pub fn new(config: WebhookConfig) -> Self {
+        Self::with_runner(SystemCommandRunner::new(), config)
+    }
+}
+
+impl<R: CommandRunner> WebhookNotifier<R> {
+    pub fn with_runner(runner: R, config: WebhookConfig) -> Self {
+        Self {
+            runner,
+            config,
+            retry_delay: Duration::from_secs(1),
+            recent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Set the delay before the first retry (doubled for each further retry).
+    #[cfg(test)]
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Render the request body, using the configured template if there is one.
+    ///
+    /// Template placeholders are `{event}`, `{subject}`, `{title}`, `{message}`,
+    /// and `{timestamp}`; values are JSON-escaped so templates stay valid JSON.
+    fn payload(&self, event: &NotificationEvent) -> String {
+        let timestamp = Local::now().to_rfc3339();
+        match &self.config.template {
+            Some(template) => {
+                // Escaped string contents without the surrounding quotes
+                let escape = |value: &str| {
+                    let quoted = serde_json::Value::from(value).to_string();
+                    quoted[1..quoted.len() - 1].to_string()
+                };
+                template
+                    .replace("{event}", &escape(event.key()))
+                    .replace("{subject}", &escape(event.subject()))
+                    .replace("{title}", &escape(&event.title()))
+                    .replace("{message}", &escape(&event.describe()))
+                    .replace("{timestamp}", &escape(&timestamp))
+            }
+            None => serde_json::json!({
+                "event": event.key(),
+                "subject": event.subject(),
+                "title": event.title(),
+                "message": event.describe(),
+                "timestamp": timestamp,
+            })
+            .to_string(),
+        }
+    }
+
+    /// Reserve a slot in the per-minute budget, or return false if it is used up.
+    fn take_rate_slot(&self) -> bool {
+        let mut recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        while recent
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= Duration::from_secs(60))
+        {
+            recent.pop_front();
+        }
+        if recent.len() >= self.config.max_per_minute as usize {
+            return false;
+        }
+        recent.push_back(now);
+        true
+    }
+
+    async fn post(&self, body: &str) -> Result<()> {
+        let args = [
+            "-sS",
+            "-f",
+            "-X",
+            "POST",
+            "-H",
+            "Content-Type: application/json",
+            "--max-time",
+            "10",
+            "--data-binary",
+            body,
+            self.config.url.as_str(),
+        ];
+        let output = self.runner.run("curl", &args, 15).await?;
+        if !output.status.success() {
+            anyhow::bail!("{}", crate::command::decode_message(&output.stderr).trim());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<R: CommandRunner> NotificationChannel for WebhookNotifier<R> {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn accepts(&self, event: &NotificationEvent) -> bool {
+        self.config.events.iter().any(|key| key == event.key())
+    }
+
+    fn background(&self) -> bool {
+        true
+    }
+
+    fn admit(&self, event: &NotificationEvent) -> Result<()> {
+        if !self.take_rate_slot() {
+            anyhow::bail!(
+                "rate limit of {} per minute reached, dropped {}",
+                self.config.max_per_minute,
+                event.key()
+            );
+        }
+        Ok(())
+    }
+
+    async fn send(&self, event: &NotificationEvent) -> Result<()> {
+        let body = self.payload(event);
+        let mut delay = self.retry_delay;
+        let mut attempt = 0;
+        loop {
+            match self.post(&body).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt >= self.config.retries => {
+                    return Err(e.context(format!("gave up after {} attempt(s)", attempt + 1)))
+                }
+                Err(_) => {
+                    attempt += 1;
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+            }
+        }
+    }
+}
+
+/// A channel that is sent to from a background task, which is started with
+/// the first event.
+struct Worker {
+    channel: Arc<dyn NotificationChannel>,
+    queue: Mutex<Option<mpsc::Sender<NotificationEvent>>>,
+}
+
+impl Worker {
+    /// Queue `event` for the background task, failing if too many are
+    /// already waiting.
+    fn enqueue(
+        &self,
+        event: &NotificationEvent,
+        failures: &mpsc::UnboundedSender<String>,
+    ) -> Result<()> {
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        let queue = queue.get_or_insert_with(|| {
+            let (sender, receiver) = mpsc::channel(QUEUE_LEN);
+            tokio::spawn(deliver(self.channel.clone(), receiver, failures.clone()));
+            sender
+        });
+        queue
+            .try_send(event.clone())
+            .map_err(|_| anyhow::anyhow!("{} events waiting, dropped {}", QUEUE_LEN, event.key()))
+    }
+}
+
+/// Send queued events to `channel` one at a time, reporting failures on
+/// `failures`. Ends once the notifier is dropped and the queue is empty.
+async fn deliver(
+    channel: Arc<dyn NotificationChannel>,
+    mut queue: mpsc::Receiver<NotificationEvent>,
+    failures: mpsc::UnboundedSender<String>,
+) {
+    while let Some(event) = queue.recv().await {
+        if let Err(e) = channel.send(&event).await {
+            let _ = failures.send(failure(channel.as_ref(), e));
+        }
+    }
+}
+
+fn failure(channel: &dyn NotificationChannel, error: anyhow::Error) -> String {
+    format!("{} notification failed: {:#}", channel.name(), error)
+}
+
+/// Delivers events to every configured channel that accepts them.
+pub struct Notifier {
+    channels: Vec<Box<dyn NotificationChannel>>,
+    workers: Vec<Worker>,
+    /// Where background tasks report failures, and where `take_failures`
+    /// picks them up.
+    report: mpsc::UnboundedSender<String>,
+    reported: mpsc::UnboundedReceiver<String>,
+}
+
+impl Default for Notifier {
+    fn default() -> Self {
+        let (report, reported) = mpsc::unbounded_channel();
+        Self {
+            channels: Vec::new(),
+            workers: Vec::new(),
+            report,
+            reported,
+        }
+    }
+}
+
+impl Notifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build the channels enabled in config; `force_desktop` is used by daemon mode.
+    pub fn from_config(config: &Config, force_desktop: bool) -> Self {
+        let hooks = &config.hooks;
+        let config = &config.notifications;
+        let mut notifier = Self::new();
+        if config.desktop || force_desktop {
+            notifier.add(Box::new(DesktopNotifier::new()));
+        }
+        if let Some(webhook) = config.webhook.as_ref().filter(|w| !w.url.is_empty()) {
+            notifier.add(Box::new(WebhookNotifier::new(webhook.clone())));
+        }
+        if config.flush_bell || config.flush_command.is_some() {
+            notifier.add(Box::new(FlushAlert::new(
+                config.flush_bell,
+                config.flush_command.clone(),
+            )));
+        }
+        if !hooks.is_empty() {
+            notifier.add(Box::new(HookRunner::new(
+                hooks.clone(),
+                config.hook_timeout_secs,
+            )));
+        }
+        notifier
+    }
+
+    pub fn add(&mut self, channel: Box<dyn NotificationChannel>) {
+        if channel.background() {
+            self.workers.push(Worker {
+                channel: Arc::from(channel),
+                queue: Mutex::new(None),
+            });
+        } else {
+            self.channels.push(channel);
+        }
+    }
+
+    /// Send every event to every interested channel, returning a description
+    /// of each failure. Background channels are only queued; their failures
+    /// come from `take_failures` later.
+    pub async fn notify_all(&self, events: &[NotificationEvent]) -> Vec<String> {
+        let mut failures = Vec::new();
+        for event in events {
+            for channel in self.channels.iter().filter(|c| c.accepts(event)) {
+                let sent = match channel.admit(event) {
+                    Ok(()) => channel.send(event).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = sent {
+                    failures.push(failure(channel.as_ref(), e));
+                }
+            }
+            for worker in self.workers.iter().filter(|w| w.channel.accepts(event)) {
+                let queued = worker
+                    .channel
+                    .admit(event)
+                    .and_then(|()| worker.enqueue(event, &self.report));
+                if let Err(e) = queued {
+                    failures.push(failure(worker.channel.as_ref(), e));
+                }
+            }
+        }
+        failures
+    }
+
+    /// Failures background channels reported since the last call.
+    pub fn take_failures(&mut self) -> Vec<String> {
+        std::iter::from_fn(|| self.reported.try_recv().ok()).collect()
+    }
+
+    /// Wait for the next failure a background channel reports.
+    #[cfg(test)]
+    pub async fn next_failure(&mut self) -> Option<String> {
+        self.reported.recv().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{failure_output, success_output, MockCommandRunner};
+    use crate::health::HealthEventKind;
+
+    fn event() -> NotificationEvent {
+        NotificationEvent::Health(HealthEvent {
+            session_name: "web".to_string(),
+            alpha: "/code/web".to_string(),
+            beta: "server:/srv/web".to_string(),
+            kind: HealthEventKind::Disconnected("beta"),
+        })
+    }
+
+    fn webhook_config(template: Option<&str>) -> WebhookConfig {
+        WebhookConfig {
+            url: "https://hooks.example.com/x".to_string(),
+            template: template.map(str::to_string),
+            ..WebhookConfig::default()
+        }
+    }
+
+    fn curl_command(body: &str) -> String {
+        format!(
+            "curl -sS -f -X POST -H Content-Type: application/json --max-time 10 \
+             --data-binary {} https://hooks.example.com/x",
+            body
+        )
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[tokio::test]
+    async fn test_desktop_notifier_runs_notify_send() {
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            "notify-send mutagui: web disconnected web: beta endpoint disconnected",
+            success_output(""),
+        );
+
+        let notifier = DesktopNotifier::with_runner(runner);
+        assert!(notifier.send(&event()).await.is_ok());
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_desktop_notifier_quotes_applescript() {
+        let (program, args) = DesktopNotifier::<MockCommandRunner>::command("say \"hi\"", "a\\b");
+        assert_eq!(program, "osascript");
+        assert_eq!(
+            args[1],
+            "display notification \"a\\\\b\" with title \"say \\\"hi\\\"\""
+        );
+    }
+
+    #[test]
+    fn test_desktop_ignores_project_events() {
+        let notifier = DesktopNotifier::with_runner(MockCommandRunner::new());
+        assert!(notifier.accepts(&event()));
+        assert!(!notifier.accepts(&NotificationEvent::ProjectStarted("app".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_notifier_collects_failures() {
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            "notify-send mutagui: web disconnected web: beta endpoint disconnected",
+            failure_output("no notification daemon"),
+        );
+
+        let mut notifier = Notifier::new();
+        notifier.add(Box::new(DesktopNotifier::with_runner(runner)));
+
+        let failures = notifier.notify_all(&[event()]).await;
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].starts_with("desktop notification failed"));
+    }
+
+    #[test]
+    fn test_webhook_template_escapes_values() {
+        let notifier = WebhookNotifier::with_runner(
+            MockCommandRunner::new(),
+            webhook_config(Some(r#"{"text": "{message} ({event})"}"#)),
+        );
+        let event = NotificationEvent::ProjectStarted("say \"hi\"".to_string());
+        assert_eq!(
+            notifier.payload(&event),
+            r#"{"text": "Project say \"hi\" started (project_started)"}"#
+        );
+    }
+
+    #[test]
+    fn test_webhook_default_payload() {
+        let notifier = WebhookNotifier::with_runner(MockCommandRunner::new(), webhook_config(None));
+        let payload: serde_json::Value = serde_json::from_str(&notifier.payload(&event())).unwrap();
+        assert_eq!(payload["event"], "disconnect");
+        assert_eq!(payload["subject"], "web");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_retries_then_succeeds() {
+        let runner = MockCommandRunner::new();
+        let command = curl_command(r#"{"text": "web"}"#);
+        runner.expect(&command, failure_output("connection refused"));
+        runner.expect(&command, success_output("ok"));
+
+        let notifier =
+            WebhookNotifier::with_runner(runner, webhook_config(Some(r#"{"text": "{subject}"}"#)))
+                .retry_delay(Duration::ZERO);
+
+        assert!(notifier.send(&event()).await.is_ok());
+        assert_eq!(notifier.runner.executed_commands().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_gives_up_after_retries() {
+        let runner = MockCommandRunner::new();
+        let command = curl_command(r#"{"text": "web"}"#);
+        for _ in 0..3 {
+            runner.expect(&command, failure_output("connection refused"));
+        }
+
+        let notifier =
+            WebhookNotifier::with_runner(runner, webhook_config(Some(r#"{"text": "{subject}"}"#)))
+                .retry_delay(Duration::ZERO);
+
+        let err = notifier.send(&event()).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("gave up after 3 attempt(s)"));
+        assert_eq!(notifier.runner.executed_commands().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_rate_limit() {
+        let runner = MockCommandRunner::new();
+        let command = curl_command(r#"{"text": "web"}"#);
+        runner.expect(&command, success_output(""));
+        runner.expect(&command, success_output(""));
+
+        let config = WebhookConfig {
+            max_per_minute: 2,
+            ..webhook_config(Some(r#"{"text": "{subject}"}"#))
+        };
+        let mut notifier = Notifier::new();
+        notifier.add(Box::new(WebhookNotifier::with_runner(runner, config)));
+
+        // Limited as events are queued, while earlier ones are still waiting
+        assert!(notifier.notify_all(&[event(), event()]).await.is_empty());
+        let failures = notifier.notify_all(&[event()]).await;
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].contains("rate limit of 2 per minute reached"));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sent_in_background() {
+        let runner = Arc::new(MockCommandRunner::new());
+        let command = curl_command(r#"{"text": "web"}"#);
+        runner.expect(&command, failure_output("connection refused"));
+
+        let config = WebhookConfig {
+            retries: 0,
+            ..webhook_config(Some(r#"{"text": "{subject}"}"#))
+        };
+        let mut notifier = Notifier::new();
+        notifier.add(Box::new(WebhookNotifier::with_runner(
+            runner.clone(),
+            config,
+        )));
+
+        assert!(notifier.notify_all(&[event()]).await.is_empty());
+        let failure = notifier.next_failure().await.unwrap();
+        assert!(failure.starts_with("webhook notification failed"));
+        assert!(runner.was_executed(&command));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_flush_alert_runs_command_with_spec_name() {
+        let runner = MockCommandRunner::new();
+        runner.expect("sh -c say \"$1 done\" mutagui web", success_output(""));
+
+        let alert = FlushAlert::with_runner(runner, false, Some("say \"$1 done\"".to_string()));
+        let flushed = NotificationEvent::FlushCompleted("web".to_string());
+        assert!(alert.accepts(&flushed));
+        assert!(!alert.accepts(&event()));
+        assert!(alert.send(&flushed).await.is_ok());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_flush_alert_reports_failed_command() {
+        let runner = MockCommandRunner::new();
+        runner.expect("sh -c false mutagui web", failure_output("oops"));
+
+        let alert = FlushAlert::with_runner(runner, false, Some("false".to_string()));
+        let err = alert
+            .send(&NotificationEvent::FlushCompleted("web".to_string()))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("oops"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hook_runs_command_with_env() {
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            "env MUTAGUI_EVENT=disconnect MUTAGUI_SESSION=web MUTAGUI_ALPHA=/code/web \
+             MUTAGUI_BETA=server:/srv/web sh -c ./smoke-test.sh",
+            success_output(""),
+        );
+        runner.expect(
+            "env MUTAGUI_EVENT=project_started MUTAGUI_SESSION=app sh -c ./smoke-test.sh",
+            failure_output("no CI"),
+        );
+
+        let hooks = BTreeMap::from([
+            ("disconnect".to_string(), "./smoke-test.sh".to_string()),
+            ("project_started".to_string(), "./smoke-test.sh".to_string()),
+        ]);
+        let hook = HookRunner::with_runner(runner, hooks, 600);
+        let started = NotificationEvent::ProjectStarted("app".to_string());
+        assert!(hook.accepts(&event()));
+        assert!(!hook.accepts(&NotificationEvent::FlushCompleted("web".to_string())));
+        assert!(hook.send(&event()).await.is_ok());
+        let err = hook.send(&started).await.unwrap_err();
+        assert!(err.to_string().contains("no CI"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hooks_run_in_background() {
+        let runner = MockCommandRunner::new();
+        runner.expect(
+            "env MUTAGUI_EVENT=project_started MUTAGUI_SESSION=app sh -c ./smoke-test.sh",
+            failure_output("no CI"),
+        );
+        let hooks =
+            BTreeMap::from([("project_started".to_string(), "./smoke-test.sh".to_string())]);
+        let mut notifier = Notifier::new();
+        notifier.add(Box::new(HookRunner::with_runner(runner, hooks, 600)));
+
+        // The hook's failure is reported afterwards, not to the caller
+        let started = NotificationEvent::ProjectStarted("app".to_string());
+        assert!(notifier.notify_all(&[started]).await.is_empty());
+        let failure = notifier.next_failure().await.unwrap();
+        assert!(failure.starts_with("hook notification failed"));
+        assert!(failure.contains("no CI"));
+        assert!(notifier.take_failures().is_empty());
+    }
+
+    #[test]
+    fn test_lifecycle_events() {
+        let session = |name: &str, mode: &str, cycles: u64| SyncSession {
+            mode: Some(mode.to_string()),
+            ..SyncSession::named(name).with_cycles(cycles)
+        };
+        let session_ref = |name: &str| SessionRef {
+            name: name.to_string(),
+            alpha: format!("/code/{}", name),
+            beta: format!("/srv/{}", name),
+        };
+        let (web, push) = (session_ref("web"), session_ref("web-push"));
+
+        let previous = [session("web-push", "one-way-replica", 0)];
+        let events = lifecycle_events(
+            &previous,
+            &[
+                session("web", "two-way-safe", 1),
+                session("web-push", "one-way-replica", 1),
+            ],
+        );
+        assert!(matches!(&events[0], NotificationEvent::SessionStarted(s) if *s == web));
+        assert!(matches!(&events[1], NotificationEvent::PushCompleted(s) if *s == push));
+        assert_eq!(events.len(), 2);
+
+        // Later cycles of the same push session are not another push
+        let previous = [session("web-push", "one-way-replica", 1)];
+        let current = [session("web-push", "one-way-replica", 2)];
+        assert!(lifecycle_events(&previous, &current).is_empty());
+    }
+
+    #[test]
+    fn test_webhook_event_filter() {
+        let config = WebhookConfig {
+            events: vec!["project_terminated".to_string()],
+            ..webhook_config(None)
+        };
+        let notifier = WebhookNotifier::with_runner(MockCommandRunner::new(), config);
+        assert!(!notifier.accepts(&event()));
+        assert!(notifier.accepts(&NotificationEvent::ProjectTerminated("app".to_string())));
+    }
+}
@@ -0,0 +1,205 @@
+//! Tracking of session changes made outside this mutagui instance.
+//!
+//! Every mutating call issued through `MutagenClient` is recorded in an
+//! `OperationJournal`. When a refresh shows that a session was created,
+//! terminated, paused, or resumed and no recent local operation explains it,
+//! the change is reported as external (another user, script, or mutagui
+//! instance sharing the same daemon).
+
+use crate::i18n::t;
+use crate::mutagen::SyncSession;
+use chrono::{DateTime, Local};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long a recorded local operation can explain an observed change.
+pub const OPERATION_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// Maximum number of external changes kept in the in-memory log.
+pub const EXTERNAL_CHANGE_LOG_LIMIT: usize = 100;
+
+/// Kind of state change observed on a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalChangeKind {
+    Created,
+    Terminated,
+    Paused,
+    Resumed,
+}
+
+impl ExternalChangeKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Created => t!("view.change_created"),
+            Self::Terminated => t!("view.change_terminated"),
+            Self::Paused => t!("view.change_paused"),
+            Self::Resumed => t!("view.change_resumed"),
+        }
+    }
+}
+
+/// A session state change that was not initiated by this mutagui instance.
+#[derive(Debug, Clone)]
+pub struct ExternalChange {
+    pub session_name: String,
+    pub kind: ExternalChangeKind,
+    pub at: DateTime<Local>,
+}
+
+impl ExternalChange {
+    /// One-line description for status messages and logs.
+    pub fn describe(&self) -> String {
+        t!(
+            "status.external_change_detail",
+            name = self.session_name,
+            kind = self.kind.label(),
+            time = self.at.format("%H:%M:%S")
+        )
+    }
+}
+
+/// Recently issued local operations, keyed by session identifier or name.
+#[derive(Debug, Default)]
+pub struct OperationJournal {
+    entries: HashMap<String, Instant>,
+}
+
+impl OperationJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that a local operation targeted a session identifier or name.
+    pub fn record(&mut self, target: impl Into<String>) {
+        self.entries.insert(target.into(), Instant::now());
+    }
+
+    /// Check whether a recent local operation targeted this session.
+    pub fn covers(&self, session: &SyncSession) -> bool {
+        [session.identifier.as_str(), session.name.as_str()]
+            .iter()
+            .any(|key| {
+                self.entries
+                    .get(*key)
+                    .is_some_and(|at| at.elapsed() <= OPERATION_GRACE_PERIOD)
+            })
+    }
+
+    /// Drop entries older than the grace period.
+    pub fn prune(&mut self) {
+        self.entries
+            .retain(|_, at| at.elapsed() <= OPERATION_GRACE_PERIOD);
+    }
+}
+
+/// Compare two session lists and report changes not explained by the journal.
+pub fn detect_external_changes(
+    old: &[SyncSession],
+    new: &[SyncSession],
+    journal: &OperationJournal,
+) -> Vec<ExternalChange> {
+    let now = Local::now();
+    let change = |session: &SyncSession, kind| ExternalChange {
+        session_name: session.name.clone(),
+        kind,
+        at: now,
+    };
+
+    let old_by_id: HashMap<&str, &SyncSession> =
+        old.iter().map(|s| (s.identifier.as_str(), s)).collect();
+    let new_by_id: HashMap<&str, &SyncSession> =
+        new.iter().map(|s| (s.identifier.as_str(), s)).collect();
+
+    let mut changes = Vec::new();
+
+    for session in new {
+        if journal.covers(session) {
+            continue;
+        }
+        match old_by_id.get(session.identifier.as_str()) {
+            None => changes.push(change(session, ExternalChangeKind::Created)),
+            Some(previous) if !previous.paused && session.paused => {
+                changes.push(change(session, ExternalChangeKind::Paused))
+            }
+            Some(previous) if previous.paused && !session.paused => {
+                changes.push(change(session, ExternalChangeKind::Resumed))
+            }
+            Some(_) => {}
+        }
+    }
+
+    for session in old {
+        if !new_by_id.contains_key(session.identifier.as_str()) && !journal.covers(session) {
+            changes.push(change(session, ExternalChangeKind::Terminated));
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn session(name: &str, paused: bool) -> SyncSession {
+        SyncSession::named(name).with_paused(paused)
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let sessions = vec![session("a", false)];
+        let changes = detect_external_changes(&sessions, &sessions, &OperationJournal::new());
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_detects_external_pause_and_resume() {
+        let old = vec![session("a", false), session("b", true)];
+        let new = vec![session("a", true), session("b", false)];
+
+        let changes = detect_external_changes(&old, &new, &OperationJournal::new());
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].session_name, "a");
+        assert_eq!(changes[0].kind, ExternalChangeKind::Paused);
+        assert_eq!(changes[1].session_name, "b");
+        assert_eq!(changes[1].kind, ExternalChangeKind::Resumed);
+    }
+
+    #[test]
+    fn test_detects_external_create_and_terminate() {
+        let old = vec![session("gone", false)];
+        let new = vec![session("fresh", false)];
+
+        let changes = detect_external_changes(&old, &new, &OperationJournal::new());
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].kind, ExternalChangeKind::Created);
+        assert_eq!(changes[0].session_name, "fresh");
+        assert_eq!(changes[1].kind, ExternalChangeKind::Terminated);
+        assert_eq!(changes[1].session_name, "gone");
+    }
+
+    #[test]
+    fn test_local_operations_are_not_external() {
+        let old = vec![session("a", false), session("gone", false)];
+        let new = vec![session("a", true), session("fresh-push", false)];
+
+        let mut journal = OperationJournal::new();
+        journal.record("sync_a"); // paused by identifier
+        journal.record("sync_gone"); // terminated by identifier
+        journal.record("fresh-push"); // created by name
+
+        let changes = detect_external_changes(&old, &new, &journal);
+        assert!(changes.is_empty(), "unexpected changes: {:?}", changes);
+    }
+
+    #[test]
+    fn test_describe() {
+        let change = ExternalChange {
+            session_name: "web".to_string(),
+            kind: ExternalChangeKind::Terminated,
+            at: Local::now(),
+        };
+        assert!(change.describe().starts_with("web terminated outside mutagui"));
+    }
+}
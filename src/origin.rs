@@ -0,0 +1,132 @@
+//! Which machine and user created a session.
+//!
+//! Sessions created through mutagui carry `mutagui.host` and `mutagui.user`
+//! labels. On a shared dev server, where several people's sessions show up
+//! in the same daemon, that tells whose session is whose before anyone
+//! terminates it.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub const HOST_LABEL: &str = "mutagui.host";
+pub const USER_LABEL: &str = "mutagui.user";
+
+/// Longest label value mutagen accepts.
+const MAX_LABEL_LEN: usize = 63;
+
+/// The host and user a session was created by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Origin {
+    pub host: String,
+    pub user: String,
+}
+
+impl Origin {
+    /// This machine and the user running mutagui, as label values.
+    pub fn current() -> Self {
+        let user = ["USER", "USERNAME"]
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+            .unwrap_or_else(|| "unknown".to_string());
+        Self {
+            host: label_value(&hostname().unwrap_or_else(|| "unknown".to_string())),
+            user: label_value(&user),
+        }
+    }
+
+    /// The origin recorded in a session's labels, if it was created by mutagui.
+    pub fn from_labels(labels: &BTreeMap<String, String>) -> Option<Self> {
+        Some(Self {
+            host: labels.get(HOST_LABEL)?.clone(),
+            user: labels.get(USER_LABEL)?.clone(),
+        })
+    }
+
+    /// `--label` arguments for `mutagen sync create`.
+    pub fn label_args(&self) -> Vec<String> {
+        vec![
+            "--label".to_string(),
+            format!("{}={}", HOST_LABEL, self.host),
+            "--label".to_string(),
+            format!("{}={}", USER_LABEL, self.user),
+        ]
+    }
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.user, self.host)
+    }
+}
+
+/// `value` in the form mutagen allows for labels: letters, digits, `-`, `_`,
+/// and `.`, starting and ending with a letter or digit, at most 63 long.
+fn label_value(value: &str) -> String {
+    let value: String = value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .take(MAX_LABEL_LEN)
+        .collect();
+    let value = value.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+    if value.is_empty() {
+        "unknown".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(unix)]
+fn hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    // SAFETY: the buffer is valid for its length, and gethostname writes at most that many bytes
+    let result = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+    if result != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    let name = String::from_utf8_lossy(&buf[..len]);
+    // The short name, as shown in a shell prompt
+    name.split('.').next().map(str::to_string)
+}
+
+#[cfg(not(unix))]
+fn hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_value() {
+        assert_eq!(label_value("build-box"), "build-box");
+        assert_eq!(label_value("Jane Doe"), "Jane-Doe");
+        assert_eq!(label_value("@ops@"), "ops");
+        assert_eq!(label_value("!!"), "unknown");
+        assert_eq!(label_value(&"x".repeat(80)).len(), MAX_LABEL_LEN);
+    }
+
+    #[test]
+    fn test_labels_round_trip() {
+        let origin = Origin {
+            host: "build-box".to_string(),
+            user: "jane".to_string(),
+        };
+        let labels: BTreeMap<String, String> = origin
+            .label_args()
+            .chunks(2)
+            .filter_map(|pair| pair[1].split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        assert_eq!(Origin::from_labels(&labels), Some(origin.clone()));
+        assert_eq!(origin.to_string(), "jane@build-box");
+        assert_eq!(Origin::from_labels(&BTreeMap::new()), None);
+    }
+}
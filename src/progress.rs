@@ -0,0 +1,249 @@
+//! Progress bookkeeping for project-wide operations.
+//!
+//! A `ProgressReporter` counts completed and failed steps, mirrors the count
+//! into the blocking modal, asks the UI to redraw after every step, and
+//! formats the final status message.
+
+use crate::app::{App, BlockingOperation, CancellationToken, StatusMessage};
+use crate::i18n::{self, t};
+use std::sync::{Arc, Mutex};
+
+/// Callback that redraws the UI between steps of a blocking operation.
+pub type Redraw<'a> = &'a mut dyn FnMut(&App);
+
+/// Steps finished so far and the total, shared with the blocking modal.
+#[derive(Debug, Clone, Default)]
+pub struct ProgressCounter(Arc<Mutex<Option<(usize, usize)>>>);
+
+impl ProgressCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self, done: usize, total: usize) {
+        if let Ok(mut progress) = self.0.lock() {
+            *progress = Some((done, total));
+        }
+    }
+
+    pub fn get(&self) -> Option<(usize, usize)> {
+        self.0.lock().ok().and_then(|progress| *progress)
+    }
+}
+
+/// Aggregates the outcome of an operation applied to several sessions. The
+/// words that make up its messages are message catalog keys.
+pub struct ProgressReporter<'a> {
+    /// Past tense used in status messages ("Terminated").
+    past: &'static str,
+    /// Infinitive used in failure messages ("terminate").
+    infinitive: &'static str,
+    /// What the steps act on ("session(s)").
+    noun: &'static str,
+    total: usize,
+    completed: usize,
+    errors: Vec<(String, String)>,
+    cancel: Option<CancellationToken>,
+    counter: Option<ProgressCounter>,
+    redraw: Option<Redraw<'a>>,
+}
+
+impl<'a> ProgressReporter<'a> {
+    pub fn new(past: &'static str, infinitive: &'static str, total: usize) -> Self {
+        Self {
+            past,
+            infinitive,
+            noun: "progress.sessions",
+            total,
+            completed: 0,
+            errors: Vec::new(),
+            cancel: None,
+            counter: None,
+            redraw: None,
+        }
+    }
+
+    /// Set the noun used in messages (default `progress.sessions`).
+    pub fn noun(mut self, noun: &'static str) -> Self {
+        self.noun = noun;
+        self
+    }
+
+    /// Report into a blocking operation's modal and honor its cancellation.
+    pub fn attach(mut self, op: Option<&BlockingOperation>) -> Self {
+        if let Some(op) = op {
+            op.progress.set(0, self.total);
+            self.cancel = Some(op.cancel.clone());
+            self.counter = Some(op.progress.clone());
+        }
+        self
+    }
+
+    /// Redraw the UI after every recorded step.
+    pub fn on_redraw(mut self, redraw: Redraw<'a>) -> Self {
+        self.redraw = Some(redraw);
+        self
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|c| c.is_cancelled())
+    }
+
+    /// Record a successful step.
+    pub fn succeed(&mut self) {
+        self.completed += 1;
+    }
+
+    /// Record a failed step for the named session.
+    pub fn fail(&mut self, name: impl Into<String>, error: impl ToString) {
+        self.errors.push((name.into(), error.to_string()));
+    }
+
+    /// Record the result of a step for the named session.
+    pub fn record<T, E: ToString>(&mut self, name: &str, result: Result<T, E>) {
+        match result {
+            Ok(_) => self.succeed(),
+            Err(e) => self.fail(name, e),
+        }
+    }
+
+    /// Number of steps that succeeded so far.
+    pub fn completed(&self) -> usize {
+        self.completed
+    }
+
+    /// Publish the current count to the modal.
+    fn publish(&self) {
+        if let Some(counter) = &self.counter {
+            counter.set(self.completed + self.errors.len(), self.total);
+        }
+    }
+
+    /// Publish the current count to the modal and redraw.
+    pub fn tick(&mut self, app: &App) {
+        self.publish();
+        if let Some(redraw) = self.redraw.as_mut() {
+            redraw(app);
+        }
+    }
+
+    /// Status message for an operation stopped by the user.
+    pub fn cancelled(&self) -> StatusMessage {
+        StatusMessage::warning(t!(
+            "progress.cancelled",
+            past = i18n::text(self.past).to_lowercase(),
+            count = self.completed,
+            total = self.total,
+            noun = i18n::text(self.noun)
+        ))
+    }
+
+    /// Final status message summarizing successes and failures.
+    pub fn finish(&self) -> StatusMessage {
+        let (past, noun) = (i18n::text(self.past), i18n::text(self.noun));
+        match self.errors.first() {
+            None if self.completed == self.total => StatusMessage::info(t!(
+                "progress.done",
+                past = past,
+                count = self.completed,
+                noun = noun
+            )),
+            None => StatusMessage::info(t!(
+                "progress.done_some",
+                past = past,
+                count = self.completed,
+                total = self.total,
+                noun = noun
+            )),
+            Some((name, error)) if self.completed > 0 => StatusMessage::warning(t!(
+                "progress.partly_failed",
+                past = past,
+                count = self.completed,
+                noun = noun,
+                failed = self.errors.len(),
+                name = name,
+                error = error
+            )),
+            Some((name, error)) => StatusMessage::error(t!(
+                "progress.failed",
+                action = i18n::text(self.infinitive),
+                failed = self.errors.len(),
+                noun = noun,
+                name = name,
+                error = error
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_succeeded() {
+        let mut reporter = ProgressReporter::new("progress.terminated", "progress.terminate", 2);
+        reporter.succeed();
+        reporter.record::<(), String>("b", Ok(()));
+
+        let status = reporter.finish();
+        assert!(matches!(status, StatusMessage::Info(_)));
+        assert_eq!(status.text(), "Terminated 2 session(s)");
+    }
+
+    #[test]
+    fn test_partial_failure() {
+        let mut reporter = ProgressReporter::new("progress.resumed", "progress.resume", 3);
+        reporter.succeed();
+        reporter.record::<(), _>("b", Err("timeout"));
+        reporter.fail("c", "halted");
+
+        let status = reporter.finish();
+        assert!(matches!(status, StatusMessage::Warning(_)));
+        assert_eq!(
+            status.text(),
+            "Resumed 1 session(s), 2 failed. First error: b: timeout"
+        );
+    }
+
+    #[test]
+    fn test_all_failed() {
+        let mut reporter = ProgressReporter::new("progress.created", "progress.create", 1)
+            .noun("progress.push_sessions");
+        reporter.fail("web", "no such host");
+
+        let status = reporter.finish();
+        assert!(matches!(status, StatusMessage::Error(_)));
+        assert_eq!(
+            status.text(),
+            "Failed to create 1 push session(s). First error: web: no such host"
+        );
+    }
+
+    #[test]
+    fn test_cancelled_message() {
+        let mut reporter = ProgressReporter::new("progress.paused", "progress.pause", 4);
+        reporter.succeed();
+        assert_eq!(
+            reporter.cancelled().text(),
+            "Cancelled: paused 1 of 4 session(s)"
+        );
+    }
+
+    #[test]
+    fn test_attach_reports_progress_and_cancellation() {
+        let op = BlockingOperation::new(t!("progress.terminating_project"));
+        let mut reporter =
+            ProgressReporter::new("progress.terminated", "progress.terminate", 3).attach(Some(&op));
+        assert_eq!(op.progress.get(), Some((0, 3)));
+        assert!(!reporter.is_cancelled());
+
+        reporter.succeed();
+        reporter.fail("b", "boom");
+        reporter.publish();
+        assert_eq!(op.progress.get(), Some((2, 3)));
+
+        op.cancel.cancel();
+        assert!(reporter.is_cancelled());
+    }
+}
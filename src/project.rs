@@ -1,19 +1,109 @@
 use anyhow::{Context, Result};
 use glob::glob;
+use globset::{GlobBuilder, GlobMatcher};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
-
-use crate::config::ProjectConfig;
-use crate::mutagen::SyncSession;
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::config::{ProjectConfig, SortMode};
+use crate::interpolate;
+use crate::lint::{self, ProjectIssue};
+use crate::mutagen::{SyncSession, SyncTime, PROJECT_LABEL};
+
+/// The directories `ignore: vcs: true` stands for, as in Mutagen.
+pub const VCS_DIRECTORIES: [&str; 6] =
+    [".git", ".svn", ".hg", ".bzr", "_darcs", ".fossil-settings"];
+
+/// Session settings passed on to `mutagen sync create`: the section and
+/// key they're written under in a project file, and the flag they become.
+const SETTING_FLAGS: [(&str, &str, &str); 8] = [
+    ("permissions", "mode", "--permissions-mode"),
+    ("permissions", "defaultFileMode", "--default-file-mode"),
+    (
+        "permissions",
+        "defaultDirectoryMode",
+        "--default-directory-mode",
+    ),
+    ("permissions", "defaultOwner", "--default-owner"),
+    ("permissions", "defaultGroup", "--default-group"),
+    ("symlink", "mode", "--symlink-mode"),
+    ("watch", "mode", "--watch-mode"),
+    ("watch", "pollingInterval", "--watch-polling-interval"),
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MutagenYml {
+    /// Shared files whose `sync.defaults` this file builds on.
+    #[serde(default)]
+    pub include: Option<Includes>,
+    #[serde(flatten)]
+    pub hooks: Hooks,
+    /// Shell commands run by name with `mutagen project run`.
+    #[serde(default)]
+    pub commands: BTreeMap<String, String>,
     pub sync: Option<SyncDefinitions>,
 }
 
+/// Shell commands a project file runs before and after its sessions are
+/// created or terminated, each list in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hooks {
+    #[serde(default)]
+    pub before_create: Vec<String>,
+    #[serde(default)]
+    pub after_create: Vec<String>,
+    #[serde(default)]
+    pub before_terminate: Vec<String>,
+    #[serde(default)]
+    pub after_terminate: Vec<String>,
+}
+
+/// A compose file, of which only the `x-mutagen` section matters here. It
+/// has the same layout as a project file.
+#[derive(Debug, Deserialize)]
+struct ComposeFile {
+    #[serde(rename = "x-mutagen")]
+    x_mutagen: Option<MutagenYml>,
+}
+
+/// File names docker compose reads by default. The ones with an `x-mutagen`
+/// section are projects for mutagen-compose.
+pub const COMPOSE_FILE_NAMES: &[&str] = &[
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+
+/// Whether `path` names a compose file rather than a mutagen project file.
+pub fn is_compose_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| COMPOSE_FILE_NAMES.contains(&name))
+}
+
+/// The `include` of a project file: one path or a list of them.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Includes {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Includes {
+    fn paths(&self) -> &[String] {
+        match self {
+            Includes::One(path) => std::slice::from_ref(path),
+            Includes::Many(paths) => paths,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SyncDefinitions {
     #[serde(default)]
     pub defaults: HashMap<String, serde_yaml::Value>,
@@ -21,7 +111,7 @@ pub struct SyncDefinitions {
     pub sessions: HashMap<String, SessionDefinition>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SessionDefinition {
     pub alpha: String,
     pub beta: String,
@@ -29,6 +119,12 @@ pub struct SessionDefinition {
     pub mode: Option<String>,
     #[serde(default)]
     pub ignore: Option<serde_yaml::Value>,
+    #[serde(default)]
+    pub permissions: Option<serde_yaml::Value>,
+    #[serde(default)]
+    pub symlink: Option<serde_yaml::Value>,
+    #[serde(default)]
+    pub watch: Option<serde_yaml::Value>,
 }
 
 impl SessionDefinition {
@@ -54,6 +150,49 @@ impl SessionDefinition {
 
         patterns
     }
+
+    /// The synchronization mode, from this session or else the defaults.
+    pub fn mode_with_defaults(&self, defaults: Option<&serde_yaml::Value>) -> Option<String> {
+        self.mode.clone().or_else(|| {
+            defaults
+                .and_then(|defaults| defaults.get("mode"))
+                .and_then(|mode| mode.as_str())
+                .map(str::to_string)
+        })
+    }
+
+    /// The `mutagen sync create` arguments that give a session this
+    /// definition's mode, ignores, permissions, symlink, and watch settings,
+    /// each taken from the defaults where the session doesn't set it, as
+    /// `mutagen project start` would.
+    pub fn create_args(&self, defaults: Option<&serde_yaml::Value>) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(mode) = self.mode_with_defaults(defaults) {
+            args.extend(["-m".to_string(), mode]);
+        }
+        for pattern in self.get_ignore_patterns(defaults) {
+            args.extend(["--ignore".to_string(), pattern]);
+        }
+        let sections = [
+            ("permissions", &self.permissions),
+            ("symlink", &self.symlink),
+            ("watch", &self.watch),
+        ];
+        for (section, key, flag) in SETTING_FLAGS {
+            let value = sections
+                .iter()
+                .find(|(name, _)| *name == section)
+                .and_then(|(_, own)| own.as_ref()?.get(key))
+                .or_else(|| defaults?.get(section)?.get(key));
+            let value = match value {
+                Some(serde_yaml::Value::String(value)) => value.clone(),
+                Some(serde_yaml::Value::Number(value)) => value.to_string(),
+                _ => continue,
+            };
+            args.extend([flag.to_string(), value]);
+        }
+        args
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -73,8 +212,9 @@ pub struct SyncSpec {
     pub name: String,
     /// Current materialization state
     pub state: SyncSpecState,
-    /// Link to running session if materialized
-    pub running_session: Option<SyncSession>,
+    /// Link to running session if materialized, shared so that keeping a
+    /// spec across refreshes doesn't copy it
+    pub running_session: Option<Arc<SyncSession>>,
 }
 
 impl SyncSpec {
@@ -95,6 +235,13 @@ impl SyncSpec {
         self.conflicts().map(|c| !c.is_empty()).unwrap_or(false)
     }
 
+    /// Whether the spec's session needs attention. See `SyncSession::has_problem`.
+    pub fn has_problem(&self) -> bool {
+        self.running_session
+            .as_ref()
+            .is_some_and(|s| s.has_problem())
+    }
+
     /// Check if session is paused
     pub fn is_paused(&self) -> bool {
         self.running_session
@@ -122,7 +269,7 @@ fn extract_patterns_from_value(value: &serde_yaml::Value, patterns: &mut Vec<Str
             // Handle vcs: true flag
             if let Some(serde_yaml::Value::Bool(true)) = map.get("vcs") {
                 // Add common VCS directories (matches Mutagen's behavior)
-                for vcs_dir in &[".git", ".svn", ".hg", ".bzr", "_darcs", ".fossil-settings"] {
+                for vcs_dir in &VCS_DIRECTORIES {
                     let pattern = vcs_dir.to_string();
                     if !patterns.contains(&pattern) {
                         patterns.push(pattern);
@@ -146,33 +293,67 @@ fn extract_patterns_from_value(value: &serde_yaml::Value, patterns: &mut Vec<Str
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ProjectFile {
     pub path: PathBuf,
     pub target_name: Option<String>,
     pub sessions: HashMap<String, SessionDefinition>,
     pub defaults: Option<HashMap<String, serde_yaml::Value>>,
+    /// The shared files the defaults came from, including the ones they
+    /// include in turn.
+    pub includes: Vec<PathBuf>,
+    pub hooks: Hooks,
+    pub commands: BTreeMap<String, String>,
 }
 
 impl ProjectFile {
     pub fn from_path(path: PathBuf) -> Result<Self> {
         let contents = fs::read_to_string(&path)
             .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_contents(path, &contents)
+    }
 
-        let yml: MutagenYml = serde_yaml::from_str(&contents)
-            .with_context(|| format!("Failed to parse {}", path.display()))?;
+    /// Parse `contents` as the project file at `path`. For a compose file,
+    /// that's its `x-mutagen` section.
+    pub fn from_contents(path: PathBuf, contents: &str) -> Result<Self> {
+        let yml: MutagenYml = if is_compose_file(&path) {
+            serde_yaml::from_str::<ComposeFile>(contents)
+                .with_context(|| format!("Failed to parse {}", path.display()))?
+                .x_mutagen
+                .unwrap_or_default()
+        } else {
+            serde_yaml::from_str(contents)
+                .with_context(|| format!("Failed to parse {}", path.display()))?
+        };
 
         let target_name = extract_target_name(&path);
 
+        let mut includes = Vec::new();
+        let mut included = HashMap::new();
+        if let Some(listed) = &yml.include {
+            let mut chain = vec![path.canonicalize().unwrap_or_else(|_| path.clone())];
+            included = read_includes(&path, listed, &mut chain, &mut includes)?;
+        }
+
         let (sessions, defaults) = yml
             .sync
-            .map(|sync| {
+            .or_else(|| (!included.is_empty()).then(SyncDefinitions::default))
+            .map(|mut sync| {
+                sync.defaults = merge_defaults(std::mem::take(&mut included), sync.defaults);
                 let mut filtered = HashMap::new();
-                for (key, value) in sync.sessions {
+                for (key, mut value) in sync.sessions {
                     if key != "defaults" {
+                        value.alpha = interpolate::expand(&value.alpha);
+                        value.beta = interpolate::expand(&value.beta);
+                        if let Some(ignore) = &mut value.ignore {
+                            expand_strings(ignore);
+                        }
                         filtered.insert(key, value);
                     }
                 }
+                if let Some(ignore) = sync.defaults.get_mut("ignore") {
+                    expand_strings(ignore);
+                }
                 let defaults = if sync.defaults.is_empty() {
                     None
                 } else {
@@ -187,11 +368,22 @@ impl ProjectFile {
             target_name,
             sessions,
             defaults,
+            includes,
+            hooks: yml.hooks,
+            commands: yml.commands,
         })
     }
 
     pub fn display_name(&self) -> String {
-        if let Some(target) = &self.target_name {
+        if self.is_compose() {
+            // mutagen-compose names the project after its directory, as compose does
+            let dir = self.path.parent().and_then(|dir| dir.canonicalize().ok());
+            let dir = dir
+                .as_deref()
+                .and_then(Path::file_name)
+                .and_then(|n| n.to_str());
+            format!("compose-{}", dir.unwrap_or("project"))
+        } else if let Some(target) = &self.target_name {
             format!("mutagen-{}", target)
         } else {
             self.path
@@ -203,6 +395,101 @@ impl ProjectFile {
                 .to_string()
         }
     }
+
+    /// Whether the sessions come from a compose file's `x-mutagen` section,
+    /// to be started and stopped with mutagen-compose.
+    pub fn is_compose(&self) -> bool {
+        is_compose_file(&self.path)
+    }
+}
+
+/// The `sync.defaults` of the files `listed` by the project file at `path`,
+/// merged in order. Each file read is added to `read`; `chain` holds the
+/// files whose includes are being read, to catch a file including itself.
+fn read_includes(
+    path: &Path,
+    listed: &Includes,
+    chain: &mut Vec<PathBuf>,
+    read: &mut Vec<PathBuf>,
+) -> Result<HashMap<String, serde_yaml::Value>> {
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut defaults = HashMap::new();
+    for include in listed.paths() {
+        let include = dir.join(expand_path(Path::new(include)));
+        let canonical = include
+            .canonicalize()
+            .with_context(|| format!("Failed to read {}", include.display()))?;
+        if chain.contains(&canonical) {
+            anyhow::bail!("{} includes itself", include.display());
+        }
+        let contents = fs::read_to_string(&include)
+            .with_context(|| format!("Failed to read {}", include.display()))?;
+        let yml: MutagenYml = serde_yaml::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", include.display()))?;
+        if !read.contains(&include) {
+            read.push(include.clone());
+        }
+        let mut own = yml.sync.map(|sync| sync.defaults).unwrap_or_default();
+        if let Some(nested) = &yml.include {
+            chain.push(canonical);
+            own = merge_defaults(read_includes(&include, nested, chain, read)?, own);
+            chain.pop();
+        }
+        defaults = merge_defaults(defaults, own);
+    }
+    Ok(defaults)
+}
+
+/// `base` with the settings in `over` on top. Lists such as ignore paths are
+/// combined; other settings in `over` replace the ones in `base`.
+fn merge_defaults(
+    mut base: HashMap<String, serde_yaml::Value>,
+    over: HashMap<String, serde_yaml::Value>,
+) -> HashMap<String, serde_yaml::Value> {
+    for (key, value) in over {
+        match base.get_mut(&key) {
+            Some(existing) => merge_value(existing, value),
+            None => {
+                base.insert(key, value);
+            }
+        }
+    }
+    base
+}
+
+fn merge_value(base: &mut serde_yaml::Value, over: serde_yaml::Value) {
+    use serde_yaml::Value;
+    match (base, over) {
+        (Value::Mapping(base), Value::Mapping(over)) => {
+            for (key, value) in over {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_value(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Sequence(base), Value::Sequence(over)) => {
+            for item in over {
+                if !base.contains(&item) {
+                    base.push(item);
+                }
+            }
+        }
+        (base, over) => *base = over,
+    }
+}
+
+/// Expand environment variables in the strings of `value`, such as ignore
+/// paths.
+fn expand_strings(value: &mut serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::String(text) => *text = interpolate::expand(text),
+        serde_yaml::Value::Sequence(items) => items.iter_mut().for_each(expand_strings),
+        serde_yaml::Value::Mapping(map) => map.values_mut().for_each(expand_strings),
+        _ => {}
+    }
 }
 
 fn extract_target_name(path: &Path) -> Option<String> {
@@ -230,6 +517,8 @@ pub struct Project {
     pub specs: Vec<SyncSpec>,
     /// Whether project tree is folded (collapsed)
     pub folded: bool,
+    /// Whether the project is marked as a favorite, which sorts it first.
+    pub favorite: bool,
 }
 
 impl Project {
@@ -237,74 +526,264 @@ impl Project {
     pub fn is_active(&self) -> bool {
         self.specs.iter().any(|s| s.is_running())
     }
+
+    /// The identifier `mutagen project start` labeled the running sessions
+    /// with, if it started them rather than mutagui one at a time.
+    pub fn project_identifier(&self) -> Option<&str> {
+        self.specs
+            .iter()
+            .filter_map(|spec| spec.running_session.as_ref())
+            .find_map(|session| session.labels.get(PROJECT_LABEL))
+            .map(String::as_str)
+    }
 }
 
-pub fn discover_project_files(
-    base_dir: Option<&Path>,
-    config: Option<&ProjectConfig>,
-) -> Result<Vec<ProjectFile>> {
-    let mut files = Vec::new();
-    let mut seen_paths = std::collections::HashSet::new();
-    let mut search_paths = build_search_paths(base_dir);
+/// A project file that was found but couldn't be read or parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectFileError {
+    pub path: PathBuf,
+    /// The underlying error, e.g. the YAML syntax error and its location.
+    pub message: String,
+}
 
-    // Add custom search paths from config
-    if let Some(cfg) = config {
-        for path in &cfg.search_paths {
-            // Expand tilde in config paths
-            let expanded = expand_tilde_in_path(path);
-            let path_str = expanded.to_string_lossy();
-            search_paths.push(format!("{}/mutagen.yml", path_str));
-            search_paths.push(format!("{}/mutagen-*.yml", path_str));
-            search_paths.push(format!("{}/.mutagen.yml", path_str));
-            search_paths.push(format!("{}/.mutagen-*.yml", path_str));
+/// Project files found by discovery, and the ones that failed to load.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveredProjects {
+    pub files: Vec<ProjectFile>,
+    pub errors: Vec<ProjectFileError>,
+    /// Problems found in the files, including the ones that failed to load.
+    pub issues: Vec<ProjectIssue>,
+}
+
+/// Project files loaded by earlier discoveries, so the ones that haven't
+/// changed since aren't read and checked again.
+#[derive(Debug, Default)]
+pub struct DiscoveryCache {
+    files: HashMap<PathBuf, CachedFile>,
+}
+
+#[derive(Debug)]
+struct CachedFile {
+    /// Modification time and size when the file was loaded.
+    stamp: (SystemTime, u64),
+    /// The files it includes, with their modification times and sizes.
+    includes: Vec<(PathBuf, Option<(SystemTime, u64)>)>,
+    loaded: std::result::Result<ProjectFile, ProjectFileError>,
+    issues: Vec<ProjectIssue>,
+}
+
+impl DiscoveryCache {
+    /// Find the project files in the search locations, loading only the ones
+    /// that are new or changed since the last call.
+    pub fn discover(
+        &mut self,
+        base_dir: Option<&Path>,
+        config: Option<&ProjectConfig>,
+    ) -> Result<DiscoveredProjects> {
+        let mut discovered = DiscoveredProjects::default();
+        let mut seen_paths = std::collections::HashSet::new();
+        let mut loaded_paths = std::collections::HashSet::new();
+        for entry in find_project_files(base_dir, config) {
+            // Canonicalize path to avoid duplicates from symlinks or different representations
+            let canonical_path = entry.canonicalize().unwrap_or_else(|_| entry.clone());
+            if !seen_paths.insert(canonical_path) {
+                continue;
+            }
+            let cached = self.load(&entry);
+            loaded_paths.insert(entry.clone());
+            discovered.issues.extend(cached.issues.iter().cloned());
+            match &cached.loaded {
+                Ok(project_file) => discovered.files.push(project_file.clone()),
+                Err(e) => discovered.errors.push(e.clone()),
+            }
+        }
+        self.files.retain(|path, _| loaded_paths.contains(path));
+        Ok(discovered)
+    }
+
+    /// The file at `path` as loaded, reloading it if it changed.
+    fn load(&mut self, path: &Path) -> &CachedFile {
+        let stamp = file_stamp(path);
+        // A file that failed to load is read again, since what's wrong may be
+        // in a file it includes
+        let fresh = self.files.get(path).is_some_and(|cached| {
+            Some(cached.stamp) == stamp
+                && cached.loaded.is_ok()
+                && cached
+                    .includes
+                    .iter()
+                    .all(|(include, stamp)| file_stamp(include) == *stamp)
+        });
+        if !fresh {
+            let (loaded, issues) = lint::load(path);
+            let loaded = loaded.map_err(|e| {
+                tracing::warn!(path = %path.display(), error = %e, "failed to parse project file");
+                ProjectFileError {
+                    path: path.to_path_buf(),
+                    message: e.root_cause().to_string(),
+                }
+            });
+            let includes = match &loaded {
+                Ok(file) => file
+                    .includes
+                    .iter()
+                    .map(|include| (include.clone(), file_stamp(include)))
+                    .collect(),
+                Err(_) => Vec::new(),
+            };
+            let cached = CachedFile {
+                // A file whose time can't be read is loaded again next time
+                stamp: stamp.unwrap_or((SystemTime::UNIX_EPOCH, u64::MAX)),
+                includes,
+                loaded,
+                issues,
+            };
+            self.files.insert(path.to_path_buf(), cached);
         }
+        &self.files[path]
+    }
+}
+
+/// The modification time and size of the file at `path`.
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    fs::metadata(path)
+        .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+        .ok()
+}
+
+/// Paths of the project files in the search locations that aren't excluded,
+/// or the ones the workspace file lists if there is one. The same file may
+/// be listed under more than one path.
+fn find_project_files(base_dir: Option<&Path>, config: Option<&ProjectConfig>) -> Vec<PathBuf> {
+    if let Some(listed) = config.and_then(workspace_projects) {
+        return listed;
     }
+    let mut found = Vec::new();
+    let search_paths = search_patterns(base_dir, config);
 
-    // Get exclude patterns from config
-    let exclude_patterns: Vec<&str> = config
-        .map(|c| c.exclude_patterns.iter().map(|s| s.as_str()).collect())
-        .unwrap_or_default();
+    let exclude_patterns =
+        ExcludePatterns::new(config.map_or(&[][..], |c| c.exclude_patterns.as_slice()));
 
     for pattern in search_paths {
         match glob(&pattern) {
             Ok(paths) => {
-                for entry in paths.flatten() {
-                    if entry.is_file() {
-                        // Check if path matches any exclude pattern
-                        if should_exclude(&entry, &exclude_patterns) {
-                            continue;
-                        }
-
-                        // Canonicalize path to avoid duplicates from symlinks or different representations
-                        let canonical_path = entry.canonicalize().unwrap_or_else(|_| entry.clone());
-
-                        if !seen_paths.contains(&canonical_path) {
-                            seen_paths.insert(canonical_path.clone());
-                            match ProjectFile::from_path(entry.clone()) {
-                                Ok(project_file) => files.push(project_file),
-                                Err(e) => {
-                                    eprintln!(
-                                        "Warning: Failed to parse {}: {}",
-                                        entry.display(),
-                                        e
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
+                found.extend(
+                    paths
+                        .flatten()
+                        .filter(|entry| entry.is_file())
+                        .filter(|entry| !exclude_patterns.excludes(entry))
+                        .filter(|entry| !is_compose_file(entry) || has_mutagen_section(entry)),
+                );
             }
             Err(e) => {
-                eprintln!("Warning: Failed to glob pattern {}: {}", pattern, e);
+                tracing::warn!(pattern, error = %e, "failed to glob project files");
             }
         }
     }
+    found
+}
+
+/// Whether the compose file at `path` has an `x-mutagen` section. Most
+/// compose files don't, and aren't projects.
+fn has_mutagen_section(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|contents| {
+        contents
+            .lines()
+            .any(|line| line.trim_end().starts_with("x-mutagen:"))
+    })
+}
+
+/// The contents of a workspace file (`projects.toml`).
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Workspace {
+    /// Project files to load, in place of discovery.
+    projects: Vec<PathBuf>,
+}
+
+/// The project files listed in the workspace file at `path`, with
+/// variables and `~` expanded.
+pub fn read_workspace(path: &Path) -> Result<Vec<PathBuf>> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let workspace: Workspace =
+        toml::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(workspace
+        .projects
+        .iter()
+        .map(|path| expand_path(path))
+        .collect())
+}
+
+/// The project files listed in the configured workspace file. Discovery
+/// searches as usual if there's no workspace file or it can't be read.
+fn workspace_projects(config: &ProjectConfig) -> Option<Vec<PathBuf>> {
+    let path = config.workspace.as_deref()?;
+    read_workspace(path)
+        .inspect_err(|e| tracing::warn!(error = %e, "workspace file not used"))
+        .ok()
+}
+
+/// Glob patterns for project files: the built-in locations and the
+/// configured search paths.
+fn search_patterns(base_dir: Option<&Path>, config: Option<&ProjectConfig>) -> Vec<String> {
+    let max_depth = config.map_or(ProjectConfig::default().max_depth, |c| c.max_depth);
+    let mut search_paths = build_search_paths(base_dir, max_depth);
+
+    // Add custom search paths from config
+    if let Some(cfg) = config {
+        for path in &cfg.search_paths {
+            // Expand variables and tilde in config paths
+            let expanded = expand_path(path);
+            search_paths.extend(project_file_patterns(&expanded.to_string_lossy()));
+        }
+    }
+    search_paths
+}
+
+/// Existing directories where a new project file would be discovered, for
+/// watching. A pattern like `~/code/*/*/mutagen.yml` contributes `~/code`
+/// itself, where a new project directory would appear, as well as each
+/// directory under it down to the files. There are none when a workspace
+/// file lists the project files.
+pub fn search_dirs(base_dir: Option<&Path>, config: Option<&ProjectConfig>) -> Vec<PathBuf> {
+    if config.and_then(workspace_projects).is_some() {
+        return Vec::new();
+    }
+    let mut dirs = Vec::new();
+    for pattern in search_patterns(base_dir, config) {
+        let Some(dir) = Path::new(&pattern).parent() else {
+            continue;
+        };
+        let mut found: Vec<PathBuf> = if dir.to_string_lossy().contains('*') {
+            let is_pattern = |ancestor: &&Path| ancestor.to_string_lossy().contains('*');
+            let fixed = dir
+                .ancestors()
+                .find(|ancestor| !is_pattern(ancestor))
+                .map(Path::to_path_buf);
+            let matches = dir
+                .ancestors()
+                .take_while(is_pattern)
+                .filter_map(|ancestor| ancestor.to_str())
+                .flat_map(|pattern| glob(pattern).into_iter().flatten().flatten());
+            fixed.into_iter().chain(matches).collect()
+        } else {
+            vec![dir.to_path_buf()]
+        };
+        found.retain(|dir| dir.is_dir() && !dirs.contains(dir));
+        dirs.extend(found);
+    }
+    dirs
+}
 
-    Ok(files)
+/// Expand environment variables and a leading tilde in a configured path.
+pub fn expand_path(path: &Path) -> PathBuf {
+    let expanded = interpolate::expand(&path.to_string_lossy());
+    expand_tilde_in_path(Path::new(&expanded))
 }
 
 /// Expand tilde (~) in a path to the user's home directory.
-fn expand_tilde_in_path(path: &Path) -> PathBuf {
+pub fn expand_tilde_in_path(path: &Path) -> PathBuf {
     let path_str = path.to_string_lossy();
     if path_str.starts_with('~') {
         if let Some(home) = dirs::home_dir() {
@@ -318,35 +797,97 @@ fn expand_tilde_in_path(path: &Path) -> PathBuf {
     path.to_path_buf()
 }
 
-/// Check if a path should be excluded based on patterns.
-fn should_exclude(path: &Path, patterns: &[&str]) -> bool {
-    if patterns.is_empty() {
-        return false;
+/// Exclude patterns, matched the way `.gitignore` lines are. A pattern
+/// without a `/` matches any one component of a path, like `node_modules` or
+/// `build-*-cache`; one with a `/` matches the whole path, like
+/// `**/vendor/**` or `~/code/old/*`, and is anchored only if it's absolute.
+/// A pattern starting with `!` brings back paths an earlier one excluded:
+/// the last pattern that matches decides.
+struct ExcludePatterns {
+    rules: Vec<ExcludeRule>,
+}
+
+struct ExcludeRule {
+    matcher: GlobMatcher,
+    /// Matched against the whole path rather than each component.
+    whole_path: bool,
+    /// A `!` pattern, which includes what it matches.
+    include: bool,
+}
+
+impl ExcludePatterns {
+    /// Compile `patterns`. Ones that aren't valid globs are logged and
+    /// skipped.
+    fn new(patterns: &[String]) -> Self {
+        let rules = patterns
+            .iter()
+            .filter_map(|pattern| {
+                let (include, pattern) = match pattern.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, pattern.as_str()),
+                };
+                let pattern = pattern.trim_end_matches('/');
+                let whole_path = pattern.contains('/');
+                let pattern = if !whole_path {
+                    pattern.to_string()
+                } else if pattern.starts_with('~') {
+                    expand_tilde_in_path(Path::new(pattern))
+                        .to_string_lossy()
+                        .into_owned()
+                } else if pattern.starts_with('/') || pattern.starts_with("**/") {
+                    pattern.to_string()
+                } else {
+                    format!("**/{}", pattern)
+                };
+                let glob = GlobBuilder::new(&pattern)
+                    .literal_separator(true)
+                    .build()
+                    .inspect_err(|e| tracing::warn!(pattern, error = %e, "bad exclude pattern"))
+                    .ok()?;
+                Some(ExcludeRule {
+                    matcher: glob.compile_matcher(),
+                    whole_path,
+                    include,
+                })
+            })
+            .collect();
+        Self { rules }
     }
 
-    // Check if any component of the path matches an exclude pattern
-    for component in path.components() {
-        let name = component.as_os_str().to_string_lossy();
-        for pattern in patterns {
-            // Support simple glob-like matching
-            if pattern.contains('*') {
-                // Simple wildcard matching
-                let parts: Vec<&str> = pattern.split('*').collect();
-                if parts.len() == 2 {
-                    let (prefix, suffix) = (parts[0], parts[1]);
-                    if name.starts_with(prefix) && name.ends_with(suffix) {
-                        return true;
-                    }
-                }
-            } else if name.contains(*pattern) {
-                return true;
+    /// Whether discovery should skip the project file at `path`.
+    fn excludes(&self, path: &Path) -> bool {
+        let mut excluded = false;
+        for rule in &self.rules {
+            let matches = if rule.whole_path {
+                rule.matcher.is_match(path)
+            } else {
+                path.components()
+                    .any(|component| rule.matcher.is_match(component.as_os_str()))
+            };
+            if matches {
+                excluded = !rule.include;
             }
         }
+        excluded
     }
-    false
 }
 
-fn build_search_paths(base_dir: Option<&Path>) -> Vec<String> {
+/// Patterns for the project files directly in `dir`: the mutagen file names
+/// and the compose ones.
+fn project_file_patterns(dir: &str) -> Vec<String> {
+    [
+        "mutagen.yml",
+        "mutagen-*.yml",
+        ".mutagen.yml",
+        ".mutagen-*.yml",
+    ]
+    .iter()
+    .chain(COMPOSE_FILE_NAMES)
+    .map(|name| format!("{}/{}", dir, name))
+    .collect()
+}
+
+fn build_search_paths(base_dir: Option<&Path>, max_depth: usize) -> Vec<String> {
     let mut paths = Vec::new();
     let home = std::env::var("HOME").ok();
 
@@ -354,10 +895,7 @@ fn build_search_paths(base_dir: Option<&Path>) -> Vec<String> {
     let start_dir_str = start_dir.to_str().unwrap_or(".");
 
     // Base directory patterns
-    paths.push(format!("{}/mutagen.yml", start_dir_str));
-    paths.push(format!("{}/mutagen-*.yml", start_dir_str));
-    paths.push(format!("{}/.mutagen.yml", start_dir_str));
-    paths.push(format!("{}/.mutagen-*.yml", start_dir_str));
+    paths.extend(project_file_patterns(start_dir_str));
 
     // Base directory subdirectories - common config locations
     paths.push(format!("{}/mutagen/*.yml", start_dir_str));
@@ -365,12 +903,13 @@ fn build_search_paths(base_dir: Option<&Path>) -> Vec<String> {
     paths.push(format!("{}/config/mutagen/*.yml", start_dir_str));
     paths.push(format!("{}/conf/mutagen/*.yml", start_dir_str));
 
-    // Direct children only (1 level deep) - for multi-project directories like ~/code
-    // This allows finding projects in subdirectories without deep traversal
-    paths.push(format!("{}/*/mutagen.yml", start_dir_str));
-    paths.push(format!("{}/*/mutagen-*.yml", start_dir_str));
-    paths.push(format!("{}/*/.mutagen.yml", start_dir_str));
-    paths.push(format!("{}/*/.mutagen-*.yml", start_dir_str));
+    // Subdirectories down to max_depth (1 by default) - for multi-project directories
+    // like ~/code. Each level is its own pattern rather than `**/`, which would walk
+    // the whole tree
+    for depth in 1..=max_depth {
+        let dir = format!("{}{}", start_dir_str, "/*".repeat(depth));
+        paths.extend(project_file_patterns(&dir));
+    }
 
     // Walk up directory tree looking for project subdirectories
     let walk_start = if let Some(base) = base_dir {
@@ -423,30 +962,12 @@ pub fn build_sync_specs(
 ) -> Vec<SyncSpec> {
     let mut specs = Vec::new();
 
-    for (name, _definition) in &project_file.sessions {
-        // Find matching running session(s)
-        let two_way_session = sessions.iter().find(|s| {
-            s.name == *name && s.mode.as_deref() != Some("one-way-replica")
-        });
-
-        let push_session = sessions.iter().find(|s| {
-            s.name == format!("{}-push", name)
-                && s.mode.as_deref() == Some("one-way-replica")
-        });
-
-        // Determine state and attach session
-        let (state, running_session) = if let Some(session) = two_way_session {
-            (SyncSpecState::RunningTwoWay, Some(session.clone()))
-        } else if let Some(session) = push_session {
-            (SyncSpecState::RunningPush, Some(session.clone()))
-        } else {
-            (SyncSpecState::NotRunning, None)
-        };
-
+    for name in project_file.sessions.keys() {
+        let (state, running_session) = find_spec_session(name, sessions);
         specs.push(SyncSpec {
             name: name.clone(),
             state,
-            running_session,
+            running_session: running_session.cloned().map(Arc::new),
         });
     }
 
@@ -455,6 +976,30 @@ pub fn build_sync_specs(
     specs
 }
 
+/// The running session of the spec called `name`, if any, and the state
+/// that gives it.
+fn find_spec_session<'a>(
+    name: &str,
+    sessions: &'a [SyncSession],
+) -> (SyncSpecState, Option<&'a SyncSession>) {
+    // Find matching running session(s)
+    let two_way_session = sessions
+        .iter()
+        .find(|s| s.name == name && s.mode.as_deref() != Some("one-way-replica"));
+
+    let push_session = sessions.iter().find(|s| {
+        s.name.strip_suffix("-push") == Some(name) && s.mode.as_deref() == Some("one-way-replica")
+    });
+
+    if let Some(session) = two_way_session {
+        (SyncSpecState::RunningTwoWay, Some(session))
+    } else if let Some(session) = push_session {
+        (SyncSpecState::RunningPush, Some(session))
+    } else {
+        (SyncSpecState::NotRunning, None)
+    }
+}
+
 /// Helper to check if specs should auto-unfold (used during construction)
 fn should_auto_unfold_specs(specs: &[SyncSpec]) -> bool {
     // Auto-unfold if any spec has conflicts
@@ -484,23 +1029,183 @@ fn should_auto_unfold_specs(specs: &[SyncSpec]) -> bool {
     false
 }
 
+/// Rank of a spec when sorting by status; lower ranks need more attention.
+fn status_rank(spec: &SyncSpec) -> u8 {
+    let Some(session) = &spec.running_session else {
+        return 6;
+    };
+    if session.has_conflicts() {
+        0
+    } else if session.status_text() == "Halted" {
+        1
+    } else if session.paused {
+        5
+    } else if !session.alpha.connected || !session.beta.connected {
+        2
+    } else if session.status_text() != "Watching" {
+        3
+    } else {
+        4
+    }
+}
+
+/// Rank of a spec when sorting by last sync; sessions seen syncing come first.
+fn last_sync_rank(spec: &SyncSpec) -> u8 {
+    match spec.running_session.as_ref().map(|s| &s.sync_time) {
+        Some(SyncTime::At(_)) => 0,
+        Some(SyncTime::Unknown) => 1,
+        Some(SyncTime::Never) => 2,
+        None => 3,
+    }
+}
+
+/// Sort specs within a project.
+///
+/// Every mode falls back to the spec name and then the beta path, so specs
+/// whose rank is unchanged keep their position across refreshes.
+pub fn sort_specs(specs: &mut [SyncSpec], mode: SortMode) {
+    specs.sort_by(|a, b| compare_specs(a, b, mode));
+}
+
+/// The order of two specs under `mode`, as `sort_specs` sorts them.
+pub fn compare_specs(a: &SyncSpec, b: &SyncSpec, mode: SortMode) -> std::cmp::Ordering {
+    let rank = |spec: &SyncSpec| match mode {
+        SortMode::Name => 0,
+        SortMode::Status => status_rank(spec),
+        SortMode::LastSync => last_sync_rank(spec),
+    };
+    let beta_path = |spec: &SyncSpec| spec.running_session.as_ref().map(|s| s.beta.path.clone());
+    let synced_at = |spec: &SyncSpec| match spec.running_session.as_ref()?.sync_time {
+        SyncTime::At(at) => Some(at),
+        _ => None,
+    };
+    rank(a)
+        .cmp(&rank(b))
+        // Most recently synced first
+        .then_with(|| match mode {
+            SortMode::LastSync => synced_at(b).cmp(&synced_at(a)),
+            _ => std::cmp::Ordering::Equal,
+        })
+        .then_with(|| a.name.cmp(&b.name))
+        .then_with(|| beta_path(a).cmp(&beta_path(b)))
+}
+
+/// Sort projects by display name, then by file path, with favorites first.
+///
+/// Display names repeat (every plain `mutagen.yml` is "mutagen"), so the path
+/// keeps the order independent of discovery order. Returns whether any
+/// project moved.
+pub fn sort_projects(projects: &mut [Project]) -> bool {
+    let order = |a: &Project, b: &Project| {
+        b.favorite
+            .cmp(&a.favorite)
+            .then_with(|| a.file.display_name().cmp(&b.file.display_name()))
+            .then_with(|| a.file.path.cmp(&b.file.path))
+    };
+    if projects.is_sorted_by(|a, b| order(a, b).is_le()) {
+        return false;
+    }
+    projects.sort_by(order);
+    true
+}
+
+/// Find the project, and optionally the spec, named by `target`: `project`,
+/// `project:spec`, or `:spec` for a spec in any project.
+///
+/// A project matches by display name or by the directory holding its file,
+/// since most project files are plain `mutagen.yml`.
+pub fn find_selection(projects: &[Project], target: &str) -> Option<(usize, Option<usize>)> {
+    let (project_name, spec_name) = match target.split_once(':') {
+        Some((project, spec)) => (project, Some(spec)),
+        None => (target, None),
+    };
+    let mut candidates = projects.iter().enumerate().filter(|(_, project)| {
+        project_name.is_empty()
+            || project.file.display_name() == project_name
+            || project
+                .file
+                .path
+                .parent()
+                .and_then(|dir| dir.file_name())
+                .is_some_and(|dir| dir == project_name)
+    });
+
+    match spec_name {
+        None => candidates.next().map(|(idx, _)| (idx, None)),
+        Some(spec_name) => candidates.find_map(|(idx, project)| {
+            project
+                .specs
+                .iter()
+                .position(|spec| spec.name == spec_name)
+                .map(|spec_idx| (idx, Some(spec_idx)))
+        }),
+    }
+}
+
+/// Bring `projects` up to date with `files` and `sessions`. A project whose
+/// file hasn't changed keeps its specs, and only those whose session changed
+/// are replaced; the rest are rebuilt. Projects keep their fold state; new
+/// ones start unfolded if their specs need a look (conflicts, or a mix of
+/// states or modes). Returns whether any project or spec was added, removed,
+/// or replaced.
+pub fn update_projects(
+    projects: &mut Vec<Project>,
+    files: Vec<ProjectFile>,
+    sessions: &[SyncSession],
+) -> bool {
+    let mut previous: HashMap<PathBuf, Project> = projects
+        .drain(..)
+        .map(|project| (project.file.path.clone(), project))
+        .collect();
+    let mut changed = previous.len() != files.len();
+
+    for file in files {
+        match previous.remove(&file.path) {
+            Some(mut project) if project.file == file => {
+                changed |= update_specs(&mut project.specs, sessions);
+                projects.push(project);
+            }
+            project => {
+                changed = true;
+                let specs = build_sync_specs(&file, sessions);
+                let folded =
+                    project.map_or_else(|| !should_auto_unfold_specs(&specs), |p| p.folded);
+                projects.push(Project {
+                    file,
+                    specs,
+                    folded,
+                    favorite: false,
+                });
+            }
+        }
+    }
+    changed
+}
+
+/// Give each of `specs` its session from `sessions`, replacing only the
+/// specs whose session changed. Returns whether any did.
+fn update_specs(specs: &mut [SyncSpec], sessions: &[SyncSession]) -> bool {
+    let mut changed = false;
+    for spec in specs {
+        let (state, session) = find_spec_session(&spec.name, sessions);
+        if spec.state != state || spec.running_session.as_deref() != session {
+            spec.state = state;
+            spec.running_session = session.cloned().map(Arc::new);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Build projects from scratch for `project_files` and `sessions`.
+#[cfg(test)]
 pub fn correlate_projects_with_sessions(
     project_files: Vec<ProjectFile>,
     sessions: &[SyncSession],
 ) -> Vec<Project> {
-    project_files
-        .into_iter()
-        .map(|file| {
-            let specs = build_sync_specs(&file, sessions);
-            let should_unfold = should_auto_unfold_specs(&specs);
-
-            Project {
-                file,
-                specs,
-                folded: !should_unfold, // Start unfolded if auto-unfold conditions met
-            }
-        })
-        .collect()
+    let mut projects = Vec::new();
+    update_projects(&mut projects, project_files, sessions);
+    projects
 }
 
 #[cfg(test)]
@@ -550,6 +1255,9 @@ mod tests {
             target_name: Some("cool30".to_string()),
             sessions: HashMap::new(),
             defaults: None,
+            includes: Vec::new(),
+            hooks: Hooks::default(),
+            commands: BTreeMap::new(),
         };
         assert_eq!(project.display_name(), "mutagen-cool30");
     }
@@ -561,6 +1269,9 @@ mod tests {
             target_name: None,
             sessions: HashMap::new(),
             defaults: None,
+            includes: Vec::new(),
+            hooks: Hooks::default(),
+            commands: BTreeMap::new(),
         };
         assert_eq!(project.display_name(), "mutagen");
     }
@@ -625,9 +1336,142 @@ mod tests {
         assert!(patterns.contains(&"session_specific".to_string()));
     }
 
-    // ============ discover_project_files tests (using temp directories) ============
+    #[test]
+    fn test_session_definition_mode_with_defaults() {
+        let defaults: serde_yaml::Value = serde_yaml::from_str("mode: one-way-safe").unwrap();
+        let session: SessionDefinition =
+            serde_yaml::from_str("alpha: /local\nbeta: server:/remote").unwrap();
+        assert_eq!(session.mode_with_defaults(None), None);
+        assert_eq!(
+            session.mode_with_defaults(Some(&defaults)).as_deref(),
+            Some("one-way-safe")
+        );
+
+        let session: SessionDefinition =
+            serde_yaml::from_str("alpha: /local\nbeta: server:/remote\nmode: two-way-resolved")
+                .unwrap();
+        assert_eq!(
+            session.mode_with_defaults(Some(&defaults)).as_deref(),
+            Some("two-way-resolved")
+        );
+    }
+
+    #[test]
+    fn test_session_definition_create_args() {
+        let defaults: serde_yaml::Value = serde_yaml::from_str(
+            "mode: one-way-safe\nignore: [.cache]\n\
+             permissions:\n  defaultFileMode: 0644\n  defaultOwner: deploy\n\
+             watch:\n  mode: force-poll\n  pollingInterval: 5",
+        )
+        .unwrap();
+        let session: SessionDefinition = serde_yaml::from_str(
+            "alpha: /local\nbeta: server:/remote\nignore: [node_modules]\n\
+             permissions:\n  defaultOwner: www\nsymlink:\n  mode: posix-raw",
+        )
+        .unwrap();
+        assert_eq!(
+            session.create_args(Some(&defaults)).join(" "),
+            "-m one-way-safe --ignore .cache --ignore node_modules \
+             --default-file-mode 0644 --default-owner www --symlink-mode posix-raw \
+             --watch-mode force-poll --watch-polling-interval 5"
+        );
+        assert!(session
+            .create_args(None)
+            .join(" ")
+            .starts_with("--ignore node_modules --default-owner www"));
+    }
+
+    #[test]
+    fn test_project_file_expands_variables() {
+        let home = std::env::var("HOME").unwrap();
+        let yaml = "sync:\n  \
+                    defaults:\n    \
+                    ignore: [\"${HOME}/cache\"]\n  \
+                    api:\n    \
+                    alpha: ${HOME}/api\n    \
+                    beta: server:/srv/$$api\n    \
+                    ignore:\n      \
+                    paths: [\"$MUTAGUI_UNSET_VAR/tmp\"]\n";
+        let file = ProjectFile::from_contents(PathBuf::from("mutagen.yml"), yaml).unwrap();
+        let api = &file.sessions["api"];
+        assert_eq!(api.alpha, format!("{}/api", home));
+        assert_eq!(api.beta, "server:/srv/$api");
+        let defaults = serde_yaml::to_value(file.defaults.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            api.get_ignore_patterns(Some(&defaults)),
+            [
+                format!("{}/cache", home),
+                "$MUTAGUI_UNSET_VAR/tmp".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_project_file_hooks_and_commands() {
+        let yaml = "beforeCreate: [make assets]\n\
+                    afterTerminate:\n  - ./notify stopped\n\
+                    sync: {}\n";
+        let file = ProjectFile::from_contents(PathBuf::from("mutagen.yml"), yaml).unwrap();
+        assert_eq!(file.hooks.before_create, ["make assets"]);
+        assert_eq!(file.hooks.after_terminate, ["./notify stopped"]);
+        assert!(file.hooks.after_create.is_empty());
+
+        let yaml = "commands:\n  deploy: ./deploy.sh\n  build: make\n";
+        let file = ProjectFile::from_contents(PathBuf::from("mutagen.yml"), yaml).unwrap();
+        assert_eq!(
+            file.commands.keys().collect::<Vec<_>>(),
+            ["build", "deploy"]
+        );
+    }
+
+    #[test]
+    fn test_project_file_includes_shared_defaults() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        fs::write(
+            dir.join("base.yml"),
+            "sync:\n  defaults:\n    mode: two-way-safe\n    ignore:\n      vcs: true\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("shared.yml"),
+            "include: base.yml\n\
+             sync:\n  defaults:\n    mode: two-way-resolved\n    \
+             ignore:\n      paths: [node_modules]\n",
+        )
+        .unwrap();
+        let project = dir.join("mutagen.yml");
+        fs::write(
+            &project,
+            "include: [shared.yml]\n\
+             sync:\n  defaults:\n    ignore:\n      paths: [.cache]\n  \
+             api:\n    alpha: .\n    beta: server:/srv\n",
+        )
+        .unwrap();
+
+        let file = ProjectFile::from_path(project.clone()).unwrap();
+        assert_eq!(
+            file.includes,
+            [dir.join("shared.yml"), dir.join("base.yml")]
+        );
+        let defaults = serde_yaml::to_value(file.defaults.as_ref().unwrap()).unwrap();
+        assert_eq!(defaults["mode"], "two-way-resolved");
+        let patterns = file.sessions["api"].get_ignore_patterns(Some(&defaults));
+        assert!(patterns.contains(&".git".to_string()));
+        assert!(patterns.contains(&"node_modules".to_string()));
+        assert!(patterns.contains(&".cache".to_string()));
+
+        fs::write(dir.join("base.yml"), "include: mutagen.yml\n").unwrap();
+        let error = ProjectFile::from_path(project.clone()).unwrap_err();
+        assert!(error.to_string().contains("includes itself"));
+
+        fs::write(&project, "include: missing.yml\n").unwrap();
+        assert!(ProjectFile::from_path(project).is_err());
+    }
+
+    // ============ discovery tests (using temp directories) ============
     //
-    // Note: discover_project_files searches multiple locations including home directories,
+    // Note: discovery searches multiple locations including home directories,
     // so these tests check that files ARE found in the temp directory rather than exact counts.
 
     #[test]
@@ -647,7 +1491,10 @@ sync:
         )
         .unwrap();
 
-        let files = discover_project_files(Some(temp_dir.path()), None).unwrap();
+        let files = DiscoveryCache::default()
+            .discover(Some(temp_dir.path()), None)
+            .unwrap()
+            .files;
         // Check that our file is found (there may be others from home directories)
         let found = files
             .iter()
@@ -673,7 +1520,10 @@ sync:
         )
         .unwrap();
 
-        let files = discover_project_files(Some(temp_dir.path()), None).unwrap();
+        let files = DiscoveryCache::default()
+            .discover(Some(temp_dir.path()), None)
+            .unwrap()
+            .files;
         // Check that our named variant is found
         let found = files
             .iter()
@@ -699,7 +1549,10 @@ sync:
         )
         .unwrap();
 
-        let files = discover_project_files(Some(temp_dir.path()), None).unwrap();
+        let files = DiscoveryCache::default()
+            .discover(Some(temp_dir.path()), None)
+            .unwrap()
+            .files;
 
         // Count how many times our temp directory file appears (should be exactly 1)
         let temp_file_count = files
@@ -715,7 +1568,10 @@ sync:
     #[test]
     fn test_discover_project_files_empty_temp_directory() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let files = discover_project_files(Some(temp_dir.path()), None).unwrap();
+        let files = DiscoveryCache::default()
+            .discover(Some(temp_dir.path()), None)
+            .unwrap()
+            .files;
 
         // Check that no files from the temp directory are found
         let temp_files: Vec<_> = files
@@ -728,6 +1584,113 @@ sync:
         );
     }
 
+    #[test]
+    fn test_discover_project_files_reports_parse_errors() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let yml_path = temp_dir.path().join("mutagen.yml");
+        fs::write(&yml_path, "sync:\n  web:\n    alpha: [unclosed\n").unwrap();
+
+        let discovered = DiscoveryCache::default()
+            .discover(Some(temp_dir.path()), None)
+            .unwrap();
+        assert!(!discovered
+            .files
+            .iter()
+            .any(|f| f.path.starts_with(temp_dir.path())));
+        let error = discovered
+            .errors
+            .iter()
+            .find(|e| e.path.starts_with(temp_dir.path()))
+            .expect("parse error should be reported");
+        assert!(!error.message.is_empty());
+        assert!(!error.message.starts_with("Failed to parse"));
+    }
+
+    #[test]
+    fn test_discover_compose_files_with_mutagen_section() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let with_sync = temp_dir.path().join("shop");
+        let without = temp_dir.path().join("blog");
+        fs::create_dir(&with_sync).unwrap();
+        fs::create_dir(&without).unwrap();
+        fs::write(
+            with_sync.join("compose.yaml"),
+            "services:\n  web:\n    image: nginx\n\
+             x-mutagen:\n  sync:\n    code:\n      alpha: .\n      beta: volume://code\n",
+        )
+        .unwrap();
+        fs::write(
+            without.join("docker-compose.yml"),
+            "services:\n  web:\n    image: nginx\n",
+        )
+        .unwrap();
+
+        let discovered = DiscoveryCache::default()
+            .discover(Some(temp_dir.path()), None)
+            .unwrap();
+        let found: Vec<_> = discovered
+            .files
+            .iter()
+            .filter(|f| f.path.starts_with(temp_dir.path()))
+            .collect();
+        assert_eq!(found.len(), 1);
+        assert!(found[0].is_compose());
+        assert_eq!(found[0].display_name(), "compose-shop");
+        assert_eq!(found[0].sessions["code"].beta, "volume://code");
+        // Only the x-mutagen section is checked
+        assert!(discovered
+            .issues
+            .iter()
+            .all(|issue| !issue.path.starts_with(temp_dir.path())));
+    }
+
+    #[test]
+    fn test_discovery_cache_reloads_changed_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let yml_path = temp_dir.path().join("mutagen.yml");
+        fs::write(
+            &yml_path,
+            "sync:\n  aaa:\n    alpha: .\n    beta: server:/srv\n",
+        )
+        .unwrap();
+        let modified = fs::metadata(&yml_path).unwrap().modified().unwrap();
+        let names = |discovered: DiscoveredProjects| -> Vec<String> {
+            discovered
+                .files
+                .into_iter()
+                .filter(|f| f.path.starts_with(temp_dir.path()))
+                .flat_map(|f| f.sessions.into_keys())
+                .collect()
+        };
+
+        let mut cache = DiscoveryCache::default();
+        let discovered = cache.discover(Some(temp_dir.path()), None).unwrap();
+        assert_eq!(names(discovered), ["aaa"]);
+
+        // Same size and modification time: taken from the cache
+        fs::write(
+            &yml_path,
+            "sync:\n  bbb:\n    alpha: .\n    beta: server:/srv\n",
+        )
+        .unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&yml_path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        let discovered = cache.discover(Some(temp_dir.path()), None).unwrap();
+        assert_eq!(names(discovered), ["aaa"]);
+
+        fs::write(
+            &yml_path,
+            "sync:\n  cccc:\n    alpha: .\n    beta: server:/srv\n",
+        )
+        .unwrap();
+        let discovered = cache.discover(Some(temp_dir.path()), None).unwrap();
+        assert_eq!(names(discovered), ["cccc"]);
+    }
+
     #[test]
     fn test_discover_project_files_with_exclude_patterns() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -753,7 +1716,10 @@ sync:
         .unwrap();
 
         // Discover without exclude - should find both
-        let files_no_exclude = discover_project_files(Some(temp_dir.path()), None).unwrap();
+        let files_no_exclude = DiscoveryCache::default()
+            .discover(Some(temp_dir.path()), None)
+            .unwrap()
+            .files;
         let temp_files_no_exclude: Vec<_> = files_no_exclude
             .iter()
             .filter(|f| f.path.starts_with(temp_dir.path()))
@@ -767,9 +1733,12 @@ sync:
         let config = ProjectConfig {
             search_paths: vec![],
             exclude_patterns: vec!["backup".to_string()],
+            ..ProjectConfig::default()
         };
-        let files_with_exclude =
-            discover_project_files(Some(temp_dir.path()), Some(&config)).unwrap();
+        let files_with_exclude = DiscoveryCache::default()
+            .discover(Some(temp_dir.path()), Some(&config))
+            .unwrap()
+            .files;
         let temp_files_with_exclude: Vec<_> = files_with_exclude
             .iter()
             .filter(|f| f.path.starts_with(temp_dir.path()))
@@ -803,7 +1772,10 @@ sync:
         // Discover without custom path - should not find it (searching from temp_dir root)
         let empty_subdir = temp_dir.path().join("empty");
         fs::create_dir(&empty_subdir).unwrap();
-        let files_no_custom = discover_project_files(Some(&empty_subdir), None).unwrap();
+        let files_no_custom = DiscoveryCache::default()
+            .discover(Some(&empty_subdir), None)
+            .unwrap()
+            .files;
         let found_custom = files_no_custom
             .iter()
             .any(|f| f.path.to_string_lossy().contains("custom-projects"));
@@ -816,8 +1788,12 @@ sync:
         let config = ProjectConfig {
             search_paths: vec![custom_dir.clone()],
             exclude_patterns: vec![],
+            ..ProjectConfig::default()
         };
-        let files_with_custom = discover_project_files(Some(&empty_subdir), Some(&config)).unwrap();
+        let files_with_custom = DiscoveryCache::default()
+            .discover(Some(&empty_subdir), Some(&config))
+            .unwrap()
+            .files;
         let found_custom_with_config = files_with_custom
             .iter()
             .any(|f| f.path.to_string_lossy().contains("custom-projects"));
@@ -827,6 +1803,130 @@ sync:
         );
     }
 
+    #[test]
+    fn test_search_dirs_include_where_projects_would_appear() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base = temp_dir.path();
+        fs::create_dir(base.join("api")).unwrap();
+        fs::create_dir_all(base.join("config/mutagen")).unwrap();
+        let custom_dir = base.join("custom");
+        fs::create_dir(&custom_dir).unwrap();
+        let config = ProjectConfig {
+            search_paths: vec![custom_dir.clone(), base.join("missing")],
+            exclude_patterns: vec![],
+            ..ProjectConfig::default()
+        };
+
+        let dirs = search_dirs(Some(base), Some(&config));
+        for dir in [
+            base.to_path_buf(),
+            base.join("api"),
+            base.join("config/mutagen"),
+            custom_dir,
+        ] {
+            assert!(dirs.contains(&dir), "{} not searched", dir.display());
+        }
+        assert!(!dirs.contains(&base.join("missing")));
+        let unique: std::collections::HashSet<_> = dirs.iter().collect();
+        assert_eq!(unique.len(), dirs.len());
+    }
+
+    #[test]
+    fn test_exclude_patterns() {
+        let patterns: Vec<String> = [
+            "node_modules",
+            "build-*-cache",
+            "**/vendor/**",
+            "old/*.yml",
+            "!old/keep.yml",
+            "[unclosed",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let patterns = ExcludePatterns::new(&patterns);
+        let excludes = |path: &str| patterns.excludes(Path::new(path));
+
+        assert!(excludes("/code/web/node_modules/pkg/mutagen.yml"));
+        assert!(!excludes("/code/node_modules_docs/mutagen.yml"));
+        assert!(excludes("./build-x86-cache/mutagen.yml"));
+        assert!(!excludes("./build-cache/mutagen.yml"));
+        assert!(excludes("/code/api/vendor/lib/mutagen.yml"));
+        assert!(excludes("/code/old/mutagen.yml"));
+        assert!(!excludes("/code/old/keep.yml"));
+        assert!(!excludes("/code/old/nested/mutagen.yml"));
+        assert!(!excludes("/code/api/mutagen.yml"));
+    }
+
+    #[test]
+    fn test_discovery_max_depth() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base = temp_dir.path();
+        let nested = base.join("clients/acme");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("mutagen.yml"), "sync: {}\n").unwrap();
+        let finds_nested = |max_depth: usize| {
+            let config = ProjectConfig {
+                max_depth,
+                ..ProjectConfig::default()
+            };
+            let discovered = DiscoveryCache::default()
+                .discover(Some(base), Some(&config))
+                .unwrap();
+            let watched = search_dirs(Some(base), Some(&config));
+            let found = discovered.files.iter().any(|f| f.path.starts_with(&nested));
+            (found, watched.contains(&base.join("clients")))
+        };
+
+        assert_eq!(finds_nested(1), (false, true));
+        assert_eq!(finds_nested(2), (true, true));
+        assert_eq!(finds_nested(0), (false, false));
+    }
+
+    #[test]
+    fn test_workspace_file_replaces_discovery() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base = temp_dir.path();
+        fs::write(base.join("mutagen.yml"), "sync: {}\n").unwrap();
+        let listed = base.join("elsewhere/mutagen-prod.yml");
+        fs::create_dir(base.join("elsewhere")).unwrap();
+        fs::write(&listed, "sync: {}\n").unwrap();
+        let workspace = base.join("projects.toml");
+        fs::write(
+            &workspace,
+            format!(
+                "projects = [\"{}\", \"{}\"]\n",
+                listed.display(),
+                base.join("gone.yml").display()
+            ),
+        )
+        .unwrap();
+        let config = ProjectConfig {
+            workspace: Some(workspace.clone()),
+            ..ProjectConfig::default()
+        };
+
+        let discovered = DiscoveryCache::default()
+            .discover(Some(base), Some(&config))
+            .unwrap();
+        let paths: Vec<_> = discovered.files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(paths, [listed]);
+        assert_eq!(discovered.errors.len(), 1);
+        assert_eq!(discovered.errors[0].path, base.join("gone.yml"));
+        assert!(search_dirs(Some(base), Some(&config)).is_empty());
+
+        // An unreadable workspace file falls back to searching
+        fs::write(&workspace, "projects = \"not a list\"\n").unwrap();
+        let discovered = DiscoveryCache::default()
+            .discover(Some(base), Some(&config))
+            .unwrap();
+        assert!(discovered
+            .files
+            .iter()
+            .any(|f| f.path == base.join("mutagen.yml")));
+        assert_eq!(crate::lint::check_workspace(&workspace).len(), 1);
+    }
+
     // ============ correlate_projects_with_sessions tests ============
 
     fn make_test_session(name: &str, alpha_path: &str, beta_path: &str) -> SyncSession {
@@ -838,7 +1938,9 @@ sync:
             alpha: Endpoint {
                 protocol: "local".to_string(),
                 path: alpha_path.to_string(),
+                user: None,
                 host: None,
+                port: None,
                 connected: true,
                 scanned: true,
                 directories: None,
@@ -846,11 +1948,18 @@ sync:
                 symbolic_links: None,
                 total_file_size: None,
                 staging_progress: None,
+                scan_problems: vec![],
+                excluded_scan_problems: 0,
+                transition_problems: vec![],
+                excluded_transition_problems: 0,
+                environment: Default::default(),
             },
             beta: Endpoint {
                 protocol: "ssh".to_string(),
                 path: beta_path.to_string(),
+                user: None,
                 host: Some("server".to_string()),
+                port: None,
                 connected: true,
                 scanned: true,
                 directories: None,
@@ -858,6 +1967,11 @@ sync:
                 symbolic_links: None,
                 total_file_size: None,
                 staging_progress: None,
+                scan_problems: vec![],
+                excluded_scan_problems: 0,
+                transition_problems: vec![],
+                excluded_transition_problems: 0,
+                environment: Default::default(),
             },
             status: "Watching for changes".to_string(),
             paused: false,
@@ -865,10 +1979,74 @@ sync:
             creation_time: None,
             successful_cycles: None,
             conflicts: vec![],
+            excluded_conflicts: 0,
+            labels: Default::default(),
+            last_error: None,
             sync_time: SyncTime::Unknown,
         }
     }
 
+    #[test]
+    fn test_update_projects_keeps_unchanged() {
+        let file = |dir: &str, specs: &[&str]| ProjectFile {
+            path: PathBuf::from(format!("/{}/mutagen.yml", dir)),
+            target_name: None,
+            sessions: specs
+                .iter()
+                .map(|spec| {
+                    let definition = SessionDefinition {
+                        alpha: format!("/{}", dir),
+                        beta: format!("server:/{}", dir),
+                        mode: None,
+                        ignore: None,
+                        permissions: None,
+                        symlink: None,
+                        watch: None,
+                    };
+                    (spec.to_string(), definition)
+                })
+                .collect(),
+            defaults: None,
+            includes: Vec::new(),
+            hooks: Hooks::default(),
+            commands: BTreeMap::new(),
+        };
+        let files = || vec![file("app", &["api", "worker"]), file("docs", &["site"])];
+        let mut sessions = vec![
+            make_test_session("api", "/app", "/app"),
+            make_test_session("site", "/docs", "/docs"),
+            make_test_session("worker", "/app", "/app"),
+        ];
+
+        let mut projects = Vec::new();
+        assert!(update_projects(&mut projects, files(), &sessions));
+        projects[1].folded = false;
+        let docs_specs = projects[1].specs.as_ptr();
+        let worker = projects[0].specs[1].running_session.clone().unwrap();
+        assert!(!update_projects(&mut projects, files(), &sessions));
+
+        sessions[0].status = "Staging files on beta".to_string();
+        assert!(update_projects(&mut projects, files(), &sessions));
+        assert_eq!(
+            projects[0].specs[0].running_session.as_deref(),
+            Some(&sessions[0])
+        );
+        // Only the spec whose session changed is replaced
+        let kept = projects[0].specs[1].running_session.as_ref().unwrap();
+        assert!(Arc::ptr_eq(kept, &worker));
+        // The other project is the same one, fold state and all
+        assert_eq!(projects[1].specs.as_ptr(), docs_specs);
+        assert!(!projects[1].folded);
+
+        assert!(update_projects(
+            &mut projects,
+            vec![file("app", &["web"])],
+            &sessions
+        ));
+        assert_eq!(projects.len(), 1);
+        assert!(!projects[0].specs[0].is_running());
+    }
+
     #[test]
     fn test_correlate_by_session_name() {
         let mut sessions_map = HashMap::new();
@@ -879,6 +2057,9 @@ sync:
                 beta: "server:/remote/path".to_string(),
                 mode: None,
                 ignore: None,
+                permissions: None,
+                symlink: None,
+                watch: None,
             },
         );
 
@@ -887,6 +2068,9 @@ sync:
             target_name: None,
             sessions: sessions_map,
             defaults: None,
+            includes: Vec::new(),
+            hooks: Hooks::default(),
+            commands: BTreeMap::new(),
         };
 
         let running_session =
@@ -911,6 +2095,9 @@ sync:
                 beta: "server:/remote/path".to_string(),
                 mode: None,
                 ignore: None,
+                permissions: None,
+                symlink: None,
+                watch: None,
             },
         );
 
@@ -919,6 +2106,9 @@ sync:
             target_name: None,
             sessions: sessions_map,
             defaults: None,
+            includes: Vec::new(),
+            hooks: Hooks::default(),
+            commands: BTreeMap::new(),
         };
 
         // Push sessions have "-push" suffix and mode "one-way-replica"
@@ -945,6 +2135,9 @@ sync:
                 beta: "server:/remote/path".to_string(),
                 mode: None,
                 ignore: None,
+                permissions: None,
+                symlink: None,
+                watch: None,
             },
         );
 
@@ -953,6 +2146,9 @@ sync:
             target_name: None,
             sessions: sessions_map,
             defaults: None,
+            includes: Vec::new(),
+            hooks: Hooks::default(),
+            commands: BTreeMap::new(),
         };
 
         // Different session name and paths
@@ -978,6 +2174,9 @@ sync:
                 beta: "server:/remote".to_string(),
                 mode: None,
                 ignore: None,
+                permissions: None,
+                symlink: None,
+                watch: None,
             },
         );
         sessions_map.insert(
@@ -987,6 +2186,9 @@ sync:
                 beta: "server:/remote".to_string(),
                 mode: None,
                 ignore: None,
+                permissions: None,
+                symlink: None,
+                watch: None,
             },
         );
 
@@ -995,6 +2197,9 @@ sync:
             target_name: None,
             sessions: sessions_map,
             defaults: None,
+            includes: Vec::new(),
+            hooks: Hooks::default(),
+            commands: BTreeMap::new(),
         };
 
         let sessions = vec![
@@ -1017,7 +2222,7 @@ sync:
         let spec = SyncSpec {
             name: "test".to_string(),
             state: SyncSpecState::RunningTwoWay,
-            running_session: Some(session),
+            running_session: Some(Arc::new(session)),
         };
 
         let project = Project {
@@ -1026,9 +2231,13 @@ sync:
                 target_name: None,
                 sessions: HashMap::new(),
                 defaults: None,
+                includes: Vec::new(),
+                hooks: Hooks::default(),
+                commands: BTreeMap::new(),
             },
             specs: vec![spec],
             folded: false,
+            favorite: false,
         };
         assert!(project.is_active());
     }
@@ -1047,11 +2256,171 @@ sync:
                 target_name: None,
                 sessions: HashMap::new(),
                 defaults: None,
+                includes: Vec::new(),
+                hooks: Hooks::default(),
+                commands: BTreeMap::new(),
             },
             specs: vec![spec],
             folded: false,
+            favorite: false,
         };
         assert!(!project.is_active());
     }
 
+    // ============ sort_specs / sort_projects tests ============
+
+    fn make_sorted_spec(name: &str, status: &str, conflicts: usize) -> SyncSpec {
+        let mut session = make_test_session(name, "/local", "/remote");
+        session.status = status.to_string();
+        session.conflicts = (0..conflicts)
+            .map(|i| crate::mutagen::Conflict {
+                root: format!("file{}", i),
+                alpha_changes: vec![],
+                beta_changes: vec![],
+            })
+            .collect();
+        SyncSpec {
+            name: name.to_string(),
+            state: SyncSpecState::RunningTwoWay,
+            running_session: Some(Arc::new(session)),
+        }
+    }
+
+    fn spec_names(specs: &[SyncSpec]) -> Vec<&str> {
+        specs.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_specs_by_status_breaks_ties_by_name() {
+        let mut specs = vec![
+            make_sorted_spec("web", "Watching for changes", 0),
+            make_sorted_spec("db", "Staging files on beta", 0),
+            make_sorted_spec("api", "Watching for changes", 0),
+            make_sorted_spec("assets", "Watching for changes", 2),
+            SyncSpec {
+                name: "docs".to_string(),
+                state: SyncSpecState::NotRunning,
+                running_session: None,
+            },
+        ];
+
+        sort_specs(&mut specs, SortMode::Status);
+        assert_eq!(spec_names(&specs), vec!["assets", "db", "api", "web", "docs"]);
+    }
+
+    #[test]
+    fn test_sort_specs_by_last_sync() {
+        let mut synced = make_sorted_spec("zebra", "Watching for changes", 0);
+        Arc::make_mut(synced.running_session.as_mut().unwrap()).sync_time =
+            SyncTime::At(chrono::Local::now());
+        let mut specs = vec![make_sorted_spec("alpha", "Watching for changes", 0), synced];
+
+        sort_specs(&mut specs, SortMode::LastSync);
+        assert_eq!(spec_names(&specs), vec!["zebra", "alpha"]);
+    }
+
+    #[test]
+    fn test_sort_specs_rows_do_not_move_when_state_unchanged() {
+        // Sessions can arrive in any order; ties must resolve the same way every refresh
+        let specs = vec![
+            make_sorted_spec("c", "Watching for changes", 0),
+            make_sorted_spec("a", "Watching for changes", 0),
+            make_sorted_spec("d", "Scanning files", 0),
+            make_sorted_spec("b", "Scanning files", 0),
+        ];
+
+        for mode in [SortMode::Name, SortMode::Status, SortMode::LastSync] {
+            let mut first = specs.clone();
+            sort_specs(&mut first, mode);
+
+            let mut reversed: Vec<_> = specs.iter().rev().cloned().collect();
+            sort_specs(&mut reversed, mode);
+            assert_eq!(spec_names(&first), spec_names(&reversed), "{:?}", mode);
+
+            let mut again = first.clone();
+            sort_specs(&mut again, mode);
+            assert_eq!(spec_names(&first), spec_names(&again), "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn test_sort_projects_same_name_by_path() {
+        let project = |path: &str| Project {
+            file: ProjectFile {
+                path: PathBuf::from(path),
+                target_name: None,
+                sessions: HashMap::new(),
+                defaults: None,
+                includes: Vec::new(),
+                hooks: Hooks::default(),
+                commands: BTreeMap::new(),
+            },
+            specs: vec![],
+            folded: false,
+            favorite: false,
+        };
+        let mut projects = vec![
+            project("/work/web/mutagen.yml"),
+            project("/work/api/mutagen.yml"),
+            project("/work/api/mutagen-prod.yml"),
+        ];
+
+        assert!(sort_projects(&mut projects));
+        assert!(!sort_projects(&mut projects));
+        let paths: Vec<_> = projects.iter().map(|p| p.file.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/work/api/mutagen.yml"),
+                PathBuf::from("/work/web/mutagen.yml"),
+                PathBuf::from("/work/api/mutagen-prod.yml"),
+            ]
+        );
+
+        // Favorites come first
+        projects[2].favorite = true;
+        sort_projects(&mut projects);
+        assert_eq!(
+            projects[0].file.path,
+            PathBuf::from("/work/api/mutagen-prod.yml")
+        );
+    }
+
+    #[test]
+    fn test_find_selection() {
+        let project = |path: &str, specs: &[&str]| Project {
+            file: ProjectFile {
+                path: PathBuf::from(path),
+                target_name: None,
+                sessions: HashMap::new(),
+                defaults: None,
+                includes: Vec::new(),
+                hooks: Hooks::default(),
+                commands: BTreeMap::new(),
+            },
+            specs: specs
+                .iter()
+                .map(|name| make_sorted_spec(name, "Watching for changes", 0))
+                .collect(),
+            folded: true,
+            favorite: false,
+        };
+        let projects = vec![
+            project("/work/api/mutagen.yml", &["server"]),
+            project("/work/web/mutagen.yml", &["assets", "server"]),
+            project("/work/web/mutagen-prod.yml", &["deploy"]),
+        ];
+
+        // By directory name, then by spec within same-named projects
+        assert_eq!(find_selection(&projects, "web"), Some((1, None)));
+        assert_eq!(find_selection(&projects, "web:server"), Some((1, Some(1))));
+        assert_eq!(find_selection(&projects, "mutagen:assets"), Some((1, Some(0))));
+        // By display name
+        assert_eq!(find_selection(&projects, "mutagen-prod"), Some((2, None)));
+        // Any project
+        assert_eq!(find_selection(&projects, ":deploy"), Some((2, Some(0))));
+
+        assert_eq!(find_selection(&projects, "web:missing"), None);
+        assert_eq!(find_selection(&projects, "nope"), None);
+    }
 }
@@ -0,0 +1,288 @@
+//! History of resolved conflicts.
+//!
+//! Mutagen forgets a conflict as soon as it goes away, which makes it hard to
+//! work out afterwards how a change was lost. Every conflict that disappears
+//! is appended to `resolutions.log` in the state directory, one JSON object
+//! per line like the audit log, along with which side won if mutagui knows.
+//! A push session resolves in alpha's favor; a conflict that vanishes between
+//! refreshes was fixed outside mutagui, so the winning side is unknown.
+
+use anyhow::{Context, Result};
+use chrono::{Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::mutagen::SyncSession;
+
+/// Maximum number of entries shown in the history popup.
+pub const RESOLUTION_VIEW_LIMIT: usize = 500;
+
+/// Which endpoint's version of a conflicting path was kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Winner {
+    Alpha,
+    /// Resolved outside mutagui (by editing or deleting files directly).
+    Unknown,
+}
+
+impl Winner {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Alpha => "alpha",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// One resolved conflict.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolutionEntry {
+    /// RFC 3339 local time.
+    pub timestamp: String,
+    pub user: String,
+    /// Spec (session name) the conflict was in.
+    pub spec: String,
+    /// Conflict root, relative to the sync roots.
+    pub path: String,
+    pub winner: Winner,
+    /// How it was resolved: "push", or "external" if it disappeared on its own.
+    pub via: String,
+}
+
+/// Append-only log file of resolved conflicts.
+#[derive(Debug, Clone)]
+pub struct ResolutionLog {
+    path: Option<PathBuf>,
+}
+
+impl ResolutionLog {
+    /// Log to `resolutions.log` next to the audit log.
+    pub fn new() -> Self {
+        let path = dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("mutagui").join("resolutions.log"));
+        Self { path }
+    }
+
+    /// Log to a specific file.
+    #[cfg(test)]
+    pub fn at(path: &std::path::Path) -> Self {
+        Self {
+            path: Some(path.to_path_buf()),
+        }
+    }
+
+    /// Append an entry for each conflict root in `spec`.
+    pub fn record(&self, spec: &str, roots: &[String], winner: Winner, via: &str) -> Result<()> {
+        if roots.is_empty() {
+            return Ok(());
+        }
+        let path = self
+            .path
+            .as_ref()
+            .context("No state directory for the resolution log")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let timestamp = Local::now().to_rfc3339_opts(SecondsFormat::Secs, false);
+        let user = crate::audit::current_user();
+        for root in roots {
+            let entry = ResolutionEntry {
+                timestamp: timestamp.clone(),
+                user: user.clone(),
+                spec: spec.to_string(),
+                path: root.clone(),
+                winner,
+                via: via.to_string(),
+            };
+            writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        }
+        Ok(())
+    }
+
+    /// The most recent `limit` entries for `spec`, newest first. Unreadable
+    /// lines are skipped.
+    pub fn for_spec(&self, spec: &str, limit: usize) -> Result<Vec<ResolutionEntry>> {
+        let Some(path) = &self.path else {
+            return Ok(Vec::new());
+        };
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        Ok(contents
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str::<ResolutionEntry>(line).ok())
+            .filter(|entry| entry.spec == spec)
+            .take(limit)
+            .collect())
+    }
+}
+
+impl Default for ResolutionLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Entries as aligned text for the history popup.
+pub fn format_history(entries: &[ResolutionEntry]) -> String {
+    if entries.is_empty() {
+        return "No resolved conflicts recorded for this spec".to_string();
+    }
+    entries
+        .iter()
+        .map(|entry| {
+            // "2026-10-16T12:34:56+02:00" → "2026-10-16 12:34:56"
+            let time = entry
+                .timestamp
+                .get(..19)
+                .unwrap_or(&entry.timestamp)
+                .replacen('T', " ", 1);
+            format!(
+                "{}  {:<10} {:<7} {:<8} {}",
+                time,
+                entry.user,
+                entry.winner.label(),
+                entry.via,
+                entry.path
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Entries as CSV, oldest first, for export.
+pub fn to_csv(entries: &[ResolutionEntry]) -> String {
+    let field = |value: &str| {
+        if value.contains([',', '"', '\n']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    };
+    let mut csv = String::from("timestamp,user,spec,path,winner,via\n");
+    for entry in entries.iter().rev() {
+        let row = [
+            entry.timestamp.as_str(),
+            entry.user.as_str(),
+            entry.spec.as_str(),
+            entry.path.as_str(),
+            entry.winner.label(),
+            entry.via.as_str(),
+        ];
+        let row: Vec<String> = row.iter().map(|value| field(value)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Remembers each session's conflict roots between refreshes to notice the
+/// ones that go away.
+#[derive(Debug, Default)]
+pub struct ConflictTracker {
+    roots: HashMap<String, HashSet<String>>,
+}
+
+impl ConflictTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a refresh and return the conflict roots that disappeared from
+    /// sessions that are still running, as (session name, roots) pairs.
+    /// Sessions that went away entirely are forgotten without reporting.
+    pub fn observe(&mut self, sessions: &[SyncSession]) -> Vec<(String, Vec<String>)> {
+        let mut resolved = Vec::new();
+        let mut next = HashMap::new();
+        for session in sessions {
+            let current: HashSet<String> = session.conflict_roots().into_iter().collect();
+            if let Some(previous) = self.roots.get(&session.identifier) {
+                let mut gone: Vec<String> = previous.difference(&current).cloned().collect();
+                if !gone.is_empty() {
+                    gone.sort();
+                    resolved.push((session.name.clone(), gone));
+                }
+            }
+            next.insert(session.identifier.clone(), current);
+        }
+        self.roots = next;
+        resolved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_with_conflicts(roots: &[&str]) -> SyncSession {
+        SyncSession::named("web").with_conflicts(roots)
+    }
+
+    #[test]
+    fn test_tracker_reports_disappeared_roots() {
+        let mut tracker = ConflictTracker::new();
+        assert!(tracker
+            .observe(&[session_with_conflicts(&["a.txt", "b.txt"])])
+            .is_empty());
+
+        let resolved = tracker.observe(&[session_with_conflicts(&["b.txt"])]);
+        assert_eq!(
+            resolved,
+            vec![("web".to_string(), vec!["a.txt".to_string()])]
+        );
+
+        // A terminated session isn't a resolution
+        assert!(tracker.observe(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_record_and_filter_by_spec() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = ResolutionLog::at(&dir.path().join("state").join("resolutions.log"));
+
+        log.record("web", &["a.txt".to_string()], Winner::Alpha, "push")
+            .unwrap();
+        log.record("api", &["b.txt".to_string()], Winner::Unknown, "external")
+            .unwrap();
+        log.record(
+            "web",
+            &["c, d.txt".to_string()],
+            Winner::Unknown,
+            "external",
+        )
+        .unwrap();
+
+        let entries = log.for_spec("web", 10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "c, d.txt");
+        assert_eq!(entries[1].winner, Winner::Alpha);
+
+        let csv = to_csv(&entries);
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines[0], "timestamp,user,spec,path,winner,via");
+        assert!(lines[1].ends_with(",web,a.txt,alpha,push"));
+        assert!(lines[2].ends_with(",web,\"c, d.txt\",unknown,external"));
+    }
+
+    #[test]
+    fn test_missing_log_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = ResolutionLog::at(&dir.path().join("resolutions.log"));
+        assert!(log.for_spec("web", 10).unwrap().is_empty());
+        assert!(format_history(&[]).starts_with("No resolved conflicts"));
+    }
+}
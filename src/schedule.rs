@@ -0,0 +1,182 @@
+//! Slower refresh cadence for data that is expensive to fetch.
+//!
+//! The session list comes from one local `mutagen sync list` and refreshes
+//! every few seconds. Other data needs a command on every endpoint, often
+//! over ssh, so each such source has its own, longer interval. A reading
+//! that is more than two intervals old (because the endpoint stopped
+//! answering, say) is shown as stale rather than silently dropped.
+//!
+//! The session list itself refreshes faster while something is happening
+//! and slower while nothing is; `Activity` says which.
+
+use crate::mutagen::SyncSession;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Data fetched on its own interval rather than with every refresh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataSource {
+    /// Free space on each endpoint's filesystem.
+    DiskSpace,
+}
+
+/// What the sessions are doing, which decides how soon to refresh again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activity {
+    /// A session is scanning, staging, or otherwise moving files, or an
+    /// action was just run.
+    Busy,
+    /// Nothing is moving, but not everything is watching either: a session
+    /// is connecting, waiting, or halted.
+    Waiting,
+    /// Every session is watching for changes or paused.
+    Idle,
+}
+
+impl Activity {
+    /// Classify `sessions`.
+    pub fn of(sessions: &[SyncSession]) -> Self {
+        let mut activity = Self::Idle;
+        for session in sessions.iter().filter(|s| !s.paused) {
+            match session.status_text() {
+                "Watching" => {}
+                "Scanning" | "Staging" | "Reconciling" | "Saving" | "Transitioning" => {
+                    return Self::Busy
+                }
+                _ => activity = Self::Waiting,
+            }
+        }
+        activity
+    }
+}
+
+/// A value and when it was fetched.
+#[derive(Debug, Clone)]
+pub struct Reading<T> {
+    pub value: T,
+    pub at: Instant,
+}
+
+impl<T> Reading<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            at: Instant::now(),
+        }
+    }
+}
+
+/// Tracks when each data source last ran and when it's due again.
+#[derive(Debug, Default)]
+pub struct RefreshScheduler {
+    intervals: HashMap<DataSource, Duration>,
+    last_run: HashMap<DataSource, Instant>,
+}
+
+impl RefreshScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refresh `source` every `secs` seconds; 0 disables it.
+    pub fn with_interval(mut self, source: DataSource, secs: u64) -> Self {
+        if secs > 0 {
+            self.intervals.insert(source, Duration::from_secs(secs));
+        }
+        self
+    }
+
+    /// Sources that have never run or whose interval has elapsed.
+    pub fn due(&self) -> Vec<DataSource> {
+        self.due_at(Instant::now())
+    }
+
+    fn due_at(&self, now: Instant) -> Vec<DataSource> {
+        self.intervals
+            .iter()
+            .filter(|(source, interval)| match self.last_run.get(source) {
+                Some(last) => now.duration_since(*last) >= **interval,
+                None => true,
+            })
+            .map(|(source, _)| *source)
+            .collect()
+    }
+
+    /// Note that `source` was just refreshed, whether or not it succeeded, so
+    /// a failing endpoint isn't retried on every tick.
+    pub fn mark_run(&mut self, source: DataSource) {
+        self.last_run.insert(source, Instant::now());
+    }
+
+    /// Whether a reading of `source` taken at `at` has missed a refresh.
+    pub fn is_stale(&self, source: DataSource, at: Instant) -> bool {
+        self.is_stale_at(source, at, Instant::now())
+    }
+
+    fn is_stale_at(&self, source: DataSource, at: Instant, now: Instant) -> bool {
+        self.intervals
+            .get(&source)
+            .is_some_and(|interval| now.duration_since(at) > *interval * 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(status: &str, paused: bool) -> SyncSession {
+        SyncSession::named("web")
+            .with_status(status)
+            .with_paused(paused)
+    }
+
+    #[test]
+    fn test_activity() {
+        let watching = session("Watching for changes", false);
+        assert_eq!(Activity::of(&[]), Activity::Idle);
+        assert_eq!(
+            Activity::of(&[watching.clone(), session("Connecting to beta", true)]),
+            Activity::Idle
+        );
+        assert_eq!(
+            Activity::of(&[watching.clone(), session("Connecting to beta", false)]),
+            Activity::Waiting
+        );
+        assert_eq!(
+            Activity::of(&[
+                session("Halted on root emptied", false),
+                session("Staging files on beta", false)
+            ]),
+            Activity::Busy
+        );
+    }
+
+    #[test]
+    fn test_due_after_interval() {
+        let mut scheduler = RefreshScheduler::new().with_interval(DataSource::DiskSpace, 60);
+        let start = Instant::now();
+        assert_eq!(scheduler.due_at(start), vec![DataSource::DiskSpace]);
+
+        scheduler.last_run.insert(DataSource::DiskSpace, start);
+        assert!(scheduler.due_at(start + Duration::from_secs(30)).is_empty());
+        assert_eq!(
+            scheduler.due_at(start + Duration::from_secs(60)),
+            vec![DataSource::DiskSpace]
+        );
+    }
+
+    #[test]
+    fn test_zero_interval_disables() {
+        let scheduler = RefreshScheduler::new().with_interval(DataSource::DiskSpace, 0);
+        assert!(scheduler.due().is_empty());
+        assert!(!scheduler.is_stale(DataSource::DiskSpace, Instant::now()));
+    }
+
+    #[test]
+    fn test_stale_after_two_intervals() {
+        let scheduler = RefreshScheduler::new().with_interval(DataSource::DiskSpace, 60);
+        let at = Instant::now();
+        assert!(!scheduler.is_stale_at(DataSource::DiskSpace, at, at + Duration::from_secs(90)));
+        assert!(scheduler.is_stale_at(DataSource::DiskSpace, at, at + Duration::from_secs(121)));
+    }
+}
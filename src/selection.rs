@@ -3,13 +3,21 @@
 //! This module encapsulates all selection logic, including navigation
 //! in a unified panel that shows projects with their sync specs.
 
-use crate::project::Project;
+use crate::config::SortMode;
+use crate::endpoint::EndpointAddress;
+use crate::hosts::host_key;
+use crate::project::{compare_specs, Project, SyncSpec};
+use crate::ssh_config::SshConfig;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 /// Item that can be selected in the unified panel
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SelectableItem {
     /// A project header (can be folded/unfolded)
     Project { index: usize },
+    /// A beta host header, when grouping by host (index into `hosts()`)
+    Host { index: usize },
     /// A sync spec within a project
     Spec {
         project_index: usize,
@@ -17,16 +25,89 @@ pub enum SelectableItem {
     },
 }
 
+/// How the list arranges specs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Grouping {
+    /// Under the project file that defines them.
+    #[default]
+    Projects,
+    /// Every spec in one list, sorted together, like `mutagen sync list`.
+    Flat,
+    /// Under the host their beta endpoint is on.
+    Hosts,
+}
+
+impl Grouping {
+    /// The grouping after this one, for cycling with a key.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Projects => Self::Flat,
+            Self::Flat => Self::Hosts,
+            Self::Hosts => Self::Projects,
+        }
+    }
+
+    /// Name for the status bar.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Projects => "by project",
+            Self::Flat => "all sessions",
+            Self::Hosts => "by host",
+        }
+    }
+}
+
+/// The host a spec syncs to: where ssh connects for its beta endpoint, as
+/// the hosts view groups it, `docker://` and its container, or "local" for
+/// a local path.
+fn beta_host(project: &Project, spec: &SyncSpec, ssh_config: &SshConfig) -> String {
+    let Some(definition) = project.file.sessions.get(&spec.name) else {
+        return "local".to_string();
+    };
+    let beta = EndpointAddress::parse(&definition.beta);
+    if let Some(target) = host_key(&beta, ssh_config) {
+        return target;
+    }
+    match beta {
+        EndpointAddress::Docker { container, .. } => format!("docker://{}", container),
+        _ => "local".to_string(),
+    }
+}
+
+/// What a row shows: its project file, and its spec's name if it's a spec,
+/// or just its host if it's a host header. Unlike its index, this stays the
+/// same when rows are re-sorted or others come and go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RowKey {
+    project: PathBuf,
+    spec: Option<String>,
+    host: Option<String>,
+}
+
 /// Manages selection state in the unified project/spec tree.
 ///
 /// The selection model maintains a flattened list of selectable items
 /// (projects and their specs) that gets rebuilt when fold states change.
+/// The selection follows its row across rebuilds.
 #[derive(Debug, Clone)]
 pub struct SelectionManager {
     /// Flattened list of selectable items (projects and their specs)
     items: Vec<SelectableItem>,
+    /// What each item shows, for finding the selected row again after a rebuild
+    keys: Vec<RowKey>,
+    /// The hosts that `SelectableItem::Host` rows stand for
+    hosts: Vec<String>,
     /// Currently selected index into items
     selected_index: usize,
+    /// Whether to leave out projects that aren't favorites
+    pub favorites_only: bool,
+    /// How the rows are arranged
+    pub grouping: Grouping,
+    /// The order of the specs in a flat list, which `App` keeps in step
+    /// with its own
+    pub sort_mode: SortMode,
+    /// For grouping ssh aliases of one machine under one host
+    ssh_config: SshConfig,
 }
 
 impl Default for SelectionManager {
@@ -40,26 +121,111 @@ impl SelectionManager {
     pub fn new() -> Self {
         Self {
             items: Vec::new(),
+            keys: Vec::new(),
+            hosts: Vec::new(),
             selected_index: 0,
+            favorites_only: false,
+            grouping: Grouping::default(),
+            sort_mode: SortMode::default(),
+            ssh_config: SshConfig::default(),
         }
     }
 
-    /// Rebuild items list from projects
-    pub fn rebuild_from_projects(&mut self, projects: &[Project]) {
-        self.items.clear();
+    /// Sort the specs of a flat list by `sort_mode`.
+    pub fn sorted_by(mut self, sort_mode: SortMode) -> Self {
+        self.sort_mode = sort_mode;
+        self
+    }
 
-        for (proj_idx, project) in projects.iter().enumerate() {
-            // Add project header
-            self.items.push(SelectableItem::Project { index: proj_idx });
+    /// Resolve ssh hosts with `ssh_config` when grouping by host.
+    pub fn with_ssh_config(mut self, ssh_config: SshConfig) -> Self {
+        self.ssh_config = ssh_config;
+        self
+    }
 
-            // Add specs if unfolded
-            if !project.folded {
-                for spec_idx in 0..project.specs.len() {
-                    self.items.push(SelectableItem::Spec {
-                        project_index: proj_idx,
-                        spec_index: spec_idx,
+    /// Rebuild items list from projects, keeping the selection on the same
+    /// spec, or on its project if the spec is gone or folded away.
+    pub fn rebuild_from_projects(&mut self, projects: &[Project]) {
+        let selected = self.keys.get(self.selected_index).cloned();
+        self.items.clear();
+        self.keys.clear();
+        self.hosts.clear();
+
+        let (favorites_only, sort_mode) = (self.favorites_only, self.sort_mode);
+        let shown = move |project: &&Project| !favorites_only || project.favorite;
+        let spec = |(proj_idx, spec_idx): (usize, usize)| &projects[proj_idx].specs[spec_idx];
+        let all_specs = || {
+            projects
+                .iter()
+                .enumerate()
+                .filter(|(_, project)| shown(project))
+                .flat_map(|(proj_idx, project)| {
+                    (0..project.specs.len()).map(move |spec_idx| (proj_idx, spec_idx))
+                })
+        };
+        let rows: Vec<(usize, Option<usize>)> = match self.grouping {
+            Grouping::Projects => projects
+                .iter()
+                .enumerate()
+                .filter(|(_, project)| shown(project))
+                .flat_map(|(proj_idx, project)| {
+                    // Specs follow their project's header unless it's folded
+                    let specs = if project.folded {
+                        0
+                    } else {
+                        project.specs.len()
+                    };
+                    std::iter::once((proj_idx, None))
+                        .chain((0..specs).map(move |spec_idx| (proj_idx, Some(spec_idx))))
+                })
+                .collect(),
+            Grouping::Flat => {
+                let mut rows: Vec<_> = all_specs().collect();
+                rows.sort_by(|&a, &b| compare_specs(spec(a), spec(b), sort_mode));
+                rows.into_iter()
+                    .map(|(proj_idx, spec_idx)| (proj_idx, Some(spec_idx)))
+                    .collect()
+            }
+            Grouping::Hosts => {
+                let mut by_host: BTreeMap<String, Vec<(usize, usize)>> = BTreeMap::new();
+                for row in all_specs() {
+                    by_host
+                        .entry(beta_host(&projects[row.0], spec(row), &self.ssh_config))
+                        .or_default()
+                        .push(row);
+                }
+                for (host, mut specs) in by_host {
+                    specs.sort_by(|&a, &b| compare_specs(spec(a), spec(b), sort_mode));
+                    self.items.push(SelectableItem::Host {
+                        index: self.hosts.len(),
+                    });
+                    self.keys.push(RowKey {
+                        project: PathBuf::new(),
+                        spec: None,
+                        host: Some(host.clone()),
                     });
+                    self.hosts.push(host);
+                    for (proj_idx, spec_idx) in specs {
+                        self.push_row(projects, proj_idx, Some(spec_idx));
+                    }
                 }
+                Vec::new()
+            }
+        };
+        for (proj_idx, spec_idx) in rows {
+            self.push_row(projects, proj_idx, spec_idx);
+        }
+
+        if let Some(selected) = selected {
+            let project_row = RowKey {
+                spec: None,
+                ..selected.clone()
+            };
+            let found = self.keys.iter().position(|key| *key == selected);
+            if let Some(index) =
+                found.or_else(|| self.keys.iter().position(|key| *key == project_row))
+            {
+                self.selected_index = index;
             }
         }
 
@@ -71,6 +237,22 @@ impl SelectionManager {
         }
     }
 
+    fn push_row(&mut self, projects: &[Project], proj_idx: usize, spec_idx: Option<usize>) {
+        let project = &projects[proj_idx];
+        self.items.push(match spec_idx {
+            Some(spec_index) => SelectableItem::Spec {
+                project_index: proj_idx,
+                spec_index,
+            },
+            None => SelectableItem::Project { index: proj_idx },
+        });
+        self.keys.push(RowKey {
+            project: project.file.path.clone(),
+            spec: spec_idx.map(|spec_idx| project.specs[spec_idx].name.clone()),
+            host: None,
+        });
+    }
+
     /// Get the total number of items.
     pub fn total_items(&self) -> usize {
         self.items.len()
@@ -91,12 +273,44 @@ impl SelectionManager {
         self.items.get(self.selected_index)
     }
 
+    /// The hosts of the host headers, when grouping by host.
+    pub fn hosts(&self) -> &[String] {
+        &self.hosts
+    }
+
     /// Get selected project index (either directly or parent of selected spec)
     pub fn selected_project_index(&self) -> Option<usize> {
         match self.selected_item()? {
             SelectableItem::Project { index } => Some(*index),
             SelectableItem::Spec { project_index, .. } => Some(*project_index),
+            SelectableItem::Host { .. } => None,
+        }
+    }
+
+    /// The selected host header's host, if one is selected.
+    pub fn selected_host(&self) -> Option<&str> {
+        match self.selected_item()? {
+            SelectableItem::Host { index } => self.hosts.get(*index).map(String::as_str),
+            _ => None,
+        }
+    }
+
+    /// The specs listed under the selected host header, as
+    /// (project_index, spec_index).
+    pub fn selected_host_specs(&self) -> Vec<(usize, usize)> {
+        if self.selected_host().is_none() {
+            return Vec::new();
         }
+        self.items[self.selected_index + 1..]
+            .iter()
+            .map_while(|item| match item {
+                SelectableItem::Spec {
+                    project_index,
+                    spec_index,
+                } => Some((*project_index, *spec_index)),
+                _ => None,
+            })
+            .collect()
     }
 
     /// Get selected spec if any (returns (project_index, spec_index))
@@ -140,7 +354,25 @@ impl SelectionManager {
         }
     }
 
-    /// Set selection directly by raw index.
+    /// Move the selection `delta` items down (up if negative), stopping at
+    /// either end rather than wrapping around.
+    pub fn move_by(&mut self, delta: isize) {
+        let last = self.total_items().saturating_sub(1);
+        self.selected_index = self.selected_index.saturating_add_signed(delta).min(last);
+    }
+
+    /// Select the given item if it is visible. Returns false if it isn't.
+    pub fn select_item(&mut self, item: &SelectableItem) -> bool {
+        match self.items.iter().position(|i| i == item) {
+            Some(index) => {
+                self.selected_index = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+        /// Set selection directly by raw index.
     #[cfg(test)]
     pub fn set_index(&mut self, index: usize) {
         let total = self.total_items();
@@ -153,8 +385,8 @@ impl SelectionManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::project::{Project, ProjectFile, SessionDefinition, SyncSpec, SyncSpecState};
-    use std::collections::HashMap;
+    use crate::project::{Hooks, Project, ProjectFile, SessionDefinition, SyncSpec, SyncSpecState};
+    use std::collections::{BTreeMap, HashMap};
     use std::path::PathBuf;
 
     fn make_test_project(name: &str, spec_count: usize, folded: bool) -> Project {
@@ -170,6 +402,9 @@ mod tests {
                     beta: "server:/remote".to_string(),
                     mode: None,
                     ignore: None,
+                    permissions: None,
+                    symlink: None,
+                    watch: None,
                 },
             );
             specs.push(SyncSpec {
@@ -185,9 +420,13 @@ mod tests {
                 target_name: None,
                 sessions,
                 defaults: None,
+                includes: Vec::new(),
+                hooks: Hooks::default(),
+                commands: BTreeMap::new(),
             },
             specs,
             folded,
+            favorite: false,
         }
     }
 
@@ -346,13 +585,124 @@ mod tests {
         sel.rebuild_from_projects(&projects);
         sel.set_index(5); // Valid in 6-item list
 
-        // Rebuild with fewer specs
-        let projects = vec![make_test_project("p1", 2, false)];
+        // Rebuild with a shorter, unrelated project: nothing to follow
+        let projects = vec![make_test_project("p2", 2, false)];
         sel.rebuild_from_projects(&projects);
 
         assert_eq!(sel.raw_index(), 2); // Clamped to max
     }
 
+    #[test]
+    fn test_rebuild_follows_selected_row() {
+        let mut sel = SelectionManager::new();
+        let mut projects = vec![
+            make_test_project("p1", 2, false),
+            make_test_project("p2", 3, false),
+        ];
+        sel.rebuild_from_projects(&projects);
+        sel.set_index(5); // p2's spec-1
+
+        // Re-sorted, with a spec of p1 gone
+        projects.swap(0, 1);
+        projects[0].specs.reverse();
+        projects[1].specs.pop();
+        sel.rebuild_from_projects(&projects);
+        assert_eq!(sel.selected_spec(), Some((0, 1)));
+
+        // The selected spec goes away: its project stays selected
+        projects[0].specs.remove(1);
+        sel.rebuild_from_projects(&projects);
+        assert_eq!(
+            sel.selected_item(),
+            Some(&SelectableItem::Project { index: 0 })
+        );
+
+        sel.set_index(4); // p1's spec-0
+        assert_eq!(sel.selected_spec(), Some((1, 0)));
+        projects[1].folded = true;
+        sel.rebuild_from_projects(&projects);
+        assert_eq!(
+            sel.selected_item(),
+            Some(&SelectableItem::Project { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_flat_grouping() {
+        let mut sel = SelectionManager::new();
+        let mut projects = vec![
+            make_test_project("p1", 2, true),
+            make_test_project("p2", 2, false),
+        ];
+        projects[1].specs[0].name = "alpha".to_string();
+        sel.rebuild_from_projects(&projects);
+        sel.set_index(3); // p2's spec-1
+
+        // Every spec, folded or not, sorted together and without headers
+        sel.grouping = Grouping::Flat;
+        sel.rebuild_from_projects(&projects);
+        let rows: Vec<_> = sel.items().cloned().collect();
+        let spec = |project_index, spec_index| SelectableItem::Spec {
+            project_index,
+            spec_index,
+        };
+        assert_eq!(rows, [spec(1, 0), spec(0, 0), spec(0, 1), spec(1, 1)]);
+        assert_eq!(sel.selected_spec(), Some((1, 1)));
+
+        sel.grouping = Grouping::Projects;
+        sel.rebuild_from_projects(&projects);
+        assert_eq!(sel.raw_index(), 3);
+    }
+
+    #[test]
+    fn test_host_grouping() {
+        let mut sel = SelectionManager::new()
+            .with_ssh_config(SshConfig::parse("Host backup\n    HostName server\n"));
+        let mut projects = vec![
+            make_test_project("p1", 2, true),
+            make_test_project("p2", 1, false),
+        ];
+        let beta = |project: &mut Project, spec: &str, beta: &str| {
+            project.file.sessions.get_mut(spec).unwrap().beta = beta.to_string();
+        };
+        beta(&mut projects[0], "spec-1", "docker://web/app");
+        beta(&mut projects[1], "spec-0", "/backup");
+
+        // A header per host, folded projects included, hosts in order
+        sel.grouping = Grouping::Hosts;
+        sel.rebuild_from_projects(&projects);
+        assert_eq!(sel.hosts(), ["docker://web", "local", "server"]);
+        let rows: Vec<_> = sel.items().cloned().collect();
+        let host = |index| SelectableItem::Host { index };
+        let spec = |project_index, spec_index| SelectableItem::Spec {
+            project_index,
+            spec_index,
+        };
+        assert_eq!(
+            rows,
+            [
+                host(0),
+                spec(0, 1),
+                host(1),
+                spec(1, 0),
+                host(2),
+                spec(0, 0)
+            ]
+        );
+
+        sel.set_index(4);
+        assert_eq!(sel.selected_host(), Some("server"));
+        assert_eq!(sel.selected_host_specs(), [(0, 0)]);
+        assert_eq!(sel.selected_project_index(), None);
+
+        // The selection follows its host header when specs come and go
+        // An ssh config alias for the host lands under the same header
+        beta(&mut projects[1], "spec-0", "backup:/backup");
+        sel.rebuild_from_projects(&projects);
+        assert_eq!(sel.selected_host(), Some("server"));
+        assert_eq!(sel.selected_host_specs(), [(0, 0), (1, 0)]);
+    }
+
     #[test]
     fn test_empty_list_navigation() {
         let mut sel = SelectionManager::new();
@@ -381,4 +731,21 @@ mod tests {
         assert_eq!(sel.raw_index(), 2);
         assert_eq!(sel.selected_spec(), Some((0, 1)));
     }
+
+    #[test]
+    fn test_select_item() {
+        let mut sel = SelectionManager::new();
+        let projects = vec![make_test_project("p1", 3, false)];
+        sel.rebuild_from_projects(&projects);
+
+        assert!(sel.select_item(&SelectableItem::Spec {
+            project_index: 0,
+            spec_index: 2
+        }));
+        assert_eq!(sel.selected_spec(), Some((0, 2)));
+
+        // Not visible: selection is unchanged
+        assert!(!sel.select_item(&SelectableItem::Project { index: 1 }));
+        assert_eq!(sel.selected_spec(), Some((0, 2)));
+    }
 }
@@ -0,0 +1,767 @@
+//! Adding, changing, and removing the specs in a project file.
+//!
+//! serde_yaml can't write a file back with its comments, so the edits are
+//! made to the text: a spec is found by its key under `sync:` and only the
+//! lines that change are rewritten. Each edit is parsed back before it is
+//! saved, and layouts the editor doesn't follow, like a spec written inline
+//! as `{alpha: ..., beta: ...}`, are refused rather than guessed at.
+
+use anyhow::{Context, Result};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::project::{ProjectFile, SessionDefinition};
+
+/// A spec's definition, as entered in the session form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecFields {
+    pub name: String,
+    pub alpha: String,
+    pub beta: String,
+    pub mode: &'static str,
+    pub ignore: Vec<String>,
+}
+
+/// The spec's own ignore paths, without the defaults or the VCS directories
+/// that `vcs: true` stands for.
+pub fn ignore_paths(definition: &SessionDefinition) -> Vec<String> {
+    let paths = match &definition.ignore {
+        Some(serde_yaml::Value::Mapping(map)) => map.get("paths"),
+        other => other.as_ref(),
+    };
+    paths
+        .and_then(serde_yaml::Value::as_sequence)
+        .map(|seq| {
+            seq.iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether the spec ignores VCS directories with `vcs: true`.
+pub fn ignores_vcs(definition: &SessionDefinition) -> bool {
+    match &definition.ignore {
+        Some(serde_yaml::Value::Mapping(map)) => map.get("vcs") == Some(&true.into()),
+        _ => false,
+    }
+}
+
+/// Add `spec` to the project file at `path`.
+pub fn add_spec(path: &Path, spec: &SpecFields) -> Result<()> {
+    edit_file(path, |project, contents| {
+        if project.sessions.contains_key(&spec.name) {
+            anyhow::bail!("{} already has a spec named {}", path.display(), spec.name);
+        }
+        let updated = insert_spec(contents, spec)?;
+        Ok((updated, Some(spec)))
+    })
+}
+
+/// Change the spec `before` in the project file at `path` to `after`,
+/// rewriting only the fields that differ.
+pub fn update_spec(path: &Path, before: &SpecFields, after: &SpecFields) -> Result<()> {
+    edit_file(path, |project, contents| {
+        if after.name != before.name && project.sessions.contains_key(&after.name) {
+            anyhow::bail!("{} already has a spec named {}", path.display(), after.name);
+        }
+        let updated = replace_spec(contents, before, after)?;
+        Ok((updated, Some(after)))
+    })
+}
+
+/// Add a copy of the spec `source` to the project file at `path`, below it,
+/// as `copy`. The copy starts from the source's lines, so settings the form
+/// doesn't show are carried over, and then takes the fields that differ.
+pub fn duplicate_spec(path: &Path, source: &SpecFields, copy: &SpecFields) -> Result<()> {
+    edit_file(path, |project, contents| {
+        if project.sessions.contains_key(&copy.name) {
+            anyhow::bail!("{} already has a spec named {}", path.display(), copy.name);
+        }
+        let updated = copy_spec(contents, source, copy)?;
+        Ok((updated, Some(copy)))
+    })
+}
+
+/// Set the ignore paths of the spec `name` in the project file at `path`,
+/// and whether it ignores VCS directories.
+pub fn update_ignores(path: &Path, name: &str, vcs: bool, paths: &[String]) -> Result<()> {
+    let project = ProjectFile::from_path(path.to_path_buf())?;
+    let definition = project
+        .sessions
+        .get(name)
+        .with_context(|| format!("{} has no spec named {}", path.display(), name))?;
+    let before = SpecFields {
+        name: name.to_string(),
+        alpha: definition.alpha.clone(),
+        beta: definition.beta.clone(),
+        mode: "",
+        ignore: ignore_paths(definition),
+    };
+    let after = SpecFields {
+        ignore: paths.to_vec(),
+        ..before.clone()
+    };
+    let vcs_before = ignores_vcs(definition);
+    edit_file(path, |_, contents| {
+        let updated = replace_ignores(contents, &before, &after, vcs_before, vcs)?;
+        let parsed = ProjectFile::from_contents(path.to_path_buf(), &updated)?;
+        if parsed.sessions.get(name).map(ignores_vcs) != Some(vcs) {
+            anyhow::bail!("vcs wasn't set for {}", name);
+        }
+        Ok((updated, Some(&after)))
+    })
+}
+
+/// Remove the spec `name` from the project file at `path`.
+pub fn remove_spec(path: &Path, name: &str) -> Result<()> {
+    edit_file(path, |_, contents| {
+        let mut lines = to_lines(contents);
+        let spec = find_spec(&lines, name)?;
+        lines.drain(spec);
+        Ok((from_lines(lines), None))
+    })
+}
+
+/// Apply `edit` to the file at `path`, and save the result if it parses
+/// with the spec matching what `edit` says it wrote, or if `edit` says it
+/// removed a spec, with one spec fewer.
+fn edit_file<'a>(
+    path: &Path,
+    edit: impl FnOnce(&ProjectFile, &str) -> Result<(String, Option<&'a SpecFields>)>,
+) -> Result<()> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let project = ProjectFile::from_contents(path.to_path_buf(), &contents)?;
+    let (updated, expected) = edit(&project, &contents)
+        .with_context(|| format!("Can't edit the specs in {}", path.display()))?;
+
+    let parsed = ProjectFile::from_contents(path.to_path_buf(), &updated)
+        .context("The edit didn't leave valid YAML")?;
+    let as_expected = match expected {
+        Some(spec) => parsed.sessions.get(&spec.name).is_some_and(|definition| {
+            definition.alpha == spec.alpha
+                && definition.beta == spec.beta
+                && ignore_paths(definition) == spec.ignore
+        }),
+        None => parsed.sessions.len() + 1 == project.sessions.len(),
+    };
+    if !as_expected {
+        anyhow::bail!(
+            "The edit didn't read back as expected; edit {} by hand with e",
+            path.display()
+        );
+    }
+    std::fs::write(path, updated).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn to_lines(contents: &str) -> Vec<String> {
+    contents.lines().map(str::to_string).collect()
+}
+
+fn from_lines(lines: Vec<String>) -> String {
+    lines.join("\n") + "\n"
+}
+
+/// A line's indentation, or `None` for blank lines and comments.
+fn indent_of(line: &str) -> Option<usize> {
+    let content = line.trim_start();
+    if content.is_empty() || content.starts_with('#') {
+        None
+    } else {
+        Some(line.len() - content.len())
+    }
+}
+
+/// The key of a `key: value` line, unquoted.
+fn key_of(line: &str) -> Option<&str> {
+    let content = line.trim_start();
+    let end = content
+        .char_indices()
+        .find(|&(i, c)| {
+            c == ':'
+                && content[i + 1..]
+                    .chars()
+                    .next()
+                    .is_none_or(char::is_whitespace)
+        })
+        .map(|(i, _)| i)?;
+    let key = content[..end].trim_end();
+    Some(key.trim_matches(|c| c == '"' || c == '\''))
+}
+
+/// Whether the key on `line` has its value on the same line, rather than
+/// nested below it.
+fn has_inline_value(line: &str) -> bool {
+    let content = line.trim();
+    let after_key = content.find(": ").map_or("", |i| &content[i + 1..]);
+    let value = after_key.trim();
+    !value.is_empty() && !value.starts_with('#')
+}
+
+/// The ` # comment` ending `line`, if it has one outside quotes.
+fn trailing_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut after_space = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' && after_space => return &line[line[..i].trim_end().len()..],
+            None => {}
+        }
+        after_space = c.is_whitespace();
+    }
+    ""
+}
+
+/// The end of the entry starting at `start` with `indent`: just past its
+/// last line that is nested deeper.
+fn entry_end(lines: &[String], start: usize, indent: usize) -> usize {
+    let mut end = start + 1;
+    for (i, line) in lines.iter().enumerate().skip(start + 1) {
+        match indent_of(line) {
+            None => {}
+            Some(nested) if nested > indent => end = i + 1,
+            Some(_) => break,
+        }
+    }
+    end
+}
+
+/// A mapping entry's key and the lines it spans.
+type Entry = (String, Range<usize>);
+
+/// The entries nested under the key on the first line of `parent`: their
+/// indentation, and each key with its lines.
+fn children(lines: &[String], parent: &Range<usize>) -> Option<(usize, Vec<Entry>)> {
+    let indent = lines[parent.start + 1..parent.end]
+        .iter()
+        .find_map(|line| indent_of(line))?;
+    let entries = (parent.start + 1..parent.end)
+        .filter(|&i| indent_of(&lines[i]) == Some(indent))
+        .map(|i| {
+            let key = key_of(&lines[i]).unwrap_or_default().to_string();
+            (key, i..entry_end(lines, i, indent).min(parent.end))
+        })
+        .collect();
+    Some((indent, entries))
+}
+
+/// The `sync:` line, or an error if the section is written inline.
+fn sync_line(lines: &[String]) -> Result<Option<usize>> {
+    let Some(line) = lines
+        .iter()
+        .position(|line| indent_of(line) == Some(0) && key_of(line) == Some("sync"))
+    else {
+        return Ok(None);
+    };
+    if has_inline_value(&lines[line]) {
+        anyhow::bail!("the sync section is written inline");
+    }
+    Ok(Some(line))
+}
+
+/// The lines of the spec `name`.
+fn find_spec(lines: &[String], name: &str) -> Result<Range<usize>> {
+    let sync = sync_line(lines)?.context("there is no sync section")?;
+    let section = sync..entry_end(lines, sync, 0);
+    let (_, specs) = children(lines, &section).context("the sync section is empty")?;
+    let (_, spec) = specs
+        .into_iter()
+        .find(|(key, _)| key == name)
+        .with_context(|| format!("there is no spec named {}", name))?;
+    if has_inline_value(&lines[spec.start]) {
+        anyhow::bail!("the {} spec is written inline", name);
+    }
+    Ok(spec)
+}
+
+/// A value written as a JSON string, which YAML reads as a quoted scalar.
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// `ignore` as a `paths:` list, with `indent` as one level of indentation.
+fn paths_lines(ignore: &[String], pad: &str, indent: &str) -> Vec<String> {
+    let mut lines = vec![format!("{}paths:", pad)];
+    for pattern in ignore {
+        lines.push(format!("{}{}- {}", pad, indent, quote(pattern)));
+    }
+    lines
+}
+
+impl SpecFields {
+    /// The spec as YAML lines, with `indent` as one level of indentation.
+    fn yaml_lines(&self, indent: &str) -> Vec<String> {
+        let mut lines = vec![
+            format!("{}{}:", indent, self.name),
+            format!("{}{}alpha: {}", indent, indent, quote(&self.alpha)),
+            format!("{}{}beta: {}", indent, indent, quote(&self.beta)),
+            format!("{}{}mode: {}", indent, indent, quote(self.mode)),
+        ];
+        if !self.ignore.is_empty() {
+            lines.push(format!("{}{}ignore:", indent, indent));
+            lines.extend(paths_lines(&self.ignore, &indent.repeat(3), indent));
+        }
+        lines
+    }
+}
+
+/// `contents` with the spec added at the top of the `sync:` section, or in a
+/// new `sync:` section at the end if there is none.
+fn insert_spec(contents: &str, spec: &SpecFields) -> Result<String> {
+    let lines = to_lines(contents);
+    let Some(sync_line) = sync_line(&lines)? else {
+        let mut updated = contents.to_string();
+        if !updated.is_empty() && !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated.push_str("sync:\n");
+        for line in spec.yaml_lines("  ") {
+            updated.push_str(&line);
+            updated.push('\n');
+        }
+        return Ok(updated);
+    };
+
+    // Match the indentation of the existing specs
+    let indent = lines[sync_line + 1..]
+        .iter()
+        .find_map(|line| indent_of(line))
+        .filter(|indent| *indent > 0)
+        .unwrap_or(2);
+
+    let mut updated = lines;
+    updated.splice(
+        sync_line + 1..sync_line + 1,
+        spec.yaml_lines(&" ".repeat(indent)),
+    );
+    Ok(from_lines(updated))
+}
+
+/// `contents` with the spec `before` changed to `after`. Fields that didn't
+/// change are left as written, and changed ones keep their comments.
+fn replace_spec(contents: &str, before: &SpecFields, after: &SpecFields) -> Result<String> {
+    let mut lines = to_lines(contents);
+    let spec = find_spec(&lines, &before.name)?;
+
+    // Work from the last field up, so earlier line numbers stay valid
+    if after.ignore != before.ignore {
+        set_ignore(&mut lines, &spec, &after.ignore);
+    }
+    let scalars = [
+        ("mode", before.mode, after.mode),
+        ("beta", before.beta.as_str(), after.beta.as_str()),
+        ("alpha", before.alpha.as_str(), after.alpha.as_str()),
+    ];
+    for (key, old, new) in scalars {
+        if old != new {
+            let spec = find_spec(&lines, &before.name)?;
+            set_scalar(&mut lines, &spec, key, new);
+        }
+    }
+
+    if after.name != before.name {
+        let header = &lines[spec.start];
+        let indent = indent_of(header).unwrap_or_default();
+        lines[spec.start] = format!(
+            "{}{}:{}",
+            " ".repeat(indent),
+            after.name,
+            trailing_comment(header)
+        );
+    }
+    Ok(from_lines(lines))
+}
+
+/// `contents` with the spec `source` copied below itself as `copy`.
+fn copy_spec(contents: &str, source: &SpecFields, copy: &SpecFields) -> Result<String> {
+    let mut lines = to_lines(contents);
+    let spec = find_spec(&lines, &source.name)?;
+    let mut copied = lines[spec.clone()].to_vec();
+    let header = &copied[0];
+    copied[0] = format!(
+        "{}{}:{}",
+        " ".repeat(indent_of(header).unwrap_or_default()),
+        copy.name,
+        trailing_comment(header)
+    );
+    // Keep the blank line that separates the specs, if there is one
+    if lines
+        .get(spec.end)
+        .is_some_and(|line| line.trim().is_empty())
+    {
+        copied.insert(0, String::new());
+    }
+    lines.splice(spec.end..spec.end, copied);
+    let renamed = SpecFields {
+        name: copy.name.clone(),
+        ..source.clone()
+    };
+    replace_spec(&from_lines(lines), &renamed, copy)
+}
+
+/// `contents` with the ignores of the spec `before` changed to those of
+/// `after`, and `vcs: true` set or removed.
+fn replace_ignores(
+    contents: &str,
+    before: &SpecFields,
+    after: &SpecFields,
+    vcs_before: bool,
+    vcs: bool,
+) -> Result<String> {
+    let mut lines = to_lines(contents);
+    let spec = find_spec(&lines, &before.name)?;
+    // Setting vcs needs the ignores written as a mapping, which this makes sure of
+    if after.ignore != before.ignore || vcs != vcs_before {
+        set_ignore(&mut lines, &spec, &after.ignore);
+    }
+    if vcs != vcs_before {
+        let spec = find_spec(&lines, &before.name)?;
+        set_vcs(&mut lines, &spec, vcs);
+    }
+    Ok(from_lines(lines))
+}
+
+/// The indentation of the fields under `parent`, or one level deeper than
+/// it if it has none yet.
+fn field_indent(lines: &[String], parent: &Range<usize>, spec_indent: usize) -> usize {
+    let parent_indent = indent_of(&lines[parent.start]).unwrap_or_default();
+    children(lines, parent).map_or(parent_indent + spec_indent.max(2), |(indent, _)| indent)
+}
+
+/// Set the `key: value` line of the spec, replacing the old one or adding it
+/// at the end of the spec.
+fn set_scalar(lines: &mut Vec<String>, spec: &Range<usize>, key: &str, value: &str) {
+    let spec_indent = indent_of(&lines[spec.start]).unwrap_or(2);
+    let indent = field_indent(lines, spec, spec_indent);
+    let existing = children(lines, spec)
+        .and_then(|(_, fields)| fields.into_iter().find(|(k, _)| k == key))
+        .map(|(_, range)| range);
+    let (range, comment) = match existing {
+        Some(range) => {
+            let comment = trailing_comment(&lines[range.start]).to_string();
+            (range, comment)
+        }
+        None => (spec.end..spec.end, String::new()),
+    };
+    let line = format!("{}{}: {}{}", " ".repeat(indent), key, quote(value), comment);
+    lines.splice(range, [line]);
+}
+
+/// Set the spec's ignore paths. Other ignore settings, like `vcs: true`, are
+/// kept; the whole `ignore:` entry goes if nothing is left in it.
+fn set_ignore(lines: &mut Vec<String>, spec: &Range<usize>, ignore: &[String]) {
+    let spec_indent = indent_of(&lines[spec.start]).unwrap_or(2);
+    let unit = " ".repeat(spec_indent.max(2));
+    let indent = field_indent(lines, spec, spec_indent);
+    let pad = " ".repeat(indent);
+    let existing = children(lines, spec)
+        .and_then(|(_, fields)| fields.into_iter().find(|(k, _)| k == "ignore"))
+        .map(|(_, range)| range);
+
+    // An ignore mapping with other settings keeps them, and has its paths
+    // replaced in place
+    if let Some(range) = &existing {
+        if !has_inline_value(&lines[range.start]) {
+            if let Some((nested, entries)) = children(lines, range) {
+                let others = entries.iter().any(|(key, _)| key != "paths");
+                if others {
+                    let paths = entries
+                        .iter()
+                        .find(|(key, _)| key == "paths")
+                        .map_or(range.end..range.end, |(_, paths)| paths.clone());
+                    let new_lines = if ignore.is_empty() {
+                        Vec::new()
+                    } else {
+                        paths_lines(ignore, &" ".repeat(nested), &unit)
+                    };
+                    lines.splice(paths, new_lines);
+                    return;
+                }
+            }
+        }
+    }
+
+    let new_lines = if ignore.is_empty() {
+        Vec::new()
+    } else {
+        let mut new_lines = vec![format!("{}ignore:", pad)];
+        new_lines.extend(paths_lines(ignore, &format!("{}{}", pad, unit), &unit));
+        new_lines
+    };
+    lines.splice(existing.unwrap_or(spec.end..spec.end), new_lines);
+}
+
+/// Add `vcs: true` to the spec's ignore mapping, creating it if need be, or
+/// remove it and the mapping if nothing else is left in it.
+fn set_vcs(lines: &mut Vec<String>, spec: &Range<usize>, vcs: bool) {
+    let spec_indent = indent_of(&lines[spec.start]).unwrap_or(2);
+    let existing = children(lines, spec)
+        .and_then(|(_, fields)| fields.into_iter().find(|(k, _)| k == "ignore"))
+        .map(|(_, range)| range);
+    let Some(ignore) = existing else {
+        if vcs {
+            let pad = " ".repeat(field_indent(lines, spec, spec_indent));
+            let unit = " ".repeat(spec_indent.max(2));
+            lines.splice(
+                spec.end..spec.end,
+                [
+                    format!("{}ignore:", pad),
+                    format!("{}{}vcs: true", pad, unit),
+                ],
+            );
+        }
+        return;
+    };
+    let nested = field_indent(lines, &ignore, spec_indent);
+    let entry = children(lines, &ignore)
+        .and_then(|(_, entries)| entries.into_iter().find(|(k, _)| k == "vcs"))
+        .map(|(_, range)| range);
+    match entry {
+        Some(entry) if vcs => {
+            lines.splice(entry, [format!("{}vcs: true", " ".repeat(nested))]);
+        }
+        None if vcs => {
+            let at = ignore.start + 1;
+            lines.insert(at, format!("{}vcs: true", " ".repeat(nested)));
+        }
+        Some(entry) => {
+            let end = ignore.end - entry.len();
+            lines.drain(entry);
+            if children(lines, &(ignore.start..end)).is_none() {
+                lines.drain(ignore.start..end);
+            }
+        }
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> SpecFields {
+        SpecFields {
+            name: "photos".to_string(),
+            alpha: "~/Pictures".to_string(),
+            beta: "nas:/volume1/photos".to_string(),
+            mode: "one-way-safe",
+            ignore: vec!["*.tmp".to_string()],
+        }
+    }
+
+    const PROJECT: &str = "\
+# Team sync config
+sync:
+  defaults:
+    mode: two-way-safe
+  # Source code
+  api:
+    alpha: ./api # checked out here
+    beta: server:/srv/api
+    ignore:
+      vcs: true
+      paths:
+        - node_modules
+  web:
+    alpha: ./web
+    beta: server:/srv/web
+";
+
+    fn api() -> SpecFields {
+        SpecFields {
+            name: "api".to_string(),
+            alpha: "./api".to_string(),
+            beta: "server:/srv/api".to_string(),
+            mode: "two-way-safe",
+            ignore: vec!["node_modules".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_insert_spec_matches_indentation() {
+        let contents = "# Sync config\nsync:\n    defaults:\n        mode: two-way-safe\n";
+        let updated = insert_spec(contents, &spec()).unwrap();
+        let expected = [
+            "# Sync config",
+            "sync:",
+            "    photos:",
+            "        alpha: \"~/Pictures\"",
+            "        beta: \"nas:/volume1/photos\"",
+            "        mode: \"one-way-safe\"",
+            "        ignore:",
+            "            paths:",
+            "                - \"*.tmp\"",
+            "    defaults:",
+            "        mode: two-way-safe",
+        ];
+        assert_eq!(updated, expected.join("\n") + "\n");
+    }
+
+    #[test]
+    fn test_add_spec() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mutagen.yml");
+        std::fs::write(&path, "beforeCreate:\n  - echo hi\n").unwrap();
+
+        add_spec(&path, &spec()).unwrap();
+        let project = ProjectFile::from_path(path.clone()).unwrap();
+        let definition = &project.sessions["photos"];
+        assert_eq!(definition.beta, "nas:/volume1/photos");
+        assert_eq!(definition.mode.as_deref(), Some("one-way-safe"));
+        assert_eq!(ignore_paths(definition), vec!["*.tmp"]);
+
+        // The same name can't be added twice
+        assert!(add_spec(&path, &spec()).is_err());
+    }
+
+    #[test]
+    fn test_replace_spec_keeps_comments_and_other_settings() {
+        let mut after = api();
+        after.name = "backend".to_string();
+        after.alpha = "./backend".to_string();
+        after.mode = "one-way-replica";
+        after.ignore = vec!["target".to_string(), "*.log".to_string()];
+
+        let updated = replace_spec(PROJECT, &api(), &after).unwrap();
+        let expected = "\
+# Team sync config
+sync:
+  defaults:
+    mode: two-way-safe
+  # Source code
+  backend:
+    alpha: \"./backend\" # checked out here
+    beta: server:/srv/api
+    ignore:
+      vcs: true
+      paths:
+        - \"target\"
+        - \"*.log\"
+    mode: \"one-way-replica\"
+  web:
+    alpha: ./web
+    beta: server:/srv/web
+";
+        assert_eq!(updated, expected);
+    }
+
+    #[test]
+    fn test_replace_spec_ignore() {
+        let web = SpecFields {
+            name: "web".to_string(),
+            alpha: "./web".to_string(),
+            beta: "server:/srv/web".to_string(),
+            mode: "two-way-safe",
+            ignore: Vec::new(),
+        };
+        let mut after = web.clone();
+        after.ignore = vec!["dist".to_string()];
+        let added = replace_spec(PROJECT, &web, &after).unwrap();
+        assert!(added.ends_with(
+            "    beta: server:/srv/web\n    ignore:\n      paths:\n        - \"dist\"\n"
+        ));
+        assert_eq!(replace_spec(&added, &after, &web).unwrap(), PROJECT);
+
+        // Clearing the paths keeps `vcs: true`
+        let mut cleared = api();
+        cleared.ignore = Vec::new();
+        let updated = replace_spec(PROJECT, &api(), &cleared).unwrap();
+        assert!(updated.contains("    ignore:\n      vcs: true\n  web:"));
+    }
+
+    #[test]
+    fn test_update_ignores() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mutagen.yml");
+        std::fs::write(&path, PROJECT).unwrap();
+
+        // Turning vcs off keeps the paths
+        update_ignores(&path, "api", false, &["node_modules".to_string()]).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("    ignore:\n      paths:\n        - \"node_modules\"\n  web:"));
+
+        // On a spec with no ignores, vcs needs a new mapping
+        update_ignores(&path, "web", true, &[]).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.ends_with("    beta: server:/srv/web\n    ignore:\n      vcs: true\n"));
+        update_ignores(&path, "web", true, &["dist".to_string()]).unwrap();
+
+        let project = ProjectFile::from_path(path.clone()).unwrap();
+        let web = &project.sessions["web"];
+        assert!(ignores_vcs(web));
+        assert_eq!(ignore_paths(web), vec!["dist"]);
+        assert!(!ignores_vcs(&project.sessions["api"]));
+
+        // Turning it off again leaves nothing behind
+        update_ignores(&path, "web", false, &[]).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.ends_with("    beta: server:/srv/web\n"));
+    }
+
+    #[test]
+    fn test_remove_and_update_spec_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mutagen.yml");
+        std::fs::write(&path, PROJECT).unwrap();
+
+        let mut after = api();
+        after.beta = "server:/srv/api-v2".to_string();
+        update_spec(&path, &api(), &after).unwrap();
+        remove_spec(&path, "web").unwrap();
+
+        let project = ProjectFile::from_path(path.clone()).unwrap();
+        assert_eq!(project.sessions.len(), 1);
+        assert_eq!(project.sessions["api"].beta, "server:/srv/api-v2");
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("# Team sync config\n"));
+        assert!(contents.contains("# Source code"));
+
+        assert!(remove_spec(&path, "web").is_err());
+        // Renaming onto another spec is refused
+        std::fs::write(&path, PROJECT).unwrap();
+        let mut clash = api();
+        clash.name = "web".to_string();
+        assert!(update_spec(&path, &api(), &clash).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_spec() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mutagen.yml");
+        std::fs::write(&path, PROJECT).unwrap();
+
+        let mut copy = api();
+        copy.name = "api-staging".to_string();
+        copy.beta = "staging:/srv/api".to_string();
+        duplicate_spec(&path, &api(), &copy).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let expected = "\
+  api-staging:
+    alpha: ./api # checked out here
+    beta: \"staging:/srv/api\"
+    ignore:
+      vcs: true
+      paths:
+        - node_modules
+  web:
+";
+        assert!(contents.contains(expected));
+        let project = ProjectFile::from_path(path.clone()).unwrap();
+        assert_eq!(project.sessions["api"].beta, "server:/srv/api");
+        assert!(ignores_vcs(&project.sessions["api-staging"]));
+
+        // The copy needs a name of its own
+        assert!(duplicate_spec(&path, &api(), &copy).is_err());
+    }
+
+    #[test]
+    fn test_inline_specs_are_refused() {
+        let contents = "sync:\n  api: {alpha: ./api, beta: server:/srv/api}\n";
+        assert!(replace_spec(contents, &api(), &spec()).is_err());
+        assert!(insert_spec("sync: {}\n", &spec()).is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Minimal `~/.ssh/config` reader.
+//!
+//! Only the `Host`, `HostName`, `User`, and `Port` keywords are interpreted.
+//! They are enough to show friendly aliases for remote endpoints and to
+//! pass the right user and port when mutagui runs its own ssh commands.
+//! `Match` blocks and `Include` directives are skipped.
+
+use std::path::Path;
+
+/// Settings that apply to one host after merging all matching blocks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SshHostConfig {
+    pub hostname: Option<String>,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Clone)]
+struct HostBlock {
+    patterns: Vec<String>,
+    config: SshHostConfig,
+}
+
+/// Parsed `Host` blocks in file order.
+#[derive(Debug, Clone, Default)]
+pub struct SshConfig {
+    blocks: Vec<HostBlock>,
+}
+
+impl SshConfig {
+    /// Load `~/.ssh/config`, returning an empty config if it is missing or unreadable.
+    pub fn load() -> Self {
+        dirs::home_dir()
+            .map(|home| Self::load_from(&home.join(".ssh").join("config")))
+            .unwrap_or_default()
+    }
+
+    fn load_from(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let mut blocks: Vec<HostBlock> = Vec::new();
+        // Settings before the first Host line, or inside Match blocks, are ignored
+        let mut in_host_block = false;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+                Some((key, value)) => (key, value.trim_start_matches(['=', ' ', '\t']).trim()),
+                None => continue,
+            };
+
+            match key.to_ascii_lowercase().as_str() {
+                "host" => {
+                    blocks.push(HostBlock {
+                        patterns: value.split_whitespace().map(str::to_string).collect(),
+                        config: SshHostConfig::default(),
+                    });
+                    in_host_block = true;
+                }
+                "match" => in_host_block = false,
+                keyword if in_host_block => {
+                    let config = &mut blocks.last_mut().expect("host block").config;
+                    // Like ssh, the first value given for a keyword wins
+                    match keyword {
+                        "hostname" if config.hostname.is_none() => {
+                            config.hostname = Some(value.to_string())
+                        }
+                        "user" if config.user.is_none() => config.user = Some(value.to_string()),
+                        "port" if config.port.is_none() => config.port = value.parse().ok(),
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self { blocks }
+    }
+
+    /// Merge every block whose patterns match `host`, first value winning.
+    pub fn resolve(&self, host: &str) -> SshHostConfig {
+        let mut resolved = SshHostConfig::default();
+        for block in self
+            .blocks
+            .iter()
+            .filter(|b| block_matches(&b.patterns, host))
+        {
+            let config = &block.config;
+            resolved.hostname = resolved.hostname.or_else(|| config.hostname.clone());
+            resolved.user = resolved.user.or_else(|| config.user.clone());
+            resolved.port = resolved.port.or(config.port);
+        }
+        resolved
+    }
+
+    /// Alias whose `HostName` is `host`, for endpoints given by real hostname.
+    pub fn alias_for(&self, host: &str) -> Option<&str> {
+        if self.is_alias(host) {
+            return None;
+        }
+        self.blocks
+            .iter()
+            .filter(|block| block.config.hostname.as_deref() == Some(host))
+            .flat_map(|block| block.patterns.iter())
+            .find(|pattern| is_literal(pattern))
+            .map(String::as_str)
+    }
+
+    /// Where ssh really connects for `host`: its `HostName`, with the port
+    /// unless it's the default, e.g. "devbox.internal.example.com:2222". A
+    /// port given in the endpoint wins over the config's.
+    pub fn target(&self, host: &str, port: Option<u16>) -> String {
+        let resolved = self.resolve(host);
+        let hostname = resolved.hostname.unwrap_or_else(|| host.to_string());
+        match port.or(resolved.port) {
+            Some(port) if port != 22 => format!("{}:{}", hostname, port),
+            _ => hostname,
+        }
+    }
+
+    /// Name to show for a host: its alias if one exists, else the host itself.
+    pub fn display_host<'a>(&'a self, host: &'a str) -> &'a str {
+        self.alias_for(host).unwrap_or(host)
+    }
+
+    fn is_alias(&self, host: &str) -> bool {
+        self.blocks
+            .iter()
+            .any(|block| block.patterns.iter().any(|p| is_literal(p) && p == host))
+    }
+}
+
+fn is_literal(pattern: &str) -> bool {
+    !pattern.contains(['*', '?', '!'])
+}
+
+/// A block matches if any pattern matches and no negated pattern does.
+fn block_matches(patterns: &[String], host: &str) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        if let Some(negated) = pattern.strip_prefix('!') {
+            if wildcard_match(negated, host) {
+                return false;
+            }
+        } else if wildcard_match(pattern, host) {
+            matched = true;
+        }
+    }
+    matched
+}
+
+/// Match ssh-style patterns where `*` is any run of characters and `?` one character.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "
+# Personal servers
+Host devbox dev
+    HostName devbox.internal.example.com
+    User alice
+    Port 2222
+
+Host *.example.com !bastion.example.com
+    User deploy
+
+Host=build
+  HostName=10.0.0.5
+
+Match host legacy
+    User ignored
+
+Host *
+    Port 22
+    User fallback
+";
+
+    #[test]
+    fn test_resolve_alias() {
+        let config = SshConfig::parse(SAMPLE);
+        let resolved = config.resolve("devbox");
+        assert_eq!(
+            resolved.hostname.as_deref(),
+            Some("devbox.internal.example.com")
+        );
+        assert_eq!(resolved.user.as_deref(), Some("alice"));
+        assert_eq!(resolved.port, Some(2222));
+    }
+
+    #[test]
+    fn test_resolve_merges_wildcards_first_value_wins() {
+        let config = SshConfig::parse(SAMPLE);
+
+        let resolved = config.resolve("web.example.com");
+        assert_eq!(resolved.user.as_deref(), Some("deploy"));
+        assert_eq!(resolved.port, Some(22));
+
+        let bastion = config.resolve("bastion.example.com");
+        assert_eq!(bastion.user.as_deref(), Some("fallback"));
+    }
+
+    #[test]
+    fn test_equals_syntax_and_match_blocks() {
+        let config = SshConfig::parse(SAMPLE);
+        assert_eq!(
+            config.resolve("build").hostname.as_deref(),
+            Some("10.0.0.5")
+        );
+        // Settings inside Match blocks are not applied to any Host
+        assert_eq!(config.resolve("legacy").user.as_deref(), Some("fallback"));
+    }
+
+    #[test]
+    fn test_alias_for_hostname() {
+        let config = SshConfig::parse(SAMPLE);
+        assert_eq!(
+            config.alias_for("devbox.internal.example.com"),
+            Some("devbox")
+        );
+        assert_eq!(config.alias_for("10.0.0.5"), Some("build"));
+        assert_eq!(config.alias_for("devbox"), None);
+        assert_eq!(config.display_host("10.0.0.5"), "build");
+        assert_eq!(config.display_host("unknown.host"), "unknown.host");
+    }
+
+    #[test]
+    fn test_target() {
+        let config = SshConfig::parse(SAMPLE);
+        assert_eq!(
+            config.target("dev", None),
+            "devbox.internal.example.com:2222"
+        );
+        assert_eq!(config.target("build", None), "10.0.0.5");
+        assert_eq!(config.target("build", Some(2200)), "10.0.0.5:2200");
+        assert_eq!(config.target("unknown.host", None), "unknown.host");
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*", "anything"));
+        assert!(wildcard_match("*.example.com", "a.b.example.com"));
+        assert!(wildcard_match("host?", "host1"));
+        assert!(!wildcard_match("host?", "host12"));
+        assert!(!wildcard_match("*.example.com", "example.org"));
+    }
+
+    #[test]
+    fn test_missing_file_is_empty() {
+        let config = SshConfig::load_from(Path::new("/nonexistent/ssh/config"));
+        assert_eq!(config.resolve("anything"), SshHostConfig::default());
+    }
+}
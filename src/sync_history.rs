@@ -0,0 +1,173 @@
+//! When each session last completed a sync cycle, as far as mutagui has seen.
+//!
+//! Mutagen reports how many cycles a session has completed but not when the
+//! last one finished, so mutagui notes the time whenever the count goes up
+//! and saves it to `sync_history.json` in the state directory. After a
+//! restart, a session whose count hasn't moved since gets its last sync time
+//! back instead of showing as unknown.
+
+use crate::mutagen::{SyncSession, SyncTime};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+/// A session's cycle count and when it was last seen to go up.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LastSync {
+    cycles: u64,
+    /// RFC 3339, like the audit log.
+    at: String,
+}
+
+/// Last sync times by session identifier, and the file they are saved in.
+#[derive(Debug, Clone, Default)]
+pub struct SyncHistory {
+    path: Option<PathBuf>,
+    sessions: BTreeMap<String, LastSync>,
+}
+
+impl SyncHistory {
+    /// Load the history from the state directory. A missing or unreadable
+    /// file gives an empty history.
+    pub fn load() -> Self {
+        let path = dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("mutagui").join("sync_history.json"));
+        Self::load_path(path)
+    }
+
+    /// Load the history from a specific file.
+    #[cfg(test)]
+    pub fn at(path: &std::path::Path) -> Self {
+        Self::load_path(Some(path.to_path_buf()))
+    }
+
+    fn load_path(path: Option<PathBuf>) -> Self {
+        let sessions = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| {
+                serde_json::from_str(&contents)
+                    .inspect_err(|e| tracing::warn!(error = %e, "ignoring unreadable sync history"))
+                    .ok()
+            })
+            .unwrap_or_default();
+        Self { path, sessions }
+    }
+
+    /// Set the `sync_time` of each of `sessions`, given the sessions from
+    /// the previous refresh (None on the first), and save any new times.
+    pub fn observe(&mut self, previous: Option<&[SyncSession]>, sessions: &mut [SyncSession]) {
+        self.observe_at(previous, sessions, Local::now());
+    }
+
+    fn observe_at(
+        &mut self,
+        previous: Option<&[SyncSession]>,
+        sessions: &mut [SyncSession],
+        now: DateTime<Local>,
+    ) {
+        let first_refresh = previous.is_none();
+        let previous: HashMap<_, _> = previous
+            .unwrap_or_default()
+            .iter()
+            .map(|session| (session.identifier.as_str(), session))
+            .collect();
+        let before = self.sessions.clone();
+
+        for session in sessions.iter_mut() {
+            let cycles = session.successful_cycles.unwrap_or(0);
+            let saved = self
+                .sessions
+                .get(&session.identifier)
+                .filter(|saved| saved.cycles == cycles)
+                .and_then(|saved| DateTime::parse_from_rfc3339(&saved.at).ok())
+                .map(|at| at.with_timezone(&Local));
+            session.sync_time = match previous.get(session.identifier.as_str()) {
+                // If successfulCycles increased, we observed a sync
+                Some(old) if cycles > old.successful_cycles.unwrap_or(0) => SyncTime::At(now),
+                Some(old) => old.sync_time.clone(),
+                None => match saved {
+                    // Nothing has synced since the time that was saved
+                    Some(at) => SyncTime::At(at),
+                    None if cycles == 0 => SyncTime::Never,
+                    // Sessions that were already running when mutagui started
+                    None if first_refresh => SyncTime::Unknown,
+                    None => SyncTime::At(now),
+                },
+            };
+            if let SyncTime::At(at) = &session.sync_time {
+                self.sessions.insert(
+                    session.identifier.clone(),
+                    LastSync {
+                        cycles,
+                        at: at.to_rfc3339(),
+                    },
+                );
+            }
+        }
+
+        // Forget terminated sessions
+        self.sessions
+            .retain(|identifier, _| sessions.iter().any(|s| s.identifier == *identifier));
+        if self.sessions != before {
+            if let Err(e) = self.save() {
+                tracing::warn!(error = %e, "failed to save sync history");
+            }
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = self
+            .path
+            .as_ref()
+            .context("No state directory for sync history")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&self.sessions)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn session(name: &str, cycles: u64) -> SyncSession {
+        SyncSession::named(name).with_cycles(cycles)
+    }
+
+    #[test]
+    fn test_sync_times_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("state").join("sync_history.json");
+        let start = Local::now();
+        let later = start + Duration::minutes(5);
+
+        let mut history = SyncHistory::at(&file);
+        let mut first = vec![session("api", 3), session("web", 0)];
+        history.observe_at(None, &mut first, start);
+        assert_eq!(first[0].sync_time, SyncTime::Unknown);
+        assert_eq!(first[1].sync_time, SyncTime::Never);
+
+        let mut second = vec![session("api", 4), session("web", 0), session("docs", 1)];
+        history.observe_at(Some(&first), &mut second, later);
+        assert_eq!(second[0].sync_time, SyncTime::At(later));
+        assert_eq!(second[1].sync_time, SyncTime::Never);
+        assert_eq!(second[2].sync_time, SyncTime::At(later));
+
+        // After a restart, only sessions that haven't synced since keep their time
+        let mut restarted = vec![session("api", 4), session("docs", 2)];
+        SyncHistory::at(&file).observe_at(None, &mut restarted, later + Duration::hours(1));
+        let SyncTime::At(at) = restarted[0].sync_time else {
+            panic!("no sync time for api");
+        };
+        assert_eq!(at.timestamp(), later.timestamp());
+        assert_eq!(restarted[1].sync_time, SyncTime::Unknown);
+    }
+}
@@ -0,0 +1,67 @@
+//! Top-level views, switched with Tab and Shift+Tab.
+//!
+//! The sync view is the project list the app started with. Panels that
+//! aren't about sync sessions each get a tab of their own rather than
+//! another overlay on top of it.
+
+use crate::i18n::t;
+
+/// One of the views in the tab bar.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tab {
+    /// Projects and their sync sessions.
+    #[default]
+    Sync,
+    /// The same specs grouped by the host their endpoints are on.
+    Hosts,
+    /// Mutagen's network forwarding sessions.
+    Forward,
+    /// The mutagen daemon and connection state.
+    Daemon,
+    /// Actions performed through mutagui, from the audit log.
+    Log,
+}
+
+impl Tab {
+    /// Every tab, in tab bar order.
+    pub const ALL: [Tab; 5] = [Tab::Sync, Tab::Hosts, Tab::Forward, Tab::Daemon, Tab::Log];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Tab::Sync => t!("tab.sync"),
+            Tab::Hosts => t!("tab.hosts"),
+            Tab::Forward => t!("tab.forward"),
+            Tab::Daemon => t!("tab.daemon"),
+            Tab::Log => t!("tab.log"),
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+
+    /// The tab to the right, wrapping around.
+    pub fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    /// The tab to the left, wrapping around.
+    pub fn previous(self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_and_previous_wrap() {
+        assert_eq!(Tab::Sync.next(), Tab::Hosts);
+        assert_eq!(Tab::Log.next(), Tab::Sync);
+        assert_eq!(Tab::Sync.previous(), Tab::Log);
+        for tab in Tab::ALL {
+            assert_eq!(tab.next().previous(), tab);
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Commands a project file defines for `mutagen project run`, and the
+//! user's own from `[commands]` in config.toml.
+//!
+//! A project file's `commands` section names shell commands, such as a build
+//! or a deploy, that belong with its sessions. Commands in config.toml work
+//! with any project: fields of the selected spec, like `{beta_host}`, are
+//! filled in before they run. A command runs in the background and its
+//! output is collected as it arrives, so the popup that shows it can follow
+//! along instead of waiting for the command to finish.
+
+use crate::command::decode_message;
+use crate::config::MutagenConfig;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// A project command that was started, with the output it printed so far.
+pub struct TaskRun {
+    pub name: String,
+    child: Child,
+    /// Standard output and error, in the order lines arrived.
+    output: Arc<Mutex<String>>,
+    /// Threads reading the output, which finish once it's all collected.
+    readers: Vec<JoinHandle<()>>,
+    status: Option<ExitStatus>,
+}
+
+impl TaskRun {
+    /// Start the command `name` from the project file at `project_file`,
+    /// running mutagen as `mutagen` configures.
+    pub fn start(
+        mutagen: &MutagenConfig,
+        project_file: &Path,
+        name: &str,
+    ) -> std::io::Result<Self> {
+        let mut command = mutagen.command("mutagen");
+        command
+            .args(["project", "run", "-f"])
+            .arg(project_file)
+            .arg(name);
+        Self::spawn(name, command)
+    }
+
+    /// Run `command` from `[commands]` in config.toml in a shell.
+    pub fn shell(name: &str, command: &str) -> std::io::Result<Self> {
+        #[cfg(unix)]
+        let (program, flag) = ("sh", "-c");
+        #[cfg(not(unix))]
+        let (program, flag) = ("cmd", "/C");
+        let mut shell = Command::new(program);
+        shell.args([flag, command]);
+        Self::spawn(name, shell)
+    }
+
+    fn spawn(name: &str, mut command: Command) -> std::io::Result<Self> {
+        tracing::info!(name, ?command, "running project command");
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let output = Arc::new(Mutex::new(String::new()));
+        let stdout = child
+            .stdout
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>);
+        let stderr = child
+            .stderr
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>);
+        let readers = stdout
+            .into_iter()
+            .chain(stderr)
+            .map(|stream| {
+                let output = Arc::clone(&output);
+                std::thread::spawn(move || collect_lines(stream, &output))
+            })
+            .collect();
+        Ok(Self {
+            name: name.to_string(),
+            child,
+            output,
+            readers,
+            status: None,
+        })
+    }
+
+    /// Everything the command printed so far.
+    pub fn output(&self) -> String {
+        self.output.lock().map(|o| o.clone()).unwrap_or_default()
+    }
+
+    /// The command's exit status, once it has finished and all of its
+    /// output has been collected.
+    pub fn poll(&mut self) -> Option<ExitStatus> {
+        if self.status.is_none() {
+            self.status = self.child.try_wait().ok().flatten();
+        }
+        self.status
+            .filter(|_| self.readers.iter().all(JoinHandle::is_finished))
+    }
+
+    /// Stop the command if it's still running.
+    pub fn stop(&mut self) {
+        if self.status.is_none() && self.child.try_wait().ok().flatten().is_none() {
+            let _ = self.child.kill();
+            self.status = self.child.wait().ok();
+        }
+    }
+}
+
+impl Drop for TaskRun {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// `template` with each `{field}` replaced by the field's value, quoted for
+/// the shell. Returns None if it uses a field that has no value, such as
+/// `{spec}` with a project selected. Braces around anything that isn't one
+/// of `fields` are left as they are.
+pub fn fill_in(template: &str, fields: &[(&str, Option<String>)]) -> Option<String> {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        filled.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let field = after
+            .find('}')
+            .and_then(|close| fields.iter().find(|(name, _)| *name == &after[..close]));
+        match field {
+            Some((name, value)) => {
+                filled.push_str(&shell_escape::escape(value.as_deref()?.into()));
+                rest = &after[name.len() + 1..];
+            }
+            None => {
+                filled.push('{');
+                rest = after;
+            }
+        }
+    }
+    filled.push_str(rest);
+    Some(filled)
+}
+
+/// Append the lines read from `stream` to `output` until it closes.
+fn collect_lines(stream: Box<dyn Read + Send>, output: &Mutex<String>) {
+    let mut reader = BufReader::new(stream);
+    let mut line = Vec::new();
+    while matches!(reader.read_until(b'\n', &mut line), Ok(n) if n > 0) {
+        if let Ok(mut output) = output.lock() {
+            output.push_str(&decode_message(&line));
+        }
+        line.clear();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// Wait for `task` to finish.
+    fn wait(task: &mut TaskRun) -> ExitStatus {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            if let Some(status) = task.poll() {
+                return status;
+            }
+            assert!(Instant::now() < deadline, "command didn't finish");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_collects_output() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo building; echo failed >&2; exit 3"]);
+        let mut task = TaskRun::spawn("build", command).unwrap();
+        assert_eq!(wait(&mut task).code(), Some(3));
+        let output = task.output();
+        assert!(output.contains("building\n"));
+        assert!(output.contains("failed\n"));
+    }
+
+    #[test]
+    fn test_fill_in() {
+        let fields = [
+            ("spec", Some("web".to_string())),
+            ("beta_host", Some("deploy@server".to_string())),
+            ("beta_path", Some("/srv/my app".to_string())),
+            ("alpha", None),
+        ];
+        assert_eq!(
+            fill_in(
+                "ssh {beta_host} ls {beta_path}; echo {spec} {other}",
+                &fields
+            )
+            .unwrap(),
+            "ssh 'deploy@server' ls '/srv/my app'; echo web {other}"
+        );
+        assert_eq!(fill_in("ls {alpha}", &fields), None);
+        assert_eq!(fill_in("awk '{print}'", &fields).unwrap(), "awk '{print}'");
+
+        let mut task = TaskRun::shell("echo", &fill_in("echo {spec}", &fields).unwrap()).unwrap();
+        assert!(wait(&mut task).success());
+        assert_eq!(task.output(), "web\n");
+    }
+
+    #[test]
+    fn test_stop() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "sleep 30"]);
+        let mut task = TaskRun::spawn("serve", command).unwrap();
+        assert!(task.poll().is_none());
+        task.stop();
+        assert!(task.status.is_some_and(|status| !status.success()));
+    }
+}
@@ -1,4 +1,90 @@
+use anyhow::{bail, Result};
 use ratatui::style::Color;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Number of colors the terminal can display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    /// The 16 standard ANSI colors.
+    Basic,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// 24-bit RGB colors.
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Probe the terminal's color capabilities from the environment.
+    pub fn detect() -> Self {
+        Self::from_env(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    /// Determine color depth from `COLORTERM` and `TERM` values.
+    pub fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        let colorterm = colorterm.unwrap_or("").to_lowercase();
+        let term = term.unwrap_or("").to_lowercase();
+
+        if colorterm == "truecolor" || colorterm == "24bit" || term.contains("direct") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Basic
+        }
+    }
+
+    /// Convert an RGB color to the closest color this depth can display.
+    pub fn adapt(&self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::TrueColor, c) => c,
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(rgb_to_ansi256(r, g, b)),
+            (ColorDepth::Basic, Color::Rgb(r, g, b)) => rgb_to_basic(r, g, b),
+            (_, c) => c,
+        }
+    }
+}
+
+/// Map an RGB color onto the 6x6x6 color cube (or grayscale ramp) of the 256-color palette.
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    if r == g && g == b {
+        if r < 8 {
+            return 16;
+        }
+        if r > 248 {
+            return 231;
+        }
+        return 232 + ((r as u16 - 8) * 24 / 247) as u8;
+    }
+    let scale = |v: u8| (v as u16 * 5 / 255) as u8;
+    16 + 36 * scale(r) + 6 * scale(g) + scale(b)
+}
+
+/// Map an RGB color onto the nearest of the 16 basic ANSI colors.
+fn rgb_to_basic(r: u8, g: u8, b: u8) -> Color {
+    let bright = r.max(g).max(b) > 170;
+    let on = |v: u8| v > 95;
+    match (on(r), on(g), on(b), bright) {
+        (false, false, false, _) => Color::Black,
+        (true, false, false, false) => Color::Red,
+        (true, false, false, true) => Color::LightRed,
+        (false, true, false, false) => Color::Green,
+        (false, true, false, true) => Color::LightGreen,
+        (true, true, false, false) => Color::Yellow,
+        (true, true, false, true) => Color::LightYellow,
+        (false, false, true, false) => Color::Blue,
+        (false, false, true, true) => Color::LightBlue,
+        (true, false, true, false) => Color::Magenta,
+        (true, false, true, true) => Color::LightMagenta,
+        (false, true, true, false) => Color::Cyan,
+        (false, true, true, true) => Color::LightCyan,
+        (true, true, true, false) => Color::DarkGray,
+        (true, true, true, true) => Color::Gray,
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ColorScheme {
@@ -14,6 +100,14 @@ pub struct ColorScheme {
     pub status_error_fg: Color,
     pub help_key_fg: Color,
     pub help_text_fg: Color,
+    /// Subtle background for project header rows (None on 16-color terminals).
+    pub project_row_bg: Option<Color>,
+    /// Progress bar color at 0%.
+    pub progress_start: Color,
+    /// Progress bar color at 100%.
+    pub progress_end: Color,
+    /// Color depth this scheme was built for.
+    pub depth: ColorDepth,
 }
 
 impl ColorScheme {
@@ -31,6 +125,10 @@ impl ColorScheme {
             status_error_fg: Color::Red,
             help_key_fg: Color::Cyan,
             help_text_fg: Color::White,
+            project_row_bg: None,
+            progress_start: Color::Yellow,
+            progress_end: Color::Green,
+            depth: ColorDepth::Basic,
         }
     }
 
@@ -48,14 +146,249 @@ impl ColorScheme {
             status_error_fg: Color::Red,
             help_key_fg: Color::Blue,
             help_text_fg: Color::Black,
+            project_row_bg: None,
+            progress_start: Color::Rgb(184, 134, 11),
+            progress_end: Color::Rgb(0, 128, 0),
+            depth: ColorDepth::Basic,
+        }
+    }
+
+    /// Dark scheme with a richer palette when the terminal supports it.
+    pub fn dark_for(depth: ColorDepth) -> Self {
+        if depth == ColorDepth::Basic {
+            return Self::dark();
+        }
+        Self {
+            header_fg: Color::Rgb(97, 175, 239),
+            session_name_fg: Color::Rgb(220, 223, 228),
+            session_alpha_fg: Color::Rgb(97, 175, 239),
+            session_beta_fg: Color::Rgb(198, 120, 221),
+            session_status_fg: Color::Rgb(150, 156, 167),
+            status_running_fg: Color::Rgb(152, 195, 121),
+            status_paused_fg: Color::Rgb(229, 192, 123),
+            selection_bg: Color::Rgb(62, 68, 81),
+            status_message_fg: Color::Rgb(229, 192, 123),
+            status_error_fg: Color::Rgb(224, 108, 117),
+            help_key_fg: Color::Rgb(86, 182, 194),
+            help_text_fg: Color::Rgb(220, 223, 228),
+            project_row_bg: Some(Color::Rgb(40, 44, 52)),
+            progress_start: Color::Rgb(229, 192, 123),
+            progress_end: Color::Rgb(152, 195, 121),
+            depth,
+        }
+        .adapted()
+    }
+
+    /// Light scheme with a richer palette when the terminal supports it.
+    pub fn light_for(depth: ColorDepth) -> Self {
+        if depth == ColorDepth::Basic {
+            return Self::light();
+        }
+        Self {
+            header_fg: Color::Rgb(1, 132, 188),
+            session_name_fg: Color::Rgb(56, 58, 66),
+            session_alpha_fg: Color::Rgb(64, 120, 242),
+            session_beta_fg: Color::Rgb(166, 38, 164),
+            session_status_fg: Color::Rgb(96, 99, 108),
+            status_running_fg: Color::Rgb(80, 161, 79),
+            status_paused_fg: Color::Rgb(193, 132, 1),
+            selection_bg: Color::Rgb(215, 218, 224),
+            status_message_fg: Color::Rgb(193, 132, 1),
+            status_error_fg: Color::Rgb(228, 86, 73),
+            help_key_fg: Color::Rgb(1, 132, 188),
+            help_text_fg: Color::Rgb(56, 58, 66),
+            project_row_bg: Some(Color::Rgb(240, 240, 241)),
+            progress_start: Color::Rgb(193, 132, 1),
+            progress_end: Color::Rgb(80, 161, 79),
+            depth,
+        }
+        .adapted()
+    }
+
+    /// Replace colors by field name, as configured for the custom theme.
+    ///
+    /// Values are `#rrggbb`, a color name such as `lightblue`, or a 256-color
+    /// index. Fields that aren't given keep this scheme's color.
+    pub fn with_colors(mut self, colors: &BTreeMap<String, String>) -> Result<Self> {
+        for (name, value) in colors {
+            let Ok(color) = Color::from_str(value.trim()) else {
+                bail!("Invalid color for {}: {:?}", name, value);
+            };
+            match name.as_str() {
+                "header_fg" => self.header_fg = color,
+                "session_name_fg" => self.session_name_fg = color,
+                "session_alpha_fg" => self.session_alpha_fg = color,
+                "session_beta_fg" => self.session_beta_fg = color,
+                "session_status_fg" => self.session_status_fg = color,
+                "status_running_fg" => self.status_running_fg = color,
+                "status_paused_fg" => self.status_paused_fg = color,
+                "selection_bg" => self.selection_bg = color,
+                "status_message_fg" => self.status_message_fg = color,
+                "status_error_fg" => self.status_error_fg = color,
+                "help_key_fg" => self.help_key_fg = color,
+                "help_text_fg" => self.help_text_fg = color,
+                "project_row_bg" => self.project_row_bg = Some(color),
+                "progress_start" => self.progress_start = color,
+                "progress_end" => self.progress_end = color,
+                _ => bail!("Unknown theme color: {}", name),
+            }
+        }
+        Ok(self.adapted())
+    }
+
+    /// Convert every RGB color to the closest color the scheme's depth can show.
+    fn adapted(mut self) -> Self {
+        let depth = self.depth;
+        for color in [
+            &mut self.header_fg,
+            &mut self.session_name_fg,
+            &mut self.session_alpha_fg,
+            &mut self.session_beta_fg,
+            &mut self.session_status_fg,
+            &mut self.status_running_fg,
+            &mut self.status_paused_fg,
+            &mut self.selection_bg,
+            &mut self.status_message_fg,
+            &mut self.status_error_fg,
+            &mut self.help_key_fg,
+            &mut self.help_text_fg,
+        ] {
+            *color = depth.adapt(*color);
+        }
+        // Progress endpoints stay RGB so `progress_color` can interpolate
+        // before adapting the result.
+        self.project_row_bg = self.project_row_bg.map(|c| depth.adapt(c));
+        self
+    }
+
+    /// Color for a progress bar cell at `fraction` (0.0-1.0) of the bar.
+    ///
+    /// Truecolor and 256-color terminals get a gradient between
+    /// `progress_start` and `progress_end`, in palette steps on 256-color
+    /// ones; 16-color terminals switch from start to end halfway.
+    pub fn progress_color(&self, fraction: f64) -> Color {
+        let fraction = fraction.clamp(0.0, 1.0);
+        match (self.depth, self.progress_start, self.progress_end) {
+            (depth, Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) if depth > ColorDepth::Basic => {
+                let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * fraction).round() as u8;
+                depth.adapt(Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2)))
+            }
+            (depth, start, _) if fraction < 0.5 => depth.adapt(start),
+            (depth, _, end) => depth.adapt(end),
         }
     }
 }
 
-pub fn detect_theme() -> ColorScheme {
+pub fn detect_theme(depth: ColorDepth) -> ColorScheme {
     match terminal_light::luma() {
-        Ok(luma) if luma > 0.6 => ColorScheme::light(),
-        Ok(_) => ColorScheme::dark(),
-        Err(_) => ColorScheme::dark(),
+        Ok(luma) if luma > 0.6 => ColorScheme::light_for(depth),
+        Ok(_) => ColorScheme::dark_for(depth),
+        Err(_) => ColorScheme::dark_for(depth),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_depth_from_env() {
+        assert_eq!(
+            ColorDepth::from_env(Some("truecolor"), Some("xterm-256color")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(ColorDepth::from_env(Some("24bit"), None), ColorDepth::TrueColor);
+        assert_eq!(
+            ColorDepth::from_env(None, Some("screen-256color")),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(ColorDepth::from_env(None, Some("xterm")), ColorDepth::Basic);
+        assert_eq!(ColorDepth::from_env(None, None), ColorDepth::Basic);
+    }
+
+    #[test]
+    fn test_basic_depth_uses_classic_schemes() {
+        let scheme = ColorScheme::dark_for(ColorDepth::Basic);
+        assert_eq!(scheme.header_fg, Color::Cyan);
+        assert!(scheme.project_row_bg.is_none());
+    }
+
+    #[test]
+    fn test_ansi256_scheme_has_no_rgb_colors() {
+        let scheme = ColorScheme::light_for(ColorDepth::Ansi256);
+        assert!(matches!(scheme.header_fg, Color::Indexed(_)));
+        assert!(matches!(scheme.project_row_bg, Some(Color::Indexed(_))));
+        assert!(matches!(scheme.progress_color(0.3), Color::Indexed(_)));
+    }
+
+    #[test]
+    fn test_truecolor_progress_gradient() {
+        let scheme = ColorScheme::dark_for(ColorDepth::TrueColor);
+        assert_eq!(scheme.progress_color(0.0), scheme.progress_start);
+        assert_eq!(scheme.progress_color(1.0), scheme.progress_end);
+        let mid = scheme.progress_color(0.5);
+        assert_ne!(mid, scheme.progress_start);
+        assert_ne!(mid, scheme.progress_end);
+    }
+
+    #[test]
+    fn test_basic_progress_steps() {
+        let scheme = ColorScheme::dark();
+        assert_eq!(scheme.progress_color(0.2), Color::Yellow);
+        assert_eq!(scheme.progress_color(0.8), Color::Green);
+
+        // RGB endpoints, as in the light scheme or from a custom theme, are
+        // mapped onto the 16 colors too
+        let scheme = ColorScheme::light();
+        assert!(matches!(scheme.progress_start, Color::Rgb(..)));
+        assert!(!matches!(scheme.progress_color(0.2), Color::Rgb(..)));
+        assert!(!matches!(scheme.progress_color(0.8), Color::Rgb(..)));
+    }
+
+    #[test]
+    fn test_with_colors() {
+        let colors: BTreeMap<String, String> = [
+            ("header_fg", "#268bd2"),
+            ("selection_bg", "black"),
+            ("project_row_bg", "236"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let scheme = ColorScheme::light_for(ColorDepth::TrueColor)
+            .with_colors(&colors)
+            .unwrap();
+        assert_eq!(scheme.header_fg, Color::Rgb(0x26, 0x8b, 0xd2));
+        assert_eq!(scheme.selection_bg, Color::Black);
+        assert_eq!(scheme.project_row_bg, Some(Color::Indexed(236)));
+        // Unset fields keep the base scheme's colors
+        assert_eq!(
+            scheme.status_error_fg,
+            ColorScheme::light_for(ColorDepth::TrueColor).status_error_fg
+        );
+
+        // Hex colors are adapted to the terminal's depth
+        let scheme = ColorScheme::dark_for(ColorDepth::Ansi256)
+            .with_colors(&colors)
+            .unwrap();
+        assert!(matches!(scheme.header_fg, Color::Indexed(_)));
+    }
+
+    #[test]
+    fn test_with_colors_rejects_bad_entries() {
+        let colors = |k: &str, v: &str| BTreeMap::from([(k.to_string(), v.to_string())]);
+        assert!(ColorScheme::dark()
+            .with_colors(&colors("header_fg", "not-a-color"))
+            .is_err());
+        assert!(ColorScheme::dark()
+            .with_colors(&colors("header", "red"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_rgb_to_ansi256() {
+        assert_eq!(rgb_to_ansi256(0, 0, 0), 16);
+        assert_eq!(rgb_to_ansi256(255, 255, 255), 231);
+        assert_eq!(rgb_to_ansi256(255, 0, 0), 196);
     }
 }
@@ -0,0 +1,273 @@
+//! Transfer-rate estimation for staging sessions.
+//!
+//! Mutagen reports how many bytes an endpoint has received while staging,
+//! but not how fast. A short history of snapshots is kept per session and
+//! the rate is derived from the change in received bytes across it, which
+//! smooths out refreshes that land between files.
+
+use crate::mutagen::{StagingProgress, SyncSession};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Number of staging snapshots kept per session.
+pub const TRANSFER_HISTORY_LEN: usize = 5;
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    received: u64,
+    /// Estimated bytes still to stage, if it can be derived.
+    remaining: Option<u64>,
+    at: Instant,
+}
+
+/// Recent staging snapshots per session identifier.
+#[derive(Debug, Default)]
+pub struct TransferTracker {
+    history: HashMap<String, VecDeque<Sample>>,
+}
+
+impl TransferTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the staging progress of every session from a refresh.
+    pub fn observe(&mut self, sessions: &[SyncSession]) {
+        self.observe_at(sessions, Instant::now());
+    }
+
+    /// Record the staging progress of some sessions, as listed by a refresh
+    /// scoped to one project, keeping the history of all others.
+    pub fn observe_subset(&mut self, sessions: &[SyncSession]) {
+        self.observe_subset_at(sessions, Instant::now());
+    }
+
+    fn observe_subset_at(&mut self, sessions: &[SyncSession], now: Instant) {
+        let listed: HashSet<&str> = sessions.iter().map(|s| s.identifier.as_str()).collect();
+        let (mine, others): (HashMap<_, _>, HashMap<_, _>) = std::mem::take(&mut self.history)
+            .into_iter()
+            .partition(|(id, _)| listed.contains(id.as_str()));
+        self.history = mine;
+        self.observe_at(sessions, now);
+        self.history.extend(others);
+    }
+
+    fn observe_at(&mut self, sessions: &[SyncSession], now: Instant) {
+        let mut history = HashMap::new();
+
+        for session in sessions {
+            let Some(progress) = session.staging_progress() else {
+                continue;
+            };
+            let Some(received) = progress.bytes_received() else {
+                continue;
+            };
+
+            let mut samples = self.history.remove(&session.identifier).unwrap_or_default();
+            // A drop in received bytes means staging restarted; old samples no longer apply
+            if samples.back().is_some_and(|last| received < last.received) {
+                samples.clear();
+            }
+            samples.push_back(Sample {
+                received,
+                remaining: estimate_remaining(progress),
+                at: now,
+            });
+            while samples.len() > TRANSFER_HISTORY_LEN {
+                samples.pop_front();
+            }
+            history.insert(session.identifier.clone(), samples);
+        }
+
+        // Sessions that stopped staging are dropped
+        self.history = history;
+    }
+
+    /// Bytes per second averaged over the recorded history.
+    pub fn rate(&self, identifier: &str) -> Option<f64> {
+        let samples = self.history.get(identifier)?;
+        let (first, last) = (samples.front()?, samples.back()?);
+        let secs = last.at.duration_since(first.at).as_secs_f64();
+        if secs > 0.0 {
+            Some((last.received - first.received) as f64 / secs)
+        } else {
+            None
+        }
+    }
+
+    /// Estimated time until staging finishes at the current rate.
+    pub fn eta(&self, identifier: &str) -> Option<Duration> {
+        let remaining = self.history.get(identifier)?.back()?.remaining?;
+        let rate = self.rate(identifier)?;
+        if rate > 0.0 {
+            Some(Duration::from_secs_f64(remaining as f64 / rate))
+        } else {
+            None
+        }
+    }
+}
+
+/// Estimate bytes left to stage.
+///
+/// Mutagen only reports the expected size of the current file, so the total
+/// is extrapolated from the average size of the files received so far.
+fn estimate_remaining(progress: &StagingProgress) -> Option<u64> {
+    let received = progress.bytes_received()?;
+    match (progress.received_files, progress.expected_files) {
+        (Some(done), Some(total)) if done > 0 && total >= done => {
+            let estimated_total = received.saturating_mul(total) / done;
+            Some(estimated_total.saturating_sub(received))
+        }
+        _ => {
+            let expected = progress.expected_size?;
+            Some(expected.saturating_sub(progress.received_size.unwrap_or(0)))
+        }
+    }
+}
+
+/// Format a byte count with a binary unit: "512 B", "12.3 KB", "4.0 MB".
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Format a transfer rate: "1.2 MB/s".
+pub fn format_rate(bytes_per_sec: f64) -> String {
+    format!("{}/s", format_bytes(bytes_per_sec.max(0.0) as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutagen::StagingProgress;
+    use std::time::Duration;
+
+    fn staging_session(received: Option<u64>) -> SyncSession {
+        let mut session = SyncSession::named("web").with_status("staging-beta");
+        session.beta.staging_progress = received.map(|bytes| StagingProgress {
+            path: Some("big.bin".to_string()),
+            received_size: Some(bytes),
+            expected_size: Some(10_000_000),
+            received_files: Some(1),
+            expected_files: Some(4),
+            total_received_size: Some(bytes),
+        });
+        session
+    }
+
+    #[test]
+    fn test_rate_from_consecutive_refreshes() {
+        let mut tracker = TransferTracker::new();
+        let start = Instant::now();
+
+        tracker.observe_at(&[staging_session(Some(1_000_000))], start);
+        assert_eq!(tracker.rate("sync_web"), None);
+
+        tracker.observe_at(
+            &[staging_session(Some(3_000_000))],
+            start + Duration::from_secs(2),
+        );
+        assert_eq!(tracker.rate("sync_web"), Some(1_000_000.0));
+    }
+
+    #[test]
+    fn test_observe_subset_keeps_other_sessions() {
+        let mut tracker = TransferTracker::new();
+        let start = Instant::now();
+        let mut api = staging_session(Some(0));
+        api.identifier = "sync_api".to_string();
+
+        tracker.observe_at(&[staging_session(Some(0)), api.clone()], start);
+        tracker.observe_subset_at(
+            &[staging_session(Some(2_000))],
+            start + Duration::from_secs(2),
+        );
+
+        assert_eq!(tracker.rate("sync_web"), Some(1_000.0));
+        // Not listed: its history is neither dropped nor padded with a stale sample
+        assert_eq!(tracker.history["sync_api"].len(), 1);
+    }
+
+    #[test]
+    fn test_rate_averages_over_history() {
+        let mut tracker = TransferTracker::new();
+        let start = Instant::now();
+
+        for (secs, bytes) in [(0, 0), (1, 3_000), (2, 3_000), (3, 6_000)] {
+            tracker.observe_at(
+                &[staging_session(Some(bytes))],
+                start + Duration::from_secs(secs),
+            );
+        }
+        assert_eq!(tracker.rate("sync_web"), Some(2_000.0));
+    }
+
+    #[test]
+    fn test_history_is_bounded_and_reset_on_restart() {
+        let mut tracker = TransferTracker::new();
+        let start = Instant::now();
+
+        for i in 0..10u64 {
+            tracker.observe_at(
+                &[staging_session(Some(i * 100))],
+                start + Duration::from_secs(i),
+            );
+        }
+        assert_eq!(tracker.history["sync_web"].len(), TRANSFER_HISTORY_LEN);
+
+        tracker.observe_at(
+            &[staging_session(Some(10))],
+            start + Duration::from_secs(11),
+        );
+        assert_eq!(tracker.history["sync_web"].len(), 1);
+        assert_eq!(tracker.rate("sync_web"), None);
+    }
+
+    #[test]
+    fn test_rate_cleared_when_staging_ends() {
+        let mut tracker = TransferTracker::new();
+        let start = Instant::now();
+
+        tracker.observe_at(&[staging_session(Some(0))], start);
+        tracker.observe_at(
+            &[staging_session(Some(500))],
+            start + Duration::from_secs(1),
+        );
+        assert!(tracker.rate("sync_web").is_some());
+
+        tracker.observe_at(&[staging_session(None)], start + Duration::from_secs(2));
+        assert_eq!(tracker.rate("sync_web"), None);
+    }
+
+    #[test]
+    fn test_eta_extrapolates_from_file_counts() {
+        let mut tracker = TransferTracker::new();
+        let start = Instant::now();
+
+        // 1 of 4 files received: 1 MB so far suggests ~3 MB remaining
+        tracker.observe_at(&[staging_session(Some(0))], start);
+        tracker.observe_at(
+            &[staging_session(Some(1_000_000))],
+            start + Duration::from_secs(1),
+        );
+        assert_eq!(tracker.eta("sync_web"), Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MB");
+        assert_eq!(format_rate(2048.0), "2.0 KB/s");
+    }
+}
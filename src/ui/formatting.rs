@@ -0,0 +1,240 @@
+//! Text formatting shared by the views.
+
+use crate::app::App;
+use crate::audit::AuditEntry;
+use crate::endpoint::EndpointAddress;
+use crate::i18n::t;
+use crate::mutagen::{Endpoint, HostReachability, SyncSession, SyncTime};
+use crate::schedule::DataSource;
+use crate::transfer::format_bytes;
+use chrono::Local;
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::ListItem,
+};
+
+/// Safely truncate a digest string to 8 characters, or return the whole string if shorter.
+/// Prevents panics when Mutagen returns unexpectedly short digest values.
+fn truncate_digest(digest: &str) -> &str {
+    if digest.len() >= 8 {
+        &digest[..8]
+    } else {
+        digest
+    }
+}
+
+/// Format a duration compactly: "45s", "3m", "2h".
+pub(super) fn format_elapsed(elapsed: std::time::Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h", secs / 3600)
+    }
+}
+
+/// Format a session's age with its two largest units: "2d 4h", "3h 12m", "5m".
+pub(super) fn format_age(age: std::time::Duration) -> String {
+    let mins = age.as_secs() / 60;
+    let (days, hours, mins) = (mins / 1440, mins / 60 % 24, mins % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else {
+        format!("{}m", mins)
+    }
+}
+
+/// " +12 more" for the items mutagen left out of a list it cut short, or
+/// nothing if it listed them all.
+pub(super) fn format_excluded(excluded: u64) -> String {
+    if excluded == 0 {
+        String::new()
+    } else {
+        format!(" {}", t!("view.excluded", count = excluded))
+    }
+}
+
+/// When a session last synced, as far as mutagui has seen: "synced 3m ago".
+pub(super) fn format_last_sync(sync_time: &SyncTime) -> String {
+    match sync_time {
+        SyncTime::At(at) => {
+            let elapsed = Local::now().signed_duration_since(*at);
+            t!(
+                "view.synced_ago",
+                elapsed = format_elapsed(elapsed.to_std().unwrap_or_default())
+            )
+        }
+        SyncTime::Never => t!("view.never_synced").to_string(),
+        SyncTime::Unknown => t!("view.not_seen_syncing").to_string(),
+    }
+}
+
+/// Format a FileState for display, handling None (deleted/new files) and Some cases.
+pub(super) fn format_file_state(state: &Option<crate::mutagen::FileState>) -> String {
+    match state {
+        Some(fs) => match &fs.digest {
+            Some(digest) => format!("{} ({})", truncate_digest(digest), fs.kind),
+            None => fs.kind.clone(),
+        },
+        None => "-".to_string(),
+    }
+}
+
+/// Number of rows `text` takes when wrapped to `width` columns.
+pub(super) fn wrapped_line_count(text: &str, width: u16) -> usize {
+    let width = width.max(1) as usize;
+    text.lines()
+        .map(|line| textwrap::wrap(line, width).len().max(1))
+        .sum()
+}
+
+/// A "Label     value" line in the details pane.
+pub(super) fn detail_field(app: &App, label: &str, value: impl Into<String>) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!("{:<11}", label),
+            Style::default().fg(app.color_scheme.help_key_fg),
+        ),
+        Span::styled(
+            value.into(),
+            Style::default().fg(app.color_scheme.session_status_fg),
+        ),
+    ])
+}
+
+/// Free space on a session's endpoints, e.g. "alpha 51.2 GB, beta 3.1 GB (2m ago)".
+pub(super) fn disk_space_summary(app: &App, session: &SyncSession) -> Option<String> {
+    let readings: Vec<_> = [("alpha", &session.alpha), ("beta", &session.beta)]
+        .into_iter()
+        .filter_map(|(side, endpoint)| {
+            app.disk_space
+                .get(&endpoint.display_path())
+                .map(|reading| (side, reading))
+        })
+        .collect();
+    let oldest = readings.iter().map(|(_, reading)| reading.at).min()?;
+    let free: Vec<String> = readings
+        .iter()
+        .map(|(side, reading)| format!("{} {}", side, format_bytes(reading.value.available_bytes)))
+        .collect();
+    let age = format_elapsed(oldest.elapsed());
+    let when = if app.scheduler.is_stale(DataSource::DiskSpace, oldest) {
+        format!("stale, {} ago", age)
+    } else {
+        format!("{} ago", age)
+    };
+    Some(format!("{} ({})", free.join(", "), when))
+}
+
+/// How the last reachability check of an endpoint's ssh host went, e.g.
+/// " (unreachable: Connection timed out, 2m ago)". None for endpoints that
+/// aren't over ssh or whose host hasn't been checked.
+pub(super) fn host_check_span(app: &App, host: Option<&str>) -> Option<Span<'static>> {
+    let theme = &app.color_scheme;
+    let reading = app.host_checks.get(host?)?;
+    let age = format_elapsed(reading.at.elapsed());
+    let (text, color) = match &reading.value {
+        HostReachability::Reachable => (
+            format!(" (reachable, {} ago)", age),
+            theme.status_running_fg,
+        ),
+        HostReachability::NeedsAuthentication(_) => (
+            format!(" (needs a password or key, {} ago)", age),
+            theme.status_paused_fg,
+        ),
+        HostReachability::Unreachable(error) => (
+            format!(" (unreachable: {}, {} ago)", error, age),
+            theme.status_error_fg,
+        ),
+    };
+    Some(Span::styled(text, Style::default().fg(color)))
+}
+
+/// The ssh host and port of a session endpoint.
+pub(super) fn session_ssh_host(endpoint: &Endpoint) -> Option<(String, Option<u16>)> {
+    match (endpoint.protocol.as_str(), &endpoint.host) {
+        ("ssh", Some(host)) => Some((host.clone(), endpoint.port)),
+        _ => None,
+    }
+}
+
+/// The ssh host and port of an endpoint as a project file gives it.
+pub(super) fn definition_ssh_host(endpoint: &str) -> Option<(String, Option<u16>)> {
+    match EndpointAddress::parse(endpoint) {
+        EndpointAddress::Ssh { host, port, .. } => Some((host, port)),
+        _ => None,
+    }
+}
+
+/// Where ssh really connects for the hosts that `~/.ssh/config` points
+/// elsewhere, e.g. "dev → devbox.example.com:2222". None if every host is
+/// used as written.
+pub(super) fn resolved_hosts(app: &App, hosts: &[(String, Option<u16>)]) -> Option<String> {
+    let mut resolved: Vec<String> = Vec::new();
+    for (host, port) in hosts {
+        let target = app.mutagen_client.ssh_config().target(host, *port);
+        let line = format!("{} {} {}", host, app.icons.get("→"), target);
+        if target != *host && !resolved.contains(&line) {
+            resolved.push(line);
+        }
+    }
+    (!resolved.is_empty()).then(|| resolved.join(", "))
+}
+
+/// One row per audit log entry: time, user, action, target, and outcome.
+pub(super) fn audit_items(app: &App, entries: &[AuditEntry]) -> Vec<ListItem<'static>> {
+    entries
+        .iter()
+        .map(|entry| {
+            // "2026-10-16T12:34:56+02:00" → "2026-10-16 12:34:56"
+            let time = entry
+                .timestamp
+                .get(..19)
+                .unwrap_or(&entry.timestamp)
+                .replacen('T', " ", 1);
+            let outcome = match &entry.error {
+                None => Span::styled(
+                    app.icons.get("✓"),
+                    Style::default().fg(app.color_scheme.status_running_fg),
+                ),
+                Some(error) => Span::styled(
+                    format!("{} {}", app.icons.get("✗"), error.trim()),
+                    Style::default().fg(app.color_scheme.status_error_fg),
+                ),
+            };
+            let mut lines = vec![Line::from(vec![
+                Span::styled(
+                    format!("{}  ", time),
+                    Style::default().fg(app.color_scheme.session_status_fg),
+                ),
+                Span::styled(
+                    format!("{:<10} ", entry.user),
+                    Style::default().fg(app.color_scheme.session_beta_fg),
+                ),
+                Span::styled(
+                    format!("{:<9} ", entry.action),
+                    Style::default()
+                        .fg(app.color_scheme.session_name_fg)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("{}  ", entry.target),
+                    Style::default().fg(app.color_scheme.session_alpha_fg),
+                ),
+                outcome,
+            ])];
+            lines.extend(entry.commands.iter().map(|command| {
+                Line::from(Span::styled(
+                    format!("    $ {}", command),
+                    Style::default().fg(app.color_scheme.session_status_fg),
+                ))
+            }));
+            ListItem::new(lines)
+        })
+        .collect()
+}
@@ -0,0 +1,333 @@
+//! Drawing the interface.
+//!
+//! Each part of the screen is a [`View`] that draws itself from the app
+//! state. The header and the bars along the bottom are in `status`, the
+//! body of each tab in `panel`, and popups in `overlays`; `draw` only lays
+//! them out. A new screen is a new `View` in one of these modules.
+
+mod formatting;
+mod overlays;
+mod panel;
+mod status;
+
+use crate::app::App;
+use crate::i18n::t;
+use crate::tabs::Tab;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use status::{Header, KeyHints, StagingPane, StatusBar};
+
+/// A part of the screen, drawn from the app state into `area`. Overlays are
+/// given the whole screen and center themselves in it.
+pub trait View {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect);
+}
+
+pub fn draw(f: &mut Frame, app: &App) {
+    // Build status text to calculate required height
+    let mut status_text = app
+        .status_message
+        .as_ref()
+        .map(|msg| msg.text().to_string())
+        .unwrap_or_else(|| t!("status.ready").to_string());
+
+    if let Some(command) = status::learned_command(app) {
+        status_text.push_str(&command);
+    }
+    if let Some(last_refresh) = app.last_refresh {
+        let time = last_refresh.format("%H:%M:%S");
+        let refresh_info = format!(" | {}", t!("status.last_refresh", time = time));
+        status_text.push_str(&refresh_info);
+    }
+
+    // Check if text will be clipped (more than 5 lines of content)
+    let content_width = if f.area().width > 4 {
+        (f.area().width - 4) as usize
+    } else {
+        1
+    };
+    let wrapped_lines = textwrap::wrap(&status_text, content_width);
+    let will_be_clipped = wrapped_lines.len() > 5;
+
+    if will_be_clipped {
+        // Add ellipsis indicator to the status text
+        status_text.push_str(" ...");
+    }
+
+    // Calculate dynamic status height based on message length (clamped to 3-7 lines)
+    let status_height = status::calculate_status_height(&status_text, f.area().width);
+
+    // Staging pane is only shown while the selected session is receiving files
+    let staging = status::selected_staging_session(app).filter(|_| app.tab == Tab::Sync);
+    let staging_height = if staging.is_some() { 4 } else { 0 };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(staging_height),
+            Constraint::Length(status_height),
+            Constraint::Length(3),
+        ])
+        .split(f.area());
+
+    Header.render(f, app, chunks[0]);
+    panel::body(app).render(f, app, chunks[1]);
+    if let Some(session) = staging {
+        StagingPane(session).render(f, app, chunks[2]);
+    }
+    StatusBar {
+        clipped: will_be_clipped,
+    }
+    .render(f, app, chunks[3]);
+    KeyHints.render(f, app, chunks[4]);
+
+    for overlay in overlays::open(app) {
+        overlay.render(f, app, f.area());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{BlockingOperation, TextPopup, YankMenu};
+    use crate::browse::{self, DirBrowser};
+    use crate::command::{success_output, MockCommandRunner};
+    use crate::endpoint::EndpointAddress;
+    use crate::init::Setup;
+    use crate::verify::{FileMismatch, MismatchKind, VerifyMode, VerifyReport};
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+    use serde_json::json;
+    use std::path::Path;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// An app over project `app` with specs `api` and `web`, and `docs` with
+    /// spec `site`; `api` is watching and `site` is paused.
+    async fn fixture() -> (App, TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        for (project, specs) in [("app", &["api", "web"][..]), ("docs", &["site"][..])] {
+            let project_dir = dir.path().join(project);
+            std::fs::create_dir(&project_dir).unwrap();
+            let mut yaml = String::from("sync:\n");
+            for spec in specs {
+                yaml.push_str(&format!(
+                    "  {}:\n    alpha: ./{}\n    beta: server:/srv/{}\n",
+                    spec, spec, spec
+                ));
+            }
+            std::fs::write(project_dir.join("mutagen.yml"), yaml).unwrap();
+        }
+        let sessions: Vec<_> = [("api", "Watching for changes", false), ("site", "", true)]
+            .iter()
+            .map(|(name, status, paused)| {
+                json!({
+                    "name": name,
+                    "identifier": format!("sync_{}", name),
+                    "alpha": {"protocol": "local", "path": format!("/code/{}", name), "connected": true},
+                    "beta": {"protocol": "ssh", "path": format!("/srv/{}", name), "host": "server", "connected": !paused},
+                    "mode": "two-way-safe",
+                    "status": status,
+                    "paused": paused,
+                })
+            })
+            .collect();
+        let runner = Arc::new(MockCommandRunner::new());
+        runner.expect(
+            "mutagen sync list --template {{json .}}",
+            success_output(&json!(sessions).to_string()),
+        );
+        let mut app = App::for_test(dir.path().to_path_buf(), runner, &dir.path().join("state"));
+        app.refresh_sessions().await.unwrap();
+        app.wait_for_discovery().await;
+        app.last_refresh = None;
+        (app, dir)
+    }
+
+    /// The screen as text, one line per row. The temporary `dir` is shown as
+    /// `<dir>`, padded with the spaces after it so borders stay in place.
+    fn render(app: &App, dir: &Path, width: u16, height: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| draw(f, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let dir = dir.display().to_string();
+        let mut text = String::new();
+        for y in 0..buffer.area.height {
+            let mut row: String = (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect();
+            if let Some(start) = row.find(&dir) {
+                let end = row[start..].find(' ').map_or(row.len(), |i| start + i);
+                let padding = " ".repeat(dir.len() - "<dir>".len());
+                row =
+                    format!("{}{}{}", &row[..end], padding, &row[end..]).replacen(&dir, "<dir>", 1);
+            }
+            text.push_str(row.trim_end());
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Compare the screen with `snapshots/<name>.txt`. Set `UPDATE_SNAPSHOTS`
+    /// to write the snapshot instead, after checking the change is intended.
+    fn assert_snapshot(name: &str, screen: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/ui/snapshots")
+            .join(format!("{}.txt", name));
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, screen).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("no snapshot {}; run with UPDATE_SNAPSHOTS=1", name));
+        assert_eq!(screen, expected, "screen differs from snapshot {}", name);
+    }
+
+    #[tokio::test]
+    async fn test_sync_view_snapshots() {
+        let (mut app, dir) = fixture().await;
+        assert_snapshot("sync_list", &render(&app, dir.path(), 90, 24));
+
+        app.select_next();
+        app.split_layout = true;
+        assert_snapshot("sync_details", &render(&app, dir.path(), 130, 24));
+    }
+
+    #[tokio::test]
+    async fn test_conflict_snapshots() {
+        let (mut app, dir) = fixture().await;
+        let spec = app.projects[0]
+            .specs
+            .iter_mut()
+            .find(|spec| spec.name == "api")
+            .unwrap();
+        let session = Arc::make_mut(spec.running_session.as_mut().unwrap());
+        session.conflicts = serde_json::from_value(json!([
+            {
+                "root": "build/app.o",
+                "alphaChanges": [{"path": "build/app.o", "new": {"kind": "file"}}],
+            },
+            {"root": "dist/bundle.js"},
+        ]))
+        .unwrap();
+        session.excluded_conflicts = 8;
+        session.beta.scan_problems = serde_json::from_value(json!([
+            {"path": "secrets", "error": "permission denied"},
+        ]))
+        .unwrap();
+        session.beta.excluded_scan_problems = 2;
+
+        app.select_next();
+        app.split_layout = true;
+        assert_snapshot("sync_conflicts", &render(&app, dir.path(), 130, 24));
+
+        app.split_layout = false;
+        app.toggle_conflict_view();
+        assert_snapshot("conflicts", &render(&app, dir.path(), 90, 24));
+    }
+
+    #[tokio::test]
+    async fn test_tab_snapshots() {
+        let (mut app, dir) = fixture().await;
+        for tab in [Tab::Hosts, Tab::Forward, Tab::Daemon, Tab::Log] {
+            app.tab = tab;
+            let name = format!("tab_{}", tab.title().to_lowercase());
+            assert_snapshot(&name, &render(&app, dir.path(), 90, 16));
+        }
+    }
+
+    /// An app over `dir` with no sessions running.
+    async fn app_without_sessions(dir: &Path) -> App {
+        let runner = Arc::new(MockCommandRunner::new());
+        runner.expect(
+            "mutagen sync list --template {{json .}}",
+            success_output("[]"),
+        );
+        let mut app = App::for_test(dir.to_path_buf(), runner, &dir.join("state"));
+        app.refresh_sessions().await.unwrap();
+        app.wait_for_discovery().await;
+        app.last_refresh = None;
+        app
+    }
+
+    #[tokio::test]
+    async fn test_empty_and_error_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = app_without_sessions(dir.path()).await;
+        assert_snapshot("empty", &render(&app, dir.path(), 90, 20));
+
+        // With no config file, setup is offered
+        app.setup = Some(Setup {
+            config: dir.path().join("config.toml"),
+            project: dir.path().join("mutagen.yml"),
+        });
+        assert_snapshot("setup", &render(&app, dir.path(), 90, 20));
+        app.run_setup();
+        assert!(app.setup.is_none());
+        assert!(dir.path().join("config.toml").exists());
+        assert!(app.status_message.as_ref().unwrap().text().starts_with("Wrote "));
+
+        std::fs::write(dir.path().join("mutagen.yml"), "sync: [\n").unwrap();
+        let app = app_without_sessions(dir.path()).await;
+        assert_snapshot("project_errors", &render(&app, dir.path(), 90, 20));
+    }
+
+    #[tokio::test]
+    async fn test_overlay_snapshots() {
+        let (mut app, dir) = fixture().await;
+        app.open_wizard();
+        assert_snapshot("wizard", &render(&app, dir.path(), 90, 30));
+        app.wizard = None;
+
+        app.text_popup = Some(TextPopup::new(
+            " Status ",
+            "Line one\nLine two",
+            app.color_scheme.status_error_fg,
+        ));
+        assert_snapshot("text_popup", &render(&app, dir.path(), 90, 24));
+        app.text_popup = None;
+
+        app.verify_report = Some(VerifyReport {
+            session_name: "api".to_string(),
+            mode: VerifyMode::Full,
+            checked: 12,
+            mismatches: vec![FileMismatch {
+                path: "src/main.rs".to_string(),
+                kind: MismatchKind::Differs,
+            }],
+        });
+        assert_snapshot("verify_report", &render(&app, dir.path(), 90, 24));
+        app.verify_report = None;
+
+        let mut browser = DirBrowser::new(
+            "api",
+            EndpointAddress::parse("/code/api"),
+            EndpointAddress::parse("server:/srv/api"),
+        );
+        browser.show(Ok(browse::parse_ls("README.md\nsrc/\n")), Some("README.md"));
+        app.browser = Some(browser);
+        assert_snapshot("browser", &render(&app, dir.path(), 90, 24));
+        app.browser = None;
+
+        app.yank_menu = Some(YankMenu {
+            spec_name: "api".to_string(),
+            items: vec![
+                ("Alpha path".to_string(), 'a', "/code/api".to_string()),
+                ("Beta endpoint".to_string(), 'b', "server:/srv/api".to_string()),
+                ("Session identifier".to_string(), 'i', "sync_api".to_string()),
+            ],
+            selected: 1,
+        });
+        assert_snapshot("yank_menu", &render(&app, dir.path(), 90, 24));
+        app.yank_menu = None;
+
+        app.blocking_op = Some(BlockingOperation::new("Pausing project app"));
+        assert_snapshot("blocking", &render(&app, dir.path(), 90, 24));
+    }
+}
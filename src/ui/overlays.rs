@@ -0,0 +1,1023 @@
+//! Popups drawn over the rest of the screen.
+
+use crate::app::{
+    App, BlockingOperation, PendingReset, TaskMenu, TextPopup, YankMenu, RESET_CONFIRMATION,
+};
+use crate::audit::AuditEntry;
+use crate::browse::DirBrowser;
+use crate::command::printable;
+use crate::i18n::t;
+use crate::ignores::IgnoreEditor;
+use crate::input::TextInput;
+use crate::project::VCS_DIRECTORIES;
+use crate::verify::{MismatchKind, VerifyReport};
+use crate::wizard::{SessionWizard, WizardField, WizardTarget, MODES};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use super::formatting::{audit_items, format_excluded, format_file_state, wrapped_line_count};
+use super::View;
+
+/// The overlays that are open, in the order they're drawn.
+pub fn open(app: &App) -> Vec<Box<dyn View + '_>> {
+    let mut views: Vec<Box<dyn View + '_>> = Vec::new();
+    if app.viewing_conflicts {
+        views.push(Box::new(ConflictDetail));
+    }
+    if let Some(entries) = &app.audit_view {
+        views.push(Box::new(AuditLog(entries)));
+    }
+    if let Some(report) = &app.verify_report {
+        views.push(Box::new(VerifyResults(report)));
+    }
+    if let Some(menu) = &app.task_menu {
+        views.push(Box::new(TaskMenuView(menu)));
+    }
+    if let Some(menu) = &app.yank_menu {
+        views.push(Box::new(YankMenuView(menu)));
+    }
+    if let Some(browser) = &app.browser {
+        views.push(Box::new(BrowserView(browser)));
+    }
+    if let Some(popup) = &app.text_popup {
+        views.push(Box::new(Popup(popup)));
+    }
+    if let Some(wizard) = &app.wizard {
+        views.push(Box::new(WizardForm(wizard)));
+    }
+    if let Some(editor) = &app.ignore_editor {
+        views.push(Box::new(IgnoreEditorView(editor)));
+    }
+    if let Some(pending) = &app.pending_reset {
+        views.push(Box::new(ResetConfirmation(pending)));
+    }
+    // Last, since it blocks input to everything else
+    if let Some(blocking_op) = &app.blocking_op {
+        views.push(Box::new(BlockingModal(blocking_op)));
+    }
+    views
+}
+
+/// The selected session's conflicts, with both sides of each change.
+pub struct ConflictDetail;
+
+impl View for ConflictDetail {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        use ratatui::layout::{Alignment, Margin};
+
+        // Create a centered overlay area (80% width, 80% height)
+        let overlay_width = (area.width as f32 * 0.8) as u16;
+        let overlay_height = (area.height as f32 * 0.8) as u16;
+        let overlay_x = (area.width - overlay_width) / 2;
+        let overlay_y = (area.height - overlay_height) / 2;
+
+        let overlay_area = Rect {
+            x: overlay_x,
+            y: overlay_y,
+            width: overlay_width,
+            height: overlay_height,
+        };
+
+        // Clear the overlay area with a background
+        let overlay_block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(app.color_scheme.help_key_fg))
+            .title(t!("title.conflicts"))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().bg(app.color_scheme.selection_bg));
+
+        // Position indicator; the list clamps the selection the same way when drawn
+        let overlay_block = match app.get_selected_spec_conflicts().filter(|c| !c.is_empty()) {
+            Some(conflicts) => {
+                let selected = app
+                    .conflict_list
+                    .borrow()
+                    .selected()
+                    .unwrap_or(0)
+                    .min(conflicts.len() - 1);
+                let excluded = app
+                    .get_selected_spec()
+                    .and_then(|(p, s)| app.projects[p].specs[s].running_session.as_ref())
+                    .map_or(0, |session| session.excluded_conflicts);
+                overlay_block.title_bottom(
+                    Line::from(format!(
+                        " {} of {}{} ",
+                        selected + 1,
+                        conflicts.len(),
+                        format_excluded(excluded)
+                    ))
+                    .right_aligned(),
+                )
+            }
+            None => overlay_block,
+        };
+
+        f.render_widget(overlay_block, overlay_area);
+
+        // Draw conflict list inside the overlay
+        let inner_area = overlay_area.inner(Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+
+        if let Some(conflicts) = app.get_selected_spec_conflicts() {
+            if conflicts.is_empty() {
+                let no_conflicts = Paragraph::new(t!("view.no_conflicts"))
+                    .style(Style::default().fg(app.color_scheme.session_status_fg))
+                    .alignment(Alignment::Center);
+                f.render_widget(no_conflicts, inner_area);
+            } else {
+                let conflict_items: Vec<ListItem> = conflicts
+                    .iter()
+                    .map(|conflict| {
+                        let mut lines = vec![Line::from(vec![
+                            Span::styled(
+                                format!("{}: ", t!("view.root")),
+                                Style::default()
+                                    .fg(app.color_scheme.session_name_fg)
+                                    .add_modifier(Modifier::BOLD),
+                            ),
+                            Span::styled(
+                                printable(&conflict.root),
+                                Style::default().fg(app.color_scheme.session_alpha_fg),
+                            ),
+                        ])];
+
+                        if !conflict.alpha_changes.is_empty() {
+                            lines.push(Line::from(vec![Span::styled(
+                                "  Alpha changes:",
+                                Style::default()
+                                    .fg(app.color_scheme.session_name_fg)
+                                    .add_modifier(Modifier::BOLD),
+                            )]));
+                            for change in &conflict.alpha_changes {
+                                lines.push(Line::from(vec![
+                                    Span::raw("    "),
+                                    Span::styled(
+                                        printable(&change.path),
+                                        Style::default().fg(app.color_scheme.session_alpha_fg),
+                                    ),
+                                ]));
+
+                                // Format the change description, handling optional FileState
+                                let old_str = format_file_state(&change.old);
+                                let new_str = format_file_state(&change.new);
+
+                                lines.push(Line::from(vec![
+                                    Span::raw("      "),
+                                    Span::styled(
+                                        old_str,
+                                        Style::default().fg(app.color_scheme.session_status_fg),
+                                    ),
+                                    Span::raw(format!(" {} ", app.icons.get("→"))),
+                                    Span::styled(
+                                        new_str,
+                                        Style::default().fg(app.color_scheme.session_status_fg),
+                                    ),
+                                ]));
+                            }
+                        }
+
+                        if !conflict.beta_changes.is_empty() {
+                            lines.push(Line::from(vec![Span::styled(
+                                "  Beta changes:",
+                                Style::default()
+                                    .fg(app.color_scheme.session_name_fg)
+                                    .add_modifier(Modifier::BOLD),
+                            )]));
+                            for change in &conflict.beta_changes {
+                                lines.push(Line::from(vec![
+                                    Span::raw("    "),
+                                    Span::styled(
+                                        printable(&change.path),
+                                        Style::default().fg(app.color_scheme.session_beta_fg),
+                                    ),
+                                ]));
+
+                                // Format the change description, handling optional FileState
+                                let old_str = format_file_state(&change.old);
+                                let new_str = format_file_state(&change.new);
+
+                                lines.push(Line::from(vec![
+                                    Span::raw("      "),
+                                    Span::styled(
+                                        old_str,
+                                        Style::default().fg(app.color_scheme.session_status_fg),
+                                    ),
+                                    Span::raw(format!(" {} ", app.icons.get("→"))),
+                                    Span::styled(
+                                        new_str,
+                                        Style::default().fg(app.color_scheme.session_status_fg),
+                                    ),
+                                ]));
+                            }
+                        }
+
+                        lines.push(Line::from(""));
+
+                        ListItem::new(lines)
+                    })
+                    .collect();
+
+                let highlight_symbol = format!("{} ", app.icons.get("▶"));
+                let conflict_list = List::new(conflict_items)
+                    .block(Block::default())
+                    .highlight_symbol(&highlight_symbol)
+                    .highlight_style(Style::default().add_modifier(Modifier::BOLD));
+                f.render_stateful_widget(
+                    conflict_list,
+                    inner_area,
+                    &mut app.conflict_list.borrow_mut(),
+                );
+            }
+        } else {
+            let error = Paragraph::new(t!("view.no_session"))
+                .style(Style::default().fg(app.color_scheme.session_status_fg))
+                .alignment(Alignment::Center);
+            f.render_widget(error, inner_area);
+        }
+    }
+}
+
+/// Recent actions from the audit log.
+pub struct AuditLog<'a>(pub &'a [AuditEntry]);
+
+impl View for AuditLog<'_> {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        use ratatui::layout::{Alignment, Margin};
+        use ratatui::widgets::Clear;
+
+        let entries = self.0;
+        let overlay_width = (area.width as f32 * 0.8) as u16;
+        let overlay_height = (area.height as f32 * 0.8) as u16;
+        let overlay_area = Rect {
+            x: (area.width - overlay_width) / 2,
+            y: (area.height - overlay_height) / 2,
+            width: overlay_width,
+            height: overlay_height,
+        };
+
+        f.render_widget(Clear, overlay_area);
+
+        let overlay_block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(app.color_scheme.help_key_fg))
+            .title(t!("title.audit_log"))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().bg(app.color_scheme.selection_bg));
+
+        f.render_widget(overlay_block, overlay_area);
+
+        let inner_area = overlay_area.inner(Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+
+        if entries.is_empty() {
+            let location = app
+                .audit_log
+                .path()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "no state directory".to_string());
+            let empty = Paragraph::new(format!("No actions recorded yet\n\n{}", location))
+                .style(Style::default().fg(app.color_scheme.session_status_fg))
+                .alignment(Alignment::Center);
+            f.render_widget(empty, inner_area);
+            return;
+        }
+
+        f.render_widget(List::new(audit_items(app, entries)), inner_area);
+    }
+}
+
+/// Files whose checksums differ between the endpoints.
+pub struct VerifyResults<'a>(pub &'a VerifyReport);
+
+impl View for VerifyResults<'_> {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        use ratatui::layout::{Alignment, Margin};
+        use ratatui::widgets::Clear;
+
+        let report = self.0;
+
+        // Same footprint as the conflict overlay (80% width, 80% height)
+        let overlay_width = (area.width as f32 * 0.8) as u16;
+        let overlay_height = (area.height as f32 * 0.8) as u16;
+        let overlay_area = Rect {
+            x: (area.width - overlay_width) / 2,
+            y: (area.height - overlay_height) / 2,
+            width: overlay_width,
+            height: overlay_height,
+        };
+
+        f.render_widget(Clear, overlay_area);
+
+        let overlay_block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(app.color_scheme.help_key_fg))
+            .title(t!("title.verify", name = report.session_name))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().bg(app.color_scheme.selection_bg));
+
+        f.render_widget(overlay_block, overlay_area);
+
+        let inner_area = overlay_area.inner(Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+
+        let summary_color = if report.mismatches.is_empty() {
+            app.color_scheme.status_running_fg
+        } else {
+            app.color_scheme.status_error_fg
+        };
+        let mut items = vec![
+            ListItem::new(Line::from(Span::styled(
+                report.summary(),
+                Style::default()
+                    .fg(summary_color)
+                    .add_modifier(Modifier::BOLD),
+            ))),
+            ListItem::new(""),
+        ];
+
+        items.extend(report.mismatches.iter().map(|mismatch| {
+            let (marker, note) = match mismatch.kind {
+                MismatchKind::Differs => ("≠", "contents differ"),
+                MismatchKind::MissingOnAlpha => ("−", "missing on alpha"),
+                MismatchKind::MissingOnBeta => ("−", "missing on beta"),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", app.icons.get(marker)),
+                    Style::default().fg(app.color_scheme.status_error_fg),
+                ),
+                Span::styled(
+                    mismatch.path.as_str(),
+                    Style::default().fg(app.color_scheme.session_alpha_fg),
+                ),
+                Span::styled(
+                    format!("  ({})", note),
+                    Style::default().fg(app.color_scheme.session_status_fg),
+                ),
+            ]))
+        }));
+
+        f.render_widget(List::new(items), inner_area);
+    }
+}
+
+/// A scrollable block of text, like the full status message.
+pub struct Popup<'a>(pub &'a TextPopup);
+
+impl View for Popup<'_> {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        use ratatui::layout::{Alignment, Margin};
+        use ratatui::widgets::Clear;
+
+        let popup = self.0;
+        let overlay_width = (area.width as f32 * 0.8) as u16;
+        let overlay_height = (area.height as f32 * 0.8) as u16;
+        let overlay_area = Rect {
+            x: (area.width - overlay_width) / 2,
+            y: (area.height - overlay_height) / 2,
+            width: overlay_width,
+            height: overlay_height,
+        };
+
+        f.render_widget(Clear, overlay_area);
+
+        let inner_area = overlay_area.inner(Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+
+        // Let scrolling stop once the last line is in view
+        let total = wrapped_line_count(&popup.text, inner_area.width);
+        let visible = inner_area.height as usize;
+        popup
+            .max_scroll
+            .set(total.saturating_sub(visible).min(u16::MAX as usize) as u16);
+        let scroll = popup.scroll.min(popup.max_scroll.get());
+
+        let mut overlay_block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(app.color_scheme.help_key_fg))
+            .title(if popup.export.is_some() {
+                t!("title.popup_export", title = popup.title)
+            } else {
+                t!("title.popup", title = popup.title)
+            })
+            .title_alignment(Alignment::Center)
+            .style(Style::default().bg(app.color_scheme.selection_bg));
+        if total > visible {
+            let first = scroll as usize + 1;
+            let last = (scroll as usize + visible).min(total);
+            overlay_block = overlay_block.title_bottom(
+                Line::from(format!(" lines {}-{} of {} ", first, last, total)).right_aligned(),
+            );
+        }
+
+        f.render_widget(overlay_block, overlay_area);
+
+        // Keep indentation and blank lines; mutagen and ssh errors are often multi-line
+        let text = Paragraph::new(popup.text.as_str())
+            .style(Style::default().fg(popup.fg))
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0));
+
+        f.render_widget(text, inner_area);
+    }
+}
+
+/// The new session form.
+pub struct WizardForm<'a>(pub &'a SessionWizard);
+
+impl View for WizardForm<'_> {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        use ratatui::layout::{Alignment, Margin};
+        use ratatui::widgets::Clear;
+
+        let wizard = self.0;
+        let theme = &app.color_scheme;
+        let mut lines = Vec::new();
+        for field in wizard.fields() {
+            let focused = field == wizard.focus;
+            let label_style = if focused {
+                Style::default()
+                    .fg(theme.help_key_fg)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.help_text_fg)
+            };
+            let mut spans = vec![Span::styled(format!("{:<9}", field.label()), label_style)];
+            let value_style = Style::default().fg(theme.session_status_fg);
+            let input = match field {
+                WizardField::Alpha => Some(&wizard.alpha),
+                WizardField::Beta => Some(&wizard.beta),
+                WizardField::Name => Some(&wizard.name),
+                WizardField::Ignore => Some(&wizard.ignore),
+                WizardField::Template | WizardField::Mode | WizardField::Target => None,
+            };
+            match input {
+                Some(input) if focused => spans.extend(input_spans(input, value_style)),
+                Some(input) => spans.push(Span::styled(input.value().to_string(), value_style)),
+                None => {
+                    let choice = match field {
+                        WizardField::Template => match wizard.selected_template() {
+                            Some((name, _)) => name.clone(),
+                            None => t!("view.no_template").to_string(),
+                        },
+                        WizardField::Mode => MODES[wizard.mode].to_string(),
+                        _ => match wizard.selected_target() {
+                            WizardTarget::Create => t!("view.create_now").to_string(),
+                            WizardTarget::Project(path) if wizard.editing.is_some() => {
+                                t!("view.save_in", path = path.display())
+                            }
+                            WizardTarget::Project(path) => t!("view.add_to", path = path.display()),
+                        },
+                    };
+                    let choice = if focused {
+                        format!("< {} >", choice)
+                    } else {
+                        choice
+                    };
+                    spans.push(Span::styled(choice, value_style));
+                }
+            }
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            match &wizard.error {
+                Some(error) => format!("{} {}", app.icons.get("✗"), error),
+                None => t!("view.ignore_hint").to_string(),
+            },
+            Style::default().fg(if wizard.error.is_some() {
+                theme.status_error_fg
+            } else {
+                theme.help_text_fg
+            }),
+        )));
+
+        let overlay_width = (area.width as f32 * 0.7).max(50.0).min(area.width as f32) as u16;
+        let plain: Vec<String> = lines.iter().map(Line::to_string).collect();
+        let text_height = wrapped_line_count(&plain.join("\n"), overlay_width.saturating_sub(4));
+        let overlay_height = (text_height as u16 + 2).min(area.height);
+        let overlay_area = Rect {
+            x: (area.width - overlay_width) / 2,
+            y: (area.height - overlay_height) / 2,
+            width: overlay_width,
+            height: overlay_height,
+        };
+
+        f.render_widget(Clear, overlay_area);
+        let hints = app.icons.text(t!("hint.wizard"));
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.help_key_fg))
+            .title(if wizard.editing.is_some() {
+                t!("title.edit_spec")
+            } else if wizard.copying.is_some() {
+                t!("title.duplicate_spec")
+            } else {
+                t!("title.new_session")
+            })
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(hints.into_owned()).right_aligned())
+            .style(Style::default().bg(theme.selection_bg));
+        f.render_widget(block, overlay_area);
+
+        let inner_area = overlay_area.inner(Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner_area);
+    }
+}
+
+/// A text input's value with the cursor drawn as the character under it in
+/// reverse video.
+fn input_spans(input: &TextInput, style: Style) -> Vec<Span<'static>> {
+    let (before, after) = input.split();
+    let mut rest = after.chars();
+    let under = rest.next().map_or(" ".to_string(), String::from);
+    vec![
+        Span::styled(before.to_string(), style),
+        Span::styled(under, style.add_modifier(Modifier::REVERSED)),
+        Span::styled(rest.as_str().to_string(), style),
+    ]
+}
+
+/// The selected spec's ignore patterns, its own ones editable.
+pub struct IgnoreEditorView<'a>(pub &'a IgnoreEditor);
+
+impl View for IgnoreEditorView<'_> {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        use ratatui::layout::{Alignment, Margin};
+        use ratatui::widgets::Clear;
+
+        let editor = self.0;
+        let theme = &app.color_scheme;
+        let heading = Style::default()
+            .fg(theme.help_key_fg)
+            .add_modifier(Modifier::BOLD);
+        let pattern_style = Style::default().fg(theme.session_status_fg);
+        let note_style = Style::default().fg(theme.help_text_fg);
+        let checkbox = |on: bool| if on { "[x]" } else { "[ ]" };
+
+        let mut lines = vec![Line::from(Span::styled(
+            t!("view.ignores_from_defaults"),
+            heading,
+        ))];
+        let defaults = editor.default_patterns();
+        if defaults.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", t!("view.ignores_none")),
+                note_style,
+            )));
+        }
+        lines.extend(
+            defaults
+                .iter()
+                .map(|pattern| Line::from(Span::styled(format!("  {}", pattern), note_style))),
+        );
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            t!("view.ignores_this_spec"),
+            heading,
+        )));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {}",
+                t!(
+                    "view.ignores_vcs",
+                    checkbox = checkbox(editor.vcs),
+                    directories = VCS_DIRECTORIES.join(", ")
+                )
+            ),
+            pattern_style,
+        )));
+        for (index, pattern) in editor.paths.iter().enumerate() {
+            let style = if index == editor.selected && editor.adding.is_none() {
+                pattern_style.add_modifier(Modifier::REVERSED)
+            } else {
+                pattern_style
+            };
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(pattern.as_str(), style),
+            ]));
+        }
+        if let Some(input) = &editor.adding {
+            let mut spans = vec![Span::styled("  + ", note_style)];
+            spans.extend(input_spans(input, pattern_style));
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            t!(
+                "view.ignores_also_save",
+                checkbox = checkbox(editor.save_to_file),
+                path = editor.path.display()
+            ),
+            pattern_style,
+        )));
+        lines.push(Line::from(Span::styled(
+            match &editor.error {
+                Some(error) => format!("{} {}", app.icons.get("✗"), error),
+                None => t!(
+                    "view.ignores_recreate",
+                    count = editor.effective_patterns().len()
+                ),
+            },
+            if editor.error.is_some() {
+                Style::default().fg(theme.status_error_fg)
+            } else {
+                note_style
+            },
+        )));
+
+        let overlay_width = (area.width as f32 * 0.7).max(50.0).min(area.width as f32) as u16;
+        let plain: Vec<String> = lines.iter().map(Line::to_string).collect();
+        let text_height = wrapped_line_count(&plain.join("\n"), overlay_width.saturating_sub(4));
+        let overlay_height = (text_height as u16 + 2).min(area.height);
+        let overlay_area = Rect {
+            x: (area.width - overlay_width) / 2,
+            y: (area.height - overlay_height) / 2,
+            width: overlay_width,
+            height: overlay_height,
+        };
+
+        f.render_widget(Clear, overlay_area);
+        let hints = app.icons.text(if editor.adding.is_some() {
+            t!("hint.ignores_adding")
+        } else {
+            t!("hint.ignores")
+        });
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.help_key_fg))
+            .title(t!("title.ignores", name = editor.spec_name))
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(hints.into_owned()).right_aligned())
+            .style(Style::default().bg(theme.selection_bg));
+        f.render_widget(block, overlay_area);
+
+        let inner_area = overlay_area.inner(Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner_area);
+    }
+}
+
+/// The selected project's commands, one of them highlighted for running.
+pub struct TaskMenuView<'a>(pub &'a TaskMenu);
+
+impl View for TaskMenuView<'_> {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        use ratatui::layout::{Alignment, Margin};
+        use ratatui::widgets::Clear;
+
+        let menu = self.0;
+        let theme = &app.color_scheme;
+        let name_width = menu
+            .commands
+            .iter()
+            .map(|(name, _, _)| name.chars().count())
+            .max();
+        let items: Vec<ListItem> = menu
+            .commands
+            .iter()
+            .map(|(name, command, _)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:width$}", name, width = name_width.unwrap_or(0)),
+                        Style::default().fg(theme.help_key_fg),
+                    ),
+                    Span::raw("  "),
+                    Span::styled(
+                        printable(command).into_owned(),
+                        Style::default().fg(theme.status_message_fg),
+                    ),
+                ]))
+            })
+            .collect();
+
+        let overlay_width = (area.width as f32 * 0.6).max(40.0).min(area.width as f32) as u16;
+        let overlay_height = (menu.commands.len() as u16 + 2).min(area.height);
+        let overlay_area = Rect {
+            x: (area.width - overlay_width) / 2,
+            y: (area.height - overlay_height) / 2,
+            width: overlay_width,
+            height: overlay_height,
+        };
+
+        f.render_widget(Clear, overlay_area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.help_key_fg))
+            .title(t!("title.commands", name = menu.project_name))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().bg(theme.selection_bg));
+        f.render_widget(block, overlay_area);
+
+        let inner_area = overlay_area.inner(Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+        // Keeps the selection in view when there are more commands than fit
+        let mut state = ListState::default().with_selected(Some(menu.selected));
+        let list =
+            List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, inner_area, &mut state);
+    }
+}
+
+/// What can be copied about the selected spec, each with the key that
+/// copies it.
+pub struct YankMenuView<'a>(pub &'a YankMenu);
+
+impl View for YankMenuView<'_> {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        use ratatui::layout::{Alignment, Margin};
+        use ratatui::widgets::Clear;
+
+        let menu = self.0;
+        let theme = &app.color_scheme;
+        let label_width = menu
+            .items
+            .iter()
+            .map(|(label, _, _)| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let items: Vec<ListItem> = menu
+            .items
+            .iter()
+            .map(|(label, key, text)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(key.to_string(), Style::default().fg(theme.help_key_fg)),
+                    Span::raw("  "),
+                    Span::raw(format!("{:width$}", label, width = label_width)),
+                    Span::raw("  "),
+                    Span::styled(
+                        printable(text).into_owned(),
+                        Style::default().fg(theme.status_message_fg),
+                    ),
+                ]))
+            })
+            .collect();
+
+        let overlay_width = (area.width as f32 * 0.7).max(40.0).min(area.width as f32) as u16;
+        let overlay_height = (menu.items.len() as u16 + 2).min(area.height);
+        let overlay_area = Rect {
+            x: (area.width - overlay_width) / 2,
+            y: (area.height - overlay_height) / 2,
+            width: overlay_width,
+            height: overlay_height,
+        };
+
+        f.render_widget(Clear, overlay_area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.help_key_fg))
+            .title(t!("title.copy", spec = menu.spec_name))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().bg(theme.selection_bg));
+        f.render_widget(block, overlay_area);
+
+        let inner_area = overlay_area.inner(Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+        let mut state = ListState::default().with_selected(Some(menu.selected));
+        let list =
+            List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, inner_area, &mut state);
+    }
+}
+
+/// The directory being browsed on one of a spec's endpoints.
+pub struct BrowserView<'a>(pub &'a DirBrowser);
+
+impl View for BrowserView<'_> {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        use ratatui::layout::{Alignment, Margin};
+        use ratatui::widgets::Clear;
+
+        let browser = self.0;
+        let theme = &app.color_scheme;
+        let items: Vec<ListItem> = if let Some(error) = &browser.error {
+            vec![ListItem::new(Span::styled(
+                printable(error).into_owned(),
+                Style::default().fg(theme.status_error_fg),
+            ))]
+        } else if browser.entries.is_empty() {
+            vec![ListItem::new(Span::styled(
+                "(empty)",
+                Style::default().fg(theme.help_text_fg),
+            ))]
+        } else {
+            browser
+                .entries
+                .iter()
+                .map(|entry| {
+                    let name = printable(&entry.name).into_owned();
+                    ListItem::new(if entry.is_dir {
+                        Span::styled(
+                            format!("{}/", name),
+                            Style::default()
+                                .fg(theme.session_name_fg)
+                                .add_modifier(Modifier::BOLD),
+                        )
+                    } else {
+                        Span::styled(name, Style::default().fg(theme.status_message_fg))
+                    })
+                })
+                .collect()
+        };
+
+        let overlay_width = (area.width as f32 * 0.7).max(40.0).min(area.width as f32) as u16;
+        let overlay_height = (area.height as f32 * 0.7) as u16;
+        let overlay_area = Rect {
+            x: (area.width - overlay_width) / 2,
+            y: (area.height - overlay_height) / 2,
+            width: overlay_width,
+            height: overlay_height,
+        };
+
+        f.render_widget(Clear, overlay_area);
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.help_key_fg))
+            .title(t!(
+                "title.browse",
+                spec = browser.spec_name,
+                side = browser.side_name(),
+                endpoint = browser.current()
+            ))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().bg(theme.selection_bg));
+        if !browser.entries.is_empty() {
+            block = block.title_bottom(
+                Line::from(format!(
+                    " {} of {} ",
+                    browser.selected + 1,
+                    browser.entries.len()
+                ))
+                .right_aligned(),
+            );
+        }
+        f.render_widget(block, overlay_area);
+
+        let inner_area = overlay_area.inner(Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+        let selected = browser.error.is_none() && !browser.entries.is_empty();
+        let mut state = ListState::default().with_selected(selected.then_some(browser.selected));
+        let list =
+            List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, inner_area, &mut state);
+    }
+}
+
+/// Asks for the confirmation word before resetting a safety-halted session.
+pub struct ResetConfirmation<'a>(pub &'a PendingReset);
+
+impl View for ResetConfirmation<'_> {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        use ratatui::layout::{Alignment, Margin};
+        use ratatui::widgets::Clear;
+
+        let pending = self.0;
+        let theme = &app.color_scheme;
+        let consequence = if pending.one_way {
+            t!("view.reset_one_way")
+        } else {
+            t!("view.reset_two_way")
+        };
+        let lines = vec![
+            Line::from(Span::styled(
+                format!(
+                    "{} {}: {}",
+                    app.icons.get("🛑"),
+                    pending.spec_name,
+                    pending.halt.label()
+                ),
+                Style::default()
+                    .fg(theme.status_error_fg)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(pending.halt.explanation()),
+            Line::from(""),
+            Line::from(consequence),
+            Line::from(""),
+            Line::from(t!("view.reset_prompt", word = RESET_CONFIRMATION)),
+            Line::from(Span::styled(
+                format!("> {}", pending.typed),
+                Style::default()
+                    .fg(theme.help_key_fg)
+                    .add_modifier(Modifier::BOLD),
+            )),
+        ];
+
+        let overlay_width = (area.width as f32 * 0.6).max(40.0).min(area.width as f32) as u16;
+        let text_width = overlay_width.saturating_sub(4);
+        let plain: Vec<String> = lines.iter().map(Line::to_string).collect();
+        let text_height = wrapped_line_count(&plain.join("\n"), text_width);
+        let overlay_height = (text_height as u16 + 2).min(area.height);
+        let overlay_area = Rect {
+            x: (area.width - overlay_width) / 2,
+            y: (area.height - overlay_height) / 2,
+            width: overlay_width,
+            height: overlay_height,
+        };
+
+        f.render_widget(Clear, overlay_area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.status_error_fg))
+            .title(t!("title.reset"))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().bg(theme.selection_bg));
+        f.render_widget(block, overlay_area);
+
+        let inner_area = overlay_area.inner(Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+        let text = Paragraph::new(lines)
+            .style(Style::default().fg(theme.status_message_fg))
+            .wrap(Wrap { trim: false });
+        f.render_widget(text, inner_area);
+    }
+}
+
+/// Shown while a project-wide operation runs.
+pub struct BlockingModal<'a>(pub &'a BlockingOperation);
+
+impl View for BlockingModal<'_> {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        use ratatui::layout::{Alignment, Margin};
+        use ratatui::widgets::Clear;
+
+        let blocking_op = self.0;
+
+        // Create a centered overlay area (50% width, 7 lines height)
+        let overlay_width = (area.width as f32 * 0.5) as u16;
+        let overlay_height = 7;
+        let overlay_x = (area.width - overlay_width) / 2;
+        let overlay_y = (area.height - overlay_height) / 2;
+
+        let overlay_area = Rect {
+            x: overlay_x,
+            y: overlay_y,
+            width: overlay_width,
+            height: overlay_height,
+        };
+
+        // Clear the background (prevents visual artifacts)
+        f.render_widget(Clear, overlay_area);
+
+        // Render the modal block
+        let modal_block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(app.color_scheme.help_key_fg))
+            .style(Style::default().bg(app.color_scheme.selection_bg));
+
+        f.render_widget(modal_block, overlay_area);
+
+        // Inner area for content
+        let inner_area = overlay_area.inner(Margin {
+            horizontal: 2,
+            vertical: 1,
+        });
+
+        // Static hourglass indicator, redrawn with a step count as the operation progresses
+        let steps = match blocking_op.progress.get() {
+            Some((done, total)) => format!(" ({}/{})", done, total),
+            None => String::new(),
+        };
+        let message = format!(
+            "{} {}{}\n\n{}",
+            app.icons.get("⏳"),
+            blocking_op.message,
+            steps,
+            t!("progress.wait")
+        );
+
+        let paragraph = Paragraph::new(message)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(app.color_scheme.status_message_fg));
+
+        f.render_widget(paragraph, inner_area);
+    }
+}
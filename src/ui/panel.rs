@@ -0,0 +1,1234 @@
+//! The body of each tab: the project list and details pane for the sync
+//! view, and the forward, daemon, and log tabs.
+
+use crate::app::{App, SessionDisplayMode};
+use crate::command::printable;
+use crate::cycles::format_cycle_rate;
+use crate::hosts::group_by_host;
+use crate::i18n::{t, tn};
+use crate::mutagen::{SessionSummary, SyncSession};
+use crate::project::SyncSpecState;
+use crate::selection::{Grouping, SelectableItem};
+use crate::tabs::Tab;
+use crate::transfer::format_rate;
+use crate::widgets::{
+    fit_width, share_width, truncate_middle, ColumnLayout, ProgressBar, StyledText,
+};
+use chrono::Local;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+use textwrap::core::display_width;
+
+use super::formatting::{
+    audit_items, definition_ssh_host, detail_field, disk_space_summary, format_age, format_elapsed,
+    format_excluded, format_last_sync, host_check_span, resolved_hosts, session_ssh_host,
+};
+use super::View;
+
+/// The body for the open tab.
+pub fn body(app: &App) -> &'static dyn View {
+    if app.tab != Tab::Sync {
+        &TabBody
+    } else if app.all_projects_invalid() {
+        &ProjectErrors
+    } else if app.projects.is_empty() {
+        &EmptyState
+    } else {
+        &MainPanel
+    }
+}
+
+/// The body of every tab except the sync view.
+pub struct TabBody;
+
+impl View for TabBody {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", app.tab.title()));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        match app.tab {
+            Tab::Sync => {}
+            Tab::Hosts => draw_hosts(f, app, inner),
+            Tab::Forward => draw_forwards(f, app, inner),
+            Tab::Daemon => draw_daemon(f, app, inner),
+            Tab::Log if app.log_entries.is_empty() => {
+                let empty = Paragraph::new(t!("view.no_actions"))
+                    .style(Style::default().fg(app.color_scheme.session_status_fg));
+                f.render_widget(empty, inner);
+            }
+            Tab::Log => f.render_widget(List::new(audit_items(app, &app.log_entries)), inner),
+        }
+    }
+}
+
+fn draw_hosts(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.color_scheme;
+    let groups = group_by_host(&app.projects, app.mutagen_client.ssh_config());
+    if groups.is_empty() {
+        let empty =
+            Paragraph::new(t!("view.no_specs")).style(Style::default().fg(theme.session_status_fg));
+        f.render_widget(empty, area);
+        return;
+    }
+    let mut items = Vec::new();
+    for group in &groups {
+        let mut heading = vec![Span::styled(
+            group
+                .target
+                .clone()
+                .unwrap_or_else(|| t!("view.local").to_string()),
+            Style::default()
+                .fg(theme.session_name_fg)
+                .add_modifier(Modifier::BOLD),
+        )];
+        if !group.aliases.is_empty() {
+            heading.push(Span::styled(
+                format!(" ({})", group.aliases.join(", ")),
+                Style::default().fg(theme.help_text_fg),
+            ));
+        }
+        items.push(ListItem::new(Line::from(heading)));
+        for &(project_idx, spec_idx) in &group.specs {
+            let project = &app.projects[project_idx];
+            let spec = &project.specs[spec_idx];
+            let (icon, color, status) = match &spec.running_session {
+                None => ("○", theme.status_paused_fg, t!("view.not_running")),
+                Some(session) if session.paused => ("⏸", theme.status_paused_fg, t!("view.paused")),
+                Some(session) => ("▶", theme.status_running_fg, session.status_text()),
+            };
+            items.push(ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("  {} ", app.icons.get(icon)),
+                    Style::default().fg(color),
+                ),
+                Span::styled(
+                    format!("{} / {}", project.file.display_name(), spec.name),
+                    Style::default().fg(theme.session_name_fg),
+                ),
+                Span::styled(
+                    format!("  {}", status),
+                    Style::default().fg(theme.session_status_fg),
+                ),
+            ])));
+        }
+    }
+    f.render_widget(List::new(items), area);
+}
+
+fn draw_forwards(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.color_scheme;
+    if app.forwards.is_empty() {
+        let empty = Paragraph::new(t!("view.no_forwards"))
+            .style(Style::default().fg(theme.session_status_fg));
+        f.render_widget(empty, area);
+        return;
+    }
+    let endpoint = |endpoint: &crate::mutagen::ForwardEndpoint| {
+        format!(
+            "{} {}",
+            app.icons.get(if endpoint.connected { "✓" } else { "⊗" }),
+            endpoint.display()
+        )
+    };
+    let items: Vec<ListItem> = app
+        .forwards
+        .iter()
+        .map(|forward| {
+            let (icon, color) = if forward.paused {
+                ("⏸", theme.status_paused_fg)
+            } else {
+                ("▶", theme.status_running_fg)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", app.icons.get(icon)),
+                    Style::default().fg(color),
+                ),
+                Span::styled(
+                    format!("{}  ", forward.name),
+                    Style::default()
+                        .fg(theme.session_name_fg)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    endpoint(&forward.source),
+                    Style::default().fg(theme.session_alpha_fg),
+                ),
+                Span::styled(
+                    format!(" {} ", app.icons.get("→")),
+                    Style::default().fg(theme.help_text_fg),
+                ),
+                Span::styled(
+                    endpoint(&forward.destination),
+                    Style::default().fg(theme.session_beta_fg),
+                ),
+                Span::styled(
+                    format!("  {} ({} open)", forward.status, forward.open_connections),
+                    Style::default().fg(theme.session_status_fg),
+                ),
+            ]))
+        })
+        .collect();
+    f.render_widget(List::new(items), area);
+}
+
+fn draw_daemon(f: &mut Frame, app: &App, area: Rect) {
+    let connection = if app.has_refresh_error {
+        t!("view.daemon_unreachable")
+    } else if app.last_refresh.is_some() {
+        t!("view.daemon_connected")
+    } else {
+        t!("view.daemon_not_checked")
+    };
+    let summary = SessionSummary::from_sessions(&app.sessions);
+    let mut lines = vec![
+        detail_field(
+            app,
+            t!("view.mutagen"),
+            app.mutagen_version
+                .as_deref()
+                .unwrap_or(t!("view.version_unknown")),
+        ),
+        detail_field(app, t!("view.daemon"), connection),
+        detail_field(
+            app,
+            t!("view.refreshed"),
+            app.last_refresh
+                .map(|at| at.format("%H:%M:%S").to_string())
+                .unwrap_or_else(|| t!("view.never").to_string()),
+        ),
+        detail_field(
+            app,
+            t!("view.interval"),
+            format!("{}s", app.refresh_interval().as_secs()),
+        ),
+        detail_field(
+            app,
+            t!("view.sessions"),
+            t!(
+                "view.session_counts",
+                running = summary.running,
+                paused = summary.paused
+            ),
+        ),
+    ];
+    if app.has_refresh_error {
+        if let Some(message) = &app.status_message {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                message.text().to_string(),
+                Style::default().fg(app.color_scheme.status_error_fg),
+            )));
+        }
+    }
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), area);
+}
+
+/// Shown when no project files were found.
+pub struct EmptyState;
+
+impl View for EmptyState {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        let theme = &app.color_scheme;
+        let create_hint = app
+            .icons
+            .text("• Create a mutagen.yml file in your project directory");
+        let new_hint = app.icons.text("• Press 'n' to create a sync session");
+        let refresh_hint = app.icons.text("• Press 'r' to refresh");
+        let setup_hint = app.offers_setup().then(|| {
+            app.icons
+                .text("• Press Enter to write a starter config.toml and an example mutagen.yml")
+        });
+        let heading = if app.is_discovering() {
+            t!("view.looking_for_projects")
+        } else {
+            t!("view.no_projects")
+        };
+        let mut lines = vec![
+            Line::from(""),
+            StyledText::new(theme)
+                .styled(
+                    heading,
+                    Style::default()
+                        .fg(theme.session_status_fg)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .build(),
+            Line::from(""),
+        ];
+        if let Some(hint) = &setup_hint {
+            lines.push(StyledText::new(theme).help_text(hint).build());
+        }
+        lines.extend([
+            StyledText::new(theme).help_text(&create_hint).build(),
+            StyledText::new(theme).help_text(&new_hint).build(),
+            StyledText::new(theme).help_text(&refresh_hint).build(),
+        ]);
+        let message = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t!("title.welcome")),
+        )
+        .style(Style::default());
+
+        f.render_widget(message, area);
+    }
+}
+
+/// Shown instead of the project list when every project file failed to parse,
+/// so a broken file isn't mistaken for there being no projects.
+pub struct ProjectErrors;
+
+impl View for ProjectErrors {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        let theme = &app.color_scheme;
+        let mut lines = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("{} No project file could be parsed", app.icons.get("⚠")),
+                Style::default()
+                    .fg(theme.status_error_fg)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+        // Leave room for the border and the two-column indent of the messages
+        let wrap_width = (area.width as usize).saturating_sub(6).max(20);
+        for (idx, error) in app.project_errors.iter().enumerate() {
+            let selected = idx == app.selected_project_error;
+            let marker = if selected { app.icons.get("▶") } else { " " };
+            let mut path_style = Style::default()
+                .fg(theme.session_name_fg)
+                .add_modifier(Modifier::BOLD);
+            if selected {
+                path_style = path_style.bg(theme.selection_bg);
+            }
+            lines.push(Line::from(Span::styled(
+                format!("{} {}", marker, error.path.display()),
+                path_style,
+            )));
+            for line in textwrap::wrap(&error.message, wrap_width) {
+                lines.push(Line::from(Span::styled(
+                    format!("    {}", line),
+                    Style::default().fg(theme.status_error_fg),
+                )));
+            }
+            lines.push(Line::from(""));
+        }
+        let hint = app
+            .icons
+            .text("• Press 'e' to edit the selected file, then 'r' to refresh");
+        lines.push(StyledText::new(theme).help_text(&hint).build());
+
+        let message = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t!("title.project_files", count = app.project_errors.len())),
+        );
+        f.render_widget(message, area);
+    }
+}
+
+/// Narrowest window the details pane is shown in; below this the list gets
+/// the full width.
+const MIN_SPLIT_WIDTH: u16 = 100;
+
+/// The project list, with the details pane beside it in split layout.
+pub struct MainPanel;
+
+impl View for MainPanel {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        if !app.split_layout || area.width < MIN_SPLIT_WIDTH {
+            ProjectList.render(f, app, area);
+            return;
+        }
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(area);
+        ProjectList.render(f, app, panes[0]);
+        Details.render(f, app, panes[1]);
+    }
+}
+
+/// Details of the selected project or spec, for the split layout.
+pub struct Details;
+
+impl View for Details {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        let lines = match (app.get_selected_project_index(), app.get_selected_spec()) {
+            (_, Some((proj_idx, spec_idx))) => app
+                .projects
+                .get(proj_idx)
+                .and_then(|project| Some((project, project.specs.get(spec_idx)?)))
+                .map(|(project, spec)| spec_details(app, project, spec, area.height))
+                .unwrap_or_default(),
+            (Some(proj_idx), None) => app
+                .projects
+                .get(proj_idx)
+                .map(|project| project_details(app, project))
+                .unwrap_or_default(),
+            (None, None) => Vec::new(),
+        };
+        let details = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(t!("title.details")),
+            )
+            .wrap(Wrap { trim: false });
+        f.render_widget(details, area);
+    }
+}
+
+fn project_details(app: &App, project: &crate::project::Project) -> Vec<Line<'static>> {
+    let theme = &app.color_scheme;
+    let running = project.specs.iter().filter(|s| s.is_running()).count();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            project.file.display_name(),
+            Style::default()
+                .fg(theme.session_name_fg)
+                .add_modifier(Modifier::BOLD),
+        )),
+        detail_field(
+            app,
+            t!("view.file"),
+            project.file.path.display().to_string(),
+        ),
+        detail_field(
+            app,
+            t!("view.specs"),
+            t!(
+                "view.specs_running",
+                running = running,
+                count = project.specs.len()
+            ),
+        ),
+        Line::from(""),
+    ];
+    for spec in &project.specs {
+        let status = match &spec.running_session {
+            Some(session) if session.has_conflicts() => tn!(
+                "view.spec_conflicts",
+                session.conflict_count(),
+                status = session.status_text(),
+                icon = app.icons.get("⚠")
+            ),
+            Some(session) if session.paused => t!("view.paused").to_string(),
+            Some(session) => session.status_text().to_string(),
+            None => t!("view.not_running").to_string(),
+        };
+        lines.push(detail_field(app, &spec.name, status));
+    }
+    lines
+}
+
+/// Scan and transition problems listed in the details pane; the field
+/// above them gives the count.
+const MAX_PROBLEMS_SHOWN: usize = 3;
+
+fn spec_details(
+    app: &App,
+    project: &crate::project::Project,
+    spec: &crate::project::SyncSpec,
+    height: u16,
+) -> Vec<Line<'static>> {
+    let theme = &app.color_scheme;
+    let mode = match spec.state {
+        SyncSpecState::RunningPush => t!("view.mode_push"),
+        _ => t!("view.mode_two_way"),
+    };
+    let mut lines = vec![Line::from(Span::styled(
+        format!("{} ({})", spec.name, mode),
+        Style::default()
+            .fg(theme.session_name_fg)
+            .add_modifier(Modifier::BOLD),
+    ))];
+
+    let Some(session) = &spec.running_session else {
+        lines.push(detail_field(app, t!("view.status"), t!("view.not_running")));
+        if let Some(definition) = project.file.sessions.get(&spec.name) {
+            let mut hosts = Vec::new();
+            let endpoints = [
+                (t!("view.alpha"), &definition.alpha),
+                (t!("view.beta"), &definition.beta),
+            ];
+            for (label, endpoint) in endpoints {
+                let mut line = detail_field(app, label, endpoint.clone());
+                let host = definition_ssh_host(endpoint);
+                line.spans
+                    .extend(host_check_span(app, host.as_ref().map(|(h, _)| h.as_str())));
+                lines.push(line);
+                hosts.extend(host);
+            }
+            if let Some(resolved) = resolved_hosts(app, &hosts) {
+                lines.push(detail_field(app, t!("view.resolves"), resolved));
+            }
+        }
+        return lines;
+    };
+
+    let status = if let Some(halt) = session.safety_halt() {
+        t!("view.halted", reason = halt.label())
+    } else if session.paused {
+        t!("view.paused").to_string()
+    } else {
+        session.status_text().to_string()
+    };
+    lines.push(detail_field(app, t!("view.status"), status));
+    if let Some(halt) = session.safety_halt() {
+        lines.push(Line::from(Span::styled(
+            t!("view.press_u_to_reset", explanation = halt.explanation()),
+            Style::default().fg(theme.status_error_fg),
+        )));
+    }
+    let endpoint = |endpoint: &crate::mutagen::Endpoint, path: String| {
+        format!("{} {}", app.icons.get(endpoint.status_icon()), path)
+    };
+    let beta_display = session.beta_display(app.mutagen_client.ssh_config());
+    let mut hosts = Vec::new();
+    for (label, side, display) in [
+        (t!("view.alpha"), &session.alpha, session.alpha_display()),
+        (t!("view.beta"), &session.beta, beta_display),
+    ] {
+        let mut line = detail_field(app, label, endpoint(side, display));
+        let host = session_ssh_host(side);
+        line.spans
+            .extend(host_check_span(app, host.as_ref().map(|(h, _)| h.as_str())));
+        lines.push(line);
+        hosts.extend(host);
+    }
+    if let Some(resolved) = resolved_hosts(app, &hosts) {
+        lines.push(detail_field(app, t!("view.resolves"), resolved));
+    }
+    if let Some(cycles) = session.successful_cycles {
+        let mut text = t!("view.cycles_successful", count = cycles);
+        // A busy session keeps a steady rate; a wedged one drops to zero
+        if let Some(rate) = app.cycles.rate(&session.identifier) {
+            text.push_str(&format!(", {}", format_cycle_rate(rate)));
+        }
+        lines.push(detail_field(app, t!("view.cycles"), text));
+    }
+    lines.push(detail_field(
+        app,
+        t!("view.last_sync"),
+        format_last_sync(&session.sync_time),
+    ));
+    if let Some(created) = session.created_at() {
+        // Recreated sessions stand out by their age
+        let age = Local::now().signed_duration_since(created);
+        lines.push(detail_field(
+            app,
+            t!("view.created"),
+            t!(
+                "view.created_at",
+                time = created.format("%Y-%m-%d %H:%M:%S"),
+                age = format_age(age.to_std().unwrap_or_default())
+            ),
+        ));
+    }
+    if let Some(origin) = session.origin() {
+        let mut line = detail_field(app, t!("view.origin"), origin.to_string());
+        if app
+            .mutagen_client
+            .origin()
+            .is_some_and(|ours| *ours != origin)
+        {
+            line.spans.push(Span::styled(
+                format!(" {}", t!("view.foreign_origin")),
+                Style::default().fg(theme.status_paused_fg),
+            ));
+        }
+        lines.push(line);
+    }
+    if let Some(pct) = session.progress_percentage() {
+        let mut spans = detail_field(app, t!("view.progress"), "").spans;
+        spans.extend(
+            ProgressBar::new(theme, pct)
+                .width(10)
+                .icons(app.icons)
+                .build_spans(),
+        );
+        if let Some(rate) = app.transfers.rate(&session.identifier) {
+            spans.push(Span::styled(
+                format!(" {}", format_rate(rate)),
+                Style::default().fg(theme.session_status_fg),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    if let Some(space) = disk_space_summary(app, session) {
+        lines.push(detail_field(app, t!("view.free"), space));
+    }
+    if let Some(change) = app.external_change_for(spec) {
+        lines.push(detail_field(
+            app,
+            t!("view.external"),
+            t!(
+                "view.external_at",
+                kind = change.kind.label(),
+                time = change.at.format("%H:%M")
+            ),
+        ));
+    }
+
+    let problems: Vec<_> = session.problems().collect();
+    if !problems.is_empty() {
+        lines.push(detail_field(
+            app,
+            t!("view.problems"),
+            format!(
+                "{}{}",
+                problems.len(),
+                format_excluded(session.excluded_problems())
+            ),
+        ));
+        for problem in problems.iter().take(MAX_PROBLEMS_SHOWN) {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  {}: {}",
+                    printable(&problem.path),
+                    printable(&problem.error)
+                ),
+                Style::default().fg(theme.status_error_fg),
+            )));
+        }
+    }
+
+    if session.has_conflicts() {
+        lines.push(Line::from(""));
+        lines.push(detail_field(
+            app,
+            t!("view.conflicts_label"),
+            format!(
+                "{}{}",
+                session.conflict_count(),
+                format_excluded(session.excluded_conflicts)
+            ),
+        ));
+        // As many as fit below the other fields and the border
+        let room = (height as usize).saturating_sub(lines.len() + 2);
+        let roots = session.conflict_roots();
+        let shown = if roots.len() > room {
+            room.saturating_sub(1)
+        } else {
+            roots.len()
+        };
+        for root in &roots[..shown] {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", printable(root)),
+                Style::default().fg(theme.status_paused_fg),
+            )));
+        }
+        if shown < roots.len() {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  {}",
+                    t!("view.more_conflicts", count = roots.len() - shown)
+                ),
+                Style::default().fg(theme.help_text_fg),
+            )));
+        }
+    }
+    lines
+}
+
+/// The projects and their sync specs.
+pub struct ProjectList;
+
+impl View for ProjectList {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        let theme = &app.color_scheme;
+        app.list_height.set(area.height.saturating_sub(2));
+        let mut items: Vec<ListItem> = Vec::new();
+
+        // Count total specs across all projects
+        let total_specs: usize = app.projects.iter().map(|p| p.specs.len()).sum();
+
+        // Size the name column for the longest spec name (project names sit two
+        // cells further left, so they get two more)
+        let columns = app.columns();
+        let longest_name = app
+            .projects
+            .iter()
+            .flat_map(|project| {
+                let specs = project.specs.iter().map(|spec| {
+                    let tag = if columns.mode && spec.state == SyncSpecState::RunningPush {
+                        PUSH_TAG.len()
+                    } else {
+                        0
+                    };
+                    display_width(&spec.name) + tag
+                });
+                let name = display_width(&project.file.display_name()).saturating_sub(2);
+                specs.chain(std::iter::once(name))
+            })
+            .max()
+            .unwrap_or(0);
+        let layout = ColumnLayout::fit(area.width.saturating_sub(2) as usize, longest_name);
+
+        // Build list items from the selection manager's flattened view
+        let rows: Vec<_> = app.selection.items().collect();
+        for (item_idx, item) in rows.iter().enumerate() {
+            let is_selected = item_idx == app.selection.raw_index();
+
+            match item {
+                SelectableItem::Host { index } => {
+                    // A host header sums up the specs listed under it
+                    let specs: Vec<_> = rows[item_idx + 1..]
+                        .iter()
+                        .map_while(|row| match row {
+                            SelectableItem::Spec {
+                                project_index,
+                                spec_index,
+                            } => app.projects.get(*project_index)?.specs.get(*spec_index),
+                            _ => None,
+                        })
+                        .collect();
+                    let host = &app.selection.hosts()[*index];
+                    let spans = render_group_header(app, host, false, &specs, &layout);
+
+                    let style = if is_selected {
+                        Style::default()
+                            .bg(theme.selection_bg)
+                            .add_modifier(Modifier::BOLD)
+                    } else if let Some(bg) = theme.project_row_bg {
+                        Style::default().bg(bg)
+                    } else {
+                        Style::default()
+                    };
+
+                    items.push(ListItem::new(Line::from(spans)).style(style));
+                }
+                SelectableItem::Project { index: proj_idx } => {
+                    // Render project header
+                    if let Some(project) = app.projects.get(*proj_idx) {
+                        let spans = render_project_header(app, project, &layout);
+
+                        let style = if is_selected {
+                            Style::default()
+                                .bg(theme.selection_bg)
+                                .add_modifier(Modifier::BOLD)
+                        } else if let Some(bg) = theme.project_row_bg {
+                            Style::default().bg(bg)
+                        } else {
+                            Style::default()
+                        };
+
+                        items.push(ListItem::new(Line::from(spans)).style(style));
+                    }
+                }
+                SelectableItem::Spec {
+                    project_index: proj_idx,
+                    spec_index: spec_idx,
+                } => {
+                    // Render spec row
+                    if let Some(project) = app.projects.get(*proj_idx) {
+                        if let Some(spec) = project.specs.get(*spec_idx) {
+                            let spans = render_spec_row(app, spec, &layout);
+
+                            let style = if is_selected {
+                                Style::default()
+                                    .bg(theme.selection_bg)
+                                    .add_modifier(Modifier::BOLD)
+                            } else {
+                                Style::default()
+                            };
+
+                            items.push(ListItem::new(Line::from(spans)).style(style));
+                        }
+                    }
+                }
+            }
+        }
+
+        let title = match app.selection.grouping {
+            Grouping::Projects => t!(
+                "title.projects",
+                projects = app.projects.len(),
+                specs = total_specs
+            ),
+            Grouping::Flat => t!("title.sessions", specs = total_specs),
+            Grouping::Hosts => t!(
+                "title.hosts",
+                hosts = app.selection.hosts().len(),
+                specs = total_specs
+            ),
+        };
+        let list = List::new(items).block(Block::default().borders(Borders::ALL).title(title));
+
+        f.render_widget(list, area);
+    }
+}
+
+/// Render a project header row with fold indicator, status, and stats
+fn render_project_header(
+    app: &App,
+    project: &crate::project::Project,
+    layout: &ColumnLayout,
+) -> Vec<Span<'static>> {
+    let theme = &app.color_scheme;
+    let specs: Vec<_> = project.specs.iter().collect();
+    let mut spans = render_group_header(
+        app,
+        &project.file.display_name(),
+        project.folded,
+        &specs,
+        layout,
+    );
+
+    // Started by `mutagen project start`, which also terminates it
+    if project.project_identifier().is_some() {
+        spans.push(Span::styled(
+            "  [project]".to_string(),
+            Style::default().fg(theme.session_status_fg),
+        ));
+    }
+
+    if project.favorite {
+        spans.push(Span::styled(
+            format!("  {}", app.icons.get("★")),
+            Style::default().fg(theme.help_key_fg),
+        ));
+    }
+
+    // Number key that jumps here
+    if let Some(digit) = app.bookmarks.digit_for(&project.file.path) {
+        spans.push(Span::styled(
+            format!("  [{}]", digit),
+            Style::default().fg(theme.help_key_fg),
+        ));
+    }
+
+    spans
+}
+
+/// Render the part of a header row that projects and hosts share: fold
+/// indicator, status, name, and how many of `specs` are running or in
+/// conflict.
+fn render_group_header(
+    app: &App,
+    name: &str,
+    folded: bool,
+    specs: &[&crate::project::SyncSpec],
+    layout: &ColumnLayout,
+) -> Vec<Span<'static>> {
+    let theme = &app.color_scheme;
+
+    // Fold icon
+    let fold_icon = app.icons.get(if folded { "▶" } else { "▼" });
+
+    // Status icon (active if any spec is running)
+    let is_active = specs.iter().any(|s| s.is_running());
+    let status_icon = app.icons.get(if is_active { "✓" } else { "○" });
+    let status_color = if is_active {
+        theme.status_running_fg
+    } else {
+        theme.status_paused_fg
+    };
+
+    // Count running specs
+    let running_count = specs.iter().filter(|s| s.is_running()).count();
+    let total_count = specs.len();
+
+    // Count push mode specs
+    let push_count = specs
+        .iter()
+        .filter(|s| s.state == crate::project::SyncSpecState::RunningPush)
+        .count();
+
+    // Count conflicts across all running specs
+    let conflict_count: usize = specs
+        .iter()
+        .filter_map(|s| s.running_session.as_ref())
+        .map(|s| s.conflict_count())
+        .sum();
+
+    let mut spans = vec![
+        Span::styled(
+            format!("{} ", fold_icon),
+            Style::default().fg(theme.session_name_fg),
+        ),
+        Span::styled(
+            format!("{} ", status_icon),
+            Style::default().fg(status_color),
+        ),
+        Span::styled(
+            fit_width(name, layout.name_width + 2, app.icons.get("…")),
+            Style::default()
+                .fg(theme.session_name_fg)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ];
+
+    // Add running status
+    if running_count == 0 {
+        spans.push(Span::styled(
+            "  Not running".to_string(),
+            Style::default().fg(theme.session_status_fg),
+        ));
+    } else if running_count == total_count {
+        let status_text = if push_count > 0 {
+            if push_count == running_count {
+                "  Running (all push)".to_string()
+            } else {
+                format!("  Running ({} push)", push_count)
+            }
+        } else {
+            "  Running".to_string()
+        };
+        spans.push(Span::styled(
+            status_text,
+            Style::default().fg(theme.session_status_fg),
+        ));
+    } else {
+        let status_text = if push_count > 0 {
+            format!(
+                "  {}/{} running ({} push)",
+                running_count, total_count, push_count
+            )
+        } else {
+            format!("  {}/{} running", running_count, total_count)
+        };
+        spans.push(Span::styled(
+            status_text,
+            Style::default().fg(theme.session_status_fg),
+        ));
+    }
+
+    // Add conflict indicator if there are conflicts
+    if conflict_count > 0 {
+        spans.push(Span::raw("  ".to_string()));
+        spans.push(Span::styled(
+            format!(
+                "{} {} conflict{}",
+                app.icons.get("⚠"),
+                conflict_count,
+                if conflict_count == 1 { "" } else { "s" }
+            ),
+            Style::default()
+                .fg(theme.status_paused_fg)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    spans
+}
+
+/// Render a spec row with state indicator and the configured columns. The
+/// endpoints get whatever width the other columns leave.
+fn render_spec_row(
+    app: &App,
+    spec: &crate::project::SyncSpec,
+    layout: &ColumnLayout,
+) -> Vec<Span<'static>> {
+    let theme = &app.color_scheme;
+    let columns = app.columns();
+    let ellipsis = app.icons.get("…");
+
+    let mut spans = vec![Span::raw("    ".to_string())]; // Indent for spec under project
+
+    let name_span = |name: String, style: Style| {
+        if columns.name {
+            Some(Span::styled(
+                fit_width(&name, layout.name_width, ellipsis),
+                style,
+            ))
+        } else if !name.is_empty() {
+            Some(Span::styled(format!("{} ", name), style))
+        } else {
+            None
+        }
+    };
+
+    match &spec.state {
+        SyncSpecState::NotRunning => {
+            // Not running: show ○ icon and "Not running" status
+            spans.push(Span::styled(
+                format!("{} ", app.icons.get("○")),
+                Style::default().fg(theme.status_paused_fg),
+            ));
+            let name = if columns.name {
+                spec.name.clone()
+            } else {
+                String::new()
+            };
+            spans.extend(name_span(name, Style::default().fg(theme.session_name_fg)));
+            spans.push(Span::styled(
+                format!("  {}", t!("view.not_running")),
+                Style::default().fg(theme.session_status_fg),
+            ));
+        }
+        SyncSpecState::RunningTwoWay | SyncSpecState::RunningPush => {
+            // Running: show session details
+            if let Some(session) = &spec.running_session {
+                let is_push = spec.state == SyncSpecState::RunningPush;
+                let status_icon = app.icons.get(if session.paused { "⏸" } else { "▶" });
+                let status_color = if session.paused {
+                    theme.status_paused_fg
+                } else {
+                    theme.status_running_fg
+                };
+
+                spans.push(Span::styled(
+                    format!("{} ", status_icon),
+                    Style::default().fg(status_color),
+                ));
+
+                // Session name with push mode indicator
+                let name = match (columns.name, columns.mode && is_push) {
+                    (true, true) => format!("{}{}", spec.name, PUSH_TAG),
+                    (true, false) => spec.name.clone(),
+                    (false, true) => PUSH_TAG.trim_start().to_string(),
+                    (false, false) => String::new(),
+                };
+                spans.extend(name_span(
+                    name,
+                    Style::default()
+                        .fg(theme.session_name_fg)
+                        .add_modifier(Modifier::BOLD),
+                ));
+                spans.push(Span::raw(" ".to_string()));
+
+                // Everything after the endpoints, built first so the endpoints
+                // can be fitted into the width that's left
+                let mut trailing = Vec::new();
+
+                if columns.stats {
+                    // Session status icon
+                    spans.push(Span::styled(
+                        format!("{}  ", app.icons.get(session.status_icon())),
+                        Style::default().fg(theme.session_status_fg),
+                    ));
+
+                    // Time in current state for transitional states ("Staging (3m)"),
+                    // or what a safety check halted on
+                    if let Some(halt) = session.safety_halt() {
+                        spans.push(Span::styled(
+                            format!("{} ", t!("view.reset_hint", reason = halt.label())),
+                            Style::default().fg(theme.status_error_fg),
+                        ));
+                    } else if session.status_text() != "Watching" {
+                        if let Some(elapsed) = app.status_elapsed(session) {
+                            spans.push(Span::styled(
+                                format!("{} ({}) ", session.status_text(), format_elapsed(elapsed)),
+                                Style::default().fg(theme.session_status_fg),
+                            ));
+                        }
+                    }
+
+                    // Staging progress bar
+                    if let Some(pct) = session.progress_percentage() {
+                        trailing.push(Span::raw(" ".to_string()));
+                        trailing.extend(
+                            ProgressBar::new(theme, pct)
+                                .width(10)
+                                .icons(app.icons)
+                                .build_spans(),
+                        );
+
+                        // Transfer rate and time remaining, once enough refreshes have been seen
+                        if let Some(rate) = app.transfers.rate(&session.identifier) {
+                            let mut transfer = format!(" {}", format_rate(rate));
+                            if let Some(eta) = app.transfers.eta(&session.identifier) {
+                                let eta = t!("view.eta", time = format_elapsed(eta));
+                                transfer.push_str(&format!(" {}", eta));
+                            }
+                            trailing.push(Span::styled(
+                                transfer,
+                                Style::default().fg(theme.session_status_fg),
+                            ));
+                        }
+                    }
+
+                    // Stalled or offline for longer than the watchdog allows
+                    if let Some(flag) = app.watchdog_flag(session) {
+                        trailing.push(Span::styled(
+                            format!(" {} {}", app.icons.get("⚠"), flag.label()),
+                            Style::default().fg(theme.status_error_fg),
+                        ));
+                    }
+
+                    // A flush from mutagui that hasn't completed a cycle yet
+                    if let Some(elapsed) = app.flush_elapsed(session) {
+                        trailing.push(Span::styled(
+                            format!(
+                                " {} {}",
+                                app.icons.get("⏳"),
+                                t!("view.flushing", elapsed = format_elapsed(elapsed))
+                            ),
+                            Style::default().fg(theme.session_status_fg),
+                        ));
+                    }
+
+                    // Conflict indicator
+                    if session.has_conflicts() {
+                        trailing.push(Span::raw(" ".to_string()));
+                        trailing.push(Span::styled(
+                            format!(
+                                "{} {}",
+                                app.icons.get("⚠"),
+                                tn!(
+                                    "view.conflicts",
+                                    session.conflict_count(),
+                                    more = format_excluded(session.excluded_conflicts)
+                                )
+                            ),
+                            Style::default()
+                                .fg(theme.status_paused_fg)
+                                .add_modifier(Modifier::BOLD),
+                        ));
+                    }
+                }
+
+                // `m` shows when the session last synced in place of its
+                // endpoints
+                let show_last_sync =
+                    app.session_display_mode == SessionDisplayMode::ShowLastRefresh;
+                if columns.last_sync && !show_last_sync {
+                    trailing.push(Span::styled(
+                        format!(" {}", format_last_sync(&session.sync_time)),
+                        Style::default().fg(theme.session_status_fg),
+                    ));
+                }
+
+                if columns.age {
+                    if let Some(created) = session.created_at() {
+                        let age = Local::now().signed_duration_since(created);
+                        trailing.push(Span::styled(
+                            format!(
+                                " {}",
+                                t!(
+                                    "view.age_old",
+                                    age = format_age(age.to_std().unwrap_or_default())
+                                )
+                            ),
+                            Style::default().fg(theme.session_status_fg),
+                        ));
+                    }
+                }
+
+                if show_last_sync {
+                    spans.push(Span::styled(
+                        format_last_sync(&session.sync_time),
+                        Style::default().fg(theme.session_status_fg),
+                    ));
+                } else if columns.endpoints {
+                    let used: usize = spans
+                        .iter()
+                        .chain(&trailing)
+                        .chain(&external_change_spans(app, spec))
+                        .map(Span::width)
+                        .sum();
+                    let width = layout.endpoints_width(used);
+                    if width > 0 {
+                        spans.extend(render_endpoints(app, session, is_push, width));
+                    }
+                }
+                spans.extend(trailing);
+            }
+        }
+    }
+
+    spans.extend(external_change_spans(app, spec));
+    spans
+}
+
+/// Suffix on the names of push sessions.
+const PUSH_TAG: &str = " (push)";
+
+/// Alpha and beta endpoints with connection icons and the sync direction,
+/// shortened with an ellipsis in the middle to fit in `width` cells.
+fn render_endpoints(
+    app: &App,
+    session: &SyncSession,
+    is_push: bool,
+    width: usize,
+) -> Vec<Span<'static>> {
+    let theme = &app.color_scheme;
+    let ellipsis = app.icons.get("…");
+    let alpha_icon = app.icons.get(session.alpha.status_icon());
+    let beta_icon = app.icons.get(session.beta.status_icon());
+    // Arrow and mode indicator (⇄ for two-way, ⬆ for push)
+    let arrow = app.icons.get(if is_push { "⬆" } else { "⇄" });
+
+    let alpha = session.alpha_display();
+    let beta = session.beta_display(app.mutagen_client.ssh_config());
+    // Icons and arrow, the spaces around the arrow, and one after the beta path
+    let fixed = display_width(alpha_icon) + display_width(beta_icon) + display_width(arrow) + 3;
+    let (alpha_width, beta_width) = share_width(
+        display_width(&alpha),
+        display_width(&beta),
+        width.saturating_sub(fixed),
+    );
+    let connected_color = |connected: bool| {
+        if connected {
+            theme.status_running_fg
+        } else {
+            theme.status_paused_fg
+        }
+    };
+
+    let mut spans = vec![
+        // Alpha endpoint
+        Span::styled(
+            alpha_icon,
+            Style::default().fg(connected_color(session.alpha.connected)),
+        ),
+        Span::styled(
+            format!("{} ", truncate_middle(&alpha, alpha_width, ellipsis)),
+            Style::default().fg(theme.session_alpha_fg),
+        ),
+    ];
+    if is_push {
+        spans.push(Span::styled(
+            format!("{} ", arrow),
+            Style::default()
+                .fg(theme.status_paused_fg)
+                .add_modifier(Modifier::BOLD),
+        ));
+    } else {
+        spans.push(Span::raw(format!("{} ", arrow)));
+    }
+    // Beta endpoint
+    spans.push(Span::styled(
+        beta_icon,
+        Style::default().fg(connected_color(session.beta.connected)),
+    ));
+    spans.push(Span::styled(
+        truncate_middle(&beta, beta_width, ellipsis),
+        Style::default().fg(theme.session_beta_fg),
+    ));
+    spans
+}
+
+/// External change marker (state changed by something other than this mutagui)
+fn external_change_spans(app: &App, spec: &crate::project::SyncSpec) -> Vec<Span<'static>> {
+    let theme = &app.color_scheme;
+    let Some(change) = app.external_change_for(spec) else {
+        return Vec::new();
+    };
+    vec![
+        Span::raw(" ".to_string()),
+        Span::styled(
+            t!(
+                "view.external_marker",
+                icon = app.icons.get("⚡"),
+                kind = change.kind.label(),
+                time = change.at.format("%H:%M")
+            ),
+            Style::default()
+                .fg(theme.status_error_fg)
+                .add_modifier(Modifier::ITALIC),
+        ),
+    ]
+}
@@ -0,0 +1,439 @@
+//! The header along the top and the staging, status, and key hint bars
+//! along the bottom.
+
+use crate::app::App;
+use crate::command::printable;
+use crate::i18n::{t, tn};
+use crate::mutagen::{SessionSummary, SyncSession};
+use crate::tabs::Tab;
+use crate::transfer::{format_bytes, format_rate};
+use crate::widgets::{HelpBar, StyledText};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Gauge, Paragraph, Wrap},
+    Frame,
+};
+
+use super::formatting::{disk_space_summary, format_elapsed, format_excluded};
+use super::View;
+
+/// Calculate the height needed for the status area based on message length.
+/// Returns a value between 3 and 7 (min 1 line of text, max 5 lines of text, plus 2 for borders).
+pub(super) fn calculate_status_height(status_text: &str, available_width: u16) -> u16 {
+    // Account for borders and padding (2 for left/right borders, 2 for internal padding)
+    let content_width = if available_width > 4 {
+        (available_width - 4) as usize
+    } else {
+        1
+    };
+
+    // Use textwrap to calculate how many lines the text will wrap to
+    let wrapped_lines = textwrap::wrap(status_text, content_width);
+    let line_count = wrapped_lines.len() as u16;
+
+    // Add 2 for borders, clamp between 3 (min) and 7 (max: 5 lines of content + 2 borders)
+    (line_count + 2).clamp(3, 7)
+}
+
+/// The title line with session counts, under the tab bar.
+pub struct Header;
+
+impl View for Header {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        let theme = &app.color_scheme;
+        let summary = SessionSummary::from_sessions(&app.sessions);
+
+        let mut line = StyledText::new(theme).header("Mutagen TUI").build();
+
+        // Global health at a glance, so project headers don't need scanning
+        let muted = Style::default().fg(theme.help_text_fg);
+        let dot = format!(" {} ", app.icons.get("·"));
+        line.spans
+            .push(Span::styled(format!("  {}  ", app.icons.get("│")), muted));
+        line.spans.push(Span::styled(
+            format!("{} running", summary.running),
+            Style::default().fg(theme.status_running_fg),
+        ));
+        line.spans.push(Span::styled(dot.clone(), muted));
+        line.spans.push(Span::styled(
+            format!("{} paused", summary.paused),
+            Style::default().fg(theme.status_paused_fg),
+        ));
+        line.spans.push(Span::styled(dot.clone(), muted));
+        line.spans.push(Span::styled(
+            format!(
+                "{} conflict{}",
+                summary.conflicts,
+                if summary.conflicts == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(if summary.conflicts > 0 {
+                theme.status_error_fg
+            } else {
+                theme.session_status_fg
+            }),
+        ));
+        if summary.bytes_staged > 0 {
+            line.spans.push(Span::styled(dot.clone(), muted));
+            line.spans.push(Span::styled(
+                format!("{} staged", format_bytes(summary.bytes_staged)),
+                Style::default().fg(theme.session_status_fg),
+            ));
+        }
+        line.spans.push(Span::styled(dot.clone(), muted));
+        // A failed refresh almost always means the mutagen daemon isn't reachable
+        line.spans.push(if app.has_refresh_error {
+            Span::styled(
+                format!("daemon {}", app.icons.get("✗")),
+                Style::default().fg(theme.status_error_fg),
+            )
+        } else if app.last_refresh.is_some() {
+            Span::styled(
+                format!("daemon {}", app.icons.get("✓")),
+                Style::default().fg(theme.status_running_fg),
+            )
+        } else {
+            Span::styled(
+                format!("daemon {}", app.icons.get("…")),
+                Style::default().fg(theme.session_status_fg),
+            )
+        });
+        if app.read_only {
+            line.spans.push(Span::styled(dot.clone(), muted));
+            line.spans.push(Span::styled(
+                t!("status.read_only_badge"),
+                Style::default()
+                    .fg(theme.status_paused_fg)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        if app.mutagen_client.is_dry_run() {
+            line.spans.push(Span::styled(dot.clone(), muted));
+            line.spans.push(Span::styled(
+                t!("status.dry_run_badge"),
+                Style::default()
+                    .fg(theme.status_paused_fg)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+
+        let title = Paragraph::new(line)
+            .style(Style::default().add_modifier(Modifier::BOLD))
+            .block(Block::default().borders(Borders::ALL).title(tab_bar(app)));
+        f.render_widget(title, area);
+    }
+}
+
+/// Tab titles for the header border, with the open tab highlighted.
+fn tab_bar(app: &App) -> Line<'static> {
+    let theme = &app.color_scheme;
+    let mut spans = Vec::new();
+    for (i, tab) in Tab::ALL.into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(
+                app.icons.get("│"),
+                Style::default().fg(theme.help_text_fg),
+            ));
+        }
+        let style = if tab == app.tab {
+            Style::default()
+                .fg(theme.help_key_fg)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            Style::default().fg(theme.help_text_fg)
+        };
+        spans.push(Span::styled(format!(" {} ", tab.title()), style));
+    }
+    Line::from(spans)
+}
+
+/// The selected spec's session, if it is currently staging files.
+pub(super) fn selected_staging_session(app: &App) -> Option<&SyncSession> {
+    let (proj_idx, spec_idx) = app.get_selected_spec()?;
+    let session = app
+        .projects
+        .get(proj_idx)?
+        .specs
+        .get(spec_idx)?
+        .running_session
+        .as_deref()?;
+    session.staging_progress().map(|_| session)
+}
+
+/// Render a gauge with bytes, file counts, and rate, plus the file being staged.
+pub struct StagingPane<'a>(pub &'a SyncSession);
+
+impl View for StagingPane<'_> {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        let session = self.0;
+        let theme = &app.color_scheme;
+        let Some(progress) = session.staging_progress() else {
+            return;
+        };
+        let percent = session.progress_percentage().unwrap_or(0);
+
+        let mut label = format!("{}%", percent);
+        if let (Some(received), Some(expected)) = (progress.received_files, progress.expected_files)
+        {
+            label.push_str(&format!(" · {}/{} files", received, expected));
+        }
+        if let Some(received) = progress.bytes_received() {
+            label.push_str(&format!(" · {}", format_bytes(received)));
+        }
+        if let Some(rate) = app.transfers.rate(&session.identifier) {
+            label.push_str(&format!(" · {}", format_rate(rate)));
+        }
+        if let Some(eta) = app.transfers.eta(&session.identifier) {
+            label.push_str(&format!(" · ETA {}", format_elapsed(eta)));
+        }
+        let label = app.icons.text(&label).into_owned();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(t!("title.staging", name = session.name));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(1)])
+            .split(inner);
+
+        let gauge = Gauge::default()
+            .gauge_style(Style::default().fg(theme.progress_color(percent as f64 / 100.0)))
+            .percent(percent as u16)
+            .label(label);
+        f.render_widget(gauge, rows[0]);
+
+        if let Some(path) = &progress.path {
+            let current = Paragraph::new(format!("{} {}", app.icons.get("→"), printable(path)))
+                .style(Style::default().fg(theme.session_status_fg));
+            f.render_widget(current, rows[1]);
+        }
+    }
+}
+
+/// The status message, or the selected spec's status.
+pub struct StatusBar {
+    /// The message didn't fit and ends in an ellipsis.
+    pub clipped: bool,
+}
+
+impl View for StatusBar {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        // Build status text: show selected spec status if available, otherwise show status message
+        let selected_spec = app.get_selected_spec().filter(|_| app.tab == Tab::Sync);
+        let (mut status_text, fg_color) = if let Some((proj_idx, spec_idx)) = selected_spec {
+            // Spec is selected - show its status
+            if let Some(project) = app.projects.get(proj_idx) {
+                if let Some(spec) = project.specs.get(spec_idx) {
+                    if let Some(session) = &spec.running_session {
+                        // Build detailed status: "Name: Status"
+                        let mut parts = vec![
+                            session.name.clone(),
+                            ": ".to_string(),
+                            session.status_text().to_string(),
+                        ];
+
+                        // Show where an ssh alias resolves to
+                        if let Some(host) = &session.beta.host {
+                            let ssh_config = app.mutagen_client.ssh_config();
+                            let alias = ssh_config.display_host(host);
+                            let resolved = ssh_config.resolve(alias);
+                            if let Some(hostname) = resolved.hostname.filter(|h| h != alias) {
+                                let mut target = hostname;
+                                if let Some(user) = resolved.user {
+                                    target = format!("{}@{}", user, target);
+                                }
+                                if let Some(port) = resolved.port {
+                                    target = format!("{}:{}", target, port);
+                                }
+                                parts.push(format!(
+                                    " | {} {} {}",
+                                    alias,
+                                    app.icons.get("→"),
+                                    target
+                                ));
+                            }
+                        }
+
+                        if let Some(space) = disk_space_summary(app, session) {
+                            parts.push(format!(" | {}: {}", t!("view.free"), space));
+                        }
+
+                        // Add conflict count if any
+                        let conflict_count = session.conflict_count();
+                        if conflict_count > 0 {
+                            let conflicts = tn!(
+                                "view.conflicts",
+                                conflict_count,
+                                more = format_excluded(session.excluded_conflicts)
+                            );
+                            parts.push(format!(" | {}", conflicts));
+                        }
+
+                        // Say why a safety check halted the session and how to recover
+                        if let Some(halt) = session.safety_halt() {
+                            let explanation = halt.explanation();
+                            let reset = t!("view.press_u_to_reset", explanation = explanation);
+                            parts.push(format!(" | {}", reset));
+                            (parts.join(""), app.color_scheme.status_error_fg)
+                        } else {
+                            (parts.join(""), app.color_scheme.status_message_fg)
+                        }
+                    } else {
+                        // Spec not running
+                        (
+                            format!("{}: {}", spec.name, t!("view.not_running")),
+                            app.color_scheme.status_message_fg,
+                        )
+                    }
+                } else {
+                    (
+                        app.status_message
+                            .as_ref()
+                            .map(|msg| msg.text().to_string())
+                            .unwrap_or_else(|| t!("status.ready").to_string()),
+                        app.color_scheme.status_message_fg,
+                    )
+                }
+            } else {
+                (
+                    app.status_message
+                        .as_ref()
+                        .map(|msg| msg.text().to_string())
+                        .unwrap_or_else(|| t!("status.ready").to_string()),
+                    app.color_scheme.status_message_fg,
+                )
+            }
+        } else {
+            // No spec selected - show status message
+            let text = app
+                .status_message
+                .as_ref()
+                .map(|msg| msg.text().to_string())
+                .unwrap_or_else(|| t!("status.ready").to_string());
+
+            let color = app
+                .status_message
+                .as_ref()
+                .map(|msg| match msg {
+                    crate::app::StatusMessage::Error(_) => app.color_scheme.status_error_fg,
+                    crate::app::StatusMessage::Warning(_) => app.color_scheme.status_paused_fg,
+                    crate::app::StatusMessage::Info(_) | crate::app::StatusMessage::Progress(_) => {
+                        app.color_scheme.status_message_fg
+                    }
+                })
+                .unwrap_or(app.color_scheme.status_message_fg);
+
+            (text, color)
+        };
+
+        if let Some(command) = learned_command(app) {
+            status_text.push_str(&command);
+        }
+        if let Some(last_refresh) = app.last_refresh {
+            let time = last_refresh.format("%H:%M:%S");
+            let refresh_info = format!(" | {}", t!("status.last_refresh", time = time));
+            status_text.push_str(&refresh_info);
+        }
+
+        let title = if self.clipped {
+            t!("title.status_clipped")
+        } else {
+            t!("title.status")
+        };
+        let status = Paragraph::new(status_text)
+            .style(Style::default().fg(fg_color))
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .wrap(Wrap { trim: true });
+
+        f.render_widget(status, area);
+    }
+}
+
+/// In learning mode, the mutagen command the last action ran, for the
+/// status bar.
+pub(super) fn learned_command(app: &App) -> Option<String> {
+    if !app.learning() {
+        return None;
+    }
+    let commands = app.last_commands.borrow();
+    let (first, rest) = commands.split_first()?;
+    Some(if rest.is_empty() {
+        format!(" | $ {}", first)
+    } else {
+        format!(" | $ {} (+{} more)", first, rest.len())
+    })
+}
+
+/// The keys that apply to the current selection.
+pub struct KeyHints;
+
+impl View for KeyHints {
+    fn render(&self, f: &mut Frame, app: &App, area: Rect) {
+        // Selection commands only apply in the sync view
+        let in_sync_view = app.tab == Tab::Sync;
+
+        // Check if project is selected
+        let is_project_selected = in_sync_view && app.selection.is_project_selected();
+
+        // Check if a spec is selected
+        let is_spec_selected = in_sync_view && app.selection.is_spec_selected();
+
+        let nav_keys = app.icons.text("↑/↓/j/k");
+        let fold_keys = app.icons.text("h/l/↵");
+        let mut help_bar = HelpBar::new(&app.color_scheme);
+        help_bar = if in_sync_view {
+            help_bar
+                .item(&nav_keys, t!("hint.nav"))
+                .item(&fold_keys, t!("hint.fold"))
+        } else {
+            help_bar.item("Tab", t!("hint.switch_view"))
+        };
+        help_bar = help_bar.item("r/R", t!("hint.refresh"));
+
+        // Greyed out in read-only mode
+        let writable = !app.read_only;
+        if in_sync_view && app.all_projects_invalid() {
+            help_bar = help_bar.item_enabled("e", t!("hint.edit_file"), writable);
+        } else if is_project_selected {
+            // Project-specific commands
+            help_bar = help_bar
+                .item_enabled("e", t!("hint.edit"), writable)
+                .item_enabled("s", t!("hint.start_stop"), writable)
+                .item_enabled("p", t!("hint.push"), writable)
+                .item_enabled("Space", t!("hint.pause_resume"), writable);
+        } else if is_spec_selected {
+            // Spec-specific commands
+            help_bar = help_bar
+                .item_enabled("p", t!("hint.push"), writable)
+                .item_enabled("Space", t!("hint.pause_resume"), writable)
+                .item_enabled("f", t!("hint.flush"), writable)
+                .item_enabled("t", t!("hint.terminate"), writable)
+                .item("c", t!("hint.conflicts"))
+                .item("v/V", t!("hint.verify"));
+        }
+
+        if in_sync_view && !app.project_issues.is_empty() {
+            help_bar = help_bar.item("I", t!("hint.issues"));
+        }
+
+        // Common commands
+        help_bar = help_bar
+            .item("x", t!("hint.details"))
+            .item("o", t!("hint.sort"))
+            .item("a", t!("hint.audit_log"))
+            .item("?", t!("hint.help"))
+            .item("q", t!("hint.quit"));
+
+        let help = Paragraph::new(help_bar.build()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t!("title.help")),
+        );
+
+        f.render_widget(help, area);
+    }
+}
@@ -0,0 +1,398 @@
+//! Checksum verification of a session's two endpoints.
+//!
+//! Mutagen reports a session as in sync once its own bookkeeping agrees,
+//! which is not the same as the bytes on disk matching. Verification hashes
+//! files on each endpoint with `sha256sum` (run locally, over ssh, or in the
+//! container) and compares the digests. By default a sample of files spread
+//! across the alpha tree is checked; a full pass hashes everything on both
+//! sides. Files the session ignores are left out, since mutagen never syncs
+//! them.
+
+use globset::{GlobBuilder, GlobMatcher};
+use shell_escape::escape;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::i18n::t;
+
+/// Number of files hashed by a sampling verification.
+pub const DEFAULT_SAMPLE_SIZE: usize = 50;
+
+/// How many files a verification hashes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyMode {
+    /// Hash up to this many files chosen from the alpha endpoint.
+    Sample(usize),
+    /// Hash every file on both endpoints.
+    Full,
+}
+
+/// How a file differs between the endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MismatchKind {
+    /// Both endpoints have the file with different contents.
+    Differs,
+    MissingOnAlpha,
+    MissingOnBeta,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMismatch {
+    /// Path relative to the endpoint root.
+    pub path: String,
+    pub kind: MismatchKind,
+}
+
+/// Outcome of verifying one session.
+#[derive(Debug, Clone)]
+pub struct VerifyReport {
+    pub session_name: String,
+    pub mode: VerifyMode,
+    /// Number of files hashed on at least one endpoint.
+    pub checked: usize,
+    pub mismatches: Vec<FileMismatch>,
+}
+
+impl VerifyReport {
+    /// Compare digests from both endpoints over `paths`.
+    pub fn compare(
+        session_name: &str,
+        mode: VerifyMode,
+        paths: &[String],
+        alpha: &HashMap<String, String>,
+        beta: &HashMap<String, String>,
+    ) -> Self {
+        let mut checked = 0;
+        let mut mismatches = Vec::new();
+
+        for path in paths {
+            let kind = match (alpha.get(path), beta.get(path)) {
+                (Some(a), Some(b)) if a == b => None,
+                (Some(_), Some(_)) => Some(MismatchKind::Differs),
+                (Some(_), None) => Some(MismatchKind::MissingOnBeta),
+                (None, Some(_)) => Some(MismatchKind::MissingOnAlpha),
+                // Removed between listing and hashing
+                (None, None) => continue,
+            };
+            checked += 1;
+            if let Some(kind) = kind {
+                mismatches.push(FileMismatch {
+                    path: path.clone(),
+                    kind,
+                });
+            }
+        }
+
+        Self {
+            session_name: session_name.to_string(),
+            mode,
+            checked,
+            mismatches,
+        }
+    }
+
+    /// One-line summary for the status bar.
+    pub fn summary(&self) -> String {
+        let scope = match self.mode {
+            VerifyMode::Sample(_) => t!("status.verify_scope_sample"),
+            VerifyMode::Full => t!("status.verify_scope_full"),
+        };
+        if self.mismatches.is_empty() {
+            t!(
+                "status.verified_match",
+                name = self.session_name,
+                scope = scope,
+                count = self.checked
+            )
+        } else {
+            t!(
+                "status.verified_differ",
+                name = self.session_name,
+                scope = scope,
+                differ = self.mismatches.len(),
+                count = self.checked
+            )
+        }
+    }
+}
+
+/// A session's ignore patterns, matched the way mutagen matches them. A
+/// pattern without a `/` matches any file or directory name, one with a `/`
+/// matches a path from the sync root, one ending in `/` matches directories
+/// only, and one starting with `!` brings back what an earlier one ignored.
+/// Nothing under an ignored directory is synced.
+pub struct Ignores {
+    rules: Vec<IgnoreRule>,
+}
+
+struct IgnoreRule {
+    matcher: GlobMatcher,
+    /// Matched against the path from the root rather than the name.
+    whole_path: bool,
+    directory_only: bool,
+    /// A `!` pattern, which brings back what it matches.
+    include: bool,
+}
+
+impl Ignores {
+    /// Compile `patterns`. Ones that aren't valid globs are logged and
+    /// skipped.
+    pub fn new(patterns: &[String]) -> Self {
+        let rules = patterns
+            .iter()
+            .filter_map(|pattern| {
+                let (include, pattern) = match pattern.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, pattern.as_str()),
+                };
+                let directory_only = pattern.ends_with('/');
+                let pattern = pattern.trim_end_matches('/');
+                let whole_path = pattern.contains('/');
+                let glob = GlobBuilder::new(pattern.trim_start_matches('/'))
+                    .literal_separator(true)
+                    .build()
+                    .inspect_err(|e| tracing::warn!(pattern, error = %e, "bad ignore pattern"))
+                    .ok()?;
+                Some(IgnoreRule {
+                    matcher: glob.compile_matcher(),
+                    whole_path,
+                    directory_only,
+                    include,
+                })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Whether the file at `path`, relative to the endpoint root, is ignored
+    /// itself or under an ignored directory.
+    pub fn is_ignored(&self, path: &str) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        let mut prefix_end = 0;
+        let components: Vec<&str> = path.split('/').collect();
+        components.iter().enumerate().any(|(i, name)| {
+            prefix_end += name.len() + usize::from(i > 0);
+            let is_directory = i + 1 < components.len();
+            let mut ignored = false;
+            for rule in &self.rules {
+                if rule.directory_only && !is_directory {
+                    continue;
+                }
+                let target = if rule.whole_path {
+                    &path[..prefix_end]
+                } else {
+                    name
+                };
+                if rule.matcher.is_match(target) {
+                    ignored = !rule.include;
+                }
+            }
+            ignored
+        })
+    }
+}
+
+/// Every path in either digest map, in sorted order.
+pub fn all_paths(alpha: &HashMap<String, String>, beta: &HashMap<String, String>) -> Vec<String> {
+    alpha
+        .keys()
+        .chain(beta.keys())
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Pick up to `count` files spread evenly across the sorted listing.
+///
+/// The choice is deterministic, so repeating a verification rechecks the
+/// same files.
+pub fn sample_files(files: &[String], count: usize) -> Vec<String> {
+    let mut sorted = files.to_vec();
+    sorted.sort();
+    if sorted.len() <= count {
+        return sorted;
+    }
+    (0..count)
+        .map(|i| sorted[i * sorted.len() / count].clone())
+        .collect()
+}
+
+/// Parse `find` output into paths relative to the endpoint root.
+pub fn parse_file_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.strip_prefix("./").unwrap_or(line).to_string())
+        .collect()
+}
+
+/// Parse `sha256sum` output into a map from relative path to digest.
+pub fn parse_checksums(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            // sha256sum marks lines whose file name needed escaping with a leading backslash
+            let (escaped, line) = match line.strip_prefix('\\') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let (digest, path) = line.split_once("  ").or_else(|| line.split_once(" *"))?;
+            let path = path.strip_prefix("./").unwrap_or(path);
+            let path = if escaped {
+                path.replace("\\n", "\n").replace("\\\\", "\\")
+            } else {
+                path.to_string()
+            };
+            Some((path, digest.to_string()))
+        })
+        .collect()
+}
+
+/// Shell word for an endpoint directory, leaving a leading `~` to the shell.
+pub fn shell_dir(path: &str) -> String {
+    if path == "~" {
+        "\"$HOME\"".to_string()
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        format!("\"$HOME\"/{}", escape(Cow::Borrowed(rest)))
+    } else {
+        escape(Cow::Borrowed(path)).into_owned()
+    }
+}
+
+/// Files mutagen keeps for its own staging, which never sync.
+const FIND_FILES: &str = "find . -type f ! -name '.mutagen*'";
+
+/// Uses `sha256sum`, falling back to `shasum` where coreutils are missing (macOS).
+const PICK_HASHER: &str =
+    "if command -v sha256sum >/dev/null; then h=sha256sum; else h='shasum -a 256'; fi";
+
+/// Script that lists files under `dir`.
+pub fn list_script(dir: &str) -> String {
+    format!("cd {} || exit 1; {}", shell_dir(dir), FIND_FILES)
+}
+
+/// Script that hashes `files` under `dir`, or every file if `files` is `None`.
+///
+/// Missing files are skipped rather than failing the whole run; they show up
+/// as missing when the endpoints are compared.
+pub fn hash_script(dir: &str, files: Option<&[String]>) -> String {
+    let hash = match files {
+        Some(files) => {
+            let args: Vec<_> = files
+                .iter()
+                .map(|f| escape(Cow::Borrowed(f.as_str())).into_owned())
+                .collect();
+            format!("$h -- {} 2>/dev/null; exit 0", args.join(" "))
+        }
+        None => format!("{} -exec $h -- {{}} +", FIND_FILES),
+    };
+    format!("cd {} || exit 1; {}; {}", shell_dir(dir), PICK_HASHER, hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digests(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(path, digest)| (path.to_string(), digest.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_checksums() {
+        let output = "\
+e3b0c442  ./a.txt
+5d41402a  ./dir/b c.txt
+\\9f86d081  ./odd\\nname
+";
+        let parsed = parse_checksums(output);
+        assert_eq!(parsed["a.txt"], "e3b0c442");
+        assert_eq!(parsed["dir/b c.txt"], "5d41402a");
+        assert_eq!(parsed["odd\nname"], "9f86d081");
+    }
+
+    #[test]
+    fn test_sample_files_is_spread_and_deterministic() {
+        let files: Vec<String> = (0..100).rev().map(|i| format!("f{:03}", i)).collect();
+        let sample = sample_files(&files, 4);
+        assert_eq!(sample, vec!["f000", "f025", "f050", "f075"]);
+        assert_eq!(sample_files(&files[..3], 4).len(), 3);
+    }
+
+    #[test]
+    fn test_compare_reports_differences() {
+        let alpha = digests(&[("same", "1"), ("changed", "2"), ("only-alpha", "3")]);
+        let beta = digests(&[("same", "1"), ("changed", "x"), ("only-beta", "4")]);
+        let paths = all_paths(&alpha, &beta);
+
+        let report = VerifyReport::compare("web", VerifyMode::Full, &paths, &alpha, &beta);
+        assert_eq!(report.checked, 4);
+        assert_eq!(
+            report.mismatches,
+            vec![
+                FileMismatch {
+                    path: "changed".to_string(),
+                    kind: MismatchKind::Differs
+                },
+                FileMismatch {
+                    path: "only-alpha".to_string(),
+                    kind: MismatchKind::MissingOnBeta
+                },
+                FileMismatch {
+                    path: "only-beta".to_string(),
+                    kind: MismatchKind::MissingOnAlpha
+                },
+            ]
+        );
+        assert_eq!(report.summary(), "Verified web (full): 3 of 4 file(s) differ");
+    }
+
+    #[test]
+    fn test_ignores() {
+        let patterns = [
+            "node_modules",
+            "/build",
+            "docs/*.pdf",
+            "cache/",
+            "*.log",
+            "!keep.log",
+        ];
+        let ignores = Ignores::new(&patterns.map(String::from));
+        for path in [
+            "node_modules/a.js",
+            "web/node_modules/b/c.js",
+            "build/out.o",
+            "docs/guide.pdf",
+            "cache/x",
+            "debug.log",
+        ] {
+            assert!(ignores.is_ignored(path), "{} should be ignored", path);
+        }
+        for path in [
+            "src/build/out.o",
+            "web/docs/guide.pdf",
+            "cache",
+            "keep.log",
+            "src/main.rs",
+        ] {
+            assert!(!ignores.is_ignored(path), "{} should not be ignored", path);
+        }
+        assert!(!Ignores::new(&[]).is_ignored("anything"));
+    }
+
+    #[test]
+    fn test_scripts_escape_paths() {
+        assert_eq!(
+            list_script("~/my project"),
+            "cd \"$HOME\"/'my project' || exit 1; find . -type f ! -name '.mutagen*'"
+        );
+        let script = hash_script("/srv/app", Some(&["a b.txt".to_string()]));
+        assert!(script.starts_with("cd /srv/app || exit 1; if command -v sha256sum"));
+        assert!(script.ends_with("$h -- 'a b.txt' 2>/dev/null; exit 0"));
+    }
+}
@@ -0,0 +1,249 @@
+//! Noticing when project files change on disk.
+//!
+//! Editing a `mutagen.yml`, in mutagui's editor integration or anywhere
+//! else, should show up in the spec tree right away rather than at the next
+//! refresh. Each file's directory is watched rather than the file itself,
+//! since editors usually save by writing a new file and renaming it over the
+//! old one.
+//!
+//! The directories discovery searches are watched too, so a project file
+//! that appears or goes away is noticed without globbing them all on every
+//! refresh.
+
+use crate::project::is_compose_file;
+use notify::event::{CreateKind, ModifyKind, RemoveKind};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+
+pub struct ProjectWatcher {
+    watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    /// Directories being watched, as canonical paths.
+    dirs: HashSet<PathBuf>,
+    /// Project files by the canonical path events report, mapped to the path
+    /// they were discovered under.
+    files: HashMap<PathBuf, PathBuf>,
+    /// Directories searched for project files, as canonical paths.
+    search_dirs: HashSet<PathBuf>,
+}
+
+/// What changed on disk since the last check.
+#[derive(Debug, Default, PartialEq)]
+pub struct Changes {
+    /// Known project files that were written, replaced, or removed, as the
+    /// paths they were discovered under.
+    pub files: Vec<PathBuf>,
+    /// A file or directory appeared or went away in a searched directory, so
+    /// discovery may find a different set of project files.
+    pub discover: bool,
+}
+
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && !self.discover
+    }
+}
+
+impl ProjectWatcher {
+    pub fn new() -> notify::Result<Self> {
+        let (sender, events) = channel();
+        Ok(Self {
+            watcher: notify::recommended_watcher(sender)?,
+            events,
+            dirs: HashSet::new(),
+            files: HashMap::new(),
+            search_dirs: HashSet::new(),
+        })
+    }
+
+    /// Report changes to the project files at `paths` and to the contents
+    /// of `search_dirs` from now on, and stop watching anything else.
+    pub fn watch<'a>(
+        &mut self,
+        paths: impl IntoIterator<Item = &'a Path>,
+        search_dirs: &[PathBuf],
+    ) {
+        self.files = paths
+            .into_iter()
+            .filter_map(|path| Some((canonical(path)?, path.to_path_buf())))
+            .collect();
+        self.search_dirs = search_dirs
+            .iter()
+            .filter_map(|dir| dir.canonicalize().ok())
+            .collect();
+        let dirs: HashSet<PathBuf> = self
+            .files
+            .keys()
+            .filter_map(|path| path.parent())
+            .map(Path::to_path_buf)
+            .chain(self.search_dirs.iter().cloned())
+            .collect();
+
+        for dir in self.dirs.difference(&dirs) {
+            let _ = self.watcher.unwatch(dir);
+        }
+        let mut watched = HashSet::new();
+        for dir in dirs {
+            if self.dirs.contains(&dir) {
+                watched.insert(dir);
+                continue;
+            }
+            match self.watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                Ok(()) => {
+                    watched.insert(dir);
+                }
+                Err(e) => {
+                    tracing::warn!(dir = %dir.display(), error = %e, "cannot watch directory");
+                }
+            }
+        }
+        self.dirs = watched;
+    }
+
+    /// Changes since the last call.
+    pub fn changes(&self) -> Changes {
+        let mut changes = Changes::default();
+        for event in self.events.try_iter() {
+            match event {
+                Ok(event) if is_change(&event.kind) => {
+                    for path in &event.paths {
+                        if let Some(file) = self.files.get(path) {
+                            if !changes.files.contains(file) {
+                                changes.files.push(file.clone());
+                            }
+                        } else if self.may_be_discovered(path, &event.kind) {
+                            changes.discover = true;
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::debug!(error = %e, "file watch error"),
+            }
+        }
+        changes
+    }
+
+    /// Whether `path` appearing or going away could change what discovery
+    /// finds: a YAML file, including a compose file, or a directory in a
+    /// searched directory.
+    fn may_be_discovered(&self, path: &Path, kind: &EventKind) -> bool {
+        let added_or_removed = matches!(
+            kind,
+            EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_))
+        );
+        let searched = path
+            .parent()
+            .is_some_and(|dir| self.search_dirs.contains(dir));
+        let candidate = path.extension().is_some_and(|ext| ext == "yml")
+            || is_compose_file(path)
+            || path.is_dir()
+            || matches!(
+                kind,
+                EventKind::Create(CreateKind::Folder) | EventKind::Remove(RemoveKind::Folder)
+            );
+        added_or_removed && searched && candidate
+    }
+}
+
+/// Whether `kind` could change what a project file says. Reads and
+/// permission changes don't.
+fn is_change(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Modify(ModifyKind::Metadata(_)) => false,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => true,
+        _ => false,
+    }
+}
+
+/// `path` with its directory resolved, the way watch events report it. The
+/// file itself may not exist.
+fn canonical(path: &Path) -> Option<PathBuf> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    Some(dir.canonicalize().ok()?.join(path.file_name()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// Changes reported within a few seconds.
+    fn wait_for_changes(watcher: &ProjectWatcher) -> Changes {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut changes = Changes::default();
+        let mut collect = |more: Changes| {
+            for file in more.files {
+                if !changes.files.contains(&file) {
+                    changes.files.push(file);
+                }
+            }
+            changes.discover |= more.discover;
+        };
+        while Instant::now() < deadline {
+            let more = watcher.changes();
+            if !more.is_empty() {
+                collect(more);
+                // Let the rest of the save's events arrive
+                std::thread::sleep(Duration::from_millis(100));
+                collect(watcher.changes());
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        changes
+    }
+
+    fn changed_files(files: &[&Path]) -> Changes {
+        Changes {
+            files: files.iter().map(|path| path.to_path_buf()).collect(),
+            discover: false,
+        }
+    }
+
+    #[test]
+    fn test_reports_changes_to_watched_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("mutagen.yml");
+        let other = dir.path().join("notes.txt");
+        std::fs::write(&project, "sync: {}\n").unwrap();
+
+        let mut watcher = ProjectWatcher::new().unwrap();
+        watcher.watch([project.as_path()], &[]);
+        std::fs::write(&other, "unrelated").unwrap();
+        std::fs::write(&project, "sync:\n  api:\n    alpha: .\n    beta: server:/srv\n").unwrap();
+        assert_eq!(wait_for_changes(&watcher), changed_files(&[&project]));
+
+        // Saving by renaming a new file over the old one, as editors do
+        let saved = dir.path().join(".mutagen.yml.swp");
+        std::fs::write(&saved, "sync: {}\n").unwrap();
+        std::fs::rename(&saved, &project).unwrap();
+        assert_eq!(wait_for_changes(&watcher), changed_files(&[&project]));
+
+        watcher.watch([], &[]);
+        std::fs::write(&project, "sync: {}\n").unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(watcher.changes().is_empty());
+    }
+
+    #[test]
+    fn test_reports_new_files_in_search_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut watcher = ProjectWatcher::new().unwrap();
+        watcher.watch([], &[dir.path().to_path_buf()]);
+
+        std::fs::write(dir.path().join("notes.txt"), "unrelated").unwrap();
+        std::fs::write(dir.path().join("mutagen.yml"), "sync: {}\n").unwrap();
+        let changes = wait_for_changes(&watcher);
+        assert!(changes.discover);
+        assert!(changes.files.is_empty());
+
+        // A new directory may hold a project
+        std::fs::create_dir(dir.path().join("api")).unwrap();
+        assert!(wait_for_changes(&watcher).discover);
+    }
+}
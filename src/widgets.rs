@@ -3,9 +3,11 @@
 //! This module provides builder patterns for common UI elements,
 //! reducing code duplication in the main UI rendering code.
 
+use crate::icons::Icons;
 use crate::theme::ColorScheme;
-use ratatui::style::Style;
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
+use textwrap::core::display_width;
 
 /// Builder for creating styled text lines with semantic color roles.
 ///
@@ -69,7 +71,7 @@ impl<'a> StyledText<'a> {
 /// Builder for creating help bar content.
 pub struct HelpBar<'a> {
     theme: &'a ColorScheme,
-    items: Vec<(&'a str, &'a str)>, // (key, description)
+    items: Vec<(&'a str, &'a str, bool)>, // (key, description, enabled)
 }
 
 impl<'a> HelpBar<'a> {
@@ -81,8 +83,13 @@ impl<'a> HelpBar<'a> {
     }
 
     /// Add a help item (key and description).
-    pub fn item(mut self, key: &'a str, description: &'a str) -> Self {
-        self.items.push((key, description));
+    pub fn item(self, key: &'a str, description: &'a str) -> Self {
+        self.item_enabled(key, description, true)
+    }
+
+    /// Add a help item, greyed out if the key is disabled.
+    pub fn item_enabled(mut self, key: &'a str, description: &'a str, enabled: bool) -> Self {
+        self.items.push((key, description, enabled));
         self
     }
 
@@ -90,27 +97,203 @@ impl<'a> HelpBar<'a> {
     pub fn build(self) -> Line<'a> {
         let mut spans = Vec::new();
 
-        for (i, (key, desc)) in self.items.iter().enumerate() {
+        for (i, (key, desc, enabled)) in self.items.iter().enumerate() {
             if i > 0 {
                 spans.push(Span::styled(
                     " | ",
                     Style::default().fg(self.theme.help_text_fg),
                 ));
             }
-            spans.push(Span::styled(
-                *key,
-                Style::default().fg(self.theme.help_key_fg),
-            ));
-            spans.push(Span::styled(
-                format!(" {}", desc),
-                Style::default().fg(self.theme.help_text_fg),
-            ));
+            let (key_style, desc_style) = if *enabled {
+                (
+                    Style::default().fg(self.theme.help_key_fg),
+                    Style::default().fg(self.theme.help_text_fg),
+                )
+            } else {
+                let disabled = Style::default()
+                    .fg(self.theme.help_text_fg)
+                    .add_modifier(Modifier::DIM);
+                (disabled, disabled)
+            };
+            spans.push(Span::styled(*key, key_style));
+            spans.push(Span::styled(format!(" {}", desc), desc_style));
         }
 
         Line::from(spans)
     }
 }
 
+/// Builder for an inline progress bar drawn with block characters.
+///
+/// Each filled cell is colored by its position in the bar, producing a
+/// gradient on truecolor terminals and a two-step bar elsewhere.
+pub struct ProgressBar<'a> {
+    theme: &'a ColorScheme,
+    percent: u8,
+    width: usize,
+    icons: Icons,
+}
+
+impl<'a> ProgressBar<'a> {
+    pub fn new(theme: &'a ColorScheme, percent: u8) -> Self {
+        Self {
+            theme,
+            percent: percent.min(100),
+            width: 10,
+            icons: Icons::default(),
+        }
+    }
+
+    /// Set the number of cells in the bar (excluding the percentage label).
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width.max(1);
+        self
+    }
+
+    /// Draw with these glyphs (ASCII `#` and `.` in ASCII mode).
+    pub fn icons(mut self, icons: Icons) -> Self {
+        self.icons = icons;
+        self
+    }
+
+    /// Build the bar as spans followed by a percentage label.
+    pub fn build_spans(self) -> Vec<Span<'static>> {
+        let filled = (self.width * self.percent as usize + 50) / 100;
+        let mut spans = Vec::with_capacity(self.width + 1);
+
+        for cell in 0..self.width {
+            let fraction = if self.width > 1 {
+                cell as f64 / (self.width - 1) as f64
+            } else {
+                1.0
+            };
+            let (glyph, color) = if cell < filled {
+                (self.icons.get("█"), self.theme.progress_color(fraction))
+            } else {
+                (self.icons.get("░"), self.theme.session_status_fg)
+            };
+            spans.push(Span::styled(glyph, Style::default().fg(color)));
+        }
+        spans.push(Span::styled(
+            format!(" {:>3}%", self.percent),
+            Style::default().fg(self.theme.session_status_fg),
+        ));
+
+        spans
+    }
+}
+
+/// Widest the name column gets, however long the names are.
+const MAX_NAME_WIDTH: usize = 36;
+
+/// Narrowest the name column gets on a small terminal.
+const MIN_NAME_WIDTH: usize = 8;
+
+/// Below this many cells the endpoints are left out rather than squeezed.
+const MIN_ENDPOINTS_WIDTH: usize = 12;
+
+/// Column widths for the project list, fitted to the panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnLayout {
+    /// Width of a row inside the panel border.
+    pub row_width: usize,
+    /// Width spec names are padded or truncated to.
+    pub name_width: usize,
+}
+
+impl ColumnLayout {
+    /// Size the name column for the longest name, giving it at most a third
+    /// of the row so the endpoints and stats still fit.
+    pub fn fit(row_width: usize, longest_name: usize) -> Self {
+        let name_width = longest_name
+            .min(MAX_NAME_WIDTH)
+            .min(row_width / 3)
+            .max(MIN_NAME_WIDTH);
+        Self {
+            row_width,
+            name_width,
+        }
+    }
+
+    /// Width left for the endpoints once `used` cells hold other columns, or
+    /// 0 if that's too little to show anything useful.
+    pub fn endpoints_width(&self, used: usize) -> usize {
+        let left = self.row_width.saturating_sub(used);
+        if left < MIN_ENDPOINTS_WIDTH {
+            0
+        } else {
+            left
+        }
+    }
+}
+
+/// Split `total` cells between two texts `a` and `b` cells wide. If both
+/// don't fit, the shorter one keeps its full width when it fits in half,
+/// and the longer one gets the rest.
+pub fn share_width(a: usize, b: usize, total: usize) -> (usize, usize) {
+    let half = total / 2;
+    if a + b <= total {
+        (a, b)
+    } else if a <= half {
+        (a, total - a)
+    } else if b <= total - half {
+        (total - b, b)
+    } else {
+        (half, total - half)
+    }
+}
+
+/// Pad `text` with spaces, or cut it short with `ellipsis`, to exactly
+/// `width` terminal cells.
+pub fn fit_width(text: &str, width: usize, ellipsis: &str) -> String {
+    let mut fitted = truncate_end(text, width, ellipsis);
+    let padding = width.saturating_sub(display_width(&fitted));
+    fitted.extend(std::iter::repeat_n(' ', padding));
+    fitted
+}
+
+/// Shorten `text` to at most `width` cells by replacing its end with
+/// `ellipsis`.
+pub fn truncate_end(text: &str, width: usize, ellipsis: &str) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    let budget = width.saturating_sub(display_width(ellipsis));
+    let mut out = take_width(text.chars(), budget);
+    out.push_str(ellipsis);
+    out
+}
+
+/// Shorten `text` to at most `width` cells by replacing its middle with
+/// `ellipsis`, keeping both the host and the last directories of a path.
+pub fn truncate_middle(text: &str, width: usize, ellipsis: &str) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    let budget = width.saturating_sub(display_width(ellipsis));
+    let head = take_width(text.chars(), budget.div_ceil(2));
+    let tail: String = take_width(text.chars().rev(), budget / 2)
+        .chars()
+        .rev()
+        .collect();
+    format!("{}{}{}", head, ellipsis, tail)
+}
+
+/// The leading characters of `chars` that fit in `width` cells.
+fn take_width(chars: impl Iterator<Item = char>, width: usize) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+    for c in chars {
+        let w = display_width(c.encode_utf8(&mut [0; 4]));
+        if used + w > width {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,4 +320,68 @@ mod tests {
         assert_eq!(line.spans.len(), 2);
     }
 
+    #[test]
+    fn test_help_bar_disabled_item() {
+        let theme = ColorScheme::dark();
+        let line = HelpBar::new(&theme)
+            .item_enabled("t", "Terminate", false)
+            .build();
+
+        assert!(line.spans[0].style.add_modifier.contains(Modifier::DIM));
+        assert_eq!(line.spans[0].style.fg, Some(theme.help_text_fg));
+    }
+
+    #[test]
+    fn test_progress_bar_cells() {
+        let theme = ColorScheme::dark();
+        let spans = ProgressBar::new(&theme, 50).width(8).build_spans();
+
+        // 8 cells plus the percentage label
+        assert_eq!(spans.len(), 9);
+        let filled = spans.iter().filter(|s| s.content == "█").count();
+        assert_eq!(filled, 4);
+        assert_eq!(spans[8].content, "  50%");
+    }
+
+    #[test]
+    fn test_progress_bar_clamps_percent() {
+        let theme = ColorScheme::dark();
+        let spans = ProgressBar::new(&theme, 250).width(4).build_spans();
+        assert!(spans[..4].iter().all(|s| s.content == "█"));
+    }
+
+    #[test]
+    fn test_column_layout_fit() {
+        // Wide terminal: names get what they need
+        assert_eq!(ColumnLayout::fit(200, 12).name_width, 12);
+        assert_eq!(ColumnLayout::fit(200, 80).name_width, 36);
+        // Narrow terminal: a third of the row, but never less than the minimum
+        assert_eq!(ColumnLayout::fit(60, 30).name_width, 20);
+        assert_eq!(ColumnLayout::fit(12, 30).name_width, 8);
+
+        let layout = ColumnLayout::fit(80, 20);
+        assert_eq!(layout.endpoints_width(50), 30);
+        assert_eq!(layout.endpoints_width(75), 0);
+    }
+
+    #[test]
+    fn test_share_width() {
+        assert_eq!(share_width(10, 10, 30), (10, 10));
+        assert_eq!(share_width(5, 40, 30), (5, 25));
+        assert_eq!(share_width(40, 5, 30), (25, 5));
+        assert_eq!(share_width(40, 40, 31), (15, 16));
+    }
+
+    #[test]
+    fn test_truncation() {
+        assert_eq!(fit_width("web", 6, "…"), "web   ");
+        assert_eq!(fit_width("frontend-assets", 8, "…"), "fronten…");
+        assert_eq!(truncate_end("short", 10, "..."), "short");
+        assert_eq!(
+            truncate_middle("host:~/code/project/src", 13, "…"),
+            "host:~…ct/src"
+        );
+        assert_eq!(truncate_middle("abcdef", 4, "..."), "a...");
+    }
+
 }
@@ -0,0 +1,509 @@
+//! Form for defining a sync session.
+//!
+//! `n` opens the form. The session is either created on the spot with
+//! `mutagen sync create`, or added as a spec to a project file so it is
+//! started with the rest of the project. `E` opens the same form on an
+//! existing spec, to change it in its project file.
+//!
+//! With `[templates]` in config.toml, the form starts on a choice of them.
+//! A template fills in the mode and ignores, and the endpoints from its
+//! patterns once the name is known, so only the project-specific bits need
+//! typing.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::SpecTemplate;
+use crate::endpoint::EndpointAddress;
+use crate::input::TextInput;
+use crate::project::{expand_tilde_in_path, SessionDefinition};
+use crate::spec_edit::{ignore_paths, SpecFields};
+
+/// Sync modes offered, mutagen's default first.
+pub const MODES: &[&str] = &[
+    "two-way-safe",
+    "two-way-resolved",
+    "one-way-safe",
+    "one-way-replica",
+];
+
+/// The form's fields, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WizardField {
+    Template,
+    Alpha,
+    Beta,
+    Name,
+    Mode,
+    Ignore,
+    Target,
+}
+
+impl WizardField {
+    pub const ALL: [WizardField; 7] = [
+        WizardField::Template,
+        WizardField::Alpha,
+        WizardField::Beta,
+        WizardField::Name,
+        WizardField::Mode,
+        WizardField::Ignore,
+        WizardField::Target,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WizardField::Template => "Template",
+            WizardField::Alpha => "Alpha",
+            WizardField::Beta => "Beta",
+            WizardField::Name => "Name",
+            WizardField::Mode => "Mode",
+            WizardField::Ignore => "Ignore",
+            WizardField::Target => "Save to",
+        }
+    }
+
+    /// Whether the field is a choice cycled with Left and Right rather than
+    /// typed text.
+    pub fn is_choice(self) -> bool {
+        matches!(
+            self,
+            WizardField::Template | WizardField::Mode | WizardField::Target
+        )
+    }
+
+    /// The field `delta` places after this one among `fields`.
+    fn offset(self, fields: &[WizardField], delta: isize) -> Self {
+        let len = fields.len() as isize;
+        let index = fields.iter().position(|f| *f == self).unwrap_or(0) as isize;
+        fields[(index + delta).rem_euclid(len) as usize]
+    }
+}
+
+/// Where the new session goes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WizardTarget {
+    /// Run `mutagen sync create` now.
+    Create,
+    /// Add a spec to this project file.
+    Project(PathBuf),
+}
+
+/// State of the new session form.
+#[derive(Debug, Clone)]
+pub struct SessionWizard {
+    pub alpha: TextInput,
+    pub beta: TextInput,
+    pub name: TextInput,
+    pub ignore: TextInput,
+    pub mode: usize,
+    pub targets: Vec<WizardTarget>,
+    pub target: usize,
+    pub focus: WizardField,
+    /// Why the last submission was rejected.
+    pub error: Option<String>,
+    /// The spec being edited, as it was when the form opened.
+    pub editing: Option<SpecFields>,
+    /// The spec being duplicated, which the new one is copied from.
+    pub copying: Option<SpecFields>,
+    /// Templates from config.toml, by name.
+    pub templates: Vec<(String, SpecTemplate)>,
+    /// The chosen template, counting from 1; 0 for none.
+    pub template: usize,
+    /// The endpoints as the template last filled them in, to tell whether
+    /// they've been changed since.
+    filled: (String, String),
+}
+
+impl SessionWizard {
+    /// An empty form that can save to any of `project_files`, starting on
+    /// `selected` if given.
+    pub fn new(project_files: Vec<PathBuf>, selected: Option<&Path>) -> Self {
+        let mut targets = vec![WizardTarget::Create];
+        targets.extend(project_files.into_iter().map(WizardTarget::Project));
+        let target = selected
+            .and_then(|selected| {
+                targets
+                    .iter()
+                    .position(|t| matches!(t, WizardTarget::Project(p) if p == selected))
+            })
+            .unwrap_or(0);
+        Self {
+            alpha: TextInput::default(),
+            beta: TextInput::default(),
+            name: TextInput::default(),
+            ignore: TextInput::default(),
+            mode: 0,
+            targets,
+            target,
+            focus: WizardField::Alpha,
+            error: None,
+            editing: None,
+            copying: None,
+            templates: Vec::new(),
+            template: 0,
+            filled: Default::default(),
+        }
+    }
+
+    /// Offer `templates`, starting on the choice between them.
+    pub fn with_templates(mut self, templates: Vec<(String, SpecTemplate)>) -> Self {
+        if !templates.is_empty() {
+            self.focus = WizardField::Template;
+        }
+        self.templates = templates;
+        self
+    }
+
+    /// A form holding the spec `name` from the project file at `path`. Its
+    /// mode, if it doesn't set one, is the one from the file's defaults.
+    pub fn edit(
+        path: PathBuf,
+        name: &str,
+        definition: &SessionDefinition,
+        defaults: Option<&HashMap<String, serde_yaml::Value>>,
+    ) -> Self {
+        let mode = definition
+            .mode
+            .as_deref()
+            .or_else(|| defaults?.get("mode")?.as_str())
+            .and_then(|mode| MODES.iter().position(|m| *m == mode))
+            .unwrap_or(0);
+        let spec = SpecFields {
+            name: name.to_string(),
+            alpha: definition.alpha.clone(),
+            beta: definition.beta.clone(),
+            mode: MODES[mode],
+            ignore: ignore_paths(definition),
+        };
+        Self {
+            alpha: TextInput::new(spec.alpha.as_str()),
+            beta: TextInput::new(spec.beta.as_str()),
+            name: TextInput::new(spec.name.as_str()),
+            ignore: TextInput::new(spec.ignore.join(", ")),
+            mode,
+            targets: vec![WizardTarget::Project(path)],
+            target: 0,
+            focus: WizardField::Alpha,
+            error: None,
+            editing: Some(spec),
+            copying: None,
+            templates: Vec::new(),
+            template: 0,
+            filled: Default::default(),
+        }
+    }
+
+    /// A form holding a copy of the spec `name`, to add to the same project
+    /// file under a new name, suggested from `name` and not one of `taken`.
+    pub fn duplicate(
+        path: PathBuf,
+        name: &str,
+        definition: &SessionDefinition,
+        defaults: Option<&HashMap<String, serde_yaml::Value>>,
+        taken: &[&str],
+    ) -> Self {
+        let mut wizard = Self::edit(path, name, definition, defaults);
+        wizard.copying = wizard.editing.take();
+        let copy_name = (1..)
+            .map(|n| match n {
+                1 => format!("{}-copy", name),
+                n => format!("{}-copy-{}", name, n),
+            })
+            .find(|candidate| !taken.contains(&candidate.as_str()))
+            .unwrap_or_default();
+        wizard.name.set(copy_name);
+        wizard.focus = WizardField::Name;
+        wizard
+    }
+
+    /// The fields shown: the template choice only when there are templates.
+    pub fn fields(&self) -> Vec<WizardField> {
+        WizardField::ALL
+            .into_iter()
+            .filter(|field| *field != WizardField::Template || !self.templates.is_empty())
+            .collect()
+    }
+
+    /// The chosen template's name and skeleton.
+    pub fn selected_template(&self) -> Option<&(String, SpecTemplate)> {
+        self.template
+            .checked_sub(1)
+            .and_then(|index| self.templates.get(index))
+    }
+
+    /// The text input of the focused field, if it is a text field.
+    pub fn focused_input(&mut self) -> Option<&mut TextInput> {
+        match self.focus {
+            WizardField::Alpha => Some(&mut self.alpha),
+            WizardField::Beta => Some(&mut self.beta),
+            WizardField::Name => Some(&mut self.name),
+            WizardField::Ignore => Some(&mut self.ignore),
+            WizardField::Template | WizardField::Mode | WizardField::Target => None,
+        }
+    }
+
+    /// Move focus `delta` fields down. Leaving the alpha field with no name
+    /// entered suggests one from the alpha directory, unless a template's
+    /// pattern makes alpha from the name, in which case the name is asked
+    /// for first.
+    pub fn move_focus(&mut self, delta: isize) {
+        let alpha_from_name = self
+            .selected_template()
+            .is_some_and(|(_, template)| template.alpha.is_some());
+        if self.focus == WizardField::Alpha && self.name.value().is_empty() && !alpha_from_name {
+            self.name.set(suggest_name(self.alpha.value()));
+        }
+        self.fill_endpoints();
+        self.focus = if self.focus == WizardField::Template && alpha_from_name && delta > 0 {
+            WizardField::Name
+        } else {
+            self.focus.offset(&self.fields(), delta)
+        };
+    }
+
+    /// Step the focused choice field by `delta`.
+    pub fn cycle_choice(&mut self, delta: isize) {
+        let (index, len) = match self.focus {
+            WizardField::Template => (&mut self.template, self.templates.len() + 1),
+            WizardField::Mode => (&mut self.mode, MODES.len()),
+            WizardField::Target => (&mut self.target, self.targets.len()),
+            _ => return,
+        };
+        *index = (*index as isize + delta).rem_euclid(len as isize) as usize;
+        if self.focus == WizardField::Template {
+            self.apply_template();
+        }
+    }
+
+    /// Fill in the mode, ignores, and endpoints from the chosen template,
+    /// or clear the endpoints the last one filled in if none is chosen.
+    fn apply_template(&mut self) {
+        let Some((_, template)) = self.selected_template().cloned() else {
+            let (alpha, beta) = std::mem::take(&mut self.filled);
+            if self.alpha.value() == alpha {
+                self.alpha.set("");
+            }
+            if self.beta.value() == beta {
+                self.beta.set("");
+            }
+            return;
+        };
+        if let Some(mode) = &template.mode {
+            self.mode = MODES.iter().position(|m| m == mode).unwrap_or(0);
+        }
+        self.ignore.set(template.ignore.join(", "));
+        self.fill_endpoints();
+    }
+
+    /// Fill the endpoints in from the template's patterns and the name,
+    /// except ones that have been changed since they were last filled in.
+    fn fill_endpoints(&mut self) {
+        let Some((_, template)) = self.selected_template().cloned() else {
+            return;
+        };
+        let name = self.name.value().trim().to_string();
+        let fill = |pattern: &str| {
+            if name.is_empty() {
+                pattern.to_string()
+            } else {
+                pattern.replace("{name}", &name)
+            }
+        };
+        if let Some(pattern) = &template.alpha {
+            if self.alpha.value().is_empty() || self.alpha.value() == self.filled.0 {
+                self.filled.0 = fill(pattern);
+                self.alpha.set(self.filled.0.as_str());
+            }
+        }
+        if self.beta.value().is_empty() || self.beta.value() == self.filled.1 {
+            self.filled.1 = fill(&template.beta);
+            self.beta.set(self.filled.1.as_str());
+        }
+    }
+
+    pub fn selected_target(&self) -> &WizardTarget {
+        &self.targets[self.target]
+    }
+
+    /// Check the fields and build the session definition.
+    pub fn validate(&self) -> Result<SpecFields, String> {
+        let alpha = self.alpha.value().trim();
+        let beta = self.beta.value().trim();
+        let name = self.name.value().trim();
+        if alpha.is_empty() || beta.is_empty() {
+            return Err("Enter both endpoints".to_string());
+        }
+        if alpha.contains("{name}") || beta.contains("{name}") {
+            return Err("Enter a name to fill in the template's endpoints".to_string());
+        }
+        if let EndpointAddress::Local(path) = EndpointAddress::parse(alpha) {
+            let path = expand_tilde_in_path(&path);
+            // Relative paths in a project file are relative to the file
+            let path = match self.selected_target() {
+                WizardTarget::Project(file) if path.is_relative() => {
+                    file.parent().unwrap_or(Path::new(".")).join(path)
+                }
+                _ => path,
+            };
+            if !path.is_dir() {
+                return Err(format!("{} is not a directory", alpha));
+            }
+        }
+        if !is_valid_name(name) {
+            return Err(
+                "Name must start with a letter and contain only letters, digits, and hyphens"
+                    .to_string(),
+            );
+        }
+        Ok(SpecFields {
+            name: name.to_string(),
+            alpha: alpha.to_string(),
+            beta: beta.to_string(),
+            mode: MODES[self.mode],
+            ignore: self
+                .ignore
+                .value()
+                .split(',')
+                .map(str::trim)
+                .filter(|pattern| !pattern.is_empty())
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+}
+
+/// Session names mutagen accepts: a letter, then letters, digits, and hyphens.
+pub fn is_valid_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// A session name made from the last directory of `alpha`.
+fn suggest_name(alpha: &str) -> String {
+    let base = EndpointAddress::parse(alpha.trim())
+        .path()
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name: String = base
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let name = name.trim_matches('-').to_lowercase();
+    if is_valid_name(&name) {
+        name
+    } else {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let dir = tempfile::tempdir().unwrap();
+        let alpha = dir.path().join("My Photos");
+        std::fs::create_dir(&alpha).unwrap();
+
+        let mut wizard = SessionWizard::new(Vec::new(), None);
+        wizard.alpha.set(alpha.display().to_string());
+        wizard.beta.set("nas:/photos");
+        wizard.move_focus(1);
+        assert_eq!(wizard.name.value(), "my-photos");
+        wizard.ignore.set("*.tmp, , .cache");
+
+        let session = wizard.validate().unwrap();
+        assert_eq!(session.mode, "two-way-safe");
+        assert_eq!(session.ignore, vec!["*.tmp", ".cache"]);
+
+        wizard.name.set("2fast");
+        assert!(wizard.validate().is_err());
+        wizard.name.set("photos");
+        wizard
+            .alpha
+            .set(dir.path().join("missing").display().to_string());
+        assert!(wizard.validate().is_err());
+    }
+
+    #[test]
+    fn test_template() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("billing")).unwrap();
+        let template = SpecTemplate {
+            alpha: Some(format!("{}/{{name}}", dir.path().display())),
+            beta: "devbox:/srv/{name}".to_string(),
+            mode: Some("two-way-resolved".to_string()),
+            ignore: vec!["node_modules".to_string(), "target".to_string()],
+        };
+        let mut wizard = SessionWizard::new(Vec::new(), None)
+            .with_templates(vec![("service".to_string(), template)]);
+        assert_eq!(wizard.focus, WizardField::Template);
+        assert!(wizard.selected_template().is_none());
+
+        wizard.cycle_choice(1);
+        assert_eq!(wizard.selected_template().unwrap().0, "service");
+        assert_eq!(MODES[wizard.mode], "two-way-resolved");
+        assert_eq!(wizard.ignore.value(), "node_modules, target");
+        assert_eq!(wizard.beta.value(), "devbox:/srv/{name}");
+        assert!(wizard.validate().is_err());
+
+        // Only the name is asked for, and fills in both endpoints
+        wizard.move_focus(1);
+        assert_eq!(wizard.focus, WizardField::Name);
+        wizard.name.set("billing");
+        wizard.move_focus(1);
+        let spec = wizard.validate().unwrap();
+        assert_eq!(spec.alpha, dir.path().join("billing").display().to_string());
+        assert_eq!(spec.beta, "devbox:/srv/billing");
+
+        // A changed endpoint is kept when the name changes
+        wizard.beta.set("other:/srv/billing");
+        wizard.name.set("payments");
+        wizard.move_focus(1);
+        assert!(wizard.alpha.value().ends_with("/payments"));
+        assert_eq!(wizard.beta.value(), "other:/srv/billing");
+
+        // Choosing no template takes back the endpoints it filled in
+        wizard.focus = WizardField::Template;
+        wizard.cycle_choice(1);
+        assert_eq!(wizard.alpha.value(), "");
+        assert_eq!(wizard.beta.value(), "other:/srv/billing");
+        assert_eq!(wizard.fields().len(), 7);
+        assert_eq!(SessionWizard::new(Vec::new(), None).fields().len(), 6);
+    }
+
+    #[test]
+    fn test_edit_fills_in_spec() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("api")).unwrap();
+        let file = dir.path().join("mutagen.yml");
+        let definition: SessionDefinition = serde_yaml::from_str(
+            "alpha: ./api\nbeta: server:/srv/api\nignore:\n  vcs: true\n  paths: [target]\n",
+        )
+        .unwrap();
+        let defaults = HashMap::from([("mode".to_string(), "one-way-safe".into())]);
+
+        let wizard = SessionWizard::edit(file.clone(), "api", &definition, Some(&defaults));
+        assert_eq!(wizard.ignore.value(), "target");
+        assert_eq!(wizard.targets, vec![WizardTarget::Project(file)]);
+        // The relative alpha is found next to the project file
+        let spec = wizard.validate().unwrap();
+        assert_eq!(spec.mode, "one-way-safe");
+        assert_eq!(Some(spec), wizard.editing);
+    }
+
+    #[test]
+    fn test_duplicate_suggests_a_free_name() {
+        let definition: SessionDefinition =
+            serde_yaml::from_str("alpha: /tmp\nbeta: server:/srv/api\n").unwrap();
+        let file = PathBuf::from("mutagen.yml");
+        let wizard = SessionWizard::duplicate(file, "api", &definition, None, &["api", "api-copy"]);
+        assert_eq!(wizard.name.value(), "api-copy-2");
+        assert_eq!(wizard.focus, WizardField::Name);
+        assert!(wizard.editing.is_none());
+        assert_eq!(wizard.copying.as_ref().unwrap().name, "api");
+        assert_eq!(wizard.validate().unwrap().beta, "server:/srv/api");
+    }
+}